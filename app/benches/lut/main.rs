@@ -85,6 +85,63 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         })
     });
 
+    for interpolation_method in [
+        InterpolationMethod::Tetrahedral,
+        InterpolationMethod::Pyramid,
+        InterpolationMethod::Prism,
+        InterpolationMethod::Linear,
+    ] {
+        c.bench_function(
+            &format!("moxcms: Interpolation error {interpolation_method:?} RGB -> RGB"),
+            |b| {
+                let color_profile = ColorProfile::new_from_slice(&srgb_perceptual_icc).unwrap();
+                let dest_profile = ColorProfile::new_srgb();
+                let options = TransformOptions {
+                    interpolation_method,
+                    ..Default::default()
+                };
+                b.iter(|| {
+                    color_profile
+                        .interpolation_error(Layout::Rgb, &dest_profile, Layout::Rgb, options)
+                        .unwrap();
+                })
+            },
+        );
+    }
+
+    // Random input defeats cache locality of natural images,
+    // this measures CLUT fetching on the whole 33x33x33 grid.
+    let noise = (0..rgb.len())
+        .map(|_| rand::random::<u8>())
+        .collect::<Vec<u8>>();
+
+    for block_size in [1, 8, 16] {
+        c.bench_function(
+            &format!("moxcms: LUT Tetra RGB -> RGB Noise, block {block_size}"),
+            |b| {
+                let color_profile = ColorProfile::new_from_slice(&srgb_perceptual_icc).unwrap();
+                let dest_profile = ColorProfile::new_srgb();
+                let mut dst = vec![0u8; noise.len()];
+                let transform = color_profile
+                    .create_transform_8bit(
+                        Layout::Rgb,
+                        &dest_profile,
+                        Layout::Rgb,
+                        TransformOptions {
+                            interpolation_method: InterpolationMethod::Tetrahedral,
+                            prefer_fixed_point: false,
+                            lut_block_size: block_size,
+                            ..Default::default()
+                        },
+                    )
+                    .unwrap();
+                b.iter(|| {
+                    transform.transform(&noise, &mut dst).unwrap();
+                })
+            },
+        );
+    }
+
     c.bench_function("moxcms: LUT Pyramid RGB -> RGB", |b| {
         let color_profile = ColorProfile::new_from_slice(&srgb_perceptual_icc).unwrap();
         let dest_profile = ColorProfile::new_srgb();
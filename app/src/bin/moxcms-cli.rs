@@ -0,0 +1,396 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Command line front-end to exercise moxcms without writing Rust.
+//!
+//! ```text
+//! moxcms-cli inspect profile.icc
+//! moxcms-cli convert [--from a.icc] [--to b.icc] [--intent p|rc|s|ac] in.png out.png
+//! moxcms-cli bake-devicelink --from a.icc --to b.icc [--intent rc] [--grid 17] out.icc
+//! moxcms-cli conformance [--intent rc] profile.icc...
+//! ```
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use moxcms::{
+    ColorProfile, ConformanceCase, DataColorSpace, Layout, LutWarehouse, ProfileText,
+    RenderingIntent, TransformOptions, run_conformance,
+};
+use std::process::ExitCode;
+
+type CliResult<T> = Result<T, String>;
+
+const USAGE: &str = "Usage:
+  moxcms-cli inspect <profile.icc>
+  moxcms-cli convert [--from <a.icc>] [--to <b.icc>] [--intent p|rc|s|ac] <in> <out>
+  moxcms-cli bake-devicelink --from <a.icc> --to <b.icc> [--intent p|rc|s|ac] [--grid <n>] <out.icc>
+  moxcms-cli conformance [--intent p|rc|s|ac] <profile.icc>...
+
+Source profile of `convert` defaults to the one embedded into the image, then to sRGB.
+Destination profile defaults to sRGB.
+`conformance` checks every profile to sRGB and back against the reference pipeline.";
+
+/// Options shared by subcommands, values not given on command line are `None`
+#[derive(Default)]
+struct Arguments {
+    from: Option<String>,
+    to: Option<String>,
+    intent: Option<RenderingIntent>,
+    grid: Option<usize>,
+    positional: Vec<String>,
+}
+
+fn parse_intent(value: &str) -> CliResult<RenderingIntent> {
+    match value {
+        "p" | "perceptual" => Ok(RenderingIntent::Perceptual),
+        "rc" | "relative" => Ok(RenderingIntent::RelativeColorimetric),
+        "s" | "saturation" => Ok(RenderingIntent::Saturation),
+        "ac" | "absolute" => Ok(RenderingIntent::AbsoluteColorimetric),
+        _ => Err(format!("Unknown rendering intent `{value}`")),
+    }
+}
+
+fn parse_arguments(args: &[String]) -> CliResult<Arguments> {
+    let mut parsed = Arguments::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for `{name}`"))
+        };
+        match arg.as_str() {
+            "--from" => parsed.from = Some(value(arg)?),
+            "--to" => parsed.to = Some(value(arg)?),
+            "--intent" => parsed.intent = Some(parse_intent(&value(arg)?)?),
+            "--grid" => {
+                let grid = value(arg)?;
+                parsed.grid = Some(
+                    grid.parse::<usize>()
+                        .map_err(|_| format!("Invalid grid size `{grid}`"))?,
+                );
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option `{arg}`")),
+            _ => parsed.positional.push(arg.clone()),
+        }
+    }
+    Ok(parsed)
+}
+
+fn read_profile(path: &str) -> CliResult<ColorProfile> {
+    let data = std::fs::read(path).map_err(|e| format!("Can't read `{path}`: {e}"))?;
+    ColorProfile::new_from_slice(&data).map_err(|e| format!("Can't parse `{path}`: {e}"))
+}
+
+fn profile_text(text: &Option<ProfileText>) -> String {
+    match text {
+        Some(ProfileText::PlainString(s)) => s.clone(),
+        Some(ProfileText::Localizable(strings)) => {
+            strings.first().map(|s| s.value.clone()).unwrap_or_default()
+        }
+        Some(ProfileText::Description(d)) => d.ascii_string.clone(),
+        None => String::new(),
+    }
+}
+
+fn inspect(args: &Arguments) -> CliResult<()> {
+    let [path] = args.positional.as_slice() else {
+        return Err("`inspect` expects exactly one profile".to_string());
+    };
+    let profile = read_profile(path)?;
+    println!("Description:      {}", profile_text(&profile.description));
+    println!("Copyright:        {}", profile_text(&profile.copyright));
+    println!("Version:          {:?}", profile.version());
+    println!("Class:            {:?}", profile.profile_class);
+    println!("Color space:      {:?}", profile.color_space);
+    println!("PCS:              {:?}", profile.pcs);
+    println!("Rendering intent: {:?}", profile.rendering_intent);
+    println!("White point:      {:?}", profile.white_point);
+    if let Some(media_white_point) = profile.media_white_point {
+        println!("Media white:      {media_white_point:?}");
+    }
+    if let Some(luminance) = profile.luminance {
+        println!("Luminance:        {} cd/m2", luminance.y);
+    }
+    if let Some(cicp) = profile.cicp {
+        println!("CICP:             {cicp:?}");
+    }
+    if let Some(technology) = profile.technology {
+        println!("Technology:       {technology:?}");
+    }
+    if let Some(date) = profile.calibration_date {
+        println!("Calibrated:       {date:?}");
+    }
+    println!("Matrix shaper:    {}", profile.is_matrix_shaper());
+    let tables = [
+        ("A2B0", &profile.lut_a_to_b_perceptual),
+        ("A2B1", &profile.lut_a_to_b_colorimetric),
+        ("A2B2", &profile.lut_a_to_b_saturation),
+        ("B2A0", &profile.lut_b_to_a_perceptual),
+        ("B2A1", &profile.lut_b_to_a_colorimetric),
+        ("B2A2", &profile.lut_b_to_a_saturation),
+        ("gamt", &profile.gamut),
+    ];
+    for (name, lut) in tables.iter() {
+        match lut {
+            Some(LutWarehouse::Lut(lut)) => println!(
+                "{name}:             {:?} {}x{}, grid {}",
+                lut.lut_type,
+                lut.num_input_channels,
+                lut.num_output_channels,
+                lut.num_clut_grid_points
+            ),
+            Some(LutWarehouse::Multidimensional(lut)) => println!(
+                "{name}:             mAB {}x{}, grid {:?}",
+                lut.num_input_channels,
+                lut.num_output_channels,
+                &lut.grid_points[..lut.num_input_channels as usize]
+            ),
+            Some(LutWarehouse::MultiProcess(mpe)) => println!(
+                "{name}:             mpet {}x{}, {} elements",
+                mpe.num_input_channels,
+                mpe.num_output_channels,
+                mpe.elements.len()
+            ),
+            None => {}
+        }
+    }
+    if let Some(named) = &profile.named_colors {
+        println!(
+            "Named colors:     {} ({} device channels)",
+            named.len(),
+            named.device_channels
+        );
+    }
+    for warning in profile.warnings() {
+        println!("Warning:          {warning:?}");
+    }
+    Ok(())
+}
+
+fn options_for(args: &Arguments) -> TransformOptions {
+    TransformOptions {
+        rendering_intent: args.intent.unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+fn convert(args: &Arguments) -> CliResult<()> {
+    let [input, output] = args.positional.as_slice() else {
+        return Err("`convert` expects input and output images".to_string());
+    };
+    let mut decoder = ImageReader::open(input)
+        .and_then(|x| x.with_guessed_format())
+        .map_err(|e| format!("Can't open `{input}`: {e}"))?
+        .into_decoder()
+        .map_err(|e| format!("Can't decode `{input}`: {e}"))?;
+    let embedded = decoder.icc_profile().ok().flatten();
+    let image =
+        DynamicImage::from_decoder(decoder).map_err(|e| format!("Can't decode `{input}`: {e}"))?;
+
+    let source = match (&args.from, embedded) {
+        (Some(path), _) => read_profile(path)?,
+        (None, Some(icc)) => ColorProfile::new_from_slice(&icc)
+            .map_err(|e| format!("Can't parse profile embedded into `{input}`: {e}"))?,
+        (None, None) => ColorProfile::new_srgb(),
+    };
+    let dest = match &args.to {
+        Some(path) => read_profile(path)?,
+        None => ColorProfile::new_srgb(),
+    };
+    let options = options_for(args);
+
+    let is_gray = |profile: &ColorProfile| profile.color_space == DataColorSpace::Gray;
+    let has_alpha = image.color().has_alpha();
+    let src_layout = match (is_gray(&source), has_alpha) {
+        (true, false) => Layout::Gray,
+        (true, true) => Layout::GrayAlpha,
+        (false, false) => Layout::Rgb,
+        (false, true) => Layout::Rgba,
+    };
+    let dst_layout = match (dest.color_space, has_alpha) {
+        (DataColorSpace::Gray, false) => Layout::Gray,
+        (DataColorSpace::Gray, true) => Layout::GrayAlpha,
+        (DataColorSpace::Rgb, false) => Layout::Rgb,
+        (DataColorSpace::Rgb, true) => Layout::Rgba,
+        (color_space, _) => {
+            return Err(format!(
+                "Destination color space {color_space:?} can't be stored as an image"
+            ));
+        }
+    };
+    let width = image.width();
+    let height = image.height();
+    let samples = width as usize * height as usize * dst_layout.channels();
+    let error = |e: moxcms::CmsError| format!("Transform failed: {e}");
+
+    let is_16bit = image.color().bytes_per_pixel() / image.color().channel_count() > 1;
+    let converted = if is_16bit {
+        let src: Vec<u16> = match src_layout {
+            Layout::Gray => image.to_luma16().into_raw(),
+            Layout::GrayAlpha => image.to_luma_alpha16().into_raw(),
+            Layout::Rgb => image.to_rgb16().into_raw(),
+            _ => image.to_rgba16().into_raw(),
+        };
+        let mut dst = vec![0u16; samples];
+        source
+            .create_transform_16bit(src_layout, &dest, dst_layout, options)
+            .map_err(error)?
+            .transform(&src, &mut dst)
+            .map_err(error)?;
+        match dst_layout {
+            Layout::Gray => {
+                image::ImageBuffer::from_raw(width, height, dst).map(DynamicImage::ImageLuma16)
+            }
+            Layout::GrayAlpha => {
+                image::ImageBuffer::from_raw(width, height, dst).map(DynamicImage::ImageLumaA16)
+            }
+            Layout::Rgb => {
+                image::ImageBuffer::from_raw(width, height, dst).map(DynamicImage::ImageRgb16)
+            }
+            _ => image::ImageBuffer::from_raw(width, height, dst).map(DynamicImage::ImageRgba16),
+        }
+    } else {
+        let src: Vec<u8> = match src_layout {
+            Layout::Gray => image.to_luma8().into_raw(),
+            Layout::GrayAlpha => image.to_luma_alpha8().into_raw(),
+            Layout::Rgb => image.to_rgb8().into_raw(),
+            _ => image.to_rgba8().into_raw(),
+        };
+        let mut dst = vec![0u8; samples];
+        source
+            .create_transform_8bit(src_layout, &dest, dst_layout, options)
+            .map_err(error)?
+            .transform(&src, &mut dst)
+            .map_err(error)?;
+        match dst_layout {
+            Layout::Gray => {
+                image::ImageBuffer::from_raw(width, height, dst).map(DynamicImage::ImageLuma8)
+            }
+            Layout::GrayAlpha => {
+                image::ImageBuffer::from_raw(width, height, dst).map(DynamicImage::ImageLumaA8)
+            }
+            Layout::Rgb => {
+                image::ImageBuffer::from_raw(width, height, dst).map(DynamicImage::ImageRgb8)
+            }
+            _ => image::ImageBuffer::from_raw(width, height, dst).map(DynamicImage::ImageRgba8),
+        }
+    }
+    .ok_or_else(|| "Converted buffer doesn't match image size".to_string())?;
+    converted
+        .save(output)
+        .map_err(|e| format!("Can't write `{output}`: {e}"))
+}
+
+/// Samples transform between two profiles into `A2B0` of a device link profile
+fn bake_devicelink(args: &Arguments) -> CliResult<()> {
+    let [output] = args.positional.as_slice() else {
+        return Err("`bake-devicelink` expects output profile".to_string());
+    };
+    let (Some(from), Some(to)) = (&args.from, &args.to) else {
+        return Err("`bake-devicelink` requires `--from` and `--to`".to_string());
+    };
+    let source = read_profile(from)?;
+    let dest = read_profile(to)?;
+    let inputs = source.color_space.channels();
+    let grid = args.grid.unwrap_or(if inputs > 3 { 17 } else { 33 });
+    if !(2..=255).contains(&grid) {
+        return Err(format!("Grid size must be in 2..=255, got {grid}"));
+    }
+    let mut link = ColorProfile::new_device_link(&source, &dest, options_for(args), grid as u8)
+        .map_err(|e| format!("Can't bake device link: {e}"))?;
+    link.description = source.description.clone();
+    let encoded = link
+        .encode()
+        .map_err(|e| format!("Can't encode device link: {e}"))?;
+    std::fs::write(output, encoded).map_err(|e| format!("Can't write `{output}`: {e}"))
+}
+
+/// Reports delta E of regular transforms against reference pipeline
+fn conformance(args: &Arguments) -> CliResult<()> {
+    if args.positional.is_empty() {
+        return Err("`conformance` expects at least one profile".to_string());
+    }
+    let srgb = ColorProfile::new_srgb();
+    let options = options_for(args);
+    println!(
+        "{:<48} {:>5} {:>8} {:>8} {:>8}",
+        "case", "bits", "mean", "p95", "max"
+    );
+    for path in args.positional.iter() {
+        let profile = read_profile(path)?;
+        let cases = [
+            ConformanceCase::new(
+                format!("{path} -> sRGB"),
+                profile.clone(),
+                srgb.clone(),
+                options,
+            ),
+            ConformanceCase::new(format!("sRGB -> {path}"), srgb.clone(), profile, options),
+        ];
+        for case in cases.iter() {
+            let reports =
+                run_conformance(case).map_err(|e| format!("`{}` failed: {e}", case.name))?;
+            for report in reports {
+                println!(
+                    "{:<48} {:>5} {:>8.4} {:>8.4} {:>8.4}",
+                    report.name,
+                    report.bit_depth,
+                    report.mean_delta_e,
+                    report.p95_delta_e,
+                    report.max_delta_e
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let result = parse_arguments(rest).and_then(|parsed| match command.as_str() {
+        "inspect" => inspect(&parsed),
+        "convert" => convert(&parsed),
+        "bake-devicelink" => bake_devicelink(&parsed),
+        "conformance" => conformance(&parsed),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(format!("Unknown command `{command}`\n\n{USAGE}")),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
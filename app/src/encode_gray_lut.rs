@@ -98,7 +98,7 @@ pub(crate) fn encode_gray_lut() -> ColorProfile {
         num_input_channels: 3,
         num_output_channels: 1,
         grid_points: [17, 17, 17, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        clut: Some(LutStore::Store16(xyz_to_gray_samples.clone())),
+        clut: Some(LutStore::Store16(xyz_to_gray_samples.clone().into())),
         b_curves: vec![
             ToneReprCurve::Lut(vec![]),
             ToneReprCurve::Lut(vec![]),
@@ -120,7 +120,7 @@ pub(crate) fn encode_gray_lut() -> ColorProfile {
         num_input_channels: 1,
         num_output_channels: 3,
         grid_points: [17, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        clut: Some(LutStore::Store16(gray_to_xyz.clone())),
+        clut: Some(LutStore::Store16(gray_to_xyz.clone().into())),
         b_curves: vec![
             ToneReprCurve::Lut(vec![]),
             ToneReprCurve::Lut(vec![]),
@@ -130,6 +130,7 @@ fn check_gray() {
                 interpolation_method: InterpolationMethod::Linear,
                 barycentric_weight_scale: BarycentricWeightScale::Low,
                 allow_extended_range_rgb_xyz: false,
+                ..Default::default()
             },
         )
         .unwrap();
@@ -245,6 +246,7 @@ fn to_lut_v4(lut: &Option<LutWarehouse>, to_pcs: bool) -> Option<LutWarehouse> {
             Some(LutWarehouse::Multidimensional(data_type))
         }
         LutWarehouse::Multidimensional(md) => Some(LutWarehouse::Multidimensional(md.clone())),
+        LutWarehouse::MultiProcess(mpe) => Some(LutWarehouse::MultiProcess(mpe.clone())),
     }
 }
 
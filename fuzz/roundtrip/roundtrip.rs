@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use moxcms::ColorProfile;
+
+fuzz_target!(|profile: ColorProfile| {
+    let Ok(mut encoded) = profile.encode() else {
+        return;
+    };
+    let decoded = ColorProfile::new_from_slice(&encoded).expect("Encoded profile must be readable");
+    let mut reencoded = decoded.encode().expect("Decoded profile must be encodable");
+    // Creation date is taken from clock at encoding
+    encoded[24..36].fill(0);
+    reencoded[24..36].fill(0);
+    assert_eq!(encoded, reencoded);
+});
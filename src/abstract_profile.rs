@@ -0,0 +1,146 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::device_link::sample_grid;
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, Lab, LutMultidimensionalType, LutStore, LutWarehouse,
+    Matrix3d, ProfileClass, ProfileVersion, RenderingIntent, ToneReprCurve, Vector3d,
+};
+
+impl ColorProfile {
+    /// Creates abstract Lab to Lab profile sampling `adjust` on `grid_size` nodes per axis.
+    ///
+    /// `adjust` receives and returns CIE Lab with `L` in \[0; 100\] and `a`, `b` in
+    /// \[-128; 127\], results are clipped to that range. Table is stored as `A2B0` `mAB`,
+    /// so the profile is used as a source with Lab input, e.g. after a transform into
+    /// [ColorProfile::new_lab], or baked with [ColorProfile::new_device_link].
+    pub fn new_abstract(
+        grid_size: u8,
+        adjust: impl Fn(Lab) -> Lab,
+    ) -> Result<ColorProfile, CmsError> {
+        if grid_size < 2 {
+            return Err(CmsError::IncorrectlyFormedLut(
+                "Abstract profile grid must have at least 2 nodes".to_string(),
+            ));
+        }
+        let grid = sample_grid(3, 3, grid_size as usize, |src, dst| {
+            let lab = adjust(Lab::new(
+                (src[0] * 100.) as f32,
+                (src[1] * 255. - 128.) as f32,
+                (src[2] * 255. - 128.) as f32,
+            ));
+            dst[0] = lab.l as f64 / 100.;
+            dst[1] = (lab.a as f64 + 128.) / 255.;
+            dst[2] = (lab.b as f64 + 128.) / 255.;
+            Ok(())
+        })?;
+        let clut = grid
+            .iter()
+            .map(|&v| (v.max(0.).min(1.) * 65535. + 0.5) as u16)
+            .collect::<Vec<u16>>();
+
+        let mut grid_points = [0u8; 16];
+        grid_points[..3].fill(grid_size);
+        let lut = LutWarehouse::Multidimensional(LutMultidimensionalType {
+            num_input_channels: 3,
+            num_output_channels: 3,
+            grid_points,
+            clut: Some(LutStore::Store16(clut.into())),
+            a_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+            b_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+            m_curves: vec![],
+            matrix: Matrix3d::IDENTITY,
+            bias: Vector3d::default(),
+        });
+        Ok(ColorProfile {
+            profile_class: ProfileClass::Abstract,
+            color_space: DataColorSpace::Lab,
+            pcs: DataColorSpace::Lab,
+            rendering_intent: RenderingIntent::Perceptual,
+            version_internal: ProfileVersion::V4_0,
+            lut_a_to_b_perceptual: Some(lut),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Layout, TransformOptions};
+
+    #[test]
+    fn test_abstract_profile() {
+        let srgb = ColorProfile::new_srgb();
+        // Lab device values passed to PCS as they are
+        let mut lab = ColorProfile::new_lab();
+        lab.pcs = DataColorSpace::Lab;
+        let options = TransformOptions::default();
+        let identity = ColorProfile::new_abstract(33, |lab| lab).unwrap();
+        let identity = ColorProfile::new_from_slice(&identity.encode().unwrap()).unwrap();
+        assert_eq!(identity.profile_class, ProfileClass::Abstract);
+        let grayscale = ColorProfile::new_abstract(17, |lab| Lab::new(lab.l, 0., 0.)).unwrap();
+
+        let src = (0..=16)
+            .flat_map(|v| {
+                let v = v as f32 / 16.;
+                [v, 1. - v, (v * 3.).fract()]
+            })
+            .collect::<Vec<f32>>();
+        let mut lab_values = vec![0f32; src.len()];
+        srgb.create_transform_f32(Layout::Rgb, &lab, Layout::Rgb, options)
+            .unwrap()
+            .transform(&src, &mut lab_values)
+            .unwrap();
+
+        let mut direct = vec![0f32; src.len()];
+        lab.create_transform_f32(Layout::Rgb, &srgb, Layout::Rgb, options)
+            .unwrap()
+            .transform(&lab_values, &mut direct)
+            .unwrap();
+        let mut dst = vec![0f32; src.len()];
+        identity
+            .create_transform_f32(Layout::Rgb, &srgb, Layout::Rgb, options)
+            .unwrap()
+            .transform(&lab_values, &mut dst)
+            .unwrap();
+        for (&a, &b) in direct.iter().zip(dst.iter()) {
+            assert!((a - b).abs() < 1e-2, "{a} vs {b}");
+        }
+
+        grayscale
+            .create_transform_f32(Layout::Rgb, &srgb, Layout::Rgb, options)
+            .unwrap()
+            .transform(&lab_values, &mut dst)
+            .unwrap();
+        for rgb in dst.chunks_exact(3) {
+            assert!((rgb[0] - rgb[1]).abs() < 1e-2, "{rgb:?}");
+            assert!((rgb[1] - rgb[2]).abs() < 1e-2, "{rgb:?}");
+        }
+    }
+}
@@ -0,0 +1,210 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{CmsError, ColorProfile, Layout, TransformF32BitExecutor, TransformOptions};
+use pxfm::{f_exp2f, f_log2f};
+
+const ACES_CCT_LINEAR_CUT: f32 = 0.0078125;
+const ACES_CCT_CODE_CUT: f32 = 0.155251141552511;
+const ACES_CCT_A: f32 = 10.5402377416545;
+const ACES_CCT_B: f32 = 0.0729055341958355;
+const ACES_HALF_MAX: f32 = 65504.;
+
+/// Encodes ACEScg linear value into ACEScct, see S-2016-001
+#[inline]
+pub fn aces_cct_from_linear(linear: f32) -> f32 {
+    if linear <= ACES_CCT_LINEAR_CUT {
+        ACES_CCT_A * linear + ACES_CCT_B
+    } else {
+        (f_log2f(linear) + 9.72) / 17.52
+    }
+}
+
+/// Decodes ACEScct value into ACEScg linear, see S-2016-001
+#[inline]
+pub fn aces_cct_to_linear(code: f32) -> f32 {
+    if code <= ACES_CCT_CODE_CUT {
+        (code - ACES_CCT_B) / ACES_CCT_A
+    } else {
+        f_exp2f(code * 17.52 - 9.72).min(ACES_HALF_MAX)
+    }
+}
+
+/// Input transform -> ACEScct working space -> output transform chain.
+///
+/// Both halves are regular profile transforms into and out of ACEScg/AP1 linear,
+/// ACEScct encoding is applied analytically in between so working values are exact
+/// ACEScct code values, suitable for grading operations.
+///
+/// Working data always has three channels, or four if input layout has alpha.
+/// Profile transforms are limited to the profile white, set
+/// [TransformOptions::allow_extended_range_rgb_xyz] to pass values above it where the profile allows.
+pub struct AcesChain {
+    input: Box<TransformF32BitExecutor>,
+    output: Box<TransformF32BitExecutor>,
+    working_layout: Layout,
+    output_layout: Layout,
+    input_scale: f32,
+}
+
+impl AcesChain {
+    /// Creates chain from `input` profile (IDT) to `output` profile (ODT)
+    pub fn new(
+        input: &ColorProfile,
+        input_layout: Layout,
+        output: &ColorProfile,
+        output_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Self, CmsError> {
+        let working = ColorProfile::new_aces_cg_linear();
+        let working_layout = if input_layout.has_alpha() {
+            Layout::Rgba
+        } else {
+            Layout::Rgb
+        };
+        let input = input.create_transform_f32(input_layout, &working, working_layout, options)?;
+        let output =
+            working.create_transform_f32(working_layout, output, output_layout, options)?;
+        Ok(Self {
+            input,
+            output,
+            working_layout,
+            output_layout,
+            input_scale: 1.,
+        })
+    }
+
+    /// Scales scene linear light after input transform.
+    ///
+    /// Profiles normalize the encoding range into the profile white, so scene referred
+    /// inputs, e.g. [crate::CameraLog] profiles, should be scaled back by
+    /// [crate::CameraLog::max_linear] to keep 0.18 as the middle gray.
+    pub fn with_input_scale(mut self, scale: f32) -> Self {
+        self.input_scale = scale;
+        self
+    }
+
+    /// Layout of the working ACEScct data
+    pub fn working_layout(&self) -> Layout {
+        self.working_layout
+    }
+
+    /// Converts input data into ACEScct working data
+    pub fn to_working(&self, src: &[f32], working: &mut [f32]) -> Result<(), CmsError> {
+        self.input.transform(src, working)?;
+        let channels = self.working_layout.channels();
+        for pixel in working.chunks_exact_mut(channels) {
+            for v in pixel.iter_mut().take(3) {
+                *v = aces_cct_from_linear(*v * self.input_scale);
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts ACEScct working data into output data
+    pub fn to_output(&self, working: &[f32], dst: &mut [f32]) -> Result<(), CmsError> {
+        let channels = self.working_layout.channels();
+        if working.len() % channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        let mut linear = working.to_vec();
+        for pixel in linear.chunks_exact_mut(channels) {
+            for v in pixel.iter_mut().take(3) {
+                *v = aces_cct_to_linear(*v);
+            }
+        }
+        self.output.transform(&linear, dst)
+    }
+
+    /// Runs the whole chain without grading in between
+    pub fn transform(&self, src: &[f32], dst: &mut [f32]) -> Result<(), CmsError> {
+        let channels = self.working_layout.channels();
+        let mut working = vec![0f32; dst.len() / self.output_layout.channels() * channels];
+        self.to_working(src, &mut working)?;
+        self.to_output(&working, dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CameraLog, TransformOptions};
+
+    #[test]
+    fn test_aces_cct_curve() {
+        assert!((aces_cct_from_linear(0.18) - 0.4135884).abs() < 1e-5);
+        assert!((aces_cct_from_linear(0.) - ACES_CCT_B).abs() < 1e-6);
+        for x in [0., 0.001, 0.0078125, 0.18, 1., 30.] {
+            assert!((aces_cct_to_linear(aces_cct_from_linear(x)) - x).abs() < 1e-4 * x.max(1.));
+        }
+    }
+
+    #[test]
+    fn test_aces_chain() {
+        let srgb = ColorProfile::new_srgb();
+        let chain = AcesChain::new(
+            &srgb,
+            Layout::Rgba,
+            &srgb,
+            Layout::Rgb,
+            TransformOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(chain.working_layout(), Layout::Rgba);
+        let src = [1., 1., 1., 0.5, 0.2, 0.6, 0.3, 1.];
+        let mut working = [0f32; 8];
+        chain.to_working(&src, &mut working).unwrap();
+        // Diffuse white is ACES 1.0
+        for v in &working[..3] {
+            assert!((v - aces_cct_from_linear(1.)).abs() < 2e-3, "{working:?}");
+        }
+        assert_eq!(working[3], 0.5);
+        let mut dst = [0f32; 6];
+        chain.transform(&src, &mut dst).unwrap();
+        for (v, e) in dst.iter().zip([1., 1., 1., 0.2, 0.6, 0.3]) {
+            assert!((v - e).abs() < 5e-3, "{dst:?}");
+        }
+
+        let log = CameraLog::SLog3;
+        let chain = AcesChain::new(
+            &ColorProfile::new_camera_log(log),
+            Layout::Rgb,
+            &srgb,
+            Layout::Rgb,
+            TransformOptions::default(),
+        )
+        .unwrap()
+        .with_input_scale(log.max_linear() as f32);
+        let gray = log.from_linear(0.18) as f32;
+        let mut working = [0f32; 3];
+        chain.to_working(&[gray, gray, gray], &mut working).unwrap();
+        for v in working {
+            assert!((v - aces_cct_from_linear(0.18)).abs() < 5e-3, "{working:?}");
+        }
+    }
+}
@@ -0,0 +1,309 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::{RgbXyzFactory, TransformMatrixShaper};
+use crate::trc::GammaLutInterpolate;
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, Layout, PointeeSizeExpressible, TransformExecutor,
+    TransformOptions, TransformScratch, Vector3f,
+};
+use num_traits::AsPrimitive;
+
+/// User tone adjustment as 1D curves applied in destination linear light,
+/// between linearization and the output curve.
+///
+/// Each curve is sampled uniformly over 0..1 and linearly interpolated,
+/// empty curve is identity.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ToneAdjustment {
+    pub red: Vec<f32>,
+    pub green: Vec<f32>,
+    pub blue: Vec<f32>,
+}
+
+impl ToneAdjustment {
+    /// Same curve for all channels made from common display sliders.
+    ///
+    /// `brightness` is added offset, `contrast` is slope around middle gray and `gamma`
+    /// is applied first as `x^(1/gamma)`. Neutral values are 0, 1 and 1.
+    pub fn new_brightness_contrast_gamma(brightness: f32, contrast: f32, gamma: f32) -> Self {
+        const SAMPLES: usize = 256;
+        let curve = (0..SAMPLES)
+            .map(|i| {
+                let x = i as f32 / (SAMPLES - 1) as f32;
+                let x = if gamma > 0. { x.powf(1. / gamma) } else { x };
+                ((x - 0.5) * contrast + 0.5 + brightness).max(0.).min(1.)
+            })
+            .collect::<Vec<f32>>();
+        Self {
+            red: curve.clone(),
+            green: curve.clone(),
+            blue: curve,
+        }
+    }
+
+    fn evaluate(curve: &[f32], x: f32) -> f32 {
+        if curve.is_empty() {
+            return x;
+        }
+        if curve.len() == 1 {
+            return curve[0];
+        }
+        let pos = x.max(0.).min(1.) * (curve.len() - 1) as f32;
+        let index = (pos as usize).min(curve.len() - 2);
+        let t = pos - index as f32;
+        curve[index] + (curve[index + 1] - curve[index]) * t
+    }
+}
+
+type RebuildFn<T> = dyn Fn(&ToneAdjustment) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+    + Send
+    + Sync;
+
+/// Matrix shaper transform with user [ToneAdjustment] that can be changed in place.
+///
+/// Changing adjustment recomposes only output curves, linearization tables
+/// and the matrix are kept, so it's cheap enough for interactive calibration sliders.
+pub struct AdjustableTransform<T> {
+    executor: Box<dyn TransformExecutor<T> + Send + Sync>,
+    rebuild: Box<RebuildFn<T>>,
+}
+
+impl<T: Copy + Default> AdjustableTransform<T> {
+    /// Replaces current adjustment, [ToneAdjustment::default] removes it.
+    pub fn set_adjustment(&mut self, adjustment: &ToneAdjustment) -> Result<(), CmsError> {
+        self.executor = (self.rebuild)(adjustment)?;
+        Ok(())
+    }
+}
+
+impl<T: Copy + Default> TransformExecutor<T> for AdjustableTransform<T> {
+    fn transform(&self, src: &[T], dst: &mut [T]) -> Result<(), CmsError> {
+        self.executor.transform(src, dst)
+    }
+
+    fn fixed_point_format(&self) -> Option<crate::FixedPointFormat> {
+        self.executor.fixed_point_format()
+    }
+
+    fn transform_with_scratch(
+        &self,
+        src: &[T],
+        dst: &mut [T],
+        scratch: &mut TransformScratch,
+    ) -> Result<(), CmsError> {
+        self.executor.transform_with_scratch(src, dst, scratch)
+    }
+}
+
+/// Composes output curve with adjustment, linear index is remapped before lookup
+fn compose_gamma<T: Copy, const GAMMA_CAP: usize>(
+    base: &[T; 65536],
+    curve: &[f32],
+) -> Box<[T; 65536]> {
+    let mut composed = Box::new(*base);
+    let scale = (GAMMA_CAP - 1) as f32;
+    for (i, dst) in composed.iter_mut().take(GAMMA_CAP).enumerate() {
+        let y = ToneAdjustment::evaluate(curve, i as f32 / scale);
+        let j = (y.max(0.).min(1.) * scale).round() as usize;
+        *dst = base[j.min(GAMMA_CAP - 1)];
+    }
+    composed
+}
+
+impl ColorProfile {
+    /// Creates 8 bit matrix shaper RGB transform with adjustable tone, see [AdjustableTransform].
+    pub fn create_adjustable_transform_8bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<AdjustableTransform<u8>, CmsError> {
+        #[cfg(feature = "integer_pixels")]
+        let options = TransformOptions {
+            fixed_point_format: Some(options.fixed_point_format.unwrap_or_default()),
+            ..options
+        };
+        self.create_adjustable_transform_nbit::<u8, 8, 256, 4096>(
+            src_layout, dst_pr, dst_layout, options,
+        )
+    }
+
+    /// Creates 16 bit matrix shaper RGB transform with adjustable tone, see [AdjustableTransform].
+    pub fn create_adjustable_transform_16bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<AdjustableTransform<u16>, CmsError> {
+        self.create_adjustable_transform_nbit::<u16, 16, 65536, 65536>(
+            src_layout, dst_pr, dst_layout, options,
+        )
+    }
+
+    fn create_adjustable_transform_nbit<
+        T: Copy
+            + Default
+            + AsPrimitive<usize>
+            + PointeeSizeExpressible
+            + Send
+            + Sync
+            + RgbXyzFactory<T>
+            + GammaLutInterpolate
+            + 'static,
+        const BIT_DEPTH: usize,
+        const LINEAR_CAP: usize,
+        const GAMMA_CAP: usize,
+    >(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<AdjustableTransform<T>, CmsError>
+    where
+        f32: AsPrimitive<T>,
+        u32: AsPrimitive<T>,
+    {
+        let is_matrix_shaper_pair = self.color_space == DataColorSpace::Rgb
+            && dst_pr.color_space == DataColorSpace::Rgb
+            && self.pcs == DataColorSpace::Xyz
+            && dst_pr.pcs == DataColorSpace::Xyz
+            && self.is_matrix_shaper()
+            && dst_pr.is_matrix_shaper()
+            && !self.has_device_to_pcs_lut()
+            && !dst_pr.has_pcs_to_device_lut();
+        if !is_matrix_shaper_pair {
+            return Err(CmsError::UnsupportedProfileConnection);
+        }
+        if src_layout == Layout::Gray || src_layout == Layout::GrayAlpha {
+            return Err(CmsError::InvalidLayout);
+        }
+        if dst_layout == Layout::Gray || dst_layout == Layout::GrayAlpha {
+            return Err(CmsError::InvalidLayout);
+        }
+
+        let mut transform = self.transform_matrix(dst_pr);
+        if let Some(scale) = options.luminance_scale(self, dst_pr) {
+            transform = transform.mul_scalar(scale);
+        }
+        let adaptation_matrix = transform.to_f32();
+
+        let use_cicp = options.allow_use_cicp_transfer;
+        let r_linear =
+            self.shared_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(&self.red_trc, use_cicp)?;
+        let g_linear =
+            self.shared_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(&self.green_trc, use_cicp)?;
+        let b_linear =
+            self.shared_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(&self.blue_trc, use_cicp)?;
+        let r_gamma = dst_pr
+            .build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(&dst_pr.red_trc, use_cicp)?;
+        let g_gamma = dst_pr
+            .build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(&dst_pr.green_trc, use_cicp)?;
+        let b_gamma = dst_pr
+            .build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(&dst_pr.blue_trc, use_cicp)?;
+
+        let rebuild = move |adjustment: &ToneAdjustment| {
+            let profile = TransformMatrixShaper {
+                r_linear: r_linear.clone(),
+                g_linear: g_linear.clone(),
+                b_linear: b_linear.clone(),
+                r_gamma: compose_gamma::<T, GAMMA_CAP>(&r_gamma, &adjustment.red),
+                g_gamma: compose_gamma::<T, GAMMA_CAP>(&g_gamma, &adjustment.green),
+                b_gamma: compose_gamma::<T, GAMMA_CAP>(&b_gamma, &adjustment.blue),
+                adaptation_matrix,
+                bias: Vector3f::default(),
+            };
+            T::make_transform::<LINEAR_CAP, GAMMA_CAP, BIT_DEPTH>(
+                src_layout, dst_layout, profile, options,
+            )
+        };
+        let executor = rebuild(&ToneAdjustment::default())?;
+        Ok(AdjustableTransform {
+            executor,
+            rebuild: Box::new(rebuild),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjustable_transform() {
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let src = (0..256 * 3).map(|x| (x / 3) as u8).collect::<Vec<u8>>();
+        let mut reference = vec![0u8; src.len()];
+        let mut dst = vec![0u8; src.len()];
+        let options = TransformOptions {
+            prefer_fixed_point: false,
+            ..Default::default()
+        };
+        srgb.create_transform_8bit(Layout::Rgb, &bt2020, Layout::Rgb, options)
+            .unwrap()
+            .transform(&src, &mut reference)
+            .unwrap();
+
+        let mut transform = srgb
+            .create_adjustable_transform_8bit(Layout::Rgb, &bt2020, Layout::Rgb, options)
+            .unwrap();
+        transform.transform(&src, &mut dst).unwrap();
+        assert_eq!(reference, dst);
+
+        transform
+            .set_adjustment(&ToneAdjustment::new_brightness_contrast_gamma(0., 1., 1.))
+            .unwrap();
+        transform.transform(&src, &mut dst).unwrap();
+        for (&a, &b) in dst.iter().zip(reference.iter()) {
+            assert!((a as i32 - b as i32).abs() <= 1);
+        }
+
+        transform
+            .set_adjustment(&ToneAdjustment::new_brightness_contrast_gamma(-0.2, 1., 1.))
+            .unwrap();
+        transform.transform(&src, &mut dst).unwrap();
+        assert!(dst.iter().zip(reference.iter()).all(|(a, b)| a <= b));
+        assert!(dst[255 * 3] < reference[255 * 3]);
+
+        transform
+            .set_adjustment(&ToneAdjustment::default())
+            .unwrap();
+        transform.transform(&src, &mut dst).unwrap();
+        assert_eq!(reference, dst);
+
+        assert!(
+            ColorProfile::new_lab()
+                .create_adjustable_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, options)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,87 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Per stage timings of transform execution.
+use std::time::Duration;
+
+/// Time spent by [crate::TransformExecutor::transform_timed] in each pipeline stage.
+///
+/// Most matrix shaper and 3D LUT executors fuse every stage into a single pass,
+/// such executors report only `total` and have `staged` unset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageTimings {
+    /// Input curves, CLUT and matrices up to PCS
+    pub to_pcs: Duration,
+    /// Conversions inside PCS, e.g. Lab to XYZ, luminance scaling
+    pub pcs: Duration,
+    /// PCS to device: matrices, CLUT and output curves
+    pub to_device: Duration,
+    /// Alpha copying and other post processing
+    pub finalization: Duration,
+    /// Wall time of the whole call
+    pub total: Duration,
+    /// Whether stages were measured separately
+    pub staged: bool,
+}
+
+impl StageTimings {
+    /// Adds timings of another call, e.g. to accumulate runs
+    pub fn accumulate(&mut self, other: StageTimings) {
+        self.to_pcs += other.to_pcs;
+        self.pcs += other.pcs;
+        self.to_device += other.to_device;
+        self.finalization += other.finalization;
+        self.total += other.total;
+        self.staged |= other.staged;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ColorProfile, Layout, TransformOptions};
+
+    #[test]
+    fn test_fused_timings() {
+        let srgb = ColorProfile::new_srgb();
+        let transform = srgb
+            .create_transform_8bit(
+                Layout::Rgb,
+                &ColorProfile::new_bt2020(),
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let src = vec![127u8; 3 * 1024];
+        let mut dst = vec![0u8; src.len()];
+        let mut timings = transform.transform_timed(&src, &mut dst).unwrap();
+        assert!(!timings.staged);
+        let once = timings.total;
+        timings.accumulate(timings);
+        assert_eq!(timings.total, once * 2);
+    }
+}
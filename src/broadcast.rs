@@ -0,0 +1,374 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::cicp::create_rec709_parametric;
+use crate::mlaf::mlaf;
+use crate::trc::lut_interp_linear_float;
+use crate::{
+    Chromaticity, CmsError, ColorPrimaries, GamutClipMode, Layout, Matrix3f, OklabGamut, Rgb,
+    ToneCurveEvaluator, ToneReprCurve, Vector3f,
+};
+
+const ENCODE_TABLE_SIZE: usize = 4096;
+
+/// Direction of the broadcast primaries conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BroadcastConversion {
+    /// UHD downconversion, BT.2020 into BT.709
+    Bt2020ToBt709,
+    /// HD upconversion, BT.709 into BT.2020
+    Bt709ToBt2020,
+}
+
+/// How colors outside of the destination gamut are brought into it
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BroadcastGamutMapping {
+    /// Matrix conversion with per channel clipping, this is what most hardware converters do.
+    /// Saturated colors may shift hue.
+    #[default]
+    Clip,
+    /// Hue preserving clipping in Oklab, see [OklabGamut::clip].
+    HuePreserving(GamutClipMode),
+}
+
+/// Luminance model of the signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BroadcastLuminance {
+    /// Non-constant luminance, luma is derived from gamma-encoded components,
+    /// BT.709 and BT.2020 NCL.
+    #[default]
+    NonConstant,
+    /// Constant luminance, BT.2020 CL, luma is derived from linear light.
+    ///
+    /// Gamut mapping restores linear luminance of the source color whenever the result
+    /// stays in gamut, so the CL luma signal is kept through the conversion.
+    Constant,
+}
+
+/// Dedicated converter between BT.2020 and BT.709.
+///
+/// Both standards share the same transfer function, so the conversion is a single matrix
+/// in linear light with gamut mapping, without going through ICC profiles and PCS.
+/// Inputs and outputs are gamma-encoded R'G'B', alpha is copied as is.
+pub struct BroadcastConverter {
+    matrix: Matrix3f,
+    mapping: BroadcastGamutMapping,
+    luminance: BroadcastLuminance,
+    gamut: OklabGamut,
+    src_luma: [f32; 3],
+    dst_luma: [f32; 3],
+    linearize: Box<dyn ToneCurveEvaluator + Send + Sync>,
+    encode: Box<dyn ToneCurveEvaluator + Send + Sync>,
+    linear_table: Box<[f32; 65536]>,
+    encode_table: Box<[f32; ENCODE_TABLE_SIZE]>,
+}
+
+impl BroadcastConverter {
+    /// Creates converter, tables for integer data are built once here.
+    pub fn new(
+        conversion: BroadcastConversion,
+        mapping: BroadcastGamutMapping,
+        luminance: BroadcastLuminance,
+    ) -> Result<Self, CmsError> {
+        let (src, dst) = match conversion {
+            BroadcastConversion::Bt2020ToBt709 => (ColorPrimaries::BT_2020, ColorPrimaries::BT_709),
+            BroadcastConversion::Bt709ToBt2020 => (ColorPrimaries::BT_709, ColorPrimaries::BT_2020),
+        };
+        let src_to_xyz = src.transform_to_xyz_d(Chromaticity::D65);
+        let dst_to_xyz = dst.transform_to_xyz_d(Chromaticity::D65);
+        let matrix = dst_to_xyz.inverse().mat_mul(src_to_xyz).to_f32();
+        let src_to_xyz = src_to_xyz.to_f32();
+        let dst_to_xyz = dst_to_xyz.to_f32();
+
+        let curve = ToneReprCurve::Parametric(create_rec709_parametric().to_vec());
+        let linearize = curve.make_linear_evaluator()?;
+        let encode = curve.make_gamma_evaluator()?;
+
+        let mut linear_table = Box::new([0f32; 65536]);
+        for (i, v) in linear_table.iter_mut().enumerate() {
+            *v = linearize.evaluate_value(i as f32 * (1. / 65535.));
+        }
+        let mut encode_table = Box::new([0f32; ENCODE_TABLE_SIZE]);
+        for (i, v) in encode_table.iter_mut().enumerate() {
+            *v = encode.evaluate_value(i as f32 / (ENCODE_TABLE_SIZE - 1) as f32);
+        }
+
+        let gamut = match dst {
+            ColorPrimaries::BT_709 => OklabGamut::srgb(),
+            _ => OklabGamut::bt2020(),
+        };
+
+        Ok(Self {
+            matrix,
+            mapping,
+            luminance,
+            gamut,
+            src_luma: src_to_xyz.v[1],
+            dst_luma: dst_to_xyz.v[1],
+            linearize,
+            encode,
+            linear_table,
+            encode_table,
+        })
+    }
+
+    /// Converts linear light RGB in source primaries into linear light destination primaries
+    pub fn convert_linear(&self, rgb: Rgb<f32>) -> Rgb<f32> {
+        let v = self.matrix.mul_vector(Vector3f {
+            v: [rgb.r, rgb.g, rgb.b],
+        });
+        let converted = Rgb::new(v.v[0], v.v[1], v.v[2]);
+        let mapped = match self.mapping {
+            BroadcastGamutMapping::Clip => converted,
+            BroadcastGamutMapping::HuePreserving(mode) => self.gamut.clip(converted, mode),
+        };
+        let mapped = match self.luminance {
+            BroadcastLuminance::NonConstant => mapped,
+            BroadcastLuminance::Constant => {
+                let y_src = dot(self.src_luma, rgb);
+                let y_dst = dot(self.dst_luma, mapped);
+                let peak = mapped.r.max(mapped.g).max(mapped.b);
+                if y_dst > 0. && peak > 0. {
+                    let scale = (y_src / y_dst).min(1. / peak);
+                    Rgb::new(mapped.r * scale, mapped.g * scale, mapped.b * scale)
+                } else {
+                    mapped
+                }
+            }
+        };
+        Rgb::new(
+            mapped.r.max(0.).min(1.),
+            mapped.g.max(0.).min(1.),
+            mapped.b.max(0.).min(1.),
+        )
+    }
+
+    /// Converts single gamma-encoded color
+    pub fn convert(&self, rgb: Rgb<f32>) -> Rgb<f32> {
+        let linear = self.linearize.evaluate_tristimulus(rgb);
+        self.encode
+            .evaluate_tristimulus(self.convert_linear(linear))
+    }
+
+    /// Converts normalized data, only [Layout::Rgb] and [Layout::Rgba] are supported.
+    pub fn transform_f32(
+        &self,
+        layout: Layout,
+        src: &[f32],
+        dst: &mut [f32],
+    ) -> Result<(), CmsError> {
+        let channels = check_lanes(layout, src.len(), dst.len())?;
+        for (src, dst) in src
+            .chunks_exact(channels)
+            .zip(dst.chunks_exact_mut(channels))
+        {
+            let rgb = self.convert(Rgb::new(src[0], src[1], src[2]));
+            dst[0] = rgb.r;
+            dst[1] = rgb.g;
+            dst[2] = rgb.b;
+            if channels == 4 {
+                dst[3] = src[3];
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts 8 bit data, only [Layout::Rgb] and [Layout::Rgba] are supported.
+    pub fn transform_8bit(
+        &self,
+        layout: Layout,
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> Result<(), CmsError> {
+        let channels = check_lanes(layout, src.len(), dst.len())?;
+        for (src, dst) in src
+            .chunks_exact(channels)
+            .zip(dst.chunks_exact_mut(channels))
+        {
+            let rgb =
+                self.convert_table(Rgb::new(src[0] as u32, src[1] as u32, src[2] as u32), 255);
+            dst[0] = rgb.r as u8;
+            dst[1] = rgb.g as u8;
+            dst[2] = rgb.b as u8;
+            if channels == 4 {
+                dst[3] = src[3];
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts data with `bit_depth` in range 1..=16,
+    /// only [Layout::Rgb] and [Layout::Rgba] are supported.
+    pub fn transform_16bit(
+        &self,
+        layout: Layout,
+        src: &[u16],
+        dst: &mut [u16],
+        bit_depth: usize,
+    ) -> Result<(), CmsError> {
+        if !(1..=16).contains(&bit_depth) {
+            return Err(CmsError::UnsupportedChannelConfiguration);
+        }
+        let channels = check_lanes(layout, src.len(), dst.len())?;
+        let max_value = (1u32 << bit_depth) - 1;
+        for (src, dst) in src
+            .chunks_exact(channels)
+            .zip(dst.chunks_exact_mut(channels))
+        {
+            let rgb = self.convert_table(
+                Rgb::new(
+                    (src[0] as u32).min(max_value),
+                    (src[1] as u32).min(max_value),
+                    (src[2] as u32).min(max_value),
+                ),
+                max_value,
+            );
+            dst[0] = rgb.r as u16;
+            dst[1] = rgb.g as u16;
+            dst[2] = rgb.b as u16;
+            if channels == 4 {
+                dst[3] = src[3];
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn convert_table(&self, rgb: Rgb<u32>, max_value: u32) -> Rgb<u32> {
+        let index = |v: u32| ((v * 65535 + max_value / 2) / max_value) as usize;
+        let linear = Rgb::new(
+            self.linear_table[index(rgb.r)],
+            self.linear_table[index(rgb.g)],
+            self.linear_table[index(rgb.b)],
+        );
+        let mapped = self.convert_linear(linear);
+        let scale = max_value as f32;
+        let encode = |v: f32| {
+            mlaf(
+                0.5,
+                lut_interp_linear_float(v, self.encode_table.as_slice()),
+                scale,
+            ) as u32
+        };
+        Rgb::new(encode(mapped.r), encode(mapped.g), encode(mapped.b))
+    }
+}
+
+#[inline]
+fn dot(weights: [f32; 3], rgb: Rgb<f32>) -> f32 {
+    mlaf(
+        mlaf(weights[0] * rgb.r, weights[1], rgb.g),
+        weights[2],
+        rgb.b,
+    )
+}
+
+fn check_lanes(layout: Layout, src: usize, dst: usize) -> Result<usize, CmsError> {
+    if layout != Layout::Rgb && layout != Layout::Rgba {
+        return Err(CmsError::InvalidLayout);
+    }
+    let channels = layout.channels();
+    if src != dst {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    if src % channels != 0 {
+        return Err(CmsError::LaneMultipleOfChannels);
+    }
+    Ok(channels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColorProfile;
+
+    #[test]
+    fn test_broadcast_matches_profiles() {
+        let converter = BroadcastConverter::new(
+            BroadcastConversion::Bt2020ToBt709,
+            BroadcastGamutMapping::Clip,
+            BroadcastLuminance::NonConstant,
+        )
+        .unwrap();
+        let bt2020 = ColorProfile::new_bt2020();
+        let mut bt709 = ColorProfile::new_srgb();
+        bt709.red_trc = bt2020.red_trc.clone();
+        bt709.green_trc = bt2020.green_trc.clone();
+        bt709.blue_trc = bt2020.blue_trc.clone();
+        bt709.cicp = None;
+        let transform = bt2020
+            .create_transform_16bit(Layout::Rgb, &bt709, Layout::Rgb, Default::default())
+            .unwrap();
+        let src = [
+            32768u16, 32768, 32768, 40000, 30000, 20000, 12000, 30000, 15000, 65535, 65535, 65535,
+        ];
+        let mut expected = [0u16; 12];
+        transform.transform(&src, &mut expected).unwrap();
+        let mut dst = [0u16; 12];
+        converter
+            .transform_16bit(Layout::Rgb, &src, &mut dst, 16)
+            .unwrap();
+        for (&v, &e) in dst.iter().zip(expected.iter()) {
+            assert!(v.abs_diff(e) <= 256, "Expected {expected:?}, got {dst:?}");
+        }
+    }
+
+    #[test]
+    fn test_broadcast_round_trip_and_mapping() {
+        let up = BroadcastConverter::new(
+            BroadcastConversion::Bt709ToBt2020,
+            BroadcastGamutMapping::Clip,
+            BroadcastLuminance::NonConstant,
+        )
+        .unwrap();
+        let down = BroadcastConverter::new(
+            BroadcastConversion::Bt2020ToBt709,
+            BroadcastGamutMapping::HuePreserving(GamutClipMode::default()),
+            BroadcastLuminance::Constant,
+        )
+        .unwrap();
+        let src = [10u8, 200, 90, 128, 255, 0, 0, 77];
+        let mut upscaled = [0u8; 8];
+        up.transform_8bit(Layout::Rgba, &src, &mut upscaled)
+            .unwrap();
+        let mut dst = [0u8; 8];
+        down.transform_8bit(Layout::Rgba, &upscaled, &mut dst)
+            .unwrap();
+        for (&v, &e) in dst.iter().zip(src.iter()) {
+            assert!(v.abs_diff(e) <= 3, "Expected {src:?}, got {dst:?}");
+        }
+
+        // Saturated BT.2020 green is out of BT.709 gamut, channels must stay ordered as hue is kept
+        let green = down.convert(Rgb::new(0.1, 0.9, 0.2));
+        assert!(green.g > green.b && green.b >= green.r, "{green:?}");
+        let white = down.convert(Rgb::new(1., 1., 1.));
+        for v in [white.r, white.g, white.b] {
+            assert!((v - 1.).abs() < 1e-3, "{white:?}");
+        }
+    }
+}
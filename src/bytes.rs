@@ -0,0 +1,239 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Viewing raw byte buffers as 16 bit or floating point samples without copying.
+use crate::{CmsError, TransformExecutor};
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for u16 {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// Sample type which may be read from bytes in native endianness.
+///
+/// Every bit pattern is a valid value of such type, so a properly aligned byte buffer
+/// can be viewed as a slice of samples.
+pub trait ByteSample: Copy + Default + sealed::Sealed {
+    fn from_ne_byte_slice(bytes: &[u8]) -> Self;
+    fn write_ne_bytes(self, into: &mut [u8]);
+}
+
+macro_rules! byte_sample {
+    ($t:ty) => {
+        impl ByteSample for $t {
+            #[inline]
+            fn from_ne_byte_slice(bytes: &[u8]) -> Self {
+                <$t>::from_ne_bytes(bytes.try_into().unwrap())
+            }
+
+            #[inline]
+            fn write_ne_bytes(self, into: &mut [u8]) {
+                into.copy_from_slice(&self.to_ne_bytes());
+            }
+        }
+    };
+}
+
+byte_sample!(u16);
+byte_sample!(f32);
+byte_sample!(f64);
+
+#[cfg(any(
+    feature = "avx",
+    feature = "sse",
+    feature = "avx512",
+    feature = "neon",
+    feature = "platform"
+))]
+fn check_byte_buffer<T: ByteSample>(bytes: &[u8]) -> Result<(), CmsError> {
+    if bytes.len() % size_of::<T>() != 0 {
+        return Err(CmsError::LaneMultipleOfChannels);
+    }
+    if (bytes.as_ptr() as usize) % align_of::<T>() != 0 {
+        return Err(CmsError::UnalignedSamples(align_of::<T>()));
+    }
+    Ok(())
+}
+
+/// Views bytes as samples in native endianness.
+///
+/// Fails when buffer is not aligned for `T` or its length is not multiple of `T` size.
+///
+/// Available only with SIMD or platform features, crate forbids `unsafe` without them.
+#[cfg(any(
+    feature = "avx",
+    feature = "sse",
+    feature = "avx512",
+    feature = "neon",
+    feature = "platform"
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "avx",
+        feature = "sse",
+        feature = "avx512",
+        feature = "neon",
+        feature = "platform"
+    )))
+)]
+pub fn samples_from_bytes<T: ByteSample>(bytes: &[u8]) -> Result<&[T], CmsError> {
+    check_byte_buffer::<T>(bytes)?;
+    // Safety: buffer is aligned, fully covers the samples and any bit pattern is valid `T`
+    Ok(unsafe {
+        std::slice::from_raw_parts(bytes.as_ptr().cast::<T>(), bytes.len() / size_of::<T>())
+    })
+}
+
+/// Mutable version of [samples_from_bytes].
+#[cfg(any(
+    feature = "avx",
+    feature = "sse",
+    feature = "avx512",
+    feature = "neon",
+    feature = "platform"
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "avx",
+        feature = "sse",
+        feature = "avx512",
+        feature = "neon",
+        feature = "platform"
+    )))
+)]
+pub fn samples_from_bytes_mut<T: ByteSample>(bytes: &mut [u8]) -> Result<&mut [T], CmsError> {
+    check_byte_buffer::<T>(bytes)?;
+    // Safety: buffer is aligned, fully covers the samples and any bit pattern is valid `T`
+    Ok(unsafe {
+        std::slice::from_raw_parts_mut(bytes.as_mut_ptr().cast::<T>(), bytes.len() / size_of::<T>())
+    })
+}
+
+/// Transforms raw byte buffers holding native endian samples.
+///
+/// Aligned buffers are transformed in place, unaligned ones are copied through
+/// temporary storage, so this never requires `unsafe` from the caller.
+/// Without SIMD and platform features buffers are always copied.
+pub trait TransformBytesExecutor<V: ByteSample> {
+    fn transform_bytes(&self, src: &[u8], dst: &mut [u8]) -> Result<(), CmsError>;
+}
+
+impl<V: ByteSample, E: TransformExecutor<V> + ?Sized> TransformBytesExecutor<V> for E {
+    fn transform_bytes(&self, src: &[u8], dst: &mut [u8]) -> Result<(), CmsError> {
+        if src.len() % size_of::<V>() != 0 || dst.len() % size_of::<V>() != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        #[cfg(any(
+            feature = "avx",
+            feature = "sse",
+            feature = "avx512",
+            feature = "neon",
+            feature = "platform"
+        ))]
+        if let (Ok(src), Ok(dst)) = (
+            samples_from_bytes::<V>(src),
+            samples_from_bytes_mut::<V>(dst),
+        ) {
+            return self.transform(src, dst);
+        }
+        let src = src
+            .chunks_exact(size_of::<V>())
+            .map(V::from_ne_byte_slice)
+            .collect::<Vec<V>>();
+        let mut samples = vec![V::default(); dst.len() / size_of::<V>()];
+        self.transform(&src, &mut samples)?;
+        for (dst, sample) in dst.chunks_exact_mut(size_of::<V>()).zip(samples) {
+            sample.write_ne_bytes(dst);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColorProfile, Layout, TransformOptions};
+
+    #[test]
+    fn test_transform_bytes() {
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let transform = srgb
+            .create_transform_16bit(
+                Layout::Rgb,
+                &bt2020,
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let src = (0..3 * 64)
+            .map(|i| (i * 1031 % 65536) as u16)
+            .collect::<Vec<_>>();
+        let mut expected = vec![0u16; src.len()];
+        transform.transform(&src, &mut expected).unwrap();
+        let expected_bytes = expected
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect::<Vec<_>>();
+
+        // One extra leading byte lets both aligned and unaligned views be tested
+        let mut src_storage = vec![0u8; src.len() * 2 + 1];
+        let mut dst_storage = vec![0u8; src.len() * 2 + 1];
+        for shift in 0..2 {
+            let src_bytes = &mut src_storage[shift..shift + src.len() * 2];
+            for (dst, v) in src_bytes.chunks_exact_mut(2).zip(src.iter()) {
+                dst.copy_from_slice(&v.to_ne_bytes());
+            }
+            let src_bytes = &src_storage[shift..shift + src.len() * 2];
+            let dst_bytes = &mut dst_storage[shift..shift + src.len() * 2];
+            transform.transform_bytes(src_bytes, dst_bytes).unwrap();
+            assert_eq!(dst_bytes, expected_bytes.as_slice());
+        }
+
+        #[cfg(any(
+            feature = "avx",
+            feature = "sse",
+            feature = "avx512",
+            feature = "neon",
+            feature = "platform"
+        ))]
+        {
+            let aligned = samples_from_bytes::<u16>(&src_storage[..4]).is_ok();
+            let unaligned = samples_from_bytes::<u16>(&src_storage[1..5]).is_ok();
+            assert_ne!(aligned, unaligned);
+            assert_eq!(
+                samples_from_bytes::<u16>(&src_storage[..3]),
+                Err(CmsError::LaneMultipleOfChannels)
+            );
+        }
+    }
+}
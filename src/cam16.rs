@@ -0,0 +1,403 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::mlaf::mlaf;
+use crate::{Chromaticity, Matrix3f, Vector3f, Xyz};
+use pxfm::{f_atan2f, f_expf, f_hypotf, f_powf, f_sincosf};
+
+/// Surround of the viewing field as defined by CIECAM02/CAM16
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Cam16Surround {
+    /// Surface colors viewed in an average lit room
+    #[default]
+    Average,
+    /// Television or display in a dim room
+    Dim,
+    /// Projection in a dark room
+    Dark,
+}
+
+impl Cam16Surround {
+    /// Returns `F`, `c` and `Nc` factors
+    #[inline]
+    const fn factors(self) -> (f32, f32, f32) {
+        match self {
+            Cam16Surround::Average => (1.0, 0.69, 1.0),
+            Cam16Surround::Dim => (0.9, 0.59, 0.9),
+            Cam16Surround::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+const M16: Matrix3f = Matrix3f {
+    v: [
+        [0.401288, 0.650173, -0.051461],
+        [-0.250268, 1.204414, 0.045854],
+        [-0.002079, 0.048952, 0.953127],
+    ],
+};
+
+const M16_INVERSE: Matrix3f = M16.inverse();
+
+/// Viewing conditions for [Cam16] appearance model
+///
+/// All dependent parameters are computed once on creation,
+/// so conditions are read only, create new ones to change them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cam16ViewingConditions {
+    white_point: Xyz,
+    adapting_luminance: f32,
+    background_luminance: f32,
+    surround: Cam16Surround,
+    n: f32,
+    z: f32,
+    nbb: f32,
+    ncb: f32,
+    c: f32,
+    nc: f32,
+    fl: f32,
+    fl_root: f32,
+    aw: f32,
+    d_rgb: [f32; 3],
+}
+
+#[inline]
+fn adapt_cone(v: f32, fl: f32) -> f32 {
+    let f = f_powf(fl * v.abs() * 0.01, 0.42);
+    (400. * f / (f + 27.13)).copysign(v)
+}
+
+#[inline]
+fn unadapt_cone(v: f32, fl: f32) -> f32 {
+    let abs = v.abs();
+    let base = (27.13 * abs / (400. - abs)).max(0.);
+    (100. / fl * f_powf(base, 1. / 0.42)).copysign(v)
+}
+
+impl Cam16ViewingConditions {
+    /// Creates new viewing conditions
+    ///
+    /// # Arguments
+    ///
+    /// * `white_point`: adapted white, `Y` normalized to `1.0`
+    /// * `adapting_luminance`: luminance of adapting field in cd/m², often 20% of the white luminance
+    /// * `background_luminance`: relative luminance of the background in `0..100`
+    /// * `surround`: see [Cam16Surround]
+    /// * `discounting`: if illuminant is discounted, then adaptation is considered as complete
+    pub fn new(
+        white_point: Xyz,
+        adapting_luminance: f32,
+        background_luminance: f32,
+        surround: Cam16Surround,
+        discounting: bool,
+    ) -> Cam16ViewingConditions {
+        let white = Vector3f {
+            v: [
+                white_point.x * 100.,
+                white_point.y * 100.,
+                white_point.z * 100.,
+            ],
+        };
+        let rgb_w = M16.mul_vector(white);
+
+        let (f, c, nc) = surround.factors();
+
+        let d = if discounting {
+            1.
+        } else {
+            (f * (1. - (1. / 3.6) * f_expf((-adapting_luminance - 42.) / 92.)))
+                .max(0.)
+                .min(1.)
+        };
+
+        let yw = white.v[1];
+        let d_rgb = [
+            mlaf(1. - d, d * yw, 1. / rgb_w.v[0]),
+            mlaf(1. - d, d * yw, 1. / rgb_w.v[1]),
+            mlaf(1. - d, d * yw, 1. / rgb_w.v[2]),
+        ];
+
+        let k = 1. / (5. * adapting_luminance + 1.);
+        let k4 = k * k * k * k;
+        let k4f = 1. - k4;
+        let fl = mlaf(
+            k4 * adapting_luminance,
+            0.1 * k4f * k4f,
+            (5. * adapting_luminance).cbrt(),
+        );
+
+        let n = background_luminance / yw;
+        let z = 1.48 + n.sqrt();
+        let nbb = 0.725 / f_powf(n, 0.2);
+        let ncb = nbb;
+
+        let rgb_aw = [
+            adapt_cone(rgb_w.v[0] * d_rgb[0], fl),
+            adapt_cone(rgb_w.v[1] * d_rgb[1], fl),
+            adapt_cone(rgb_w.v[2] * d_rgb[2], fl),
+        ];
+        let aw = mlaf(mlaf(2. * rgb_aw[0], 1., rgb_aw[1]), 0.05, rgb_aw[2]) * nbb;
+
+        Cam16ViewingConditions {
+            white_point,
+            adapting_luminance,
+            background_luminance,
+            surround,
+            n,
+            z,
+            nbb,
+            ncb,
+            c,
+            nc,
+            fl,
+            fl_root: f_powf(fl, 0.25),
+            aw,
+            d_rgb,
+        }
+    }
+
+    /// Adapted white, `Y` is normalized to `1.0`
+    #[inline]
+    pub fn white_point(&self) -> Xyz {
+        self.white_point
+    }
+
+    /// Adapting field luminance in cd/m²
+    #[inline]
+    pub fn adapting_luminance(&self) -> f32 {
+        self.adapting_luminance
+    }
+
+    /// Relative background luminance in `0..100`
+    #[inline]
+    pub fn background_luminance(&self) -> f32 {
+        self.background_luminance
+    }
+
+    /// Surround of the viewing field
+    #[inline]
+    pub fn surround(&self) -> Cam16Surround {
+        self.surround
+    }
+
+    /// Luminance level adaptation factor `F_L`
+    #[inline]
+    pub fn luminance_adaptation(&self) -> f32 {
+        self.fl
+    }
+
+    /// Achromatic response of the white
+    #[inline]
+    pub fn white_achromatic_response(&self) -> f32 {
+        self.aw
+    }
+}
+
+impl Default for Cam16ViewingConditions {
+    /// D65 white, adapting luminance of ~11.72 cd/m² (`200 / π` times
+    /// luminance of 50 L*), background of L* 50 and average surround
+    fn default() -> Self {
+        Cam16ViewingConditions::new(
+            Chromaticity::D65.to_xyz(),
+            200. / std::f32::consts::PI * 0.18418652,
+            18.418652,
+            Cam16Surround::Average,
+            false,
+        )
+    }
+}
+
+/// CAM16 color appearance correlates
+///
+/// See [Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS](https://doi.org/10.1002/col.22131).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Cam16 {
+    /// Lightness `J` in `0..100`
+    pub j: f32,
+    /// Chroma `C`
+    pub c: f32,
+    /// Hue angle `h` in degrees `0..360`
+    pub h: f32,
+    /// Colorfulness `M`
+    pub m: f32,
+    /// Saturation `s`
+    pub s: f32,
+    /// Brightness `Q`
+    pub q: f32,
+}
+
+impl Cam16 {
+    /// Computes appearance correlates for the given `XYZ`.
+    ///
+    /// `XYZ` expected to be normalized so the white has `Y = 1.0`.
+    pub fn from_xyz(xyz: Xyz, conditions: &Cam16ViewingConditions) -> Cam16 {
+        let rgb = M16.mul_vector(Vector3f {
+            v: [xyz.x * 100., xyz.y * 100., xyz.z * 100.],
+        });
+
+        let ra = adapt_cone(rgb.v[0] * conditions.d_rgb[0], conditions.fl);
+        let ga = adapt_cone(rgb.v[1] * conditions.d_rgb[1], conditions.fl);
+        let ba = adapt_cone(rgb.v[2] * conditions.d_rgb[2], conditions.fl);
+
+        // Opponent dimensions
+        let a = (11. * ra - 12. * ga + ba) / 11.;
+        let b = (ra + ga - 2. * ba) / 9.;
+
+        let u = (20. * ra + 20. * ga + 21. * ba) / 20.;
+        let p2 = (40. * ra + 20. * ga + ba) / 20.;
+
+        let hue_rad = f_atan2f(b, a);
+        let mut h = hue_rad.to_degrees();
+        if h < 0. {
+            h += 360.;
+        } else if h >= 360. {
+            h -= 360.;
+        }
+
+        let ac = p2 * conditions.nbb;
+
+        let j = 100. * f_powf(ac / conditions.aw, conditions.c * conditions.z);
+        let q = (4. / conditions.c) * (j * 0.01).sqrt() * (conditions.aw + 4.) * conditions.fl_root;
+
+        let e_hue = 0.25 * (f_sincosf(hue_rad + 2.).1 + 3.8);
+        let p1 = 50000. / 13. * e_hue * conditions.nc * conditions.ncb;
+        let t = p1 * f_hypotf(a, b) / (u + 0.305);
+        let alpha = f_powf(t, 0.9) * f_powf(1.64 - f_powf(0.29, conditions.n), 0.73);
+
+        let c = alpha * (j * 0.01).sqrt();
+        let m = c * conditions.fl_root;
+        let s = if q > 0. {
+            50. * (conditions.c * alpha / (conditions.aw + 4.)).sqrt()
+        } else {
+            0.
+        };
+
+        Cam16 { j, c, h, m, s, q }
+    }
+
+    /// Converts lightness `J`, chroma `C` and hue `h` in degrees back to `XYZ`.
+    ///
+    /// Output is normalized so the white has `Y = 1.0`.
+    pub fn jch_to_xyz(j: f32, c: f32, h: f32, conditions: &Cam16ViewingConditions) -> Xyz {
+        if j <= 0. {
+            return Xyz::new(0., 0., 0.);
+        }
+        let alpha = if c == 0. { 0. } else { c / (j * 0.01).sqrt() };
+        let t = f_powf(
+            alpha / f_powf(1.64 - f_powf(0.29, conditions.n), 0.73),
+            1. / 0.9,
+        );
+        let hue_rad = h.to_radians();
+
+        let e_hue = 0.25 * (f_sincosf(hue_rad + 2.).1 + 3.8);
+        let ac = conditions.aw * f_powf(j * 0.01, 1. / conditions.c / conditions.z);
+        let p1 = e_hue * (50000. / 13.) * conditions.nc * conditions.ncb;
+        let p2 = ac / conditions.nbb;
+
+        let (h_sin, h_cos) = f_sincosf(hue_rad);
+
+        let gamma = 23. * (p2 + 0.305) * t / (23. * p1 + 11. * t * h_cos + 108. * t * h_sin);
+        let a = gamma * h_cos;
+        let b = gamma * h_sin;
+
+        let ra = (460. * p2 + 451. * a + 288. * b) / 1403.;
+        let ga = (460. * p2 - 891. * a - 261. * b) / 1403.;
+        let ba = (460. * p2 - 220. * a - 6300. * b) / 1403.;
+
+        let rgb = Vector3f {
+            v: [
+                unadapt_cone(ra, conditions.fl) / conditions.d_rgb[0],
+                unadapt_cone(ga, conditions.fl) / conditions.d_rgb[1],
+                unadapt_cone(ba, conditions.fl) / conditions.d_rgb[2],
+            ],
+        };
+
+        let xyz = M16_INVERSE.mul_vector(rgb);
+        Xyz::new(xyz.v[0] * 0.01, xyz.v[1] * 0.01, xyz.v[2] * 0.01)
+    }
+
+    /// Converts this appearance back to `XYZ` using `J`, `C` and `h`
+    #[inline]
+    pub fn to_xyz(&self, conditions: &Cam16ViewingConditions) -> Xyz {
+        Cam16::jch_to_xyz(self.j, self.c, self.h, conditions)
+    }
+}
+
+/// Computes CAM16 colorfulness `M` for `XYZ` normalized to `Y = 1.0` white
+#[inline]
+pub fn cam16_colorfulness(xyz: Xyz, conditions: &Cam16ViewingConditions) -> f32 {
+    Cam16::from_xyz(xyz, conditions).m
+}
+
+/// Computes CAM16 saturation `s` for `XYZ` normalized to `Y = 1.0` white
+#[inline]
+pub fn cam16_saturation(xyz: Xyz, conditions: &Cam16ViewingConditions) -> f32 {
+    Cam16::from_xyz(xyz, conditions).s
+}
+
+/// Computes CAM16 brightness `Q` for `XYZ` normalized to `Y = 1.0` white
+#[inline]
+pub fn cam16_brightness(xyz: Xyz, conditions: &Cam16ViewingConditions) -> f32 {
+    Cam16::from_xyz(xyz, conditions).q
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cam16_red() {
+        let conditions = Cam16ViewingConditions::new(
+            Xyz::new(0.95047, 1.0, 1.08883),
+            200. / std::f32::consts::PI * 0.18418652,
+            18.418652,
+            Cam16Surround::Average,
+            false,
+        );
+        let red = Xyz::new(0.41233895, 0.2126, 0.01932141);
+        let cam = Cam16::from_xyz(red, &conditions);
+        assert!((cam.h - 27.408).abs() < 0.05, "Hue {}", cam.h);
+        assert!((cam.c - 113.357).abs() < 0.1, "Chroma {}", cam.c);
+        assert!((cam.j - 46.445).abs() < 0.05, "Lightness {}", cam.j);
+        assert!((cam.m - 89.494).abs() < 0.1, "Colorfulness {}", cam.m);
+        assert!((cam.s - 91.889).abs() < 0.1, "Saturation {}", cam.s);
+        assert!((cam.q - 105.988).abs() < 0.1, "Brightness {}", cam.q);
+    }
+
+    #[test]
+    fn test_cam16_round_trip() {
+        let conditions = Cam16ViewingConditions::default();
+        let xyz = Xyz::new(0.3, 0.4, 0.2);
+        let cam = Cam16::from_xyz(xyz, &conditions);
+        let restored = cam.to_xyz(&conditions);
+        assert!((restored.x - xyz.x).abs() < 1e-3);
+        assert!((restored.y - xyz.y).abs() < 1e-3);
+        assert!((restored.z - xyz.z).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,195 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::trc::build_trc_table;
+use crate::{ColorPrimaries, ToneReprCurve};
+use pxfm::{f_log10, f_pow};
+
+const CAMERA_LOG_TABLE_SIZE: i32 = 4096;
+
+/// Log encodings of digital cinema cameras.
+///
+/// Code values are normalized full range values as in the manufacturer's formulas,
+/// legal range footage must be expanded first.
+/// Scene linear light uses 0.18 for the middle gray.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CameraLog {
+    /// Sony S-Log3
+    SLog3,
+    /// Panasonic V-Log
+    VLog,
+    /// Canon Log 3
+    CLog3,
+}
+
+impl CameraLog {
+    /// Converts code value to scene linear light
+    pub fn to_linear(self, v: f64) -> f64 {
+        match self {
+            CameraLog::SLog3 => {
+                let cv = v * 1023.;
+                if cv >= 171.2102946929 {
+                    f_pow(10., (cv - 420.) / 261.5) * (0.18 + 0.01) - 0.01
+                } else {
+                    (cv - 95.) * 0.01125 / (171.2102946929 - 95.)
+                }
+            }
+            CameraLog::VLog => {
+                if v < 0.181 {
+                    (v - 0.125) / 5.6
+                } else {
+                    f_pow(10., (v - 0.598206) / 0.241514) - 0.00873
+                }
+            }
+            CameraLog::CLog3 => {
+                let x = if v < 0.097465473 {
+                    -(f_pow(10., (0.12783901 - v) / 0.36726845) - 1.) / 14.98325
+                } else if v <= 0.15277891 {
+                    (v - 0.12512219) / 1.9754798
+                } else {
+                    (f_pow(10., (v - 0.12240537) / 0.36726845) - 1.) / 14.98325
+                };
+                x * 0.9
+            }
+        }
+    }
+
+    /// Converts scene linear light to code value
+    pub fn from_linear(self, x: f64) -> f64 {
+        match self {
+            CameraLog::SLog3 => {
+                if x >= 0.01125 {
+                    (420. + f_log10((x + 0.01) / (0.18 + 0.01)) * 261.5) / 1023.
+                } else {
+                    (x * (171.2102946929 - 95.) / 0.01125 + 95.) / 1023.
+                }
+            }
+            CameraLog::VLog => {
+                if x < 0.01 {
+                    5.6 * x + 0.125
+                } else {
+                    0.241514 * f_log10(x + 0.00873) + 0.598206
+                }
+            }
+            CameraLog::CLog3 => {
+                let y = x / 0.9;
+                if y < -0.014 {
+                    -0.36726845 * f_log10(1. - 14.98325 * y) + 0.12783901
+                } else if y <= 0.014 {
+                    1.9754798 * y + 0.12512219
+                } else {
+                    0.36726845 * f_log10(14.98325 * y + 1.) + 0.12240537
+                }
+            }
+        }
+    }
+
+    /// Scene linear light at the maximum code value
+    pub fn max_linear(self) -> f64 {
+        self.to_linear(1.)
+    }
+
+    /// Native primaries of the camera encoding, all of them use D65 white point
+    pub const fn primaries(self) -> ColorPrimaries {
+        match self {
+            CameraLog::SLog3 => ColorPrimaries::S_GAMUT3_CINE,
+            CameraLog::VLog => ColorPrimaries::V_GAMUT,
+            CameraLog::CLog3 => ColorPrimaries::CINEMA_GAMUT,
+        }
+    }
+
+    /// Creates tone curve for ICC profile.
+    ///
+    /// ICC curves are limited to \[0; 1\], so linear light is divided by [CameraLog::max_linear],
+    /// the maximum code value maps to the profile white.
+    pub fn curve(self) -> ToneReprCurve {
+        let max_linear = self.max_linear();
+        ToneReprCurve::Lut(build_trc_table(CAMERA_LOG_TABLE_SIZE, |v| {
+            self.to_linear(v) / max_linear
+        }))
+    }
+}
+
+impl ToneReprCurve {
+    /// Sony S-Log3 curve, see [CameraLog::curve]
+    pub fn new_s_log3() -> ToneReprCurve {
+        CameraLog::SLog3.curve()
+    }
+
+    /// Panasonic V-Log curve, see [CameraLog::curve]
+    pub fn new_v_log() -> ToneReprCurve {
+        CameraLog::VLog.curve()
+    }
+
+    /// Canon Log 3 curve, see [CameraLog::curve]
+    pub fn new_c_log3() -> ToneReprCurve {
+        CameraLog::CLog3.curve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColorProfile, Layout};
+
+    #[test]
+    fn test_camera_log_round_trip() {
+        for log in [CameraLog::SLog3, CameraLog::VLog, CameraLog::CLog3] {
+            for x in [0., 0.005, 0.18, 0.9, 5., 12.] {
+                let v = log.from_linear(x);
+                assert!((log.to_linear(v) - x).abs() < 1e-6, "{log:?} {x}");
+            }
+        }
+        // Published middle gray code values
+        assert!((CameraLog::SLog3.from_linear(0.18) * 1023. - 420.).abs() < 0.5);
+        assert!((CameraLog::VLog.from_linear(0.18) - 0.423).abs() < 1e-3);
+        assert!((CameraLog::CLog3.from_linear(0.18) - 0.343).abs() < 2e-3);
+    }
+
+    #[test]
+    fn test_camera_log_profile() {
+        let profile = ColorProfile::new_camera_log(CameraLog::SLog3);
+        let transform = profile
+            .create_transform_f32(
+                Layout::Rgb,
+                &ColorProfile::new_srgb(),
+                Layout::Rgb,
+                Default::default(),
+            )
+            .unwrap();
+        let mut dst = [0f32; 6];
+        transform
+            .transform(&[1., 1., 1., 0.41, 0.41, 0.41], &mut dst)
+            .unwrap();
+        for v in &dst[..3] {
+            assert!((v - 1.).abs() < 1e-2, "{dst:?}");
+        }
+        assert!(dst[3] < dst[0] && dst[3] > 0., "{dst:?}");
+        assert!((dst[3] - dst[4]).abs() < 1e-2 && (dst[4] - dst[5]).abs() < 1e-2);
+    }
+}
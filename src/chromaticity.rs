@@ -26,9 +26,9 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::{CmsError, XyY, XyYRepresentable, Xyz, Xyzd};
+use crate::{CmsError, ColorPrimaries, Vector2f, XyY, XyYRepresentable, Xyz, Xyzd};
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq)]
 #[repr(C)]
 pub struct Chromaticity {
     pub x: f32,
@@ -93,6 +93,35 @@ impl Chromaticity {
         }
     }
 
+    /// Converts this chromaticity (`x`, `y`) to a tristimulus [`Xyz`] value
+    /// with the provided luminance `Y`.
+    #[inline]
+    pub const fn to_xyz_with_luminance(&self, luminance: f32) -> Xyz {
+        let reciprocal = if self.y != 0. { luminance / self.y } else { 0. };
+        Xyz {
+            x: self.x * reciprocal,
+            y: luminance,
+            z: (1f32 - self.x - self.y) * reciprocal,
+        }
+    }
+
+    /// Checks if this chromaticity lies inside the triangle formed by `primaries`.
+    ///
+    /// Points exactly on the triangle edges are considered inside.
+    #[inline]
+    pub const fn contains(&self, primaries: ColorPrimaries) -> bool {
+        #[inline]
+        const fn edge(a: Chromaticity, b: Chromaticity, p: Chromaticity) -> f32 {
+            Vector2f::new(b.x - a.x, b.y - a.y).cross(Vector2f::new(p.x - a.x, p.y - a.y))
+        }
+        let d0 = edge(primaries.red, primaries.green, *self);
+        let d1 = edge(primaries.green, primaries.blue, *self);
+        let d2 = edge(primaries.blue, primaries.red, *self);
+        let has_negative = d0 < 0. || d1 < 0. || d2 < 0.;
+        let has_positive = d0 > 0. || d1 > 0. || d2 > 0.;
+        !(has_negative && has_positive)
+    }
+
     #[inline]
     pub const fn to_xyyb(&self) -> XyY {
         XyY {
@@ -141,3 +170,42 @@ impl TryFrom<Xyz> for Chromaticity {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        assert!(Chromaticity::D65.contains(ColorPrimaries::BT_709));
+        assert!(ColorPrimaries::BT_709.red.contains(ColorPrimaries::BT_709));
+        assert!(
+            ColorPrimaries::BT_2020
+                .green
+                .contains(ColorPrimaries::BT_2020)
+        );
+        assert!(
+            !ColorPrimaries::BT_2020
+                .green
+                .contains(ColorPrimaries::BT_709)
+        );
+        assert!(
+            ColorPrimaries::BT_709
+                .green
+                .contains(ColorPrimaries::BT_2020)
+        );
+        assert!(!Chromaticity::new(0.1, 0.8).contains(ColorPrimaries::DISPLAY_P3));
+    }
+
+    #[test]
+    fn test_to_xyz_with_luminance() {
+        let xyz = Chromaticity::D65.to_xyz_with_luminance(0.5);
+        let unit = Chromaticity::D65.to_xyz();
+        assert!((xyz.x - unit.x * 0.5).abs() < 1e-6);
+        assert_eq!(xyz.y, 0.5);
+        assert!((xyz.z - unit.z * 0.5).abs() < 1e-6);
+        let back = Chromaticity::try_from(xyz).unwrap();
+        assert!((back.x - Chromaticity::D65.x).abs() < 1e-6);
+        assert!((back.y - Chromaticity::D65.y).abs() < 1e-6);
+    }
+}
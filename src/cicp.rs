@@ -108,7 +108,7 @@ impl TryFrom<u8> for CicpColorPrimaries {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(C)]
 pub struct ColorPrimaries {
     pub red: Chromaticity,
@@ -141,6 +141,52 @@ impl ColorPrimaries {
         },
     };
 
+    /// ACES AP0 primaries, same as [`ACES_2065_1`](Self::ACES_2065_1).
+    pub const ACES_AP0: ColorPrimaries = Self::ACES_2065_1;
+
+    /// ACES AP1 primaries, same as [`ACES_CG`](Self::ACES_CG).
+    pub const ACES_AP1: ColorPrimaries = Self::ACES_CG;
+
+    /// Sony S-Gamut3, D65 white point.
+    pub const S_GAMUT3: ColorPrimaries = ColorPrimaries {
+        red: Chromaticity { x: 0.730, y: 0.280 },
+        green: Chromaticity { x: 0.140, y: 0.855 },
+        blue: Chromaticity {
+            x: 0.100,
+            y: -0.050,
+        },
+    };
+
+    /// Sony S-Gamut3.Cine, D65 white point.
+    pub const S_GAMUT3_CINE: ColorPrimaries = ColorPrimaries {
+        red: Chromaticity { x: 0.766, y: 0.275 },
+        green: Chromaticity { x: 0.225, y: 0.800 },
+        blue: Chromaticity {
+            x: 0.089,
+            y: -0.087,
+        },
+    };
+
+    /// Panasonic V-Gamut, D65 white point.
+    pub const V_GAMUT: ColorPrimaries = ColorPrimaries {
+        red: Chromaticity { x: 0.730, y: 0.280 },
+        green: Chromaticity { x: 0.165, y: 0.840 },
+        blue: Chromaticity {
+            x: 0.100,
+            y: -0.030,
+        },
+    };
+
+    /// Canon Cinema Gamut, D65 white point.
+    pub const CINEMA_GAMUT: ColorPrimaries = ColorPrimaries {
+        red: Chromaticity { x: 0.740, y: 0.270 },
+        green: Chromaticity { x: 0.170, y: 1.140 },
+        blue: Chromaticity {
+            x: 0.080,
+            y: -0.100,
+        },
+    };
+
     /// [Adobe RGB](https://en.wikipedia.org/wiki/Adobe_RGB_color_space) (1998).
     pub const ADOBE_RGB: ColorPrimaries = ColorPrimaries {
         red: Chromaticity { x: 0.64, y: 0.33 },
@@ -0,0 +1,262 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::TransformMatrixShaper;
+use crate::mlaf::mlaf;
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, Layout, TransformExecutor, TransformOptions, Vector3f,
+};
+
+/// Bits of clipping mask produced by [ClippingTransform16Bit]
+pub struct ClipMask;
+
+impl ClipMask {
+    pub const RED_HIGHLIGHT: u8 = 1 << 0;
+    pub const GREEN_HIGHLIGHT: u8 = 1 << 1;
+    pub const BLUE_HIGHLIGHT: u8 = 1 << 2;
+    pub const RED_SHADOW: u8 = 1 << 3;
+    pub const GREEN_SHADOW: u8 = 1 << 4;
+    pub const BLUE_SHADOW: u8 = 1 << 5;
+    /// Any channel was clipped above destination range
+    pub const HIGHLIGHTS: u8 = Self::RED_HIGHLIGHT | Self::GREEN_HIGHLIGHT | Self::BLUE_HIGHLIGHT;
+    /// Any channel was clipped below destination range
+    pub const SHADOWS: u8 = Self::RED_SHADOW | Self::GREEN_SHADOW | Self::BLUE_SHADOW;
+}
+
+/// 16 bit matrix shaper RGB transform that reports clipped pixels
+/// while converting, see [ClippingTransform16Bit::transform_with_clip_mask].
+pub struct ClippingTransform16Bit {
+    profile: TransformMatrixShaper<u16, 65536>,
+    src_layout: Layout,
+    dst_layout: Layout,
+}
+
+/// Values that rounds to the range edge are not considered as clipped
+const CLIP_THRESHOLD: f32 = 0.5 / 65535.;
+
+impl ClippingTransform16Bit {
+    /// Transforms pixels, when `mask` is provided it receives [ClipMask] bits for each pixel,
+    /// zero means nothing was clipped.
+    ///
+    /// `mask` length must be equal to pixels count.
+    pub fn transform_with_clip_mask(
+        &self,
+        src: &[u16],
+        dst: &mut [u16],
+        mut mask: Option<&mut [u8]>,
+    ) -> Result<(), CmsError> {
+        let src_cn = self.src_layout;
+        let dst_cn = self.dst_layout;
+        let src_channels = src_cn.channels();
+        let dst_channels = dst_cn.channels();
+
+        if src.len() / src_channels != dst.len() / dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        if src.len() % src_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if dst.len() % dst_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if mask
+            .as_deref()
+            .is_some_and(|mask| mask.len() != src.len() / src_channels)
+        {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+
+        let transform = self.profile.adaptation_matrix;
+        let scale = 65535f32;
+
+        for (i, (src, dst)) in src
+            .chunks_exact(src_channels)
+            .zip(dst.chunks_exact_mut(dst_channels))
+            .enumerate()
+        {
+            let r = self.profile.r_linear[src[src_cn.r_i()] as usize];
+            let g = self.profile.g_linear[src[src_cn.g_i()] as usize];
+            let b = self.profile.b_linear[src[src_cn.b_i()] as usize];
+            let a = if src_channels == 4 {
+                src[src_cn.a_i()]
+            } else {
+                u16::MAX
+            };
+
+            let new_r = mlaf(
+                mlaf(r * transform.v[0][0], g, transform.v[0][1]),
+                b,
+                transform.v[0][2],
+            );
+            let new_g = mlaf(
+                mlaf(r * transform.v[1][0], g, transform.v[1][1]),
+                b,
+                transform.v[1][2],
+            );
+            let new_b = mlaf(
+                mlaf(r * transform.v[2][0], g, transform.v[2][1]),
+                b,
+                transform.v[2][2],
+            );
+
+            if let Some(mask) = mask.as_deref_mut() {
+                let flag = |v: f32, highlight: u8, shadow: u8| {
+                    if v > 1. + CLIP_THRESHOLD {
+                        highlight
+                    } else if v < -CLIP_THRESHOLD {
+                        shadow
+                    } else {
+                        0
+                    }
+                };
+                mask[i] = flag(new_r, ClipMask::RED_HIGHLIGHT, ClipMask::RED_SHADOW)
+                    | flag(new_g, ClipMask::GREEN_HIGHLIGHT, ClipMask::GREEN_SHADOW)
+                    | flag(new_b, ClipMask::BLUE_HIGHLIGHT, ClipMask::BLUE_SHADOW);
+            }
+
+            let new_r = mlaf(0.5f32, new_r.max(0f32).min(1f32), scale);
+            let new_g = mlaf(0.5f32, new_g.max(0f32).min(1f32), scale);
+            let new_b = mlaf(0.5f32, new_b.max(0f32).min(1f32), scale);
+
+            dst[dst_cn.r_i()] = self.profile.r_gamma[(new_r as u16) as usize];
+            dst[dst_cn.g_i()] = self.profile.g_gamma[(new_g as u16) as usize];
+            dst[dst_cn.b_i()] = self.profile.b_gamma[(new_b as u16) as usize];
+            if dst_channels == 4 {
+                dst[dst_cn.a_i()] = a;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TransformExecutor<u16> for ClippingTransform16Bit {
+    fn transform(&self, src: &[u16], dst: &mut [u16]) -> Result<(), CmsError> {
+        self.transform_with_clip_mask(src, dst, None)
+    }
+}
+
+impl ColorProfile {
+    /// Creates 16 bit RGB transform which may report clipping, see [ClippingTransform16Bit].
+    ///
+    /// Only matrix shaper profiles are supported, since only their out of range values are known exactly.
+    pub fn create_clipping_transform_16bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<ClippingTransform16Bit, CmsError> {
+        let is_matrix_shaper_pair = self.color_space == DataColorSpace::Rgb
+            && dst_pr.color_space == DataColorSpace::Rgb
+            && self.pcs == DataColorSpace::Xyz
+            && dst_pr.pcs == DataColorSpace::Xyz
+            && self.is_matrix_shaper()
+            && dst_pr.is_matrix_shaper()
+            && !self.has_device_to_pcs_lut()
+            && !dst_pr.has_pcs_to_device_lut();
+        if !is_matrix_shaper_pair {
+            return Err(CmsError::UnsupportedProfileConnection);
+        }
+        if src_layout != Layout::Rgb && src_layout != Layout::Rgba {
+            return Err(CmsError::InvalidLayout);
+        }
+        if dst_layout != Layout::Rgb && dst_layout != Layout::Rgba {
+            return Err(CmsError::InvalidLayout);
+        }
+
+        let mut transform = self.transform_matrix(dst_pr);
+        if let Some(scale) = options.luminance_scale(self, dst_pr) {
+            transform = transform.mul_scalar(scale);
+        }
+
+        let use_cicp = options.allow_use_cicp_transfer;
+        let profile = TransformMatrixShaper {
+            r_linear: self.shared_linearize_table::<u16, 65536, 16>(&self.red_trc, use_cicp)?,
+            g_linear: self.shared_linearize_table::<u16, 65536, 16>(&self.green_trc, use_cicp)?,
+            b_linear: self.shared_linearize_table::<u16, 65536, 16>(&self.blue_trc, use_cicp)?,
+            r_gamma: dst_pr
+                .build_gamma_table::<u16, 65536, 65536, 16>(&dst_pr.red_trc, use_cicp)?,
+            g_gamma: dst_pr
+                .build_gamma_table::<u16, 65536, 65536, 16>(&dst_pr.green_trc, use_cicp)?,
+            b_gamma: dst_pr
+                .build_gamma_table::<u16, 65536, 65536, 16>(&dst_pr.blue_trc, use_cicp)?,
+            adaptation_matrix: transform.to_f32(),
+            bias: Vector3f::default(),
+        };
+        Ok(ClippingTransform16Bit {
+            profile,
+            src_layout,
+            dst_layout,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipping_mask() {
+        let bt2020 = ColorProfile::new_bt2020();
+        let srgb = ColorProfile::new_srgb();
+        let transform = bt2020
+            .create_clipping_transform_16bit(
+                Layout::Rgb,
+                &srgb,
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let src = [
+            65535u16, 65535, 65535, // white
+            32768, 32768, 32768, // gray
+            0, 65535, 0, // Rec.2020 green
+        ];
+        let mut dst = [0u16; 9];
+        let mut mask = [0u8; 3];
+        transform
+            .transform_with_clip_mask(&src, &mut dst, Some(&mut mask))
+            .unwrap();
+        assert_eq!(mask[0], 0);
+        assert_eq!(mask[1], 0);
+        assert_ne!(mask[2] & ClipMask::GREEN_HIGHLIGHT, 0);
+        assert_ne!(mask[2] & ClipMask::SHADOWS, 0);
+        assert!(dst[0] >= 65534 && dst[1] >= 65534 && dst[2] >= 65534);
+
+        let mut plain = [0u16; 9];
+        transform.transform(&src, &mut plain).unwrap();
+        assert_eq!(plain, dst);
+
+        assert!(
+            transform
+                .transform_with_clip_mask(&src, &mut dst, Some(&mut [0u8; 2]))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,440 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Slice conversions between color models, for analytics over millions of samples.
+//!
+//! Pixels are converted in blocks of [LANES], split into planes, so the per-lane math
+//! is vectorized by the compiler, with AVX2 and FMA enabled at runtime when available.
+use crate::luv::{
+    LUV_CUTOFF_FORWARD_Y, LUV_MULTIPLIER_FORWARD_Y, LUV_MULTIPLIER_INVERSE_Y, LUV_WHITE_U_PRIME,
+    LUV_WHITE_V_PRIME,
+};
+use crate::{Chromaticity, CmsError, Matrix3f, SRGB_MATRIX, adaption_matrix};
+
+const LANES: usize = 8;
+
+/// Color model of interleaved triplets for [convert_color_models].
+///
+/// Models connect through CIE XYZ with D50 white where `Y` = 1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColorModel {
+    /// CIE XYZ, D50 white, `Y` of white is 1
+    Xyz,
+    /// CIE L\*a\*b\*, D50 white, same as [crate::Lab::from_xyz] of XYZ in PCS encoding
+    Lab,
+    /// CIE L\*u\*v\*, D50 white, same as [crate::Luv::from_xyz]
+    Luv,
+    /// [crate::Oklab] of sRGB primaries, D50 XYZ is taken as adapted sRGB
+    Oklab,
+    /// Kirk [crate::Yrg], XYZ is adapted to D65 with Bradford, `Y` is kept CIE 1931
+    Yrg,
+}
+
+type Planes = [[f32; LANES]; 3];
+
+#[inline(always)]
+fn cbrt(x: f32) -> f32 {
+    // Bit hack estimate refined by two Halley iterations, branchless so it vectorizes
+    let a = x.abs();
+    let mut y = f32::from_bits(a.to_bits() / 3 + 0x2a51_4067);
+    for _ in 0..2 {
+        let y3 = y * y * y;
+        y *= (y3 + 2. * a) / (2. * y3 + a).max(f32::MIN_POSITIVE);
+    }
+    y.copysign(x)
+}
+
+#[inline(always)]
+fn mul(m: &Matrix3f, v: [f32; 3]) -> [f32; 3] {
+    [
+        m.v[0][0] * v[0] + m.v[0][1] * v[1] + m.v[0][2] * v[2],
+        m.v[1][0] * v[0] + m.v[1][1] * v[1] + m.v[1][2] * v[2],
+        m.v[2][0] * v[0] + m.v[2][1] * v[1] + m.v[2][2] * v[2],
+    ]
+}
+
+#[inline(always)]
+fn lab_f(t: f32) -> f32 {
+    let linear = (841. / 108.) * t + 16. / 116.;
+    let curve = cbrt(t);
+    if t <= (24. / 116.) * (24. / 116.) * (24. / 116.) {
+        linear
+    } else {
+        curve
+    }
+}
+
+#[inline(always)]
+fn lab_f_inv(t: f32) -> f32 {
+    let linear = (108. / 841.) * (t - 16. / 116.);
+    let curve = t * t * t;
+    if t <= 24. / 116. { linear } else { curve }
+}
+
+#[inline(always)]
+fn xyz_to_lab(xyz: [f32; 3]) -> [f32; 3] {
+    let wp = Chromaticity::D50.to_xyz();
+    let fx = lab_f(xyz[0] / wp.x);
+    let fy = lab_f(xyz[1] / wp.y);
+    let fz = lab_f(xyz[2] / wp.z);
+    [116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz)]
+}
+
+#[inline(always)]
+fn lab_to_xyz(lab: [f32; 3]) -> [f32; 3] {
+    let wp = Chromaticity::D50.to_xyz();
+    let y = (lab[0] + 16.) / 116.;
+    [
+        lab_f_inv(y + 0.002 * lab[1]) * wp.x,
+        lab_f_inv(y) * wp.y,
+        lab_f_inv(y - 0.005 * lab[2]) * wp.z,
+    ]
+}
+
+#[inline(always)]
+fn xyz_to_luv(xyz: [f32; 3]) -> [f32; 3] {
+    let [x, y, z] = xyz;
+    let den = x + 15. * y + 3. * z;
+    let linear = LUV_MULTIPLIER_FORWARD_Y * y;
+    let curve = 116. * cbrt(y) - 16.;
+    let l = if y < LUV_CUTOFF_FORWARD_Y {
+        linear
+    } else {
+        curve
+    };
+    let l = l.clamp(0., 100.);
+    let r_den = if den != 0. { 1. / den } else { 0. };
+    let u = 13. * l * (4. * x * r_den - LUV_WHITE_U_PRIME);
+    let v = 13. * l * (9. * y * r_den - LUV_WHITE_V_PRIME);
+    if den != 0. { [l, u, v] } else { [l, 0., 0.] }
+}
+
+#[inline(always)]
+fn luv_to_xyz(luv: [f32; 3]) -> [f32; 3] {
+    let [l, u, v] = luv;
+    let l13 = 1. / (13. * l);
+    let u = LUV_WHITE_U_PRIME + u * l13;
+    let v = LUV_WHITE_V_PRIME + v * l13;
+    let jx = (l + 16.) / 116.;
+    let y = if l > 8. {
+        jx * jx * jx
+    } else {
+        l * LUV_MULTIPLIER_INVERSE_Y
+    };
+    let den = 1. / (4. * v);
+    let x = y * 9. * u * den;
+    let z = y * (12. - 3. * u - 20. * v) * den;
+    if l <= 0. || v == 0. {
+        [0., if l <= 0. { 0. } else { y }, 0.]
+    } else {
+        [x, y, z]
+    }
+}
+
+const OKLAB_M1: Matrix3f = Matrix3f {
+    v: [
+        [0.4122214708, 0.5363325363, 0.0514459929],
+        [0.2119034982, 0.6806995451, 0.1073969566],
+        [0.0883024619, 0.2817188376, 0.6299787005],
+    ],
+};
+
+const OKLAB_M2: Matrix3f = Matrix3f {
+    v: [
+        [0.2104542553, 0.7936177850, -0.0040720468],
+        [1.9779984951, -2.4285922050, 0.4505937099],
+        [0.0259040371, 0.7827717662, -0.8086757660],
+    ],
+};
+
+const OKLAB_M2_INV: Matrix3f = Matrix3f {
+    v: [
+        [1., 0.3963377774, 0.2158037573],
+        [1., -0.1055613458, -0.0638541728],
+        [1., -0.0894841775, -1.2914855480],
+    ],
+};
+
+const OKLAB_M1_INV: Matrix3f = Matrix3f {
+    v: [
+        [4.0767416621, -3.3077115913, 0.2309699292],
+        [-1.2684380046, 2.6097574011, -0.3413193965],
+        [-0.0041960863, -0.7034186147, 1.7076147010],
+    ],
+};
+
+const YRG_XYZ_TO_LMS: Matrix3f = Matrix3f {
+    v: [
+        [0.257085, 0.859943, -0.031061],
+        [-0.394427, 1.175800, 0.106423],
+        [0.064856, -0.076250, 0.559067],
+    ],
+};
+
+const YRG_LMS_TO_XYZ: Matrix3f = Matrix3f {
+    v: [
+        [1.8079466, -1.2997167, 0.34785876],
+        [0.61783963, 0.39595452, -0.041046873],
+        [-0.12546961, 0.20478038, 1.7427418],
+    ],
+};
+
+/// Matrices that depend on the white point, computed once per call
+struct Context {
+    xyz_to_srgb: Matrix3f,
+    srgb_to_xyz: Matrix3f,
+    d50_to_d65: Matrix3f,
+    d65_to_d50: Matrix3f,
+}
+
+impl Context {
+    fn new() -> Context {
+        let srgb_to_xyz = SRGB_MATRIX.to_f32();
+        let d50 = Chromaticity::D50.to_xyz();
+        let d65 = Chromaticity::D65.to_xyz();
+        Context {
+            xyz_to_srgb: SRGB_MATRIX.inverse().to_f32(),
+            srgb_to_xyz,
+            d50_to_d65: adaption_matrix(d50, d65),
+            d65_to_d50: adaption_matrix(d65, d50),
+        }
+    }
+}
+
+#[inline(always)]
+fn xyz_to_oklab(ctx: &Context, xyz: [f32; 3]) -> [f32; 3] {
+    let lms = mul(&OKLAB_M1, mul(&ctx.xyz_to_srgb, xyz));
+    mul(&OKLAB_M2, lms.map(cbrt))
+}
+
+#[inline(always)]
+fn oklab_to_xyz(ctx: &Context, oklab: [f32; 3]) -> [f32; 3] {
+    let lms = mul(&OKLAB_M2_INV, oklab).map(|x| x * x * x);
+    mul(&ctx.srgb_to_xyz, mul(&OKLAB_M1_INV, lms))
+}
+
+#[inline(always)]
+fn xyz_to_yrg(ctx: &Context, xyz: [f32; 3]) -> [f32; 3] {
+    let lms = mul(&YRG_XYZ_TO_LMS, mul(&ctx.d50_to_d65, xyz));
+    let y = 0.68990272 * lms[0] + 0.34832189 * lms[1];
+    let a = lms[0] + lms[1] + lms[2];
+    let r_a = if a == 0. { 0. } else { 1. / a };
+    let l = lms[0] * r_a;
+    let m = lms[1] * r_a;
+    let r = 1.0671 * l - 0.6873 * m + 0.02062;
+    let g = -0.0362 * l + 1.7182 * m - 0.05155;
+    [y, r, g]
+}
+
+#[inline(always)]
+fn yrg_to_xyz(ctx: &Context, yrg: [f32; 3]) -> [f32; 3] {
+    let [y, r, g] = yrg;
+    let l = 0.95 * r + 0.38 * g;
+    let m = 0.03 + 0.59 * g + 0.02 * r;
+    let den = 0.68990272 * l + 0.34832189 * m;
+    let a = if den == 0. { 0. } else { y / den };
+    let lms = [l * a, m * a, (1. - l - m) * a];
+    mul(&ctx.d65_to_d50, mul(&YRG_LMS_TO_XYZ, lms))
+}
+
+#[inline(always)]
+fn map_planes(planes: &mut Planes, f: impl Fn([f32; 3]) -> [f32; 3]) {
+    let [p0, p1, p2] = planes;
+    for ((x, y), z) in p0.iter_mut().zip(p1.iter_mut()).zip(p2.iter_mut()) {
+        [*x, *y, *z] = f([*x, *y, *z]);
+    }
+}
+
+#[inline(always)]
+fn to_xyz(ctx: &Context, model: ColorModel, planes: &mut Planes) {
+    match model {
+        ColorModel::Xyz => {}
+        ColorModel::Lab => map_planes(planes, lab_to_xyz),
+        ColorModel::Luv => map_planes(planes, luv_to_xyz),
+        ColorModel::Oklab => map_planes(planes, |v| oklab_to_xyz(ctx, v)),
+        ColorModel::Yrg => map_planes(planes, |v| yrg_to_xyz(ctx, v)),
+    }
+}
+
+#[inline(always)]
+fn from_xyz(ctx: &Context, model: ColorModel, planes: &mut Planes) {
+    match model {
+        ColorModel::Xyz => {}
+        ColorModel::Lab => map_planes(planes, xyz_to_lab),
+        ColorModel::Luv => map_planes(planes, xyz_to_luv),
+        ColorModel::Oklab => map_planes(planes, |v| xyz_to_oklab(ctx, v)),
+        ColorModel::Yrg => map_planes(planes, |v| xyz_to_yrg(ctx, v)),
+    }
+}
+
+#[inline(always)]
+fn convert_impl(src: &[f32], src_model: ColorModel, dst: &mut [f32], dst_model: ColorModel) {
+    let ctx = Context::new();
+    for (src, dst) in src.chunks(LANES * 3).zip(dst.chunks_mut(LANES * 3)) {
+        let mut planes: Planes = [[0.; LANES]; 3];
+        for (i, px) in src.chunks_exact(3).enumerate() {
+            planes[0][i] = px[0];
+            planes[1][i] = px[1];
+            planes[2][i] = px[2];
+        }
+        to_xyz(&ctx, src_model, &mut planes);
+        from_xyz(&ctx, dst_model, &mut planes);
+        for (i, px) in dst.chunks_exact_mut(3).enumerate() {
+            px[0] = planes[0][i];
+            px[1] = planes[1][i];
+            px[2] = planes[2][i];
+        }
+    }
+}
+
+#[cfg(all(feature = "avx", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2", enable = "fma")]
+unsafe fn convert_avx2(src: &[f32], src_model: ColorModel, dst: &mut [f32], dst_model: ColorModel) {
+    convert_impl(src, src_model, dst, dst_model);
+}
+
+#[cfg(all(feature = "sse", any(target_arch = "x86", target_arch = "x86_64")))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn convert_sse_41(
+    src: &[f32],
+    src_model: ColorModel,
+    dst: &mut [f32],
+    dst_model: ColorModel,
+) {
+    convert_impl(src, src_model, dst, dst_model);
+}
+
+/// Converts interleaved triplets of `src_model` into `dst_model`.
+///
+/// Results match per value functions of the models within float rounding,
+/// cube roots are approximated close to single precision.
+pub fn convert_color_models(
+    src: &[f32],
+    src_model: ColorModel,
+    dst: &mut [f32],
+    dst_model: ColorModel,
+) -> Result<(), CmsError> {
+    if src.len() != dst.len() {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    if src.len() % 3 != 0 {
+        return Err(CmsError::LaneMultipleOfChannels);
+    }
+    if src_model == dst_model {
+        dst.copy_from_slice(src);
+        return Ok(());
+    }
+    #[cfg(all(feature = "avx", target_arch = "x86_64"))]
+    if crate::equivalence::x86_feature_detected!("avx2")
+        && crate::equivalence::x86_feature_detected!("fma")
+    {
+        unsafe { convert_avx2(src, src_model, dst, dst_model) };
+        return Ok(());
+    }
+    #[cfg(all(feature = "sse", any(target_arch = "x86", target_arch = "x86_64")))]
+    if crate::equivalence::x86_feature_detected!("sse4.1") {
+        unsafe { convert_sse_41(src, src_model, dst, dst_model) };
+        return Ok(());
+    }
+    convert_impl(src, src_model, dst, dst_model);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Lab, Luv, Oklab, Rgb, Xyz, Yrg};
+
+    #[test]
+    fn test_convert_color_models() {
+        let pcs_scale = 1. + 32767. / 32768.;
+        let mut xyz = Vec::new();
+        for i in 1..=37 {
+            let t = i as f32 / 37.;
+            xyz.extend_from_slice(&[0.9642 * t, t * t, 0.8249 * (1. - t * 0.5)]);
+        }
+        let mut out = vec![0f32; xyz.len()];
+        let mut back = vec![0f32; xyz.len()];
+        let ctx = Context::new();
+        for model in [
+            ColorModel::Lab,
+            ColorModel::Luv,
+            ColorModel::Oklab,
+            ColorModel::Yrg,
+        ] {
+            convert_color_models(&xyz, ColorModel::Xyz, &mut out, model).unwrap();
+            for (src, v) in xyz.chunks_exact(3).zip(out.chunks_exact(3)) {
+                let p = Xyz::new(src[0], src[1], src[2]);
+                let expected = match model {
+                    ColorModel::Lab => {
+                        let lab = Lab::from_xyz(Xyz::new(
+                            p.x / pcs_scale,
+                            p.y / pcs_scale,
+                            p.z / pcs_scale,
+                        ));
+                        [lab.l, lab.a, lab.b]
+                    }
+                    ColorModel::Luv => {
+                        let luv = Luv::from_xyz(p);
+                        [luv.l, luv.u, luv.v]
+                    }
+                    ColorModel::Oklab => {
+                        let rgb = mul(&ctx.xyz_to_srgb, [p.x, p.y, p.z]);
+                        let oklab = Oklab::from_linear_rgb(Rgb::new(rgb[0], rgb[1], rgb[2]));
+                        [oklab.l, oklab.a, oklab.b]
+                    }
+                    _ => {
+                        let d65 = mul(&ctx.d50_to_d65, [p.x, p.y, p.z]);
+                        let yrg = Yrg::from_xyz(Xyz::new(d65[0], d65[1], d65[2]));
+                        [yrg.y, yrg.r, yrg.g]
+                    }
+                };
+                for (a, b) in v.iter().zip(expected.iter()) {
+                    assert!((a - b).abs() < 1e-3, "{model:?} {v:?} {expected:?}");
+                }
+            }
+            convert_color_models(&out, model, &mut back, ColorModel::Xyz).unwrap();
+            for (a, b) in back.iter().zip(xyz.iter()) {
+                assert!((a - b).abs() < 1e-3, "{model:?} {a} {b}");
+            }
+        }
+
+        let lab = [50f32, 20., -30.];
+        let mut oklab = [0f32; 3];
+        let mut lab_back = [0f32; 3];
+        convert_color_models(&lab, ColorModel::Lab, &mut oklab, ColorModel::Oklab).unwrap();
+        convert_color_models(&oklab, ColorModel::Oklab, &mut lab_back, ColorModel::Lab).unwrap();
+        for (a, b) in lab.iter().zip(lab_back.iter()) {
+            assert!((a - b).abs() < 1e-2, "{lab_back:?}");
+        }
+        assert_eq!(
+            convert_color_models(&lab, ColorModel::Lab, &mut [0.; 2], ColorModel::Oklab),
+            Err(CmsError::LaneSizeMismatch)
+        );
+    }
+}
@@ -0,0 +1,159 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::chad::adaption_matrix_d;
+use crate::{
+    Chromaticity, CmsError, ColorPrimaries, ColorProfile, DataColorSpace, Matrix3d, ToneReprCurve,
+    Xyzd,
+};
+
+/// Plain description of RGB colorimetry.
+///
+/// Summarizes *Matrix Shaper* profile into primaries, white point and a single transfer curve,
+/// suitable for config files and negotiation between processes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colorimetry {
+    pub primaries: ColorPrimaries,
+    pub white_point: Chromaticity,
+    pub transfer: ToneReprCurve,
+}
+
+impl Colorimetry {
+    pub fn new(
+        primaries: ColorPrimaries,
+        white_point: Chromaticity,
+        transfer: ToneReprCurve,
+    ) -> Colorimetry {
+        Colorimetry {
+            primaries,
+            white_point,
+            transfer,
+        }
+    }
+}
+
+fn chromaticity_from_xyzd(xyz: Xyzd) -> Result<Chromaticity, CmsError> {
+    let sum = xyz.x + xyz.y + xyz.z;
+    if sum == 0. {
+        return Err(CmsError::DivisionByZero);
+    }
+    Ok(Chromaticity::new(
+        (xyz.x / sum) as f32,
+        (xyz.y / sum) as f32,
+    ))
+}
+
+impl TryFrom<&ColorProfile> for Colorimetry {
+    type Error = CmsError;
+
+    /// Only RGB *Matrix Shaper* profiles with the same curve on each channel are representable.
+    fn try_from(profile: &ColorProfile) -> Result<Self, Self::Error> {
+        if profile.color_space != DataColorSpace::Rgb || !profile.is_matrix_shaper() {
+            return Err(CmsError::UnsupportedProfileConnection);
+        }
+        let (Some(red_trc), Some(green_trc), Some(blue_trc)) =
+            (&profile.red_trc, &profile.green_trc, &profile.blue_trc)
+        else {
+            return Err(CmsError::InvalidTrcCurve);
+        };
+        if red_trc != green_trc || green_trc != blue_trc {
+            return Err(CmsError::BuildTransferFunction);
+        }
+
+        let white_point = match profile.media_white_point {
+            Some(media_white_point) => chromaticity_from_xyzd(media_white_point)?,
+            None => Chromaticity::D50,
+        };
+
+        // Colorants are stored adapted to D50, so adaptation is reverted first.
+        let adaptation = adaption_matrix_d(Chromaticity::D50.to_xyz(), white_point.to_xyz());
+        let colorants: Matrix3d = adaptation.mat_mul(profile.colorant_matrix());
+        let primary = |column: usize| {
+            chromaticity_from_xyzd(Xyzd::new(
+                colorants.v[0][column],
+                colorants.v[1][column],
+                colorants.v[2][column],
+            ))
+        };
+
+        Ok(Colorimetry {
+            primaries: ColorPrimaries {
+                red: primary(0)?,
+                green: primary(1)?,
+                blue: primary(2)?,
+            },
+            white_point,
+            transfer: red_trc.clone(),
+        })
+    }
+}
+
+impl From<Colorimetry> for ColorProfile {
+    fn from(value: Colorimetry) -> Self {
+        let mut profile = ColorProfile::basic_rgb_profile();
+        profile.update_rgb_colorimetry(value.white_point.to_xyyb(), value.primaries);
+        profile.media_white_point = Some(value.white_point.to_xyzd());
+        profile.green_trc = Some(value.transfer.clone());
+        profile.blue_trc = Some(value.transfer.clone());
+        profile.red_trc = Some(value.transfer);
+        profile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colorimetry_round_trip() {
+        let srgb = ColorProfile::new_srgb();
+        let colorimetry = Colorimetry::try_from(&srgb).unwrap();
+        let expected = ColorPrimaries::BT_709;
+        for (a, b) in [
+            (colorimetry.primaries.red, expected.red),
+            (colorimetry.primaries.green, expected.green),
+            (colorimetry.primaries.blue, expected.blue),
+        ] {
+            assert!((a.x - b.x).abs() < 1e-3, "Primary mismatch {a:?} {b:?}");
+            assert!((a.y - b.y).abs() < 1e-3, "Primary mismatch {a:?} {b:?}");
+        }
+        assert!((colorimetry.white_point.x - Chromaticity::D65.x).abs() < 1e-3);
+        assert!((colorimetry.white_point.y - Chromaticity::D65.y).abs() < 1e-3);
+        assert_eq!(Some(colorimetry.transfer.clone()), srgb.red_trc);
+
+        let profile: ColorProfile = colorimetry.into();
+        assert!((profile.red_colorant.x - srgb.red_colorant.x).abs() < 1e-3);
+        assert!((profile.green_colorant.y - srgb.green_colorant.y).abs() < 1e-3);
+        assert!((profile.blue_colorant.z - srgb.blue_colorant.z).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_colorimetry_not_representable() {
+        assert!(Colorimetry::try_from(&ColorProfile::new_gray_with_gamma(2.2)).is_err());
+    }
+}
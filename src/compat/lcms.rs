@@ -0,0 +1,403 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Mirrors shapes of `lcms2` crate API.
+//!
+//! Existing code written against `lcms2` usually needs only import changes:
+//!
+//! ```
+//! use moxcms::compat::lcms::{Intent, PixelFormat, Profile, Transform};
+//!
+//! let srgb = Profile::new_srgb();
+//! let p3 = Profile::new_icc(&moxcms::ColorProfile::new_display_p3().encode().unwrap()).unwrap();
+//! let transform: Transform<[u8; 3], [u8; 3]> =
+//!     Transform::new(&srgb, PixelFormat::RGB_8, &p3, PixelFormat::RGB_8, Intent::Perceptual)
+//!         .unwrap();
+//! let src = [[255u8, 0, 0]; 4];
+//! let mut dst = [[0u8; 3]; 4];
+//! transform.transform_pixels(&src, &mut dst).unwrap();
+//! ```
+//!
+//! Unlike `lcms2`, input and output samples must have the same type,
+//! and methods report errors instead of panicking.
+use crate::{CmsError, ColorProfile, Layout, TransformExecutor, TransformOptions};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// Same as `lcms2::Intent`
+pub use crate::RenderingIntent as Intent;
+
+/// Same as `lcms2::LCMSResult`
+pub type LCMSResult<T> = Result<T, CmsError>;
+
+/// Counterpart of `lcms2::Profile`
+#[derive(Clone, Debug)]
+pub struct Profile {
+    profile: ColorProfile,
+}
+
+impl Profile {
+    /// Parses ICC profile from memory
+    pub fn new_icc(data: &[u8]) -> LCMSResult<Profile> {
+        Ok(Profile {
+            profile: ColorProfile::new_from_slice(data)?,
+        })
+    }
+
+    /// Reads ICC profile from file
+    pub fn new_file<P: AsRef<Path>>(path: P) -> std::io::Result<Profile> {
+        let data = std::fs::read(path)?;
+        Profile::new_icc(&data).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Creates sRGB profile
+    pub fn new_srgb() -> Profile {
+        Profile {
+            profile: ColorProfile::new_srgb(),
+        }
+    }
+
+    /// Creates gray profile with pure gamma curve and D50 white point
+    pub fn new_gray_with_gamma(gamma: f32) -> Profile {
+        Profile {
+            profile: ColorProfile::new_gray_with_gamma(gamma),
+        }
+    }
+
+    /// Creates Lab profile
+    pub fn new_lab4() -> Profile {
+        Profile {
+            profile: ColorProfile::new_lab(),
+        }
+    }
+
+    /// Serializes profile into ICC
+    pub fn icc(&self) -> LCMSResult<Vec<u8>> {
+        self.profile.encode()
+    }
+
+    /// Returns underlying profile
+    pub fn color_profile(&self) -> &ColorProfile {
+        &self.profile
+    }
+}
+
+impl From<ColorProfile> for Profile {
+    fn from(profile: ColorProfile) -> Self {
+        Profile { profile }
+    }
+}
+
+impl From<Profile> for ColorProfile {
+    fn from(value: Profile) -> Self {
+        value.profile
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SampleKind {
+    U8,
+    U16,
+    F32,
+}
+
+/// Counterpart of `lcms2::PixelFormat`, limited to layouts supported by this crate.
+///
+/// CMYK formats are 4 channels without alpha.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelFormat {
+    layout: Layout,
+    kind: SampleKind,
+}
+
+impl PixelFormat {
+    pub const GRAY_8: PixelFormat = PixelFormat::new(Layout::Gray, SampleKind::U8);
+    pub const GRAYA_8: PixelFormat = PixelFormat::new(Layout::GrayAlpha, SampleKind::U8);
+    pub const RGB_8: PixelFormat = PixelFormat::new(Layout::Rgb, SampleKind::U8);
+    pub const RGBA_8: PixelFormat = PixelFormat::new(Layout::Rgba, SampleKind::U8);
+    pub const CMYK_8: PixelFormat = PixelFormat::new(Layout::Rgba, SampleKind::U8);
+    pub const GRAY_16: PixelFormat = PixelFormat::new(Layout::Gray, SampleKind::U16);
+    pub const GRAYA_16: PixelFormat = PixelFormat::new(Layout::GrayAlpha, SampleKind::U16);
+    pub const RGB_16: PixelFormat = PixelFormat::new(Layout::Rgb, SampleKind::U16);
+    pub const RGBA_16: PixelFormat = PixelFormat::new(Layout::Rgba, SampleKind::U16);
+    pub const CMYK_16: PixelFormat = PixelFormat::new(Layout::Rgba, SampleKind::U16);
+    pub const GRAY_FLT: PixelFormat = PixelFormat::new(Layout::Gray, SampleKind::F32);
+    pub const GRAYA_FLT: PixelFormat = PixelFormat::new(Layout::GrayAlpha, SampleKind::F32);
+    pub const RGB_FLT: PixelFormat = PixelFormat::new(Layout::Rgb, SampleKind::F32);
+    pub const RGBA_FLT: PixelFormat = PixelFormat::new(Layout::Rgba, SampleKind::F32);
+    pub const CMYK_FLT: PixelFormat = PixelFormat::new(Layout::Rgba, SampleKind::F32);
+
+    const fn new(layout: Layout, kind: SampleKind) -> PixelFormat {
+        PixelFormat { layout, kind }
+    }
+
+    /// Channels count including alpha
+    pub fn channels(self) -> usize {
+        self.layout.channels()
+    }
+
+    /// Layout used by transforms of this crate
+    pub fn layout(self) -> Layout {
+        self.layout
+    }
+
+    /// Bytes in one sample
+    pub fn bytes_per_channel(self) -> usize {
+        match self.kind {
+            SampleKind::U8 => 1,
+            SampleKind::U16 => 2,
+            SampleKind::F32 => 4,
+        }
+    }
+
+    /// Returns true if samples are floating point
+    pub fn is_float(self) -> bool {
+        self.kind == SampleKind::F32
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Sample types that transforms can process, `u8`, `u16` and `f32`
+pub trait Sample: Copy + Default + private::Sealed + 'static {
+    #[doc(hidden)]
+    fn is_compatible(format: PixelFormat) -> bool;
+
+    #[doc(hidden)]
+    fn create_executor(
+        source: &ColorProfile,
+        src_layout: Layout,
+        dest: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<dyn TransformExecutor<Self> + Send + Sync>, CmsError>;
+}
+
+macro_rules! impl_sample {
+    ($sample: ty, $kind: expr, $method: ident) => {
+        impl private::Sealed for $sample {}
+
+        impl Sample for $sample {
+            fn is_compatible(format: PixelFormat) -> bool {
+                format.kind == $kind
+            }
+
+            fn create_executor(
+                source: &ColorProfile,
+                src_layout: Layout,
+                dest: &ColorProfile,
+                dst_layout: Layout,
+                options: TransformOptions,
+            ) -> Result<Box<dyn TransformExecutor<Self> + Send + Sync>, CmsError> {
+                source.$method(src_layout, dest, dst_layout, options)
+            }
+        }
+    };
+}
+
+impl_sample!(u8, SampleKind::U8, create_transform_8bit);
+impl_sample!(u16, SampleKind::U16, create_transform_16bit);
+impl_sample!(f32, SampleKind::F32, create_transform_f32);
+
+/// Pixel types accepted by [Transform], either plain samples or arrays of samples
+pub trait Pixel: Copy + 'static {
+    type Sample: Sample;
+
+    fn samples(pixels: &[Self]) -> &[Self::Sample];
+
+    fn samples_mut(pixels: &mut [Self]) -> &mut [Self::Sample];
+}
+
+impl<T: Sample> Pixel for T {
+    type Sample = T;
+
+    fn samples(pixels: &[Self]) -> &[Self::Sample] {
+        pixels
+    }
+
+    fn samples_mut(pixels: &mut [Self]) -> &mut [Self::Sample] {
+        pixels
+    }
+}
+
+impl<T: Sample, const N: usize> Pixel for [T; N] {
+    type Sample = T;
+
+    fn samples(pixels: &[Self]) -> &[Self::Sample] {
+        pixels.as_flattened()
+    }
+
+    fn samples_mut(pixels: &mut [Self]) -> &mut [Self::Sample] {
+        pixels.as_flattened_mut()
+    }
+}
+
+/// Counterpart of `lcms2::Transform`.
+///
+/// Input and output pixels may differ in shape, e.g. `[u8; 4]` and `[u8; 3]`,
+/// but must share sample type.
+pub struct Transform<I: Pixel, O: Pixel<Sample = I::Sample> = I> {
+    executor: Box<dyn TransformExecutor<I::Sample> + Send + Sync>,
+    src_channels: usize,
+    dst_channels: usize,
+    _phantom: PhantomData<(I, O)>,
+}
+
+impl<I: Pixel, O: Pixel<Sample = I::Sample>> Transform<I, O> {
+    /// Creates transform, as `lcms2::Transform::new`
+    ///
+    /// Matrices of `lut8` and `lut16` tags are handled as lcms2 does,
+    /// see [TransformOptions::lcms_lut_matrix].
+    pub fn new(
+        input: &Profile,
+        in_format: PixelFormat,
+        output: &Profile,
+        out_format: PixelFormat,
+        intent: Intent,
+    ) -> LCMSResult<Self> {
+        Transform::new_with_options(
+            input,
+            in_format,
+            output,
+            out_format,
+            TransformOptions {
+                rendering_intent: intent,
+                lcms_lut_matrix: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Creates transform with full set of options of this crate
+    pub fn new_with_options(
+        input: &Profile,
+        in_format: PixelFormat,
+        output: &Profile,
+        out_format: PixelFormat,
+        options: TransformOptions,
+    ) -> LCMSResult<Self> {
+        if !I::Sample::is_compatible(in_format) || !I::Sample::is_compatible(out_format) {
+            return Err(CmsError::InvalidLayout);
+        }
+        let executor = I::Sample::create_executor(
+            &input.profile,
+            in_format.layout,
+            &output.profile,
+            out_format.layout,
+            options,
+        )?;
+        Ok(Transform {
+            executor,
+            src_channels: in_format.channels(),
+            dst_channels: out_format.channels(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Transforms pixels, as `lcms2::Transform::transform_pixels`.
+    ///
+    /// Pixel count is taken from `src`, `dst` must be able to hold the same count.
+    pub fn transform_pixels(&self, src: &[I], dst: &mut [O]) -> LCMSResult<()> {
+        let src = I::samples(src);
+        let dst = O::samples_mut(dst);
+        let pixels = src.len() / self.src_channels;
+        let dst_len = pixels * self.dst_channels;
+        if dst.len() < dst_len {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        self.executor.transform(src, &mut dst[..dst_len])
+    }
+}
+
+impl<I: Pixel> Transform<I, I> {
+    /// Transforms pixels in place, as `lcms2::Transform::transform_in_place`
+    pub fn transform_in_place(&self, pixels: &mut [I]) -> LCMSResult<()> {
+        if self.src_channels != self.dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let src = I::samples(pixels).to_vec();
+        self.executor.transform(&src, I::samples_mut(pixels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lcms_compat_transform() {
+        let srgb = Profile::new_srgb();
+        let p3 = Profile::from(ColorProfile::new_display_p3());
+        let transform: Transform<[u8; 4], [u8; 3]> = Transform::new(
+            &srgb,
+            PixelFormat::RGBA_8,
+            &p3,
+            PixelFormat::RGB_8,
+            Intent::Perceptual,
+        )
+        .unwrap();
+        let src = [[255u8, 0, 0, 255]; 8];
+        let mut dst = [[0u8; 3]; 8];
+        transform.transform_pixels(&src, &mut dst).unwrap();
+
+        let direct = ColorProfile::new_srgb()
+            .create_transform_8bit(
+                Layout::Rgba,
+                &ColorProfile::new_display_p3(),
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let mut expected = [0u8; 3 * 8];
+        direct.transform(src.as_flattened(), &mut expected).unwrap();
+        assert_eq!(dst.as_flattened(), expected.as_slice());
+
+        let in_place: Transform<u16> = Transform::new(
+            &srgb,
+            PixelFormat::RGB_16,
+            &p3,
+            PixelFormat::RGB_16,
+            Intent::RelativeColorimetric,
+        )
+        .unwrap();
+        let mut pixels = vec![65535u16, 0, 0, 0, 65535, 0];
+        in_place.transform_in_place(&mut pixels).unwrap();
+        assert_ne!(pixels, vec![65535u16, 0, 0, 0, 65535, 0]);
+
+        let mismatch: Result<Transform<u8>, _> = Transform::new(
+            &srgb,
+            PixelFormat::RGB_16,
+            &p3,
+            PixelFormat::RGB_8,
+            Intent::Perceptual,
+        );
+        assert!(mismatch.is_err());
+    }
+}
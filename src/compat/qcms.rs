@@ -0,0 +1,247 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Mirrors shapes of `qcms` crate API.
+//!
+//! ```
+//! use moxcms::compat::qcms::{DataType, Intent, Profile, Transform};
+//!
+//! let srgb = Profile::new_sRGB();
+//! let gray = Profile::new_gray_with_gamma(2.2);
+//! let transform = Transform::new_to(&srgb, &gray, DataType::BGRA8, DataType::Gray8, Intent::default())
+//!     .unwrap();
+//! let src = [0u8, 0, 255, 255, 255, 255, 255, 255];
+//! let mut dst = [0u8; 2];
+//! transform.convert(&src, &mut dst);
+//! ```
+use crate::{CmsError, ColorProfile, Layout, Transform8BitExecutor, TransformOptions};
+
+/// Same as `qcms::Intent`
+pub use crate::RenderingIntent as Intent;
+
+/// Counterpart of `qcms::DataType`, 8 bit pixel formats
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DataType {
+    RGB8,
+    RGBA8,
+    BGRA8,
+    Gray8,
+    GrayA8,
+    CMYK,
+}
+
+impl DataType {
+    /// Bytes in one pixel
+    pub fn bytes_per_pixel(self) -> usize {
+        self.layout().channels()
+    }
+
+    fn layout(self) -> Layout {
+        match self {
+            DataType::RGB8 => Layout::Rgb,
+            DataType::RGBA8 | DataType::BGRA8 | DataType::CMYK => Layout::Rgba,
+            DataType::Gray8 => Layout::Gray,
+            DataType::GrayA8 => Layout::GrayAlpha,
+        }
+    }
+
+    /// Swaps red and blue for BGRA, transforms work only with RGBA order
+    fn swizzle(self, pixels: &mut [u8]) {
+        if self == DataType::BGRA8 {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+    }
+}
+
+/// Counterpart of `qcms::Profile`
+#[derive(Clone, Debug)]
+pub struct Profile {
+    profile: ColorProfile,
+}
+
+#[allow(non_snake_case)]
+impl Profile {
+    /// Parses ICC profile from memory, `curves_only` is accepted for compatibility and ignored
+    pub fn new_from_slice(mem: &[u8], curves_only: bool) -> Option<Box<Profile>> {
+        _ = curves_only;
+        ColorProfile::new_from_slice(mem)
+            .ok()
+            .map(|profile| Box::new(Profile { profile }))
+    }
+
+    /// Creates sRGB profile
+    pub fn new_sRGB() -> Box<Profile> {
+        Box::new(Profile {
+            profile: ColorProfile::new_srgb(),
+        })
+    }
+
+    /// Creates Display P3 profile
+    pub fn new_displayP3() -> Box<Profile> {
+        Box::new(Profile {
+            profile: ColorProfile::new_display_p3(),
+        })
+    }
+
+    /// Creates gray profile with pure gamma curve
+    pub fn new_gray_with_gamma(gamma: f32) -> Box<Profile> {
+        Box::new(Profile {
+            profile: ColorProfile::new_gray_with_gamma(gamma),
+        })
+    }
+
+    /// Does nothing, transforms are always built from scratch, kept for compatibility
+    pub fn precache_output_transform(&mut self) {}
+
+    /// Returns underlying profile
+    pub fn color_profile(&self) -> &ColorProfile {
+        &self.profile
+    }
+}
+
+impl From<ColorProfile> for Profile {
+    fn from(profile: ColorProfile) -> Self {
+        Profile { profile }
+    }
+}
+
+/// Counterpart of `qcms::Transform`
+pub struct Transform {
+    executor: Box<Transform8BitExecutor>,
+    src_type: DataType,
+    dst_type: DataType,
+}
+
+impl Transform {
+    /// Creates transform between same input and output format
+    pub fn new(input: &Profile, output: &Profile, ty: DataType, intent: Intent) -> Option<Self> {
+        Transform::new_to(input, output, ty, ty, intent)
+    }
+
+    /// Creates transform between different input and output formats
+    pub fn new_to(
+        input: &Profile,
+        output: &Profile,
+        input_type: DataType,
+        output_type: DataType,
+        intent: Intent,
+    ) -> Option<Self> {
+        let options = TransformOptions {
+            rendering_intent: intent,
+            ..Default::default()
+        };
+        let executor = input
+            .profile
+            .create_transform_8bit(
+                input_type.layout(),
+                &output.profile,
+                output_type.layout(),
+                options,
+            )
+            .ok()?;
+        Some(Transform {
+            executor,
+            src_type: input_type,
+            dst_type: output_type,
+        })
+    }
+
+    /// Transforms pixels in place, only for transforms that keep format
+    ///
+    /// # Panics
+    ///
+    /// Panics if input and output formats differ, or data is not whole pixels.
+    pub fn apply(&self, data: &mut [u8]) {
+        assert_eq!(
+            self.src_type.bytes_per_pixel(),
+            self.dst_type.bytes_per_pixel(),
+            "In place transform requires same pixel size"
+        );
+        let src = data.to_vec();
+        self.convert(&src, data);
+    }
+
+    /// Transforms `src` into `dst`
+    ///
+    /// # Panics
+    ///
+    /// Panics if buffers don't hold the same count of whole pixels.
+    pub fn convert(&self, src: &[u8], dst: &mut [u8]) {
+        self.try_convert(src, dst)
+            .expect("Buffers must match transform data types");
+    }
+
+    /// Same as [Transform::convert] but reports errors instead of panicking
+    pub fn try_convert(&self, src: &[u8], dst: &mut [u8]) -> Result<(), CmsError> {
+        if self.src_type == DataType::BGRA8 {
+            let mut swizzled = src.to_vec();
+            self.src_type.swizzle(&mut swizzled);
+            self.executor.transform(&swizzled, dst)?;
+        } else {
+            self.executor.transform(src, dst)?;
+        }
+        self.dst_type.swizzle(dst);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qcms_compat_bgra() {
+        let srgb = Profile::new_sRGB();
+        let p3 = Profile::new_displayP3();
+        let rgba = Transform::new(&srgb, &p3, DataType::RGBA8, Intent::Perceptual).unwrap();
+        let bgra = Transform::new(&srgb, &p3, DataType::BGRA8, Intent::Perceptual).unwrap();
+        let src = [255u8, 64, 0, 255, 12, 200, 90, 128];
+        let mut rgba_dst = [0u8; 8];
+        rgba.convert(&src, &mut rgba_dst);
+
+        let mut bgra_data = [0u8, 64, 255, 255, 90, 200, 12, 128];
+        bgra.apply(&mut bgra_data);
+        assert_eq!(
+            bgra_data,
+            [
+                rgba_dst[2],
+                rgba_dst[1],
+                rgba_dst[0],
+                rgba_dst[3],
+                rgba_dst[6],
+                rgba_dst[5],
+                rgba_dst[4],
+                rgba_dst[7]
+            ]
+        );
+        assert!(bgra.try_convert(&src[..7], &mut rgba_dst[..7]).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{
+    CmsError, ColorProfile, Layout, LinearLightTransform, Matrix3f, Oklab, Rgb, TransformOptions,
+    Vector3f,
+};
+use num_traits::AsPrimitive;
+
+const CHUNK_PIXELS: usize = 512;
+
+/// Space where blending is performed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendSpace {
+    /// Linear light in the profile primaries
+    #[default]
+    LinearRgb,
+    /// Perceptual blending, avoids dark fringes between saturated colors
+    Oklab,
+}
+
+/// Alpha compositing of RGBA buffers encoded in one profile, see [ColorProfile::create_compositor_8bit].
+pub struct Compositor<T> {
+    transform: LinearLightTransform<T>,
+    space: BlendSpace,
+    to_srgb: Matrix3f,
+    from_srgb: Matrix3f,
+}
+
+impl<T: Copy + AsPrimitive<f32>> Compositor<T>
+where
+    f32: AsPrimitive<T>,
+{
+    /// Composites `foreground` over `background` using straight (not premultiplied) alpha.
+    ///
+    /// All buffers are RGBA.
+    pub fn blend_over(
+        &self,
+        foreground: &[T],
+        background: &[T],
+        dst: &mut [T],
+    ) -> Result<(), CmsError> {
+        if foreground.len() != background.len() || foreground.len() != dst.len() {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        if foreground.len() % 4 != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        let mut fg_linear = [0f32; CHUNK_PIXELS * 4];
+        let mut bg_linear = [0f32; CHUNK_PIXELS * 4];
+        for ((fg, bg), dst) in foreground
+            .chunks(CHUNK_PIXELS * 4)
+            .zip(background.chunks(CHUNK_PIXELS * 4))
+            .zip(dst.chunks_mut(CHUNK_PIXELS * 4))
+        {
+            let fg_linear = &mut fg_linear[..fg.len()];
+            let bg_linear = &mut bg_linear[..bg.len()];
+            self.transform.linearizer().linearize(fg, fg_linear)?;
+            self.transform.linearizer().linearize(bg, bg_linear)?;
+            for (fg, bg) in fg_linear.chunks_exact_mut(4).zip(bg_linear.chunks_exact(4)) {
+                self.blend_pixel(fg, bg);
+            }
+            self.transform.encoder().encode(fg_linear, dst)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn blend_pixel(&self, fg: &mut [f32], bg: &[f32]) {
+        let fg_a = fg[3];
+        let bg_a = bg[3] * (1. - fg_a);
+        let alpha = fg_a + bg_a;
+        if alpha <= 0. {
+            fg.copy_from_slice(&[0., 0., 0., 0.]);
+            return;
+        }
+        let (fg_w, bg_w) = (fg_a / alpha, bg_a / alpha);
+        match self.space {
+            BlendSpace::LinearRgb => {
+                for i in 0..3 {
+                    fg[i] = fg[i] * fg_w + bg[i] * bg_w;
+                }
+            }
+            BlendSpace::Oklab => {
+                let to_oklab = |px: &[f32]| {
+                    let v = self.to_srgb.mul_vector(Vector3f {
+                        v: [px[0], px[1], px[2]],
+                    });
+                    Oklab::from_linear_rgb(Rgb::new(v.v[0], v.v[1], v.v[2]))
+                };
+                let a = to_oklab(fg);
+                let b = to_oklab(bg);
+                let mixed = Oklab::new(
+                    a.l * fg_w + b.l * bg_w,
+                    a.a * fg_w + b.a * bg_w,
+                    a.b * fg_w + b.b * bg_w,
+                )
+                .to_linear_rgb();
+                let v = self.from_srgb.mul_vector(Vector3f {
+                    v: [mixed.r, mixed.g, mixed.b],
+                });
+                fg[..3].copy_from_slice(&v.v);
+            }
+        }
+        fg[3] = alpha;
+    }
+}
+
+impl ColorProfile {
+    /// Creates compositor for 8 bit RGBA buffers in this profile
+    pub fn create_compositor_8bit(
+        &self,
+        space: BlendSpace,
+        options: TransformOptions,
+    ) -> Result<Compositor<u8>, CmsError> {
+        let transform =
+            self.create_linear_light_transform_8bit(Layout::Rgba, self, Layout::Rgba, options)?;
+        Ok(self.make_compositor(transform, space))
+    }
+
+    /// Creates compositor for 16 bit RGBA buffers in this profile
+    pub fn create_compositor_16bit(
+        &self,
+        space: BlendSpace,
+        options: TransformOptions,
+    ) -> Result<Compositor<u16>, CmsError> {
+        let transform =
+            self.create_linear_light_transform_16bit(Layout::Rgba, self, Layout::Rgba, options)?;
+        Ok(self.make_compositor(transform, space))
+    }
+
+    /// Creates compositor for f32 RGBA buffers in this profile
+    pub fn create_compositor_f32(
+        &self,
+        space: BlendSpace,
+        options: TransformOptions,
+    ) -> Result<Compositor<f32>, CmsError> {
+        let transform =
+            self.create_linear_light_transform_f32(Layout::Rgba, self, Layout::Rgba, options)?;
+        Ok(self.make_compositor(transform, space))
+    }
+
+    fn make_compositor<T>(
+        &self,
+        transform: LinearLightTransform<T>,
+        space: BlendSpace,
+    ) -> Compositor<T> {
+        // Oklab is defined over linear sRGB, matrix only conversion keeps out of gamut values
+        let srgb = ColorProfile::new_srgb();
+        let to_srgb = self.transform_matrix(&srgb);
+        Compositor {
+            transform,
+            space,
+            to_srgb: to_srgb.to_f32(),
+            from_srgb: to_srgb.inverse().to_f32(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend_over() {
+        let srgb = ColorProfile::new_srgb();
+        let compositor = srgb
+            .create_compositor_8bit(BlendSpace::LinearRgb, TransformOptions::default())
+            .unwrap();
+        let foreground = [255u8, 255, 255, 128, 255, 0, 0, 255, 0, 0, 0, 0];
+        let background = [0u8, 0, 0, 255, 0, 0, 255, 255, 0, 255, 0, 255];
+        let mut dst = [0u8; 12];
+        compositor
+            .blend_over(&foreground, &background, &mut dst)
+            .unwrap();
+        // Half white over black is middle gray in linear light
+        assert!((dst[0] as i32 - 188).abs() <= 1);
+        assert_eq!(dst[3], 255);
+        assert_eq!(&dst[4..8], &[255, 0, 0, 255]);
+        assert_eq!(&dst[8..12], &[0, 255, 0, 255]);
+
+        let oklab = srgb
+            .create_compositor_8bit(BlendSpace::Oklab, TransformOptions::default())
+            .unwrap();
+        let foreground = [255u8, 0, 0, 128];
+        let background = [0u8, 255, 0, 255];
+        let mut linear_dst = [0u8; 4];
+        let mut oklab_dst = [0u8; 4];
+        compositor
+            .blend_over(&foreground, &background, &mut linear_dst)
+            .unwrap();
+        oklab
+            .blend_over(&foreground, &background, &mut oklab_dst)
+            .unwrap();
+        assert_eq!(linear_dst[3], 255);
+        assert_eq!(oklab_dst[3], 255);
+        assert_ne!(linear_dst, oklab_dst);
+    }
+}
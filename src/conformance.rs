@@ -0,0 +1,379 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Conformance runner for packagers.
+//!
+//! Every case is sampled on a regular grid, transformed with regular 8 and 16 bit executors,
+//! so whatever SIMD kernels the build dispatches to, and compared against [ReferenceTransform].
+//! Both outputs are taken back to PCS through the destination profile and compared as CIE76 delta E,
+//! so the statistics are in perceptual units regardless of destination color space.
+//!
+//! [ReferenceTransform] shares profile parsing with regular executors, so [run_expected]
+//! additionally compares against colors produced by an independent CMM.
+
+use crate::lab::xyz_to_lab;
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, Layout, ReferenceTransform, TransformExecutor,
+    TransformOptions,
+};
+use num_traits::AsPrimitive;
+
+/// Pair of profiles with options to check
+#[derive(Clone, Debug)]
+pub struct ConformanceCase {
+    pub name: String,
+    pub source: ColorProfile,
+    pub destination: ColorProfile,
+    pub options: TransformOptions,
+}
+
+impl ConformanceCase {
+    pub fn new(
+        name: impl Into<String>,
+        source: ColorProfile,
+        destination: ColorProfile,
+        options: TransformOptions,
+    ) -> ConformanceCase {
+        ConformanceCase {
+            name: name.into(),
+            source,
+            destination,
+            options,
+        }
+    }
+}
+
+/// Delta E statistics of one case at one bit depth
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConformanceReport {
+    pub name: String,
+    pub bit_depth: usize,
+    /// Number of sampled source colors
+    pub samples: usize,
+    pub mean_delta_e: f64,
+    /// 95th percentile
+    pub p95_delta_e: f64,
+    pub max_delta_e: f64,
+}
+
+/// Color transformed by an independent CMM
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConformanceSample {
+    /// Normalized source device values
+    pub source: Vec<f32>,
+    /// Normalized destination device values, or CIE Lab D50 when destination is Lab
+    pub expected: Vec<f32>,
+}
+
+/// Nodes per input so that grid stays within few thousands of colors
+const fn conformance_grid_size(inputs: usize) -> usize {
+    match inputs {
+        1 => 256,
+        2 => 65,
+        3 => 17,
+        4 => 9,
+        _ => 5,
+    }
+}
+
+/// Runs `case` at 8 and 16 bit, returning one report per bit depth
+pub fn run_conformance(case: &ConformanceCase) -> Result<Vec<ConformanceReport>, CmsError> {
+    let source = &case.source;
+    let destination = &case.destination;
+    let options = case.options;
+    let src_layout = source.color_space.suggested_layout();
+    let dst_layout = destination.color_space.suggested_layout();
+    Ok(vec![
+        compare::<u8>(case, 8, || {
+            source.create_transform_8bit(src_layout, destination, dst_layout, options)
+        })?,
+        compare::<u16>(case, 16, || {
+            source.create_transform_16bit(src_layout, destination, dst_layout, options)
+        })?,
+    ])
+}
+
+/// Transforms `samples` sources at 32 bit float and compares against their expected values.
+///
+/// When destination color space is Lab, executor writes [Layout::Lab] and expected values
+/// are PCS, otherwise both are taken to PCS through destination profile.
+pub fn run_expected(
+    case: &ConformanceCase,
+    samples: &[ConformanceSample],
+) -> Result<ConformanceReport, CmsError> {
+    let source = &case.source;
+    let destination = &case.destination;
+    let inputs = source.color_space.channels();
+    let is_pcs = destination.color_space == DataColorSpace::Lab;
+    let (dst_layout, outputs) = if is_pcs {
+        (Layout::Lab, 3)
+    } else {
+        (
+            destination.color_space.suggested_layout(),
+            destination.color_space.channels(),
+        )
+    };
+    let mut src = Vec::with_capacity(samples.len() * inputs);
+    for sample in samples.iter() {
+        if sample.source.len() != inputs || sample.expected.len() != outputs {
+            return Err(CmsError::UnsupportedChannelConfiguration);
+        }
+        src.extend_from_slice(&sample.source);
+    }
+    let mut dst = vec![0f32; samples.len() * outputs];
+    source
+        .create_transform_f32(
+            source.color_space.suggested_layout(),
+            destination,
+            dst_layout,
+            case.options,
+        )?
+        .transform(&src, &mut dst)?;
+
+    let intent = case.options.rendering_intent;
+    let to_lab = |values: &[f32]| -> Result<[f64; 3], CmsError> {
+        if is_pcs {
+            return Ok([values[0] as f64, values[1] as f64, values[2] as f64]);
+        }
+        let clamped = values
+            .iter()
+            .map(|v| v.max(0.).min(1.))
+            .collect::<Vec<f32>>();
+        let xyz = destination.device_to_pcs(&clamped, intent)?;
+        Ok(xyz_to_lab([xyz.x as f64, xyz.y as f64, xyz.z as f64]))
+    };
+    let mut deltas = Vec::with_capacity(samples.len());
+    for (sample, actual) in samples.iter().zip(dst.chunks_exact(outputs)) {
+        let expected_lab = to_lab(&sample.expected)?;
+        let actual_lab = to_lab(actual)?;
+        let delta = (0..3)
+            .map(|i| (expected_lab[i] - actual_lab[i]).powi(2))
+            .sum::<f64>();
+        deltas.push(delta.sqrt());
+    }
+    Ok(report(case, 32, deltas))
+}
+
+fn report(case: &ConformanceCase, bit_depth: usize, mut deltas: Vec<f64>) -> ConformanceReport {
+    deltas.sort_by(|a, b| a.total_cmp(b));
+    let samples = deltas.len();
+    ConformanceReport {
+        name: case.name.clone(),
+        bit_depth,
+        samples,
+        mean_delta_e: deltas.iter().sum::<f64>() / samples.max(1) as f64,
+        p95_delta_e: deltas
+            .get((samples * 95 / 100).min(samples.saturating_sub(1)))
+            .copied()
+            .unwrap_or(0.),
+        max_delta_e: deltas.last().copied().unwrap_or(0.),
+    }
+}
+
+fn compare<T: Copy + Default + AsPrimitive<f64>>(
+    case: &ConformanceCase,
+    bit_depth: usize,
+    create: impl FnOnce() -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>,
+) -> Result<ConformanceReport, CmsError>
+where
+    f64: AsPrimitive<T>,
+{
+    let inputs = case.source.color_space.channels();
+    let outputs = case.destination.color_space.channels();
+    let max_value = ((1u32 << bit_depth) - 1) as f64;
+    let grid_size = conformance_grid_size(inputs);
+    let samples = grid_size.pow(inputs as u32);
+
+    let mut src = vec![T::default(); samples * inputs];
+    for (node, values) in src.chunks_exact_mut(inputs).enumerate() {
+        let mut index = node;
+        for v in values.iter_mut().rev() {
+            let x = (index % grid_size) as f64 / (grid_size - 1) as f64;
+            *v = (x * max_value + 0.5).as_();
+            index /= grid_size;
+        }
+    }
+    let mut dst = vec![T::default(); samples * outputs];
+    create()?.transform(&src, &mut dst)?;
+
+    let reference = ReferenceTransform::new(&case.source, &case.destination, case.options)?;
+    let intent = case.options.rendering_intent;
+    let to_lab = |device: &[f32]| -> Result<[f64; 3], CmsError> {
+        let xyz = case.destination.device_to_pcs(device, intent)?;
+        Ok(xyz_to_lab([xyz.x as f64, xyz.y as f64, xyz.z as f64]))
+    };
+    let mut src_values = vec![0f64; inputs];
+    let mut expected = vec![0f64; outputs];
+    let mut expected_device = vec![0f32; outputs];
+    let mut actual_device = vec![0f32; outputs];
+    let mut deltas = Vec::with_capacity(samples);
+    for (src, dst) in src.chunks_exact(inputs).zip(dst.chunks_exact(outputs)) {
+        for (v, &s) in src_values.iter_mut().zip(src.iter()) {
+            *v = s.as_() / max_value;
+        }
+        reference.transform_pixel(&src_values, &mut expected)?;
+        for (v, &e) in expected_device.iter_mut().zip(expected.iter()) {
+            *v = e.max(0.).min(1.) as f32;
+        }
+        for (v, &a) in actual_device.iter_mut().zip(dst.iter()) {
+            *v = (a.as_() / max_value) as f32;
+        }
+        let expected_lab = to_lab(&expected_device)?;
+        let actual_lab = to_lab(&actual_device)?;
+        let delta = (0..3)
+            .map(|i| (expected_lab[i] - actual_lab[i]).powi(2))
+            .sum::<f64>();
+        deltas.push(delta.sqrt());
+    }
+    Ok(report(case, bit_depth, deltas))
+}
+
+#[cfg(all(test, feature = "conformance"))]
+mod tests {
+    use super::*;
+    use crate::RenderingIntent;
+
+    #[test]
+    fn test_conformance_corpus() {
+        let srgb = ColorProfile::new_srgb();
+        let assets = [
+            (
+                "bt_2020",
+                include_bytes!("../assets/bt_2020.icc").as_slice(),
+            ),
+            ("display_p3", include_bytes!("../assets/Display P3.icc")),
+            (
+                "gray_2_2",
+                include_bytes!("../assets/Generic Gray Gamma 2.2 Profile.icc"),
+            ),
+            (
+                "srgb_perceptual",
+                include_bytes!("../assets/srgb_perceptual.icc"),
+            ),
+            (
+                "us_swop_coated",
+                include_bytes!("../assets/us_swop_coated.icc"),
+            ),
+        ];
+        for (name, data) in assets {
+            let profile = ColorProfile::new_from_slice(data).unwrap();
+            for case in [
+                ConformanceCase::new(
+                    format!("{name} -> srgb"),
+                    profile.clone(),
+                    srgb.clone(),
+                    TransformOptions::default(),
+                ),
+                ConformanceCase::new(
+                    format!("srgb -> {name}"),
+                    srgb.clone(),
+                    profile.clone(),
+                    TransformOptions::default(),
+                ),
+            ] {
+                for report in run_conformance(&case).unwrap() {
+                    // Max is dominated by gamut clipping kinks interpolated across CLUT cells
+                    assert!(report.mean_delta_e <= 1., "{report:?}");
+                    assert!(report.p95_delta_e <= 2., "{report:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_conformance_lcms2() {
+        let srgb = ColorProfile::new_srgb();
+        let lab = ColorProfile::new_lab();
+        let assets = [
+            (
+                "bt_2020",
+                include_bytes!("../assets/bt_2020.icc").as_slice(),
+            ),
+            ("display_p3", include_bytes!("../assets/Display P3.icc")),
+            (
+                "gray_2_2",
+                include_bytes!("../assets/Generic Gray Gamma 2.2 Profile.icc"),
+            ),
+            (
+                "srgb_perceptual",
+                include_bytes!("../assets/srgb_perceptual.icc"),
+            ),
+            (
+                "us_swop_coated",
+                include_bytes!("../assets/us_swop_coated.icc"),
+            ),
+        ];
+        let parse = |values: &str| {
+            values
+                .split_whitespace()
+                .map(|v| v.parse::<f32>().unwrap())
+                .collect::<Vec<f32>>()
+        };
+        let mut expected: Vec<(String, Vec<ConformanceSample>)> = Vec::new();
+        for line in include_str!("../assets/conformance_lcms2.txt").lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split(';');
+            let name = parts.next().unwrap().trim();
+            let sample = ConformanceSample {
+                source: parse(parts.next().unwrap()),
+                expected: parse(parts.next().unwrap()),
+            };
+            match expected.last_mut() {
+                Some((last, samples)) if last == name => samples.push(sample),
+                _ => expected.push((name.to_string(), vec![sample])),
+            }
+        }
+        assert_eq!(expected.len(), assets.len() * 3);
+
+        for (name, data) in assets {
+            let profile = ColorProfile::new_from_slice(data).unwrap();
+            for (case_name, source, destination) in [
+                (format!("{name} -> srgb"), &profile, &srgb),
+                (format!("srgb -> {name}"), &srgb, &profile),
+                (format!("{name} -> lab"), &profile, &lab),
+            ] {
+                let samples = &expected.iter().find(|x| x.0 == case_name).unwrap().1;
+                let case = ConformanceCase::new(
+                    case_name,
+                    source.clone(),
+                    destination.clone(),
+                    TransformOptions {
+                        rendering_intent: RenderingIntent::RelativeColorimetric,
+                        ..Default::default()
+                    },
+                );
+                let report = run_expected(&case, samples).unwrap();
+                assert_eq!(report.samples, samples.len());
+                assert!(report.mean_delta_e <= 0.25, "{report:?}");
+                assert!(report.max_delta_e <= 1., "{report:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! sRGB and Display P3 conversion tables evaluated at compile time.
+use crate::{ColorPrimaries, ColorProfile, Matrix3d, Matrix3f, WHITE_POINT_D65};
+
+/// `log2` for positive finite values usable in `const` context
+const fn const_log2(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    // Mantissa in [1, 2)
+    let m = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    // ln(m) = 2 * atanh((m - 1) / (m + 1))
+    let t = (m - 1.) / (m + 1.);
+    let t2 = t * t;
+    let mut term = t;
+    let mut sum = 0.;
+    let mut k = 1;
+    while k < 60 {
+        sum += term / k as f64;
+        term *= t2;
+        k += 2;
+    }
+    exponent as f64 + 2. * sum * std::f64::consts::LOG2_E
+}
+
+/// `exp2` usable in `const` context
+const fn const_exp2(x: f64) -> f64 {
+    let mut n = x as i64;
+    if n as f64 > x {
+        n -= 1;
+    }
+    let f = (x - n as f64) * std::f64::consts::LN_2;
+    let mut term = 1.;
+    let mut sum = 1.;
+    let mut k = 1;
+    while k < 30 {
+        term *= f / k as f64;
+        sum += term;
+        k += 1;
+    }
+    if n < -1022 {
+        return 0.;
+    }
+    sum * f64::from_bits(((n + 1023) as u64) << 52)
+}
+
+const fn const_pow(x: f64, y: f64) -> f64 {
+    if x <= 0. {
+        return 0.;
+    }
+    const_exp2(y * const_log2(x))
+}
+
+const fn srgb_eotf(v: f64) -> f64 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        const_pow((v + 0.055) / 1.055, 2.4)
+    }
+}
+
+const fn srgb_oetf(v: f64) -> f64 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * const_pow(v, 1. / 2.4) - 0.055
+    }
+}
+
+/// Count of entries in [LINEAR_TO_SRGB_8BIT]
+pub const LINEAR_TO_SRGB_8BIT_SIZE: usize = 4096;
+
+/// sRGB 8 bit value to linear light in [0, 1]
+pub const SRGB_TO_LINEAR_8BIT: [f32; 256] = {
+    let mut table = [0f32; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = srgb_eotf(i as f64 / 255.) as f32;
+        i += 1;
+    }
+    table
+};
+
+/// Linear light quantized to [LINEAR_TO_SRGB_8BIT_SIZE] steps to sRGB 8 bit value
+pub const LINEAR_TO_SRGB_8BIT: [u8; LINEAR_TO_SRGB_8BIT_SIZE] = {
+    let mut table = [0u8; LINEAR_TO_SRGB_8BIT_SIZE];
+    let mut i = 0;
+    while i < LINEAR_TO_SRGB_8BIT_SIZE {
+        let v = srgb_oetf(i as f64 / (LINEAR_TO_SRGB_8BIT_SIZE - 1) as f64);
+        table[i] = (v * 255. + 0.5) as u8;
+        i += 1;
+    }
+    table
+};
+
+const fn rgb_to_xyz(primaries: ColorPrimaries) -> Matrix3d {
+    let red_xyz = primaries.red.to_scaled_xyzd();
+    let green_xyz = primaries.green.to_scaled_xyzd();
+    let blue_xyz = primaries.blue.to_scaled_xyzd();
+    let xyz_matrix = Matrix3d {
+        v: [
+            [red_xyz.x, green_xyz.x, blue_xyz.x],
+            [red_xyz.y, green_xyz.y, blue_xyz.y],
+            [red_xyz.z, green_xyz.z, blue_xyz.z],
+        ],
+    };
+    ColorProfile::rgb_to_xyz_d(xyz_matrix, WHITE_POINT_D65.to_xyzd())
+}
+
+/// Linear sRGB to linear Display P3, both share D65 white point so no adaptation is involved
+pub const SRGB_TO_DISPLAY_P3: Matrix3f = rgb_to_xyz(ColorPrimaries::DISPLAY_P3)
+    .inverse()
+    .mat_mul_const(rgb_to_xyz(ColorPrimaries::BT_709))
+    .to_f32();
+
+/// Linear Display P3 to linear sRGB
+pub const DISPLAY_P3_TO_SRGB: Matrix3f = rgb_to_xyz(ColorPrimaries::BT_709)
+    .inverse()
+    .mat_mul_const(rgb_to_xyz(ColorPrimaries::DISPLAY_P3))
+    .to_f32();
+
+const fn convert_8bit(rgb: [u8; 3], matrix: Matrix3f) -> [u8; 3] {
+    let r = SRGB_TO_LINEAR_8BIT[rgb[0] as usize];
+    let g = SRGB_TO_LINEAR_8BIT[rgb[1] as usize];
+    let b = SRGB_TO_LINEAR_8BIT[rgb[2] as usize];
+    let mut dst = [0u8; 3];
+    let mut i = 0;
+    while i < 3 {
+        let v = r * matrix.v[i][0] + g * matrix.v[i][1] + b * matrix.v[i][2];
+        let v = v.clamp(0., 1.);
+        dst[i] = LINEAR_TO_SRGB_8BIT[(v * (LINEAR_TO_SRGB_8BIT_SIZE - 1) as f32 + 0.5) as usize];
+        i += 1;
+    }
+    dst
+}
+
+/// Converts 8 bit sRGB pixel to 8 bit Display P3 pixel, out of gamut values are clipped.
+///
+/// Uses only compile time tables, so there is no setup cost.
+pub const fn srgb_to_display_p3_8bit(rgb: [u8; 3]) -> [u8; 3] {
+    convert_8bit(rgb, SRGB_TO_DISPLAY_P3)
+}
+
+/// Converts 8 bit Display P3 pixel to 8 bit sRGB pixel, out of gamut values are clipped.
+///
+/// Uses only compile time tables, so there is no setup cost.
+pub const fn display_p3_to_srgb_8bit(rgb: [u8; 3]) -> [u8; 3] {
+    convert_8bit(rgb, DISPLAY_P3_TO_SRGB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Layout, TransformOptions};
+
+    #[test]
+    fn test_const_tables_match_transform() {
+        for (i, &v) in SRGB_TO_LINEAR_8BIT.iter().enumerate() {
+            let reference = srgb_eotf_reference(i as f64 / 255.);
+            assert!((v as f64 - reference).abs() < 1e-6);
+        }
+        let srgb = ColorProfile::new_srgb();
+        let p3 = ColorProfile::new_display_p3();
+        let src = (0..256 * 3)
+            .map(|x| (x * 7 % 256) as u8)
+            .collect::<Vec<_>>();
+        for (src_pr, dst_pr, convert) in [
+            (
+                &srgb,
+                &p3,
+                srgb_to_display_p3_8bit as fn([u8; 3]) -> [u8; 3],
+            ),
+            (&p3, &srgb, display_p3_to_srgb_8bit),
+        ] {
+            let transform = src_pr
+                .create_transform_8bit(
+                    Layout::Rgb,
+                    dst_pr,
+                    Layout::Rgb,
+                    TransformOptions::default(),
+                )
+                .unwrap();
+            let mut dst = vec![0u8; src.len()];
+            transform.transform(&src, &mut dst).unwrap();
+            for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact(3)) {
+                let converted = convert([src[0], src[1], src[2]]);
+                for (&a, &b) in converted.iter().zip(dst.iter()) {
+                    assert!(a.abs_diff(b) <= 1, "{src:?} {converted:?} {dst:?}");
+                }
+            }
+        }
+    }
+
+    fn srgb_eotf_reference(v: f64) -> f64 {
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    }
+}
@@ -219,7 +219,7 @@ where
                             Box::new(PrismaticAvxFmaDouble::<GRID_SIZE> {}),
                         );
                     }
-                    InterpolationMethod::Linear => {
+                    InterpolationMethod::Linear | InterpolationMethod::Auto => {
                         self.transform_chunk(
                             src,
                             dst,
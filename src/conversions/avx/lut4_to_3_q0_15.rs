@@ -214,7 +214,7 @@ where
                             Box::new(PrismaticAvxQ0_15Double::<GRID_SIZE> {}),
                         );
                     }
-                    InterpolationMethod::Linear => {
+                    InterpolationMethod::Linear | InterpolationMethod::Auto => {
                         self.transform_chunk(
                             src,
                             dst,
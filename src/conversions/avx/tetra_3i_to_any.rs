@@ -0,0 +1,98 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::md_lut::{MultidimensionalLut, tetra_3i_corners};
+use std::arch::x86_64::*;
+
+#[inline(always)]
+unsafe fn load_lane8(arr: &[f32], offset: usize) -> __m256 {
+    unsafe {
+        if offset + 8 <= arr.len() {
+            _mm256_loadu_ps(arr.as_ptr().add(offset))
+        } else {
+            let mut tmp = [0f32; 8];
+            let available = arr.len().saturating_sub(offset).min(8);
+            tmp[..available].copy_from_slice(&arr[offset..offset + available]);
+            _mm256_loadu_ps(tmp.as_ptr())
+        }
+    }
+}
+
+#[target_feature(enable = "avx2", enable = "fma")]
+unsafe fn tetra_3i_to_any_avx_impl(
+    lut: &MultidimensionalLut,
+    arr: &[f32],
+    x: f32,
+    y: f32,
+    z: f32,
+    dst: &mut [f32],
+    inks: usize,
+) {
+    let corners = tetra_3i_corners(lut, x, y, z);
+    let rx = _mm256_set1_ps(corners.w[0]);
+    let ry = _mm256_set1_ps(corners.w[1]);
+    let rz = _mm256_set1_ps(corners.w[2]);
+    let mut store = [0f32; 8];
+    for (k, dst) in dst[..inks].chunks_mut(8).enumerate() {
+        let k = k * 8;
+        unsafe {
+            let c0 = load_lane8(arr, corners.c0 + k);
+            let c1 = _mm256_sub_ps(
+                load_lane8(arr, corners.a[0] + k),
+                load_lane8(arr, corners.b[0] + k),
+            );
+            let c2 = _mm256_sub_ps(
+                load_lane8(arr, corners.a[1] + k),
+                load_lane8(arr, corners.b[1] + k),
+            );
+            let c3 = _mm256_sub_ps(
+                load_lane8(arr, corners.a[2] + k),
+                load_lane8(arr, corners.b[2] + k),
+            );
+            let s0 = _mm256_fmadd_ps(c1, rx, c0);
+            let s1 = _mm256_fmadd_ps(c2, ry, s0);
+            let v = _mm256_fmadd_ps(c3, rz, s1);
+            _mm256_storeu_ps(store.as_mut_ptr(), v);
+        }
+        dst.copy_from_slice(&store[..dst.len()]);
+    }
+}
+
+/// AVX2 version of [crate::conversions::md_lut::tetra_3i_to_any_vec],
+/// may be selected only when `avx2` and `fma` are available
+pub(crate) fn tetra_3i_to_any_avx(
+    lut: &MultidimensionalLut,
+    arr: &[f32],
+    x: f32,
+    y: f32,
+    z: f32,
+    dst: &mut [f32],
+    inks: usize,
+) {
+    unsafe { tetra_3i_to_any_avx_impl(lut, arr, x, y, z, dst, inks) }
+}
@@ -26,96 +26,161 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-//
-// use crate::conversions::interpolator::{MultidimensionalInterpolation, Tetrahedral};
-// use crate::conversions::transform_lut4_to_4::{NonFiniteVector3fLerp, Vector3fCmykLerp};
-// use crate::mlaf::mlaf;
-// use crate::{Chromaticity, ColorProfile, DataColorSpace, Lab, Xyz};
-//
-// impl ColorProfile {
-//     #[inline]
-//     pub(crate) fn detect_black_point<const GRID_SIZE: usize>(&self, lut: &[f32]) -> Option<Xyz> {
-//         if self.color_space == DataColorSpace::Cmyk {
-//             // if let Some(mut bp) = self.black_point {
-//             //     if let Some(wp) = self.media_white_point.map(|x| x.normalize()) {
-//             //         if wp != Chromaticity::D50.to_xyz() {
-//             //             let ad = adaption_matrix(wp, Chromaticity::D50.to_xyz());
-//             //             let v = ad.mul_vector(bp.to_vector());
-//             //             bp = Xyz {
-//             //                 x: v.v[0],
-//             //                 y: v.v[1],
-//             //                 z: v.v[2],
-//             //             };
-//             //         }
-//             //     }
-//             //     let mut lab = Lab::from_xyz(bp);
-//             //     lab.a = 0.;
-//             //     lab.b = 0.;
-//             //     if lab.l > 50. {
-//             //         lab.l = 50.;
-//             //     }
-//             //     bp = lab.to_xyz();
-//             //     return Some(bp);
-//             // }
-//             let c = 65535;
-//             let m = 65535;
-//             let y = 65535;
-//             let k = 65535;
-//
-//             let linear_k: f32 = k as f32 * (1. / 65535.);
-//             let w: i32 = k * (GRID_SIZE as i32 - 1) / 65535;
-//             let w_n: i32 = (w + 1).min(GRID_SIZE as i32 - 1);
-//             let t: f32 = linear_k * (GRID_SIZE as i32 - 1) as f32 - w as f32;
-//
-//             let grid_size = GRID_SIZE as i32;
-//             let grid_size3 = grid_size * grid_size * grid_size;
-//
-//             let table1 = &lut[(w * grid_size3 * 3) as usize..];
-//             let table2 = &lut[(w_n * grid_size3 * 3) as usize..];
-//
-//             let tetrahedral1 = Tetrahedral::<GRID_SIZE>::new(table1);
-//             let tetrahedral2 = Tetrahedral::<GRID_SIZE>::new(table2);
-//             let r1 = tetrahedral1.inter3(c, m, y);
-//             let r2 = tetrahedral2.inter3(c, m, y);
-//             let r = NonFiniteVector3fLerp::interpolate(r1, r2, t, 1.0);
-//
-//             let mut lab = Lab::from_xyz(Xyz {
-//                 x: r.v[0],
-//                 y: r.v[1],
-//                 z: r.v[2],
-//             });
-//             lab.a = 0.;
-//             lab.b = 0.;
-//             if lab.l > 50. {
-//                 lab.l = 50.;
-//             }
-//             let bp = lab.to_xyz();
-//
-//             return Some(bp);
-//         }
-//         if self.color_space == DataColorSpace::Rgb {
-//             return Some(Xyz::new(0.0, 0.0, 0.0));
-//         }
-//         None
-//     }
-// }
-//
-// pub(crate) fn compensate_bpc_in_lut(lut_xyz: &mut [f32], src_bp: Xyz, dst_bp: Xyz) {
-//     const WP_50: Xyz = Chromaticity::D50.to_xyz();
-//     let tx = src_bp.x - WP_50.x;
-//     let ty = src_bp.y - WP_50.y;
-//     let tz = src_bp.z - WP_50.z;
-//     let ax = (dst_bp.x - WP_50.x) / tx;
-//     let ay = (dst_bp.y - WP_50.y) / ty;
-//     let az = (dst_bp.z - WP_50.z) / tz;
-//
-//     let bx = -WP_50.x * (dst_bp.x - src_bp.x) / tx;
-//     let by = -WP_50.y * (dst_bp.y - src_bp.y) / ty;
-//     let bz = -WP_50.z * (dst_bp.z - src_bp.z) / tz;
-//
-//     for dst in lut_xyz.chunks_exact_mut(3) {
-//         dst[0] = mlaf(bx, dst[0], ax);
-//         dst[1] = mlaf(by, dst[1], ay);
-//         dst[2] = mlaf(bz, dst[2], az);
-//     }
-// }
+use crate::conversions::katana::KatanaIntermediateStage;
+use crate::{
+    Chromaticity, CmsError, ColorProfile, DataColorSpace, InPlaceStage, Lab, RenderingIntent,
+    TransformOptions, Vector3d, Xyz,
+};
+
+/// Affine of PCS XYZ that moves source black onto destination black
+/// while keeping D50 white in place, as lcms2 does.
+///
+/// Black points are treated as neutral, so the scale is the same for all axes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct BlackPointCompensation {
+    pub(crate) scale: f64,
+    pub(crate) offset: Vector3d,
+}
+
+impl BlackPointCompensation {
+    /// Returns `None` when compensation is disabled, not applicable to the intent,
+    /// or black points are equal or cannot be estimated.
+    pub(crate) fn new(
+        source: &ColorProfile,
+        dest: &ColorProfile,
+        options: TransformOptions,
+    ) -> Option<BlackPointCompensation> {
+        if !options.black_point_compensation
+            || !matches!(
+                options.rendering_intent,
+                RenderingIntent::RelativeColorimetric | RenderingIntent::Saturation
+            )
+        {
+            return None;
+        }
+        let src_black = source.relative_black_luminance().ok()?.clamp(0., 0.5);
+        let dst_black = dest.relative_black_luminance().ok()?.clamp(0., 0.5);
+        if (src_black - dst_black).abs() < 1e-6 {
+            return None;
+        }
+        let white = Chromaticity::D50.to_xyzd();
+        let a = (1. - dst_black) / (1. - src_black);
+        let b = (dst_black - src_black) / (1. - src_black);
+        Some(BlackPointCompensation {
+            scale: a,
+            offset: Vector3d {
+                v: [white.x * b, white.y * b, white.z * b],
+            },
+        })
+    }
+
+    #[inline]
+    pub(crate) fn apply(&self, xyz: [f64; 3]) -> [f64; 3] {
+        [
+            xyz[0] * self.scale + self.offset.v[0],
+            xyz[1] * self.scale + self.offset.v[1],
+            xyz[2] * self.scale + self.offset.v[2],
+        ]
+    }
+
+    /// Compensates PCS values, Lab PCS goes through XYZ.
+    ///
+    /// LUT pipeline keeps XYZ in ICC encoding where white has Y = 32768 / 65535,
+    /// so the offset is encoded the same way.
+    pub(crate) fn apply_pcs(&self, dst: &mut [f32], pcs: DataColorSpace) {
+        let scale = self.scale as f32;
+        let offset = self.offset.v.map(|v| (v * (32768. / 65535.)) as f32);
+        for dst in dst.chunks_exact_mut(3) {
+            let xyz = if pcs == DataColorSpace::Lab {
+                Lab::new(dst[0], dst[1], dst[2]).to_pcs_xyz()
+            } else {
+                Xyz::new(dst[0], dst[1], dst[2])
+            };
+            let xyz = Xyz::new(
+                xyz.x * scale + offset[0],
+                xyz.y * scale + offset[1],
+                xyz.z * scale + offset[2],
+            );
+            if pcs == DataColorSpace::Lab {
+                let lab = Lab::from_pcs_xyz(xyz);
+                dst[0] = lab.l;
+                dst[1] = lab.a;
+                dst[2] = lab.b;
+            } else {
+                dst[0] = xyz.x;
+                dst[1] = xyz.y;
+                dst[2] = xyz.z;
+            }
+        }
+    }
+}
+
+pub(crate) struct StageBlackPointCompensation {
+    pub(crate) bpc: BlackPointCompensation,
+    pub(crate) pcs: DataColorSpace,
+}
+
+impl InPlaceStage for StageBlackPointCompensation {
+    fn transform(&self, dst: &mut [f32]) -> Result<(), CmsError> {
+        self.bpc.apply_pcs(dst, self.pcs);
+        Ok(())
+    }
+}
+
+impl KatanaIntermediateStage<f32> for StageBlackPointCompensation {
+    fn stage(&self, input: &mut Vec<f32>) -> Result<Vec<f32>, CmsError> {
+        self.bpc.apply_pcs(input, self.pcs);
+        Ok(std::mem::take(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Layout, ReferenceTransform};
+
+    #[test]
+    fn test_black_point_compensation() {
+        let cmyk = ColorProfile::new_from_slice(include_bytes!("../../assets/us_swop_coated.icc"))
+            .unwrap();
+        let srgb = ColorProfile::new_srgb();
+        let relative = TransformOptions {
+            rendering_intent: RenderingIntent::RelativeColorimetric,
+            ..Default::default()
+        };
+        let adobe = TransformOptions::adobe_compatible(RenderingIntent::RelativeColorimetric);
+        assert!(BlackPointCompensation::new(&cmyk, &srgb, relative).is_none());
+        let bpc = BlackPointCompensation::new(&cmyk, &srgb, adobe).unwrap();
+        assert!(bpc.scale > 1., "{bpc:?}");
+        let absolute = TransformOptions::adobe_compatible(RenderingIntent::AbsoluteColorimetric);
+        assert!(BlackPointCompensation::new(&cmyk, &srgb, absolute).is_none());
+
+        let reference = ReferenceTransform::new(&cmyk, &srgb, adobe).unwrap();
+        let black = reference.device_to_pcs(&[1., 1., 1., 1.]).unwrap();
+        assert!(black[1].abs() < 5e-3, "{black:?}");
+
+        let src = [255u8, 255, 255, 255];
+        let mut plain = [0u8; 3];
+        let mut compensated = [0u8; 3];
+        cmyk.create_transform_8bit(Layout::Rgba, &srgb, Layout::Rgb, relative)
+            .unwrap()
+            .transform(&src, &mut plain)
+            .unwrap();
+        cmyk.create_transform_8bit(Layout::Rgba, &srgb, Layout::Rgb, adobe)
+            .unwrap()
+            .transform(&src, &mut compensated)
+            .unwrap();
+        assert!(plain.iter().any(|&v| v > 10), "{plain:?}");
+        let mut expected = [0f64; 3];
+        reference
+            .transform_pixel(&[1., 1., 1., 1.], &mut expected)
+            .unwrap();
+        for ((&v, &e), &p) in compensated.iter().zip(expected.iter()).zip(plain.iter()) {
+            assert!(
+                (v as f64 - e * 255.).abs() <= 2.,
+                "{compensated:?} {expected:?}"
+            );
+            assert!(v + 15 < p, "{compensated:?} {plain:?}");
+        }
+    }
+}
@@ -100,15 +100,15 @@ where
         Layout::GrayAlpha => match dst_layout {
             Layout::Rgb => Ok(Box::new(TransformGray2RgbFusedExecutor::<
                 T,
-                { Layout::Gray as u8 },
                 { Layout::GrayAlpha as u8 },
+                { Layout::Rgb as u8 },
             > {
                 fused_gamma,
                 bit_depth,
             })),
             Layout::Rgba => Ok(Box::new(TransformGray2RgbFusedExecutor::<
                 T,
-                { Layout::Gray as u8 },
+                { Layout::GrayAlpha as u8 },
                 { Layout::Rgba as u8 },
             > {
                 fused_gamma,
@@ -116,7 +116,7 @@ where
             })),
             Layout::Gray => Ok(Box::new(TransformGray2RgbFusedExecutor::<
                 T,
-                { Layout::Gray as u8 },
+                { Layout::GrayAlpha as u8 },
                 { Layout::Gray as u8 },
             > {
                 fused_gamma,
@@ -276,8 +276,8 @@ where
         Layout::GrayAlpha => match dst_layout {
             Layout::Rgb => Ok(Box::new(TransformGrayToRgbExecutor::<
                 T,
-                { Layout::Gray as u8 },
                 { Layout::GrayAlpha as u8 },
+                { Layout::Rgb as u8 },
             > {
                 gray_linear,
                 red_gamma,
@@ -288,7 +288,7 @@ where
             })),
             Layout::Rgba => Ok(Box::new(TransformGrayToRgbExecutor::<
                 T,
-                { Layout::Gray as u8 },
+                { Layout::GrayAlpha as u8 },
                 { Layout::Rgba as u8 },
             > {
                 gray_linear,
@@ -300,7 +300,7 @@ where
             })),
             Layout::Gray => Ok(Box::new(TransformGrayToRgbExecutor::<
                 T,
-                { Layout::Gray as u8 },
+                { Layout::GrayAlpha as u8 },
                 { Layout::Gray as u8 },
             > {
                 gray_linear,
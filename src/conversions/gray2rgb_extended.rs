@@ -103,8 +103,8 @@ where
         Layout::GrayAlpha => match dst_layout {
             Layout::Rgb => Ok(Box::new(TransformGrayOneToOneExecutor::<
                 T,
-                { Layout::Gray as u8 },
                 { Layout::GrayAlpha as u8 },
+                { Layout::Rgb as u8 },
             > {
                 linear_eval,
                 gamma_eval,
@@ -113,7 +113,7 @@ where
             })),
             Layout::Rgba => Ok(Box::new(TransformGrayOneToOneExecutor::<
                 T,
-                { Layout::Gray as u8 },
+                { Layout::GrayAlpha as u8 },
                 { Layout::Rgba as u8 },
             > {
                 linear_eval,
@@ -123,7 +123,7 @@ where
             })),
             Layout::Gray => Ok(Box::new(TransformGrayOneToOneExecutor::<
                 T,
-                { Layout::Gray as u8 },
+                { Layout::GrayAlpha as u8 },
                 { Layout::Gray as u8 },
             > {
                 linear_eval,
@@ -274,8 +274,8 @@ where
         Layout::GrayAlpha => match dst_layout {
             Layout::Rgb => Ok(Box::new(TransformGrayToRgbExtendedExecutor::<
                 T,
-                { Layout::Gray as u8 },
                 { Layout::GrayAlpha as u8 },
+                { Layout::Rgb as u8 },
             > {
                 linear_eval,
                 gamma_eval,
@@ -284,7 +284,7 @@ where
             })),
             Layout::Rgba => Ok(Box::new(TransformGrayToRgbExtendedExecutor::<
                 T,
-                { Layout::Gray as u8 },
+                { Layout::GrayAlpha as u8 },
                 { Layout::Rgba as u8 },
             > {
                 linear_eval,
@@ -294,7 +294,7 @@ where
             })),
             Layout::Gray => Ok(Box::new(TransformGrayToRgbExtendedExecutor::<
                 T,
-                { Layout::Gray as u8 },
+                { Layout::GrayAlpha as u8 },
                 { Layout::Gray as u8 },
             > {
                 linear_eval,
@@ -27,6 +27,7 @@
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::conversions::katana::{KatanaFinalStage, KatanaInitialStage};
+use crate::err::try_resize_vec;
 use crate::mlaf::mlaf;
 use crate::safe_math::SafeMul;
 use crate::trc::lut_interp_linear_float;
@@ -165,7 +166,7 @@ impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync
 impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync>
     KatanaInitialStage<f32, T> for Multidimensional3x3<T>
 {
-    fn to_pcs(&self, input: &[T]) -> Result<Vec<f32>, CmsError> {
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
         if input.len() % 3 != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
@@ -173,32 +174,32 @@ impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync
         let new_clut = self.clut.as_ref().unwrap_or(&fixed_new_clut);
         let lut = Cube::new_checked_cube(new_clut, self.grid_size, 3)?;
 
-        let mut new_dst = vec![0f32; input.len()];
+        try_resize_vec(dst, input.len(), 0.)?;
 
         // If PCS is LAB then linear interpolation should be used
         if self.pcs == DataColorSpace::Lab || self.pcs == DataColorSpace::Xyz {
-            self.to_pcs_impl(input, &mut new_dst, |x, y, z| lut.trilinear_vec3(x, y, z))?;
-            return Ok(new_dst);
+            self.to_pcs_impl(input, dst, |x, y, z| lut.trilinear_vec3(x, y, z))?;
+            return Ok(());
         }
 
         match self.options.interpolation_method {
             #[cfg(feature = "options")]
             InterpolationMethod::Tetrahedral => {
-                self.to_pcs_impl(input, &mut new_dst, |x, y, z| lut.tetra_vec3(x, y, z))?;
+                self.to_pcs_impl(input, dst, |x, y, z| lut.tetra_vec3(x, y, z))?;
             }
             #[cfg(feature = "options")]
             InterpolationMethod::Pyramid => {
-                self.to_pcs_impl(input, &mut new_dst, |x, y, z| lut.pyramid_vec3(x, y, z))?;
+                self.to_pcs_impl(input, dst, |x, y, z| lut.pyramid_vec3(x, y, z))?;
             }
             #[cfg(feature = "options")]
             InterpolationMethod::Prism => {
-                self.to_pcs_impl(input, &mut new_dst, |x, y, z| lut.prism_vec3(x, y, z))?;
+                self.to_pcs_impl(input, dst, |x, y, z| lut.prism_vec3(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
-                self.to_pcs_impl(input, &mut new_dst, |x, y, z| lut.trilinear_vec3(x, y, z))?;
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
+                self.to_pcs_impl(input, dst, |x, y, z| lut.trilinear_vec3(x, y, z))?;
             }
         }
-        Ok(new_dst)
+        Ok(())
     }
 }
 
@@ -335,7 +336,7 @@ where
             InterpolationMethod::Prism => {
                 self.to_output_impl(src, dst, |x, y, z| lut.prism_vec3(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.to_output_impl(src, dst, |x, y, z| lut.trilinear_vec3(x, y, z))?;
             }
         }
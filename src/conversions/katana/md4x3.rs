@@ -28,6 +28,7 @@
  */
 use crate::conversions::katana::KatanaInitialStage;
 use crate::conversions::katana::md3x3::MultidimensionalDirection;
+use crate::err::try_resize_vec;
 use crate::mlaf::mlaf;
 use crate::safe_math::SafeMul;
 use crate::trc::lut_interp_linear_float;
@@ -153,7 +154,7 @@ impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync
 impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync>
     KatanaInitialStage<f32, T> for Multidimensional4x3<T>
 {
-    fn to_pcs(&self, input: &[T]) -> Result<Vec<f32>, CmsError> {
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
         if input.len() % 4 != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
@@ -161,38 +162,32 @@ impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync
         let new_clut = self.clut.as_ref().unwrap_or(&fixed_new_clut);
         let lut = Hypercube::new_checked_hypercube(new_clut, self.grid_size, 3)?;
 
-        let mut new_dst = vec![0f32; (input.len() / 4) * 3];
+        try_resize_vec(dst, (input.len() / 4) * 3, 0.)?;
 
         // If PCS is LAB then linear interpolation should be used
         if self.pcs == DataColorSpace::Lab || self.pcs == DataColorSpace::Xyz {
-            self.to_pcs_impl(input, &mut new_dst, |x, y, z, w| {
-                lut.quadlinear_vec3(x, y, z, w)
-            })?;
-            return Ok(new_dst);
+            self.to_pcs_impl(input, dst, |x, y, z, w| lut.quadlinear_vec3(x, y, z, w))?;
+            return Ok(());
         }
 
         match self.options.interpolation_method {
             #[cfg(feature = "options")]
             InterpolationMethod::Tetrahedral => {
-                self.to_pcs_impl(input, &mut new_dst, |x, y, z, w| lut.tetra_vec3(x, y, z, w))?;
+                self.to_pcs_impl(input, dst, |x, y, z, w| lut.tetra_vec3(x, y, z, w))?;
             }
             #[cfg(feature = "options")]
             InterpolationMethod::Pyramid => {
-                self.to_pcs_impl(input, &mut new_dst, |x, y, z, w| {
-                    lut.pyramid_vec3(x, y, z, w)
-                })?;
+                self.to_pcs_impl(input, dst, |x, y, z, w| lut.pyramid_vec3(x, y, z, w))?;
             }
             #[cfg(feature = "options")]
             InterpolationMethod::Prism => {
-                self.to_pcs_impl(input, &mut new_dst, |x, y, z, w| lut.prism_vec3(x, y, z, w))?;
+                self.to_pcs_impl(input, dst, |x, y, z, w| lut.prism_vec3(x, y, z, w))?;
             }
-            InterpolationMethod::Linear => {
-                self.to_pcs_impl(input, &mut new_dst, |x, y, z, w| {
-                    lut.quadlinear_vec3(x, y, z, w)
-                })?;
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
+                self.to_pcs_impl(input, dst, |x, y, z, w| lut.quadlinear_vec3(x, y, z, w))?;
             }
         }
-        Ok(new_dst)
+        Ok(())
     }
 }
 
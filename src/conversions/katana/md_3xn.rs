@@ -29,7 +29,7 @@
 use crate::conversions::katana::KatanaFinalStage;
 use crate::conversions::katana::md3x3::MultidimensionalDirection;
 use crate::conversions::katana::md4x3::{execute_matrix_stage3, execute_simple_curves3};
-use crate::conversions::md_lut::{MultidimensionalLut, tetra_3i_to_any_vec};
+use crate::conversions::md_lut::{MultidimensionalLut, tetra_3i_to_any_handler};
 use crate::safe_math::SafeMul;
 use crate::trc::lut_interp_linear_float;
 use crate::{
@@ -86,25 +86,27 @@ where
         }
 
         if let (Some(a_curves), Some(clut)) = (self.a_curves.as_ref(), self.clut.as_ref()) {
-            let mut inks = vec![0.; self.output_inks];
+            let mut inks = [0f32; 16];
+            let inks = &mut inks[..self.output_inks];
 
             if clut.is_empty() {
                 return Err(CmsError::InvalidAtoBLut);
             }
 
             let md_lut = MultidimensionalLut::new(self.grid_size, 3, self.output_inks);
+            let tetra = tetra_3i_to_any_handler(self.output_inks);
 
             for (src, dst) in src
                 .chunks_exact(3)
                 .zip(dst.chunks_exact_mut(self.dst_layout.channels()))
             {
-                tetra_3i_to_any_vec(
+                tetra(
                     &md_lut,
                     clut,
                     src[0],
                     src[1],
                     src[2],
-                    &mut inks,
+                    inks,
                     self.output_inks,
                 );
 
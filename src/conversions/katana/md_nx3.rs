@@ -35,6 +35,7 @@ use crate::conversions::md_lut::{
     linear_9i_vec3f, linear_10i_vec3f, linear_11i_vec3f, linear_12i_vec3f, linear_13i_vec3f,
     linear_14i_vec3f, linear_15i_vec3f,
 };
+use crate::err::try_resize_vec;
 use crate::safe_math::SafeMul;
 use crate::trc::lut_interp_linear_float;
 use crate::{
@@ -61,12 +62,11 @@ struct MultidimensionalNx3<
 }
 
 #[inline(never)]
-pub(crate) fn interpolate_out_function(
+pub(crate) fn interpolate_out_function<const OUT: usize>(
     layout: Layout,
-) -> fn(lut: &MultidimensionalLut, arr: &[f32], inputs: &[f32]) -> NVector<f32, 3> {
-    const OUT: usize = 3;
+) -> fn(lut: &MultidimensionalLut, arr: &[f32], inputs: &[f32]) -> NVector<f32, OUT> {
     match layout {
-        Layout::Rgb => linear_3i_vec3f_direct::<OUT>,
+        Layout::Rgb | Layout::Xyz | Layout::Lab => linear_3i_vec3f_direct::<OUT>,
         Layout::Rgba => linear_4i_vec3f::<OUT>,
         Layout::Gray => linear_1i_vec3f::<OUT>,
         Layout::GrayAlpha => linear_2i_vec3f_direct::<OUT>,
@@ -105,13 +105,14 @@ impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync
         if let (Some(a_curves), Some(clut)) = (self.a_curves.as_ref(), self.clut.as_ref()) {
             let layout = Layout::from_inks(self.input_inks);
 
-            let mut inks = vec![0.; self.input_inks];
+            let mut inks = [0f32; 16];
+            let inks = &mut inks[..self.input_inks];
 
             if clut.is_empty() {
                 return Err(CmsError::InvalidAtoBLut);
             }
 
-            let fetcher = interpolate_out_function(layout);
+            let fetcher = interpolate_out_function::<3>(layout);
 
             let md_lut = MultidimensionalLut::new(self.grid_size, self.input_inks, 3);
 
@@ -123,7 +124,7 @@ impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync
                     *ink = lut_interp_linear_float(src_ink.as_() * norm_value, curve);
                 }
 
-                let interpolated = fetcher(&md_lut, clut, &inks);
+                let interpolated = fetcher(&md_lut, clut, inks);
 
                 dst[0] = interpolated.v[0];
                 dst[1] = interpolated.v[1];
@@ -152,15 +153,14 @@ impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync
 impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync>
     KatanaInitialStage<f32, T> for MultidimensionalNx3<T>
 {
-    fn to_pcs(&self, input: &[T]) -> Result<Vec<f32>, CmsError> {
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
         if input.len() % self.input_inks != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
 
-        let mut new_dst = vec![0f32; (input.len() / self.input_inks) * 3];
+        try_resize_vec(dst, (input.len() / self.input_inks) * 3, 0.)?;
 
-        self.to_pcs_impl(input, &mut new_dst)?;
-        Ok(new_dst)
+        self.to_pcs_impl(input, dst)
     }
 }
 
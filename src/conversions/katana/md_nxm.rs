@@ -0,0 +1,397 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::katana::md_nx3::interpolate_out_function;
+use crate::conversions::katana::md4x3::{execute_matrix_stage3, execute_simple_curves3};
+use crate::conversions::katana::{KatanaFinalStage, KatanaInitialStage};
+use crate::conversions::md_lut::{MultidimensionalLut, tetra_3i_to_any_handler};
+use crate::err::try_resize_vec;
+use crate::mpe::MPE_MAX_CHANNELS;
+use crate::safe_math::SafeMul;
+use crate::trc::lut_interp_linear_float;
+use crate::{
+    CmsError, Layout, LutDataType, LutMultidimensionalType, LutWarehouse, MalformedSize, Matrix3d,
+    Matrix3f, MultiProcessType, PointeeSizeExpressible, Vector3d, Vector3f,
+};
+use num_traits::AsPrimitive;
+use std::marker::PhantomData;
+
+/// Device link CLUT evaluated directly, `input_inks` device values into `output_inks`
+/// device values without an intermediate PCS
+struct KatanaLinkNxM<T> {
+    a_curves: Vec<Vec<f32>>,
+    clut: Vec<f32>,
+    grid_size: [u8; 16],
+    m_curves: Option<Box<[Vec<f32>; 3]>>,
+    matrix: Matrix3f,
+    bias: Vector3f,
+    b_curves: Vec<Vec<f32>>,
+    layout: Layout,
+    input_inks: usize,
+    output_inks: usize,
+    bit_depth: usize,
+    _phantom: PhantomData<T>,
+}
+
+/// Device link `mpet` evaluated directly in floating point
+struct KatanaLinkMpe<T> {
+    mpe: MultiProcessType,
+    layout: Layout,
+    bit_depth: usize,
+    _phantom: PhantomData<T>,
+}
+
+/// Writes `inks` device values into `layout`, remaining channels are left to finalizers
+struct KatanaDeviceOutput<T> {
+    layout: Layout,
+    inks: usize,
+    bit_depth: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync> KatanaLinkNxM<T> {
+    fn to_device_impl(&self, input: &[T], dst: &mut [f32]) {
+        let norm_value = if T::FINITE {
+            1.0 / ((1u32 << self.bit_depth) - 1) as f32
+        } else {
+            1.0
+        };
+        let md_lut = MultidimensionalLut::new(self.grid_size, self.input_inks, self.output_inks);
+
+        let mut inks = [0f32; 16];
+        let inks = &mut inks[..self.input_inks];
+
+        let pixels = input
+            .chunks_exact(self.layout.channels())
+            .zip(dst.chunks_exact_mut(self.output_inks));
+
+        if self.input_inks == 3 {
+            let tetra = tetra_3i_to_any_handler(self.output_inks);
+            for (src, dst) in pixels {
+                for ((ink, src_ink), curve) in inks.iter_mut().zip(src).zip(self.a_curves.iter()) {
+                    *ink = lut_interp_linear_float(src_ink.as_() * norm_value, curve);
+                }
+                tetra(
+                    &md_lut,
+                    &self.clut,
+                    inks[0],
+                    inks[1],
+                    inks[2],
+                    dst,
+                    self.output_inks,
+                );
+            }
+        } else {
+            // Outputs are fetched by four from shifted CLUT, tail one by one
+            let layout = Layout::from_inks(self.input_inks);
+            let fetch4 = interpolate_out_function::<4>(layout);
+            let fetch1 = interpolate_out_function::<1>(layout);
+            let quads = self.output_inks / 4 * 4;
+            for (src, dst) in pixels {
+                for ((ink, src_ink), curve) in inks.iter_mut().zip(src).zip(self.a_curves.iter()) {
+                    *ink = lut_interp_linear_float(src_ink.as_() * norm_value, curve);
+                }
+                for (k, dst) in dst[..quads].chunks_exact_mut(4).enumerate() {
+                    let v = fetch4(&md_lut, &self.clut[k * 4..], inks);
+                    dst.copy_from_slice(&v.v);
+                }
+                for (k, dst) in dst.iter_mut().enumerate().skip(quads) {
+                    *dst = fetch1(&md_lut, &self.clut[k..], inks).v[0];
+                }
+            }
+        }
+
+        if let Some(m_curves) = self.m_curves.as_ref() {
+            execute_simple_curves3(dst, m_curves);
+            execute_matrix_stage3(self.matrix, self.bias, dst);
+        }
+
+        for dst in dst.chunks_exact_mut(self.output_inks) {
+            for (v, curve) in dst.iter_mut().zip(self.b_curves.iter()) {
+                *v = lut_interp_linear_float(*v, curve);
+            }
+        }
+    }
+}
+
+impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync>
+    KatanaInitialStage<f32, T> for KatanaLinkNxM<T>
+{
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
+        let channels = self.layout.channels();
+        if input.len() % channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        try_resize_vec(dst, input.len() / channels * self.output_inks, 0.)?;
+        self.to_device_impl(input, dst);
+        Ok(())
+    }
+}
+
+impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync>
+    KatanaInitialStage<f32, T> for KatanaLinkMpe<T>
+{
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
+        let channels = self.layout.channels();
+        if input.len() % channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        let norm_value = if T::FINITE {
+            1.0 / ((1u32 << self.bit_depth) - 1) as f32
+        } else {
+            1.0
+        };
+        let inputs = self.mpe.num_input_channels as usize;
+        let outputs = self.mpe.num_output_channels as usize;
+        try_resize_vec(dst, input.len() / channels * outputs, 0.)?;
+        let mut device = [0f32; MPE_MAX_CHANNELS];
+        for (src, dst) in input
+            .chunks_exact(channels)
+            .zip(dst.chunks_exact_mut(outputs))
+        {
+            for (dst, src) in device.iter_mut().zip(src[..inputs].iter()) {
+                *dst = src.as_() * norm_value;
+            }
+            self.mpe.evaluate(&device, dst);
+        }
+        Ok(())
+    }
+}
+
+impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync>
+    KatanaFinalStage<f32, T> for KatanaDeviceOutput<T>
+where
+    f32: AsPrimitive<T>,
+{
+    fn to_output(&self, src: &mut [f32], dst: &mut [T]) -> Result<(), CmsError> {
+        let channels = self.layout.channels();
+        if src.len() % self.inks != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if dst.len() % channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / self.inks != dst.len() / channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let norm_value = if T::FINITE {
+            ((1u32 << self.bit_depth) - 1) as f32
+        } else {
+            1.0
+        };
+        for (src, dst) in src
+            .chunks_exact(self.inks)
+            .zip(dst.chunks_exact_mut(channels))
+        {
+            if T::FINITE {
+                for (dst, src) in dst.iter_mut().zip(src.iter()) {
+                    *dst = (*src * norm_value).round().max(0.).min(norm_value).as_();
+                }
+            } else {
+                for (dst, src) in dst.iter_mut().zip(src.iter()) {
+                    *dst = src.as_();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_clut_size(
+    clut: &[f32],
+    grid_size: &[u8; 16],
+    inputs: usize,
+    outputs: usize,
+) -> Result<(), CmsError> {
+    let mut expected = outputs;
+    for &grid in grid_size.iter().take(inputs) {
+        expected = expected.safe_mul(grid as usize)?;
+    }
+    if clut.len() != expected {
+        return Err(CmsError::MalformedClut(MalformedSize {
+            size: clut.len(),
+            expected,
+        }));
+    }
+    Ok(())
+}
+
+/// Splits interleaved `lut8`/`lut16` tables into per channel curves
+fn split_lut_curves(
+    table: Vec<f32>,
+    entries: usize,
+    channels: usize,
+) -> Result<Vec<Vec<f32>>, CmsError> {
+    if table.len() < entries * channels {
+        return Err(CmsError::MalformedCurveLutTable(MalformedSize {
+            size: table.len(),
+            expected: entries * channels,
+        }));
+    }
+    Ok(table
+        .chunks_exact(entries)
+        .take(channels)
+        .map(|x| x.to_vec())
+        .collect())
+}
+
+fn make_link_lut<T>(
+    lut: &LutDataType,
+    layout: Layout,
+    bit_depth: usize,
+) -> Result<KatanaLinkNxM<T>, CmsError> {
+    let inputs = lut.num_input_channels as usize;
+    let outputs = lut.num_output_channels as usize;
+    let grid_size = [lut.num_clut_grid_points; 16];
+    let clut = lut.clut_table.to_clut_f32();
+    check_clut_size(&clut, &grid_size, inputs, outputs)?;
+    Ok(KatanaLinkNxM {
+        a_curves: split_lut_curves(
+            lut.input_table.to_clut_f32(),
+            lut.num_input_table_entries as usize,
+            inputs,
+        )?,
+        clut,
+        grid_size,
+        m_curves: None,
+        matrix: Matrix3f::IDENTITY,
+        bias: Vector3f::default(),
+        b_curves: split_lut_curves(
+            lut.output_table.to_clut_f32(),
+            lut.num_output_table_entries as usize,
+            outputs,
+        )?,
+        layout,
+        input_inks: inputs,
+        output_inks: outputs,
+        bit_depth,
+        _phantom: PhantomData,
+    })
+}
+
+fn make_link_mab<T>(
+    mab: &LutMultidimensionalType,
+    layout: Layout,
+    bit_depth: usize,
+) -> Result<KatanaLinkNxM<T>, CmsError> {
+    let inputs = mab.num_input_channels as usize;
+    let outputs = mab.num_output_channels as usize;
+    if mab.a_curves.len() != inputs || mab.b_curves.len() != outputs {
+        return Err(CmsError::InvalidAtoBLut);
+    }
+    let clut = mab
+        .clut
+        .as_ref()
+        .map(|x| x.to_clut_f32())
+        .ok_or(CmsError::InvalidAtoBLut)?;
+    check_clut_size(&clut, &mab.grid_points, inputs, outputs)?;
+
+    let m_curves: Option<Box<[Vec<f32>; 3]>> = if mab.m_curves.is_empty() {
+        None
+    } else if mab.m_curves.len() == 3 && outputs == 3 {
+        let all_curves_linear = mab.m_curves.iter().all(|curve| curve.is_linear());
+        if !all_curves_linear
+            || !mab.matrix.test_equality(Matrix3d::IDENTITY)
+            || mab.bias.ne(&Vector3d::default())
+        {
+            let mut arr = Box::<[Vec<f32>; 3]>::default();
+            for (curve, dst) in mab.m_curves.iter().zip(arr.iter_mut()) {
+                *dst = curve.to_clut()?;
+            }
+            Some(arr)
+        } else {
+            None
+        }
+    } else {
+        return Err(CmsError::InvalidAtoBLut);
+    };
+
+    Ok(KatanaLinkNxM {
+        a_curves: mab
+            .a_curves
+            .iter()
+            .map(|x| x.to_clut())
+            .collect::<Result<_, _>>()?,
+        clut,
+        grid_size: mab.grid_points,
+        m_curves,
+        matrix: mab.matrix.to_f32(),
+        bias: mab.bias.cast(),
+        b_curves: mab
+            .b_curves
+            .iter()
+            .map(|x| x.to_clut())
+            .collect::<Result<_, _>>()?,
+        layout,
+        input_inks: inputs,
+        output_inks: outputs,
+        bit_depth,
+        _phantom: PhantomData,
+    })
+}
+
+/// Device link `lut` evaluated from `src_layout` straight into device values of the link output
+pub(crate) fn katana_device_link_nxm<
+    T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync + 'static,
+>(
+    src_layout: Layout,
+    lut: &LutWarehouse,
+    bit_depth: usize,
+) -> Result<Box<dyn KatanaInitialStage<f32, T> + Send + Sync>, CmsError> {
+    Ok(match lut {
+        LutWarehouse::Lut(lut) => Box::new(make_link_lut::<T>(lut, src_layout, bit_depth)?),
+        LutWarehouse::Multidimensional(mab) => {
+            Box::new(make_link_mab::<T>(mab, src_layout, bit_depth)?)
+        }
+        LutWarehouse::MultiProcess(mpe) => Box::new(KatanaLinkMpe::<T> {
+            mpe: mpe.clone(),
+            layout: src_layout,
+            bit_depth,
+            _phantom: PhantomData,
+        }),
+    })
+}
+
+/// Writes `inks` device values per pixel into `dst_layout`
+pub(crate) fn katana_device_output<
+    T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync + 'static,
+>(
+    dst_layout: Layout,
+    inks: usize,
+    bit_depth: usize,
+) -> Box<dyn KatanaFinalStage<f32, T> + Send + Sync>
+where
+    f32: AsPrimitive<T>,
+{
+    Box::new(KatanaDeviceOutput::<T> {
+        layout: dst_layout,
+        inks,
+        bit_depth,
+        _phantom: PhantomData,
+    })
+}
@@ -28,12 +28,15 @@
  */
 use crate::conversions::katana::md_nx3::interpolate_out_function;
 use crate::conversions::katana::{KatanaFinalStage, KatanaInitialStage};
-use crate::conversions::md_lut::{MultidimensionalLut, tetra_3i_to_any_vec};
+use crate::conversions::lut3x3::apply_compat_matrix;
+use crate::conversions::md_lut::{MultidimensionalLut, tetra_3i_to_any_handler};
+use crate::err::try_resize_vec;
 use crate::profile::LutDataType;
 use crate::safe_math::{SafeMul, SafePowi};
 use crate::trc::lut_interp_linear_float;
 use crate::{
-    CmsError, DataColorSpace, Layout, MalformedSize, PointeeSizeExpressible, TransformOptions,
+    CmsError, DataColorSpace, Layout, MalformedSize, Matrix3f, PointeeSizeExpressible,
+    TransformOptions,
 };
 use num_traits::AsPrimitive;
 use std::array::from_fn;
@@ -56,6 +59,7 @@ struct KatanaLut3xN<T> {
     grid_size: u8,
     output_inks: usize,
     output: Vec<Vec<f32>>,
+    matrix: Option<Matrix3f>,
     dst_layout: Layout,
     target_color_space: DataColorSpace,
     _phantom: PhantomData<T>,
@@ -63,7 +67,7 @@ struct KatanaLut3xN<T> {
 }
 
 impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaLutNx3<T> {
-    fn to_pcs_impl(&self, input: &[T]) -> Result<Vec<f32>, CmsError> {
+    fn to_pcs_impl(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
         if input.len() % self.input_inks != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
@@ -85,11 +89,12 @@ impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaLutNx3<T> {
 
         let layout = Layout::from_inks(self.input_inks);
 
-        let mut inks = vec![0.; self.input_inks];
+        let mut inks = [0f32; 16];
+        let inks = &mut inks[..self.input_inks];
 
-        let mut dst = vec![0.; (input.len() / layout.channels()) * 3];
+        try_resize_vec(dst, (input.len() / layout.channels()) * 3, 0.)?;
 
-        let fetcher = interpolate_out_function(layout);
+        let fetcher = interpolate_out_function::<3>(layout);
 
         for (dest, src) in dst
             .chunks_exact_mut(3)
@@ -99,7 +104,7 @@ impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaLutNx3<T> {
                 *ink = lut_interp_linear_float(src_ink.as_() * norm_value, curve);
             }
 
-            let clut = fetcher(&md_lut, &self.clut, &inks);
+            let clut = fetcher(&md_lut, &self.clut, inks);
 
             let pcs_x = lut_interp_linear_float(clut.v[0], &self.output[0]);
             let pcs_y = lut_interp_linear_float(clut.v[1], &self.output[1]);
@@ -109,19 +114,19 @@ impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaLutNx3<T> {
             dest[1] = pcs_y;
             dest[2] = pcs_z;
         }
-        Ok(dst)
+        Ok(())
     }
 }
 
 impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaInitialStage<f32, T>
     for KatanaLutNx3<T>
 {
-    fn to_pcs(&self, input: &[T]) -> Result<Vec<f32>, CmsError> {
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
         if input.len() % self.input_inks != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
 
-        self.to_pcs_impl(input)
+        self.to_pcs_impl(input, dst)
     }
 }
 
@@ -144,6 +149,7 @@ where
         });
 
         let md_lut = MultidimensionalLut::new(grid_sizes, 3, self.output_inks);
+        let tetra = tetra_3i_to_any_handler(self.output_inks);
 
         let scale_value = if T::FINITE {
             ((1u32 << self.bit_depth) - 1) as f32
@@ -151,17 +157,19 @@ where
             1.0
         };
 
-        let mut working = vec![0.; self.output_inks];
+        let mut working = [0f32; 16];
+        let working = &mut working[..self.output_inks];
 
         for (dest, src) in dst
             .chunks_exact_mut(self.dst_layout.channels())
             .zip(src.chunks_exact(3))
         {
+            let src = apply_compat_matrix(&self.matrix, [src[0], src[1], src[2]]);
             let x = lut_interp_linear_float(src[0], &self.linearization[0]);
             let y = lut_interp_linear_float(src[1], &self.linearization[1]);
             let z = lut_interp_linear_float(src[2], &self.linearization[2]);
 
-            tetra_3i_to_any_vec(&md_lut, &self.clut, x, y, z, &mut working, self.output_inks);
+            tetra(&md_lut, &self.clut, x, y, z, working, self.output_inks);
 
             for (ink, curve) in working.iter_mut().zip(self.output.iter()) {
                 *ink = lut_interp_linear_float(*ink, curve);
@@ -263,7 +271,7 @@ fn katana_make_lut_3xn<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>>(
     inks: usize,
     dst_layout: Layout,
     lut: &LutDataType,
-    _: TransformOptions,
+    options: TransformOptions,
     target_color_space: DataColorSpace,
     bit_depth: usize,
 ) -> Result<KatanaLut3xN<T>, CmsError> {
@@ -331,6 +339,7 @@ fn katana_make_lut_3xn<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>>(
         clut: clut_table,
         grid_size: lut.num_clut_grid_points,
         output: gamma,
+        matrix: lut.compat_input_matrix(options),
         output_inks: inks,
         _phantom: PhantomData,
         target_color_space,
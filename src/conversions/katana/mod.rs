@@ -31,9 +31,12 @@ mod md3x3;
 mod md4x3;
 mod md_3xn;
 mod md_nx3;
+mod md_nxm;
 mod md_pipeline;
+mod pcs_output;
 mod pcs_stages;
 mod rgb_xyz;
+mod spectral;
 mod stages;
 mod xyz_lab;
 mod xyz_rgb;
@@ -41,16 +44,26 @@ mod xyz_rgb;
 pub(crate) use finalizers::{CopyAlphaStage, InjectAlphaStage};
 pub(crate) use md_3xn::katana_multi_dimensional_3xn_to_device;
 pub(crate) use md_nx3::katana_multi_dimensional_nx3_to_pcs;
+pub(crate) use md_nxm::{katana_device_link_nxm, katana_device_output};
 pub(crate) use md_pipeline::{katana_input_make_lut_nx3, katana_output_make_lut_3xn};
 pub(crate) use md3x3::{multi_dimensional_3x3_to_device, multi_dimensional_3x3_to_pcs};
 pub(crate) use md4x3::multi_dimensional_4x3_to_pcs;
+pub(crate) use pcs_output::{katana_gray_trc_to_pcs, katana_pcs_output};
 pub(crate) use pcs_stages::{
     KatanaDefaultIntermediate, katana_pcs_lab_v2_to_v4, katana_pcs_lab_v4_to_v2,
+    katana_pcs_luminance_scale,
 };
 pub(crate) use rgb_xyz::katana_create_rgb_lin_lut;
+pub(crate) use spectral::{
+    katana_float_device_to_pcs, katana_float_pcs_to_device, katana_spectral_device_to_pcs,
+    katana_spectral_pcs_to_device,
+};
 pub(crate) use stages::{
     Katana, KatanaFinalStage, KatanaInitialStage, KatanaIntermediateStage,
     KatanaPostFinalizationStage,
 };
-pub(crate) use xyz_lab::{KatanaStageLabToXyz, KatanaStageXyzToLab};
+pub(crate) use xyz_lab::{
+    KatanaStageLabToXyz, KatanaStageLuminanceScale, KatanaStagePcsMatrix,
+    KatanaStageSurroundCompensation, KatanaStageXyzToLab,
+};
 pub(crate) use xyz_rgb::katana_prepare_inverse_lut_rgb_xyz;
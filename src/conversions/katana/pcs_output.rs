@@ -0,0 +1,117 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::katana::{
+    KatanaFinalStage, KatanaInitialStage, katana_spectral_device_to_pcs,
+    katana_spectral_pcs_to_device,
+};
+use crate::lab::xyz_to_lab;
+use crate::{
+    Chromaticity, CmsError, ColorProfile, Layout, PointeeSizeExpressible, Stage, ToneCurveEvaluator,
+};
+use num_traits::AsPrimitive;
+
+/// Gray TRC to plain CIE XYZ, gray is luminance of PCS illuminant
+struct GrayTrcStage {
+    evaluator: Box<dyn ToneCurveEvaluator + Send + Sync>,
+    channels: usize,
+}
+
+impl Stage for GrayTrcStage {
+    fn transform(&self, src: &[f32], dst: &mut [f32]) -> Result<(), CmsError> {
+        let wp = Chromaticity::D50.to_xyz();
+        for (src, dst) in src.chunks_exact(self.channels).zip(dst.chunks_exact_mut(3)) {
+            let y = self.evaluator.evaluate_value(src[0]);
+            dst[0] = y * wp.x;
+            dst[1] = y * wp.y;
+            dst[2] = y * wp.z;
+        }
+        Ok(())
+    }
+}
+
+/// Plain CIE XYZ to [Layout::Xyz] or [Layout::Lab] values
+struct PcsOutputStage {
+    layout: Layout,
+}
+
+impl Stage for PcsOutputStage {
+    fn transform(&self, src: &[f32], dst: &mut [f32]) -> Result<(), CmsError> {
+        for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
+            if self.layout == Layout::Lab {
+                let lab = xyz_to_lab([src[0] as f64, src[1] as f64, src[2] as f64]);
+                dst[0] = lab[0] as f32;
+                dst[1] = lab[1] as f32;
+                dst[2] = lab[2] as f32;
+            } else {
+                dst.copy_from_slice(src);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Device to PCS stage for gray profiles that have only `kTRC`
+pub(crate) fn katana_gray_trc_to_pcs<
+    T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync,
+>(
+    source: &ColorProfile,
+    layout: Layout,
+    bit_depth: usize,
+) -> Result<Box<dyn KatanaInitialStage<f32, T> + Send + Sync>, CmsError> {
+    if layout != Layout::Gray && layout != Layout::GrayAlpha {
+        return Err(CmsError::InvalidLayout);
+    }
+    let evaluator = source
+        .gray_trc
+        .as_ref()
+        .ok_or(CmsError::UnsupportedProfileConnection)?
+        .make_linear_evaluator()?;
+    let stage = GrayTrcStage {
+        evaluator,
+        channels: layout.channels(),
+    };
+    Ok(katana_spectral_device_to_pcs::<T>(
+        Box::new(stage),
+        layout,
+        bit_depth,
+    ))
+}
+
+/// PCS to [Layout::Xyz] or [Layout::Lab] output stage
+pub(crate) fn katana_pcs_output<
+    T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync,
+>(
+    layout: Layout,
+    bit_depth: usize,
+) -> Box<dyn KatanaFinalStage<f32, T> + Send + Sync>
+where
+    f32: AsPrimitive<T>,
+{
+    katana_spectral_pcs_to_device::<T>(Box::new(PcsOutputStage { layout }), layout, bit_depth)
+}
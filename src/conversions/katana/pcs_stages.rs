@@ -26,10 +26,14 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+use crate::conversions::bpc::{BlackPointCompensation, StageBlackPointCompensation};
 use crate::conversions::katana::KatanaIntermediateStage;
 use crate::conversions::katana::stages::BlackholeIntermediateStage;
+use crate::conversions::katana::{
+    KatanaStageLuminanceScale, KatanaStagePcsMatrix, KatanaStageSurroundCompensation,
+};
 use crate::mlaf::mlaf;
-use crate::{CmsError, ColorProfile, DataColorSpace, Matrix3f, ProfileVersion};
+use crate::{CmsError, ColorProfile, DataColorSpace, Matrix3f, ProfileVersion, TransformOptions};
 use std::marker::PhantomData;
 
 pub(crate) struct KatanaMatrixStage {
@@ -98,3 +102,38 @@ pub(crate) fn katana_pcs_lab_v2_to_v4(profile: &ColorProfile) -> Box<KatanaDefau
         _phantom: PhantomData,
     })
 }
+
+/// Luminance scale, black point compensation, viewing adaptation and surround compensation,
+/// all in source PCS
+pub(crate) fn katana_pcs_luminance_scale(
+    source: &ColorProfile,
+    dest: &ColorProfile,
+    options: TransformOptions,
+) -> Vec<Box<KatanaDefaultIntermediate>> {
+    let mut stages: Vec<Box<KatanaDefaultIntermediate>> = Vec::new();
+    if let Some(factor) = options.luminance_scale(source, dest) {
+        stages.push(Box::new(KatanaStageLuminanceScale {
+            factor: factor as f32,
+            pcs: source.pcs,
+        }));
+    }
+    if let Some(bpc) = BlackPointCompensation::new(source, dest, options) {
+        stages.push(Box::new(StageBlackPointCompensation {
+            bpc,
+            pcs: source.pcs,
+        }));
+    }
+    if let Some(matrix) = options.viewing_adaptation.and_then(|v| v.matrix()) {
+        stages.push(Box::new(KatanaStagePcsMatrix {
+            matrix: matrix.to_f32(),
+            pcs: source.pcs,
+        }));
+    }
+    if let Some(gamma) = options.surround_compensation.and_then(|v| v.gamma()) {
+        stages.push(Box::new(KatanaStageSurroundCompensation {
+            gamma: gamma as f32,
+            pcs: source.pcs,
+        }));
+    }
+    stages
+}
@@ -28,7 +28,7 @@
  */
 use crate::conversions::katana::pcs_stages::KatanaMatrixStage;
 use crate::conversions::katana::{KatanaInitialStage, KatanaIntermediateStage};
-use crate::err::try_vec;
+use crate::err::try_resize_vec;
 use crate::{CmsError, ColorProfile, Layout, Matrix3f, PointeeSizeExpressible, TransformOptions};
 use num_traits::AsPrimitive;
 use std::marker::PhantomData;
@@ -48,12 +48,12 @@ impl<
     const LINEAR_CAP: usize,
 > KatanaInitialStage<f32, T> for KatanaRgbLinearizationStage<T, LAYOUT, LINEAR_CAP>
 {
-    fn to_pcs(&self, input: &[T]) -> Result<Vec<f32>, CmsError> {
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
         let src_layout = Layout::from(LAYOUT);
         if input.len() % src_layout.channels() != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
-        let mut dst = try_vec![0.; input.len() / src_layout.channels() * 3];
+        try_resize_vec(dst, input.len() / src_layout.channels() * 3, 0.)?;
 
         let scale = if T::FINITE {
             (self.linear_cap as f32 - 1.) / ((1 << self.bit_depth) - 1) as f32
@@ -78,7 +78,7 @@ impl<
             dst[1] = self.g_lin[(j_g.round().min(cap_value).max(0.) as u16) as usize];
             dst[2] = self.b_lin[(j_b.round().min(cap_value).max(0.) as u16) as usize];
         }
-        Ok(dst)
+        Ok(())
     }
 }
 
@@ -0,0 +1,213 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::katana::{KatanaFinalStage, KatanaInitialStage};
+use crate::err::try_resize_vec;
+use crate::{CmsError, Layout, PointeeSizeExpressible, Stage};
+use num_traits::AsPrimitive;
+use std::marker::PhantomData;
+
+// Handlers work with plain CIE XYZ, LUT pipeline keeps XYZ PCS in ICC encoding
+const XYZ_TO_PCS: f32 = 32768.0 / 65535.0;
+const PCS_TO_XYZ: f32 = 65535.0 / 32768.0;
+
+// Handler stages are fed by chunks to keep device values on the stack
+const CHUNK_PIXELS: usize = 128;
+
+/// Device to PCS stage backed by user provided [crate::SpectralPcsHandler]
+/// or by floating point `mpet` evaluator
+struct KatanaSpectralInitialStage<T> {
+    stage: Box<dyn Stage + Send + Sync>,
+    layout: Layout,
+    bit_depth: usize,
+    pcs_scale: f32,
+    _phantom: PhantomData<T>,
+}
+
+/// PCS to device stage backed by user provided [crate::SpectralPcsHandler]
+/// or by floating point `mpet` evaluator
+struct KatanaSpectralFinalStage<T> {
+    stage: Box<dyn Stage + Send + Sync>,
+    layout: Layout,
+    bit_depth: usize,
+    pcs_scale: f32,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync>
+    KatanaInitialStage<f32, T> for KatanaSpectralInitialStage<T>
+{
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
+        let channels = self.layout.channels();
+        if input.len() % channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        let norm_value = if T::FINITE {
+            1.0 / ((1u32 << self.bit_depth) - 1) as f32
+        } else {
+            1.0
+        };
+        try_resize_vec(dst, input.len() / channels * 3, 0.)?;
+        let mut normalized = [0f32; CHUNK_PIXELS * 16];
+        for (src, dst) in input
+            .chunks(CHUNK_PIXELS * channels)
+            .zip(dst.chunks_mut(CHUNK_PIXELS * 3))
+        {
+            let normalized = &mut normalized[..src.len()];
+            for (dst, &src) in normalized.iter_mut().zip(src.iter()) {
+                *dst = src.as_() * norm_value;
+            }
+            self.stage.transform(normalized, dst)?;
+        }
+        if self.pcs_scale != 1. {
+            for v in dst.iter_mut() {
+                *v *= self.pcs_scale;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync>
+    KatanaFinalStage<f32, T> for KatanaSpectralFinalStage<T>
+where
+    f32: AsPrimitive<T>,
+{
+    fn to_output(&self, src: &mut [f32], dst: &mut [T]) -> Result<(), CmsError> {
+        let channels = self.layout.channels();
+        if src.len() % 3 != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if dst.len() % channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / 3 != dst.len() / channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        if self.pcs_scale != 1. {
+            for v in src.iter_mut() {
+                *v *= self.pcs_scale;
+            }
+        }
+        let norm_value = if T::FINITE {
+            ((1u32 << self.bit_depth) - 1) as f32
+        } else {
+            1.0
+        };
+        let mut device = [0f32; CHUNK_PIXELS * 16];
+        for (src, dst) in src
+            .chunks(CHUNK_PIXELS * 3)
+            .zip(dst.chunks_mut(CHUNK_PIXELS * channels))
+        {
+            let device = &mut device[..dst.len()];
+            self.stage.transform(src, device)?;
+            if T::FINITE {
+                for (dst, src) in dst.iter_mut().zip(device.iter()) {
+                    *dst = (*src * norm_value).round().max(0.).min(norm_value).as_();
+                }
+            } else {
+                for (dst, src) in dst.iter_mut().zip(device.iter()) {
+                    *dst = (*src * norm_value).as_();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn katana_spectral_device_to_pcs<
+    T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync,
+>(
+    stage: Box<dyn Stage + Send + Sync>,
+    layout: Layout,
+    bit_depth: usize,
+) -> Box<dyn KatanaInitialStage<f32, T> + Send + Sync> {
+    Box::new(KatanaSpectralInitialStage::<T> {
+        stage,
+        layout,
+        bit_depth,
+        pcs_scale: XYZ_TO_PCS,
+        _phantom: PhantomData,
+    })
+}
+
+/// Device to PCS stage for `stage` that already produces PCS in LUT encoding
+pub(crate) fn katana_float_device_to_pcs<
+    T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync,
+>(
+    stage: Box<dyn Stage + Send + Sync>,
+    layout: Layout,
+    bit_depth: usize,
+) -> Box<dyn KatanaInitialStage<f32, T> + Send + Sync> {
+    Box::new(KatanaSpectralInitialStage::<T> {
+        stage,
+        layout,
+        bit_depth,
+        pcs_scale: 1.,
+        _phantom: PhantomData,
+    })
+}
+
+pub(crate) fn katana_spectral_pcs_to_device<
+    T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync,
+>(
+    stage: Box<dyn Stage + Send + Sync>,
+    layout: Layout,
+    bit_depth: usize,
+) -> Box<dyn KatanaFinalStage<f32, T> + Send + Sync>
+where
+    f32: AsPrimitive<T>,
+{
+    Box::new(KatanaSpectralFinalStage::<T> {
+        stage,
+        layout,
+        bit_depth,
+        pcs_scale: PCS_TO_XYZ,
+        _phantom: PhantomData,
+    })
+}
+
+/// PCS to device stage for `stage` that consumes PCS in LUT encoding
+pub(crate) fn katana_float_pcs_to_device<
+    T: Copy + Default + AsPrimitive<f32> + PointeeSizeExpressible + Send + Sync,
+>(
+    stage: Box<dyn Stage + Send + Sync>,
+    layout: Layout,
+    bit_depth: usize,
+) -> Box<dyn KatanaFinalStage<f32, T> + Send + Sync>
+where
+    f32: AsPrimitive<T>,
+{
+    Box::new(KatanaSpectralFinalStage::<T> {
+        stage,
+        layout,
+        bit_depth,
+        pcs_scale: 1.,
+        _phantom: PhantomData,
+    })
+}
@@ -26,13 +26,15 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::{CmsError, TransformExecutor};
+use crate::{CmsError, Layout, TransformExecutor, TransformScratch};
+use std::cell::RefCell;
 use std::marker::PhantomData;
 
 /// W storage working data type
 /// I input/output data type
 pub(crate) trait KatanaInitialStage<W, I> {
-    fn to_pcs(&self, input: &[I]) -> Result<Vec<W>, CmsError>;
+    /// Writes PCS into `dst`, resizing it as needed, capacity is reused between calls.
+    fn to_pcs(&self, input: &[I], dst: &mut Vec<W>) -> Result<(), CmsError>;
 }
 
 /// W storage working data type
@@ -68,18 +70,195 @@ pub(crate) struct Katana<W, I> {
     pub(crate) final_stage: Box<dyn KatanaFinalStage<W, I> + Sync + Send>,
     pub(crate) stages: Vec<Box<dyn KatanaIntermediateStage<W> + Send + Sync>>,
     pub(crate) post_finalization: Vec<Box<dyn KatanaPostFinalizationStage<I> + Send + Sync>>,
+    pub(crate) src_layout: Layout,
+    pub(crate) dst_layout: Layout,
+    /// Pixels processed at once, whole row if `None`
+    pub(crate) chunk_pixels: Option<usize>,
 }
 
-impl<W, I: Copy + Default> TransformExecutor<I> for Katana<W, I> {
-    fn transform(&self, src: &[I], dst: &mut [I]) -> Result<(), CmsError> {
-        let mut working_vec = self.initial_stage.to_pcs(src)?;
+// Thread local working buffer is dropped if it grows above this, 4 MB
+const KATANA_SCRATCH_RETAIN: usize = 1024 * 1024;
+
+thread_local! {
+    static KATANA_SCRATCH: RefCell<Vec<f32>> = const { RefCell::new(Vec::new()) };
+}
+
+impl<W, I> Katana<W, I> {
+    fn execute_chunk(
+        &self,
+        src: &[I],
+        dst: &mut [I],
+        working_vec: &mut Vec<W>,
+    ) -> Result<(), CmsError> {
+        self.initial_stage.to_pcs(src, working_vec)?;
+        for stage in self.stages.iter() {
+            *working_vec = stage.stage(working_vec)?;
+        }
+        self.final_stage.to_output(working_vec, dst)?;
+        for finalization in self.post_finalization.iter() {
+            finalization.finalize(src, dst)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "bench")]
+    fn execute_chunk_timed(
+        &self,
+        src: &[I],
+        dst: &mut [I],
+        working_vec: &mut Vec<W>,
+        timings: &mut crate::StageTimings,
+    ) -> Result<(), CmsError> {
+        use std::time::Instant;
+        let start = Instant::now();
+        self.initial_stage.to_pcs(src, working_vec)?;
+        let to_pcs = Instant::now();
         for stage in self.stages.iter() {
-            working_vec = stage.stage(&mut working_vec)?;
+            *working_vec = stage.stage(working_vec)?;
         }
-        self.final_stage.to_output(&mut working_vec, dst)?;
+        let pcs = Instant::now();
+        self.final_stage.to_output(working_vec, dst)?;
+        let to_device = Instant::now();
         for finalization in self.post_finalization.iter() {
             finalization.finalize(src, dst)?;
         }
+        timings.to_pcs += to_pcs - start;
+        timings.pcs += pcs - to_pcs;
+        timings.to_device += to_device - pcs;
+        timings.finalization += to_device.elapsed();
         Ok(())
     }
+
+    fn execute(&self, src: &[I], dst: &mut [I], working_vec: &mut Vec<W>) -> Result<(), CmsError> {
+        let Some(chunk_pixels) = self.chunk_pixels else {
+            return self.execute_chunk(src, dst, working_vec);
+        };
+        let src_channels = self.src_layout.channels();
+        let dst_channels = self.dst_layout.channels();
+        if src.len() % src_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if dst.len() % dst_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / src_channels != dst.len() / dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        for (src, dst) in src
+            .chunks(chunk_pixels * src_channels)
+            .zip(dst.chunks_mut(chunk_pixels * dst_channels))
+        {
+            self.execute_chunk(src, dst, working_vec)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I: Copy + Default> TransformExecutor<I> for Katana<f32, I> {
+    fn transform(&self, src: &[I], dst: &mut [I]) -> Result<(), CmsError> {
+        KATANA_SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+            Ok(mut working_vec) => {
+                let result = self.execute(src, dst, &mut working_vec);
+                if working_vec.capacity() > KATANA_SCRATCH_RETAIN {
+                    *working_vec = Vec::new();
+                }
+                result
+            }
+            // Re-entrant call from a user provided stage
+            Err(_) => self.execute(src, dst, &mut Vec::new()),
+        })
+    }
+
+    fn transform_with_scratch(
+        &self,
+        src: &[I],
+        dst: &mut [I],
+        scratch: &mut TransformScratch,
+    ) -> Result<(), CmsError> {
+        self.execute(src, dst, &mut scratch.buffer)
+    }
+
+    #[cfg(feature = "bench")]
+    fn transform_timed(&self, src: &[I], dst: &mut [I]) -> Result<crate::StageTimings, CmsError> {
+        let start = std::time::Instant::now();
+        let mut timings = crate::StageTimings {
+            staged: true,
+            ..Default::default()
+        };
+        let mut working_vec = Vec::new();
+        match self.chunk_pixels {
+            None => self.execute_chunk_timed(src, dst, &mut working_vec, &mut timings)?,
+            Some(chunk_pixels) => {
+                let src_channels = self.src_layout.channels();
+                let dst_channels = self.dst_layout.channels();
+                if src.len() % src_channels != 0 || dst.len() % dst_channels != 0 {
+                    return Err(CmsError::LaneMultipleOfChannels);
+                }
+                if src.len() / src_channels != dst.len() / dst_channels {
+                    return Err(CmsError::LaneSizeMismatch);
+                }
+                for (src, dst) in src
+                    .chunks(chunk_pixels * src_channels)
+                    .zip(dst.chunks_mut(chunk_pixels * dst_channels))
+                {
+                    self.execute_chunk_timed(src, dst, &mut working_vec, &mut timings)?;
+                }
+            }
+        }
+        timings.total = start.elapsed();
+        Ok(timings)
+    }
+}
+
+#[cfg(all(test, feature = "bench"))]
+mod tests {
+    use super::*;
+
+    struct Widen;
+
+    impl KatanaInitialStage<f32, u8> for Widen {
+        fn to_pcs(&self, input: &[u8], dst: &mut Vec<f32>) -> Result<(), CmsError> {
+            dst.clear();
+            dst.extend(input.iter().map(|&x| x as f32));
+            Ok(())
+        }
+    }
+
+    struct Invert;
+
+    impl KatanaIntermediateStage<f32> for Invert {
+        fn stage(&self, input: &mut Vec<f32>) -> Result<Vec<f32>, CmsError> {
+            Ok(input.iter().map(|&x| 255. - x).collect())
+        }
+    }
+
+    struct Narrow;
+
+    impl KatanaFinalStage<f32, u8> for Narrow {
+        fn to_output(&self, src: &mut [f32], dst: &mut [u8]) -> Result<(), CmsError> {
+            for (dst, &src) in dst.iter_mut().zip(src.iter()) {
+                *dst = src as u8;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_katana_stage_timings() {
+        let katana = Katana::<f32, u8> {
+            initial_stage: Box::new(Widen),
+            final_stage: Box::new(Narrow),
+            stages: vec![Box::new(Invert)],
+            post_finalization: Vec::new(),
+            src_layout: Layout::Rgb,
+            dst_layout: Layout::Rgb,
+            chunk_pixels: Some(7),
+        };
+        let src = (0..300).map(|x| x as u8).collect::<Vec<_>>();
+        let mut dst = vec![0u8; src.len()];
+        let timings = katana.transform_timed(&src, &mut dst).unwrap();
+        assert!(timings.staged);
+        assert!(timings.total >= timings.to_pcs + timings.pcs + timings.to_device);
+        assert!(dst.iter().zip(src.iter()).all(|(&d, &s)| d == 255 - s));
+    }
 }
@@ -27,7 +27,10 @@
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::conversions::katana::KatanaIntermediateStage;
-use crate::{CmsError, Lab, Xyz};
+use crate::conversions::xyz_lab::{
+    compensate_pcs_surround, scale_pcs_luminance, transform_pcs_matrix,
+};
+use crate::{CmsError, DataColorSpace, Lab, Matrix3f, Xyz};
 
 #[derive(Default)]
 pub(crate) struct KatanaStageLabToXyz {}
@@ -60,3 +63,39 @@ impl KatanaIntermediateStage<f32> for KatanaStageXyzToLab {
         Ok(std::mem::take(input))
     }
 }
+
+pub(crate) struct KatanaStageLuminanceScale {
+    pub(crate) factor: f32,
+    pub(crate) pcs: DataColorSpace,
+}
+
+impl KatanaIntermediateStage<f32> for KatanaStageLuminanceScale {
+    fn stage(&self, input: &mut Vec<f32>) -> Result<Vec<f32>, CmsError> {
+        scale_pcs_luminance(input, self.factor, self.pcs);
+        Ok(std::mem::take(input))
+    }
+}
+
+pub(crate) struct KatanaStagePcsMatrix {
+    pub(crate) matrix: Matrix3f,
+    pub(crate) pcs: DataColorSpace,
+}
+
+impl KatanaIntermediateStage<f32> for KatanaStagePcsMatrix {
+    fn stage(&self, input: &mut Vec<f32>) -> Result<Vec<f32>, CmsError> {
+        transform_pcs_matrix(input, self.matrix, self.pcs);
+        Ok(std::mem::take(input))
+    }
+}
+
+pub(crate) struct KatanaStageSurroundCompensation {
+    pub(crate) gamma: f32,
+    pub(crate) pcs: DataColorSpace,
+}
+
+impl KatanaIntermediateStage<f32> for KatanaStageSurroundCompensation {
+    fn stage(&self, input: &mut Vec<f32>) -> Result<Vec<f32>, CmsError> {
+        compensate_pcs_surround(input, self.gamma, self.pcs);
+        Ok(std::mem::take(input))
+    }
+}
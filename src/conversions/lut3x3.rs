@@ -27,16 +27,25 @@
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::conversions::katana::{KatanaFinalStage, KatanaInitialStage};
-use crate::err::{MalformedSize, try_vec};
+use crate::err::{MalformedSize, try_resize_vec, try_vec};
 use crate::profile::LutDataType;
 use crate::safe_math::{SafeMul, SafePowi};
 use crate::trc::lut_interp_linear_float;
 use crate::{
-    CmsError, Cube, DataColorSpace, InterpolationMethod, PointeeSizeExpressible, Stage,
+    CmsError, Cube, DataColorSpace, InterpolationMethod, Matrix3f, PointeeSizeExpressible, Stage,
     TransformOptions, Vector3f,
 };
 use num_traits::AsPrimitive;
 
+/// Applies `lut8`/`lut16` matrix kept for lcms2 compatibility, see [TransformOptions::lcms_lut_matrix].
+#[inline]
+pub(crate) fn apply_compat_matrix(matrix: &Option<Matrix3f>, v: [f32; 3]) -> [f32; 3] {
+    match matrix {
+        Some(matrix) => matrix.mul_vector(Vector3f { v }).v,
+        None => v,
+    }
+}
+
 #[derive(Default)]
 struct Lut3x3 {
     input: [Vec<f32>; 3],
@@ -45,6 +54,7 @@ struct Lut3x3 {
     gamma: [Vec<f32>; 3],
     interpolation_method: InterpolationMethod,
     pcs: DataColorSpace,
+    matrix: Option<Matrix3f>,
 }
 
 #[derive(Default)]
@@ -55,6 +65,7 @@ struct KatanaLut3x3<T: Copy + Default> {
     gamma: [Vec<f32>; 3],
     interpolation_method: InterpolationMethod,
     pcs: DataColorSpace,
+    matrix: Option<Matrix3f>,
     _phantom: std::marker::PhantomData<T>,
     bit_depth: usize,
 }
@@ -117,6 +128,7 @@ fn make_lut_3x3(
         clut: clut_table,
         grid_size: lut.num_clut_grid_points,
         pcs,
+        matrix: lut.compat_input_matrix(options),
     };
 
     Ok(transform)
@@ -136,6 +148,7 @@ fn stage_lut_3x3(
         clut: lut.clut,
         grid_size: lut.grid_size,
         pcs: lut.pcs,
+        matrix: lut.matrix,
     };
 
     Ok(Box::new(transform))
@@ -161,6 +174,7 @@ where
         clut: lut.clut,
         grid_size: lut.grid_size,
         pcs: lut.pcs,
+        matrix: lut.matrix,
         _phantom: std::marker::PhantomData,
         bit_depth,
     };
@@ -188,6 +202,7 @@ where
         clut: lut.clut,
         grid_size: lut.grid_size,
         pcs: lut.pcs,
+        matrix: lut.matrix,
         _phantom: std::marker::PhantomData,
         bit_depth,
     };
@@ -207,6 +222,7 @@ impl Lut3x3 {
         let linearization_2 = &self.input[2];
         for (dest, src) in dst.chunks_exact_mut(3).zip(src.chunks_exact(3)) {
             debug_assert!(self.grid_size as i32 >= 1);
+            let src = apply_compat_matrix(&self.matrix, [src[0], src[1], src[2]]);
             let linear_x = lut_interp_linear_float(src[0], linearization_0);
             let linear_y = lut_interp_linear_float(src[1], linearization_1);
             let linear_z = lut_interp_linear_float(src[2], linearization_2);
@@ -246,7 +262,7 @@ impl Stage for Lut3x3 {
             InterpolationMethod::Prism => {
                 self.transform_impl(src, dst, |x, y, z| l_tbl.prism_vec3(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(src, dst, |x, y, z| l_tbl.trilinear_vec3(x, y, z))?;
             }
         }
@@ -261,8 +277,9 @@ where
     fn to_pcs_impl<Fetch: Fn(f32, f32, f32) -> Vector3f>(
         &self,
         input: &[T],
+        dst: &mut Vec<f32>,
         fetch: Fetch,
-    ) -> Result<Vec<f32>, CmsError> {
+    ) -> Result<(), CmsError> {
         if input.len() % 3 != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
@@ -271,17 +288,22 @@ where
         } else {
             1.0
         };
-        let mut dst = try_vec![0.; input.len()];
+        try_resize_vec(dst, input.len(), 0.)?;
         let linearization_0 = &self.input[0];
         let linearization_1 = &self.input[1];
         let linearization_2 = &self.input[2];
         for (dest, src) in dst.chunks_exact_mut(3).zip(input.chunks_exact(3)) {
-            let linear_x =
-                lut_interp_linear_float(src[0].as_() * normalizing_value, linearization_0);
-            let linear_y =
-                lut_interp_linear_float(src[1].as_() * normalizing_value, linearization_1);
-            let linear_z =
-                lut_interp_linear_float(src[2].as_() * normalizing_value, linearization_2);
+            let src = apply_compat_matrix(
+                &self.matrix,
+                [
+                    src[0].as_() * normalizing_value,
+                    src[1].as_() * normalizing_value,
+                    src[2].as_() * normalizing_value,
+                ],
+            );
+            let linear_x = lut_interp_linear_float(src[0], linearization_0);
+            let linear_y = lut_interp_linear_float(src[1], linearization_1);
+            let linear_z = lut_interp_linear_float(src[2], linearization_2);
 
             let clut = fetch(linear_x, linear_y, linear_z);
 
@@ -292,7 +314,7 @@ where
             dest[1] = pcs_y;
             dest[2] = pcs_z;
         }
-        Ok(dst)
+        Ok(())
     }
 
     fn to_output<Fetch: Fn(f32, f32, f32) -> Vector3f>(
@@ -320,6 +342,7 @@ where
         let linearization_1 = &self.input[1];
         let linearization_2 = &self.input[2];
         for (dest, src) in dst.chunks_exact_mut(3).zip(src.chunks_exact(3)) {
+            let src = apply_compat_matrix(&self.matrix, [src[0], src[1], src[2]]);
             let linear_x = lut_interp_linear_float(src[0], linearization_0);
             let linear_y = lut_interp_linear_float(src[1], linearization_1);
             let linear_z = lut_interp_linear_float(src[2], linearization_2);
@@ -349,29 +372,29 @@ impl<T: Copy + Default + PointeeSizeExpressible + AsPrimitive<f32>> KatanaInitia
 where
     f32: AsPrimitive<T>,
 {
-    fn to_pcs(&self, input: &[T]) -> Result<Vec<f32>, CmsError> {
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
         let l_tbl = Cube::new_checked(&self.clut, self.grid_size as usize, 3)?;
 
         // If PCS is LAB then linear interpolation should be used
         if self.pcs == DataColorSpace::Lab || self.pcs == DataColorSpace::Xyz {
-            return self.to_pcs_impl(input, |x, y, z| l_tbl.trilinear_vec3(x, y, z));
+            return self.to_pcs_impl(input, dst, |x, y, z| l_tbl.trilinear_vec3(x, y, z));
         }
 
         match self.interpolation_method {
             #[cfg(feature = "options")]
             InterpolationMethod::Tetrahedral => {
-                self.to_pcs_impl(input, |x, y, z| l_tbl.tetra_vec3(x, y, z))
+                self.to_pcs_impl(input, dst, |x, y, z| l_tbl.tetra_vec3(x, y, z))
             }
             #[cfg(feature = "options")]
             InterpolationMethod::Pyramid => {
-                self.to_pcs_impl(input, |x, y, z| l_tbl.pyramid_vec3(x, y, z))
+                self.to_pcs_impl(input, dst, |x, y, z| l_tbl.pyramid_vec3(x, y, z))
             }
             #[cfg(feature = "options")]
             InterpolationMethod::Prism => {
-                self.to_pcs_impl(input, |x, y, z| l_tbl.prism_vec3(x, y, z))
+                self.to_pcs_impl(input, dst, |x, y, z| l_tbl.prism_vec3(x, y, z))
             }
-            InterpolationMethod::Linear => {
-                self.to_pcs_impl(input, |x, y, z| l_tbl.trilinear_vec3(x, y, z))
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
+                self.to_pcs_impl(input, dst, |x, y, z| l_tbl.trilinear_vec3(x, y, z))
             }
         }
     }
@@ -403,7 +426,7 @@ where
             InterpolationMethod::Prism => {
                 self.to_output(src, dst, |x, y, z| l_tbl.prism_vec3(x, y, z))
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.to_output(src, dst, |x, y, z| l_tbl.trilinear_vec3(x, y, z))
             }
         }
@@ -26,13 +26,14 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+use crate::conversions::lut3x3::apply_compat_matrix;
 use crate::err::try_vec;
 use crate::profile::LutDataType;
 use crate::safe_math::{SafeMul, SafePowi};
 use crate::trc::lut_interp_linear_float;
 use crate::{
-    CmsError, Cube, DataColorSpace, InterpolationMethod, MalformedSize, Stage, TransformOptions,
-    Vector4f,
+    CmsError, Cube, DataColorSpace, InterpolationMethod, MalformedSize, Matrix3f, Stage,
+    TransformOptions, Vector4f,
 };
 use num_traits::AsPrimitive;
 
@@ -44,6 +45,7 @@ struct Lut3x4 {
     gamma: [Vec<f32>; 4],
     interpolation_method: InterpolationMethod,
     pcs: DataColorSpace,
+    matrix: Option<Matrix3f>,
 }
 
 fn make_lut_3x4(
@@ -106,6 +108,7 @@ fn make_lut_3x4(
         clut: clut_table,
         grid_size: lut.num_clut_grid_points,
         pcs,
+        matrix: lut.compat_input_matrix(options),
         gamma: [gamma_curve0, gamma_curve1, gamma_curve2, gamma_curve3],
     };
     Ok(transform)
@@ -124,6 +127,7 @@ fn stage_lut_3x4(
         clut: lut.clut,
         grid_size: lut.grid_size,
         pcs: lut.pcs,
+        matrix: lut.matrix,
         gamma: lut.gamma,
     };
     Ok(Box::new(transform))
@@ -141,6 +145,7 @@ impl Lut3x4 {
         let linearization_2 = &self.input[2];
         for (dest, src) in dst.chunks_exact_mut(4).zip(src.chunks_exact(3)) {
             debug_assert!(self.grid_size as i32 >= 1);
+            let src = apply_compat_matrix(&self.matrix, [src[0], src[1], src[2]]);
             let linear_x = lut_interp_linear_float(src[0], linearization_0);
             let linear_y = lut_interp_linear_float(src[1], linearization_1);
             let linear_z = lut_interp_linear_float(src[2], linearization_2);
@@ -182,7 +187,7 @@ impl Stage for Lut3x4 {
             InterpolationMethod::Prism => {
                 self.transform_impl(src, dst, |x, y, z| l_tbl.prism_vec4(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(src, dst, |x, y, z| l_tbl.trilinear_vec4(x, y, z))?;
             }
         }
@@ -27,7 +27,7 @@
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::conversions::katana::KatanaInitialStage;
-use crate::err::try_vec;
+use crate::err::{try_resize_vec, try_vec};
 use crate::profile::LutDataType;
 use crate::safe_math::{SafeMul, SafePowi};
 use crate::trc::lut_interp_linear_float;
@@ -119,11 +119,8 @@ macro_rules! define_lut4_dispatch {
                     InterpolationMethod::Prism => {
                         self.transform_impl(src, dst, |x, y, z, w| l_tbl.prism_vec3(x, y, z, w))?
                     }
-                    InterpolationMethod::Linear => {
-                        self.transform_impl(src, dst, |x, y, z, w| {
-                            l_tbl.quadlinear_vec3(x, y, z, w)
-                        })?
-                    }
+                    InterpolationMethod::Linear | InterpolationMethod::Auto => self
+                        .transform_impl(src, dst, |x, y, z, w| l_tbl.quadlinear_vec3(x, y, z, w))?,
                 }
                 Ok(())
             }
@@ -135,8 +132,9 @@ impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaLut4x3<T> {
     fn to_pcs_impl<Fetch: Fn(f32, f32, f32, f32) -> Vector3f>(
         &self,
         input: &[T],
+        dst: &mut Vec<f32>,
         fetch: Fetch,
-    ) -> Result<Vec<f32>, CmsError> {
+    ) -> Result<(), CmsError> {
         if input.len() % 4 != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
@@ -145,7 +143,7 @@ impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaLut4x3<T> {
         } else {
             1.0
         };
-        let mut dst = try_vec![0.; (input.len() / 4) * 3];
+        try_resize_vec(dst, (input.len() / 4) * 3, 0.)?;
         let linearization_0 = &self.linearization[0];
         let linearization_1 = &self.linearization[1];
         let linearization_2 = &self.linearization[2];
@@ -165,14 +163,14 @@ impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaLut4x3<T> {
             dest[1] = pcs_y;
             dest[2] = pcs_z;
         }
-        Ok(dst)
+        Ok(())
     }
 }
 
 impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaInitialStage<f32, T>
     for KatanaLut4x3<T>
 {
-    fn to_pcs(&self, input: &[T]) -> Result<Vec<f32>, CmsError> {
+    fn to_pcs(&self, input: &[T], dst: &mut Vec<f32>) -> Result<(), CmsError> {
         if input.len() % 4 != 0 {
             return Err(CmsError::LaneMultipleOfChannels);
         }
@@ -180,24 +178,24 @@ impl<T: Copy + PointeeSizeExpressible + AsPrimitive<f32>> KatanaInitialStage<f32
 
         // If Source PCS is LAB trilinear should be used
         if self.pcs == DataColorSpace::Lab || self.pcs == DataColorSpace::Xyz {
-            return self.to_pcs_impl(input, |x, y, z, w| l_tbl.quadlinear_vec3(x, y, z, w));
+            return self.to_pcs_impl(input, dst, |x, y, z, w| l_tbl.quadlinear_vec3(x, y, z, w));
         }
 
         match self.interpolation_method {
             #[cfg(feature = "options")]
             InterpolationMethod::Tetrahedral => {
-                self.to_pcs_impl(input, |x, y, z, w| l_tbl.tetra_vec3(x, y, z, w))
+                self.to_pcs_impl(input, dst, |x, y, z, w| l_tbl.tetra_vec3(x, y, z, w))
             }
             #[cfg(feature = "options")]
             InterpolationMethod::Pyramid => {
-                self.to_pcs_impl(input, |x, y, z, w| l_tbl.pyramid_vec3(x, y, z, w))
+                self.to_pcs_impl(input, dst, |x, y, z, w| l_tbl.pyramid_vec3(x, y, z, w))
             }
             #[cfg(feature = "options")]
             InterpolationMethod::Prism => {
-                self.to_pcs_impl(input, |x, y, z, w| l_tbl.prism_vec3(x, y, z, w))
+                self.to_pcs_impl(input, dst, |x, y, z, w| l_tbl.prism_vec3(x, y, z, w))
             }
-            InterpolationMethod::Linear => {
-                self.to_pcs_impl(input, |x, y, z, w| l_tbl.quadlinear_vec3(x, y, z, w))
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
+                self.to_pcs_impl(input, dst, |x, y, z, w| l_tbl.quadlinear_vec3(x, y, z, w))
             }
         }
     }
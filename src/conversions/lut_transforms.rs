@@ -35,8 +35,8 @@ use crate::conversions::mab::{prepare_mab_3x3, prepare_mba_3x3};
 use crate::conversions::transform_lut3_to_4::make_transform_3x4;
 use crate::mlaf::mlaf;
 use crate::{
-    CmsError, ColorProfile, DataColorSpace, InPlaceStage, Layout, LutWarehouse, Matrix3f,
-    ProfileVersion, TransformExecutor, TransformOptions,
+    CmsError, ColorProfile, DataColorSpace, InPlaceStage, Layout, LutMultidimensionalType,
+    LutWarehouse, Matrix3f, ProfileVersion, TransformExecutor, TransformOptions, TransformScratch,
 };
 use num_traits::AsPrimitive;
 
@@ -137,6 +137,45 @@ fn pcs_lab_v4_to_v2(profile: &ColorProfile, lut: &mut [f32]) {
     }
 }
 
+/// Luminance scale, black point compensation, viewing adaptation and surround compensation,
+/// all in source PCS
+fn pcs_luminance_scale(
+    source: &ColorProfile,
+    dest: &ColorProfile,
+    options: TransformOptions,
+    lut: &mut [f32],
+) -> Result<(), CmsError> {
+    if let Some(factor) = options.luminance_scale(source, dest) {
+        let stage = StageLuminanceScale {
+            factor: factor as f32,
+            pcs: source.pcs,
+        };
+        stage.transform(lut)?;
+    }
+    if let Some(bpc) = BlackPointCompensation::new(source, dest, options) {
+        let stage = StageBlackPointCompensation {
+            bpc,
+            pcs: source.pcs,
+        };
+        stage.transform(lut)?;
+    }
+    if let Some(matrix) = options.viewing_adaptation.and_then(|v| v.matrix()) {
+        let stage = StagePcsMatrix {
+            matrix: matrix.to_f32(),
+            pcs: source.pcs,
+        };
+        stage.transform(lut)?;
+    }
+    if let Some(gamma) = options.surround_compensation.and_then(|v| v.gamma()) {
+        let stage = StageSurroundCompensation {
+            gamma: gamma as f32,
+            pcs: source.pcs,
+        };
+        stage.transform(lut)?;
+    }
+    Ok(())
+}
+
 fn pcs_lab_v2_to_v4(profile: &ColorProfile, lut: &mut [f32]) {
     if profile.pcs == DataColorSpace::Lab
         && profile.version_internal < ProfileVersion::V4_0
@@ -297,12 +336,13 @@ use crate::conversions::interpolator::LutBarycentricReduction;
 use crate::conversions::katana::{
     Katana, KatanaDefaultIntermediate, KatanaInitialStage, KatanaPostFinalizationStage,
     KatanaStageLabToXyz, KatanaStageXyzToLab, katana_create_rgb_lin_lut, katana_pcs_lab_v2_to_v4,
-    katana_pcs_lab_v4_to_v2, katana_prepare_inverse_lut_rgb_xyz, multi_dimensional_3x3_to_device,
-    multi_dimensional_3x3_to_pcs, multi_dimensional_4x3_to_pcs,
+    katana_pcs_lab_v4_to_v2, katana_pcs_luminance_scale, katana_prepare_inverse_lut_rgb_xyz,
+    multi_dimensional_3x3_to_device, multi_dimensional_3x3_to_pcs, multi_dimensional_4x3_to_pcs,
 };
 use crate::conversions::mab4x3::prepare_mab_4x3;
+use crate::conversions::make_fixed_any_to_any;
 use crate::conversions::mba3x4::prepare_mba_3x4;
-use crate::conversions::md_luts_factory::{do_any_to_any, prepare_alpha_finalizer};
+use crate::conversions::md_luts_factory::{do_any_to_any, do_device_link, prepare_alpha_finalizer};
 // use crate::conversions::bpc::compensate_bpc_in_lut;
 
 #[cfg(all(target_arch = "x86_64", feature = "avx"))]
@@ -319,10 +359,14 @@ use crate::conversions::transform_lut4_to_3::DefaultLut4x3Factory;
 #[cfg(not(all(target_arch = "aarch64", target_feature = "neon", feature = "neon")))]
 make_transform_4x3_fn!(make_transformer_4x3, DefaultLut4x3Factory);
 
+use crate::conversions::bpc::{BlackPointCompensation, StageBlackPointCompensation};
 #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
 use crate::conversions::neon::NeonLut4x3Factory;
 use crate::conversions::prelude_lut_xyz_rgb::{create_rgb_lin_lut, prepare_inverse_lut_rgb_xyz};
-use crate::conversions::xyz_lab::{StageLabToXyz, StageXyzToLab};
+use crate::conversions::xyz_lab::{
+    StageLabToXyz, StageLuminanceScale, StagePcsMatrix, StageSurroundCompensation, StageXyzToLab,
+};
+use crate::trace::trace_span;
 use crate::transform::PointeeSizeExpressible;
 use crate::trc::GammaLutInterpolate;
 
@@ -356,6 +400,16 @@ where
     (): LutBarycentricReduction<T, u8>,
     (): LutBarycentricReduction<T, u16>,
 {
+    let _span = trace_span!("moxcms::bake_lut", bit_depth = BIT_DEPTH);
+    if source.spectral_device_to_pcs().is_some()
+        || dest.spectral_pcs_to_device().is_some()
+        || source.is_multi_process_device_to_pcs(options.rendering_intent)
+        || dest.is_multi_process_pcs_to_device(options.rendering_intent)
+    {
+        return do_any_to_any::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_LUT>(
+            src_layout, source, dst_layout, dest, options,
+        );
+    }
     if (source.color_space == DataColorSpace::Cmyk || source.color_space == DataColorSpace::Color4)
         && (dest.color_space == DataColorSpace::Rgb || dest.color_space == DataColorSpace::Lab)
     {
@@ -399,11 +453,15 @@ where
                     LutWarehouse::Multidimensional(mab) => {
                         multi_dimensional_4x3_to_pcs::<T>(mab, options, source.pcs, BIT_DEPTH)?
                     }
+                    LutWarehouse::MultiProcess(_) => {
+                        return Err(CmsError::UnsupportedProfileConnection);
+                    }
                 };
 
             let mut stages = Vec::new();
 
             stages.push(katana_pcs_lab_v2_to_v4(source));
+            stages.extend(katana_pcs_luminance_scale(source, dest, options));
             if source.pcs == DataColorSpace::Lab {
                 stages.push(Box::new(KatanaStageLabToXyz::default()));
             }
@@ -423,6 +481,9 @@ where
                     LutWarehouse::Multidimensional(mab) => {
                         multi_dimensional_3x3_to_device::<T>(mab, options, dest.pcs, BIT_DEPTH)?
                     }
+                    LutWarehouse::MultiProcess(_) => {
+                        return Err(CmsError::UnsupportedProfileConnection);
+                    }
                 }
             } else if dest.is_matrix_shaper() {
                 let state = katana_prepare_inverse_lut_rgb_xyz::<T, BIT_DEPTH, GAMMA_LUT>(
@@ -447,6 +508,11 @@ where
                 final_stage,
                 stages,
                 post_finalization,
+                src_layout,
+                dst_layout,
+                chunk_pixels: options
+                    .real_time
+                    .then_some(TransformScratch::REAL_TIME_PIXELS),
             }));
         }
 
@@ -458,28 +524,17 @@ where
                 let mut samples = create_lut4_norm_samples::<GRID_SIZE>();
                 prepare_mab_4x3(m_curves, &mut samples, options, source.pcs)?
             }
+            LutWarehouse::MultiProcess(_) => return Err(CmsError::UnsupportedProfileConnection),
         };
 
         pcs_lab_v2_to_v4(source, &mut lut);
+        pcs_luminance_scale(source, dest, options, &mut lut)?;
 
         if source.pcs == DataColorSpace::Lab {
             let lab_to_xyz_stage = StageLabToXyz::default();
             lab_to_xyz_stage.transform(&mut lut)?;
         }
 
-        // if source.color_space == DataColorSpace::Cmyk
-        //     && (options.rendering_intent == RenderingIntent::Perceptual
-        //         || options.rendering_intent == RenderingIntent::RelativeColorimetric)
-        //     && options.black_point_compensation
-        // {
-        //     if let (Some(src_bp), Some(dst_bp)) = (
-        //         source.detect_black_point::<GRID_SIZE>(&lut),
-        //         dest.detect_black_point::<GRID_SIZE>(&lut),
-        //     ) {
-        //         compensate_bpc_in_lut(&mut lut, src_bp, dst_bp);
-        //     }
-        // }
-
         if dest.pcs == DataColorSpace::Lab {
             let lab_to_xyz_stage = StageXyzToLab::default();
             lab_to_xyz_stage.transform(&mut lut)?;
@@ -504,6 +559,9 @@ where
                 LutWarehouse::Multidimensional(mab) => {
                     prepare_mba_3x3(mab, &mut lut, options, dest.pcs)?
                 }
+                LutWarehouse::MultiProcess(_) => {
+                    return Err(CmsError::UnsupportedProfileConnection);
+                }
             }
         }
 
@@ -512,7 +570,8 @@ where
             && dest.is_linear_matrix_shaper();
 
         #[cfg(all(target_arch = "x86_64", feature = "avx"))]
-        if std::arch::is_x86_feature_detected!("avx2") && std::arch::is_x86_feature_detected!("fma")
+        if crate::equivalence::x86_feature_detected!("avx2")
+            && crate::equivalence::x86_feature_detected!("fma")
         {
             return Ok(make_transformer_4x3_avx_fma::<T, GRID_SIZE, BIT_DEPTH>(
                 dst_layout,
@@ -523,7 +582,7 @@ where
             ));
         }
         #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
-        if std::arch::is_x86_feature_detected!("sse4.1") {
+        if crate::equivalence::x86_feature_detected!("sse4.1") {
             return Ok(make_transformer_4x3_sse41::<T, GRID_SIZE, BIT_DEPTH>(
                 dst_layout,
                 lut,
@@ -568,6 +627,9 @@ where
                 LutWarehouse::Multidimensional(mab) => {
                     prepare_mab_3x3(mab, &mut lut, options, source.pcs)?
                 }
+                LutWarehouse::MultiProcess(_) => {
+                    return Err(CmsError::UnsupportedProfileConnection);
+                }
             }
         } else if source.is_matrix_shaper() {
             lut = create_rgb_lin_lut::<T, BIT_DEPTH, LINEAR_CAP, GRID_SIZE>(source, options)?;
@@ -576,6 +638,7 @@ where
         }
 
         pcs_lab_v2_to_v4(source, &mut lut);
+        pcs_luminance_scale(source, dest, options, &mut lut)?;
 
         if source.pcs == DataColorSpace::Xyz && dest.pcs == DataColorSpace::Lab {
             let xyz_to_lab = StageXyzToLab::default();
@@ -595,6 +658,7 @@ where
             LutWarehouse::Multidimensional(m_curves) => {
                 prepare_mba_3x4(m_curves, &mut lut, options, dest.pcs)?
             }
+            LutWarehouse::MultiProcess(_) => return Err(CmsError::UnsupportedProfileConnection),
         };
 
         let is_dest_linear_profile = dest.color_space == DataColorSpace::Rgb
@@ -657,10 +721,14 @@ where
                         LutWarehouse::Multidimensional(mab) => {
                             multi_dimensional_3x3_to_pcs::<T>(mab, options, source.pcs, BIT_DEPTH)?
                         }
+                        LutWarehouse::MultiProcess(_) => {
+                            return Err(CmsError::UnsupportedProfileConnection);
+                        }
                     }
                 };
 
             stages.push(katana_pcs_lab_v2_to_v4(source));
+            stages.extend(katana_pcs_luminance_scale(source, dest, options));
             if source.pcs == DataColorSpace::Lab {
                 stages.push(Box::new(KatanaStageLabToXyz::default()));
             }
@@ -680,6 +748,9 @@ where
                     LutWarehouse::Multidimensional(mab) => {
                         multi_dimensional_3x3_to_device::<T>(mab, options, dest.pcs, BIT_DEPTH)?
                     }
+                    LutWarehouse::MultiProcess(_) => {
+                        return Err(CmsError::UnsupportedProfileConnection);
+                    }
                 }
             } else if dest.is_matrix_shaper() {
                 let state = katana_prepare_inverse_lut_rgb_xyz::<T, BIT_DEPTH, GAMMA_LUT>(
@@ -704,6 +775,11 @@ where
                 final_stage,
                 stages,
                 post_finalization,
+                src_layout,
+                dst_layout,
+                chunk_pixels: options
+                    .real_time
+                    .then_some(TransformScratch::REAL_TIME_PIXELS),
             }));
         }
 
@@ -722,6 +798,9 @@ where
                 LutWarehouse::Multidimensional(mab) => {
                     prepare_mab_3x3(mab, &mut lut, options, source.pcs)?
                 }
+                LutWarehouse::MultiProcess(_) => {
+                    return Err(CmsError::UnsupportedProfileConnection);
+                }
             }
         } else if source.is_matrix_shaper() {
             lut = create_rgb_lin_lut::<T, BIT_DEPTH, LINEAR_CAP, GRID_SIZE>(source, options)?;
@@ -730,6 +809,7 @@ where
         }
 
         pcs_lab_v2_to_v4(source, &mut lut);
+        pcs_luminance_scale(source, dest, options, &mut lut)?;
 
         if source.pcs == DataColorSpace::Xyz && dest.pcs == DataColorSpace::Lab {
             let xyz_to_lab = StageXyzToLab::default();
@@ -752,6 +832,9 @@ where
                 LutWarehouse::Multidimensional(mab) => {
                     prepare_mba_3x3(mab, &mut lut, options, dest.pcs)?
                 }
+                LutWarehouse::MultiProcess(_) => {
+                    return Err(CmsError::UnsupportedProfileConnection);
+                }
             }
         } else if dest.is_matrix_shaper() {
             prepare_inverse_lut_rgb_xyz::<T, BIT_DEPTH, GAMMA_LUT>(dest, &mut lut, options)?;
@@ -764,7 +847,9 @@ where
             && dest.is_linear_matrix_shaper();
 
         #[cfg(all(feature = "avx", target_arch = "x86_64"))]
-        if std::arch::is_x86_feature_detected!("avx2") && std::is_x86_feature_detected!("fma") {
+        if crate::equivalence::x86_feature_detected!("avx2")
+            && crate::equivalence::x86_feature_detected!("fma")
+        {
             return Ok(make_transformer_3x3_avx_fma::<T, GRID_SIZE, BIT_DEPTH>(
                 src_layout,
                 dst_layout,
@@ -775,7 +860,7 @@ where
             ));
         }
         #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
-        if std::arch::is_x86_feature_detected!("sse4.1") {
+        if crate::equivalence::x86_feature_detected!("sse4.1") {
             return Ok(make_transformer_3x3_sse41::<T, GRID_SIZE, BIT_DEPTH>(
                 src_layout,
                 dst_layout,
@@ -795,8 +880,144 @@ where
             is_dest_linear_profile,
         ))
     } else {
+        if let Some(fixed) =
+            make_fixed_any_to_any::<T, BIT_DEPTH>(src_layout, source, dst_layout, dest, options)?
+        {
+            return Ok(fixed);
+        }
         do_any_to_any::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_LUT>(
             src_layout, source, dst_layout, dest, options,
         )
     }
 }
+
+/// Device link `A2B0` baked into 3D or 4D LUT, link PCS holds device color space
+/// of the output, so there is no PCS stage between link input and output.
+/// Links which can't be baked accurately are evaluated directly.
+pub(crate) fn make_device_link_lut_transform<
+    T: Copy
+        + Default
+        + AsPrimitive<f32>
+        + Send
+        + Sync
+        + AsPrimitive<usize>
+        + PointeeSizeExpressible
+        + GammaLutInterpolate,
+    const BIT_DEPTH: usize,
+>(
+    src_layout: Layout,
+    link: &ColorProfile,
+    lut: &LutWarehouse,
+    dst_layout: Layout,
+    options: TransformOptions,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    f32: AsPrimitive<T>,
+    u32: AsPrimitive<T>,
+    (): LutBarycentricReduction<T, u8>,
+    (): LutBarycentricReduction<T, u16>,
+{
+    let _span = trace_span!("moxcms::bake_device_link", bit_depth = BIT_DEPTH);
+    if lut.is_katana_required() {
+        return do_device_link::<T, BIT_DEPTH>(src_layout, link, lut, dst_layout, options);
+    }
+    // lut8 and lut16 matrix is defined for PCS XYZ input only
+    let options = TransformOptions {
+        lcms_lut_matrix: false,
+        ..options
+    };
+    // Device color space as PCS keeps requested interpolation method
+    let pcs = link.color_space;
+    let inputs = link.color_space.channels();
+    let outputs = link.pcs.channels();
+
+    if inputs == 3 && outputs == 3 {
+        const GRID_SIZE: usize = 33;
+
+        let mut samples = create_lut3_samples_norm::<GRID_SIZE>();
+        let lut = match lut {
+            LutWarehouse::Lut(lut) => create_lut3x3(lut, &samples, options, pcs)?,
+            LutWarehouse::Multidimensional(mab) => {
+                prepare_mab_3x3(mab, &mut samples, options, pcs)?;
+                samples
+            }
+            LutWarehouse::MultiProcess(_) => {
+                return do_device_link::<T, BIT_DEPTH>(src_layout, link, lut, dst_layout, options);
+            }
+        };
+
+        #[cfg(all(feature = "avx", target_arch = "x86_64"))]
+        if crate::equivalence::x86_feature_detected!("avx2")
+            && crate::equivalence::x86_feature_detected!("fma")
+        {
+            return Ok(make_transformer_3x3_avx_fma::<T, GRID_SIZE, BIT_DEPTH>(
+                src_layout, dst_layout, lut, options, link.pcs, false,
+            ));
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+        if crate::equivalence::x86_feature_detected!("sse4.1") {
+            return Ok(make_transformer_3x3_sse41::<T, GRID_SIZE, BIT_DEPTH>(
+                src_layout, dst_layout, lut, options, link.pcs, false,
+            ));
+        }
+
+        Ok(make_transformer_3x3::<T, GRID_SIZE, BIT_DEPTH>(
+            src_layout, dst_layout, lut, options, link.pcs, false,
+        ))
+    } else if inputs == 4 && outputs == 3 {
+        const GRID_SIZE: usize = 17;
+
+        let lut = match lut {
+            LutWarehouse::Lut(lut) => create_lut4::<GRID_SIZE>(lut, options, pcs)?,
+            LutWarehouse::Multidimensional(mab) => {
+                let mut samples = create_lut4_norm_samples::<GRID_SIZE>();
+                prepare_mab_4x3(mab, &mut samples, options, pcs)?
+            }
+            LutWarehouse::MultiProcess(_) => {
+                return do_device_link::<T, BIT_DEPTH>(src_layout, link, lut, dst_layout, options);
+            }
+        };
+
+        #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+        if crate::equivalence::x86_feature_detected!("avx2")
+            && crate::equivalence::x86_feature_detected!("fma")
+        {
+            return Ok(make_transformer_4x3_avx_fma::<T, GRID_SIZE, BIT_DEPTH>(
+                dst_layout, lut, options, link.pcs, false,
+            ));
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+        if crate::equivalence::x86_feature_detected!("sse4.1") {
+            return Ok(make_transformer_4x3_sse41::<T, GRID_SIZE, BIT_DEPTH>(
+                dst_layout, lut, options, link.pcs, false,
+            ));
+        }
+
+        Ok(make_transformer_4x3::<T, GRID_SIZE, BIT_DEPTH>(
+            dst_layout, lut, options, link.pcs, false,
+        ))
+    } else if inputs == 3 && outputs == 4 {
+        const GRID_SIZE: usize = 33;
+
+        let mut samples = create_lut3_samples_norm::<GRID_SIZE>();
+        let lut = match lut {
+            LutWarehouse::Lut(lut) => create_lut3x4(lut, &samples, options, pcs)?,
+            // A2B without M curves runs in the same order as B2A with swapped curves
+            LutWarehouse::Multidimensional(mab) if mab.m_curves.is_empty() => {
+                let mba = LutMultidimensionalType {
+                    a_curves: mab.b_curves.clone(),
+                    b_curves: mab.a_curves.clone(),
+                    ..mab.clone()
+                };
+                prepare_mba_3x4(&mba, &mut samples, options, pcs)?
+            }
+            _ => return do_device_link::<T, BIT_DEPTH>(src_layout, link, lut, dst_layout, options),
+        };
+
+        Ok(make_transform_3x4::<T, GRID_SIZE, BIT_DEPTH>(
+            src_layout, lut, options, link.pcs, false,
+        ))
+    } else {
+        do_device_link::<T, BIT_DEPTH>(src_layout, link, lut, dst_layout, options)
+    }
+}
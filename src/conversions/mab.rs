@@ -120,7 +120,7 @@ impl InPlaceStage for ACurves3<'_> {
             InterpolationMethod::Prism => {
                 self.transform_impl(dst, |x, y, z| lut.prism_vec3(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(dst, |x, y, z| lut.trilinear_vec3(x, y, z))?;
             }
         }
@@ -150,7 +150,7 @@ impl InPlaceStage for ACurves3Optimized<'_> {
             InterpolationMethod::Prism => {
                 self.transform_impl(dst, |x, y, z| lut.prism_vec3(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(dst, |x, y, z| lut.trilinear_vec3(x, y, z))?;
             }
         }
@@ -217,7 +217,7 @@ impl InPlaceStage for ACurves3Inverse<'_> {
             InterpolationMethod::Prism => {
                 self.transform_impl(dst, |x, y, z| lut.prism_vec3(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(dst, |x, y, z| lut.trilinear_vec3(x, y, z))?;
             }
         }
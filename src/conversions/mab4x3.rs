@@ -133,7 +133,7 @@ impl Stage for ACurves4x3<'_> {
             InterpolationMethod::Prism => {
                 self.transform_impl(src, dst, |x, y, z, w| lut.prism_vec3(x, y, z, w))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(src, dst, |x, y, z, w| lut.quadlinear_vec3(x, y, z, w))?;
             }
         }
@@ -163,7 +163,7 @@ impl Stage for ACurves4x3Optimized<'_> {
             InterpolationMethod::Prism => {
                 self.transform_impl(src, dst, |x, y, z, w| lut.prism_vec3(x, y, z, w))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(src, dst, |x, y, z, w| lut.quadlinear_vec3(x, y, z, w))?;
             }
         }
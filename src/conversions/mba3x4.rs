@@ -129,7 +129,7 @@ impl Stage for ACurves3x4Inverse<'_> {
             InterpolationMethod::Prism => {
                 self.transform_impl(src, dst, |x, y, z| lut.prism_vec4(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(src, dst, |x, y, z| lut.trilinear_vec4(x, y, z))?;
             }
         }
@@ -159,7 +159,7 @@ impl Stage for ACurves3x4InverseOptimized<'_> {
             InterpolationMethod::Prism => {
                 self.transform_impl(src, dst, |x, y, z| lut.prism_vec4(x, y, z))?;
             }
-            InterpolationMethod::Linear => {
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
                 self.transform_impl(src, dst, |x, y, z| lut.trilinear_vec4(x, y, z))?;
             }
         }
@@ -0,0 +1,424 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Integer only executors for transforms that otherwise run `do_any_to_any` in floating point.
+//!
+//! Gray source has few enough codes to tabulate the whole transform,
+//! three and four channel sources are sampled into a grid interpolated in Q0.15.
+use crate::conversions::do_any_to_any;
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, Layout, PointeeSizeExpressible, TransformExecutor,
+    TransformF32BitExecutor, TransformOptions,
+};
+use num_traits::AsPrimitive;
+use std::marker::PhantomData;
+
+const GRID_SIZE_3: usize = 33;
+const GRID_SIZE_4: usize = 17;
+
+/// Builds integer executor for `do_any_to_any` connections when
+/// [TransformOptions::prefer_fixed_point] is set, `None` when there is no such executor.
+pub(crate) fn make_fixed_any_to_any<
+    T: Copy + Default + PointeeSizeExpressible + AsPrimitive<usize> + Send + Sync + 'static,
+    const BIT_DEPTH: usize,
+>(
+    src_layout: Layout,
+    source: &ColorProfile,
+    dst_layout: Layout,
+    dest: &ColorProfile,
+    options: TransformOptions,
+) -> Result<Option<Box<dyn TransformExecutor<T> + Send + Sync>>, CmsError>
+where
+    u32: AsPrimitive<T>,
+{
+    if !options.prefer_fixed_point || !T::FINITE || BIT_DEPTH > 16 {
+        return Ok(None);
+    }
+    // Three channel grid is coarser than float interpolation of profile LUTs,
+    // it is used only when floats are not allowed per pixel.
+    if source.color_space.channels() == 3 {
+        return Ok(None);
+    }
+    make_fixed_tabulated::<T, BIT_DEPTH>(src_layout, source, dst_layout, dest, |layout| {
+        do_any_to_any::<f32, 1, 65536, 32768>(layout, source, dst_layout, dest, options)
+    })
+}
+
+/// Tabulates transform of gray, three or four channel source into integer executor,
+/// `None` when source has other count of channels.
+///
+/// `reference` creates floating point executor of the same transform for the given source layout.
+pub(crate) fn make_fixed_tabulated<
+    T: Copy + Default + AsPrimitive<usize> + Send + Sync + 'static,
+    const BIT_DEPTH: usize,
+>(
+    src_layout: Layout,
+    source: &ColorProfile,
+    dst_layout: Layout,
+    dest: &ColorProfile,
+    reference: impl FnOnce(Layout) -> Result<Box<TransformF32BitExecutor>, CmsError>,
+) -> Result<Option<Box<dyn TransformExecutor<T> + Send + Sync>>, CmsError>
+where
+    u32: AsPrimitive<T>,
+{
+    let dst_channels = dst_layout.channels();
+    // Four channel layout of CMYK is inks, not alpha
+    let color_channels = dest.color_space.channels().min(dst_channels);
+    let max_value = ((1u32 << BIT_DEPTH) - 1) as f32;
+    let quantize = |v: f32| (v * max_value).round().max(0.).min(max_value) as u16;
+    if source.color_space == DataColorSpace::Gray {
+        if src_layout != Layout::Gray && src_layout != Layout::GrayAlpha {
+            return Err(CmsError::InvalidLayout);
+        }
+        // Every gray code is converted once, transform becomes a table lookup
+        let reference = reference(Layout::Gray)?;
+        let codes = 1usize << BIT_DEPTH;
+        let src = (0..codes)
+            .map(|code| code as f32 / max_value)
+            .collect::<Vec<_>>();
+        let mut sampled = vec![0f32; codes * dst_channels];
+        reference.transform(&src, &mut sampled)?;
+        let table = sampled
+            .chunks_exact(dst_channels)
+            .flat_map(|px| px[..color_channels].iter().map(|&v| quantize(v)))
+            .collect::<Vec<_>>();
+        return Ok(Some(Box::new(TransformGrayTable::<T> {
+            table,
+            src_layout,
+            dst_layout,
+            color_channels,
+            max_value: (1u32 << BIT_DEPTH) - 1,
+            _phantom: PhantomData,
+        })));
+    }
+    if source.color_space.channels() == 3 && BIT_DEPTH < 16 {
+        if src_layout != Layout::Rgb && src_layout != Layout::Rgba {
+            return Err(CmsError::InvalidLayout);
+        }
+        let reference = reference(Layout::Rgb)?;
+        let src = grid_nodes::<3>(GRID_SIZE_3);
+        return Ok(Some(Box::new(TransformLutQ0_15::<T, 3> {
+            grid: sample_grid::<3, BIT_DEPTH>(&src, reference, dst_channels, color_channels)?,
+            grid_size: GRID_SIZE_3,
+            weights: grid_weights::<BIT_DEPTH>(GRID_SIZE_3),
+            src_layout,
+            dst_layout,
+            color_channels,
+            max_value: (1u32 << BIT_DEPTH) - 1,
+            _phantom: PhantomData,
+        })));
+    }
+    if source.color_space.channels() == 4 && BIT_DEPTH < 16 {
+        if src_layout.channels() != 4 {
+            return Err(CmsError::InvalidLayout);
+        }
+        let reference = reference(src_layout)?;
+        let src = grid_nodes::<4>(GRID_SIZE_4);
+        return Ok(Some(Box::new(TransformLutQ0_15::<T, 4> {
+            grid: sample_grid::<4, BIT_DEPTH>(&src, reference, dst_channels, color_channels)?,
+            grid_size: GRID_SIZE_4,
+            weights: grid_weights::<BIT_DEPTH>(GRID_SIZE_4),
+            src_layout,
+            dst_layout,
+            color_channels,
+            max_value: (1u32 << BIT_DEPTH) - 1,
+            _phantom: PhantomData,
+        })));
+    }
+    Ok(None)
+}
+
+/// Normalized coordinates of every grid node, the last input changes fastest.
+fn grid_nodes<const INPUTS: usize>(grid_size: usize) -> Vec<f32> {
+    let scale = 1. / (grid_size - 1) as f32;
+    let nodes = grid_size.pow(INPUTS as u32);
+    let mut src = Vec::with_capacity(nodes * INPUTS);
+    for node in 0..nodes {
+        let mut coordinates = [0f32; INPUTS];
+        let mut index = node;
+        for v in coordinates.iter_mut().rev() {
+            *v = (index % grid_size) as f32 * scale;
+            index /= grid_size;
+        }
+        src.extend_from_slice(&coordinates);
+    }
+    src
+}
+
+/// Evaluates reference at grid nodes and quantizes color channels to integer codes.
+fn sample_grid<const INPUTS: usize, const BIT_DEPTH: usize>(
+    src: &[f32],
+    reference: Box<TransformF32BitExecutor>,
+    dst_channels: usize,
+    color_channels: usize,
+) -> Result<Vec<i32>, CmsError> {
+    let max_value = ((1u32 << BIT_DEPTH) - 1) as f32;
+    let mut sampled = vec![0f32; src.len() / INPUTS * dst_channels];
+    reference.transform(src, &mut sampled)?;
+    Ok(sampled
+        .chunks_exact(dst_channels)
+        .flat_map(|px| {
+            px[..color_channels]
+                .iter()
+                .map(|&v| (v * max_value).round().max(0.).min(max_value) as i32)
+        })
+        .collect())
+}
+
+/// Grid cell and Q0.15 fraction of every input code.
+fn grid_weights<const BIT_DEPTH: usize>(grid_size: usize) -> Vec<(u32, i32)> {
+    let max_value = ((1u32 << BIT_DEPTH) - 1) as f32;
+    (0..1usize << BIT_DEPTH)
+        .map(|code| {
+            let position = code as f32 * (grid_size - 1) as f32 / max_value;
+            let index = (position as usize).min(grid_size - 2);
+            let weight = ((position - index as f32) * 32768.).round() as i32;
+            (index as u32, weight)
+        })
+        .collect()
+}
+
+fn check_lanes(
+    src: usize,
+    src_channels: usize,
+    dst: usize,
+    dst_channels: usize,
+) -> Result<(), CmsError> {
+    if src % src_channels != 0 || dst % dst_channels != 0 {
+        return Err(CmsError::LaneMultipleOfChannels);
+    }
+    if src / src_channels != dst / dst_channels {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    Ok(())
+}
+
+struct TransformGrayTable<T> {
+    table: Vec<u16>,
+    src_layout: Layout,
+    dst_layout: Layout,
+    color_channels: usize,
+    max_value: u32,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Copy + Default + AsPrimitive<usize>> TransformExecutor<T> for TransformGrayTable<T>
+where
+    u32: AsPrimitive<T>,
+{
+    fn transform(&self, src: &[T], dst: &mut [T]) -> Result<(), CmsError> {
+        let src_channels = self.src_layout.channels();
+        let dst_channels = self.dst_layout.channels();
+        check_lanes(src.len(), src_channels, dst.len(), dst_channels)?;
+        let max_code = self.table.len() / self.color_channels - 1;
+        for (src, dst) in src
+            .chunks_exact(src_channels)
+            .zip(dst.chunks_exact_mut(dst_channels))
+        {
+            let code: usize = src[0].as_();
+            let start = code.min(max_code) * self.color_channels;
+            for (dst, &v) in dst
+                .iter_mut()
+                .zip(self.table[start..start + self.color_channels].iter())
+            {
+                *dst = (v as u32).as_();
+            }
+            if dst_channels > self.color_channels {
+                dst[dst_channels - 1] = if self.src_layout.has_alpha() {
+                    src[1]
+                } else {
+                    self.max_value.as_()
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Grid of `INPUTS` dimensions interpolated over Kuhn simplices in Q0.15.
+struct TransformLutQ0_15<T, const INPUTS: usize> {
+    grid: Vec<i32>,
+    grid_size: usize,
+    weights: Vec<(u32, i32)>,
+    src_layout: Layout,
+    dst_layout: Layout,
+    color_channels: usize,
+    max_value: u32,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Copy + Default + AsPrimitive<usize>, const INPUTS: usize> TransformExecutor<T>
+    for TransformLutQ0_15<T, INPUTS>
+where
+    u32: AsPrimitive<T>,
+{
+    fn transform(&self, src: &[T], dst: &mut [T]) -> Result<(), CmsError> {
+        let src_channels = self.src_layout.channels();
+        let dst_channels = self.dst_layout.channels();
+        check_lanes(src.len(), src_channels, dst.len(), dst_channels)?;
+        let channels = self.color_channels;
+        let mut strides = [channels; INPUTS];
+        for i in (0..INPUTS - 1).rev() {
+            strides[i] = strides[i + 1] * self.grid_size;
+        }
+        // Four channel layout of CMYK is inks, not alpha
+        let has_alpha = INPUTS < src_channels;
+        let max_code = self.weights.len() - 1;
+        let max_value = self.max_value as i32;
+        let mut acc = [0i32; 16];
+        for (src, dst) in src
+            .chunks_exact(src_channels)
+            .zip(dst.chunks_exact_mut(dst_channels))
+        {
+            let mut base = 0usize;
+            let mut fractions = [(0i32, 0usize); INPUTS];
+            for ((&v, fraction), &stride) in
+                src.iter().zip(fractions.iter_mut()).zip(strides.iter())
+            {
+                let code: usize = v.as_();
+                let (index, weight) = self.weights[code.min(max_code)];
+                base += index as usize * stride;
+                *fraction = (weight, stride);
+            }
+            // Kuhn simplex: walk from base node along axes in order of decreasing fraction
+            fractions.sort_unstable_by_key(|&(weight, _)| std::cmp::Reverse(weight));
+            let acc = &mut acc[..channels];
+            let mut previous = &self.grid[base..base + channels];
+            for (acc, &v) in acc.iter_mut().zip(previous.iter()) {
+                *acc = v << 15;
+            }
+            let mut node = base;
+            for &(weight, stride) in fractions.iter() {
+                node += stride;
+                let next = &self.grid[node..node + channels];
+                for ((acc, &v), &p) in acc.iter_mut().zip(next.iter()).zip(previous.iter()) {
+                    *acc += (v - p) * weight;
+                }
+                previous = next;
+            }
+            for (dst, &acc) in dst.iter_mut().zip(acc.iter()) {
+                let v = ((acc + (1 << 14)) >> 15).clamp(0, max_value);
+                *dst = (v as u32).as_();
+            }
+            if dst_channels > channels {
+                dst[dst_channels - 1] = if has_alpha {
+                    src[src_channels - 1]
+                } else {
+                    self.max_value.as_()
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_gray_and_cmyk_paths() {
+        let Ok(us_swop_coated) = std::fs::read("./assets/us_swop_coated.icc") else {
+            return;
+        };
+        let cmyk = ColorProfile::new_from_slice(&us_swop_coated).unwrap();
+        // Gray described by `lut16` A2B0: L* follows gamma 2.2, a* and b* are neutral
+        let mut gray = ColorProfile {
+            color_space: DataColorSpace::Gray,
+            pcs: DataColorSpace::Lab,
+            ..Default::default()
+        };
+        let input_table = (0..256u32)
+            .map(|i| ((i as f32 / 255.).powf(2.2) * 65535.).round() as u16)
+            .collect::<Vec<_>>();
+        let identity = (0..256u32).map(|i| (i * 257) as u16).collect::<Vec<_>>();
+        gray.lut_a_to_b_perceptual = Some(crate::LutWarehouse::Lut(crate::LutDataType {
+            num_input_channels: 1,
+            num_output_channels: 3,
+            num_clut_grid_points: 2,
+            matrix: crate::Matrix3d::IDENTITY,
+            num_input_table_entries: 256,
+            num_output_table_entries: 256,
+            input_table: crate::LutStore::Store16(input_table.into()),
+            clut_table: crate::LutStore::Store16(vec![0, 32896, 32896, 65535, 32896, 32896].into()),
+            output_table: crate::LutStore::Store16(identity.repeat(3).into()),
+            lut_type: crate::LutType::Lut16,
+        }));
+        let fixed = TransformOptions::default();
+        let float = TransformOptions {
+            prefer_fixed_point: false,
+            ..Default::default()
+        };
+
+        let gray_src = (0..=255u8).collect::<Vec<_>>();
+        let mut expected = vec![0u8; 256 * 4];
+        let mut dst = vec![0u8; 256 * 4];
+        gray.create_transform_8bit(Layout::Gray, &cmyk, Layout::Rgba, float)
+            .unwrap()
+            .transform(&gray_src, &mut expected)
+            .unwrap();
+        gray.create_transform_8bit(Layout::Gray, &cmyk, Layout::Rgba, fixed)
+            .unwrap()
+            .transform(&gray_src, &mut dst)
+            .unwrap();
+        for (&a, &b) in dst.iter().zip(expected.iter()) {
+            assert!(a.abs_diff(b) <= 1, "gray: {a} vs {b}");
+        }
+        // Table path takes alpha of gray, CMYK layout has no alpha to write it to
+        let gray_alpha = gray_src.iter().flat_map(|&v| [v, 7]).collect::<Vec<_>>();
+        let mut with_alpha = vec![0u8; 256 * 4];
+        gray.create_transform_8bit(Layout::GrayAlpha, &cmyk, Layout::Rgba, fixed)
+            .unwrap()
+            .transform(&gray_alpha, &mut with_alpha)
+            .unwrap();
+        assert_eq!(with_alpha, dst);
+
+        let cmyk_src = (0..4096u32)
+            .flat_map(|i| [i * 7, i * 13 + 5, i * 31 + 11, i * 3].map(|v| (v % 256) as u8))
+            .collect::<Vec<_>>();
+        let mut expected = vec![0u8; cmyk_src.len()];
+        let mut dst = vec![0u8; cmyk_src.len()];
+        cmyk.create_transform_8bit(Layout::Rgba, &cmyk, Layout::Rgba, float)
+            .unwrap()
+            .transform(&cmyk_src, &mut expected)
+            .unwrap();
+        cmyk.create_transform_8bit(Layout::Rgba, &cmyk, Layout::Rgba, fixed)
+            .unwrap()
+            .transform(&cmyk_src, &mut dst)
+            .unwrap();
+        let mut total = 0u32;
+        for (&a, &b) in dst.iter().zip(expected.iter()) {
+            assert!(a.abs_diff(b) <= 8, "cmyk: {a} vs {b}");
+            total += a.abs_diff(b) as u32;
+        }
+        assert!(
+            total as f32 / dst.len() as f32 <= 1.,
+            "mean difference is too high"
+        );
+    }
+}
@@ -624,6 +624,140 @@ pub(crate) fn linear_15i_vec3f<const N: usize>(
     linear_n_i_vec3f::<N, 14, FHandle<N>>(lut, arr, inputs, f)
 }
 
+/// Tetrahedron of 3D CLUT cell selected for a point, offsets are in floats.
+///
+/// Result is `c0 + (a[0] - b[0]) * w[0] + (a[1] - b[1]) * w[1] + (a[2] - b[2]) * w[2]`
+/// for each output channel, matching [FastCube] tetrahedral interpolation.
+#[cfg(any(
+    all(target_arch = "x86_64", feature = "avx"),
+    all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"),
+    all(target_arch = "aarch64", target_feature = "neon", feature = "neon")
+))]
+pub(crate) struct TetraCorners {
+    pub(crate) c0: usize,
+    pub(crate) a: [usize; 3],
+    pub(crate) b: [usize; 3],
+    pub(crate) w: [f32; 3],
+}
+
+#[inline(always)]
+#[cfg(any(
+    all(target_arch = "x86_64", feature = "avx"),
+    all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"),
+    all(target_arch = "aarch64", target_feature = "neon", feature = "neon")
+))]
+pub(crate) fn tetra_3i_corners(lut: &MultidimensionalLut, x: f32, y: f32, z: f32) -> TetraCorners {
+    let lx = x.max(0.0).min(1.0) * lut.grid_scale[0];
+    let ly = y.max(0.0).min(1.0) * lut.grid_scale[1];
+    let lz = z.max(0.0).min(1.0) * lut.grid_scale[2];
+
+    let x = lx.floor() as u32;
+    let y = ly.floor() as u32;
+    let z = lz.floor() as u32;
+
+    let x_n = lx.ceil() as u32;
+    let y_n = ly.ceil() as u32;
+    let z_n = lz.ceil() as u32;
+
+    let rx = lx - x as f32;
+    let ry = ly - y as f32;
+    let rz = lz - z as f32;
+
+    let offset = |x: u32, y: u32, z: u32| -> usize {
+        (x * lut.grid_strides[0] + y * lut.grid_strides[1] + z * lut.grid_strides[2]) as usize
+            * lut.output_inks
+    };
+
+    let c0 = offset(x, y, z);
+    let (a, b) = if rx >= ry {
+        if ry >= rz {
+            (
+                [
+                    offset(x_n, y, z),
+                    offset(x_n, y_n, z),
+                    offset(x_n, y_n, z_n),
+                ],
+                [c0, offset(x_n, y, z), offset(x_n, y_n, z)],
+            )
+        } else if rx >= rz {
+            (
+                [
+                    offset(x_n, y, z),
+                    offset(x_n, y_n, z_n),
+                    offset(x_n, y, z_n),
+                ],
+                [c0, offset(x_n, y, z_n), offset(x_n, y, z)],
+            )
+        } else {
+            (
+                [
+                    offset(x_n, y, z_n),
+                    offset(x_n, y_n, z_n),
+                    offset(x, y, z_n),
+                ],
+                [offset(x, y, z_n), offset(x_n, y, z_n), c0],
+            )
+        }
+    } else if rx >= rz {
+        (
+            [
+                offset(x_n, y_n, z),
+                offset(x, y_n, z),
+                offset(x_n, y_n, z_n),
+            ],
+            [offset(x, y_n, z), c0, offset(x_n, y_n, z)],
+        )
+    } else if ry >= rz {
+        (
+            [
+                offset(x_n, y_n, z_n),
+                offset(x, y_n, z),
+                offset(x, y_n, z_n),
+            ],
+            [offset(x, y_n, z_n), c0, offset(x, y_n, z)],
+        )
+    } else {
+        (
+            [
+                offset(x_n, y_n, z_n),
+                offset(x, y_n, z_n),
+                offset(x, y, z_n),
+            ],
+            [offset(x, y_n, z_n), offset(x, y, z_n), c0],
+        )
+    };
+    TetraCorners {
+        c0,
+        a,
+        b,
+        w: [rx, ry, rz],
+    }
+}
+
+pub(crate) type Tetra3iToAnyFn = fn(&MultidimensionalLut, &[f32], f32, f32, f32, &mut [f32], usize);
+
+/// Selects 3D CLUT interpolation into `inks` outputs, vectorized over output channels
+/// when CPU allows it
+pub(crate) fn tetra_3i_to_any_handler(inks: usize) -> Tetra3iToAnyFn {
+    #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+    if inks > 4
+        && crate::equivalence::x86_feature_detected!("avx2")
+        && crate::equivalence::x86_feature_detected!("fma")
+    {
+        return crate::conversions::avx::tetra_3i_to_any_avx;
+    }
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+    if inks >= 4 && crate::equivalence::x86_feature_detected!("sse4.1") {
+        return crate::conversions::sse::tetra_3i_to_any_sse;
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
+    if inks >= 4 {
+        return crate::conversions::neon::tetra_3i_to_any_neon;
+    }
+    let _ = inks;
+    tetra_3i_to_any_vec
+}
+
 #[inline(never)]
 pub(crate) fn tetra_3i_to_any_vec(
     lut: &MultidimensionalLut,
@@ -726,3 +860,41 @@ pub(crate) fn tetra_3i_to_any_vec(
         _ => unreachable!(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_tetra_3i_to_any_handler() {
+        let mut rng = rand::rng();
+        for inks in 1..=15 {
+            let grid = 5u8;
+            let md_lut = MultidimensionalLut::new([grid; 16], 3, inks);
+            let clut = (0..grid as usize * grid as usize * grid as usize * inks)
+                .map(|_| rng.random_range(0.0..1.0))
+                .collect::<Vec<f32>>();
+            let handler = tetra_3i_to_any_handler(inks);
+            let mut expected = vec![0f32; inks];
+            let mut dst = vec![0f32; inks];
+            // Last grid point makes SIMD loads run past CLUT end
+            let points = [[1f32, 1., 1.], [0., 0., 0.], [1., 0.5, 0.99]]
+                .into_iter()
+                .chain((0..256).map(|_| {
+                    [
+                        rng.random_range(0.0..1.0),
+                        rng.random_range(0.0..1.0),
+                        rng.random_range(0.0..1.0),
+                    ]
+                }));
+            for [x, y, z] in points {
+                tetra_3i_to_any_vec(&md_lut, &clut, x, y, z, &mut expected, inks);
+                handler(&md_lut, &clut, x, y, z, &mut dst, inks);
+                for (a, b) in dst.iter().zip(expected.iter()) {
+                    assert!((a - b).abs() < 1e-5, "inks {inks}: {dst:?} {expected:?}");
+                }
+            }
+        }
+    }
+}
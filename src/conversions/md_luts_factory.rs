@@ -30,13 +30,18 @@ use crate::conversions::LutBarycentricReduction;
 use crate::conversions::katana::{
     CopyAlphaStage, InjectAlphaStage, Katana, KatanaInitialStage, KatanaIntermediateStage,
     KatanaPostFinalizationStage, KatanaStageLabToXyz, KatanaStageXyzToLab,
-    katana_create_rgb_lin_lut, katana_input_make_lut_nx3, katana_multi_dimensional_3xn_to_device,
+    katana_create_rgb_lin_lut, katana_device_link_nxm, katana_device_output,
+    katana_float_device_to_pcs, katana_float_pcs_to_device, katana_gray_trc_to_pcs,
+    katana_input_make_lut_nx3, katana_multi_dimensional_3xn_to_device,
     katana_multi_dimensional_nx3_to_pcs, katana_output_make_lut_3xn, katana_pcs_lab_v2_to_v4,
-    katana_pcs_lab_v4_to_v2, katana_prepare_inverse_lut_rgb_xyz,
+    katana_pcs_lab_v4_to_v2, katana_pcs_luminance_scale, katana_pcs_output,
+    katana_prepare_inverse_lut_rgb_xyz, katana_spectral_device_to_pcs,
+    katana_spectral_pcs_to_device,
 };
+use crate::mpe::{MultiProcessDeviceToPcs, MultiProcessPcsToDevice};
 use crate::{
     CmsError, ColorProfile, DataColorSpace, GammaLutInterpolate, Layout, LutWarehouse,
-    PointeeSizeExpressible, TransformExecutor, TransformOptions,
+    PointeeSizeExpressible, TransformExecutor, TransformOptions, TransformScratch,
 };
 use num_traits::AsPrimitive;
 
@@ -67,33 +72,11 @@ where
 {
     let mut stages: Vec<Box<dyn KatanaIntermediateStage<f32> + Send + Sync>> = Vec::new();
 
-    let initial_stage: Box<dyn KatanaInitialStage<f32, T> + Send + Sync> = match source
-        .is_matrix_shaper()
-    {
-        true => {
-            let state =
-                katana_create_rgb_lin_lut::<T, BIT_DEPTH, LINEAR_CAP>(src_layout, source, options)?;
-            stages.extend(state.stages);
-            state.initial_stage
-        }
-        false => match source.get_device_to_pcs(options.rendering_intent).ok_or(
-            CmsError::UnsupportedLutRenderingIntent(source.rendering_intent),
-        )? {
-            LutWarehouse::Lut(lut) => katana_input_make_lut_nx3::<T>(
-                src_layout,
-                src_layout.channels(),
-                lut,
-                options,
-                source.pcs,
-                BIT_DEPTH,
-            )?,
-            LutWarehouse::Multidimensional(mab) => katana_multi_dimensional_nx3_to_pcs::<T>(
-                src_layout, mab, options, source.pcs, BIT_DEPTH,
-            )?,
-        },
-    };
+    let initial_stage =
+        katana_device_to_pcs::<T, BIT_DEPTH, LINEAR_CAP>(src_layout, source, options, &mut stages)?;
 
     stages.push(katana_pcs_lab_v2_to_v4(source));
+    stages.extend(katana_pcs_luminance_scale(source, dest, options));
     if source.pcs == DataColorSpace::Lab {
         stages.push(Box::new(KatanaStageLabToXyz::default()));
     }
@@ -102,7 +85,14 @@ where
     }
     stages.push(katana_pcs_lab_v4_to_v2(dest));
 
-    let final_stage = if dest.has_pcs_to_device_lut() {
+    let final_stage = if let Some(spectral_pcs) = dest.spectral_pcs_to_device() {
+        let handler = dest
+            .spectral_pcs_handler
+            .as_ref()
+            .ok_or(CmsError::UnsupportedSpectralPcs(spectral_pcs))?;
+        let stage = handler.pcs_to_device(dest, dst_layout, options.rendering_intent)?;
+        katana_spectral_pcs_to_device::<T>(stage, dst_layout, BIT_DEPTH)
+    } else if dest.has_pcs_to_device_lut() {
         let pcs_to_device = dest
             .get_pcs_to_device(options.rendering_intent)
             .ok_or(CmsError::UnsupportedProfileConnection)?;
@@ -117,6 +107,10 @@ where
             LutWarehouse::Multidimensional(mab) => katana_multi_dimensional_3xn_to_device::<T>(
                 dst_layout, mab, options, dest.pcs, BIT_DEPTH,
             )?,
+            LutWarehouse::MultiProcess(mpe) => {
+                let stage = MultiProcessPcsToDevice::new(mpe, dst_layout.channels(), dest.pcs)?;
+                katana_float_pcs_to_device::<T>(Box::new(stage), dst_layout, BIT_DEPTH)
+            }
         }
     } else if dest.is_matrix_shaper() {
         let state = katana_prepare_inverse_lut_rgb_xyz::<T, BIT_DEPTH, GAMMA_LUT>(
@@ -141,6 +135,197 @@ where
         final_stage,
         stages,
         post_finalization,
+        src_layout,
+        dst_layout,
+        chunk_pixels: options
+            .real_time
+            .then_some(TransformScratch::REAL_TIME_PIXELS),
+    }))
+}
+
+/// Device to PCS stage of `source`, its auxiliary stages are appended to `stages`
+fn katana_device_to_pcs<
+    T: Copy
+        + Default
+        + AsPrimitive<f32>
+        + Send
+        + Sync
+        + AsPrimitive<usize>
+        + PointeeSizeExpressible
+        + GammaLutInterpolate,
+    const BIT_DEPTH: usize,
+    const LINEAR_CAP: usize,
+>(
+    src_layout: Layout,
+    source: &ColorProfile,
+    options: TransformOptions,
+    stages: &mut Vec<Box<dyn KatanaIntermediateStage<f32> + Send + Sync>>,
+) -> Result<Box<dyn KatanaInitialStage<f32, T> + Send + Sync>, CmsError>
+where
+    f32: AsPrimitive<T>,
+    u32: AsPrimitive<T>,
+    (): LutBarycentricReduction<T, u8>,
+    (): LutBarycentricReduction<T, u16>,
+{
+    if let Some(spectral_pcs) = source.spectral_device_to_pcs() {
+        let handler = source
+            .spectral_pcs_handler
+            .as_ref()
+            .ok_or(CmsError::UnsupportedSpectralPcs(spectral_pcs))?;
+        let stage = handler.device_to_pcs(source, src_layout, options.rendering_intent)?;
+        return Ok(katana_spectral_device_to_pcs::<T>(
+            stage, src_layout, BIT_DEPTH,
+        ));
+    }
+    if source.is_matrix_shaper() {
+        let state =
+            katana_create_rgb_lin_lut::<T, BIT_DEPTH, LINEAR_CAP>(src_layout, source, options)?;
+        stages.extend(state.stages);
+        return Ok(state.initial_stage);
+    }
+    let Some(device_to_pcs) = source.get_device_to_pcs(options.rendering_intent) else {
+        if source.color_space == DataColorSpace::Gray && source.gray_trc.is_some() {
+            return katana_gray_trc_to_pcs::<T>(source, src_layout, BIT_DEPTH);
+        }
+        return Err(CmsError::UnsupportedLutRenderingIntent(
+            source.rendering_intent,
+        ));
+    };
+    match device_to_pcs {
+        LutWarehouse::Lut(lut) => katana_input_make_lut_nx3::<T>(
+            src_layout,
+            src_layout.channels(),
+            lut,
+            options,
+            source.pcs,
+            BIT_DEPTH,
+        ),
+        LutWarehouse::Multidimensional(mab) => katana_multi_dimensional_nx3_to_pcs::<T>(
+            src_layout, mab, options, source.pcs, BIT_DEPTH,
+        ),
+        LutWarehouse::MultiProcess(mpe) => {
+            let stage = MultiProcessDeviceToPcs::new(mpe, src_layout.channels(), source.pcs)?;
+            Ok(katana_float_device_to_pcs::<T>(
+                Box::new(stage),
+                src_layout,
+                BIT_DEPTH,
+            ))
+        }
+    }
+}
+
+/// Device to [Layout::Xyz] or [Layout::Lab] transform, PCS is relative to D50
+/// as it would be written into destination with `source` PCS
+pub(crate) fn do_any_to_pcs<
+    T: Copy
+        + Default
+        + AsPrimitive<f32>
+        + Send
+        + Sync
+        + AsPrimitive<usize>
+        + PointeeSizeExpressible
+        + GammaLutInterpolate,
+    const BIT_DEPTH: usize,
+    const LINEAR_CAP: usize,
+>(
+    src_layout: Layout,
+    source: &ColorProfile,
+    dst_layout: Layout,
+    options: TransformOptions,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    f32: AsPrimitive<T>,
+    u32: AsPrimitive<T>,
+    (): LutBarycentricReduction<T, u8>,
+    (): LutBarycentricReduction<T, u16>,
+{
+    if !dst_layout.is_pcs() {
+        return Err(CmsError::InvalidLayout);
+    }
+    let mut stages: Vec<Box<dyn KatanaIntermediateStage<f32> + Send + Sync>> = Vec::new();
+
+    let initial_stage =
+        katana_device_to_pcs::<T, BIT_DEPTH, LINEAR_CAP>(src_layout, source, options, &mut stages)?;
+
+    stages.push(katana_pcs_lab_v2_to_v4(source));
+    stages.extend(katana_pcs_luminance_scale(
+        source,
+        &ColorProfile::new_lab(),
+        options,
+    ));
+    if source.pcs == DataColorSpace::Lab {
+        stages.push(Box::new(KatanaStageLabToXyz::default()));
+    }
+
+    Ok(Box::new(Katana::<f32, T> {
+        initial_stage,
+        final_stage: katana_pcs_output::<T>(dst_layout, BIT_DEPTH),
+        stages,
+        post_finalization: Vec::new(),
+        src_layout,
+        dst_layout,
+        chunk_pixels: options
+            .real_time
+            .then_some(TransformScratch::REAL_TIME_PIXELS),
+    }))
+}
+
+/// Device link `lut` evaluated without resampling, device values of the link output
+/// are written as is, so the link PCS acts as identity
+pub(crate) fn do_device_link<
+    T: Copy
+        + Default
+        + AsPrimitive<f32>
+        + Send
+        + Sync
+        + AsPrimitive<usize>
+        + PointeeSizeExpressible
+        + GammaLutInterpolate,
+    const BIT_DEPTH: usize,
+>(
+    src_layout: Layout,
+    link: &ColorProfile,
+    lut: &LutWarehouse,
+    dst_layout: Layout,
+    options: TransformOptions,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    f32: AsPrimitive<T>,
+{
+    let initial_stage = katana_device_link_nxm::<T>(src_layout, lut, BIT_DEPTH)?;
+    let final_stage = katana_device_output::<T>(dst_layout, link.pcs.channels(), BIT_DEPTH);
+
+    // Link color spaces are device ones, only RGB and gray layouts may carry alpha
+    let mut post_finalization: Vec<Box<dyn KatanaPostFinalizationStage<T> + Send + Sync>> =
+        Vec::new();
+    if dst_layout.channels() > link.pcs.channels() {
+        if src_layout.channels() > link.color_space.channels() {
+            post_finalization.push(Box::new(CopyAlphaStage {
+                src_layout,
+                dst_layout,
+                target_color_space: link.pcs,
+                _phantom: Default::default(),
+            }));
+        } else {
+            post_finalization.push(Box::new(InjectAlphaStage {
+                dst_layout,
+                target_color_space: link.pcs,
+                _phantom: Default::default(),
+                bit_depth: BIT_DEPTH,
+            }));
+        }
+    }
+
+    Ok(Box::new(Katana::<f32, T> {
+        initial_stage,
+        final_stage,
+        stages: Vec::new(),
+        post_finalization,
+        src_layout,
+        dst_layout,
+        chunk_pixels: options
+            .real_time
+            .then_some(TransformScratch::REAL_TIME_PIXELS),
     }))
 }
 
@@ -42,10 +42,12 @@ mod lut_transforms;
 mod mab;
 mod mab4x3;
 mod mba3x4;
+mod md_fixed;
 mod md_lut;
 mod md_luts_factory;
 #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
 mod neon;
+mod prefetch;
 mod prelude_lut_xyz_rgb;
 mod rgb2gray;
 mod rgb2gray_extended;
@@ -60,14 +62,22 @@ mod transform_lut3_to_4;
 mod transform_lut4_to_3;
 mod xyz_lab;
 
+pub(crate) use bpc::BlackPointCompensation;
 pub(crate) use gray2rgb::{make_gray_to_unfused, make_gray_to_x};
 pub(crate) use gray2rgb_extended::{make_gray_to_one_trc_extended, make_gray_to_rgb_extended};
 pub(crate) use interpolator::LutBarycentricReduction;
-pub(crate) use lut_transforms::make_lut_transform;
+pub(crate) use lut_transforms::{make_device_link_lut_transform, make_lut_transform};
+pub(crate) use md_fixed::make_fixed_any_to_any;
+#[cfg(feature = "integer_pixels")]
+pub(crate) use md_fixed::make_fixed_tabulated;
+pub(crate) use md_luts_factory::{do_any_to_any, do_any_to_pcs};
+pub(crate) use prefetch::LUT_BLOCK_SIZE;
 pub(crate) use rgb_xyz_factory::{RgbXyzFactory, RgbXyzFactoryOpt};
-pub(crate) use rgb2gray::{ToneReproductionRgbToGray, make_rgb_to_gray};
+pub(crate) use rgb2gray::{ToneReproductionRgbToGray, make_rgb_to_gray, make_rgb_to_luma};
 pub(crate) use rgb2gray_extended::make_rgb_to_gray_extended;
-pub(crate) use rgbxyz::{TransformMatrixShaper, TransformMatrixShaperOptimized};
+pub(crate) use rgbxyz::{
+    TransformMatrixShaper, TransformMatrixShaperOptimized, make_rgb_xyz_rgb_transform_scalar,
+};
 pub(crate) use rgbxyz_float::{
     TransformShaperFloatInOut, TransformShaperRgbFloat, make_rgb_xyz_rgb_transform_float,
     make_rgb_xyz_rgb_transform_float_in_out,
@@ -204,7 +204,7 @@ where
                 InterpolationMethod::Prism => {
                     self.transform_chunk(src, dst, Box::new(PrismaticNeonDouble::<GRID_SIZE> {}));
                 }
-                InterpolationMethod::Linear => {
+                InterpolationMethod::Linear | InterpolationMethod::Auto => {
                     self.transform_chunk(src, dst, Box::new(TrilinearNeonDouble::<GRID_SIZE> {}));
                 }
             }
@@ -236,7 +236,7 @@ impl Lut4x3Factory for NeonLut4x3Factory {
     {
         if options.prefer_fixed_point
             && BIT_DEPTH < 16
-            && std::arch::is_aarch64_feature_detected!("rdm")
+            && crate::equivalence::aarch64_feature_detected!("rdm")
         {
             let q: f32 = if T::FINITE {
                 ((1i32 << BIT_DEPTH as i32) - 1) as f32
@@ -210,7 +210,7 @@ where
                             Box::new(PrismaticNeonQ0_15Double::<GRID_SIZE> {}),
                         );
                     }
-                    InterpolationMethod::Linear => {
+                    InterpolationMethod::Linear | InterpolationMethod::Auto => {
                         self.transform_chunk(
                             src,
                             dst,
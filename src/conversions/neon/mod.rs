@@ -31,17 +31,23 @@ mod interpolator_q0_15;
 mod lut4_to_3;
 mod lut4_to_3_q0_15;
 mod rgb_xyz;
+#[cfg(feature = "neon-fp16")]
+mod rgb_xyz_fp16;
 mod rgb_xyz_opt;
 mod rgb_xyz_q1_30_opt;
 mod rgb_xyz_q2_13;
 mod rgb_xyz_q2_13_opt;
 mod t_lut3_to_3;
 mod t_lut3_to_3_q0_15;
+mod tetra_3i_to_any;
 
 pub(crate) use lut4_to_3::NeonLut4x3Factory;
 pub(crate) use rgb_xyz::TransformShaperRgbNeon;
+#[cfg(feature = "neon-fp16")]
+pub(crate) use rgb_xyz_fp16::{fp16_shaper_fits, make_rgb_xyz_rgb_transform_fp16};
 pub(crate) use rgb_xyz_opt::TransformShaperRgbOptNeon;
 pub(crate) use rgb_xyz_q1_30_opt::TransformShaperQ1_30NeonOpt;
 pub(crate) use rgb_xyz_q2_13::TransformShaperQ2_13Neon;
 pub(crate) use rgb_xyz_q2_13_opt::TransformShaperQ2_13NeonOpt;
 pub(crate) use t_lut3_to_3::NeonLut3x3Factory;
+pub(crate) use tetra_3i_to_any::tetra_3i_to_any_neon;
@@ -0,0 +1,340 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::neon::rgb_xyz::NeonAlignedU16;
+use crate::conversions::rgbxyz::TransformMatrixShaperOptimized;
+use crate::{CmsError, Layout, TransformExecutor};
+use std::arch::aarch64::*;
+
+/// Matrix shaper for 8 bit RGB with matrix evaluated in half precision,
+/// so one NEON register holds two pixels.
+pub(crate) struct TransformShaperRgbFp16Neon<const SRC_LAYOUT: u8, const DST_LAYOUT: u8> {
+    /// Linearization table stored as IEEE 754 half precision bits
+    pub(crate) linear: Vec<u16>,
+    /// Matrix columns duplicated for two pixels as half precision bits
+    pub(crate) matrix: [[u16; 8]; 3],
+    pub(crate) gamma: Box<[u8; 65536]>,
+    pub(crate) gamma_lut: usize,
+}
+
+/// Rounds to nearest even half precision float and returns its bits
+fn f32_to_f16_bits(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exp == 0xff {
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let e = exp - 127 + 15;
+    if e >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if e <= 0 {
+        if e < -10 {
+            return sign;
+        }
+        let m = mantissa | 0x80_0000;
+        let shift = (14 - e) as u32;
+        let half = 1u32 << (shift - 1);
+        let rem = m & ((1u32 << shift) - 1);
+        let mut r = m >> shift;
+        if rem > half || (rem == half && (r & 1) != 0) {
+            r += 1;
+        }
+        return sign | r as u16;
+    }
+    let mut r = ((e as u32) << 10) | (mantissa >> 13);
+    let rem = mantissa & 0x1fff;
+    if rem > 0x1000 || (rem == 0x1000 && (r & 1) != 0) {
+        // Carry may overflow into exponent, what is correct rounding up to the next binade or inf
+        r += 1;
+    }
+    sign | r as u16
+}
+
+fn f16_bits_to_f32(h: u16) -> f32 {
+    let sign = if h & 0x8000 != 0 { -1f32 } else { 1f32 };
+    let exp = ((h >> 10) & 0x1f) as i32;
+    let mantissa = (h & 0x3ff) as f32;
+    match exp {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f => {
+            if mantissa == 0. {
+                sign * f32::INFINITY
+            } else {
+                f32::NAN
+            }
+        }
+        _ => sign * (1. + mantissa / 1024.) * 2f32.powi(exp - 15),
+    }
+}
+
+#[inline]
+fn round_f16(x: f32) -> f32 {
+    f16_bits_to_f32(f32_to_f16_bits(x))
+}
+
+/// Checks that half precision matrix evaluation stays within 1 code value
+/// of single precision kernels for this profile.
+///
+/// Compared on a coarse lattice and per channel ramps, large or cancelling matrix
+/// coefficients will fail it and single precision kernel is used instead.
+pub(crate) fn fp16_shaper_fits<const LINEAR_CAP: usize>(
+    profile: &TransformMatrixShaperOptimized<u8, LINEAR_CAP>,
+    gamma_lut: usize,
+) -> bool {
+    if profile.linear.len() < 256 || gamma_lut > 65536 {
+        return false;
+    }
+    let m = profile.adaptation_matrix;
+    if m.v
+        .iter()
+        .flatten()
+        .any(|x| !x.is_finite() || x.abs() > 64.)
+    {
+        return false;
+    }
+    let m16 = m.v.map(|row| row.map(round_f16));
+    let scale = (gamma_lut - 1) as f32;
+    let to_index = |v: f32| v.mul_add(scale, 0.5).min(scale) as usize;
+
+    let check = |r: usize, g: usize, b: usize| -> bool {
+        let lin = [profile.linear[r], profile.linear[g], profile.linear[b]];
+        let lin16 = lin.map(round_f16);
+        for (row, row16) in m.v.iter().zip(m16.iter()) {
+            let reference = lin[2].mul_add(row[2], lin[1].mul_add(row[1], lin[0] * row[0]));
+            let mut acc = round_f16(lin16[0] * row16[0]);
+            acc = round_f16((acc as f64 + lin16[1] as f64 * row16[1] as f64) as f32);
+            acc = round_f16((acc as f64 + lin16[2] as f64 * row16[2] as f64) as f32);
+            let expected = profile.gamma[to_index(reference)];
+            let value = profile.gamma[to_index(acc)];
+            if expected.abs_diff(value) > 1 {
+                return false;
+            }
+        }
+        true
+    };
+
+    for i in 0..256usize {
+        if !check(i, 0, 0) || !check(0, i, 0) || !check(0, 0, i) || !check(i, i, i) {
+            return false;
+        }
+    }
+    for r in (0..256usize).step_by(17) {
+        for g in (0..256usize).step_by(17) {
+            for b in (0..256usize).step_by(17) {
+                if !check(r, g, b) {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+pub(crate) fn make_rgb_xyz_rgb_transform_fp16<const LINEAR_CAP: usize>(
+    src_layout: Layout,
+    dst_layout: Layout,
+    profile: TransformMatrixShaperOptimized<u8, LINEAR_CAP>,
+    gamma_lut: usize,
+) -> Result<Box<dyn TransformExecutor<u8> + Send + Sync>, CmsError> {
+    let linear = profile
+        .linear
+        .iter()
+        .map(|&x| f32_to_f16_bits(x))
+        .collect::<Vec<_>>();
+    let m = profile.adaptation_matrix;
+    let column = |i: usize| {
+        let [c0, c1, c2] = [m.v[0][i], m.v[1][i], m.v[2][i]].map(f32_to_f16_bits);
+        [c0, c1, c2, 0, c0, c1, c2, 0]
+    };
+    let matrix = [column(0), column(1), column(2)];
+    let gamma = profile.gamma;
+
+    macro_rules! create {
+        ($src: expr, $dst: expr) => {
+            Ok(Box::new(TransformShaperRgbFp16Neon::<
+                { $src as u8 },
+                { $dst as u8 },
+            > {
+                linear,
+                matrix,
+                gamma,
+                gamma_lut,
+            }))
+        };
+    }
+
+    match (src_layout, dst_layout) {
+        (Layout::Rgba, Layout::Rgba) => create!(Layout::Rgba, Layout::Rgba),
+        (Layout::Rgb, Layout::Rgba) => create!(Layout::Rgb, Layout::Rgba),
+        (Layout::Rgba, Layout::Rgb) => create!(Layout::Rgba, Layout::Rgb),
+        (Layout::Rgb, Layout::Rgb) => create!(Layout::Rgb, Layout::Rgb),
+        _ => Err(CmsError::UnsupportedProfileConnection),
+    }
+}
+
+/// Evaluates matrix for two pixels packed as `[r0 r0 r0 r0 r1 r1 r1 r1]` etc.,
+/// and returns gamma table indices `[r0 g0 b0 _ r1 g1 b1 _]`.
+#[target_feature(enable = "fp16")]
+#[inline]
+unsafe fn shape_pair(
+    r: uint16x8_t,
+    g: uint16x8_t,
+    b: uint16x8_t,
+    m: &[float16x8_t; 3],
+    v_scale: float32x4_t,
+    rnd: float32x4_t,
+) -> uint16x8_t {
+    unsafe {
+        let v = vmulq_f16(vreinterpretq_f16_u16(r), m[0]);
+        let v = vfmaq_f16(v, vreinterpretq_f16_u16(g), m[1]);
+        let v = vfmaq_f16(v, vreinterpretq_f16_u16(b), m[2]);
+
+        let lo = vfmaq_f32(rnd, vcvt_f32_f16(vget_low_f16(v)), v_scale);
+        let hi = vfmaq_f32(rnd, vcvt_f32_f16(vget_high_f16(v)), v_scale);
+        let lo = vcvtq_u32_f32(vminq_f32(lo, v_scale));
+        let hi = vcvtq_u32_f32(vminq_f32(hi, v_scale));
+        vcombine_u16(vmovn_u32(lo), vmovn_u32(hi))
+    }
+}
+
+impl<const SRC_LAYOUT: u8, const DST_LAYOUT: u8>
+    TransformShaperRgbFp16Neon<SRC_LAYOUT, DST_LAYOUT>
+{
+    #[target_feature(enable = "fp16")]
+    unsafe fn transform_impl(&self, src: &[u8], dst: &mut [u8]) -> Result<(), CmsError> {
+        let src_cn = Layout::from(SRC_LAYOUT);
+        let dst_cn = Layout::from(DST_LAYOUT);
+        let src_channels = src_cn.channels();
+        let dst_channels = dst_cn.channels();
+
+        if src.len() / src_channels != dst.len() / dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        if src.len() % src_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if dst.len() % dst_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+
+        // safety precondition for linearization table
+        assert!(self.linear.len() >= 256);
+        let lut_lin = &self.linear;
+        let scale = (self.gamma_lut - 1) as f32;
+        let mut temporary = NeonAlignedU16([0; 8]);
+
+        unsafe {
+            let m = [
+                vreinterpretq_f16_u16(vld1q_u16(self.matrix[0].as_ptr())),
+                vreinterpretq_f16_u16(vld1q_u16(self.matrix[1].as_ptr())),
+                vreinterpretq_f16_u16(vld1q_u16(self.matrix[2].as_ptr())),
+            ];
+            let v_scale = vdupq_n_f32(scale);
+            let rnd = vdupq_n_f32(0.5);
+
+            let load = |v0: u8, v1: u8| {
+                vcombine_u16(
+                    vdup_n_u16(*lut_lin.get_unchecked(v0 as usize)),
+                    vdup_n_u16(*lut_lin.get_unchecked(v1 as usize)),
+                )
+            };
+
+            let src_chunks = src.chunks_exact(src_channels * 2);
+            let src_remainder = src_chunks.remainder();
+            let mut dst_chunks = dst.chunks_exact_mut(dst_channels * 2);
+
+            for (src, dst) in src_chunks.zip(&mut dst_chunks) {
+                let r = load(src[src_cn.r_i()], src[src_cn.r_i() + src_channels]);
+                let g = load(src[src_cn.g_i()], src[src_cn.g_i() + src_channels]);
+                let b = load(src[src_cn.b_i()], src[src_cn.b_i() + src_channels]);
+
+                let v = shape_pair(r, g, b, &m, v_scale, rnd);
+                vst1q_u16(temporary.0.as_mut_ptr(), v);
+
+                dst[dst_cn.r_i()] = self.gamma[temporary.0[0] as usize];
+                dst[dst_cn.g_i()] = self.gamma[temporary.0[1] as usize];
+                dst[dst_cn.b_i()] = self.gamma[temporary.0[2] as usize];
+                dst[dst_cn.r_i() + dst_channels] = self.gamma[temporary.0[4] as usize];
+                dst[dst_cn.g_i() + dst_channels] = self.gamma[temporary.0[5] as usize];
+                dst[dst_cn.b_i() + dst_channels] = self.gamma[temporary.0[6] as usize];
+                if dst_channels == 4 {
+                    dst[dst_cn.a_i()] = if src_channels == 4 {
+                        src[src_cn.a_i()]
+                    } else {
+                        255
+                    };
+                    dst[dst_cn.a_i() + dst_channels] = if src_channels == 4 {
+                        src[src_cn.a_i() + src_channels]
+                    } else {
+                        255
+                    };
+                }
+            }
+
+            let dst_remainder = dst_chunks.into_remainder();
+            if let (Some(src), Some(dst)) = (
+                src_remainder.get(..src_channels),
+                dst_remainder.get_mut(..dst_channels),
+            ) {
+                let r = load(src[src_cn.r_i()], src[src_cn.r_i()]);
+                let g = load(src[src_cn.g_i()], src[src_cn.g_i()]);
+                let b = load(src[src_cn.b_i()], src[src_cn.b_i()]);
+
+                let v = shape_pair(r, g, b, &m, v_scale, rnd);
+                vst1q_u16(temporary.0.as_mut_ptr(), v);
+
+                dst[dst_cn.r_i()] = self.gamma[temporary.0[0] as usize];
+                dst[dst_cn.g_i()] = self.gamma[temporary.0[1] as usize];
+                dst[dst_cn.b_i()] = self.gamma[temporary.0[2] as usize];
+                if dst_channels == 4 {
+                    dst[dst_cn.a_i()] = if src_channels == 4 {
+                        src[src_cn.a_i()]
+                    } else {
+                        255
+                    };
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<const SRC_LAYOUT: u8, const DST_LAYOUT: u8> TransformExecutor<u8>
+    for TransformShaperRgbFp16Neon<SRC_LAYOUT, DST_LAYOUT>
+{
+    fn transform(&self, src: &[u8], dst: &mut [u8]) -> Result<(), CmsError> {
+        unsafe {
+            assert!(std::arch::is_aarch64_feature_detected!("fp16"));
+            self.transform_impl(src, dst)
+        }
+    }
+}
@@ -33,6 +33,7 @@ use crate::conversions::neon::interpolator::*;
 use crate::conversions::neon::interpolator_q0_15::NeonAlignedI16x4;
 use crate::conversions::neon::rgb_xyz::NeonAlignedF32;
 use crate::conversions::neon::t_lut3_to_3_q0_15::TransformLut3x3NeonQ0_15;
+use crate::conversions::prefetch::prefetch_lut3;
 use crate::transform::PointeeSizeExpressible;
 use crate::{
     BarycentricWeightScale, CmsError, DataColorSpace, InterpolationMethod, Layout,
@@ -59,6 +60,7 @@ struct TransformLut3x3Neon<
     weights: Box<[BarycentricWeight<f32>; BINS]>,
     color_space: DataColorSpace,
     is_linear: bool,
+    block_size: usize,
 }
 
 impl<
@@ -93,48 +95,69 @@ where
             let value_scale = vdupq_n_f32(((1 << BIT_DEPTH) - 1) as f32);
             let max_value = ((1u32 << BIT_DEPTH) - 1).as_();
 
-            for (src, dst) in src
-                .chunks_exact(src_channels)
-                .zip(dst.chunks_exact_mut(dst_channels))
+            let block_size = self.block_size.max(1);
+            let src_blocks = src.chunks(src_channels * block_size);
+
+            for (i, (block, dst)) in src_blocks
+                .clone()
+                .zip(dst.chunks_mut(dst_channels * block_size))
+                .enumerate()
             {
-                let x = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.r_i()],
-                );
-                let y = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.g_i()],
-                );
-                let z = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.b_i()],
-                );
+                if let Some(next_block) = src_blocks.clone().nth(i + 1).filter(|_| block_size > 1) {
+                    prefetch_lut3::<T, U, _, NeonAlignedF32, GRID_SIZE, BIT_DEPTH, BARYCENTRIC_BINS>(
+                        next_block,
+                        src_cn,
+                        self.weights.as_slice(),
+                        &self.lut,
+                        1,
+                    );
+                }
+                for (src, dst) in block
+                    .chunks_exact(src_channels)
+                    .zip(dst.chunks_exact_mut(dst_channels))
+                {
+                    let x = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.r_i()]);
+                    let y = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.g_i()]);
+                    let z = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.b_i()]);
 
-                let a = if src_channels == 4 {
-                    src[src_cn.a_i()]
-                } else {
-                    max_value
-                };
+                    let a = if src_channels == 4 {
+                        src[src_cn.a_i()]
+                    } else {
+                        max_value
+                    };
 
-                let v = interpolator.inter3_neon(
-                    &self.lut,
-                    x.as_(),
-                    y.as_(),
-                    z.as_(),
-                    self.weights.as_slice(),
-                );
-                if T::FINITE {
-                    let mut r = vfmaq_f32(vdupq_n_f32(0.5f32), v.v, value_scale);
-                    r = vminq_f32(r, value_scale);
-                    let jvx = vcvtaq_u32_f32(r);
+                    let v = interpolator.inter3_neon(
+                        &self.lut,
+                        x.as_(),
+                        y.as_(),
+                        z.as_(),
+                        self.weights.as_slice(),
+                    );
+                    if T::FINITE {
+                        let mut r = vfmaq_f32(vdupq_n_f32(0.5f32), v.v, value_scale);
+                        r = vminq_f32(r, value_scale);
+                        let jvx = vcvtaq_u32_f32(r);
 
-                    dst[dst_cn.r_i()] = vgetq_lane_u32::<0>(jvx).as_();
-                    dst[dst_cn.g_i()] = vgetq_lane_u32::<1>(jvx).as_();
-                    dst[dst_cn.b_i()] = vgetq_lane_u32::<2>(jvx).as_();
-                } else {
-                    dst[dst_cn.r_i()] = vgetq_lane_f32::<0>(v.v).as_();
-                    dst[dst_cn.g_i()] = vgetq_lane_f32::<1>(v.v).as_();
-                    dst[dst_cn.b_i()] = vgetq_lane_f32::<2>(v.v).as_();
-                }
-                if dst_channels == 4 {
-                    dst[dst_cn.a_i()] = a;
+                        dst[dst_cn.r_i()] = vgetq_lane_u32::<0>(jvx).as_();
+                        dst[dst_cn.g_i()] = vgetq_lane_u32::<1>(jvx).as_();
+                        dst[dst_cn.b_i()] = vgetq_lane_u32::<2>(jvx).as_();
+                    } else {
+                        dst[dst_cn.r_i()] = vgetq_lane_f32::<0>(v.v).as_();
+                        dst[dst_cn.g_i()] = vgetq_lane_f32::<1>(v.v).as_();
+                        dst[dst_cn.b_i()] = vgetq_lane_f32::<2>(v.v).as_();
+                    }
+                    if dst_channels == 4 {
+                        dst[dst_cn.a_i()] = a;
+                    }
                 }
             }
         }
@@ -203,7 +226,7 @@ where
                 InterpolationMethod::Prism => {
                     self.transform_chunk(src, dst, Box::new(PrismaticNeon::<GRID_SIZE> {}));
                 }
-                InterpolationMethod::Linear => {
+                InterpolationMethod::Linear | InterpolationMethod::Auto => {
                     self.transform_chunk(src, dst, Box::new(TrilinearNeon::<GRID_SIZE> {}));
                 }
             }
@@ -236,7 +259,7 @@ impl Lut3x3Factory for NeonLut3x3Factory {
     {
         if options.prefer_fixed_point
             && BIT_DEPTH < 16
-            && std::arch::is_aarch64_feature_detected!("rdm")
+            && crate::equivalence::aarch64_feature_detected!("rdm")
         {
             let q: f32 = if T::FINITE {
                 ((1i32 << BIT_DEPTH as i32) - 1) as f32
@@ -272,6 +295,7 @@ impl Lut3x3Factory for NeonLut3x3Factory {
                     weights: BarycentricWeight::<i16>::create_ranged_256::<GRID_SIZE>(),
                     color_space,
                     is_linear,
+                    block_size: options.lut_block_size,
                 }),
                 #[cfg(feature = "options")]
                 BarycentricWeightScale::High => Box::new(TransformLut3x3NeonQ0_15::<
@@ -291,6 +315,7 @@ impl Lut3x3Factory for NeonLut3x3Factory {
                     weights: BarycentricWeight::<i16>::create_binned::<GRID_SIZE, 65536>(),
                     color_space,
                     is_linear,
+                    block_size: options.lut_block_size,
                 }),
             };
         }
@@ -316,6 +341,7 @@ impl Lut3x3Factory for NeonLut3x3Factory {
                 weights: BarycentricWeight::<f32>::create_ranged_256::<GRID_SIZE>(),
                 color_space,
                 is_linear,
+                block_size: options.lut_block_size,
             }),
             #[cfg(feature = "options")]
             BarycentricWeightScale::High => Box::new(TransformLut3x3Neon::<
@@ -335,6 +361,7 @@ impl Lut3x3Factory for NeonLut3x3Factory {
                 weights: BarycentricWeight::<f32>::create_binned::<GRID_SIZE, 65536>(),
                 color_space,
                 is_linear,
+                block_size: options.lut_block_size,
             }),
         }
     }
@@ -29,6 +29,7 @@
 use crate::conversions::LutBarycentricReduction;
 use crate::conversions::interpolator::BarycentricWeight;
 use crate::conversions::neon::interpolator_q0_15::*;
+use crate::conversions::prefetch::prefetch_lut3;
 use crate::transform::PointeeSizeExpressible;
 use crate::{CmsError, DataColorSpace, InterpolationMethod, Layout, TransformExecutor};
 use num_traits::AsPrimitive;
@@ -52,6 +53,7 @@ pub(crate) struct TransformLut3x3NeonQ0_15<
     pub(crate) weights: Box<[BarycentricWeight<i16>; BINS]>,
     pub(crate) color_space: DataColorSpace,
     pub(crate) is_linear: bool,
+    pub(crate) block_size: usize,
 }
 
 impl<
@@ -103,48 +105,71 @@ where
                 vdup_n_s16(((1i32 << 14i32) - 1) as i16)
             };
 
-            for (src, dst) in src
-                .chunks_exact(src_channels)
-                .zip(dst.chunks_exact_mut(dst_channels))
+            let block_size = self.block_size.max(1);
+            let src_blocks = src.chunks(src_channels * block_size);
+
+            for (i, (block, dst)) in src_blocks
+                .clone()
+                .zip(dst.chunks_mut(dst_channels * block_size))
+                .enumerate()
             {
-                let x = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.r_i()],
-                );
-                let y = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.g_i()],
-                );
-                let z = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.b_i()],
-                );
+                if let Some(next_block) = src_blocks.clone().nth(i + 1).filter(|_| block_size > 1) {
+                    prefetch_lut3::<
+                        T,
+                        U,
+                        _,
+                        NeonAlignedI16x4,
+                        GRID_SIZE,
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(next_block, src_cn, self.weights.as_slice(), &self.lut, 1);
+                }
+                for (src, dst) in block
+                    .chunks_exact(src_channels)
+                    .zip(dst.chunks_exact_mut(dst_channels))
+                {
+                    let x = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.r_i()]);
+                    let y = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.g_i()]);
+                    let z = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.b_i()]);
 
-                let a = if src_channels == 4 {
-                    src[src_cn.a_i()]
-                } else {
-                    max_value
-                };
+                    let a = if src_channels == 4 {
+                        src[src_cn.a_i()]
+                    } else {
+                        max_value
+                    };
 
-                let v = interpolator.inter3_neon(
-                    &self.lut,
-                    x.as_(),
-                    y.as_(),
-                    z.as_(),
-                    self.weights.as_slice(),
-                );
-                if T::FINITE {
-                    let mut o = vmax_s16(v.v, vdup_n_s16(0));
-                    o = vmin_s16(o, v_max_scale);
-                    dst[dst_cn.r_i()] = (vget_lane_s16::<0>(o) as u32).as_();
-                    dst[dst_cn.g_i()] = (vget_lane_s16::<1>(o) as u32).as_();
-                    dst[dst_cn.b_i()] = (vget_lane_s16::<2>(o) as u32).as_();
-                } else {
-                    let o = vcvtq_f32_s32(vmovl_s16(v.v));
-                    let r = vmulq_f32(o, f_value_scale);
-                    dst[dst_cn.r_i()] = vgetq_lane_f32::<0>(r).as_();
-                    dst[dst_cn.g_i()] = vgetq_lane_f32::<1>(r).as_();
-                    dst[dst_cn.b_i()] = vgetq_lane_f32::<2>(r).as_();
-                }
-                if dst_channels == 4 {
-                    dst[dst_cn.a_i()] = a;
+                    let v = interpolator.inter3_neon(
+                        &self.lut,
+                        x.as_(),
+                        y.as_(),
+                        z.as_(),
+                        self.weights.as_slice(),
+                    );
+                    if T::FINITE {
+                        let mut o = vmax_s16(v.v, vdup_n_s16(0));
+                        o = vmin_s16(o, v_max_scale);
+                        dst[dst_cn.r_i()] = (vget_lane_s16::<0>(o) as u32).as_();
+                        dst[dst_cn.g_i()] = (vget_lane_s16::<1>(o) as u32).as_();
+                        dst[dst_cn.b_i()] = (vget_lane_s16::<2>(o) as u32).as_();
+                    } else {
+                        let o = vcvtq_f32_s32(vmovl_s16(v.v));
+                        let r = vmulq_f32(o, f_value_scale);
+                        dst[dst_cn.r_i()] = vgetq_lane_f32::<0>(r).as_();
+                        dst[dst_cn.g_i()] = vgetq_lane_f32::<1>(r).as_();
+                        dst[dst_cn.b_i()] = vgetq_lane_f32::<2>(r).as_();
+                    }
+                    if dst_channels == 4 {
+                        dst[dst_cn.a_i()] = a;
+                    }
                 }
             }
         }
@@ -226,7 +251,7 @@ where
                             Box::new(PrismaticNeonQ0_15::<GRID_SIZE> {}),
                         );
                     }
-                    InterpolationMethod::Linear => {
+                    InterpolationMethod::Linear | InterpolationMethod::Auto => {
                         self.transform_chunk(
                             src,
                             dst,
@@ -0,0 +1,116 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::Layout;
+use crate::conversions::LutBarycentricReduction;
+use crate::conversions::interpolator::BarycentricWeight;
+use num_traits::AsPrimitive;
+
+/// Default count of pixels processed between CLUT prefetches
+pub(crate) const LUT_BLOCK_SIZE: usize = 8;
+
+#[inline(always)]
+fn prefetch_node<E, const GRID_SIZE: usize>(
+    cube: &[E],
+    x: i32,
+    y: i32,
+    z: i32,
+    node_stride: usize,
+) {
+    let offset = (x as u32 * (GRID_SIZE as u32 * GRID_SIZE as u32)
+        + y as u32 * GRID_SIZE as u32
+        + z as u32) as usize
+        * node_stride;
+    let Some(node) = cube.get(offset) else {
+        return;
+    };
+    #[cfg(all(target_arch = "x86_64", feature = "sse"))]
+    unsafe {
+        use std::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+        _mm_prefetch::<_MM_HINT_T0>(node as *const E as *const i8);
+    }
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
+    unsafe {
+        std::arch::asm!(
+            "prfm pldl1keep, [{0}]",
+            in(reg) node as *const E,
+            options(nostack, preserves_flags, readonly)
+        );
+    }
+    #[cfg(not(any(
+        all(target_arch = "x86_64", feature = "sse"),
+        all(target_arch = "aarch64", target_feature = "neon", feature = "neon")
+    )))]
+    {
+        _ = node;
+    }
+}
+
+/// Requests nearest and farthest CLUT nodes for every pixel in the block.
+///
+/// Memory access dominates LUT transforms, prefetching a block ahead
+/// lets loads overlap with interpolation of the current one.
+#[inline(always)]
+pub(crate) fn prefetch_lut3<
+    T: Copy,
+    U: AsPrimitive<usize>,
+    W,
+    E,
+    const GRID_SIZE: usize,
+    const BIT_DEPTH: usize,
+    const BARYCENTRIC_BINS: usize,
+>(
+    src: &[T],
+    src_layout: Layout,
+    weights: &[BarycentricWeight<W>],
+    cube: &[E],
+    node_stride: usize,
+) where
+    (): LutBarycentricReduction<T, U>,
+{
+    for src in src.chunks_exact(src_layout.channels()) {
+        let x = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+            src[src_layout.r_i()],
+        );
+        let y = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+            src[src_layout.g_i()],
+        );
+        let z = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+            src[src_layout.b_i()],
+        );
+        let (Some(x), Some(y), Some(z)) = (
+            weights.get(x.as_()),
+            weights.get(y.as_()),
+            weights.get(z.as_()),
+        ) else {
+            continue;
+        };
+        prefetch_node::<E, GRID_SIZE>(cube, x.x, y.x, z.x, node_stride);
+        prefetch_node::<E, GRID_SIZE>(cube, x.x_n, y.x_n, z.x_n, node_stride);
+    }
+}
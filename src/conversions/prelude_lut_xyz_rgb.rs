@@ -269,7 +269,7 @@ where
     u32: AsPrimitive<T>,
 {
     if !T::FINITE {
-        if let Some(extended_gamma) = dest.try_extended_gamma_evaluator() {
+        if let Some(extended_gamma) = dest.try_extended_gamma_evaluator(options.curve_extension) {
             let xyz_to_rgb = dest.rgb_to_xyz_matrix().inverse();
 
             let mut matrices = vec![Matrix3f {
@@ -187,3 +187,132 @@ where
         Ok(())
     }
 }
+
+#[derive(Clone)]
+struct TransformRgbToLumaExecutor<T, const SRC_LAYOUT: u8, const DST_LAYOUT: u8> {
+    weights: [f32; 3],
+    bit_depth: usize,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+/// Computes luma directly on encoded values, when source and destination share transfer curve
+pub(crate) fn make_rgb_to_luma<
+    T: Copy + Default + PointeeSizeExpressible + AsPrimitive<f32> + Send + Sync + 'static,
+>(
+    src_layout: Layout,
+    dst_layout: Layout,
+    weights: [f32; 3],
+    bit_depth: usize,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    u32: AsPrimitive<T>,
+    f32: AsPrimitive<T>,
+{
+    match src_layout {
+        Layout::Rgb => match dst_layout {
+            Layout::Gray => Ok(Box::new(TransformRgbToLumaExecutor::<
+                T,
+                { Layout::Rgb as u8 },
+                { Layout::Gray as u8 },
+            > {
+                weights,
+                bit_depth,
+                _phantom: std::marker::PhantomData,
+            })),
+            Layout::GrayAlpha => Ok(Box::new(TransformRgbToLumaExecutor::<
+                T,
+                { Layout::Rgb as u8 },
+                { Layout::GrayAlpha as u8 },
+            > {
+                weights,
+                bit_depth,
+                _phantom: std::marker::PhantomData,
+            })),
+            _ => Err(CmsError::UnsupportedProfileConnection),
+        },
+        Layout::Rgba => match dst_layout {
+            Layout::Gray => Ok(Box::new(TransformRgbToLumaExecutor::<
+                T,
+                { Layout::Rgba as u8 },
+                { Layout::Gray as u8 },
+            > {
+                weights,
+                bit_depth,
+                _phantom: std::marker::PhantomData,
+            })),
+            Layout::GrayAlpha => Ok(Box::new(TransformRgbToLumaExecutor::<
+                T,
+                { Layout::Rgba as u8 },
+                { Layout::GrayAlpha as u8 },
+            > {
+                weights,
+                bit_depth,
+                _phantom: std::marker::PhantomData,
+            })),
+            _ => Err(CmsError::UnsupportedProfileConnection),
+        },
+        _ => Err(CmsError::UnsupportedProfileConnection),
+    }
+}
+
+impl<
+    T: Copy + Default + PointeeSizeExpressible + AsPrimitive<f32> + 'static,
+    const SRC_LAYOUT: u8,
+    const DST_LAYOUT: u8,
+> TransformExecutor<T> for TransformRgbToLumaExecutor<T, SRC_LAYOUT, DST_LAYOUT>
+where
+    u32: AsPrimitive<T>,
+    f32: AsPrimitive<T>,
+{
+    fn transform(&self, src: &[T], dst: &mut [T]) -> Result<(), CmsError> {
+        let src_cn = Layout::from(SRC_LAYOUT);
+        let dst_cn = Layout::from(DST_LAYOUT);
+        let src_channels = src_cn.channels();
+        let dst_channels = dst_cn.channels();
+
+        if src.len() / src_channels != dst.len() / dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        if src.len() % src_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if dst.len() % dst_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+
+        let max_value: T = ((1u32 << self.bit_depth) - 1).as_();
+        let max_f: f32 = max_value.as_();
+        let (bias, min_f, max_f) = if T::FINITE {
+            (0.5, 0., max_f)
+        } else {
+            (0., f32::MIN, f32::MAX)
+        };
+
+        for (src, dst) in src
+            .chunks_exact(src_channels)
+            .zip(dst.chunks_exact_mut(dst_channels))
+        {
+            let r: f32 = src[src_cn.r_i()].as_();
+            let g: f32 = src[src_cn.g_i()].as_();
+            let b: f32 = src[src_cn.b_i()].as_();
+            let a = if src_channels == 4 {
+                src[src_cn.a_i()]
+            } else {
+                max_value
+            };
+            let luma = mlaf(
+                mlaf(self.weights[0] * r, self.weights[1], g),
+                self.weights[2],
+                b,
+            )
+            .min(max_f)
+            .max(min_f);
+            dst[0] = (luma + bias).as_();
+            if dst_channels == 2 {
+                dst[1] = a;
+            }
+        }
+
+        Ok(())
+    }
+}
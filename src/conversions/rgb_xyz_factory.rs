@@ -31,7 +31,8 @@ use crate::conversions::rgbxyz::{
     TransformMatrixShaperOptimized, make_rgb_xyz_rgb_transform, make_rgb_xyz_rgb_transform_opt,
 };
 use crate::conversions::rgbxyz_fixed::{make_rgb_xyz_q2_13, make_rgb_xyz_q2_13_opt};
-use crate::{CmsError, Layout, TransformExecutor, TransformOptions};
+use crate::transform::PointeeSizeExpressible;
+use crate::{CmsError, FixedPointFormat, Layout, Matrix3f, TransformExecutor, TransformOptions};
 use num_traits::AsPrimitive;
 
 const FIXED_POINT_SCALE: i32 = 13; // Q2.13;
@@ -58,6 +59,221 @@ pub(crate) trait RgbXyzFactoryOpt<T: Clone + AsPrimitive<usize> + Default> {
     ) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>;
 }
 
+/// Reports fixed point format of the underlying executor
+struct FixedPointExecutor<T> {
+    executor: Box<dyn TransformExecutor<T> + Send + Sync>,
+    format: FixedPointFormat,
+}
+
+impl<T: Copy + Default> TransformExecutor<T> for FixedPointExecutor<T> {
+    fn transform(&self, src: &[T], dst: &mut [T]) -> Result<(), CmsError> {
+        self.executor.transform(src, dst)
+    }
+
+    fn fixed_point_format(&self) -> Option<FixedPointFormat> {
+        Some(self.format)
+    }
+}
+
+fn with_format<T: Copy + Default + 'static>(
+    executor: Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>,
+    format: FixedPointFormat,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError> {
+    executor.map(|executor| {
+        Box::new(FixedPointExecutor { executor, format })
+            as Box<dyn TransformExecutor<T> + Send + Sync>
+    })
+}
+
+/// Forced format must be able to hold adaptation matrix without saturation
+fn check_fixed_point_range(matrix: Matrix3f, format: FixedPointFormat) -> Result<(), CmsError> {
+    let scale = (1i64 << format.fractional_bits()) as f32;
+    let (min, max) = if format == FixedPointFormat::Q1_30 {
+        (i32::MIN as f32, i32::MAX as f32)
+    } else {
+        (i16::MIN as f32, i16::MAX as f32)
+    };
+    if matrix
+        .v
+        .iter()
+        .flatten()
+        .any(|&x| !(min..=max).contains(&(x * scale).round()))
+    {
+        return Err(CmsError::OverflowingError);
+    }
+    Ok(())
+}
+
+fn make_fixed_point<
+    T: Copy + Send + Sync + AsPrimitive<usize> + Default + PointeeSizeExpressible,
+    const LINEAR_CAP: usize,
+    const PRECISION: i32,
+>(
+    src_layout: Layout,
+    dst_layout: Layout,
+    profile: TransformMatrixShaper<T, LINEAR_CAP>,
+    gamma_lut: usize,
+    bit_depth: usize,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    u32: AsPrimitive<T>,
+{
+    #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+    {
+        use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx2;
+        if crate::equivalence::x86_feature_detected!("avx2") {
+            return make_rgb_xyz_q2_13_transform_avx2::<T, LINEAR_CAP, PRECISION>(
+                src_layout, dst_layout, profile, gamma_lut, bit_depth,
+            );
+        }
+    }
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+    {
+        use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_sse_41;
+        if crate::equivalence::x86_feature_detected!("sse4.1") {
+            return make_rgb_xyz_q2_13_transform_sse_41::<T, LINEAR_CAP, PRECISION>(
+                src_layout, dst_layout, profile, gamma_lut, bit_depth,
+            );
+        }
+    }
+    make_rgb_xyz_q2_13::<T, LINEAR_CAP, PRECISION>(
+        src_layout, dst_layout, profile, gamma_lut, bit_depth,
+    )
+}
+
+fn make_fixed_point_opt<
+    T: Copy + Send + Sync + AsPrimitive<usize> + Default + PointeeSizeExpressible,
+    const LINEAR_CAP: usize,
+    const PRECISION: i32,
+>(
+    src_layout: Layout,
+    dst_layout: Layout,
+    profile: TransformMatrixShaperOptimized<T, LINEAR_CAP>,
+    gamma_lut: usize,
+    bit_depth: usize,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    u32: AsPrimitive<T>,
+{
+    #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+    {
+        use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx2_opt;
+        if crate::equivalence::x86_feature_detected!("avx2") {
+            return make_rgb_xyz_q2_13_transform_avx2_opt::<T, LINEAR_CAP, PRECISION>(
+                src_layout, dst_layout, profile, gamma_lut, bit_depth,
+            );
+        }
+    }
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+    {
+        use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_sse_41_opt;
+        if crate::equivalence::x86_feature_detected!("sse4.1") {
+            return make_rgb_xyz_q2_13_transform_sse_41_opt::<T, LINEAR_CAP, PRECISION>(
+                src_layout, dst_layout, profile, gamma_lut, bit_depth,
+            );
+        }
+    }
+    make_rgb_xyz_q2_13_opt::<T, LINEAR_CAP, PRECISION>(
+        src_layout, dst_layout, profile, gamma_lut, bit_depth,
+    )
+}
+
+/// Creates transform in exactly requested fixed point format
+fn make_forced_fixed_point<
+    T: Copy + Send + Sync + AsPrimitive<usize> + Default + PointeeSizeExpressible + 'static,
+    const LINEAR_CAP: usize,
+>(
+    format: FixedPointFormat,
+    src_layout: Layout,
+    dst_layout: Layout,
+    profile: TransformMatrixShaper<T, LINEAR_CAP>,
+    gamma_lut: usize,
+    bit_depth: usize,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    u32: AsPrimitive<T>,
+{
+    check_fixed_point_range(profile.adaptation_matrix, format)?;
+    let executor = match format {
+        FixedPointFormat::Q2_13 => make_fixed_point::<T, LINEAR_CAP, 13>(
+            src_layout, dst_layout, profile, gamma_lut, bit_depth,
+        ),
+        #[cfg(feature = "options")]
+        FixedPointFormat::Q4_12 => make_fixed_point::<T, LINEAR_CAP, 12>(
+            src_layout, dst_layout, profile, gamma_lut, bit_depth,
+        ),
+        #[cfg(feature = "options")]
+        FixedPointFormat::Q2_14 => make_fixed_point::<T, LINEAR_CAP, 14>(
+            src_layout, dst_layout, profile, gamma_lut, bit_depth,
+        ),
+        FixedPointFormat::Q1_30 => Err(CmsError::UnsupportedFixedPointFormat(format)),
+    };
+    with_format(executor, format)
+}
+
+/// Creates transform in exactly requested fixed point format
+fn make_forced_fixed_point_opt<
+    T: Copy + Send + Sync + AsPrimitive<usize> + Default + PointeeSizeExpressible + 'static,
+    const LINEAR_CAP: usize,
+>(
+    format: FixedPointFormat,
+    src_layout: Layout,
+    dst_layout: Layout,
+    profile: TransformMatrixShaperOptimized<T, LINEAR_CAP>,
+    gamma_lut: usize,
+    bit_depth: usize,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    u32: AsPrimitive<T>,
+{
+    check_fixed_point_range(profile.adaptation_matrix, format)?;
+    let executor = match format {
+        FixedPointFormat::Q2_13 => make_fixed_point_opt::<T, LINEAR_CAP, 13>(
+            src_layout, dst_layout, profile, gamma_lut, bit_depth,
+        ),
+        #[cfg(feature = "options")]
+        FixedPointFormat::Q4_12 => make_fixed_point_opt::<T, LINEAR_CAP, 12>(
+            src_layout, dst_layout, profile, gamma_lut, bit_depth,
+        ),
+        #[cfg(feature = "options")]
+        FixedPointFormat::Q2_14 => make_fixed_point_opt::<T, LINEAR_CAP, 14>(
+            src_layout, dst_layout, profile, gamma_lut, bit_depth,
+        ),
+        FixedPointFormat::Q1_30 => make_forced_q1_30_opt::<T, LINEAR_CAP>(
+            src_layout, dst_layout, profile, gamma_lut, bit_depth,
+        ),
+    };
+    with_format(executor, format)
+}
+
+#[allow(unused_variables)]
+fn make_forced_q1_30_opt<
+    T: Copy + Send + Sync + AsPrimitive<usize> + Default + PointeeSizeExpressible,
+    const LINEAR_CAP: usize,
+>(
+    src_layout: Layout,
+    dst_layout: Layout,
+    profile: TransformMatrixShaperOptimized<T, LINEAR_CAP>,
+    gamma_lut: usize,
+    bit_depth: usize,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    u32: AsPrimitive<T>,
+{
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
+    {
+        if crate::equivalence::aarch64_feature_detected!("rdm") {
+            use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q1_30_opt;
+            return make_rgb_xyz_q1_30_opt::<T, LINEAR_CAP, 30>(
+                src_layout, dst_layout, profile, gamma_lut, bit_depth,
+            );
+        }
+    }
+    Err(CmsError::UnsupportedFixedPointFormat(
+        FixedPointFormat::Q1_30,
+    ))
+}
+
 impl RgbXyzFactory<u16> for u16 {
     fn make_transform<const LINEAR_CAP: usize, const GAMMA_LUT: usize, const BIT_DEPTH: usize>(
         src_layout: Layout,
@@ -65,29 +281,46 @@ impl RgbXyzFactory<u16> for u16 {
         profile: TransformMatrixShaper<u16, LINEAR_CAP>,
         transform_options: TransformOptions,
     ) -> Result<Box<dyn TransformExecutor<u16> + Send + Sync>, CmsError> {
+        if let Some(format) = transform_options.fixed_point_format {
+            if BIT_DEPTH >= 16 {
+                return Err(CmsError::UnsupportedFixedPointFormat(format));
+            }
+            return make_forced_fixed_point::<u16, LINEAR_CAP>(
+                format, src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+            );
+        }
         if BIT_DEPTH < 16 && transform_options.prefer_fixed_point {
             #[cfg(all(target_arch = "x86_64", feature = "avx"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx2;
-                if std::arch::is_x86_feature_detected!("avx2") {
-                    return make_rgb_xyz_q2_13_transform_avx2::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                if crate::equivalence::x86_feature_detected!("avx2") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_avx2::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_sse_41;
-                if std::arch::is_x86_feature_detected!("sse4.1") {
-                    return make_rgb_xyz_q2_13_transform_sse_41::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                if crate::equivalence::x86_feature_detected!("sse4.1") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_sse_41::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
             {
-                return make_rgb_xyz_q2_13::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
-                    src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                return with_format(
+                    make_rgb_xyz_q2_13::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
+                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                    ),
+                    FixedPointFormat::Q2_13,
                 );
             }
         }
@@ -104,29 +337,43 @@ impl RgbXyzFactory<f32> for f32 {
         profile: TransformMatrixShaper<f32, LINEAR_CAP>,
         transform_options: TransformOptions,
     ) -> Result<Box<dyn TransformExecutor<f32> + Send + Sync>, CmsError> {
+        if let Some(format) = transform_options.fixed_point_format {
+            return make_forced_fixed_point::<f32, LINEAR_CAP>(
+                format, src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+            );
+        }
         if transform_options.prefer_fixed_point {
             #[cfg(all(target_arch = "x86_64", feature = "avx"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx2;
-                if std::arch::is_x86_feature_detected!("avx2") {
-                    return make_rgb_xyz_q2_13_transform_avx2::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                if crate::equivalence::x86_feature_detected!("avx2") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_avx2::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_sse_41;
-                if std::arch::is_x86_feature_detected!("sse4.1") {
-                    return make_rgb_xyz_q2_13_transform_sse_41::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                if crate::equivalence::x86_feature_detected!("sse4.1") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_sse_41::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
             {
-                return make_rgb_xyz_q2_13::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
-                    src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                return with_format(
+                    make_rgb_xyz_q2_13::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
+                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                    ),
+                    FixedPointFormat::Q2_13,
                 );
             }
         }
@@ -141,8 +388,11 @@ impl RgbXyzFactory<f64> for f64 {
         src_layout: Layout,
         dst_layout: Layout,
         profile: TransformMatrixShaper<f64, LINEAR_CAP>,
-        _: TransformOptions,
+        transform_options: TransformOptions,
     ) -> Result<Box<dyn TransformExecutor<f64> + Send + Sync>, CmsError> {
+        if let Some(format) = transform_options.fixed_point_format {
+            return Err(CmsError::UnsupportedFixedPointFormat(format));
+        }
         make_rgb_xyz_rgb_transform::<f64, LINEAR_CAP>(
             src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
         )
@@ -156,27 +406,41 @@ impl RgbXyzFactory<u8> for u8 {
         profile: TransformMatrixShaper<u8, LINEAR_CAP>,
         transform_options: TransformOptions,
     ) -> Result<Box<dyn TransformExecutor<u8> + Send + Sync>, CmsError> {
+        if let Some(format) = transform_options.fixed_point_format {
+            return make_forced_fixed_point::<u8, LINEAR_CAP>(
+                format, src_layout, dst_layout, profile, GAMMA_LUT, 8,
+            );
+        }
         if transform_options.prefer_fixed_point {
             #[cfg(all(target_arch = "x86_64", feature = "avx"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx2;
-                if std::arch::is_x86_feature_detected!("avx2") {
-                    return make_rgb_xyz_q2_13_transform_avx2::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                if crate::equivalence::x86_feature_detected!("avx2") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_avx2::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_sse_41;
-                if std::arch::is_x86_feature_detected!("sse4.1") {
-                    return make_rgb_xyz_q2_13_transform_sse_41::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                if crate::equivalence::x86_feature_detected!("sse4.1") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_sse_41::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
-            make_rgb_xyz_q2_13::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
-                src_layout, dst_layout, profile, GAMMA_LUT, 8,
+            with_format(
+                make_rgb_xyz_q2_13::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
+                    src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                ),
+                FixedPointFormat::Q2_13,
             )
         } else {
             make_rgb_xyz_rgb_transform::<u8, LINEAR_CAP>(
@@ -199,13 +463,24 @@ impl RgbXyzFactoryOpt<u16> for u16 {
         profile: TransformMatrixShaperOptimized<u16, LINEAR_CAP>,
         transform_options: TransformOptions,
     ) -> Result<Box<dyn TransformExecutor<u16> + Send + Sync>, CmsError> {
+        if let Some(format) = transform_options.fixed_point_format {
+            if BIT_DEPTH >= 16 && format != FixedPointFormat::Q1_30 {
+                return Err(CmsError::UnsupportedFixedPointFormat(format));
+            }
+            return make_forced_fixed_point_opt::<u16, LINEAR_CAP>(
+                format, src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+            );
+        }
         if BIT_DEPTH >= 12 && transform_options.prefer_fixed_point {
             #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
             {
-                if std::arch::is_aarch64_feature_detected!("rdm") {
+                if crate::equivalence::aarch64_feature_detected!("rdm") {
                     use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q1_30_opt;
-                    return make_rgb_xyz_q1_30_opt::<u16, LINEAR_CAP, 30>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                    return with_format(
+                        make_rgb_xyz_q1_30_opt::<u16, LINEAR_CAP, 30>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q1_30,
                     );
                 }
             }
@@ -214,33 +489,34 @@ impl RgbXyzFactoryOpt<u16> for u16 {
             #[cfg(all(target_arch = "x86_64", feature = "avx"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx2_opt;
-                if std::arch::is_x86_feature_detected!("avx2") {
-                    return make_rgb_xyz_q2_13_transform_avx2_opt::<
-                        u16,
-                        LINEAR_CAP,
-                        FIXED_POINT_SCALE,
-                    >(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH
+                if crate::equivalence::x86_feature_detected!("avx2") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_avx2_opt::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_sse_41_opt;
-                if std::arch::is_x86_feature_detected!("sse4.1") {
-                    return make_rgb_xyz_q2_13_transform_sse_41_opt::<
-                        u16,
-                        LINEAR_CAP,
-                        FIXED_POINT_SCALE,
-                    >(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH
+                if crate::equivalence::x86_feature_detected!("sse4.1") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_sse_41_opt::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
             {
-                return make_rgb_xyz_q2_13_opt::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
-                    src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                return with_format(
+                    make_rgb_xyz_q2_13_opt::<u16, LINEAR_CAP, FIXED_POINT_SCALE>(
+                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                    ),
+                    FixedPointFormat::Q2_13,
                 );
             }
         }
@@ -261,43 +537,52 @@ impl RgbXyzFactoryOpt<f32> for f32 {
         profile: TransformMatrixShaperOptimized<f32, LINEAR_CAP>,
         transform_options: TransformOptions,
     ) -> Result<Box<dyn TransformExecutor<f32> + Send + Sync>, CmsError> {
+        if let Some(format) = transform_options.fixed_point_format {
+            return make_forced_fixed_point_opt::<f32, LINEAR_CAP>(
+                format, src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+            );
+        }
         if transform_options.prefer_fixed_point {
             #[cfg(all(target_arch = "x86_64", feature = "avx"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx2_opt;
-                if std::arch::is_x86_feature_detected!("avx2") {
-                    return make_rgb_xyz_q2_13_transform_avx2_opt::<
-                        f32,
-                        LINEAR_CAP,
-                        FIXED_POINT_SCALE,
-                    >(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH
+                if crate::equivalence::x86_feature_detected!("avx2") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_avx2_opt::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_sse_41_opt;
-                if std::arch::is_x86_feature_detected!("sse4.1") {
-                    return make_rgb_xyz_q2_13_transform_sse_41_opt::<
-                        f32,
-                        LINEAR_CAP,
-                        FIXED_POINT_SCALE,
-                    >(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH
+                if crate::equivalence::x86_feature_detected!("sse4.1") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_sse_41_opt::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     );
                 }
             }
             #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
             {
-                return if std::arch::is_aarch64_feature_detected!("rdm") {
+                return if crate::equivalence::aarch64_feature_detected!("rdm") {
                     use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q1_30_opt;
-                    make_rgb_xyz_q1_30_opt::<f32, LINEAR_CAP, 30>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                    with_format(
+                        make_rgb_xyz_q1_30_opt::<f32, LINEAR_CAP, 30>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q1_30,
                     )
                 } else {
-                    make_rgb_xyz_q2_13_opt::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                    with_format(
+                        make_rgb_xyz_q2_13_opt::<f32, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q2_13,
                     )
                 };
             }
@@ -319,13 +604,24 @@ impl RgbXyzFactoryOpt<f64> for f64 {
         profile: TransformMatrixShaperOptimized<f64, LINEAR_CAP>,
         transform_options: TransformOptions,
     ) -> Result<Box<dyn TransformExecutor<f64> + Send + Sync>, CmsError> {
+        if let Some(format) = transform_options.fixed_point_format {
+            if format != FixedPointFormat::Q1_30 {
+                return Err(CmsError::UnsupportedFixedPointFormat(format));
+            }
+            return make_forced_fixed_point_opt::<f64, LINEAR_CAP>(
+                format, src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+            );
+        }
         if transform_options.prefer_fixed_point {
             #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
             {
-                if std::arch::is_aarch64_feature_detected!("rdm") {
+                if crate::equivalence::aarch64_feature_detected!("rdm") {
                     use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q1_30_opt;
-                    return make_rgb_xyz_q1_30_opt::<f64, LINEAR_CAP, 30>(
-                        src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                    return with_format(
+                        make_rgb_xyz_q1_30_opt::<f64, LINEAR_CAP, 30>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, BIT_DEPTH,
+                        ),
+                        FixedPointFormat::Q1_30,
                     );
                 }
             }
@@ -347,46 +643,73 @@ impl RgbXyzFactoryOpt<u8> for u8 {
         profile: TransformMatrixShaperOptimized<u8, LINEAR_CAP>,
         transform_options: TransformOptions,
     ) -> Result<Box<dyn TransformExecutor<u8> + Send + Sync>, CmsError> {
+        if let Some(format) = transform_options.fixed_point_format {
+            return make_forced_fixed_point_opt::<u8, LINEAR_CAP>(
+                format, src_layout, dst_layout, profile, GAMMA_LUT, 8,
+            );
+        }
         if transform_options.prefer_fixed_point {
             #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx512_opt;
-                if std::arch::is_x86_feature_detected!("avx512bw")
-                    && std::arch::is_x86_feature_detected!("avx512vl")
+                if crate::equivalence::x86_feature_detected!("avx512bw")
+                    && crate::equivalence::x86_feature_detected!("avx512vl")
                 {
-                    return make_rgb_xyz_q2_13_transform_avx512_opt::<
-                        u8,
-                        LINEAR_CAP,
-                        FIXED_POINT_SCALE,
-                    >(src_layout, dst_layout, profile, GAMMA_LUT, 8);
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_avx512_opt::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                        ),
+                        FixedPointFormat::Q2_13,
+                    );
                 }
             }
             #[cfg(all(target_arch = "x86_64", feature = "avx"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_avx2_opt;
-                if std::arch::is_x86_feature_detected!("avx2") {
-                    return make_rgb_xyz_q2_13_transform_avx2_opt::<
-                        u8,
-                        LINEAR_CAP,
-                        FIXED_POINT_SCALE,
-                    >(src_layout, dst_layout, profile, GAMMA_LUT, 8);
+                if crate::equivalence::x86_feature_detected!("avx2") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_avx2_opt::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                        ),
+                        FixedPointFormat::Q2_13,
+                    );
                 }
             }
             #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
             {
                 use crate::conversions::rgbxyz_fixed::make_rgb_xyz_q2_13_transform_sse_41_opt;
-                if std::arch::is_x86_feature_detected!("sse4.1") {
-                    return make_rgb_xyz_q2_13_transform_sse_41_opt::<
-                        u8,
-                        LINEAR_CAP,
-                        FIXED_POINT_SCALE,
-                    >(src_layout, dst_layout, profile, GAMMA_LUT, 8);
+                if crate::equivalence::x86_feature_detected!("sse4.1") {
+                    return with_format(
+                        make_rgb_xyz_q2_13_transform_sse_41_opt::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
+                            src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                        ),
+                        FixedPointFormat::Q2_13,
+                    );
                 }
             }
-            make_rgb_xyz_q2_13_opt::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
-                src_layout, dst_layout, profile, GAMMA_LUT, 8,
+            with_format(
+                make_rgb_xyz_q2_13_opt::<u8, LINEAR_CAP, FIXED_POINT_SCALE>(
+                    src_layout, dst_layout, profile, GAMMA_LUT, 8,
+                ),
+                FixedPointFormat::Q2_13,
             )
         } else {
+            #[cfg(all(
+                target_arch = "aarch64",
+                target_feature = "neon",
+                feature = "neon",
+                feature = "neon-fp16"
+            ))]
+            {
+                use crate::conversions::neon::{fp16_shaper_fits, make_rgb_xyz_rgb_transform_fp16};
+                if crate::equivalence::aarch64_feature_detected!("fp16")
+                    && fp16_shaper_fits(&profile, GAMMA_LUT)
+                {
+                    return make_rgb_xyz_rgb_transform_fp16::<LINEAR_CAP>(
+                        src_layout, dst_layout, profile, GAMMA_LUT,
+                    );
+                }
+            }
             make_rgb_xyz_rgb_transform_opt::<u8, LINEAR_CAP>(
                 src_layout, dst_layout, profile, GAMMA_LUT, 8,
             )
@@ -26,17 +26,20 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::{CmsError, Layout, Matrix3, Matrix3f, TransformExecutor};
+use crate::{CmsError, Layout, Matrix3, Matrix3f, TransformExecutor, Vector3f};
 use num_traits::AsPrimitive;
+use std::sync::Arc;
 
 pub(crate) struct TransformMatrixShaper<T: Clone, const BUCKET: usize> {
-    pub(crate) r_linear: Box<[f32; BUCKET]>,
-    pub(crate) g_linear: Box<[f32; BUCKET]>,
-    pub(crate) b_linear: Box<[f32; BUCKET]>,
+    pub(crate) r_linear: Arc<[f32; BUCKET]>,
+    pub(crate) g_linear: Arc<[f32; BUCKET]>,
+    pub(crate) b_linear: Arc<[f32; BUCKET]>,
     pub(crate) r_gamma: Box<[T; 65536]>,
     pub(crate) g_gamma: Box<[T; 65536]>,
     pub(crate) b_gamma: Box<[T; 65536]>,
     pub(crate) adaptation_matrix: Matrix3f,
+    /// Offset added after the matrix, only scalar executors support non-zero bias
+    pub(crate) bias: Vector3f,
 }
 
 impl<T: Clone, const BUCKET: usize> TransformMatrixShaper<T, BUCKET> {
@@ -69,7 +72,7 @@ pub(crate) struct TransformMatrixShaperV<T: Clone> {
 /// Low memory footprint optimized routine for matrix shaper profiles with the same
 /// Gamma and linear curves.
 pub(crate) struct TransformMatrixShaperOptimized<T: Clone, const BUCKET: usize> {
-    pub(crate) linear: Box<[f32; BUCKET]>,
+    pub(crate) linear: Arc<[f32; BUCKET]>,
     pub(crate) gamma: Box<[T; 65536]>,
     pub(crate) adaptation_matrix: Matrix3f,
 }
@@ -550,17 +553,38 @@ where
     u32: AsPrimitive<T>,
 {
     #[cfg(all(feature = "avx", target_arch = "x86_64"))]
-    if std::arch::is_x86_feature_detected!("avx2") && std::arch::is_x86_feature_detected!("fma") {
+    if crate::equivalence::x86_feature_detected!("avx2")
+        && crate::equivalence::x86_feature_detected!("fma")
+    {
         return make_rgb_xyz_rgb_transform_avx2::<T, LINEAR_CAP>(
             src_layout, dst_layout, profile, gamma_lut, bit_depth,
         );
     }
     #[cfg(all(feature = "sse", any(target_arch = "x86", target_arch = "x86_64")))]
-    if std::arch::is_x86_feature_detected!("sse4.1") {
+    if crate::equivalence::x86_feature_detected!("sse4.1") {
         return make_rgb_xyz_rgb_transform_sse_41::<T, LINEAR_CAP>(
             src_layout, dst_layout, profile, gamma_lut, bit_depth,
         );
     }
+    make_rgb_xyz_rgb_transform_scalar::<T, LINEAR_CAP>(
+        src_layout, dst_layout, profile, gamma_lut, bit_depth,
+    )
+}
+
+/// Scalar executor, the only one that supports affine matrix stage with non-zero bias
+pub(crate) fn make_rgb_xyz_rgb_transform_scalar<
+    T: Clone + Send + Sync + PointeeSizeExpressible + 'static + Copy + Default,
+    const LINEAR_CAP: usize,
+>(
+    src_layout: Layout,
+    dst_layout: Layout,
+    profile: TransformMatrixShaper<T, LINEAR_CAP>,
+    gamma_lut: usize,
+    bit_depth: usize,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    u32: AsPrimitive<T>,
+{
     if (src_layout == Layout::Rgba) && (dst_layout == Layout::Rgba) {
         return Ok(Box::new(TransformMatrixShaperScalar::<
             T,
@@ -624,22 +648,24 @@ where
     u32: AsPrimitive<T>,
 {
     #[cfg(all(feature = "avx512", target_arch = "x86_64"))]
-    if std::arch::is_x86_feature_detected!("avx512bw")
-        && std::arch::is_x86_feature_detected!("avx512vl")
-        && std::arch::is_x86_feature_detected!("fma")
+    if crate::equivalence::x86_feature_detected!("avx512bw")
+        && crate::equivalence::x86_feature_detected!("avx512vl")
+        && crate::equivalence::x86_feature_detected!("fma")
     {
         return make_rgb_xyz_rgb_transform_avx512_opt::<T, LINEAR_CAP>(
             src_layout, dst_layout, profile, gamma_lut, bit_depth,
         );
     }
     #[cfg(all(feature = "avx", target_arch = "x86_64"))]
-    if std::arch::is_x86_feature_detected!("avx2") && std::arch::is_x86_feature_detected!("fma") {
+    if crate::equivalence::x86_feature_detected!("avx2")
+        && crate::equivalence::x86_feature_detected!("fma")
+    {
         return make_rgb_xyz_rgb_transform_avx2_opt::<T, LINEAR_CAP>(
             src_layout, dst_layout, profile, gamma_lut, bit_depth,
         );
     }
     #[cfg(all(feature = "sse", any(target_arch = "x86", target_arch = "x86_64")))]
-    if std::arch::is_x86_feature_detected!("sse4.1") {
+    if crate::equivalence::x86_feature_detected!("sse4.1") {
         return make_rgb_xyz_rgb_transform_sse_41_opt::<T, LINEAR_CAP>(
             src_layout, dst_layout, profile, gamma_lut, bit_depth,
         );
@@ -742,6 +768,7 @@ where
         }
 
         let transform = self.profile.adaptation_matrix;
+        let bias = self.profile.bias;
         let scale = (self.gamma_lut - 1) as f32;
         let max_colors: T = ((1 << self.bit_depth) - 1).as_();
 
@@ -761,7 +788,7 @@ where
             let new_r = mlaf(
                 0.5f32,
                 mlaf(
-                    mlaf(r * transform.v[0][0], g, transform.v[0][1]),
+                    mlaf(mlaf(bias.v[0], r, transform.v[0][0]), g, transform.v[0][1]),
                     b,
                     transform.v[0][2],
                 )
@@ -773,7 +800,7 @@ where
             let new_g = mlaf(
                 0.5f32,
                 mlaf(
-                    mlaf(r * transform.v[1][0], g, transform.v[1][1]),
+                    mlaf(mlaf(bias.v[1], r, transform.v[1][0]), g, transform.v[1][1]),
                     b,
                     transform.v[1][2],
                 )
@@ -785,7 +812,7 @@ where
             let new_b = mlaf(
                 0.5f32,
                 mlaf(
-                    mlaf(r * transform.v[2][0], g, transform.v[2][1]),
+                    mlaf(mlaf(bias.v[2], r, transform.v[2][0]), g, transform.v[2][1]),
                     b,
                     transform.v[2][2],
                 )
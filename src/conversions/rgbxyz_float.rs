@@ -27,7 +27,7 @@
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::trc::ToneCurveEvaluator;
-use crate::{CmsError, Layout, Matrix3f, PointeeSizeExpressible, Rgb, TransformExecutor};
+use crate::{CmsError, Layout, Matrix3f, PointeeSizeExpressible, Rgb, TransformExecutor, Vector3f};
 use num_traits::AsPrimitive;
 use std::marker::PhantomData;
 
@@ -37,6 +37,7 @@ pub(crate) struct TransformShaperRgbFloat<T: Clone, const BUCKET: usize> {
     pub(crate) b_linear: Box<[f32; BUCKET]>,
     pub(crate) gamma_evaluator: Box<dyn ToneCurveEvaluator + Send + Sync>,
     pub(crate) adaptation_matrix: Matrix3f,
+    pub(crate) bias: Vector3f,
     pub(crate) phantom_data: PhantomData<T>,
 }
 
@@ -44,6 +45,7 @@ pub(crate) struct TransformShaperFloatInOut<T: Clone> {
     pub(crate) linear_evaluator: Box<dyn ToneCurveEvaluator + Send + Sync>,
     pub(crate) gamma_evaluator: Box<dyn ToneCurveEvaluator + Send + Sync>,
     pub(crate) adaptation_matrix: Matrix3f,
+    pub(crate) bias: Vector3f,
     pub(crate) phantom_data: PhantomData<T>,
 }
 
@@ -199,6 +201,7 @@ where
         }
 
         let transform = self.profile.adaptation_matrix;
+        let bias = self.profile.bias;
         let max_colors: T = ((1 << self.bit_depth) - 1).as_();
 
         for (src, dst) in src
@@ -215,19 +218,19 @@ where
             };
 
             let new_r = mlaf(
-                mlaf(r * transform.v[0][0], g, transform.v[0][1]),
+                mlaf(mlaf(bias.v[0], r, transform.v[0][0]), g, transform.v[0][1]),
                 b,
                 transform.v[0][2],
             );
 
             let new_g = mlaf(
-                mlaf(r * transform.v[1][0], g, transform.v[1][1]),
+                mlaf(mlaf(bias.v[1], r, transform.v[1][0]), g, transform.v[1][1]),
                 b,
                 transform.v[1][2],
             );
 
             let new_b = mlaf(
-                mlaf(r * transform.v[2][0], g, transform.v[2][1]),
+                mlaf(mlaf(bias.v[2], r, transform.v[2][0]), g, transform.v[2][1]),
                 b,
                 transform.v[2][2],
             );
@@ -274,6 +277,7 @@ where
         }
 
         let transform = self.profile.adaptation_matrix;
+        let bias = self.profile.bias;
         let max_colors: T = ((1 << self.bit_depth) - 1).as_();
 
         for (src, dst) in src
@@ -296,19 +300,19 @@ where
             };
 
             let new_r = mlaf(
-                mlaf(r * transform.v[0][0], g, transform.v[0][1]),
+                mlaf(mlaf(bias.v[0], r, transform.v[0][0]), g, transform.v[0][1]),
                 b,
                 transform.v[0][2],
             );
 
             let new_g = mlaf(
-                mlaf(r * transform.v[1][0], g, transform.v[1][1]),
+                mlaf(mlaf(bias.v[1], r, transform.v[1][0]), g, transform.v[1][1]),
                 b,
                 transform.v[1][2],
             );
 
             let new_b = mlaf(
-                mlaf(r * transform.v[2][0], g, transform.v[2][1]),
+                mlaf(mlaf(bias.v[2], r, transform.v[2][0]), g, transform.v[2][1]),
                 b,
                 transform.v[2][2],
             );
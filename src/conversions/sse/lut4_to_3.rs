@@ -210,7 +210,7 @@ where
                     InterpolationMethod::Prism => {
                         self.transform_chunk(src, dst, Box::new(PrismaticSse::<GRID_SIZE> {}));
                     }
-                    InterpolationMethod::Linear => {
+                    InterpolationMethod::Linear | InterpolationMethod::Auto => {
                         self.transform_chunk(src, dst, Box::new(TrilinearSse::<GRID_SIZE> {}));
                     }
                 }
@@ -36,6 +36,7 @@ mod rgb_xyz_q2_13;
 mod rgb_xyz_q2_13_opt;
 mod t_lut3_to_3;
 mod t_lut3_to_3_q0_15;
+mod tetra_3i_to_any;
 
 pub(crate) use lut4_to_3::SseLut4x3Factory;
 pub(crate) use rgb_xyz::TransformShaperRgbSse;
@@ -43,3 +44,4 @@ pub(crate) use rgb_xyz_opt::TransformShaperRgbOptSse;
 pub(crate) use rgb_xyz_q2_13::TransformShaperQ2_13Sse;
 pub(crate) use rgb_xyz_q2_13_opt::TransformShaperQ2_13OptSse;
 pub(crate) use t_lut3_to_3::SseLut3x3Factory;
+pub(crate) use tetra_3i_to_any::tetra_3i_to_any_sse;
@@ -29,6 +29,7 @@
 use crate::conversions::LutBarycentricReduction;
 use crate::conversions::interpolator::BarycentricWeight;
 use crate::conversions::lut_transforms::Lut3x3Factory;
+use crate::conversions::prefetch::prefetch_lut3;
 use crate::conversions::sse::interpolator::*;
 use crate::conversions::sse::interpolator_q0_15::SseAlignedI16x4;
 use crate::conversions::sse::t_lut3_to_3_q0_15::TransformLut3x3SseQ0_15;
@@ -61,6 +62,7 @@ struct TransformLut3x3Sse<
     weights: Box<[BarycentricWeight<f32>; BINS]>,
     color_space: DataColorSpace,
     is_linear: bool,
+    block_size: usize,
 }
 
 impl<
@@ -95,58 +97,76 @@ where
         let value_scale = unsafe { _mm_set1_ps(((1 << BIT_DEPTH) - 1) as f32) };
         let max_value = ((1u32 << BIT_DEPTH) - 1).as_();
 
-        for (src, dst) in src
-            .chunks_exact(src_channels)
-            .zip(dst.chunks_exact_mut(dst_channels))
+        let block_size = self.block_size.max(1);
+        let src_blocks = src.chunks(src_channels * block_size);
+
+        for (i, (block, dst)) in src_blocks
+            .clone()
+            .zip(dst.chunks_mut(dst_channels * block_size))
+            .enumerate()
         {
-            let x = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                src[src_cn.r_i()],
-            );
-            let y = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                src[src_cn.g_i()],
-            );
-            let z = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                src[src_cn.b_i()],
-            );
+            if let Some(next_block) = src_blocks.clone().nth(i + 1).filter(|_| block_size > 1) {
+                prefetch_lut3::<T, U, _, SseAlignedF32, GRID_SIZE, BIT_DEPTH, BARYCENTRIC_BINS>(
+                    next_block,
+                    src_cn,
+                    self.weights.as_slice(),
+                    &self.lut,
+                    1,
+                );
+            }
+            for (src, dst) in block
+                .chunks_exact(src_channels)
+                .zip(dst.chunks_exact_mut(dst_channels))
+            {
+                let x = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+                    src[src_cn.r_i()],
+                );
+                let y = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+                    src[src_cn.g_i()],
+                );
+                let z = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+                    src[src_cn.b_i()],
+                );
 
-            let a = if src_channels == 4 {
-                src[src_cn.a_i()]
-            } else {
-                max_value
-            };
+                let a = if src_channels == 4 {
+                    src[src_cn.a_i()]
+                } else {
+                    max_value
+                };
 
-            let v = interpolator.inter3_sse(
-                &self.lut,
-                x.as_(),
-                y.as_(),
-                z.as_(),
-                self.weights.as_slice(),
-            );
-            if T::FINITE {
-                unsafe {
-                    let mut r = _mm_mul_ps(v.v, value_scale);
-                    r = _mm_max_ps(r, _mm_setzero_ps());
-                    r = _mm_min_ps(r, value_scale);
-                    let jvz = _mm_cvtps_epi32(r);
+                let v = interpolator.inter3_sse(
+                    &self.lut,
+                    x.as_(),
+                    y.as_(),
+                    z.as_(),
+                    self.weights.as_slice(),
+                );
+                if T::FINITE {
+                    unsafe {
+                        let mut r = _mm_mul_ps(v.v, value_scale);
+                        r = _mm_max_ps(r, _mm_setzero_ps());
+                        r = _mm_min_ps(r, value_scale);
+                        let jvz = _mm_cvtps_epi32(r);
 
-                    let x = _mm_extract_epi32::<0>(jvz);
-                    let y = _mm_extract_epi32::<1>(jvz);
-                    let z = _mm_extract_epi32::<2>(jvz);
+                        let x = _mm_extract_epi32::<0>(jvz);
+                        let y = _mm_extract_epi32::<1>(jvz);
+                        let z = _mm_extract_epi32::<2>(jvz);
 
-                    dst[dst_cn.r_i()] = (x as u32).as_();
-                    dst[dst_cn.g_i()] = (y as u32).as_();
-                    dst[dst_cn.b_i()] = (z as u32).as_();
+                        dst[dst_cn.r_i()] = (x as u32).as_();
+                        dst[dst_cn.g_i()] = (y as u32).as_();
+                        dst[dst_cn.b_i()] = (z as u32).as_();
+                    }
+                } else {
+                    unsafe {
+                        dst[dst_cn.r_i()] = f32::from_bits(_mm_extract_ps::<0>(v.v) as u32).as_();
+                        dst[dst_cn.g_i()] = f32::from_bits(_mm_extract_ps::<1>(v.v) as u32).as_();
+                        dst[dst_cn.b_i()] = f32::from_bits(_mm_extract_ps::<2>(v.v) as u32).as_();
+                    }
                 }
-            } else {
-                unsafe {
-                    dst[dst_cn.r_i()] = f32::from_bits(_mm_extract_ps::<0>(v.v) as u32).as_();
-                    dst[dst_cn.g_i()] = f32::from_bits(_mm_extract_ps::<1>(v.v) as u32).as_();
-                    dst[dst_cn.b_i()] = f32::from_bits(_mm_extract_ps::<2>(v.v) as u32).as_();
+                if dst_channels == 4 {
+                    dst[dst_cn.a_i()] = a;
                 }
             }
-            if dst_channels == 4 {
-                dst[dst_cn.a_i()] = a;
-            }
         }
     }
 }
@@ -214,7 +234,7 @@ where
                     InterpolationMethod::Prism => {
                         self.transform_chunk(src, dst, Box::new(PrismaticSse::<GRID_SIZE> {}));
                     }
-                    InterpolationMethod::Linear => {
+                    InterpolationMethod::Linear | InterpolationMethod::Auto => {
                         self.transform_chunk(src, dst, Box::new(TrilinearSse::<GRID_SIZE> {}));
                     }
                 }
@@ -280,6 +300,7 @@ impl Lut3x3Factory for SseLut3x3Factory {
                     weights: BarycentricWeight::<i16>::create_ranged_256::<GRID_SIZE>(),
                     color_space,
                     is_linear,
+                    block_size: options.lut_block_size,
                 }),
                 #[cfg(feature = "options")]
                 BarycentricWeightScale::High => Box::new(TransformLut3x3SseQ0_15::<
@@ -299,6 +320,7 @@ impl Lut3x3Factory for SseLut3x3Factory {
                     weights: BarycentricWeight::<i16>::create_binned::<GRID_SIZE, 65536>(),
                     color_space,
                     is_linear,
+                    block_size: options.lut_block_size,
                 }),
             };
         }
@@ -324,6 +346,7 @@ impl Lut3x3Factory for SseLut3x3Factory {
                 weights: BarycentricWeight::<f32>::create_ranged_256::<GRID_SIZE>(),
                 color_space,
                 is_linear,
+                block_size: options.lut_block_size,
             }),
             #[cfg(feature = "options")]
             BarycentricWeightScale::High => Box::new(TransformLut3x3Sse::<
@@ -343,6 +366,7 @@ impl Lut3x3Factory for SseLut3x3Factory {
                 weights: BarycentricWeight::<f32>::create_binned::<GRID_SIZE, 65536>(),
                 color_space,
                 is_linear,
+                block_size: options.lut_block_size,
             }),
         }
     }
@@ -28,6 +28,7 @@
  */
 use crate::conversions::LutBarycentricReduction;
 use crate::conversions::interpolator::BarycentricWeight;
+use crate::conversions::prefetch::prefetch_lut3;
 use crate::conversions::sse::interpolator_q0_15::*;
 use crate::transform::PointeeSizeExpressible;
 use crate::{CmsError, DataColorSpace, InterpolationMethod, Layout, TransformExecutor};
@@ -55,6 +56,7 @@ pub(crate) struct TransformLut3x3SseQ0_15<
     pub(crate) weights: Box<[BarycentricWeight<i16>; BINS]>,
     pub(crate) color_space: DataColorSpace,
     pub(crate) is_linear: bool,
+    pub(crate) block_size: usize,
 }
 
 impl<
@@ -106,52 +108,73 @@ where
                 _mm_set1_epi16(((1i32 << 14i32) - 1) as i16)
             };
 
-            for (src, dst) in src
-                .chunks_exact(src_channels)
-                .zip(dst.chunks_exact_mut(dst_channels))
+            let block_size = self.block_size.max(1);
+            let src_blocks = src.chunks(src_channels * block_size);
+
+            for (i, (block, dst)) in src_blocks
+                .clone()
+                .zip(dst.chunks_mut(dst_channels * block_size))
+                .enumerate()
             {
-                let x = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.r_i()],
-                );
-                let y = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.g_i()],
-                );
-                let z = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                    src[src_cn.b_i()],
-                );
+                if let Some(next_block) = src_blocks.clone().nth(i + 1).filter(|_| block_size > 1) {
+                    prefetch_lut3::<T, U, _, SseAlignedI16x4, GRID_SIZE, BIT_DEPTH, BARYCENTRIC_BINS>(
+                        next_block,
+                        src_cn,
+                        self.weights.as_slice(),
+                        &self.lut,
+                        1,
+                    );
+                }
+                for (src, dst) in block
+                    .chunks_exact(src_channels)
+                    .zip(dst.chunks_exact_mut(dst_channels))
+                {
+                    let x = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.r_i()]);
+                    let y = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.g_i()]);
+                    let z = <() as LutBarycentricReduction<T, U>>::reduce::<
+                        BIT_DEPTH,
+                        BARYCENTRIC_BINS,
+                    >(src[src_cn.b_i()]);
 
-                let a = if src_channels == 4 {
-                    src[src_cn.a_i()]
-                } else {
-                    max_value
-                };
+                    let a = if src_channels == 4 {
+                        src[src_cn.a_i()]
+                    } else {
+                        max_value
+                    };
 
-                let v = interpolator.inter3_sse(
-                    &self.lut,
-                    x.as_(),
-                    y.as_(),
-                    z.as_(),
-                    self.weights.as_slice(),
-                );
-                if T::FINITE {
-                    let mut o = _mm_max_epi16(v.v, _mm_setzero_si128());
-                    o = _mm_min_epi16(o, v_max_scale);
-                    let x = _mm_extract_epi16::<0>(o);
-                    let y = _mm_extract_epi16::<1>(o);
-                    let z = _mm_extract_epi16::<2>(o);
+                    let v = interpolator.inter3_sse(
+                        &self.lut,
+                        x.as_(),
+                        y.as_(),
+                        z.as_(),
+                        self.weights.as_slice(),
+                    );
+                    if T::FINITE {
+                        let mut o = _mm_max_epi16(v.v, _mm_setzero_si128());
+                        o = _mm_min_epi16(o, v_max_scale);
+                        let x = _mm_extract_epi16::<0>(o);
+                        let y = _mm_extract_epi16::<1>(o);
+                        let z = _mm_extract_epi16::<2>(o);
 
-                    dst[dst_cn.r_i()] = (x as u32).as_();
-                    dst[dst_cn.g_i()] = (y as u32).as_();
-                    dst[dst_cn.b_i()] = (z as u32).as_();
-                } else {
-                    let mut r = _mm_cvtepi32_ps(_mm_cvtepi16_epi32(v.v));
-                    r = _mm_mul_ps(r, f_value_scale);
-                    dst[dst_cn.r_i()] = f32::from_bits(_mm_extract_ps::<0>(r) as u32).as_();
-                    dst[dst_cn.g_i()] = f32::from_bits(_mm_extract_ps::<1>(r) as u32).as_();
-                    dst[dst_cn.b_i()] = f32::from_bits(_mm_extract_ps::<2>(r) as u32).as_();
-                }
-                if dst_channels == 4 {
-                    dst[dst_cn.a_i()] = a;
+                        dst[dst_cn.r_i()] = (x as u32).as_();
+                        dst[dst_cn.g_i()] = (y as u32).as_();
+                        dst[dst_cn.b_i()] = (z as u32).as_();
+                    } else {
+                        let mut r = _mm_cvtepi32_ps(_mm_cvtepi16_epi32(v.v));
+                        r = _mm_mul_ps(r, f_value_scale);
+                        dst[dst_cn.r_i()] = f32::from_bits(_mm_extract_ps::<0>(r) as u32).as_();
+                        dst[dst_cn.g_i()] = f32::from_bits(_mm_extract_ps::<1>(r) as u32).as_();
+                        dst[dst_cn.b_i()] = f32::from_bits(_mm_extract_ps::<2>(r) as u32).as_();
+                    }
+                    if dst_channels == 4 {
+                        dst[dst_cn.a_i()] = a;
+                    }
                 }
             }
         }
@@ -225,7 +248,7 @@ where
                     InterpolationMethod::Prism => {
                         self.transform_chunk(src, dst, Box::new(PrismaticSseQ0_15::<GRID_SIZE> {}));
                     }
-                    InterpolationMethod::Linear => {
+                    InterpolationMethod::Linear | InterpolationMethod::Auto => {
                         self.transform_chunk(src, dst, Box::new(TrilinearSseQ0_15::<GRID_SIZE> {}));
                     }
                 }
@@ -30,6 +30,7 @@
 use crate::conversions::LutBarycentricReduction;
 use crate::conversions::interpolator::{BarycentricWeight, MultidimensionalInterpolation};
 use crate::conversions::lut_transforms::Lut3x3Factory;
+use crate::conversions::prefetch::prefetch_lut3;
 use crate::transform::PointeeSizeExpressible;
 use crate::{
     BarycentricWeightScale, CmsError, DataColorSpace, InterpolationMethod, Layout,
@@ -55,6 +56,7 @@ pub(crate) struct TransformLut3x3<
     pub(crate) weights: Box<[BarycentricWeight<f32>; BINS]>,
     pub(crate) color_space: DataColorSpace,
     pub(crate) is_linear: bool,
+    pub(crate) block_size: usize,
 }
 
 impl<
@@ -88,46 +90,64 @@ where
         let value_scale = ((1 << BIT_DEPTH) - 1) as f32;
         let max_value = ((1u32 << BIT_DEPTH) - 1).as_();
 
-        for (src, dst) in src
-            .chunks_exact(src_channels)
-            .zip(dst.chunks_exact_mut(dst_channels))
+        let block_size = self.block_size.max(1);
+        let src_blocks = src.chunks(src_channels * block_size);
+
+        for (i, (block, dst)) in src_blocks
+            .clone()
+            .zip(dst.chunks_mut(dst_channels * block_size))
+            .enumerate()
         {
-            let x = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                src[src_cn.r_i()],
-            );
-            let y = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                src[src_cn.g_i()],
-            );
-            let z = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
-                src[src_cn.b_i()],
-            );
+            if let Some(next_block) = src_blocks.clone().nth(i + 1).filter(|_| block_size > 1) {
+                prefetch_lut3::<T, U, _, f32, GRID_SIZE, BIT_DEPTH, BARYCENTRIC_BINS>(
+                    next_block,
+                    src_cn,
+                    self.weights.as_slice(),
+                    &self.lut,
+                    3,
+                );
+            }
+            for (src, dst) in block
+                .chunks_exact(src_channels)
+                .zip(dst.chunks_exact_mut(dst_channels))
+            {
+                let x = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+                    src[src_cn.r_i()],
+                );
+                let y = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+                    src[src_cn.g_i()],
+                );
+                let z = <() as LutBarycentricReduction<T, U>>::reduce::<BIT_DEPTH, BARYCENTRIC_BINS>(
+                    src[src_cn.b_i()],
+                );
 
-            let a = if src_channels == 4 {
-                src[src_cn.a_i()]
-            } else {
-                max_value
-            };
+                let a = if src_channels == 4 {
+                    src[src_cn.a_i()]
+                } else {
+                    max_value
+                };
 
-            let v = interpolator.inter3(
-                &self.lut,
-                &self.weights[x.as_()],
-                &self.weights[y.as_()],
-                &self.weights[z.as_()],
-            );
-            if T::FINITE {
-                let r = v * value_scale + 0.5;
-                dst[dst_cn.r_i()] = r.v[0].min(value_scale).max(0.).as_();
-                dst[dst_cn.g_i()] = r.v[1].min(value_scale).max(0.).as_();
-                dst[dst_cn.b_i()] = r.v[2].min(value_scale).max(0.).as_();
-                if dst_channels == 4 {
-                    dst[dst_cn.a_i()] = a;
-                }
-            } else {
-                dst[dst_cn.r_i()] = v.v[0].as_();
-                dst[dst_cn.g_i()] = v.v[1].as_();
-                dst[dst_cn.b_i()] = v.v[2].as_();
-                if dst_channels == 4 {
-                    dst[dst_cn.a_i()] = a;
+                let v = interpolator.inter3(
+                    &self.lut,
+                    &self.weights[x.as_()],
+                    &self.weights[y.as_()],
+                    &self.weights[z.as_()],
+                );
+                if T::FINITE {
+                    let r = v * value_scale + 0.5;
+                    dst[dst_cn.r_i()] = r.v[0].min(value_scale).max(0.).as_();
+                    dst[dst_cn.g_i()] = r.v[1].min(value_scale).max(0.).as_();
+                    dst[dst_cn.b_i()] = r.v[2].min(value_scale).max(0.).as_();
+                    if dst_channels == 4 {
+                        dst[dst_cn.a_i()] = a;
+                    }
+                } else {
+                    dst[dst_cn.r_i()] = v.v[0].as_();
+                    dst[dst_cn.g_i()] = v.v[1].as_();
+                    dst[dst_cn.b_i()] = v.v[2].as_();
+                    if dst_channels == 4 {
+                        dst[dst_cn.a_i()] = a;
+                    }
                 }
             }
         }
@@ -191,7 +211,7 @@ where
                     use crate::conversions::interpolator::Prismatic;
                     self.transform_chunk(src, dst, Box::new(Prismatic::<GRID_SIZE> {}));
                 }
-                InterpolationMethod::Linear => {
+                InterpolationMethod::Linear | InterpolationMethod::Auto => {
                     use crate::conversions::interpolator::Trilinear;
                     self.transform_chunk(src, dst, Box::new(Trilinear::<GRID_SIZE> {}));
                 }
@@ -241,6 +261,7 @@ impl Lut3x3Factory for DefaultLut3x3Factory {
                 weights: BarycentricWeight::<f32>::create_ranged_256::<GRID_SIZE>(),
                 color_space,
                 is_linear,
+                block_size: options.lut_block_size,
             }),
             #[cfg(feature = "options")]
             BarycentricWeightScale::High => Box::new(TransformLut3x3::<
@@ -260,6 +281,7 @@ impl Lut3x3Factory for DefaultLut3x3Factory {
                 weights: BarycentricWeight::<f32>::create_binned::<GRID_SIZE, 65536>(),
                 color_space,
                 is_linear,
+                block_size: options.lut_block_size,
             }),
         }
     }
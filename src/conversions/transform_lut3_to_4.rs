@@ -166,7 +166,7 @@ where
                     use crate::conversions::interpolator::Prismatic;
                     self.transform_chunk(src, dst, Box::new(Prismatic::<GRID_SIZE> {}));
                 }
-                InterpolationMethod::Linear => {
+                InterpolationMethod::Linear | InterpolationMethod::Auto => {
                     use crate::conversions::interpolator::Trilinear;
                     self.transform_chunk(src, dst, Box::new(Trilinear::<GRID_SIZE> {}));
                 }
@@ -274,7 +274,7 @@ where
                         );
                     }
                 }
-                InterpolationMethod::Linear => {
+                InterpolationMethod::Linear | InterpolationMethod::Auto => {
                     if T::FINITE {
                         self.transform_chunk::<DefaultVector3fLerp>(
                             src,
@@ -26,7 +26,8 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::{CmsError, InPlaceStage, Lab, Xyz};
+use crate::{CmsError, DataColorSpace, InPlaceStage, Lab, Matrix3f, Xyz};
+use pxfm::f_powf;
 
 #[derive(Default)]
 pub(crate) struct StageLabToXyz {}
@@ -59,3 +60,101 @@ impl InPlaceStage for StageXyzToLab {
         Ok(())
     }
 }
+
+/// Scales luminance of PCS values, Lab PCS goes through XYZ
+pub(crate) fn scale_pcs_luminance(dst: &mut [f32], factor: f32, pcs: DataColorSpace) {
+    if pcs == DataColorSpace::Lab {
+        for dst in dst.chunks_exact_mut(3) {
+            let xyz = Lab::new(dst[0], dst[1], dst[2]).to_pcs_xyz();
+            let lab = Lab::from_pcs_xyz(Xyz::new(xyz.x * factor, xyz.y * factor, xyz.z * factor));
+            dst[0] = lab.l;
+            dst[1] = lab.a;
+            dst[2] = lab.b;
+        }
+    } else {
+        for v in dst.iter_mut() {
+            *v *= factor;
+        }
+    }
+}
+
+/// Multiplies PCS values by XYZ `matrix`, Lab PCS goes through XYZ
+pub(crate) fn transform_pcs_matrix(dst: &mut [f32], matrix: Matrix3f, pcs: DataColorSpace) {
+    for dst in dst.chunks_exact_mut(3) {
+        if pcs == DataColorSpace::Lab {
+            let xyz = Lab::new(dst[0], dst[1], dst[2]).to_pcs_xyz();
+            let lab = Lab::from_pcs_xyz(xyz.matrix_mul(matrix));
+            dst[0] = lab.l;
+            dst[1] = lab.a;
+            dst[2] = lab.b;
+        } else {
+            let xyz = Xyz::new(dst[0], dst[1], dst[2]).matrix_mul(matrix);
+            dst[0] = xyz.x;
+            dst[1] = xyz.y;
+            dst[2] = xyz.z;
+        }
+    }
+}
+
+/// Raises relative PCS `Y` to `gamma` keeping chromaticity, Lab PCS goes through XYZ.
+///
+/// PCS XYZ is ICC encoded, white has Y = 32768 / 65535.
+pub(crate) fn compensate_pcs_surround(dst: &mut [f32], gamma: f32, pcs: DataColorSpace) {
+    let compensate = |xyz: Xyz| -> Xyz {
+        if xyz.y <= 0. {
+            return xyz;
+        }
+        let factor = f_powf(xyz.y * (65535. / 32768.), gamma - 1.);
+        Xyz::new(xyz.x * factor, xyz.y * factor, xyz.z * factor)
+    };
+    for dst in dst.chunks_exact_mut(3) {
+        if pcs == DataColorSpace::Lab {
+            let xyz = compensate(Lab::new(dst[0], dst[1], dst[2]).to_pcs_xyz());
+            let lab = Lab::from_pcs_xyz(xyz);
+            dst[0] = lab.l;
+            dst[1] = lab.a;
+            dst[2] = lab.b;
+        } else {
+            let xyz = compensate(Xyz::new(dst[0], dst[1], dst[2]));
+            dst[0] = xyz.x;
+            dst[1] = xyz.y;
+            dst[2] = xyz.z;
+        }
+    }
+}
+
+pub(crate) struct StageLuminanceScale {
+    pub(crate) factor: f32,
+    pub(crate) pcs: DataColorSpace,
+}
+
+impl InPlaceStage for StageLuminanceScale {
+    fn transform(&self, dst: &mut [f32]) -> Result<(), CmsError> {
+        scale_pcs_luminance(dst, self.factor, self.pcs);
+        Ok(())
+    }
+}
+
+pub(crate) struct StagePcsMatrix {
+    pub(crate) matrix: Matrix3f,
+    pub(crate) pcs: DataColorSpace,
+}
+
+impl InPlaceStage for StagePcsMatrix {
+    fn transform(&self, dst: &mut [f32]) -> Result<(), CmsError> {
+        transform_pcs_matrix(dst, self.matrix, self.pcs);
+        Ok(())
+    }
+}
+
+pub(crate) struct StageSurroundCompensation {
+    pub(crate) gamma: f32,
+    pub(crate) pcs: DataColorSpace,
+}
+
+impl InPlaceStage for StageSurroundCompensation {
+    fn transform(&self, dst: &mut [f32]) -> Result<(), CmsError> {
+        compensate_pcs_surround(dst, self.gamma, self.pcs);
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{
+    CmsError, ColorProfile, Lab, Layout, RenderingIntent, Transform8BitExecutor,
+    TransformF32BitExecutor, TransformOptions,
+};
+
+/// Converts single colors between two profiles.
+///
+/// Meant for scattered calls from UI code, e.g. color pickers and swatches, where
+/// building a transform per color is too expensive. All transforms are built once in
+/// [ColorConverter::new], every conversion is a single pixel pass through them.
+pub struct ColorConverter {
+    rgb8: Box<Transform8BitExecutor>,
+    rgbf: Box<TransformF32BitExecutor>,
+    lab: Box<TransformF32BitExecutor>,
+}
+
+impl ColorConverter {
+    /// Creates converter from `src` RGB profile to `dst` profile with three channels.
+    pub fn new(
+        src: &ColorProfile,
+        dst: &ColorProfile,
+        intent: RenderingIntent,
+    ) -> Result<Self, CmsError> {
+        let options = TransformOptions {
+            rendering_intent: intent,
+            ..Default::default()
+        };
+        let rgb8 = src.create_transform_8bit(Layout::Rgb, dst, Layout::Rgb, options)?;
+        let rgbf = src.create_transform_f32(Layout::Rgb, dst, Layout::Rgb, options)?;
+        // Lab profile works on PCS XYZ, Lab values are converted before
+        let lab =
+            ColorProfile::new_lab().create_transform_f32(Layout::Rgb, dst, Layout::Rgb, options)?;
+        Ok(Self { rgb8, rgbf, lab })
+    }
+
+    /// Converts 8 bit color from source profile
+    pub fn convert_rgb8(&self, rgb: [u8; 3]) -> Result<[u8; 3], CmsError> {
+        let mut dst = [0u8; 3];
+        self.rgb8.transform(&rgb, &mut dst)?;
+        Ok(dst)
+    }
+
+    /// Converts normalized color from source profile
+    pub fn convert_rgbf(&self, rgb: [f32; 3]) -> Result<[f32; 3], CmsError> {
+        let mut dst = [0f32; 3];
+        self.rgbf.transform(&rgb, &mut dst)?;
+        Ok(dst)
+    }
+
+    /// Converts D50 CIE [Lab] color, L in range 0..100, into destination profile
+    pub fn convert_lab(&self, lab: Lab) -> Result<[f32; 3], CmsError> {
+        let xyz = lab.to_xyz();
+        let mut dst = [0f32; 3];
+        self.lab.transform(&[xyz.x, xyz.y, xyz.z], &mut dst)?;
+        Ok(dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_converter() {
+        let srgb = ColorProfile::new_srgb();
+        let display_p3 = ColorProfile::new_display_p3();
+        let converter =
+            ColorConverter::new(&srgb, &display_p3, RenderingIntent::Perceptual).unwrap();
+        let transform = srgb
+            .create_transform_8bit(Layout::Rgb, &display_p3, Layout::Rgb, Default::default())
+            .unwrap();
+        for rgb in [[255u8, 0, 0], [12, 200, 90], [255, 255, 255]] {
+            let mut expected = [0u8; 3];
+            transform.transform(&rgb, &mut expected).unwrap();
+            assert_eq!(converter.convert_rgb8(rgb).unwrap(), expected);
+        }
+
+        let white = converter.convert_rgbf([1., 1., 1.]).unwrap();
+        for v in white {
+            assert!((v - 1.).abs() < 1e-3, "{white:?}");
+        }
+
+        let white = converter.convert_lab(Lab::new(100., 0., 0.)).unwrap();
+        for v in white {
+            assert!((v - 1.).abs() < 1e-2, "{white:?}");
+        }
+        let black = converter.convert_lab(Lab::new(0., 0., 0.)).unwrap();
+        for v in black {
+            assert!(v.abs() < 1e-2, "{black:?}");
+        }
+    }
+}
@@ -0,0 +1,209 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::profile::MAX_CHANNELS;
+use crate::{CmsError, ColorProfile, Layout, Transform16BitExecutor, TransformOptions};
+use std::marker::PhantomData;
+
+const CHUNK_PIXELS: usize = 512;
+
+/// Transformation executor with different source and destination sample types.
+///
+/// Samples are widened into 16 bit pipeline in small chunks,
+/// so no separate pass over the whole image is required.
+pub trait CrossDepthExecutor<I, O> {
+    /// Count of samples always must match, see [crate::TransformExecutor::transform].
+    fn transform(&self, src: &[I], dst: &mut [O]) -> Result<(), CmsError>;
+}
+
+pub type Transform8To16BitExecutor = dyn CrossDepthExecutor<u8, u16> + Send + Sync;
+pub type Transform16To8BitExecutor = dyn CrossDepthExecutor<u16, u8> + Send + Sync;
+
+trait DepthSample: Copy + Send + Sync {
+    fn to_u16(self) -> u16;
+    fn from_u16(v: u16) -> Self;
+}
+
+impl DepthSample for u8 {
+    #[inline]
+    fn to_u16(self) -> u16 {
+        self as u16 * 257
+    }
+
+    #[inline]
+    fn from_u16(v: u16) -> Self {
+        ((v as u32 * 255 + 32767) / 65535) as u8
+    }
+}
+
+impl DepthSample for u16 {
+    #[inline]
+    fn to_u16(self) -> u16 {
+        self
+    }
+
+    #[inline]
+    fn from_u16(v: u16) -> Self {
+        v
+    }
+}
+
+struct CrossDepthTransform<I, O> {
+    executor: Box<Transform16BitExecutor>,
+    src_layout: Layout,
+    dst_layout: Layout,
+    _phantom: PhantomData<(I, O)>,
+}
+
+impl<I: DepthSample, O: DepthSample> CrossDepthExecutor<I, O> for CrossDepthTransform<I, O> {
+    fn transform(&self, src: &[I], dst: &mut [O]) -> Result<(), CmsError> {
+        let src_channels = self.src_layout.channels();
+        let dst_channels = self.dst_layout.channels();
+        if src.len() % src_channels != 0 || dst.len() % dst_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / src_channels != dst.len() / dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let mut src_working = [0u16; CHUNK_PIXELS * MAX_CHANNELS];
+        let mut dst_working = [0u16; CHUNK_PIXELS * MAX_CHANNELS];
+        for (src, dst) in src
+            .chunks(CHUNK_PIXELS * src_channels)
+            .zip(dst.chunks_mut(CHUNK_PIXELS * dst_channels))
+        {
+            let src_working = &mut src_working[..src.len()];
+            let dst_working = &mut dst_working[..dst.len()];
+            for (dst, &src) in src_working.iter_mut().zip(src.iter()) {
+                *dst = src.to_u16();
+            }
+            self.executor.transform(src_working, dst_working)?;
+            for (dst, &src) in dst.iter_mut().zip(dst_working.iter()) {
+                *dst = O::from_u16(src);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ColorProfile {
+    /// Creates transform from 8 bit source into 16 bit destination.
+    ///
+    /// Source is evaluated exactly as [ColorProfile::create_transform_16bit] would do
+    /// with samples scaled by 257.
+    pub fn create_transform_8to16(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<Transform8To16BitExecutor>, CmsError> {
+        self.create_cross_depth_transform::<u8, u16>(src_layout, dst_pr, dst_layout, options)
+    }
+
+    /// Creates transform from 16 bit source into 8 bit destination.
+    ///
+    /// Conversion runs in 16 bit and rounds only the final result.
+    pub fn create_transform_16to8(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<Transform16To8BitExecutor>, CmsError> {
+        self.create_cross_depth_transform::<u16, u8>(src_layout, dst_pr, dst_layout, options)
+    }
+
+    fn create_cross_depth_transform<I: DepthSample + 'static, O: DepthSample + 'static>(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<dyn CrossDepthExecutor<I, O> + Send + Sync>, CmsError> {
+        let executor = self.create_transform_16bit(src_layout, dst_pr, dst_layout, options)?;
+        Ok(Box::new(CrossDepthTransform {
+            executor,
+            src_layout,
+            dst_layout,
+            _phantom: PhantomData,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_depth_matches_16bit() {
+        let srgb = ColorProfile::new_srgb();
+        let pro_photo = ColorProfile::new_pro_photo_rgb();
+        let options = TransformOptions::default();
+        let src: Vec<u8> = (0..1500u32)
+            .flat_map(|i| [(i * 7) as u8, (i * 13 + 5) as u8, (i * 29 + 11) as u8, 200])
+            .collect();
+        let wide: Vec<u16> = src.iter().map(|&v| v as u16 * 257).collect();
+
+        let reference = srgb
+            .create_transform_16bit(Layout::Rgba, &pro_photo, Layout::Rgb, options)
+            .unwrap();
+        let mut expected = vec![0u16; 1500 * 3];
+        reference.transform(&wide, &mut expected).unwrap();
+
+        let widening = srgb
+            .create_transform_8to16(Layout::Rgba, &pro_photo, Layout::Rgb, options)
+            .unwrap();
+        let mut dst = vec![0u16; 1500 * 3];
+        widening.transform(&src, &mut dst).unwrap();
+        assert_eq!(dst, expected);
+
+        let narrowing = pro_photo
+            .create_transform_16to8(Layout::Rgb, &srgb, Layout::Rgba, options)
+            .unwrap();
+        let mut back = vec![0u8; 1500 * 4];
+        narrowing.transform(&dst, &mut back).unwrap();
+        for (src, back) in src.chunks_exact(4).zip(back.chunks_exact(4)) {
+            for c in 0..3 {
+                assert!(
+                    (src[c] as i32 - back[c] as i32).abs() <= 1,
+                    "Round trip {src:?} -> {back:?}"
+                );
+            }
+            assert_eq!(back[3], 255);
+        }
+
+        assert!(matches!(
+            narrowing.transform(&dst[..10], &mut back[..12]),
+            Err(CmsError::LaneMultipleOfChannels)
+        ));
+        assert!(matches!(
+            narrowing.transform(&dst[..9], &mut back[..16]),
+            Err(CmsError::LaneSizeMismatch)
+        ));
+    }
+}
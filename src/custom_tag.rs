@@ -0,0 +1,164 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::CmsError;
+use std::any::Any;
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+/// Parser and encoder for a tag signature unknown to this crate, e.g. ArgyllCMS `DevD` or `CIED`.
+///
+/// Handlers are registered with [register_custom_tag_handler]. Profiles read afterwards
+/// keep parsed values in [crate::ColorProfile::custom_tags], and encoding writes them back
+/// through the same handler.
+pub trait CustomTagHandler: Debug + Send + Sync {
+    /// Tag signature, e.g. `u32::from_be_bytes(*b"DevD")`
+    fn signature(&self) -> u32;
+
+    /// Parses whole tag data, starting with tag type signature.
+    fn parse(&self, data: &[u8]) -> Result<Box<dyn Any + Send + Sync>, CmsError>;
+
+    /// Encodes value previously produced by [CustomTagHandler::parse] or created by
+    /// application, starting with tag type signature.
+    fn encode(&self, value: &(dyn Any + Send + Sync)) -> Result<Vec<u8>, CmsError>;
+}
+
+/// Value of a custom tag parsed by [CustomTagHandler]
+#[derive(Debug, Clone)]
+pub struct CustomTag {
+    pub signature: u32,
+    pub value: Arc<dyn Any + Send + Sync>,
+}
+
+impl CustomTag {
+    pub fn new(signature: u32, value: impl Any + Send + Sync) -> CustomTag {
+        CustomTag {
+            signature,
+            value: Arc::new(value),
+        }
+    }
+
+    /// Returns value if it has type `T`
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+}
+
+static HANDLERS: RwLock<Vec<Arc<dyn CustomTagHandler>>> = RwLock::new(Vec::new());
+
+/// Registers process wide handler, replacing and returning handler of the same signature if any.
+pub fn register_custom_tag_handler(
+    handler: Arc<dyn CustomTagHandler>,
+) -> Option<Arc<dyn CustomTagHandler>> {
+    let mut handlers = HANDLERS.write().unwrap_or_else(|e| e.into_inner());
+    let signature = handler.signature();
+    match handlers.iter_mut().find(|h| h.signature() == signature) {
+        Some(existing) => Some(std::mem::replace(existing, handler)),
+        None => {
+            handlers.push(handler);
+            None
+        }
+    }
+}
+
+/// Removes handler for `signature`, returning it if it was registered.
+pub fn unregister_custom_tag_handler(signature: u32) -> Option<Arc<dyn CustomTagHandler>> {
+    let mut handlers = HANDLERS.write().unwrap_or_else(|e| e.into_inner());
+    let index = handlers.iter().position(|h| h.signature() == signature)?;
+    Some(handlers.remove(index))
+}
+
+pub(crate) fn custom_tag_handler(signature: u32) -> Option<Arc<dyn CustomTagHandler>> {
+    let handlers = HANDLERS.read().unwrap_or_else(|e| e.into_inner());
+    handlers
+        .iter()
+        .find(|h| h.signature() == signature)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColorProfile;
+
+    /// `DevD`-like tag holding a list of big endian u32 values
+    #[derive(Debug)]
+    struct ListHandler;
+
+    const SIGNATURE: u32 = u32::from_be_bytes(*b"tsTL");
+
+    impl CustomTagHandler for ListHandler {
+        fn signature(&self) -> u32 {
+            SIGNATURE
+        }
+
+        fn parse(&self, data: &[u8]) -> Result<Box<dyn Any + Send + Sync>, CmsError> {
+            if data.len() < 8 || &data[0..4] != b"tsTL" {
+                return Err(CmsError::InvalidProfile);
+            }
+            Ok(Box::new(
+                data[8..]
+                    .chunks_exact(4)
+                    .map(|v| u32::from_be_bytes(v.try_into().unwrap()))
+                    .collect::<Vec<u32>>(),
+            ))
+        }
+
+        fn encode(&self, value: &(dyn Any + Send + Sync)) -> Result<Vec<u8>, CmsError> {
+            let values = value
+                .downcast_ref::<Vec<u32>>()
+                .ok_or(CmsError::InvalidProfile)?;
+            let mut data = b"tsTL\0\0\0\0".to_vec();
+            for v in values {
+                data.extend_from_slice(&v.to_be_bytes());
+            }
+            Ok(data)
+        }
+    }
+
+    #[test]
+    fn test_custom_tag_round_trip() {
+        let mut srgb = ColorProfile::new_srgb();
+        srgb.custom_tags
+            .push(CustomTag::new(SIGNATURE, vec![1u32, 2, 3]));
+
+        // Without handler tag can't be written nor read
+        let encoded = srgb.encode().unwrap();
+        let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+        assert!(decoded.custom_tags.is_empty());
+
+        register_custom_tag_handler(Arc::new(ListHandler));
+        let encoded = srgb.encode().unwrap();
+        let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+        unregister_custom_tag_handler(SIGNATURE);
+        assert_eq!(
+            decoded.custom_tag::<Vec<u32>>(SIGNATURE),
+            Some(&vec![1u32, 2, 3])
+        );
+    }
+}
@@ -30,11 +30,12 @@ use crate::chad::BRADFORD_D;
 use crate::cicp::create_rec709_parametric;
 use crate::trc::{ToneReprCurve, curve_from_gamma};
 use crate::{
-    CicpColorPrimaries, CicpProfile, ColorPrimaries, ColorProfile, DataColorSpace,
+    CameraLog, CicpColorPrimaries, CicpProfile, ColorPrimaries, ColorProfile, DataColorSpace,
     LocalizableString, LutMultidimensionalType, LutWarehouse, Matrix3d, MatrixCoefficients,
     ProfileClass, ProfileText, RenderingIntent, TransferCharacteristics, Vector3, XyY,
 };
 use pxfm::{copysignk, exp, floor, pow};
+use std::sync::OnceLock;
 
 /// From lcms: `cmsWhitePointFromTemp`
 /// tempK must be >= 4000. and <= 25000.
@@ -78,6 +79,7 @@ const fn white_point_from_temperature(temp_k: i32) -> XyY {
 }
 
 pub const WHITE_POINT_D50: XyY = white_point_from_temperature(5003);
+pub const WHITE_POINT_D55: XyY = white_point_from_temperature(5503);
 pub const WHITE_POINT_D60: XyY = white_point_from_temperature(6000);
 pub const WHITE_POINT_D65: XyY = white_point_from_temperature(6504);
 pub const WHITE_POINT_DCI_P3: XyY = white_point_from_temperature(6300);
@@ -180,6 +182,8 @@ pub const PQ_LUT_TABLE: [u16; 4096] = build_trc_table_pq();
 /// Hybrid Log Gamma Lookup table
 pub const HLG_LUT_TABLE: [u16; 4096] = build_trc_table_hlg();
 
+// Built-in profiles without parameters are immutable, so each one is built once
+// on first use and cloned afterwards.
 impl ColorProfile {
     const SRGB_COLORANTS: Matrix3d =
         ColorProfile::colorants_matrix(WHITE_POINT_D65, ColorPrimaries::BT_709);
@@ -206,7 +210,7 @@ impl ColorProfile {
         ColorProfile::colorants_matrix(WHITE_POINT_D60, ColorPrimaries::ACES_CG);
 
     #[inline]
-    fn basic_rgb_profile() -> ColorProfile {
+    pub(crate) fn basic_rgb_profile() -> ColorProfile {
         ColorProfile {
             profile_class: ProfileClass::DisplayDevice,
             rendering_intent: RenderingIntent::Perceptual,
@@ -227,6 +231,11 @@ impl ColorProfile {
 
     /// Creates new sRGB profile
     pub fn new_srgb() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE.get_or_init(ColorProfile::build_srgb).clone()
+    }
+
+    fn build_srgb() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::SRGB_COLORANTS);
 
@@ -255,8 +264,30 @@ impl ColorProfile {
         profile
     }
 
+    /// Re-derives RGB colorants for the custom white point.
+    /// CICP is dropped since it always implies its own white point.
+    fn adapt_rgb_to_white_point(&mut self, white_point: XyY, primaries: ColorPrimaries) {
+        self.update_rgb_colorimetry(white_point, primaries);
+        self.media_white_point = Some(white_point.to_xyzd());
+        self.cicp = None;
+    }
+
+    /// Creates new sRGB profile with custom white point.
+    ///
+    /// Colorants are derived from Rec.709 primaries and provided white point.
+    pub fn new_srgb_with_white_point(white_point: XyY) -> ColorProfile {
+        let mut profile = ColorProfile::new_srgb();
+        profile.adapt_rgb_to_white_point(white_point, ColorPrimaries::BT_709);
+        profile
+    }
+
     /// Creates new Adobe RGB profile
     pub fn new_adobe_rgb() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE.get_or_init(ColorProfile::build_adobe_rgb).clone()
+    }
+
+    fn build_adobe_rgb() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::ADOBE_RGB_COLORANTS);
 
@@ -279,8 +310,20 @@ impl ColorProfile {
         profile
     }
 
+    /// Creates new Adobe RGB profile with custom white point
+    pub fn new_adobe_rgb_with_white_point(white_point: XyY) -> ColorProfile {
+        let mut profile = ColorProfile::new_adobe_rgb();
+        profile.adapt_rgb_to_white_point(white_point, ColorPrimaries::ADOBE_RGB);
+        profile
+    }
+
     /// Creates new Display P3 profile
     pub fn new_display_p3() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE.get_or_init(ColorProfile::build_display_p3).clone()
+    }
+
+    fn build_display_p3() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::DISPLAY_P3_COLORANTS);
 
@@ -291,7 +334,7 @@ impl ColorProfile {
         profile.green_trc = Some(curve);
         profile.media_white_point = Some(WHITE_POINT_D65.to_xyzd());
         profile.cicp = Some(CicpProfile {
-            color_primaries: CicpColorPrimaries::Smpte431,
+            color_primaries: CicpColorPrimaries::Smpte432,
             transfer_characteristics: TransferCharacteristics::Srgb,
             matrix_coefficients: MatrixCoefficients::Bt709,
             full_range: false,
@@ -309,8 +352,22 @@ impl ColorProfile {
         profile
     }
 
+    /// Creates new Display P3 profile with custom white point
+    pub fn new_display_p3_with_white_point(white_point: XyY) -> ColorProfile {
+        let mut profile = ColorProfile::new_display_p3();
+        profile.adapt_rgb_to_white_point(white_point, ColorPrimaries::SMPTE_432);
+        profile
+    }
+
     /// Creates new Display P3 PQ profile
     pub fn new_display_p3_pq() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE
+            .get_or_init(ColorProfile::build_display_p3_pq)
+            .clone()
+    }
+
+    fn build_display_p3_pq() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::DISPLAY_P3_COLORANTS);
 
@@ -321,7 +378,7 @@ impl ColorProfile {
         profile.green_trc = Some(curve);
         profile.media_white_point = Some(WHITE_POINT_D65.to_xyzd());
         profile.cicp = Some(CicpProfile {
-            color_primaries: CicpColorPrimaries::Smpte431,
+            color_primaries: CicpColorPrimaries::Smpte432,
             transfer_characteristics: TransferCharacteristics::Smpte2084,
             matrix_coefficients: MatrixCoefficients::Bt709,
             full_range: false,
@@ -341,6 +398,11 @@ impl ColorProfile {
 
     /// Creates new DCI P3 profile
     pub fn new_dci_p3() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE.get_or_init(ColorProfile::build_dci_p3).clone()
+    }
+
+    fn build_dci_p3() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::DCI_P3_COLORANTS);
 
@@ -349,12 +411,8 @@ impl ColorProfile {
         profile.blue_trc = Some(curve.clone());
         profile.green_trc = Some(curve);
         profile.media_white_point = Some(WHITE_POINT_DCI_P3.to_xyzd());
-        profile.cicp = Some(CicpProfile {
-            color_primaries: CicpColorPrimaries::Smpte432,
-            transfer_characteristics: TransferCharacteristics::Srgb,
-            matrix_coefficients: MatrixCoefficients::Bt709,
-            full_range: false,
-        });
+        // No CICP: white point is 6300K daylight rather than SMPTE 431 white,
+        // and there is no code point for pure 2.6 gamma
         profile.description = Some(ProfileText::Localizable(vec![LocalizableString::new(
             "en".to_string(),
             "US".to_string(),
@@ -370,6 +428,13 @@ impl ColorProfile {
 
     /// Creates new ProPhoto RGB profile
     pub fn new_pro_photo_rgb() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE
+            .get_or_init(ColorProfile::build_pro_photo_rgb)
+            .clone()
+    }
+
+    fn build_pro_photo_rgb() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::PRO_PHOTO_RGB_COLORANTS);
 
@@ -393,6 +458,11 @@ impl ColorProfile {
 
     /// Creates new Bt.2020 profile
     pub fn new_bt2020() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE.get_or_init(ColorProfile::build_bt2020).clone()
+    }
+
+    fn build_bt2020() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::BT2020_COLORANTS);
 
@@ -414,8 +484,20 @@ impl ColorProfile {
         profile
     }
 
+    /// Creates new Bt.2020 profile with custom white point
+    pub fn new_bt2020_with_white_point(white_point: XyY) -> ColorProfile {
+        let mut profile = ColorProfile::new_bt2020();
+        profile.adapt_rgb_to_white_point(white_point, ColorPrimaries::BT_2020);
+        profile
+    }
+
     /// Creates new Bt.2020 PQ profile
     pub fn new_bt2020_pq() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE.get_or_init(ColorProfile::build_bt2020_pq).clone()
+    }
+
+    fn build_bt2020_pq() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::BT2020_COLORANTS);
 
@@ -446,6 +528,11 @@ impl ColorProfile {
 
     /// Creates new Bt.2020 HLG profile
     pub fn new_bt2020_hlg() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE.get_or_init(ColorProfile::build_bt2020_hlg).clone()
+    }
+
+    fn build_bt2020_hlg() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::BT2020_COLORANTS);
 
@@ -495,6 +582,13 @@ impl ColorProfile {
 
     /// Creates new ACES 2065-1/AP0 profile
     pub fn new_aces_aces_2065_1_linear() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE
+            .get_or_init(ColorProfile::build_aces_aces_2065_1_linear)
+            .clone()
+    }
+
+    fn build_aces_aces_2065_1_linear() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::ACES_2065_1_COLORANTS);
 
@@ -518,6 +612,13 @@ impl ColorProfile {
 
     /// Creates new ACEScg profile
     pub fn new_aces_cg_linear() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE
+            .get_or_init(ColorProfile::build_aces_cg_linear)
+            .clone()
+    }
+
+    fn build_aces_cg_linear() -> ColorProfile {
         let mut profile = ColorProfile::basic_rgb_profile();
         profile.update_colorants(ColorProfile::ACES_CG_COLORANTS);
 
@@ -539,8 +640,39 @@ impl ColorProfile {
         profile
     }
 
+    /// Creates new camera log profile with native primaries of the camera, see [CameraLog::curve]
+    pub fn new_camera_log(log: CameraLog) -> ColorProfile {
+        let mut profile = ColorProfile::basic_rgb_profile();
+        profile.update_colorants(ColorProfile::colorants_matrix(
+            WHITE_POINT_D65,
+            log.primaries(),
+        ));
+
+        let curve = log.curve();
+        profile.red_trc = Some(curve.clone());
+        profile.blue_trc = Some(curve.clone());
+        profile.green_trc = Some(curve);
+        profile.media_white_point = Some(WHITE_POINT_D65.to_xyzd());
+        let name = match log {
+            CameraLog::SLog3 => "S-Log3/S-Gamut3.Cine",
+            CameraLog::VLog => "V-Log/V-Gamut",
+            CameraLog::CLog3 => "Canon Log 3/Cinema Gamut",
+        };
+        profile.description = Some(ProfileText::Localizable(vec![LocalizableString::new(
+            "en".to_string(),
+            "US".to_string(),
+            name.to_string(),
+        )]));
+        profile
+    }
+
     /// Creates new Generic CIE LAB profile
     pub fn new_lab() -> ColorProfile {
+        static PROFILE: OnceLock<ColorProfile> = OnceLock::new();
+        PROFILE.get_or_init(ColorProfile::build_lab).clone()
+    }
+
+    fn build_lab() -> ColorProfile {
         let mut profile = ColorProfile {
             profile_class: ProfileClass::DisplayDevice,
             rendering_intent: RenderingIntent::Perceptual,
@@ -602,3 +734,35 @@ impl ColorProfile {
         profile
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_profiles_cached() {
+        let profiles = std::thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|_| scope.spawn(ColorProfile::new_srgb))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|x| x.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        let built = ColorProfile::build_srgb();
+        for profile in profiles.iter() {
+            assert_eq!(profile.red_trc, built.red_trc);
+            assert_eq!(profile.red_colorant, built.red_colorant);
+            assert_eq!(profile.media_white_point, built.media_white_point);
+        }
+        assert_eq!(
+            ColorProfile::new_bt2020_pq().green_trc,
+            ColorProfile::build_bt2020_pq().green_trc
+        );
+
+        let mut adapted = ColorProfile::new_srgb();
+        adapted.cicp = None;
+        assert!(ColorProfile::new_srgb().cicp.is_some());
+    }
+}
@@ -0,0 +1,285 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::{LutBarycentricReduction, make_device_link_lut_transform};
+use crate::transform::PointeeSizeExpressible;
+use crate::trc::GammaLutInterpolate;
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, Layout, LutMultidimensionalType, LutStore,
+    LutWarehouse, Matrix3d, ProfileClass, ProfileVersion, ToneReprCurve, TransformExecutor,
+    TransformOptions, Vector3d,
+};
+use num_traits::AsPrimitive;
+
+/// Samples `evaluate` on a regular grid, first input has the largest stride as ICC CLUT has
+pub(crate) fn sample_grid(
+    inputs: usize,
+    outputs: usize,
+    grid_size: usize,
+    mut evaluate: impl FnMut(&[f64], &mut [f64]) -> Result<(), CmsError>,
+) -> Result<Vec<f32>, CmsError> {
+    let nodes = grid_size.pow(inputs as u32);
+    let mut grid = vec![0f32; nodes * outputs];
+    let mut src = vec![0f64; inputs];
+    let mut dst = vec![0f64; outputs];
+    let scale = 1. / (grid_size - 1) as f64;
+    for (node, values) in grid.chunks_exact_mut(outputs).enumerate() {
+        let mut index = node;
+        for v in src.iter_mut().rev() {
+            *v = (index % grid_size) as f64 * scale;
+            index /= grid_size;
+        }
+        evaluate(&src, &mut dst)?;
+        for (dst, &v) in values.iter_mut().zip(dst.iter()) {
+            *dst = v as f32;
+        }
+    }
+    Ok(grid)
+}
+
+impl ColorProfile {
+    /// Bakes transform from `source` to `dest` into a device link profile.
+    ///
+    /// Whole chain with `options` is sampled on a grid of `grid_size` nodes per input into
+    /// `A2B0` of [ProfileClass::DeviceLink] profile. Color space of the link is the source
+    /// color space, its PCS field holds the destination color space as ICC requires.
+    pub fn new_device_link(
+        source: &ColorProfile,
+        dest: &ColorProfile,
+        options: TransformOptions,
+        grid_size: u8,
+    ) -> Result<ColorProfile, CmsError> {
+        let inputs = source.color_space.channels();
+        let outputs = dest.color_space.channels();
+        if grid_size < 2 {
+            return Err(CmsError::IncorrectlyFormedLut(
+                "Device link grid must have at least 2 nodes".to_string(),
+            ));
+        }
+        if (grid_size as usize)
+            .checked_pow(inputs as u32)
+            .and_then(|x| x.checked_mul(outputs))
+            .is_none_or(|x| x > 10_000_000)
+        {
+            return Err(CmsError::IncorrectlyFormedLut(
+                "Device link grid exceeds 10_000_000 points".to_string(),
+            ));
+        }
+        let transform = source.create_transform_f32(
+            source.color_space.suggested_layout(),
+            dest,
+            dest.color_space.suggested_layout(),
+            options,
+        )?;
+        let mut src = vec![0f32; inputs];
+        let mut dst = vec![0f32; outputs];
+        let grid = sample_grid(inputs, outputs, grid_size as usize, |v, out| {
+            for (src, &v) in src.iter_mut().zip(v.iter()) {
+                *src = v as f32;
+            }
+            transform.transform(&src, &mut dst)?;
+            for (out, &v) in out.iter_mut().zip(dst.iter()) {
+                *out = v as f64;
+            }
+            Ok(())
+        })?;
+        let clut = grid
+            .iter()
+            .map(|&v| (v.max(0.).min(1.) * 65535. + 0.5) as u16)
+            .collect::<Vec<u16>>();
+
+        let mut grid_points = [0u8; 16];
+        grid_points[..inputs].fill(grid_size);
+        let lut = LutWarehouse::Multidimensional(LutMultidimensionalType {
+            num_input_channels: inputs as u8,
+            num_output_channels: outputs as u8,
+            grid_points,
+            clut: Some(LutStore::Store16(clut.into())),
+            a_curves: vec![ToneReprCurve::Lut(vec![]); inputs],
+            b_curves: vec![ToneReprCurve::Lut(vec![]); outputs],
+            m_curves: vec![],
+            matrix: Matrix3d::IDENTITY,
+            bias: Vector3d::default(),
+        });
+        Ok(ColorProfile {
+            profile_class: ProfileClass::DeviceLink,
+            color_space: source.color_space,
+            pcs: dest.color_space,
+            rendering_intent: options.rendering_intent,
+            version_internal: ProfileVersion::V4_0,
+            lut_a_to_b_perceptual: Some(lut),
+            ..Default::default()
+        })
+    }
+
+    /// Color space produced by device link, `None` for other profile classes
+    pub fn device_link_output(&self) -> Option<DataColorSpace> {
+        if self.profile_class == ProfileClass::DeviceLink {
+            Some(self.pcs)
+        } else {
+            None
+        }
+    }
+}
+
+/// Creates executor of device link `link`, `dest` only has to share the link output color space
+pub(crate) fn make_device_link_transform<
+    T: Copy
+        + Default
+        + AsPrimitive<f32>
+        + Send
+        + Sync
+        + AsPrimitive<usize>
+        + PointeeSizeExpressible
+        + GammaLutInterpolate,
+    const BIT_DEPTH: usize,
+>(
+    src_layout: Layout,
+    link: &ColorProfile,
+    dst_layout: Layout,
+    dest: &ColorProfile,
+    options: TransformOptions,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    f32: AsPrimitive<T>,
+    u32: AsPrimitive<T>,
+    (): LutBarycentricReduction<T, u8>,
+    (): LutBarycentricReduction<T, u16>,
+{
+    if dest.color_space != link.pcs {
+        return Err(CmsError::UnsupportedProfileConnection);
+    }
+    link.color_space.check_layout(src_layout)?;
+    dest.color_space.check_layout(dst_layout)?;
+    let lut = link
+        .lut_a_to_b_perceptual
+        .as_ref()
+        .ok_or(CmsError::UnsupportedProfileConnection)?;
+    let (lut_inputs, lut_outputs) = match lut {
+        LutWarehouse::Lut(lut) => (lut.num_input_channels, lut.num_output_channels),
+        LutWarehouse::Multidimensional(mab) => (mab.num_input_channels, mab.num_output_channels),
+        LutWarehouse::MultiProcess(mpe) => (mpe.num_input_channels, mpe.num_output_channels),
+    };
+    if lut_inputs as usize != link.color_space.channels()
+        || lut_outputs as usize != link.pcs.channels()
+    {
+        return Err(CmsError::UnsupportedChannelConfiguration);
+    }
+    make_device_link_lut_transform::<T, BIT_DEPTH>(src_layout, link, lut, dst_layout, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_link() {
+        let srgb = ColorProfile::new_srgb();
+        let cmyk =
+            ColorProfile::new_from_slice(include_bytes!("../assets/us_swop_coated.icc")).unwrap();
+        let options = TransformOptions::default();
+        let link = ColorProfile::new_device_link(&srgb, &cmyk, options, 33).unwrap();
+        assert_eq!(link.device_link_output(), Some(DataColorSpace::Cmyk));
+        let link = ColorProfile::new_from_slice(&link.encode().unwrap()).unwrap();
+        assert_eq!(link.profile_class, ProfileClass::DeviceLink);
+
+        let src = (0..=16u16)
+            .flat_map(|v| [v * 4095, 65535 - v * 4095, (v * 2047) % 65535])
+            .collect::<Vec<u16>>();
+        let mut direct = vec![0u16; src.len() / 3 * 4];
+        let mut linked = vec![0u16; src.len() / 3 * 4];
+        srgb.create_transform_16bit(Layout::Rgb, &cmyk, Layout::Rgba, options)
+            .unwrap()
+            .transform(&src, &mut direct)
+            .unwrap();
+        link.create_transform_16bit(Layout::Rgb, &cmyk, Layout::Rgba, options)
+            .unwrap()
+            .transform(&src, &mut linked)
+            .unwrap();
+        for (&a, &b) in direct.iter().zip(linked.iter()) {
+            assert!(a.abs_diff(b) < 1200, "{a} vs {b}");
+        }
+
+        assert!(
+            link.create_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, options)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_device_link_cmyk() {
+        let srgb = ColorProfile::new_srgb();
+        let cmyk =
+            ColorProfile::new_from_slice(include_bytes!("../assets/us_swop_coated.icc")).unwrap();
+        let options = TransformOptions::default();
+        let src = (0..=16u16)
+            .flat_map(|v| {
+                [
+                    v * 4095,
+                    65535 - v * 4095,
+                    (v * 2047) % 65535,
+                    (v * 3071) % 65535,
+                ]
+            })
+            .collect::<Vec<u16>>();
+
+        // 4 to 4 inks link is evaluated directly without a PCS
+        let link = ColorProfile::new_device_link(&cmyk, &cmyk, options, 17).unwrap();
+        let mut direct = vec![0u16; src.len()];
+        let mut linked = vec![0u16; src.len()];
+        cmyk.create_transform_16bit(Layout::Rgba, &cmyk, Layout::Rgba, options)
+            .unwrap()
+            .transform(&src, &mut direct)
+            .unwrap();
+        link.create_transform_16bit(Layout::Rgba, &cmyk, Layout::Rgba, options)
+            .unwrap()
+            .transform(&src, &mut linked)
+            .unwrap();
+        for (&a, &b) in direct.iter().zip(linked.iter()) {
+            assert!(a.abs_diff(b) < 1200, "{a} vs {b}");
+        }
+
+        let link = ColorProfile::new_device_link(&cmyk, &srgb, options, 17).unwrap();
+        let mut direct = vec![0u16; src.len()];
+        let mut linked = vec![0u16; src.len()];
+        cmyk.create_transform_16bit(Layout::Rgba, &srgb, Layout::Rgba, options)
+            .unwrap()
+            .transform(&src, &mut direct)
+            .unwrap();
+        link.create_transform_16bit(Layout::Rgba, &srgb, Layout::Rgba, options)
+            .unwrap()
+            .transform(&src, &mut linked)
+            .unwrap();
+        for (a, b) in direct.chunks_exact(4).zip(linked.chunks_exact(4)) {
+            for (&a, &b) in a[..3].iter().zip(b[..3].iter()) {
+                assert!(a.abs_diff(b) < 1200, "{a} vs {b}");
+            }
+            assert_eq!(b[3], 65535);
+        }
+    }
+}
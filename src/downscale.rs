@@ -0,0 +1,251 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Resizing in linear light.
+//!
+//! Averaging gamma encoded values darkens high contrast detail and shifts colors,
+//! so pixels are linearized, resampled and encoded back.
+use crate::{CmsError, Layout, LinearLightTransform};
+use num_traits::AsPrimitive;
+use std::f32::consts::PI;
+
+/// Resampling filter used by [LinearLightTransform::downscale]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum DownscaleFilter {
+    /// Area average, each source pixel contributes proportionally to its coverage
+    Box,
+    /// Lanczos windowed sinc with 3 lobes, sharper but may ring near edges
+    #[default]
+    Lanczos3,
+}
+
+/// Contribution of source pixels into a single destination pixel along one axis
+struct Contribution {
+    start: usize,
+    weights: Vec<f32>,
+}
+
+fn lanczos3(x: f32) -> f32 {
+    if x == 0. {
+        return 1.;
+    }
+    if x.abs() >= 3. {
+        return 0.;
+    }
+    let px = PI * x;
+    3. * px.sin() * (px / 3.).sin() / (px * px)
+}
+
+fn contributions(src_len: usize, dst_len: usize, filter: DownscaleFilter) -> Vec<Contribution> {
+    let scale = src_len as f32 / dst_len as f32;
+    (0..dst_len)
+        .map(|i| {
+            let (start, mut weights) = match filter {
+                DownscaleFilter::Box => {
+                    let from = i as f32 * scale;
+                    let to = from + scale;
+                    let start = (from.floor() as usize).min(src_len - 1);
+                    let end = (to.ceil() as usize).clamp(start + 1, src_len);
+                    let weights = (start..end)
+                        .map(|j| (to.min(j as f32 + 1.) - from.max(j as f32)).max(0.))
+                        .collect::<Vec<f32>>();
+                    (start, weights)
+                }
+                DownscaleFilter::Lanczos3 => {
+                    let stretch = scale.max(1.);
+                    let center = (i as f32 + 0.5) * scale - 0.5;
+                    let support = 3. * stretch;
+                    let start = (center - support).ceil().max(0.) as usize;
+                    let end = ((center + support).floor() as usize + 1).min(src_len);
+                    let start = start.min(end - 1);
+                    let weights = (start..end)
+                        .map(|j| lanczos3((j as f32 - center) / stretch))
+                        .collect::<Vec<f32>>();
+                    (start, weights)
+                }
+            };
+            let sum = weights.iter().sum::<f32>();
+            if sum != 0. {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            }
+            Contribution { start, weights }
+        })
+        .collect()
+}
+
+impl<T: Copy + AsPrimitive<f32>> LinearLightTransform<T>
+where
+    f32: AsPrimitive<T>,
+{
+    /// Converts `src` image and resizes it in linear light into `dst`.
+    ///
+    /// Images are tightly packed rows of source and destination layouts.
+    /// Alpha, when present, is premultiplied while filtering.
+    #[allow(clippy::too_many_arguments)]
+    pub fn downscale(
+        &self,
+        src: &[T],
+        src_width: usize,
+        src_height: usize,
+        dst: &mut [T],
+        dst_width: usize,
+        dst_height: usize,
+        filter: DownscaleFilter,
+    ) -> Result<(), CmsError> {
+        let src_layout = self.linearizer().src_layout();
+        let layout = self.encoder().layout();
+        let channels = layout.channels();
+        if src.len() != src_width * src_height * src_layout.channels()
+            || dst.len() != dst_width * dst_height * channels
+        {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        if dst.is_empty() {
+            return Ok(());
+        }
+        if src.is_empty() {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let has_alpha = layout == Layout::Rgba;
+
+        let mut linear = vec![0f32; src_width * src_height * channels];
+        self.linearizer().linearize(src, &mut linear)?;
+        if has_alpha {
+            for px in linear.chunks_exact_mut(4) {
+                let a = px[3].clamp(0., 1.);
+                px[0] *= a;
+                px[1] *= a;
+                px[2] *= a;
+            }
+        }
+
+        let horizontal = contributions(src_width, dst_width, filter);
+        let mut columns = vec![0f32; dst_width * src_height * channels];
+        for (src_row, dst_row) in linear
+            .chunks_exact(src_width * channels)
+            .zip(columns.chunks_exact_mut(dst_width * channels))
+        {
+            for (dst_px, contribution) in dst_row.chunks_exact_mut(channels).zip(horizontal.iter())
+            {
+                for (k, &w) in contribution.weights.iter().enumerate() {
+                    let src_px = &src_row[(contribution.start + k) * channels..];
+                    for (dst, &src) in dst_px.iter_mut().zip(src_px.iter()) {
+                        *dst += src * w;
+                    }
+                }
+            }
+        }
+
+        let vertical = contributions(src_height, dst_height, filter);
+        let row_len = dst_width * channels;
+        let mut resized = vec![0f32; dst_height * row_len];
+        for (dst_row, contribution) in resized.chunks_exact_mut(row_len).zip(vertical.iter()) {
+            for (k, &w) in contribution.weights.iter().enumerate() {
+                let start = (contribution.start + k) * row_len;
+                for (dst, &src) in dst_row
+                    .iter_mut()
+                    .zip(columns[start..start + row_len].iter())
+                {
+                    *dst += src * w;
+                }
+            }
+        }
+
+        if has_alpha {
+            for px in resized.chunks_exact_mut(4) {
+                let a = px[3].clamp(0., 1.);
+                let scale = if a > 0. { 1. / a } else { 0. };
+                px[0] = (px[0] * scale).max(0.);
+                px[1] = (px[1] * scale).max(0.);
+                px[2] = (px[2] * scale).max(0.);
+                px[3] = a;
+            }
+        } else {
+            // Lanczos lobes may undershoot near edges
+            resized.iter_mut().for_each(|v| *v = v.max(0.));
+        }
+        self.encoder().encode(&resized, dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColorProfile, TransformOptions};
+
+    #[test]
+    fn test_downscale_in_linear_light() {
+        let srgb = ColorProfile::new_srgb();
+        let transform = srgb
+            .create_linear_light_transform_8bit(
+                Layout::Rgb,
+                &srgb,
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        // Checkerboard of black and white averages to linear middle gray, not to 128
+        let (width, height) = (8, 8);
+        let src = (0..width * height)
+            .flat_map(|i| {
+                let v = if (i % width + i / width) % 2 == 0 {
+                    255u8
+                } else {
+                    0
+                };
+                [v, v, v]
+            })
+            .collect::<Vec<u8>>();
+        for filter in [DownscaleFilter::Box, DownscaleFilter::Lanczos3] {
+            let mut dst = vec![0u8; 2 * 2 * 3];
+            transform
+                .downscale(&src, width, height, &mut dst, 2, 2, filter)
+                .unwrap();
+            for &v in dst.iter() {
+                assert!((v as i32 - 188).abs() <= 2, "{filter:?} {dst:?}");
+            }
+        }
+
+        // Flat color stays the same at any ratio
+        let flat = [200u8, 30, 90].repeat(7 * 5);
+        let mut dst = vec![0u8; 3 * 2 * 3];
+        transform
+            .downscale(&flat, 7, 5, &mut dst, 3, 2, DownscaleFilter::Lanczos3)
+            .unwrap();
+        for px in dst.chunks_exact(3) {
+            for (&a, &b) in px.iter().zip([200u8, 30, 90].iter()) {
+                assert!((a as i32 - b as i32).abs() <= 1, "{dst:?}");
+            }
+        }
+        assert!(
+            transform
+                .downscale(&flat, 7, 4, &mut dst, 3, 2, DownscaleFilter::Box)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,146 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{
+    Chromaticity, CmsError, ColorPrimaries, ColorProfile, LocalizableString, ProfileText, XyY,
+    curve_from_gamma,
+};
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+impl ColorProfile {
+    /// Creates display profile from EDID base block.
+    ///
+    /// Colorimetry is as good as monitor vendor reports, it's a fallback when
+    /// no calibrated ICC profile exists. Gamma is pure power curve from EDID,
+    /// undefined gamma is treated as 2.2.
+    pub fn new_from_edid(edid: &[u8]) -> Result<ColorProfile, CmsError> {
+        if edid.len() < 128 || edid[..8] != EDID_HEADER {
+            return Err(CmsError::InvalidProfile);
+        }
+        let lo_a = edid[25];
+        let lo_b = edid[26];
+        let coordinate = |hi: u8, lo: u8, shift: u8| -> f32 {
+            let value = ((hi as u32) << 2) | ((lo >> shift) & 0b11) as u32;
+            value as f32 / 1024.
+        };
+        let red = Chromaticity::new(coordinate(edid[27], lo_a, 6), coordinate(edid[28], lo_a, 4));
+        let green = Chromaticity::new(coordinate(edid[29], lo_a, 2), coordinate(edid[30], lo_a, 0));
+        let blue = Chromaticity::new(coordinate(edid[31], lo_b, 6), coordinate(edid[32], lo_b, 4));
+        let white = Chromaticity::new(coordinate(edid[33], lo_b, 2), coordinate(edid[34], lo_b, 0));
+        if [red, green, blue, white].iter().any(|c| c.y <= 0.) {
+            return Err(CmsError::InvalidProfile);
+        }
+        let gamma = if edid[23] == 0xFF {
+            2.2
+        } else {
+            (edid[23] as f32 + 100.) / 100.
+        };
+
+        let white_point = XyY {
+            x: white.x as f64,
+            y: white.y as f64,
+            yb: 1.,
+        };
+        let mut profile = ColorProfile::basic_rgb_profile();
+        profile.update_rgb_colorimetry(white_point, ColorPrimaries { red, green, blue });
+        let curve = curve_from_gamma(gamma);
+        profile.red_trc = Some(curve.clone());
+        profile.green_trc = Some(curve.clone());
+        profile.blue_trc = Some(curve);
+        profile.media_white_point = Some(white_point.to_xyzd());
+        if let Some(name) = Self::edid_monitor_name(edid) {
+            profile.description = Some(ProfileText::Localizable(vec![LocalizableString::new(
+                "en".to_string(),
+                "US".to_string(),
+                name,
+            )]));
+        }
+        Ok(profile)
+    }
+
+    /// Monitor name is stored in display descriptor with tag `0xFC`
+    fn edid_monitor_name(edid: &[u8]) -> Option<String> {
+        edid[54..126]
+            .chunks_exact(18)
+            .find(|d| d[0] == 0 && d[1] == 0 && d[3] == 0xFC)
+            .map(|d| {
+                d[5..]
+                    .iter()
+                    .take_while(|&&c| c != 0x0A && c != 0)
+                    .map(|&c| c as char)
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            })
+            .filter(|name| !name.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_from_edid() {
+        let mut edid = [0u8; 128];
+        edid[..8].copy_from_slice(&EDID_HEADER);
+        edid[23] = 120;
+        // Rec.709 primaries and D65 white, 10 bit coordinates
+        let encode = |v: f32| (v * 1024.).round() as u32;
+        let coordinates = [0.64, 0.33, 0.30, 0.60, 0.15, 0.06, 0.3127, 0.3290].map(encode);
+        edid[25] = coordinates[..4]
+            .iter()
+            .enumerate()
+            .fold(0u8, |acc, (i, &c)| acc | (((c & 3) as u8) << (6 - i * 2)));
+        edid[26] = coordinates[4..]
+            .iter()
+            .enumerate()
+            .fold(0u8, |acc, (i, &c)| acc | (((c & 3) as u8) << (6 - i * 2)));
+        for (i, &c) in coordinates.iter().enumerate() {
+            edid[27 + i] = (c >> 2) as u8;
+        }
+        edid[54..59].copy_from_slice(&[0, 0, 0, 0xFC, 0]);
+        edid[59..67].copy_from_slice(b"Monitor\n");
+
+        let profile = ColorProfile::new_from_edid(&edid).unwrap();
+        let srgb = ColorProfile::new_srgb();
+        let diff = |a: f64, b: f64| (a - b).abs() < 2e-3;
+        assert!(diff(profile.red_colorant.x, srgb.red_colorant.x));
+        assert!(diff(profile.green_colorant.y, srgb.green_colorant.y));
+        assert!(diff(profile.blue_colorant.z, srgb.blue_colorant.z));
+        assert_eq!(profile.red_trc, Some(curve_from_gamma(2.2)));
+        assert!(matches!(
+            profile.description,
+            Some(ProfileText::Localizable(ref v)) if v[0].value == "Monitor"
+        ));
+
+        edid[0] = 1;
+        assert!(ColorProfile::new_from_edid(&edid).is_err());
+    }
+}
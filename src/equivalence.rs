@@ -0,0 +1,337 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Scalar vs SIMD equivalence harness.
+//!
+//! Runtime dispatch goes through `x86_feature_detected!` and `aarch64_feature_detected!`,
+//! with `equivalence` feature enabled a backend may be forced for the current thread,
+//! so the same transform might be created once per backend and compared against scalar one.
+//!
+//! NEON itself is selected at compile time, so on aarch64 it is the baseline and only
+//! runtime detected extensions are compared.
+
+#[cfg(all(
+    feature = "equivalence",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+#[allow(unused_macros)]
+macro_rules! x86_feature_detected {
+    ($feature:tt) => {
+        std::arch::is_x86_feature_detected!($feature)
+            && crate::equivalence::backend_allowed(
+                crate::equivalence::SimdBackend::from_cpu_feature($feature),
+            )
+    };
+}
+
+#[cfg(all(
+    not(feature = "equivalence"),
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+#[allow(unused_macros)]
+macro_rules! x86_feature_detected {
+    ($feature:tt) => {
+        std::arch::is_x86_feature_detected!($feature)
+    };
+}
+
+#[cfg(all(feature = "equivalence", target_arch = "aarch64"))]
+#[allow(unused_macros)]
+macro_rules! aarch64_feature_detected {
+    ($feature:tt) => {
+        std::arch::is_aarch64_feature_detected!($feature)
+            && crate::equivalence::backend_allowed(
+                crate::equivalence::SimdBackend::from_cpu_feature($feature),
+            )
+    };
+}
+
+#[cfg(all(not(feature = "equivalence"), target_arch = "aarch64"))]
+#[allow(unused_macros)]
+macro_rules! aarch64_feature_detected {
+    ($feature:tt) => {
+        std::arch::is_aarch64_feature_detected!($feature)
+    };
+}
+
+#[cfg(target_arch = "aarch64")]
+#[allow(unused_imports)]
+pub(crate) use aarch64_feature_detected;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_imports)]
+pub(crate) use x86_feature_detected;
+
+#[cfg(feature = "equivalence")]
+mod harness {
+    use crate::{CmsError, TransformExecutor};
+    use std::cell::Cell;
+
+    /// Runtime dispatched kernel family
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub enum SimdBackend {
+        /// Portable executors, on aarch64 this is NEON baseline
+        Scalar,
+        Sse41,
+        Avx2,
+        Avx512,
+        /// Q0.15 LUT kernels using `sqrdmlah`
+        NeonRdm,
+        /// Half precision matrix shaper, requires `neon-fp16` feature
+        NeonFp16,
+    }
+
+    impl SimdBackend {
+        /// Maps detected CPU feature to the backend it gates,
+        /// features shared between backends such as `fma` map to `None`.
+        pub(crate) fn from_cpu_feature(feature: &str) -> Option<SimdBackend> {
+            match feature {
+                "sse4.1" => Some(SimdBackend::Sse41),
+                "avx2" => Some(SimdBackend::Avx2),
+                "avx512f" | "avx512bw" | "avx512vl" => Some(SimdBackend::Avx512),
+                "rdm" => Some(SimdBackend::NeonRdm),
+                "fp16" => Some(SimdBackend::NeonFp16),
+                _ => None,
+            }
+        }
+
+        /// Backends compiled in and supported by the current CPU
+        pub fn available() -> Vec<SimdBackend> {
+            let mut backends = vec![];
+            #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+            if std::arch::is_x86_feature_detected!("sse4.1") {
+                backends.push(SimdBackend::Sse41);
+            }
+            #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+            if std::arch::is_x86_feature_detected!("avx2")
+                && std::arch::is_x86_feature_detected!("fma")
+            {
+                backends.push(SimdBackend::Avx2);
+            }
+            #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+            if std::arch::is_x86_feature_detected!("avx512bw")
+                && std::arch::is_x86_feature_detected!("avx512vl")
+            {
+                backends.push(SimdBackend::Avx512);
+            }
+            #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
+            if std::arch::is_aarch64_feature_detected!("rdm") {
+                backends.push(SimdBackend::NeonRdm);
+            }
+            #[cfg(all(
+                target_arch = "aarch64",
+                target_feature = "neon",
+                feature = "neon-fp16"
+            ))]
+            if std::arch::is_aarch64_feature_detected!("fp16") {
+                backends.push(SimdBackend::NeonFp16);
+            }
+            backends
+        }
+    }
+
+    thread_local! {
+        static FORCED_BACKEND: Cell<Option<SimdBackend>> = const { Cell::new(None) };
+    }
+
+    pub(crate) fn backend_allowed(backend: Option<SimdBackend>) -> bool {
+        match (FORCED_BACKEND.with(|x| x.get()), backend) {
+            (None, _) | (_, None) => true,
+            (Some(forced), Some(backend)) => forced == backend,
+        }
+    }
+
+    /// Creates transforms with only `backend` kernels allowed on the current thread
+    pub fn with_backend<R>(backend: SimdBackend, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<SimdBackend>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                FORCED_BACKEND.with(|x| x.set(self.0));
+            }
+        }
+        let _restore = Restore(FORCED_BACKEND.with(|x| x.replace(Some(backend))));
+        f()
+    }
+
+    /// Sample type usable in equivalence batches
+    pub trait EquivalenceSample: Copy + Default {
+        /// Uniform sample in `0..=(1 << bit_depth) - 1` for integers and in \[0; 1\] for floats
+        fn sample(state: &mut u64, bit_depth: usize) -> Self;
+        fn to_f64(self) -> f64;
+    }
+
+    /// SplitMix64, so batches are identical on every platform for the same seed
+    fn next_u64(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    impl EquivalenceSample for u8 {
+        fn sample(state: &mut u64, bit_depth: usize) -> Self {
+            (next_u64(state) >> (64 - bit_depth.clamp(1, 8))) as u8
+        }
+
+        fn to_f64(self) -> f64 {
+            self as f64
+        }
+    }
+
+    impl EquivalenceSample for u16 {
+        fn sample(state: &mut u64, bit_depth: usize) -> Self {
+            (next_u64(state) >> (64 - bit_depth.clamp(1, 16))) as u16
+        }
+
+        fn to_f64(self) -> f64 {
+            self as f64
+        }
+    }
+
+    impl EquivalenceSample for f32 {
+        fn sample(state: &mut u64, _: usize) -> Self {
+            (next_u64(state) >> 40) as f32 / ((1u32 << 24) - 1) as f32
+        }
+
+        fn to_f64(self) -> f64 {
+            self as f64
+        }
+    }
+
+    impl EquivalenceSample for f64 {
+        fn sample(state: &mut u64, _: usize) -> Self {
+            (next_u64(state) >> 11) as f64 / ((1u64 << 53) - 1) as f64
+        }
+
+        fn to_f64(self) -> f64 {
+            self
+        }
+    }
+
+    /// Deterministic pixel batch of `len` samples for `seed`
+    pub fn seeded_batch<T: EquivalenceSample>(seed: u64, len: usize, bit_depth: usize) -> Vec<T> {
+        let mut state = seed;
+        (0..len).map(|_| T::sample(&mut state, bit_depth)).collect()
+    }
+
+    /// Deviation of one backend from scalar executor
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    pub struct EquivalenceReport {
+        pub backend: SimdBackend,
+        /// Max absolute difference to scalar output, in sample units
+        pub max_deviation: f64,
+        /// Count of samples that differ at all
+        pub mismatches: usize,
+    }
+
+    /// Runs `src` through transform created by `create` with scalar kernels,
+    /// and then with every available SIMD backend, reporting max deviation per backend.
+    ///
+    /// `create` is called once per backend, e.g.
+    /// `|| src_profile.create_transform_8bit(Layout::Rgb, &dst_profile, Layout::Rgb, options)`.
+    pub fn check_simd_equivalence<T: EquivalenceSample>(
+        src: &[T],
+        dst_len: usize,
+        create: impl Fn() -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>,
+    ) -> Result<Vec<EquivalenceReport>, CmsError> {
+        let run = |backend: SimdBackend| -> Result<Vec<T>, CmsError> {
+            let executor = with_backend(backend, &create)?;
+            let mut dst = vec![T::default(); dst_len];
+            executor.transform(src, &mut dst)?;
+            Ok(dst)
+        };
+        let reference = run(SimdBackend::Scalar)?;
+        SimdBackend::available()
+            .into_iter()
+            .map(|backend| {
+                let dst = run(backend)?;
+                let mut max_deviation = 0f64;
+                let mut mismatches = 0usize;
+                for (&a, &b) in dst.iter().zip(reference.iter()) {
+                    let diff = (a.to_f64() - b.to_f64()).abs();
+                    if diff != 0. {
+                        mismatches += 1;
+                        max_deviation = max_deviation.max(diff);
+                    }
+                }
+                Ok(EquivalenceReport {
+                    backend,
+                    max_deviation,
+                    mismatches,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "equivalence")]
+pub(crate) use harness::backend_allowed;
+#[cfg(feature = "equivalence")]
+pub use harness::{
+    EquivalenceReport, EquivalenceSample, SimdBackend, check_simd_equivalence, seeded_batch,
+    with_backend,
+};
+
+#[cfg(all(test, feature = "equivalence"))]
+mod tests {
+    use super::*;
+    use crate::{ColorProfile, Layout, TransformOptions};
+
+    #[test]
+    fn test_simd_equivalence() {
+        let batch = seeded_batch::<u8>(42, 4096 * 3, 8);
+        assert_eq!(batch, seeded_batch::<u8>(42, 4096 * 3, 8));
+        assert_ne!(batch, seeded_batch::<u8>(43, 4096 * 3, 8));
+        assert!(seeded_batch::<u16>(7, 1024, 12).iter().all(|&x| x < 4096));
+
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let lab = ColorProfile::new_lab();
+        for prefer_fixed_point in [false, true] {
+            let options = TransformOptions {
+                prefer_fixed_point,
+                ..Default::default()
+            };
+            let reports = check_simd_equivalence(&batch, batch.len(), || {
+                srgb.create_transform_8bit(Layout::Rgb, &bt2020, Layout::Rgb, options)
+            })
+            .unwrap();
+            for report in reports {
+                assert!(report.max_deviation <= 2., "{report:?}");
+            }
+        }
+        let batch = seeded_batch::<u16>(42, 4096 * 3, 16);
+        let reports = check_simd_equivalence(&batch, batch.len(), || {
+            lab.create_transform_16bit(Layout::Rgb, &srgb, Layout::Rgb, Default::default())
+        })
+        .unwrap();
+        for report in reports {
+            assert!(report.max_deviation <= 64., "{report:?}");
+        }
+    }
+}
@@ -26,7 +26,7 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::RenderingIntent;
+use crate::{FixedPointFormat, Layout, RenderingIntent, SpectralPcs};
 use std::error::Error;
 use std::fmt::Display;
 
@@ -36,6 +36,12 @@ pub struct MalformedSize {
     pub expected: usize,
 }
 
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+pub struct LayoutChannels {
+    pub expected: usize,
+    pub actual: usize,
+}
+
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
 pub enum CmsError {
     LaneSizeMismatch,
@@ -50,6 +56,7 @@ pub enum CmsError {
     UnsupportedColorPrimaries(u8),
     UnsupportedTrc(u8),
     InvalidLayout,
+    InvalidLayoutChannels(LayoutChannels),
     UnsupportedProfileConnection,
     BuildTransferFunction,
     UnsupportedChannelConfiguration,
@@ -65,6 +72,13 @@ pub enum CmsError {
     MalformedTrcCurve(String),
     OutOfMemory(usize),
     IncorrectlyFormedLut(String),
+    UnsupportedSpectralPcs(SpectralPcs),
+    UnsupportedFixedPointFormat(FixedPointFormat),
+    FloatingPointRequired,
+    ProfileNotRegistered(String),
+    UnalignedSamples(usize),
+    NamedColorOutOfRange(usize),
+    PcsSourceLayout(Layout),
 }
 
 impl Display for CmsError {
@@ -90,6 +104,10 @@ impl Display for CmsError {
             }
             CmsError::UnsupportedTrc(value) => f.write_fmt(format_args!("Unsupported TRC {value}")),
             CmsError::InvalidLayout => f.write_str("Invalid layout"),
+            CmsError::InvalidLayoutChannels(channels) => f.write_fmt(format_args!(
+                "Layout has {} channels, but profile color space expects {}",
+                channels.actual, channels.expected
+            )),
             CmsError::UnsupportedProfileConnection => f.write_str("Unsupported profile connection"),
             CmsError::BuildTransferFunction => f.write_str("Can't reconstruct transfer function"),
             CmsError::UnsupportedChannelConfiguration => {
@@ -121,6 +139,28 @@ impl Display for CmsError {
                 "There is no enough memory to allocate {capacity} bytes"
             )),
             CmsError::IncorrectlyFormedLut(str) => f.write_str(str),
+            CmsError::UnsupportedSpectralPcs(pcs) => f.write_fmt(format_args!(
+                "Unsupported spectral PCS {:?} with {} components, only 3 components PCS is supported without a custom handler",
+                pcs.kind, pcs.components
+            )),
+            CmsError::UnsupportedFixedPointFormat(format) => f.write_fmt(format_args!(
+                "Fixed point format {format:?} is not supported for this transform"
+            )),
+            CmsError::FloatingPointRequired => f.write_str(
+                "Transform requires floating point arithmetic per pixel, which `integer_pixels` feature disables",
+            ),
+            CmsError::ProfileNotRegistered(name) => {
+                f.write_fmt(format_args!("No profile registered as \"{name}\""))
+            }
+            CmsError::UnalignedSamples(align) => f.write_fmt(format_args!(
+                "Byte buffer must be aligned to {align} bytes to be viewed as samples"
+            )),
+            CmsError::NamedColorOutOfRange(index) => {
+                f.write_fmt(format_args!("Named color index {index} is out of range"))
+            }
+            CmsError::PcsSourceLayout(layout) => f.write_fmt(format_args!(
+                "Layout {layout:?} is output only and can't be used as transform source"
+            )),
         }
     }
 }
@@ -141,3 +181,12 @@ macro_rules! try_vec {
 }
 
 pub(crate) use try_vec;
+
+/// Resizes reused buffer to `n` elements filled with `elem` without shrinking capacity.
+pub(crate) fn try_resize_vec<T: Clone>(v: &mut Vec<T>, n: usize, elem: T) -> Result<(), CmsError> {
+    v.clear();
+    v.try_reserve_exact(n)
+        .map_err(|_| CmsError::OutOfMemory(n))?;
+    v.resize(n, elem);
+    Ok(())
+}
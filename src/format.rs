@@ -0,0 +1,427 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Pixel format descriptors for byte buffer transforms.
+//!
+//! Describes storage as a single value instead of picking one of `create_transform_*`
+//! methods for every combination of channel order, depth and alpha representation.
+use crate::bytes::TransformBytesExecutor;
+use crate::{
+    CmsError, ColorProfile, Layout, Transform8BitExecutor, Transform16BitExecutor,
+    TransformF32BitExecutor, TransformF64BitExecutor, TransformOptions,
+};
+
+/// Storage of a single sample, always in native endianness
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SampleFormat {
+    U8,
+    /// 16 bit storage holding `bit_depth` significant bits, 10, 12 or 16
+    U16 {
+        bit_depth: u8,
+    },
+    /// IEEE 754 half precision float
+    F16,
+    F32,
+    F64,
+}
+
+impl SampleFormat {
+    /// Size of a single sample in bytes
+    pub const fn bytes(self) -> usize {
+        match self {
+            SampleFormat::U8 => 1,
+            SampleFormat::U16 { .. } | SampleFormat::F16 => 2,
+            SampleFormat::F32 => 4,
+            SampleFormat::F64 => 8,
+        }
+    }
+
+    #[inline]
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            SampleFormat::U8 => bytes[0] as f32 * (1. / 255.),
+            SampleFormat::U16 { bit_depth } => {
+                u16::from_ne_bytes([bytes[0], bytes[1]]) as f32
+                    / ((1u32 << bit_depth as u32) - 1) as f32
+            }
+            SampleFormat::F16 => f16_to_f32(u16::from_ne_bytes([bytes[0], bytes[1]])),
+            SampleFormat::F32 => f32::from_ne_bytes(bytes.try_into().unwrap()),
+            SampleFormat::F64 => f64::from_ne_bytes(bytes.try_into().unwrap()) as f32,
+        }
+    }
+
+    #[inline]
+    fn encode(self, value: f32, into: &mut [u8]) {
+        match self {
+            SampleFormat::U8 => into[0] = (value.max(0.).min(1.) * 255. + 0.5) as u8,
+            SampleFormat::U16 { bit_depth } => {
+                let max_value = ((1u32 << bit_depth as u32) - 1) as f32;
+                let v = (value.max(0.).min(1.) * max_value + 0.5) as u16;
+                into.copy_from_slice(&v.to_ne_bytes());
+            }
+            SampleFormat::F16 => into.copy_from_slice(&f32_to_f16(value).to_ne_bytes()),
+            SampleFormat::F32 => into.copy_from_slice(&value.to_ne_bytes()),
+            SampleFormat::F64 => into.copy_from_slice(&(value as f64).to_ne_bytes()),
+        }
+    }
+}
+
+/// Describes interleaved pixels: channel order, sample storage and alpha representation
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PixelFormat {
+    pub layout: Layout,
+    pub sample: SampleFormat,
+    /// Color channels are multiplied by alpha, has effect only for layouts with alpha
+    pub premultiplied: bool,
+}
+
+impl PixelFormat {
+    pub const RGB8: PixelFormat = PixelFormat::new(Layout::Rgb, SampleFormat::U8);
+    pub const RGBA8: PixelFormat = PixelFormat::new(Layout::Rgba, SampleFormat::U8);
+    pub const RGB16: PixelFormat =
+        PixelFormat::new(Layout::Rgb, SampleFormat::U16 { bit_depth: 16 });
+    pub const RGBA16: PixelFormat =
+        PixelFormat::new(Layout::Rgba, SampleFormat::U16 { bit_depth: 16 });
+    /// Half float RGBA as GPU `RGBA16F` textures store it
+    pub const RGBA16F: PixelFormat = PixelFormat::new(Layout::Rgba, SampleFormat::F16);
+    pub const RGBA32F: PixelFormat = PixelFormat::new(Layout::Rgba, SampleFormat::F32);
+    pub const GRAY8: PixelFormat = PixelFormat::new(Layout::Gray, SampleFormat::U8);
+
+    /// Creates format with straight alpha
+    pub const fn new(layout: Layout, sample: SampleFormat) -> Self {
+        Self {
+            layout,
+            sample,
+            premultiplied: false,
+        }
+    }
+
+    /// Same format with premultiplied alpha
+    pub const fn premultiplied(self) -> Self {
+        Self {
+            premultiplied: true,
+            ..self
+        }
+    }
+
+    /// Size of a single pixel in bytes
+    pub const fn bytes_per_pixel(&self) -> usize {
+        self.layout.channels() * self.sample.bytes()
+    }
+
+    const fn is_premultiplied(&self) -> bool {
+        self.premultiplied && self.layout.has_alpha()
+    }
+}
+
+/// Converts IEEE 754 half precision bits into `f32`
+pub(crate) fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits as u32) & 0x8000) << 16;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+    let magnitude = match exponent {
+        0 => {
+            // Zero or subnormal, value is mantissa * 2^-24
+            let v = mantissa as f32 * (1. / 16777216.);
+            return if sign != 0 { -v } else { v };
+        }
+        0x1f => 0x7f80_0000 | (mantissa << 13),
+        _ => ((exponent + 112) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(sign | magnitude)
+}
+
+/// Converts `f32` into IEEE 754 half precision bits, rounding to nearest even
+pub(crate) fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let half_exponent = exponent - 112;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign;
+        }
+        // Subnormal, implicit bit becomes explicit
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        let half_mantissa = mantissa >> shift;
+        let remainder = mantissa & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let round = remainder > halfway || (remainder == halfway && half_mantissa & 1 != 0);
+        return sign | (half_mantissa + round as u32) as u16;
+    }
+    let half = ((half_exponent as u32) << 10) | (mantissa >> 13);
+    let remainder = mantissa & 0x1fff;
+    let round = remainder > 0x1000 || (remainder == 0x1000 && half & 1 != 0);
+    // Carry from rounding correctly propagates into exponent
+    sign | (half + round as u32) as u16
+}
+
+enum FormatExecutor {
+    U8(Box<Transform8BitExecutor>),
+    U16(Box<Transform16BitExecutor>),
+    F32(Box<TransformF32BitExecutor>),
+    F64(Box<TransformF64BitExecutor>),
+    /// Samples are decoded into `f32`, alpha is unpremultiplied where required
+    Converting(Box<TransformF32BitExecutor>),
+}
+
+/// Transform between byte buffers described by [PixelFormat]
+pub struct FormatTransform {
+    executor: FormatExecutor,
+    src: PixelFormat,
+    dst: PixelFormat,
+}
+
+/// Pixels converted through intermediate `f32` storage at once
+const FORMAT_CHUNK_PIXELS: usize = 512;
+
+impl FormatTransform {
+    /// Source pixel format
+    pub fn src_format(&self) -> PixelFormat {
+        self.src
+    }
+
+    /// Destination pixel format
+    pub fn dst_format(&self) -> PixelFormat {
+        self.dst
+    }
+
+    /// Transforms `src` into `dst`, both are tightly packed pixels of their formats.
+    ///
+    /// Buffers do not have to be aligned for the sample type.
+    pub fn transform(&self, src: &[u8], dst: &mut [u8]) -> Result<(), CmsError> {
+        let src_pixel = self.src.bytes_per_pixel();
+        let dst_pixel = self.dst.bytes_per_pixel();
+        if src.len() % src_pixel != 0 || dst.len() % dst_pixel != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / src_pixel != dst.len() / dst_pixel {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        match &self.executor {
+            FormatExecutor::U8(executor) => executor.transform(src, dst),
+            FormatExecutor::U16(executor) => executor.transform_bytes(src, dst),
+            FormatExecutor::F32(executor) => executor.transform_bytes(src, dst),
+            FormatExecutor::F64(executor) => executor.transform_bytes(src, dst),
+            FormatExecutor::Converting(executor) => {
+                self.transform_converting(executor.as_ref(), src, dst)
+            }
+        }
+    }
+
+    fn transform_converting(
+        &self,
+        executor: &TransformF32BitExecutor,
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> Result<(), CmsError> {
+        let src_channels = self.src.layout.channels();
+        let dst_channels = self.dst.layout.channels();
+        let src_sample = self.src.sample.bytes();
+        let dst_sample = self.dst.sample.bytes();
+        let pixels = (src.len() / self.src.bytes_per_pixel()).min(FORMAT_CHUNK_PIXELS);
+        let mut src_working = vec![0f32; pixels * src_channels];
+        let mut dst_working = vec![0f32; pixels * dst_channels];
+        for (src, dst) in src
+            .chunks(FORMAT_CHUNK_PIXELS * self.src.bytes_per_pixel())
+            .zip(dst.chunks_mut(FORMAT_CHUNK_PIXELS * self.dst.bytes_per_pixel()))
+        {
+            let src_working = &mut src_working[..src.len() / src_sample];
+            let dst_working = &mut dst_working[..dst.len() / dst_sample];
+            for (v, bytes) in src_working.iter_mut().zip(src.chunks_exact(src_sample)) {
+                *v = self.src.sample.decode(bytes);
+            }
+            if self.src.is_premultiplied() {
+                let a_i = self.src.layout.a_i();
+                for px in src_working.chunks_exact_mut(src_channels) {
+                    let a = px[a_i];
+                    let scale = if a > 0. { 1. / a } else { 0. };
+                    for (i, v) in px.iter_mut().enumerate() {
+                        if i != a_i {
+                            *v *= scale;
+                        }
+                    }
+                }
+            }
+            executor.transform(src_working, dst_working)?;
+            if self.dst.is_premultiplied() {
+                let a_i = self.dst.layout.a_i();
+                for px in dst_working.chunks_exact_mut(dst_channels) {
+                    let a = px[a_i];
+                    for (i, v) in px.iter_mut().enumerate() {
+                        if i != a_i {
+                            *v *= a;
+                        }
+                    }
+                }
+            }
+            for (&v, bytes) in dst_working.iter().zip(dst.chunks_exact_mut(dst_sample)) {
+                self.dst.sample.encode(v, bytes);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ColorProfile {
+    /// Creates transform between byte buffers of arbitrary [PixelFormat]s.
+    ///
+    /// When both formats store the same sample type with straight alpha the regular
+    /// transform for that type is used, otherwise samples go through `f32` pipeline.
+    pub fn create_format_transform(
+        &self,
+        src_format: PixelFormat,
+        dst_pr: &ColorProfile,
+        dst_format: PixelFormat,
+        options: TransformOptions,
+    ) -> Result<FormatTransform, CmsError> {
+        let (src_layout, dst_layout) = (src_format.layout, dst_format.layout);
+        let native = !src_format.is_premultiplied() && !dst_format.is_premultiplied();
+        let executor = match (src_format.sample, dst_format.sample) {
+            (SampleFormat::U8, SampleFormat::U8) if native => FormatExecutor::U8(
+                self.create_transform_8bit(src_layout, dst_pr, dst_layout, options)?,
+            ),
+            (
+                SampleFormat::U16 { bit_depth },
+                SampleFormat::U16 {
+                    bit_depth: dst_depth,
+                },
+            ) if native && bit_depth == dst_depth => FormatExecutor::U16(match bit_depth {
+                10 => self.create_transform_10bit(src_layout, dst_pr, dst_layout, options)?,
+                12 => self.create_transform_12bit(src_layout, dst_pr, dst_layout, options)?,
+                16 => self.create_transform_16bit(src_layout, dst_pr, dst_layout, options)?,
+                _ => return Err(CmsError::UnsupportedChannelConfiguration),
+            }),
+            (SampleFormat::F32, SampleFormat::F32) if native => FormatExecutor::F32(
+                self.create_transform_f32(src_layout, dst_pr, dst_layout, options)?,
+            ),
+            (SampleFormat::F64, SampleFormat::F64) if native => FormatExecutor::F64(
+                self.create_transform_f64(src_layout, dst_pr, dst_layout, options)?,
+            ),
+            _ => {
+                for sample in [src_format.sample, dst_format.sample] {
+                    if let SampleFormat::U16 { bit_depth } = sample {
+                        if !matches!(bit_depth, 10 | 12 | 16) {
+                            return Err(CmsError::UnsupportedChannelConfiguration);
+                        }
+                    }
+                }
+                FormatExecutor::Converting(
+                    self.create_transform_f32(src_layout, dst_pr, dst_layout, options)?,
+                )
+            }
+        };
+        Ok(FormatTransform {
+            executor,
+            src: src_format,
+            dst: dst_format,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_half_float_conversion() {
+        for bits in [
+            0x0000u16, 0x8000, 0x3c00, 0xbc00, 0x0001, 0x03ff, 0x7bff, 0x7c00, 0x3555,
+        ] {
+            assert_eq!(f32_to_f16(f16_to_f32(bits)), bits, "{bits:#x}");
+        }
+        assert_eq!(f16_to_f32(0x3c00), 1.);
+        assert_eq!(f16_to_f32(0x0001), 2f32.powi(-24));
+        assert_eq!(f32_to_f16(65520.), 0x7c00);
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+    }
+
+    #[test]
+    fn test_format_transform() {
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let options = TransformOptions::default();
+
+        // Premultiplied half float into straight 8 bit matches plain 8 bit transform
+        let straight = [200u8, 100, 50, 255, 40, 220, 90, 128];
+        let reference = srgb
+            .create_transform_8bit(Layout::Rgba, &bt2020, Layout::Rgba, options)
+            .unwrap();
+        let mut expected = [0u8; 8];
+        reference.transform(&straight, &mut expected).unwrap();
+
+        let src = straight
+            .chunks_exact(4)
+            .flat_map(|px| {
+                let a = px[3] as f32 / 255.;
+                [
+                    px[0] as f32 / 255. * a,
+                    px[1] as f32 / 255. * a,
+                    px[2] as f32 / 255. * a,
+                    a,
+                ]
+            })
+            .flat_map(|v| f32_to_f16(v).to_ne_bytes())
+            .collect::<Vec<u8>>();
+        let transform = srgb
+            .create_format_transform(
+                PixelFormat::RGBA16F.premultiplied(),
+                &bt2020,
+                PixelFormat::RGBA8,
+                options,
+            )
+            .unwrap();
+        let mut dst = [0u8; 8];
+        transform.transform(&src, &mut dst).unwrap();
+        for (&a, &b) in dst.iter().zip(expected.iter()) {
+            assert!((a as i32 - b as i32).abs() <= 1, "{dst:?} {expected:?}");
+        }
+
+        let native = srgb
+            .create_format_transform(PixelFormat::RGBA8, &bt2020, PixelFormat::RGBA8, options)
+            .unwrap();
+        native.transform(&straight, &mut dst).unwrap();
+        assert_eq!(dst, expected);
+        assert!(native.transform(&straight, &mut dst[..4]).is_err());
+
+        let ten_bits = PixelFormat::new(Layout::Rgb, SampleFormat::U16 { bit_depth: 9 });
+        assert!(
+            srgb.create_format_transform(ten_bits, &bt2020, PixelFormat::RGB8, options)
+                .is_err()
+        );
+    }
+}
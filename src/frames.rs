@@ -0,0 +1,220 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::err::try_resize_vec;
+use crate::{
+    CmsError, ColorProfile, Layout, Transform16BitExecutor, TransformExecutor, TransformOptions,
+    TransformScratch,
+};
+
+/// Transform for sequences of frames, e.g. video.
+///
+/// Intermediate storage is kept between frames, so transforms that need it
+/// don't allocate once the first frame was processed.
+pub struct FrameTransform<T> {
+    executor: Box<dyn TransformExecutor<T> + Send + Sync>,
+    scratch: TransformScratch,
+    frame: u64,
+}
+
+impl<T: Copy + Default> FrameTransform<T> {
+    pub fn new(executor: Box<dyn TransformExecutor<T> + Send + Sync>) -> Self {
+        Self {
+            executor,
+            scratch: TransformScratch::new(),
+            frame: 0,
+        }
+    }
+
+    /// Count of frames transformed so far
+    pub fn frame_index(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn transform_frame(&mut self, src: &[T], dst: &mut [T]) -> Result<(), CmsError> {
+        self.executor
+            .transform_with_scratch(src, dst, &mut self.scratch)?;
+        self.frame += 1;
+        Ok(())
+    }
+
+    /// Transforms every `(src, dst)` frame pair, stops on the first error.
+    pub fn transform_frames<'a, I>(&mut self, frames: I) -> Result<(), CmsError>
+    where
+        I: IntoIterator<Item = (&'a [T], &'a mut [T])>,
+        T: 'a,
+    {
+        for (src, dst) in frames {
+            self.transform_frame(src, dst)?;
+        }
+        Ok(())
+    }
+}
+
+/// 4x4 Bayer thresholds in a row order
+const BAYER: [u32; 16] = [0, 8, 2, 10, 12, 4, 14, 6, 3, 11, 1, 9, 15, 7, 13, 5];
+
+/// 8 bit frame transform with temporal dithering.
+///
+/// Frames are converted with 16 bit precision and quantized back to 8 bit with
+/// an ordered dither pattern that shifts on every frame, so banding in smooth gradients
+/// is averaged out over time instead of staying fixed on screen.
+/// Values that are exactly representable in 8 bit, e.g. copied alpha, are kept intact.
+pub struct DitheredFrameTransform {
+    executor: Box<Transform16BitExecutor>,
+    scratch: TransformScratch,
+    src: Vec<u16>,
+    dst: Vec<u16>,
+    frame: u64,
+}
+
+impl DitheredFrameTransform {
+    /// Count of frames transformed so far
+    pub fn frame_index(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn transform_frame(&mut self, src: &[u8], dst: &mut [u8]) -> Result<(), CmsError> {
+        try_resize_vec(&mut self.src, src.len(), 0)?;
+        try_resize_vec(&mut self.dst, dst.len(), 0)?;
+        for (dst, &src) in self.src.iter_mut().zip(src.iter()) {
+            *dst = src as u16 * 257;
+        }
+        self.executor
+            .transform_with_scratch(&self.src, &mut self.dst, &mut self.scratch)?;
+
+        // Shift is odd, so every phase of the pattern is visited within 16 frames
+        let phase = (self.frame as usize).wrapping_mul(7);
+        for (i, (dst, &src)) in dst.iter_mut().zip(self.dst.iter()).enumerate() {
+            let threshold = BAYER[i.wrapping_add(phase) & 15] * 2 + 1;
+            *dst = ((src as u32 * 255 * 32 + threshold * 65535) / (65535 * 32)).min(255) as u8;
+        }
+        self.frame += 1;
+        Ok(())
+    }
+
+    /// Transforms every `(src, dst)` frame pair, stops on the first error.
+    pub fn transform_frames<'a, I>(&mut self, frames: I) -> Result<(), CmsError>
+    where
+        I: IntoIterator<Item = (&'a [u8], &'a mut [u8])>,
+    {
+        for (src, dst) in frames {
+            self.transform_frame(src, dst)?;
+        }
+        Ok(())
+    }
+}
+
+impl ColorProfile {
+    /// Creates 8 bit frame transform with temporal dithering, see [DitheredFrameTransform].
+    pub fn create_dithered_frame_transform_8bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<DitheredFrameTransform, CmsError> {
+        let executor = self.create_transform_16bit(src_layout, dst_pr, dst_layout, options)?;
+        Ok(DitheredFrameTransform {
+            executor,
+            scratch: TransformScratch::new(),
+            src: Vec::new(),
+            dst: Vec::new(),
+            frame: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_transforms() {
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let frames = (0..4)
+            .map(|f| {
+                (0..64 * 4)
+                    .map(|x| ((x * 3 + f) % 256) as u8)
+                    .collect::<Vec<u8>>()
+            })
+            .collect::<Vec<_>>();
+        let executor = srgb
+            .create_transform_8bit(Layout::Rgba, &bt2020, Layout::Rgba, Default::default())
+            .unwrap();
+        let mut reference = vec![vec![0u8; 64 * 4]; frames.len()];
+        for (src, dst) in frames.iter().zip(reference.iter_mut()) {
+            executor.transform(src, dst).unwrap();
+        }
+
+        let mut plain = FrameTransform::new(executor);
+        let mut dst = vec![vec![0u8; 64 * 4]; frames.len()];
+        plain
+            .transform_frames(
+                frames
+                    .iter()
+                    .map(|x| x.as_slice())
+                    .zip(dst.iter_mut().map(|x| x.as_mut_slice())),
+            )
+            .unwrap();
+        assert_eq!(plain.frame_index(), 4);
+        assert_eq!(reference, dst);
+
+        let mut dithered = srgb
+            .create_dithered_frame_transform_8bit(
+                Layout::Rgba,
+                &bt2020,
+                Layout::Rgba,
+                Default::default(),
+            )
+            .unwrap();
+        dithered
+            .transform_frames(
+                frames
+                    .iter()
+                    .map(|x| x.as_slice())
+                    .zip(dst.iter_mut().map(|x| x.as_mut_slice())),
+            )
+            .unwrap();
+        assert_eq!(dithered.frame_index(), 4);
+        for ((src, dst), reference) in frames.iter().zip(dst.iter()).zip(reference.iter()) {
+            for ((src, dst), reference) in src
+                .chunks_exact(4)
+                .zip(dst.chunks_exact(4))
+                .zip(reference.chunks_exact(4))
+            {
+                // Alpha is copied exactly
+                assert_eq!(src[3], dst[3]);
+                for (&a, &b) in dst[..3].iter().zip(reference[..3].iter()) {
+                    assert!((a as i32 - b as i32).abs() <= 1, "{dst:?} {reference:?}");
+                }
+            }
+        }
+    }
+}
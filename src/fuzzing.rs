@@ -0,0 +1,336 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! [arbitrary::Arbitrary] implementations producing structurally valid profile data,
+//! so encoder and decoder round trip can be fuzzed.
+use crate::{
+    CicpColorPrimaries, CicpProfile, ColorProfile, DataColorSpace, LocalizableString, LutDataType,
+    LutStore, LutType, LutWarehouse, Matrix3d, MatrixCoefficients, ProfileClass, ProfileText,
+    RenderingIntent, ToneReprCurve, TransferCharacteristics, Xyzd,
+};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::sync::Arc;
+
+/// Value exactly representable as s15Fixed16Number and f32
+fn arbitrary_fixed(u: &mut Unstructured<'_>) -> Result<f64> {
+    Ok(u.int_in_range(-(1i32 << 23)..=(1i32 << 23))? as f64 / 65536.)
+}
+
+fn arbitrary_xyz(u: &mut Unstructured<'_>) -> Result<Xyzd> {
+    Ok(Xyzd::new(
+        arbitrary_fixed(u)?,
+        arbitrary_fixed(u)?,
+        arbitrary_fixed(u)?,
+    ))
+}
+
+/// Rounds colorants, adaptation and parametric TRC to s15Fixed16Number, as they are stored
+fn quantize_colorimetry(profile: &mut ColorProfile) {
+    let quantize = |v: f64| (v * 65536.).round() / 65536.;
+    for colorant in [
+        &mut profile.red_colorant,
+        &mut profile.green_colorant,
+        &mut profile.blue_colorant,
+    ] {
+        *colorant = Xyzd::new(
+            quantize(colorant.x),
+            quantize(colorant.y),
+            quantize(colorant.z),
+        );
+    }
+    if let Some(chad) = profile.chromatic_adaptation.as_mut() {
+        for v in chad.v.iter_mut().flatten() {
+            *v = quantize(*v);
+        }
+    }
+    for trc in [
+        &mut profile.red_trc,
+        &mut profile.green_trc,
+        &mut profile.blue_trc,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if let ToneReprCurve::Parametric(params) = trc {
+            for v in params.iter_mut() {
+                *v = quantize(*v as f64) as f32;
+            }
+        }
+    }
+}
+
+fn arbitrary_matrix(u: &mut Unstructured<'_>) -> Result<Matrix3d> {
+    let mut matrix = Matrix3d::default();
+    for row in matrix.v.iter_mut() {
+        for v in row.iter_mut() {
+            *v = arbitrary_fixed(u)?;
+        }
+    }
+    Ok(matrix)
+}
+
+fn arbitrary_store(u: &mut Unstructured<'_>, lut_type: LutType, len: usize) -> Result<LutStore> {
+    Ok(match lut_type {
+        LutType::Lut8 => LutStore::Store8(
+            (0..len)
+                .map(|_| u.arbitrary::<u8>())
+                .collect::<Result<Arc<[u8]>>>()?,
+        ),
+        _ => LutStore::Store16(
+            (0..len)
+                .map(|_| u.arbitrary::<u16>())
+                .collect::<Result<Arc<[u16]>>>()?,
+        ),
+    })
+}
+
+fn arbitrary_text(u: &mut Unstructured<'_>) -> Result<ProfileText> {
+    let len = u.int_in_range(1usize..=32)?;
+    let value = (0..len)
+        .map(|_| u.int_in_range(b' '..=b'~').map(|c| c as char))
+        .collect::<Result<String>>()?;
+    Ok(ProfileText::Localizable(vec![LocalizableString::new(
+        "en".to_string(),
+        "US".to_string(),
+        value,
+    )]))
+}
+
+impl ToneReprCurve {
+    fn arbitrary_lut(u: &mut Unstructured<'_>) -> Result<ToneReprCurve> {
+        let len = u.int_in_range(0usize..=1024)?;
+        let lut = (0..len)
+            .map(|_| u.arbitrary::<u16>())
+            .collect::<Result<Vec<u16>>>()?;
+        Ok(ToneReprCurve::Lut(lut))
+    }
+}
+
+impl<'a> Arbitrary<'a> for ToneReprCurve {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.arbitrary::<bool>()? {
+            return ToneReprCurve::arbitrary_lut(u);
+        }
+        // Parametric curve types 0..4 have 1, 3, 4, 5 and 7 parameters
+        let count = *u.choose(&[1usize, 3, 4, 5, 7])?;
+        let params = (0..count)
+            .map(|_| arbitrary_fixed(u).map(|x| x as f32))
+            .collect::<Result<Vec<f32>>>()?;
+        Ok(ToneReprCurve::Parametric(params))
+    }
+}
+
+impl LutDataType {
+    fn arbitrary_with_channels(
+        u: &mut Unstructured<'_>,
+        num_input_channels: u8,
+        num_output_channels: u8,
+    ) -> Result<LutDataType> {
+        let lut_type = *u.choose(&[LutType::Lut8, LutType::Lut16])?;
+        let num_clut_grid_points = u.int_in_range(2u8..=9)?;
+        let table_entries = match lut_type {
+            LutType::Lut8 => 256,
+            _ => u.int_in_range(2u16..=256)?,
+        };
+        let clut_size = (num_clut_grid_points as usize).pow(num_input_channels as u32)
+            * num_output_channels as usize;
+        Ok(LutDataType {
+            num_input_channels,
+            num_output_channels,
+            num_clut_grid_points,
+            matrix: arbitrary_matrix(u)?,
+            num_input_table_entries: table_entries,
+            num_output_table_entries: table_entries,
+            input_table: arbitrary_store(
+                u,
+                lut_type,
+                num_input_channels as usize * table_entries as usize,
+            )?,
+            clut_table: arbitrary_store(u, lut_type, clut_size)?,
+            output_table: arbitrary_store(
+                u,
+                lut_type,
+                num_output_channels as usize * table_entries as usize,
+            )?,
+            lut_type,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for LutDataType {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let num_input_channels = u.int_in_range(1u8..=4)?;
+        let num_output_channels = u.int_in_range(1u8..=4)?;
+        LutDataType::arbitrary_with_channels(u, num_input_channels, num_output_channels)
+    }
+}
+
+impl<'a> Arbitrary<'a> for ColorProfile {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let color_space = *u.choose(&[
+            DataColorSpace::Rgb,
+            DataColorSpace::Gray,
+            DataColorSpace::Cmyk,
+        ])?;
+        let mut profile = ColorProfile {
+            color_space,
+            pcs: *u.choose(&[DataColorSpace::Xyz, DataColorSpace::Lab])?,
+            profile_class: *u.choose(&[
+                ProfileClass::InputDevice,
+                ProfileClass::DisplayDevice,
+                ProfileClass::OutputDevice,
+                ProfileClass::ColorSpace,
+            ])?,
+            rendering_intent: *u.choose(&[
+                RenderingIntent::Perceptual,
+                RenderingIntent::RelativeColorimetric,
+                RenderingIntent::Saturation,
+                RenderingIntent::AbsoluteColorimetric,
+            ])?,
+            white_point: arbitrary_xyz(u)?,
+            ..Default::default()
+        };
+        if u.arbitrary::<bool>()? {
+            profile.media_white_point = Some(arbitrary_xyz(u)?);
+        }
+        if u.arbitrary::<bool>()? {
+            profile.chromatic_adaptation = Some(arbitrary_matrix(u)?);
+        }
+        match color_space {
+            DataColorSpace::Rgb => {
+                profile.red_colorant = arbitrary_xyz(u)?;
+                profile.green_colorant = arbitrary_xyz(u)?;
+                profile.blue_colorant = arbitrary_xyz(u)?;
+                profile.red_trc = Some(u.arbitrary()?);
+                profile.green_trc = Some(u.arbitrary()?);
+                profile.blue_trc = Some(u.arbitrary()?);
+                if u.arbitrary::<bool>()? {
+                    let primaries = *u.choose(&[1u8, 9, 12])?;
+                    let transfer = *u.choose(&[1u8, 8, 13, 16, 18])?;
+                    let matrix = *u.choose(&[0u8, 1])?;
+                    let cicp = CicpColorPrimaries::try_from(primaries)
+                        .ok()
+                        .zip(TransferCharacteristics::try_from(transfer).ok())
+                        .zip(MatrixCoefficients::try_from(matrix).ok())
+                        .map(
+                            |((color_primaries, transfer_characteristics), matrix)| CicpProfile {
+                                color_primaries,
+                                transfer_characteristics,
+                                matrix_coefficients: matrix,
+                                full_range: true,
+                            },
+                        );
+                    // Encoder rejects CICP contradicting colorants and TRC
+                    if let Some(cicp) = cicp {
+                        profile.update_rgb_colorimetry_from_cicp(cicp);
+                        quantize_colorimetry(&mut profile);
+                    }
+                }
+            }
+            DataColorSpace::Gray => {
+                profile.gray_trc = Some(u.arbitrary()?);
+            }
+            _ => {}
+        }
+        let device_channels = color_space.channels() as u8;
+        if color_space != DataColorSpace::Gray && u.arbitrary::<bool>()? {
+            profile.lut_a_to_b_perceptual = Some(LutWarehouse::Lut(
+                LutDataType::arbitrary_with_channels(u, device_channels, 3)?,
+            ));
+        }
+        if color_space != DataColorSpace::Gray && u.arbitrary::<bool>()? {
+            profile.lut_b_to_a_perceptual = Some(LutWarehouse::Lut(
+                LutDataType::arbitrary_with_channels(u, 3, device_channels)?,
+            ));
+        }
+        if u.arbitrary::<bool>()? {
+            profile.description = Some(arbitrary_text(u)?);
+        }
+        if u.arbitrary::<bool>()? {
+            profile.copyright = Some(arbitrary_text(u)?);
+        }
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compares everything generator produces, decoder may derive missing `wtpt` and illuminant
+    fn assert_same_profile(expected: &ColorProfile, actual: &ColorProfile) {
+        assert_eq!(expected.color_space, actual.color_space);
+        assert_eq!(expected.pcs, actual.pcs);
+        assert_eq!(expected.profile_class, actual.profile_class);
+        assert_eq!(expected.rendering_intent, actual.rendering_intent);
+        // Invalid illuminant is replaced with colorants sum
+        if expected.white_point.y > 0. {
+            assert_eq!(expected.white_point, actual.white_point);
+        }
+        if expected.media_white_point.is_some() {
+            assert_eq!(expected.media_white_point, actual.media_white_point);
+        }
+        assert_eq!(expected.chromatic_adaptation, actual.chromatic_adaptation);
+        assert_eq!(expected.red_colorant, actual.red_colorant);
+        assert_eq!(expected.green_colorant, actual.green_colorant);
+        assert_eq!(expected.blue_colorant, actual.blue_colorant);
+        assert_eq!(expected.red_trc, actual.red_trc);
+        assert_eq!(expected.green_trc, actual.green_trc);
+        assert_eq!(expected.blue_trc, actual.blue_trc);
+        assert_eq!(expected.gray_trc, actual.gray_trc);
+        assert_eq!(format!("{:?}", expected.cicp), format!("{:?}", actual.cicp));
+        assert_eq!(expected.lut_a_to_b_perceptual, actual.lut_a_to_b_perceptual);
+        assert_eq!(expected.lut_b_to_a_perceptual, actual.lut_b_to_a_perceptual);
+        assert_eq!(
+            format!("{:?}", expected.description),
+            format!("{:?}", actual.description)
+        );
+        assert_eq!(
+            format!("{:?}", expected.copyright),
+            format!("{:?}", actual.copyright)
+        );
+    }
+
+    #[test]
+    fn test_arbitrary_round_trip() {
+        let data = (0..65536u32)
+            .map(|x| (x.wrapping_mul(2654435761) >> 13) as u8)
+            .collect::<Vec<u8>>();
+        for chunk in data.chunks(4096) {
+            let mut u = Unstructured::new(chunk);
+            let profile = ColorProfile::arbitrary(&mut u).unwrap();
+            let encoded = profile.encode().unwrap();
+            let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+            assert_same_profile(&profile, &decoded);
+            let reencoded = decoded.encode().unwrap();
+            let redecoded = ColorProfile::new_from_slice(&reencoded).unwrap();
+            assert_same_profile(&decoded, &redecoded);
+            assert_eq!(decoded.media_white_point, redecoded.media_white_point);
+        }
+    }
+}
@@ -26,7 +26,12 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::Rgb;
+use crate::mlaf::mlaf;
+use crate::{
+    Chromaticity, CmsError, ColorPrimaries, ColorProfile, DataColorSpace, Matrix3d, Matrix3f,
+    Oklab, Rgb, Vector3d, Vector3f, XyYRepresentable, Xyz,
+};
+use pxfm::{f_atan2f, f_cbrtf, f_hypotf};
 
 #[inline]
 fn filmlike_clip_rgb_tone(r: &mut f32, g: &mut f32, b: &mut f32, l: f32) {
@@ -64,3 +69,453 @@ pub fn filmlike_clip(rgb: Rgb<f32>) -> Rgb<f32> {
     }
     rgb
 }
+
+/// Strategy used by [OklabGamut::clip] to move an out-of-gamut color onto
+/// the gamut boundary.
+///
+/// All modes keep the hue in Oklab constant and differ only in the point on
+/// the lightness axis the color is projected towards.
+/// See [Björn Ottosson, sRGB gamut clipping](https://bottosson.github.io/posts/gamutclipping/).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamutClipMode {
+    /// Keeps lightness and reduces only chroma
+    PreserveChroma,
+    /// Projects towards L = 0.5
+    ProjectToMidGray,
+    /// Projects towards the lightness of the cusp for the hue
+    ProjectToCusp,
+    /// Adaptive projection towards L = 0.5, `alpha` controls how much chroma is preserved
+    AdaptiveMidGray(f32),
+    /// Adaptive projection towards the cusp lightness, `alpha` controls how much chroma is preserved
+    AdaptiveCusp(f32),
+}
+
+impl Default for GamutClipMode {
+    fn default() -> Self {
+        GamutClipMode::AdaptiveMidGray(0.05)
+    }
+}
+
+/// Point with the highest chroma for a given hue, in Oklab `L` and `C`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct OklabCusp {
+    pub(crate) l: f32,
+    pub(crate) c: f32,
+}
+
+const CUSP_TABLE_SIZE: usize = 360;
+
+// Oklab XYZ (D65) -> LMS
+const OKLAB_M1: Matrix3d = Matrix3d {
+    v: [
+        [0.8189330101, 0.3618667424, -0.1288597137],
+        [0.0329845436, 0.9293118715, 0.0361456387],
+        [0.0482003018, 0.2643662691, 0.6338517070],
+    ],
+};
+
+/// Hue-preserving gamut clipping in Oklab against an arbitrary RGB gamut.
+///
+/// Oklab is evaluated relative to target primaries and white point,
+/// cusps of the gamut are precomputed for each hue, so it is suitable
+/// for clipping into sRGB, Display P3, BT.2020 or any other RGB destination.
+///
+/// All inputs and outputs are linear RGB in the target primaries.
+#[derive(Debug, Clone)]
+pub struct OklabGamut {
+    rgb_to_lms: Matrix3f,
+    lms_to_rgb: Matrix3f,
+    rgb_to_xyz: Matrix3f,
+    xyz_to_rgb: Matrix3f,
+    cusps: Box<[OklabCusp; CUSP_TABLE_SIZE]>,
+}
+
+impl OklabGamut {
+    /// Creates gamut description for the given primaries and white point.
+    ///
+    /// Cone responses are normalized, so the white point always maps to neutral
+    /// Oklab `L = 1, a = 0, b = 0`.
+    pub fn new(primaries: ColorPrimaries, white_point: impl XyYRepresentable) -> OklabGamut {
+        OklabGamut::from_rgb_to_xyz(primaries.transform_to_xyz_d(white_point))
+    }
+
+    /// Creates gamut description from RGB matrix shaper profile colorants.
+    ///
+    /// XYZ of such gamut is relative to the profile connection space D50 white.
+    pub fn from_profile(profile: &ColorProfile) -> Result<OklabGamut, CmsError> {
+        if profile.color_space != DataColorSpace::Rgb || !profile.is_matrix_shaper() {
+            return Err(CmsError::UnsupportedProfileConnection);
+        }
+        let rgb_to_xyz = profile.rgb_to_xyz_matrix();
+        if rgb_to_xyz.determinant().is_none() {
+            return Err(CmsError::DivisionByZero);
+        }
+        Ok(OklabGamut::from_rgb_to_xyz(rgb_to_xyz))
+    }
+
+    fn from_rgb_to_xyz(rgb_to_xyz: Matrix3d) -> OklabGamut {
+        let mut rgb_to_lms = OKLAB_M1.mat_mul(rgb_to_xyz);
+        for row in rgb_to_lms.v.iter_mut() {
+            let sum = row[0] + row[1] + row[2];
+            if sum != 0. {
+                let recip = 1. / sum;
+                for v in row.iter_mut() {
+                    *v *= recip;
+                }
+            }
+        }
+        let lms_to_rgb = rgb_to_lms.inverse();
+
+        let mut cusps = Box::new([OklabCusp::default(); CUSP_TABLE_SIZE]);
+        for (i, cusp) in cusps.iter_mut().enumerate() {
+            let hue = (i as f64 / CUSP_TABLE_SIZE as f64) * std::f64::consts::TAU;
+            *cusp = compute_cusp(hue.cos(), hue.sin(), &lms_to_rgb);
+        }
+
+        OklabGamut {
+            rgb_to_lms: rgb_to_lms.to_f32(),
+            lms_to_rgb: lms_to_rgb.to_f32(),
+            rgb_to_xyz: rgb_to_xyz.to_f32(),
+            xyz_to_rgb: rgb_to_xyz.inverse().to_f32(),
+            cusps,
+        }
+    }
+
+    /// Gamut of sRGB/BT.709
+    pub fn srgb() -> OklabGamut {
+        OklabGamut::new(ColorPrimaries::BT_709, Chromaticity::D65)
+    }
+
+    /// Gamut of Display P3
+    pub fn display_p3() -> OklabGamut {
+        OklabGamut::new(ColorPrimaries::DISPLAY_P3, Chromaticity::D65)
+    }
+
+    /// Gamut of BT.2020
+    pub fn bt2020() -> OklabGamut {
+        OklabGamut::new(ColorPrimaries::BT_2020, Chromaticity::D65)
+    }
+
+    /// Converts linear RGB in target primaries to [Oklab]
+    #[inline]
+    pub fn to_oklab(&self, rgb: Rgb<f32>) -> Oklab {
+        let lms = self.rgb_to_lms.f_mul_vector(Vector3f {
+            v: [rgb.r, rgb.g, rgb.b],
+        });
+        let l_ = f_cbrtf(lms.v[0]);
+        let m_ = f_cbrtf(lms.v[1]);
+        let s_ = f_cbrtf(lms.v[2]);
+        Oklab::new(
+            mlaf(
+                mlaf(0.2104542553f32 * l_, 0.7936177850f32, m_),
+                -0.0040720468f32,
+                s_,
+            ),
+            mlaf(
+                mlaf(1.9779984951f32 * l_, -2.4285922050f32, m_),
+                0.4505937099f32,
+                s_,
+            ),
+            mlaf(
+                mlaf(0.0259040371f32 * l_, 0.7827717662f32, m_),
+                -0.8086757660f32,
+                s_,
+            ),
+        )
+    }
+
+    /// Converts linear RGB in target primaries to XYZ relative to the gamut white
+    #[inline]
+    pub(crate) fn to_xyz(&self, rgb: Rgb<f32>) -> Xyz {
+        let xyz = self.rgb_to_xyz.f_mul_vector(Vector3f {
+            v: [rgb.r, rgb.g, rgb.b],
+        });
+        Xyz::new(xyz.v[0], xyz.v[1], xyz.v[2])
+    }
+
+    /// Converts XYZ relative to the gamut white to linear RGB in target primaries
+    #[inline]
+    pub(crate) fn xyz_to_linear_rgb(&self, xyz: Xyz) -> Rgb<f32> {
+        let rgb = self.xyz_to_rgb.f_mul_vector(Vector3f {
+            v: [xyz.x, xyz.y, xyz.z],
+        });
+        Rgb::new(rgb.v[0], rgb.v[1], rgb.v[2])
+    }
+
+    /// Converts [Oklab] to linear RGB in target primaries
+    #[inline]
+    pub fn to_linear_rgb(&self, oklab: Oklab) -> Rgb<f32> {
+        let l_ = mlaf(
+            mlaf(oklab.l, 0.3963377774f32, oklab.a),
+            0.2158037573f32,
+            oklab.b,
+        );
+        let m_ = mlaf(
+            mlaf(oklab.l, -0.1055613458f32, oklab.a),
+            -0.0638541728f32,
+            oklab.b,
+        );
+        let s_ = mlaf(
+            mlaf(oklab.l, -0.0894841775f32, oklab.a),
+            -1.2914855480f32,
+            oklab.b,
+        );
+        let rgb = self.lms_to_rgb.f_mul_vector(Vector3f {
+            v: [l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_],
+        });
+        Rgb::new(rgb.v[0], rgb.v[1], rgb.v[2])
+    }
+
+    /// Returns lightness and chroma of the gamut cusp for normalized `a` and `b`
+    #[inline]
+    fn find_cusp(&self, a: f32, b: f32) -> OklabCusp {
+        let hue = f_atan2f(b, a);
+        let pos = if hue < 0. {
+            hue + std::f32::consts::TAU
+        } else {
+            hue
+        } * (CUSP_TABLE_SIZE as f32 / std::f32::consts::TAU);
+        let idx0 = (pos as usize).min(CUSP_TABLE_SIZE - 1);
+        let idx1 = if idx0 + 1 == CUSP_TABLE_SIZE {
+            0
+        } else {
+            idx0 + 1
+        };
+        let w = pos - idx0 as f32;
+        let c0 = self.cusps[idx0];
+        let c1 = self.cusps[idx1];
+        OklabCusp {
+            l: mlaf(c0.l, c1.l - c0.l, w),
+            c: mlaf(c0.c, c1.c - c0.c, w),
+        }
+    }
+
+    /// Finds intersection of the line from (L0, 0) to (L1, C1) with the gamut boundary,
+    /// `a` and `b` must be normalized so `a^2 + b^2 == 1`.
+    fn find_gamut_intersection(&self, a: f32, b: f32, l1: f32, c1: f32, l0: f32) -> f32 {
+        self.find_gamut_intersection_with_cusp(a, b, l1, c1, l0, self.find_cusp(a, b))
+    }
+
+    /// Same as [OklabGamut::find_gamut_intersection] with precomputed `cusp` of the hue
+    pub(crate) fn find_gamut_intersection_with_cusp(
+        &self,
+        a: f32,
+        b: f32,
+        l1: f32,
+        c1: f32,
+        l0: f32,
+        cusp: OklabCusp,
+    ) -> f32 {
+        if ((l1 - l0) * cusp.c - (cusp.l - l0) * c1) <= 0. {
+            // Lower half, triangle is good enough
+            return cusp.c * l0 / (c1 * cusp.l + cusp.c * (l0 - l1));
+        }
+
+        // Upper half, start with the triangle and refine with one Halley step
+        let mut t = cusp.c * (l0 - 1.) / (c1 * (cusp.l - 1.) + cusp.c * (l0 - l1));
+
+        let dl = l1 - l0;
+        let dc = c1;
+
+        let k_l = mlaf(0.3963377774f32 * a, 0.2158037573f32, b);
+        let k_m = mlaf(-0.1055613458f32 * a, -0.0638541728f32, b);
+        let k_s = mlaf(-0.0894841775f32 * a, -1.2914855480f32, b);
+
+        let l_dt = mlaf(dl, dc, k_l);
+        let m_dt = mlaf(dl, dc, k_m);
+        let s_dt = mlaf(dl, dc, k_s);
+
+        let l = mlaf(l0 * (1. - t), t, l1);
+        let c = t * c1;
+
+        let l_ = mlaf(l, c, k_l);
+        let m_ = mlaf(l, c, k_m);
+        let s_ = mlaf(l, c, k_s);
+
+        let lms = Vector3f {
+            v: [l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_],
+        };
+        let lms_dt = Vector3f {
+            v: [
+                3. * l_dt * l_ * l_,
+                3. * m_dt * m_ * m_,
+                3. * s_dt * s_ * s_,
+            ],
+        };
+        let lms_dt2 = Vector3f {
+            v: [
+                6. * l_dt * l_dt * l_,
+                6. * m_dt * m_dt * m_,
+                6. * s_dt * s_dt * s_,
+            ],
+        };
+
+        let rgb = self.lms_to_rgb.f_mul_vector(lms);
+        let rgb_dt = self.lms_to_rgb.f_mul_vector(lms_dt);
+        let rgb_dt2 = self.lms_to_rgb.f_mul_vector(lms_dt2);
+
+        let mut step = f32::MAX;
+        for i in 0..3 {
+            let r = rgb.v[i] - 1.;
+            let r1 = rgb_dt.v[i];
+            let r2 = rgb_dt2.v[i];
+            let u = r1 / (r1 * r1 - 0.5 * r * r2);
+            if u >= 0. {
+                step = step.min(-r * u);
+            }
+        }
+        t += step;
+        t
+    }
+
+    /// Clips linear RGB in target primaries into the gamut preserving hue.
+    ///
+    /// Values already inside \[0; 1\] are returned unchanged.
+    pub fn clip(&self, rgb: Rgb<f32>, mode: GamutClipMode) -> Rgb<f32> {
+        if rgb.r <= 1. && rgb.g <= 1. && rgb.b <= 1. && rgb.r >= 0. && rgb.g >= 0. && rgb.b >= 0. {
+            return rgb;
+        }
+
+        let lab = self.to_oklab(rgb);
+
+        const EPS: f32 = 0.00001;
+        let l = lab.l;
+        let c = f_hypotf(lab.a, lab.b).max(EPS);
+        let a_ = lab.a / c;
+        let b_ = lab.b / c;
+
+        let l0 = match mode {
+            GamutClipMode::PreserveChroma => l.max(0.).min(1.),
+            GamutClipMode::ProjectToMidGray => 0.5,
+            GamutClipMode::ProjectToCusp => self.find_cusp(a_, b_).l,
+            GamutClipMode::AdaptiveMidGray(alpha) => {
+                let ld = l - 0.5;
+                let e1 = 0.5 + ld.abs() + alpha * c;
+                0.5 * (1. + ld.signum() * (e1 - (e1 * e1 - 2. * ld.abs()).max(0.).sqrt()))
+            }
+            GamutClipMode::AdaptiveCusp(alpha) => {
+                let cusp = self.find_cusp(a_, b_);
+                let ld = l - cusp.l;
+                let k = 2. * if ld > 0. { 1. - cusp.l } else { cusp.l };
+                let e1 = 0.5 * k + ld.abs() + alpha * c / k;
+                cusp.l + 0.5 * (ld.signum() * (e1 - (e1 * e1 - 2. * k * ld.abs()).max(0.).sqrt()))
+            }
+        };
+
+        let t = self.find_gamut_intersection(a_, b_, l, c, l0);
+        let l_clipped = mlaf(l0 * (1. - t), t, l);
+        let c_clipped = t * c;
+
+        self.to_linear_rgb(Oklab::new(l_clipped, c_clipped * a_, c_clipped * b_))
+    }
+}
+
+/// Computes cusp for hue direction (`a`, `b`) by numerically searching
+/// for the maximum saturation `S = C / L` where one of the channels reaches zero.
+fn compute_cusp(a: f64, b: f64, lms_to_rgb: &Matrix3d) -> OklabCusp {
+    let k_l = 0.3963377774 * a + 0.2158037573 * b;
+    let k_m = -0.1055613458 * a - 0.0638541728 * b;
+    let k_s = -0.0894841775 * a - 1.2914855480 * b;
+
+    let rgb_at = |s: f64| -> Vector3d {
+        let l_ = 1. + s * k_l;
+        let m_ = 1. + s * k_m;
+        let s_ = 1. + s * k_s;
+        lms_to_rgb.mul_vector(Vector3d {
+            v: [l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_],
+        })
+    };
+    let min_channel = |s: f64| -> f64 {
+        let rgb = rgb_at(s);
+        rgb.v[0].min(rgb.v[1]).min(rgb.v[2])
+    };
+
+    const STEP: f64 = 0.01;
+    const MAX_SATURATION: f64 = 16.;
+
+    let mut lo = 0f64;
+    let mut hi = STEP;
+    while hi < MAX_SATURATION && min_channel(hi) > 0. {
+        lo = hi;
+        hi += STEP;
+    }
+    for _ in 0..60 {
+        let mid = 0.5 * (lo + hi);
+        if min_channel(mid) > 0. {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let saturation = lo;
+
+    let rgb = rgb_at(saturation);
+    let max_channel = rgb.v[0].max(rgb.v[1]).max(rgb.v[2]);
+    let l_cusp = if max_channel > 0. {
+        (1. / max_channel).cbrt()
+    } else {
+        1.
+    };
+    OklabCusp {
+        l: l_cusp as f32,
+        c: (l_cusp * saturation) as f32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oklab_gamut_srgb_matches_oklab() {
+        let gamut = OklabGamut::srgb();
+        let rgb = Rgb::new(0.2f32, 0.5, 0.7);
+        let custom = gamut.to_oklab(rgb);
+        let reference = Oklab::from_linear_rgb(rgb);
+        assert!((custom.l - reference.l).abs() < 1e-3);
+        assert!((custom.a - reference.a).abs() < 1e-3);
+        assert!((custom.b - reference.b).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_oklab_gamut_clip() {
+        let modes = [
+            GamutClipMode::PreserveChroma,
+            GamutClipMode::ProjectToMidGray,
+            GamutClipMode::ProjectToCusp,
+            GamutClipMode::AdaptiveMidGray(0.05),
+            GamutClipMode::AdaptiveCusp(0.05),
+        ];
+        for gamut in [
+            OklabGamut::srgb(),
+            OklabGamut::display_p3(),
+            OklabGamut::bt2020(),
+        ] {
+            let inside = Rgb::new(0.3f32, 0.4, 0.5);
+            assert_eq!(gamut.clip(inside, GamutClipMode::default()), inside);
+            for mode in modes {
+                for src in [
+                    Rgb::new(1.3f32, -0.2, 0.1),
+                    Rgb::new(-0.1f32, 0.9, 1.2),
+                    Rgb::new(0.1f32, -0.05, 1.1),
+                ] {
+                    let clipped = gamut.clip(src, mode);
+                    let src_lab = gamut.to_oklab(src);
+                    let clipped_lab = gamut.to_oklab(clipped.max(0.));
+                    let src_hue = f_atan2f(src_lab.b, src_lab.a);
+                    let dst_hue = f_atan2f(clipped_lab.b, clipped_lab.a);
+                    assert!(
+                        (src_hue - dst_hue).abs() < 0.02,
+                        "Hue was not preserved for {mode:?}: {src_hue} {dst_hue}"
+                    );
+                    for v in [clipped.r, clipped.g, clipped.b] {
+                        assert!(
+                            (-0.01..=1.01).contains(&v),
+                            "Value is out of gamut for {mode:?}: {clipped:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
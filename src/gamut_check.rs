@@ -0,0 +1,156 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Checks whether colors are reproducible on an output device.
+//!
+//! Old ICC v2 workflows trust the `gamt` tag written by the profile vendor, others prefer
+//! to measure how far a color moves after a PCS to device to PCS round trip.
+use crate::lab::{lab_to_xyz, xyz_to_lab};
+use crate::reference::ReferenceGamutTag;
+use crate::{CmsError, ColorProfile, Lab, ReferenceTransform, RenderingIntent, TransformOptions};
+
+/// Method used by [GamutChecker]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GamutCheckMethod {
+    /// `gamt` tag when the profile has one, round trip otherwise
+    #[default]
+    Auto,
+    /// Profile `gamt` tag, color is out of gamut when tag output exceeds one half
+    GamutTag,
+    /// Relative colorimetric PCS to device to PCS round trip,
+    /// color is out of gamut when it moves further than the threshold in Lab
+    RoundTrip,
+}
+
+/// Decides whether D50 CIE Lab colors are in gamut of an output profile.
+#[derive(Clone, Debug)]
+pub struct GamutChecker {
+    method: GamutCheckMethod,
+    gamut_tag: Option<ReferenceGamutTag>,
+    round_trip: ReferenceTransform,
+    threshold: f32,
+}
+
+impl GamutChecker {
+    /// Default round trip threshold, in CIE76 delta E
+    pub const DEFAULT_THRESHOLD: f32 = 2.;
+
+    /// Creates checker for `profile`, [GamutCheckMethod::GamutTag] fails with
+    /// [CmsError::UnsupportedProfileConnection] when profile has no `gamt` tag.
+    pub fn new(profile: &ColorProfile, method: GamutCheckMethod) -> Result<GamutChecker, CmsError> {
+        let gamut_tag = ReferenceGamutTag::new(profile)?;
+        let method = match (method, gamut_tag.is_some()) {
+            (GamutCheckMethod::Auto, true) => GamutCheckMethod::GamutTag,
+            (GamutCheckMethod::Auto, false) => GamutCheckMethod::RoundTrip,
+            (GamutCheckMethod::GamutTag, false) => {
+                return Err(CmsError::UnsupportedProfileConnection);
+            }
+            (method, _) => method,
+        };
+        let options = TransformOptions {
+            rendering_intent: RenderingIntent::RelativeColorimetric,
+            ..Default::default()
+        };
+        Ok(GamutChecker {
+            method,
+            gamut_tag,
+            round_trip: ReferenceTransform::new(profile, profile, options)?,
+            threshold: GamutChecker::DEFAULT_THRESHOLD,
+        })
+    }
+
+    /// Sets round trip threshold, in CIE76 delta E
+    pub fn with_threshold(mut self, threshold: f32) -> GamutChecker {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Method actually used for checks, never [GamutCheckMethod::Auto]
+    pub fn method(&self) -> GamutCheckMethod {
+        self.method
+    }
+
+    /// Checks color with the method returned by [GamutChecker::method]
+    pub fn is_in_gamut(&self, lab: Lab) -> Result<bool, CmsError> {
+        match self.method {
+            GamutCheckMethod::GamutTag => Ok(self.gamut_tag_value(lab)?.unwrap_or(0.) <= 0.5),
+            _ => Ok(self.round_trip_delta_e(lab)? <= self.threshold),
+        }
+    }
+
+    /// Output of `gamt` tag in \[0; 1\], `None` when profile has no `gamt` tag
+    pub fn gamut_tag_value(&self, lab: Lab) -> Result<Option<f32>, CmsError> {
+        let Some(gamut_tag) = self.gamut_tag.as_ref() else {
+            return Ok(None);
+        };
+        let value = gamut_tag.evaluate(lab_to_xyz([lab.l as f64, lab.a as f64, lab.b as f64]))?;
+        Ok(Some(value as f32))
+    }
+
+    /// CIE76 delta E between color and its PCS to device to PCS round trip,
+    /// available regardless of the method, so both methods may be compared
+    pub fn round_trip_delta_e(&self, lab: Lab) -> Result<f32, CmsError> {
+        let lab = [lab.l as f64, lab.a as f64, lab.b as f64];
+        let mut device = vec![0f64; self.round_trip.dst_channels()];
+        self.round_trip
+            .pcs_to_device(lab_to_xyz(lab), &mut device)?;
+        let back = xyz_to_lab(self.round_trip.device_to_pcs(&device)?);
+        let delta = (0..3).map(|i| (lab[i] - back[i]).powi(2)).sum::<f64>();
+        Ok(delta.sqrt() as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gamut_check_methods() {
+        let cmyk =
+            ColorProfile::new_from_slice(include_bytes!("../assets/us_swop_coated.icc")).unwrap();
+        let auto = GamutChecker::new(&cmyk, GamutCheckMethod::Auto).unwrap();
+        assert_eq!(auto.method(), GamutCheckMethod::GamutTag);
+        let round_trip = GamutChecker::new(&cmyk, GamutCheckMethod::RoundTrip).unwrap();
+        assert_eq!(round_trip.method(), GamutCheckMethod::RoundTrip);
+
+        let gray = Lab::new(50., 0., 0.);
+        let saturated = Lab::new(60., -100., -60.);
+        for checker in [&auto, &round_trip] {
+            assert!(checker.is_in_gamut(gray).unwrap());
+            assert!(!checker.is_in_gamut(saturated).unwrap());
+        }
+        assert!(auto.round_trip_delta_e(saturated).unwrap() > GamutChecker::DEFAULT_THRESHOLD);
+        assert!(auto.gamut_tag_value(saturated).unwrap().unwrap() > 0.5);
+
+        let srgb = ColorProfile::new_srgb();
+        assert!(GamutChecker::new(&srgb, GamutCheckMethod::GamutTag).is_err());
+        let checker = GamutChecker::new(&srgb, GamutCheckMethod::Auto).unwrap();
+        assert_eq!(checker.method(), GamutCheckMethod::RoundTrip);
+        assert!(checker.is_in_gamut(gray).unwrap());
+    }
+}
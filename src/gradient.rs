@@ -0,0 +1,187 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{Cam16, Cam16ViewingConditions, GamutClipMode, Oklab, OklabGamut, Rgb};
+use pxfm::{f_atan2f, f_expf, f_hypotf, f_logf, f_powf, f_sincosf};
+
+/// Space where gradient steps are evenly spaced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientSpace {
+    /// Straight line in [Oklab] of the gamut
+    Oklab,
+    /// Straight line in CAM16-UCS `J'a'b'`.
+    ///
+    /// Viewing conditions white is expected to match the gamut white,
+    /// e.g. D50 for [OklabGamut::from_profile].
+    Cam16Ucs(Cam16ViewingConditions),
+}
+
+const UCS_C1: f32 = 0.007;
+const UCS_C2: f32 = 0.0228;
+
+fn to_ucs(rgb: Rgb<f32>, gamut: &OklabGamut, conditions: &Cam16ViewingConditions) -> [f32; 3] {
+    let cam = Cam16::from_xyz(gamut.to_xyz(rgb), conditions);
+    let j = (1. + 100. * UCS_C1) * cam.j / (1. + UCS_C1 * cam.j);
+    let m = f_logf(1. + UCS_C2 * cam.m) / UCS_C2;
+    let (h_sin, h_cos) = f_sincosf(cam.h.to_radians());
+    [j, m * h_cos, m * h_sin]
+}
+
+fn from_ucs(ucs: [f32; 3], gamut: &OklabGamut, conditions: &Cam16ViewingConditions) -> Rgb<f32> {
+    let [j_ucs, a, b] = ucs;
+    let j = j_ucs / (1. + 100. * UCS_C1 - UCS_C1 * j_ucs);
+    let m = (f_expf(UCS_C2 * f_hypotf(a, b)) - 1.) / UCS_C2;
+    let c = m / f_powf(conditions.luminance_adaptation(), 0.25);
+    let h = f_atan2f(b, a).to_degrees();
+    let h = if h < 0. { h + 360. } else { h };
+    gamut.xyz_to_linear_rgb(Cam16::jch_to_xyz(j, c, h, conditions))
+}
+
+/// Produces `n` colors from `src` to `dst` inclusive, evenly spaced in `space`.
+///
+/// Colors are linear RGB in `gamut` primaries. Interpolating gamut colors in perceptual
+/// space may leave the gamut, if `clip` is set such steps are clipped with [OklabGamut::clip].
+pub fn gradient(
+    src: Rgb<f32>,
+    dst: Rgb<f32>,
+    n: usize,
+    space: GradientSpace,
+    gamut: &OklabGamut,
+    clip: Option<GamutClipMode>,
+) -> Vec<Rgb<f32>> {
+    let weight = |i: usize| {
+        if n > 1 { i as f32 / (n - 1) as f32 } else { 0. }
+    };
+    let steps: Vec<Rgb<f32>> = match space {
+        GradientSpace::Oklab => {
+            let start = gamut.to_oklab(src);
+            let end = gamut.to_oklab(dst);
+            (0..n)
+                .map(|i| {
+                    let t = weight(i);
+                    gamut.to_linear_rgb(Oklab::new(
+                        start.l + (end.l - start.l) * t,
+                        start.a + (end.a - start.a) * t,
+                        start.b + (end.b - start.b) * t,
+                    ))
+                })
+                .collect()
+        }
+        GradientSpace::Cam16Ucs(conditions) => {
+            let start = to_ucs(src, gamut, &conditions);
+            let end = to_ucs(dst, gamut, &conditions);
+            (0..n)
+                .map(|i| {
+                    let t = weight(i);
+                    let ucs = [0, 1, 2].map(|c| start[c] + (end[c] - start[c]) * t);
+                    from_ucs(ucs, gamut, &conditions)
+                })
+                .collect()
+        }
+    };
+    match clip {
+        Some(mode) => steps.into_iter().map(|x| gamut.clip(x, mode)).collect(),
+        None => steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Chromaticity, ColorProfile};
+
+    #[test]
+    fn test_gradient() {
+        let gamut = OklabGamut::srgb();
+        let red = Rgb::new(1f32, 0., 0.);
+        let blue = Rgb::new(0f32, 0., 1.);
+        assert!(gradient(red, blue, 0, GradientSpace::Oklab, &gamut, None).is_empty());
+
+        let steps = gradient(red, blue, 9, GradientSpace::Oklab, &gamut, None);
+        assert_eq!(steps.len(), 9);
+        for (v, e) in [(steps[0], red), (steps[8], blue)] {
+            assert!(
+                (v.r - e.r).abs() < 1e-3 && (v.g - e.g).abs() < 1e-3 && (v.b - e.b).abs() < 1e-3
+            );
+        }
+        let distances = steps
+            .windows(2)
+            .map(|w| {
+                gamut
+                    .to_oklab(w[0])
+                    .euclidean_distance(gamut.to_oklab(w[1]))
+            })
+            .collect::<Vec<_>>();
+        for d in distances.iter() {
+            assert!((d - distances[0]).abs() < 1e-3, "{distances:?}");
+        }
+
+        let clipped = gradient(
+            red,
+            blue,
+            9,
+            GradientSpace::Oklab,
+            &gamut,
+            Some(GamutClipMode::PreserveChroma),
+        );
+        for v in clipped.iter() {
+            for c in [v.r, v.g, v.b] {
+                assert!((-1e-3..=1. + 1e-3).contains(&c), "{clipped:?}");
+            }
+        }
+
+        let srgb = OklabGamut::from_profile(&ColorProfile::new_srgb()).unwrap();
+        let conditions = Cam16ViewingConditions::new(
+            Chromaticity::D50.to_xyz(),
+            64.,
+            20.,
+            Default::default(),
+            false,
+        );
+        let white = Rgb::new(1f32, 1., 1.);
+        let gray = Rgb::new(0.2f32, 0.2, 0.2);
+        let steps = gradient(
+            gray,
+            white,
+            5,
+            GradientSpace::Cam16Ucs(conditions),
+            &srgb,
+            None,
+        );
+        for (v, e) in [(steps[0], gray), (steps[4], white)] {
+            assert!(
+                (v.r - e.r).abs() < 1e-2 && (v.g - e.g).abs() < 1e-2 && (v.b - e.b).abs() < 1e-2
+            );
+        }
+        // Achromatic ramp stays neutral and increases monotonically
+        for w in steps.windows(2) {
+            assert!(w[1].g > w[0].g);
+            assert!((w[1].r - w[1].g).abs() < 1e-2 && (w[1].b - w[1].g).abs() < 1e-2);
+        }
+    }
+}
@@ -49,6 +49,16 @@ impl LutStore {
         }
     }
 
+    /// Quantizes CLUT values in \[0; 1\], e.g. built with [crate::Cube::from_fn], to 16 bit store
+    pub fn from_clut_f32(values: &[f32]) -> LutStore {
+        LutStore::Store16(
+            values
+                .iter()
+                .map(|&x| (x.max(0.).min(1.) * 65535. + 0.5) as u16)
+                .collect(),
+        )
+    }
+
     pub(crate) fn is_degenerated(&self, entries: usize, channel: usize) -> bool {
         let start = entries * channel;
         let end = start + entries;
@@ -122,9 +132,7 @@ impl ToneReprCurve {
                 }
                 if lut.len() == 1 {
                     let gamma = 1. / crate::trc::u8_fixed_8number_to_float(lut[0]);
-                    if (gamma - 1.).abs() < 1e-4 {
-                        return true;
-                    }
+                    return (gamma - 1.).abs() < 1e-4;
                 }
                 is_curve_linear16(lut)
             }
@@ -137,6 +145,11 @@ impl ToneReprCurve {
                 }
                 false
             }
+            ToneReprCurve::Segmented(curve) => {
+                let lut = curve.to_lut(256);
+                is_curve_linear16(&lut)
+            }
+            ToneReprCurve::Custom(curve) => is_curve_linear16(&curve.to_lut()),
         }
     }
 
@@ -144,6 +157,8 @@ impl ToneReprCurve {
         match &self {
             ToneReprCurve::Lut(lut) => is_curve_monotonic(lut),
             ToneReprCurve::Parametric(_) => true,
+            ToneReprCurve::Segmented(curve) => is_curve_monotonic(&curve.to_lut(4096)),
+            ToneReprCurve::Custom(curve) => is_curve_monotonic(&curve.to_lut()),
         }
     }
 
@@ -151,6 +166,8 @@ impl ToneReprCurve {
         match &self {
             ToneReprCurve::Lut(lut) => is_curve_degenerated(lut),
             ToneReprCurve::Parametric(_) => false,
+            ToneReprCurve::Segmented(curve) => is_curve_degenerated(&curve.to_lut(4096)),
+            ToneReprCurve::Custom(curve) => is_curve_degenerated(&curve.to_lut()),
         }
     }
 
@@ -158,6 +175,8 @@ impl ToneReprCurve {
         match &self {
             ToneReprCurve::Lut(lut) => does_curve_have_discontinuity(lut),
             ToneReprCurve::Parametric(_) => false,
+            ToneReprCurve::Segmented(curve) => does_curve_have_discontinuity(&curve.to_lut(4096)),
+            ToneReprCurve::Custom(curve) => does_curve_have_discontinuity(&curve.to_lut()),
         }
     }
 }
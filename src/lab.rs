@@ -56,6 +56,41 @@ impl Lab {
     }
 }
 
+const LAB_EPSILON: f64 = 216. / 24389.;
+const LAB_KAPPA: f64 = 24389. / 27.;
+
+/// Converts CIE Lab into CIE XYZ relative to D50 in `f64`
+pub(crate) fn lab_to_xyz(lab: [f64; 3]) -> [f64; 3] {
+    let wp = Chromaticity::D50.to_xyzd();
+    let fy = (lab[0] + 16.) / 116.;
+    let fx = fy + lab[1] / 500.;
+    let fz = fy - lab[2] / 200.;
+    let f_inv = |t: f64| {
+        if t * t * t > LAB_EPSILON {
+            t * t * t
+        } else {
+            (116. * t - 16.) / LAB_KAPPA
+        }
+    };
+    [f_inv(fx) * wp.x, f_inv(fy) * wp.y, f_inv(fz) * wp.z]
+}
+
+/// Converts CIE XYZ relative to D50 into CIE Lab in `f64`
+pub(crate) fn xyz_to_lab(xyz: [f64; 3]) -> [f64; 3] {
+    let wp = Chromaticity::D50.to_xyzd();
+    let f = |t: f64| {
+        if t > LAB_EPSILON {
+            t.cbrt()
+        } else {
+            (LAB_KAPPA * t + 16.) / 116.
+        }
+    };
+    let fx = f(xyz[0] / wp.x);
+    let fy = f(xyz[1] / wp.y);
+    let fz = f(xyz[2] / wp.z);
+    [116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz)]
+}
+
 #[inline(always)]
 const fn f_1(t: f32) -> f32 {
     if t <= 24.0 / 116.0 {
@@ -37,38 +37,98 @@
 )]
 #![allow(stable_features)]
 #![cfg_attr(
-    not(any(feature = "avx", feature = "sse", feature = "avx512", feature = "neon")),
+    all(feature = "neon-fp16", target_arch = "aarch64"),
+    feature(stdarch_neon_f16, f16)
+)]
+#![cfg_attr(
+    not(any(
+        feature = "avx",
+        feature = "sse",
+        feature = "avx512",
+        feature = "neon",
+        feature = "platform"
+    )),
     forbid(unsafe_code)
 )]
+mod abstract_profile;
+mod aces;
+mod adjustment;
+#[cfg(feature = "bench")]
+mod bench;
+mod broadcast;
+mod bytes;
+mod cam16;
+mod camera_log;
 mod chad;
 mod cicp;
+mod clipping;
+mod color_models;
+mod colorimetry;
+pub mod compat;
+mod composite;
+#[cfg(feature = "conformance")]
+mod conformance;
+mod const_tables;
 mod conversions;
+mod converter;
+mod cross_depth;
+mod custom_tag;
 mod dat;
 mod defaults;
+mod device_link;
+mod downscale;
+mod edid;
+mod equivalence;
 mod err;
+mod format;
+mod frames;
+#[cfg(feature = "arbitrary")]
+mod fuzzing;
 mod gamma;
 mod gamut;
+mod gamut_check;
+mod gradient;
 mod ictcp;
 mod jzazbz;
 mod jzczhz;
 mod lab;
+mod linear_light;
+mod linearization_cache;
+mod lut_compress;
 mod luv;
 /// One of main intent is to provide fast math available in const context
 /// ULP most of the methods <= 0.5
 mod math;
 mod matrix;
+mod media;
 mod mlaf;
+mod mpe;
+mod named;
 mod nd_array;
+mod okhsl;
+mod okhsv;
 mod oklab;
 mod oklch;
+mod parallel;
+#[cfg(feature = "platform")]
+mod platform;
+mod precision;
 mod profile;
 mod reader;
+mod rect;
+mod reference;
+mod registry;
 mod rgb;
 mod safe_math;
+mod segmented;
+mod spectral;
 mod tag;
+mod trace;
 mod transform;
 mod trc;
+mod vcgt;
 mod writer;
+mod ycbcr;
 mod yrg;
 // Simple math analysis module
 mod chromaticity;
@@ -79,46 +139,122 @@ mod matan;
 mod srlab2;
 mod xyy;
 
+pub use aces::{AcesChain, aces_cct_from_linear, aces_cct_to_linear};
+pub use adjustment::{AdjustableTransform, ToneAdjustment};
+#[cfg(feature = "bench")]
+pub use bench::StageTimings;
+pub use broadcast::{
+    BroadcastConversion, BroadcastConverter, BroadcastGamutMapping, BroadcastLuminance,
+};
+pub use bytes::{ByteSample, TransformBytesExecutor};
+#[cfg(any(
+    feature = "avx",
+    feature = "sse",
+    feature = "avx512",
+    feature = "neon",
+    feature = "platform"
+))]
+pub use bytes::{samples_from_bytes, samples_from_bytes_mut};
+pub use cam16::{
+    Cam16, Cam16Surround, Cam16ViewingConditions, cam16_brightness, cam16_colorfulness,
+    cam16_saturation,
+};
+pub use camera_log::CameraLog;
 pub use chad::{
     adapt_to_d50, adapt_to_d50_d, adapt_to_illuminant, adapt_to_illuminant_d,
     adapt_to_illuminant_xyz, adapt_to_illuminant_xyz_d, adaption_matrix, adaption_matrix_d,
 };
 pub use chromaticity::Chromaticity;
 pub use cicp::{CicpColorPrimaries, ColorPrimaries, MatrixCoefficients, TransferCharacteristics};
+pub use clipping::{ClipMask, ClippingTransform16Bit};
+pub use color_models::{ColorModel, convert_color_models};
+pub use colorimetry::Colorimetry;
+pub use composite::{BlendSpace, Compositor};
+#[cfg(feature = "conformance")]
+pub use conformance::{
+    ConformanceCase, ConformanceReport, ConformanceSample, run_conformance, run_expected,
+};
+pub use const_tables::{
+    DISPLAY_P3_TO_SRGB, LINEAR_TO_SRGB_8BIT, LINEAR_TO_SRGB_8BIT_SIZE, SRGB_TO_DISPLAY_P3,
+    SRGB_TO_LINEAR_8BIT, display_p3_to_srgb_8bit, srgb_to_display_p3_8bit,
+};
+pub use converter::ColorConverter;
+pub use cross_depth::{CrossDepthExecutor, Transform8To16BitExecutor, Transform16To8BitExecutor};
+pub use custom_tag::{
+    CustomTag, CustomTagHandler, register_custom_tag_handler, unregister_custom_tag_handler,
+};
 pub use dat::ColorDateTime;
 pub use defaults::{
-    HLG_LUT_TABLE, PQ_LUT_TABLE, WHITE_POINT_D50, WHITE_POINT_D60, WHITE_POINT_D65,
-    WHITE_POINT_DCI_P3,
+    HLG_LUT_TABLE, PQ_LUT_TABLE, WHITE_POINT_D50, WHITE_POINT_D55, WHITE_POINT_D60,
+    WHITE_POINT_D65, WHITE_POINT_DCI_P3,
 };
+pub use downscale::DownscaleFilter;
 pub use dt_ucs::{DtUchHcb, DtUchHsb, DtUchJch};
-pub use err::{CmsError, MalformedSize};
-pub use gamut::filmlike_clip;
+#[cfg(feature = "equivalence")]
+pub use equivalence::{
+    EquivalenceReport, EquivalenceSample, SimdBackend, check_simd_equivalence, seeded_batch,
+    with_backend,
+};
+pub use err::{CmsError, LayoutChannels, MalformedSize};
+pub use format::{FormatTransform, PixelFormat, SampleFormat};
+pub use frames::{DitheredFrameTransform, FrameTransform};
+pub use gamut::{GamutClipMode, OklabGamut, filmlike_clip};
+pub use gamut_check::{GamutCheckMethod, GamutChecker};
+pub use gradient::{GradientSpace, gradient};
 pub use ictcp::ICtCp;
 pub use jzazbz::Jzazbz;
 pub use jzczhz::Jzczhz;
 pub use lab::Lab;
+pub use linear_light::{LinearEncoder, LinearLightTransform, Linearizer};
+pub use lut_compress::CompressLut;
 pub use luv::{LCh, Luv};
 pub use math::rounding_div_ceil;
 pub use matrix::{
-    BT2020_MATRIX, DISPLAY_P3_MATRIX, Matrix3, Matrix3d, Matrix3f, Matrix4f, SRGB_MATRIX, Vector3,
-    Vector3d, Vector3f, Vector3i, Vector3u, Vector4, Vector4d, Vector4f, Vector4i, Xyz, Xyzd,
+    BT2020_MATRIX, DISPLAY_P3_MATRIX, Matrix3, Matrix3d, Matrix3f, Matrix4f, SRGB_MATRIX, Vector2,
+    Vector2d, Vector2f, Vector3, Vector3d, Vector3f, Vector3i, Vector3u, Vector4, Vector4d,
+    Vector4f, Vector4i, Xyz, Xyzd,
 };
+pub use media::MediaPoints;
+pub use mpe::{MultiProcessType, ProcessElement};
+pub use named::{NamedColor, NamedColorList};
 pub use nd_array::{Cube, Hypercube};
+pub use okhsl::Okhsl;
+pub use okhsv::Okhsv;
 pub use oklab::Oklab;
-pub use oklch::Oklch;
+pub use oklch::{HuePath, Oklch};
+pub use parallel::{
+    ChunkPlan, ChunkRunner, ScopedThreads, TransformCost, transform_many_parallel,
+    transform_parallel, transform_parallel_with,
+};
+#[cfg(feature = "platform")]
+pub use platform::{DisplayProfileWatcher, display_profile};
+pub use precision::InterpolationError;
 pub use profile::{
     CicpProfile, ColorProfile, DataColorSpace, DescriptionString, LocalizableString, LutDataType,
-    LutMultidimensionalType, LutStore, LutType, LutWarehouse, Measurement, MeasurementGeometry,
-    ParsingOptions, ProfileClass, ProfileSignature, ProfileText, ProfileVersion, RenderingIntent,
-    StandardIlluminant, StandardObserver, TechnologySignatures, ViewingConditions,
+    LutMultidimensionalType, LutStore, LutType, LutWarehouse, MAX_CHANNELS, Measurement,
+    MeasurementGeometry, ParsingOptions, ProfileClass, ProfileSignature, ProfileText,
+    ProfileVersion, ProfileWarning, RenderingIntent, StandardIlluminant, StandardObserver,
+    TechnologySignatures, ViewingConditions,
 };
+pub use rect::{Rect, RectTransform};
+pub use reference::ReferenceTransform;
+pub use registry::ProfileRegistry;
 pub use rgb::{FusedExp, FusedExp2, FusedExp10, FusedLog, FusedLog2, FusedLog10, FusedPow, Rgb};
+pub use segmented::{CurveSegment, SegmentFormula, SegmentedCurve};
+pub use spectral::{SpectralPcs, SpectralPcsHandler, SpectralPcsKind};
 pub use srlab2::Srlab2;
 pub use transform::{
-    BarycentricWeightScale, InPlaceStage, InterpolationMethod, Layout, PointeeSizeExpressible,
-    Stage, Transform8BitExecutor, Transform16BitExecutor, TransformExecutor,
-    TransformF32BitExecutor, TransformF64BitExecutor, TransformOptions,
+    BarycentricWeightScale, FixedPointFormat, GrayWeights, InPlaceStage, InterpolationMethod,
+    Layout, PointeeSizeExpressible, Stage, Surround, SurroundCompensation, Transform8BitExecutor,
+    Transform16BitExecutor, TransformExecutor, TransformF32BitExecutor, TransformF64BitExecutor,
+    TransformOptions, TransformScratch, ViewingAdaptation,
+};
+pub use trc::{
+    CurveExtension, CustomCurve, GammaLutInterpolate, ToneCurveEvaluator, ToneReprCurve,
+    curve_from_gamma,
 };
-pub use trc::{GammaLutInterpolate, ToneCurveEvaluator, ToneReprCurve, curve_from_gamma};
+pub use vcgt::{VideoCardGamma, VideoCardGammaFormula, VideoCardGammaRamps};
+pub use writer::EncodingOptions;
 pub use xyy::{XyY, XyYRepresentable};
-pub use yrg::{Ych, Yrg, cie_y_1931_to_cie_y_2006};
+pub use ycbcr::{ChromaSubsampling, LumaTransform, YCbCrPlanes, YCbCrTransform};
+pub use yrg::{Ych, Yrg, YrgConverter, cie_y_1931_to_cie_y_2006};
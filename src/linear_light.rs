@@ -0,0 +1,286 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, Layout, ToneReprCurve, TransformF32BitExecutor,
+    TransformOptions,
+};
+use num_traits::AsPrimitive;
+use std::marker::PhantomData;
+
+const CHUNK_PIXELS: usize = 512;
+
+/// Source device values to linear light RGB in destination primaries
+pub struct Linearizer<T> {
+    executor: Box<TransformF32BitExecutor>,
+    src_layout: Layout,
+    dst_layout: Layout,
+    max_value: f32,
+    _phantom: PhantomData<T>,
+}
+
+/// Linear light RGB in destination primaries to destination device values
+pub struct LinearEncoder<T> {
+    executor: Box<TransformF32BitExecutor>,
+    layout: Layout,
+    max_value: f32,
+    quantize: bool,
+    _phantom: PhantomData<T>,
+}
+
+/// Transform split into linear light halves, so that resizing, blending or compositing
+/// may run in linear light between them.
+///
+/// Linear data is in destination primaries with destination layout, alpha is normalized to 0..1.
+pub struct LinearLightTransform<T> {
+    linearizer: Linearizer<T>,
+    encoder: LinearEncoder<T>,
+}
+
+impl<T> LinearLightTransform<T> {
+    /// Device to linear light half
+    pub fn linearizer(&self) -> &Linearizer<T> {
+        &self.linearizer
+    }
+
+    /// Linear light to device half
+    pub fn encoder(&self) -> &LinearEncoder<T> {
+        &self.encoder
+    }
+}
+
+fn check_lanes(
+    src_len: usize,
+    src_layout: Layout,
+    dst_len: usize,
+    dst_layout: Layout,
+) -> Result<(), CmsError> {
+    if src_len % src_layout.channels() != 0 || dst_len % dst_layout.channels() != 0 {
+        return Err(CmsError::LaneMultipleOfChannels);
+    }
+    if src_len / src_layout.channels() != dst_len / dst_layout.channels() {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    Ok(())
+}
+
+impl<T> Linearizer<T> {
+    /// Layout of source device values
+    pub fn src_layout(&self) -> Layout {
+        self.src_layout
+    }
+
+    /// Layout of produced linear light values
+    pub fn dst_layout(&self) -> Layout {
+        self.dst_layout
+    }
+}
+
+impl<T> LinearEncoder<T> {
+    /// Layout of linear light values and of produced device values
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+}
+
+impl<T: Copy + AsPrimitive<f32>> Linearizer<T> {
+    pub fn linearize(&self, src: &[T], dst: &mut [f32]) -> Result<(), CmsError> {
+        check_lanes(src.len(), self.src_layout, dst.len(), self.dst_layout)?;
+        let src_channels = self.src_layout.channels();
+        let dst_channels = self.dst_layout.channels();
+        let scale = 1. / self.max_value;
+        let mut working = [0f32; CHUNK_PIXELS * 4];
+        for (src, dst) in src
+            .chunks(CHUNK_PIXELS * src_channels)
+            .zip(dst.chunks_mut(CHUNK_PIXELS * dst_channels))
+        {
+            let working = &mut working[..src.len()];
+            for (dst, &src) in working.iter_mut().zip(src.iter()) {
+                *dst = src.as_() * scale;
+            }
+            self.executor.transform(working, dst)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Copy + 'static> LinearEncoder<T>
+where
+    f32: AsPrimitive<T>,
+{
+    pub fn encode(&self, src: &[f32], dst: &mut [T]) -> Result<(), CmsError> {
+        check_lanes(src.len(), self.layout, dst.len(), self.layout)?;
+        let channels = self.layout.channels();
+        let mut working = [0f32; CHUNK_PIXELS * 4];
+        for (src, dst) in src
+            .chunks(CHUNK_PIXELS * channels)
+            .zip(dst.chunks_mut(CHUNK_PIXELS * channels))
+        {
+            let working = &mut working[..src.len()];
+            self.executor.transform(src, working)?;
+            if self.quantize {
+                for (dst, &src) in dst.iter_mut().zip(working.iter()) {
+                    *dst = (src.max(0.).min(1.) * self.max_value + 0.5).as_();
+                }
+            } else {
+                for (dst, &src) in dst.iter_mut().zip(working.iter()) {
+                    *dst = src.as_();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ColorProfile {
+    /// Creates 8 bit transform split into linear light halves, see [LinearLightTransform].
+    pub fn create_linear_light_transform_8bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<LinearLightTransform<u8>, CmsError> {
+        self.create_linear_light_transform_impl(src_layout, dst_pr, dst_layout, options, 255., true)
+    }
+
+    /// Creates 16 bit transform split into linear light halves, see [LinearLightTransform].
+    pub fn create_linear_light_transform_16bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<LinearLightTransform<u16>, CmsError> {
+        self.create_linear_light_transform_impl(
+            src_layout, dst_pr, dst_layout, options, 65535., true,
+        )
+    }
+
+    /// Creates f32 transform split into linear light halves, see [LinearLightTransform].
+    pub fn create_linear_light_transform_f32(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<LinearLightTransform<f32>, CmsError> {
+        self.create_linear_light_transform_impl(src_layout, dst_pr, dst_layout, options, 1., false)
+    }
+
+    fn create_linear_light_transform_impl<T>(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+        max_value: f32,
+        quantize: bool,
+    ) -> Result<LinearLightTransform<T>, CmsError> {
+        // Linear light of destination is defined only by its colorants
+        if dst_pr.color_space != DataColorSpace::Rgb
+            || !dst_pr.is_matrix_shaper()
+            || dst_pr.has_pcs_to_device_lut()
+        {
+            return Err(CmsError::UnsupportedProfileConnection);
+        }
+        if dst_layout != Layout::Rgb && dst_layout != Layout::Rgba {
+            return Err(CmsError::InvalidLayout);
+        }
+        let mut linear = dst_pr.clone();
+        linear.red_trc = Some(ToneReprCurve::Lut(vec![]));
+        linear.green_trc = Some(ToneReprCurve::Lut(vec![]));
+        linear.blue_trc = Some(ToneReprCurve::Lut(vec![]));
+        linear.cicp = None;
+        linear.lut_a_to_b_perceptual = None;
+        linear.lut_a_to_b_colorimetric = None;
+        linear.lut_a_to_b_saturation = None;
+
+        let linearizer = self.create_transform_f32(src_layout, &linear, dst_layout, options)?;
+        let encoder = linear.create_transform_f32(dst_layout, dst_pr, dst_layout, options)?;
+        Ok(LinearLightTransform {
+            linearizer: Linearizer {
+                executor: linearizer,
+                src_layout,
+                dst_layout,
+                max_value,
+                _phantom: PhantomData,
+            },
+            encoder: LinearEncoder {
+                executor: encoder,
+                layout: dst_layout,
+                max_value,
+                quantize,
+                _phantom: PhantomData,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_light_halves() {
+        let srgb = ColorProfile::new_srgb();
+        let p3 = ColorProfile::new_display_p3();
+        let transform = srgb
+            .create_linear_light_transform_8bit(
+                Layout::Rgb,
+                &p3,
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let src = (0..256 * 3)
+            .map(|x| ((x * 7) % 256) as u8)
+            .collect::<Vec<u8>>();
+        let mut linear = vec![0f32; src.len()];
+        transform.linearizer().linearize(&src, &mut linear).unwrap();
+        let mut dst = vec![0u8; src.len()];
+        transform.encoder().encode(&linear, &mut dst).unwrap();
+
+        let mut reference = vec![0u8; src.len()];
+        srgb.create_transform_8bit(Layout::Rgb, &p3, Layout::Rgb, TransformOptions::default())
+            .unwrap()
+            .transform(&src, &mut reference)
+            .unwrap();
+        for (&a, &b) in dst.iter().zip(reference.iter()) {
+            assert!((a as i32 - b as i32).abs() <= 1, "{a} {b}");
+        }
+
+        // Middle gray in linear light, averaging black and white
+        let mut gray = [0u8; 3];
+        transform
+            .encoder()
+            .encode(&[0.5f32, 0.5, 0.5], &mut gray)
+            .unwrap();
+        assert!((gray[0] as i32 - 188).abs() <= 1);
+    }
+}
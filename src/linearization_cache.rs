@@ -0,0 +1,142 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::trace::trace_span;
+use crate::trc::ToneReprCurve;
+use crate::{CmsError, ColorProfile, PointeeSizeExpressible, TransferCharacteristics};
+use std::sync::{Arc, Mutex, Weak};
+
+#[derive(Debug, PartialEq)]
+enum LinearizationSource {
+    Cicp(TransferCharacteristics),
+    Curve(ToneReprCurve),
+}
+
+#[derive(Debug, PartialEq)]
+struct LinearizationKey {
+    source: LinearizationSource,
+    table_size: usize,
+    bit_depth: usize,
+    finite: bool,
+    not_finite_table_size: usize,
+}
+
+/// Linearization tables currently owned by live transforms.
+///
+/// Tables are keyed by curve content rather than by profile address, so the same curve
+/// coming from profile clones or from different profiles is shared as well.
+/// Entries are weak, table is freed with the last transform that holds it.
+static LINEARIZATION_CACHE: Mutex<Vec<(LinearizationKey, Weak<[f32]>)>> = Mutex::new(Vec::new());
+
+impl ColorProfile {
+    /// Returns linearization table for the curve, shared with other live transforms
+    /// that were built from the same curve with the same depth.
+    pub(crate) fn shared_linearize_table<
+        T: PointeeSizeExpressible,
+        const N: usize,
+        const BIT_DEPTH: usize,
+    >(
+        &self,
+        trc: &Option<ToneReprCurve>,
+        use_cicp: bool,
+    ) -> Result<Arc<[f32; N]>, CmsError> {
+        let _span = trace_span!("moxcms::build_linearization_table", bit_depth = BIT_DEPTH);
+        let cicp = self
+            .cicp
+            .as_ref()
+            .map(|c| c.transfer_characteristics)
+            .filter(|tc| use_cicp && tc.has_transfer_curve());
+        let source = match cicp {
+            Some(tc) => LinearizationSource::Cicp(tc),
+            None => LinearizationSource::Curve(
+                trc.as_ref().ok_or(CmsError::BuildTransferFunction)?.clone(),
+            ),
+        };
+        let key = LinearizationKey {
+            source,
+            table_size: N,
+            bit_depth: BIT_DEPTH,
+            finite: T::FINITE,
+            not_finite_table_size: T::NOT_FINITE_LINEAR_TABLE_SIZE,
+        };
+
+        let cached = LINEARIZATION_CACHE
+            .lock()
+            .ok()
+            .and_then(|cache| {
+                cache
+                    .iter()
+                    .filter(|(k, _)| *k == key)
+                    .find_map(|(_, table)| table.upgrade())
+            })
+            .and_then(|table| Arc::<[f32; N]>::try_from(table).ok());
+        if let Some(table) = cached {
+            return Ok(table);
+        }
+
+        let table: Arc<[f32; N]> = Arc::from(match &key.source {
+            LinearizationSource::Cicp(tc) => tc.make_linear_table::<T, N, BIT_DEPTH>(),
+            LinearizationSource::Curve(trc) => trc
+                .build_linearize_table::<T, N, BIT_DEPTH>()
+                .ok_or(CmsError::BuildTransferFunction)?,
+        });
+
+        if let Ok(mut cache) = LINEARIZATION_CACHE.lock() {
+            cache.retain(|(_, table)| table.strong_count() > 0);
+            let shared: Arc<[f32]> = table.clone();
+            cache.push((key, Arc::downgrade(&shared)));
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ColorProfile;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_shared_linearize_table() {
+        let srgb = ColorProfile::new_srgb();
+        let first = srgb
+            .shared_linearize_table::<u8, 256, 8>(&srgb.red_trc, false)
+            .unwrap();
+        let second = srgb
+            .clone()
+            .shared_linearize_table::<u8, 256, 8>(&srgb.green_trc, false)
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        let built = srgb.build_r_linearize_table::<u8, 256, 8>(false).unwrap();
+        assert_eq!(first.as_slice(), built.as_slice());
+
+        let deeper = srgb
+            .shared_linearize_table::<u16, 65536, 16>(&srgb.red_trc, false)
+            .unwrap();
+        assert_eq!(deeper.len(), 65536);
+    }
+}
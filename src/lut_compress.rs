@@ -0,0 +1,284 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Shrinking of CLUT grids within an error bound, so stored device links fit embedded targets.
+use crate::{
+    CmsError, Cube, Hypercube, InterpolationMethod, LutDataType, LutMultidimensionalType, LutStore,
+    LutWarehouse, MalformedSize,
+};
+
+/// Reduces CLUT grid of a LUT while it still reproduces the original grid.
+///
+/// Only CLUTs with 3 or 4 inputs and 3 or 4 outputs are compressed, curves and matrices
+/// are kept as they are. Other LUTs are returned unchanged.
+pub trait CompressLut: Sized {
+    /// Returns copy with the smallest grid whose linear interpolation stays within
+    /// `max_error` of every original grid node, in normalized \[0; 1\] units.
+    ///
+    /// Compressed values keep precision of the original table, and the original LUT is
+    /// returned when no smaller grid meets the bound.
+    fn compress(&self, max_error: f32) -> Result<Self, CmsError>;
+}
+
+impl CompressLut for LutWarehouse {
+    fn compress(&self, max_error: f32) -> Result<Self, CmsError> {
+        Ok(match self {
+            LutWarehouse::Lut(lut) => LutWarehouse::Lut(lut.compress(max_error)?),
+            LutWarehouse::Multidimensional(mab) => {
+                LutWarehouse::Multidimensional(mab.compress(max_error)?)
+            }
+            // Floating point tables are kept as they are
+            LutWarehouse::MultiProcess(mpe) => LutWarehouse::MultiProcess(mpe.clone()),
+        })
+    }
+}
+
+impl CompressLut for LutDataType {
+    fn compress(&self, max_error: f32) -> Result<Self, CmsError> {
+        let grid = [self.num_clut_grid_points; 4];
+        let compressed = compress_clut(
+            &self.clut_table,
+            self.num_input_channels as usize,
+            self.num_output_channels as usize,
+            grid,
+            max_error,
+        )?;
+        Ok(match compressed {
+            Some((clut_table, grid)) => LutDataType {
+                num_clut_grid_points: grid[0],
+                clut_table,
+                ..self.clone()
+            },
+            None => self.clone(),
+        })
+    }
+}
+
+impl CompressLut for LutMultidimensionalType {
+    fn compress(&self, max_error: f32) -> Result<Self, CmsError> {
+        let Some(clut) = self.clut.as_ref() else {
+            return Ok(self.clone());
+        };
+        let grid = [
+            self.grid_points[0],
+            self.grid_points[1],
+            self.grid_points[2],
+            self.grid_points[3],
+        ];
+        let compressed = compress_clut(
+            clut,
+            self.num_input_channels as usize,
+            self.num_output_channels as usize,
+            grid,
+            max_error,
+        )?;
+        Ok(match compressed {
+            Some((clut, grid)) => {
+                let mut grid_points = self.grid_points;
+                grid_points[..4].copy_from_slice(&grid);
+                LutMultidimensionalType {
+                    grid_points,
+                    clut: Some(clut),
+                    ..self.clone()
+                }
+            }
+            None => self.clone(),
+        })
+    }
+}
+
+fn store_to_f32(store: &LutStore) -> Vec<f32> {
+    match store {
+        LutStore::Store8(v) => v.iter().map(|&x| x as f32 * (1. / 255.)).collect(),
+        LutStore::Store16(v) => v.iter().map(|&x| x as f32 * (1. / 65535.)).collect(),
+    }
+}
+
+fn quantize_like(store: &LutStore, values: &[f32]) -> LutStore {
+    match store {
+        LutStore::Store8(_) => LutStore::Store8(
+            values
+                .iter()
+                .map(|&x| (x * 255.).round().clamp(0., 255.) as u8)
+                .collect(),
+        ),
+        LutStore::Store16(_) => LutStore::Store16(
+            values
+                .iter()
+                .map(|&x| (x * 65535.).round().clamp(0., 65535.) as u16)
+                .collect(),
+        ),
+    }
+}
+
+/// Samples CLUT at `point`, only first `inputs` coordinates and `outputs` values are used
+fn sample_clut(
+    clut: &[f32],
+    inputs: usize,
+    outputs: usize,
+    grid: [u8; 4],
+    point: [f32; 4],
+) -> [f32; 4] {
+    let method = InterpolationMethod::Linear;
+    match (inputs, outputs) {
+        (3, 3) => {
+            let v = Cube::new_cube(clut, [grid[0], grid[1], grid[2]])
+                .sample_vec3([point[0], point[1], point[2]], method)
+                .v;
+            [v[0], v[1], v[2], 0.]
+        }
+        (3, 4) => {
+            Cube::new_cube(clut, [grid[0], grid[1], grid[2]])
+                .sample_vec4([point[0], point[1], point[2]], method)
+                .v
+        }
+        (4, 3) => {
+            let v = Hypercube::new_hypercube(clut, grid)
+                .sample_vec3(point, method)
+                .v;
+            [v[0], v[1], v[2], 0.]
+        }
+        _ => {
+            Hypercube::new_hypercube(clut, grid)
+                .sample_vec4(point, method)
+                .v
+        }
+    }
+}
+
+/// Calls `f` for every node of the grid in CLUT order, with node coordinates in \[0; 1\]
+fn for_each_node(grid: [u8; 4], inputs: usize, mut f: impl FnMut([f32; 4])) {
+    let dims: [usize; 4] = std::array::from_fn(|i| if i < inputs { grid[i] as usize } else { 1 });
+    let scale = dims.map(|x| 1. / (x.max(2) - 1) as f32);
+    for x in 0..dims[0] {
+        for y in 0..dims[1] {
+            for z in 0..dims[2] {
+                for w in 0..dims[3] {
+                    f([
+                        x as f32 * scale[0],
+                        y as f32 * scale[1],
+                        z as f32 * scale[2],
+                        w as f32 * scale[3],
+                    ]);
+                }
+            }
+        }
+    }
+}
+
+fn compress_clut(
+    store: &LutStore,
+    inputs: usize,
+    outputs: usize,
+    grid: [u8; 4],
+    max_error: f32,
+) -> Result<Option<(LutStore, [u8; 4])>, CmsError> {
+    if !matches!(inputs, 3 | 4) || !matches!(outputs, 3 | 4) {
+        return Ok(None);
+    }
+    let largest = grid[..inputs].iter().copied().max().unwrap_or(0);
+    if grid[..inputs].contains(&0) || largest <= 2 {
+        return Ok(None);
+    }
+    let original = store_to_f32(store);
+    let nodes: usize = grid[..inputs].iter().map(|&x| x as usize).product();
+    if original.len() < nodes * outputs {
+        return Err(CmsError::MalformedClut(MalformedSize {
+            size: original.len(),
+            expected: nodes * outputs,
+        }));
+    }
+
+    for size in 2..largest {
+        let mut candidate_grid = grid;
+        for dim in candidate_grid[..inputs].iter_mut() {
+            *dim = (*dim).min(size);
+        }
+        let mut resampled = Vec::new();
+        for_each_node(candidate_grid, inputs, |point| {
+            let v = sample_clut(&original, inputs, outputs, grid, point);
+            resampled.extend_from_slice(&v[..outputs]);
+        });
+        let candidate = quantize_like(store, &resampled);
+        let quantized = store_to_f32(&candidate);
+
+        let mut error = 0f32;
+        let mut node = 0usize;
+        for_each_node(grid, inputs, |point| {
+            let v = sample_clut(&quantized, inputs, outputs, candidate_grid, point);
+            let reference = &original[node * outputs..(node + 1) * outputs];
+            for (a, b) in v[..outputs].iter().zip(reference) {
+                error = error.max((a - b).abs());
+            }
+            node += 1;
+        });
+        if error <= max_error {
+            return Ok(Some((candidate, candidate_grid)));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColorProfile, LutType, Matrix3d};
+
+    #[test]
+    fn test_compress_lut() {
+        let identity = Cube::from_fn([17, 17, 17], |x, y, z| [x, y, z]);
+        let lut = LutDataType {
+            num_input_channels: 3,
+            num_output_channels: 3,
+            num_clut_grid_points: 17,
+            matrix: Matrix3d::IDENTITY,
+            num_input_table_entries: 2,
+            num_output_table_entries: 2,
+            input_table: LutStore::Store16(vec![0, 65535, 0, 65535, 0, 65535].into()),
+            clut_table: quantize_like(&LutStore::Store16(vec![].into()), &identity),
+            output_table: LutStore::Store16(vec![0, 65535, 0, 65535, 0, 65535].into()),
+            lut_type: LutType::Lut16,
+        };
+        let compressed = lut.compress(1e-4).unwrap();
+        assert_eq!(compressed.num_clut_grid_points, 2);
+        assert_eq!(store_to_f32(&compressed.clut_table).len(), 8 * 3);
+
+        let cmyk =
+            ColorProfile::new_from_slice(include_bytes!("../assets/us_swop_coated.icc")).unwrap();
+        let a2b0 = cmyk.lut_a_to_b_perceptual.as_ref().unwrap();
+        assert_eq!(&a2b0.compress(0.).unwrap(), a2b0);
+        let LutWarehouse::Lut(shrunk) = a2b0.compress(0.02).unwrap() else {
+            panic!("LUT type must be kept");
+        };
+        let LutWarehouse::Lut(original) = a2b0 else {
+            unreachable!()
+        };
+        assert!(shrunk.num_clut_grid_points < original.num_clut_grid_points);
+        assert_eq!(shrunk.input_table, original.input_table);
+    }
+}
@@ -28,6 +28,16 @@
  */
 use crate::LutWarehouse;
 
+/// CLUT properties used to pick interpolation automatically
+#[cfg(feature = "options")]
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ClutSmoothness {
+    /// Smallest grid size among input channels
+    pub(crate) grid_size: u8,
+    /// Mean absolute second derivative estimated from grid second differences
+    pub(crate) curvature: f32,
+}
+
 impl LutWarehouse {
     /// Method tests if mathematical fusion on LUT table is allowed.
     /// If it's not, full brute-force pass in [Katana] is required.
@@ -101,6 +111,74 @@ impl LutWarehouse {
 
                 false
             }
+            // Floating point elements are always evaluated in full
+            LutWarehouse::MultiProcess(_) => true,
+        }
+    }
+}
+
+impl LutWarehouse {
+    /// Estimates CLUT grid density and smoothness, `None` if there is no CLUT.
+    #[cfg(feature = "options")]
+    pub(crate) fn clut_smoothness(&self) -> Option<ClutSmoothness> {
+        let (clut, grid, inputs, outputs) = match self {
+            LutWarehouse::Lut(lut) => (
+                &lut.clut_table,
+                [lut.num_clut_grid_points; 16],
+                lut.num_input_channels as usize,
+                lut.num_output_channels as usize,
+            ),
+            LutWarehouse::Multidimensional(mab) => (
+                mab.clut.as_ref()?,
+                mab.grid_points,
+                mab.num_input_channels as usize,
+                mab.num_output_channels as usize,
+            ),
+            LutWarehouse::MultiProcess(_) => return None,
+        };
+        if inputs == 0 || inputs > 16 || outputs == 0 {
+            return None;
+        }
+        let clut = clut.to_clut_f32();
+        let grid_size = grid.iter().take(inputs).copied().min()?;
+        if grid_size < 3 {
+            return Some(ClutSmoothness {
+                grid_size,
+                curvature: 0.,
+            });
+        }
+        let mut strides = [outputs; 16];
+        for axis in (0..inputs.saturating_sub(1)).rev() {
+            strides[axis] = strides[axis + 1] * grid[axis + 1] as usize;
+        }
+        let expected = strides[0] * grid[0] as usize;
+        if clut.len() != expected {
+            return None;
+        }
+
+        let mut sum = 0f64;
+        let mut count = 0usize;
+        for axis in 0..inputs {
+            let stride = strides[axis];
+            let axis_grid = grid[axis] as usize;
+            let scale = ((axis_grid - 1) * (axis_grid - 1)) as f32;
+            for (i, &v) in clut.iter().enumerate() {
+                let position = (i / stride) % axis_grid;
+                if position == 0 || position == axis_grid - 1 {
+                    continue;
+                }
+                let d2 = clut[i - stride] - 2. * v + clut[i + stride];
+                sum += (d2.abs() * scale) as f64;
+                count += 1;
+            }
         }
+        Some(ClutSmoothness {
+            grid_size,
+            curvature: if count == 0 {
+                0.
+            } else {
+                (sum / count as f64) as f32
+            },
+        })
     }
 }
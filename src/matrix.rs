@@ -32,6 +32,13 @@ use crate::reader::s15_fixed16_number_to_double;
 use num_traits::{AsPrimitive, MulAdd};
 use std::ops::{Add, Div, Mul, Neg, Shr, Sub};
 
+/// Vector math helper, e.g. for chromaticity plane
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Vector2<T> {
+    pub v: [T; 2],
+}
+
 /// Vector math helper
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Default)]
@@ -46,6 +53,9 @@ pub struct Vector4<T> {
     pub v: [T; 4],
 }
 
+pub type Vector2f = Vector2<f32>;
+pub type Vector2d = Vector2<f64>;
+
 pub type Vector4f = Vector4<f32>;
 pub type Vector4d = Vector4<f64>;
 pub type Vector4i = Vector4<i32>;
@@ -55,6 +65,128 @@ pub type Vector3d = Vector3<f64>;
 pub type Vector3i = Vector3<i32>;
 pub type Vector3u = Vector3<u32>;
 
+impl<T> Add<Vector2<T>> for Vector2<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    type Output = Vector2<T>;
+
+    #[inline(always)]
+    fn add(self, rhs: Vector2<T>) -> Self::Output {
+        Self {
+            v: [self.v[0] + rhs.v[0], self.v[1] + rhs.v[1]],
+        }
+    }
+}
+
+impl<T> Sub<Vector2<T>> for Vector2<T>
+where
+    T: Sub<Output = T> + Copy,
+{
+    type Output = Vector2<T>;
+
+    #[inline(always)]
+    fn sub(self, rhs: Vector2<T>) -> Self::Output {
+        Self {
+            v: [self.v[0] - rhs.v[0], self.v[1] - rhs.v[1]],
+        }
+    }
+}
+
+impl<T> Mul<T> for Vector2<T>
+where
+    T: Mul<Output = T> + Copy,
+{
+    type Output = Vector2<T>;
+
+    #[inline(always)]
+    fn mul(self, rhs: T) -> Self::Output {
+        Self {
+            v: [self.v[0] * rhs, self.v[1] * rhs],
+        }
+    }
+}
+
+impl<T> Vector2<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Copy,
+{
+    /// Linear interpolation, `t == 0` gives `self`, `t == 1` gives `other`
+    #[inline(always)]
+    pub fn lerp(self, other: Vector2<T>, t: T) -> Vector2<T> {
+        self + (other - self) * t
+    }
+}
+
+impl Vector2f {
+    #[inline(always)]
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { v: [x, y] }
+    }
+
+    #[inline(always)]
+    pub const fn dot(self, other: Vector2f) -> f32 {
+        self.v[0] * other.v[0] + self.v[1] * other.v[1]
+    }
+
+    /// Z component of 3D cross product, positive when `other` is counter-clockwise from `self`
+    #[inline(always)]
+    pub const fn cross(self, other: Vector2f) -> f32 {
+        self.v[0] * other.v[1] - self.v[1] * other.v[0]
+    }
+
+    #[inline(always)]
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Intersects ray `origin + t * direction` with segment `a`-`b`.
+    ///
+    /// Returns ray parameter `t >= 0` or `None` if they don't intersect or are parallel.
+    pub fn ray_segment_intersection(
+        origin: Vector2f,
+        direction: Vector2f,
+        a: Vector2f,
+        b: Vector2f,
+    ) -> Option<f32> {
+        let edge = b - a;
+        let denominator = direction.cross(edge);
+        if denominator.abs() < f32::EPSILON {
+            return None;
+        }
+        let to_a = a - origin;
+        let t = to_a.cross(edge) / denominator;
+        let u = to_a.cross(direction) / denominator;
+        if t >= 0. && (0. ..=1.).contains(&u) {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    /// Intersects segments `a0`-`a1` and `b0`-`b1`, returns intersection point if any
+    pub fn segment_intersection(
+        a0: Vector2f,
+        a1: Vector2f,
+        b0: Vector2f,
+        b1: Vector2f,
+    ) -> Option<Vector2f> {
+        let t = Vector2f::ray_segment_intersection(a0, a1 - a0, b0, b1)?;
+        if t <= 1. { Some(a0.lerp(a1, t)) } else { None }
+    }
+}
+
+impl<T> Vector3<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Copy,
+{
+    /// Linear interpolation, `t == 0` gives `self`, `t == 1` gives `other`
+    #[inline(always)]
+    pub fn lerp(self, other: Vector3<T>, t: T) -> Vector3<T> {
+        self + (other - self) * t
+    }
+}
+
 impl<T> PartialEq<Self> for Vector3<T>
 where
     T: AsPrimitive<f32>,
@@ -775,6 +907,29 @@ impl Matrix3d {
         }
     }
 
+    #[inline]
+    pub const fn mul_scalar(&self, scalar: f64) -> Matrix3d {
+        Matrix3d {
+            v: [
+                [
+                    self.v[0][0] * scalar,
+                    self.v[0][1] * scalar,
+                    self.v[0][2] * scalar,
+                ],
+                [
+                    self.v[1][0] * scalar,
+                    self.v[1][1] * scalar,
+                    self.v[1][2] * scalar,
+                ],
+                [
+                    self.v[2][0] * scalar,
+                    self.v[2][1] * scalar,
+                    self.v[2][2] * scalar,
+                ],
+            ],
+        }
+    }
+
     pub const IDENTITY: Matrix3d = Matrix3d {
         v: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
     };
@@ -1270,3 +1425,35 @@ impl Xyzd {
 
 define_xyz!(Xyz, f32, Matrix3f);
 define_xyz!(Xyzd, f64, Matrix3d);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector2_intersections() {
+        let a = Vector2f::new(0., 0.);
+        let b = Vector2f::new(2., 2.);
+        assert_eq!(a.lerp(b, 0.25), Vector2f::new(0.5, 0.5));
+        let hit =
+            Vector2f::segment_intersection(a, b, Vector2f::new(0., 2.), Vector2f::new(2., 0.));
+        assert_eq!(hit, Some(Vector2f::new(1., 1.)));
+        let t = Vector2f::ray_segment_intersection(
+            a,
+            Vector2f::new(1., 0.),
+            Vector2f::new(3., -1.),
+            Vector2f::new(3., 1.),
+        );
+        assert_eq!(t, Some(3.));
+        assert_eq!(
+            Vector2f::ray_segment_intersection(
+                a,
+                Vector2f::new(-1., 0.),
+                Vector2f::new(3., -1.),
+                Vector2f::new(3., 1.),
+            ),
+            None
+        );
+        assert!((Vector2f::new(3., 4.).length() - 5.).abs() < 1e-6);
+    }
+}
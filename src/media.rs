@@ -0,0 +1,175 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{Chromaticity, CmsError, ColorProfile, DataColorSpace, RenderingIntent, Xyz, Xyzd};
+
+/// Measured media white and black of a profile, in absolute XYZ where PCS white has Y = 1.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MediaPoints {
+    pub white: Xyzd,
+    pub black: Xyzd,
+    /// True when black point was estimated from LUTs or curves rather than taken from `bkpt` tag
+    pub black_estimated: bool,
+}
+
+impl MediaPoints {
+    /// Ratio of white to black luminance, infinite when black has no luminance
+    pub fn contrast_ratio(&self) -> f64 {
+        if self.black.y <= 0. {
+            return f64::INFINITY;
+        }
+        self.white.y / self.black.y
+    }
+
+    /// Dynamic range in stops, i.e. `log2` of [MediaPoints::contrast_ratio]
+    pub fn dynamic_range_stops(&self) -> f64 {
+        self.contrast_ratio().log2()
+    }
+}
+
+impl ColorProfile {
+    /// Media white point from `wtpt` tag, or PCS D50 white when tag is absent
+    pub fn media_white(&self) -> Xyzd {
+        self.media_white_point
+            .unwrap_or_else(|| Chromaticity::D50.to_xyzd())
+    }
+
+    /// Media black point from `bkpt` tag, or estimated from the profile.
+    ///
+    /// Additive spaces evaluate device zero. Subtractive spaces map PCS black through
+    /// perceptual `B2A0` to find the darkest reproducible ink combination, falling back
+    /// to full ink when profile has no `B2A0`. Black is measured through colorimetric
+    /// `A2B1` when present, since perceptual tables usually map it to PCS zero.
+    /// Estimated black is scaled into absolute XYZ by [ColorProfile::media_white].
+    pub fn media_black(&self) -> Result<Xyzd, CmsError> {
+        if let Some(black) = self.black_point {
+            return Ok(black);
+        }
+        let relative = self.estimate_relative_black()?;
+        let white = self.media_white();
+        let d50 = Chromaticity::D50.to_xyzd();
+        Ok(Xyzd::new(
+            relative.x as f64 * white.x / d50.x,
+            relative.y as f64 * white.y / d50.y,
+            relative.z as f64 * white.z / d50.z,
+        ))
+    }
+
+    /// Media white, black and derived contrast ratio, see [ColorProfile::media_black]
+    pub fn media_points(&self) -> Result<MediaPoints, CmsError> {
+        Ok(MediaPoints {
+            white: self.media_white(),
+            black: self.media_black()?,
+            black_estimated: self.black_point.is_none(),
+        })
+    }
+
+    /// Luminance of media black relative to media white, used by black point compensation
+    pub(crate) fn relative_black_luminance(&self) -> Result<f64, CmsError> {
+        if let Some(black) = self.black_point {
+            let white = self.media_white();
+            return Ok(black.y * Chromaticity::D50.to_xyzd().y / white.y);
+        }
+        Ok(self.estimate_relative_black()?.y as f64)
+    }
+
+    fn estimate_relative_black(&self) -> Result<Xyz, CmsError> {
+        let measure_intent = if self
+            .get_device_to_pcs(RenderingIntent::RelativeColorimetric)
+            .is_some()
+            || !self.has_device_to_pcs_lut()
+        {
+            RenderingIntent::RelativeColorimetric
+        } else {
+            RenderingIntent::Perceptual
+        };
+        let channels = self.color_space.channels();
+        match self.color_space {
+            DataColorSpace::Rgb | DataColorSpace::Gray => {
+                self.device_to_pcs(&vec![0.; channels], measure_intent)
+            }
+            DataColorSpace::Cmyk | DataColorSpace::Cmy => {
+                let mut ink = vec![1f32; channels];
+                if self
+                    .get_pcs_to_device(RenderingIntent::Perceptual)
+                    .is_some()
+                {
+                    self.pcs_to_device(
+                        Xyz::new(0., 0., 0.),
+                        RenderingIntent::Perceptual,
+                        &mut ink,
+                    )?;
+                }
+                self.device_to_pcs(&ink, measure_intent)
+            }
+            _ => Err(CmsError::UnsupportedProfileConnection),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_points() {
+        let srgb = ColorProfile::new_srgb();
+        let points = srgb.media_points().unwrap();
+        assert!(points.black_estimated);
+        assert!(points.black.y.abs() < 1e-6, "{points:?}");
+        assert_eq!(points.contrast_ratio(), f64::INFINITY);
+
+        let tagged = ColorProfile {
+            black_point: Some(Xyzd::new(0.0048, 0.005, 0.0041)),
+            ..srgb.clone()
+        };
+        let points = tagged.media_points().unwrap();
+        assert!(!points.black_estimated);
+        assert!((points.contrast_ratio() - points.white.y / 0.005).abs() < 1e-9);
+
+        if let Ok(us_swop_coated) = std::fs::read("./assets/us_swop_coated.icc") {
+            let cmyk = ColorProfile::new_from_slice(&us_swop_coated).unwrap();
+            let points = cmyk.media_points().unwrap();
+            // Coated paper with ink reaches roughly 1:100 - 1:1000
+            let ratio = points.contrast_ratio();
+            assert!((20. ..5000.).contains(&ratio), "{points:?} {ratio}");
+            let estimated = ColorProfile {
+                black_point: None,
+                ..cmyk
+            }
+            .media_points()
+            .unwrap();
+            assert!(estimated.black_estimated);
+            let estimated_ratio = estimated.contrast_ratio();
+            assert!(
+                (ratio / estimated_ratio - 1.).abs() < 0.5,
+                "{estimated:?} {estimated_ratio}"
+            );
+        }
+    }
+}
@@ -0,0 +1,388 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{CmsError, DataColorSpace, SegmentedCurve, Stage};
+use std::sync::Arc;
+
+/// Maximum channels count handled by multi process elements
+pub(crate) const MPE_MAX_CHANNELS: usize = 15;
+
+/// Processing element of `multiProcessElementsType` (`mpet`), all values are 32-bit float
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessElement {
+    /// `cvst`, one segmented curve per channel
+    CurveSet(Vec<SegmentedCurve>),
+    /// `matf`, `outputs` rows of `inputs` coefficients, followed by `outputs` offsets
+    Matrix {
+        inputs: u8,
+        outputs: u8,
+        matrix: Vec<f32>,
+        offsets: Vec<f32>,
+    },
+    /// `clut`, first input varies slowest, inputs are clamped to [0, 1]
+    Clut {
+        grid_points: [u8; 16],
+        inputs: u8,
+        outputs: u8,
+        table: Arc<[f32]>,
+    },
+}
+
+impl ProcessElement {
+    pub fn num_input_channels(&self) -> usize {
+        match self {
+            ProcessElement::CurveSet(curves) => curves.len(),
+            ProcessElement::Matrix { inputs, .. } => *inputs as usize,
+            ProcessElement::Clut { inputs, .. } => *inputs as usize,
+        }
+    }
+
+    pub fn num_output_channels(&self) -> usize {
+        match self {
+            ProcessElement::CurveSet(curves) => curves.len(),
+            ProcessElement::Matrix { outputs, .. } => *outputs as usize,
+            ProcessElement::Clut { outputs, .. } => *outputs as usize,
+        }
+    }
+
+    fn evaluate(&self, src: &[f32], dst: &mut [f32]) {
+        match self {
+            ProcessElement::CurveSet(curves) => {
+                for ((dst, &src), curve) in dst.iter_mut().zip(src.iter()).zip(curves.iter()) {
+                    *dst = curve.eval(src);
+                }
+            }
+            ProcessElement::Matrix {
+                inputs,
+                matrix,
+                offsets,
+                ..
+            } => {
+                for ((dst, row), &offset) in dst
+                    .iter_mut()
+                    .zip(matrix.chunks_exact(*inputs as usize))
+                    .zip(offsets.iter())
+                {
+                    let mut acc = offset;
+                    for (&c, &v) in row.iter().zip(src.iter()) {
+                        acc = c.mul_add(v, acc);
+                    }
+                    *dst = acc;
+                }
+            }
+            ProcessElement::Clut {
+                grid_points,
+                inputs,
+                outputs,
+                table,
+            } => {
+                let inputs = *inputs as usize;
+                let outputs = *outputs as usize;
+                let mut base = [0usize; MPE_MAX_CHANNELS];
+                let mut fraction = [0f32; MPE_MAX_CHANNELS];
+                let mut strides = [0usize; MPE_MAX_CHANNELS];
+                let mut stride = outputs;
+                for i in (0..inputs).rev() {
+                    let max_index = grid_points[i].max(1) as usize - 1;
+                    let x = src[i].max(0.).min(1.) * max_index as f32;
+                    let floor = (x as usize).min(max_index.saturating_sub(1));
+                    base[i] = floor;
+                    fraction[i] = if max_index == 0 { 0. } else { x - floor as f32 };
+                    strides[i] = stride;
+                    stride *= grid_points[i].max(1) as usize;
+                }
+                let dst = &mut dst[..outputs];
+                dst.fill(0.);
+                for corner in 0..1usize << inputs {
+                    let mut weight = 1f32;
+                    let mut offset = 0usize;
+                    for i in 0..inputs {
+                        if corner & (1 << i) != 0 {
+                            weight *= fraction[i];
+                            offset +=
+                                (base[i] + 1).min(grid_points[i].max(1) as usize - 1) * strides[i];
+                        } else {
+                            weight *= 1. - fraction[i];
+                            offset += base[i] * strides[i];
+                        }
+                    }
+                    if weight == 0. {
+                        continue;
+                    }
+                    for (dst, &v) in dst.iter_mut().zip(table[offset..offset + outputs].iter()) {
+                        *dst = weight.mul_add(v, *dst);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Multi process elements `mpet` transform, used by v4.3 `D2Bx`/`B2Dx` tags
+/// to connect in floating point.
+///
+/// Device values are expected in [0, 1], XYZ PCS is relative to D50 with `Y = 1`,
+/// Lab PCS uses plain `L* [0, 100]` and `a*`, `b*` `[-128, 127]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiProcessType {
+    pub num_input_channels: u8,
+    pub num_output_channels: u8,
+    pub elements: Vec<ProcessElement>,
+}
+
+impl MultiProcessType {
+    /// Checks that elements chain matches declared channels count
+    pub(crate) fn validate(&self) -> Result<(), CmsError> {
+        if self.num_input_channels == 0
+            || self.num_output_channels == 0
+            || self.num_input_channels as usize > MPE_MAX_CHANNELS
+            || self.num_output_channels as usize > MPE_MAX_CHANNELS
+        {
+            return Err(CmsError::IncorrectlyFormedLut(
+                "Multi process elements channels count is out of range".to_string(),
+            ));
+        }
+        let mut channels = self.num_input_channels as usize;
+        for element in self.elements.iter() {
+            if element.num_input_channels() != channels
+                || element.num_output_channels() > MPE_MAX_CHANNELS
+            {
+                return Err(CmsError::IncorrectlyFormedLut(
+                    "Multi process element channels do not match the chain".to_string(),
+                ));
+            }
+            channels = element.num_output_channels();
+        }
+        if channels != self.num_output_channels as usize {
+            return Err(CmsError::IncorrectlyFormedLut(
+                "Multi process elements output channels mismatch".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Evaluates one pixel, `src` must hold at least input channels count values,
+    /// `dst` at least output channels count values
+    pub fn evaluate(&self, src: &[f32], dst: &mut [f32]) {
+        let mut current = [0f32; MPE_MAX_CHANNELS];
+        let mut next = [0f32; MPE_MAX_CHANNELS];
+        let inputs = self.num_input_channels as usize;
+        current[..inputs].copy_from_slice(&src[..inputs]);
+        for element in self.elements.iter() {
+            element.evaluate(&current, &mut next);
+            std::mem::swap(&mut current, &mut next);
+        }
+        let outputs = self.num_output_channels as usize;
+        dst[..outputs].copy_from_slice(&current[..outputs]);
+    }
+}
+
+/// Converts `mpet` PCS values into normalized LUT PCS encoding
+#[inline]
+pub(crate) fn mpe_to_pipeline_pcs(pcs: DataColorSpace, v: [f32; 3]) -> [f32; 3] {
+    if pcs == DataColorSpace::Lab {
+        [
+            v[0] * (1. / 100.),
+            (v[1] + 128.) * (1. / 255.),
+            (v[2] + 128.) * (1. / 255.),
+        ]
+    } else {
+        const XYZ_TO_PCS: f32 = 32768.0 / 65535.0;
+        [v[0] * XYZ_TO_PCS, v[1] * XYZ_TO_PCS, v[2] * XYZ_TO_PCS]
+    }
+}
+
+/// Converts normalized LUT PCS encoding into `mpet` PCS values
+#[inline]
+pub(crate) fn mpe_from_pipeline_pcs(pcs: DataColorSpace, v: [f32; 3]) -> [f32; 3] {
+    if pcs == DataColorSpace::Lab {
+        [
+            v[0] * 100.,
+            v[1].mul_add(255., -128.),
+            v[2].mul_add(255., -128.),
+        ]
+    } else {
+        const PCS_TO_XYZ: f32 = 65535.0 / 32768.0;
+        [v[0] * PCS_TO_XYZ, v[1] * PCS_TO_XYZ, v[2] * PCS_TO_XYZ]
+    }
+}
+
+/// Device to PCS stage, reads `channels` interleaved device values
+/// and writes PCS triplets in LUT PCS encoding
+pub(crate) struct MultiProcessDeviceToPcs {
+    mpe: MultiProcessType,
+    channels: usize,
+    pcs: DataColorSpace,
+}
+
+impl MultiProcessDeviceToPcs {
+    pub(crate) fn new(
+        mpe: &MultiProcessType,
+        channels: usize,
+        pcs: DataColorSpace,
+    ) -> Result<Self, CmsError> {
+        if mpe.num_output_channels != 3 || mpe.num_input_channels as usize > channels {
+            return Err(CmsError::UnsupportedChannelConfiguration);
+        }
+        Ok(Self {
+            mpe: mpe.clone(),
+            channels,
+            pcs,
+        })
+    }
+}
+
+impl Stage for MultiProcessDeviceToPcs {
+    fn transform(&self, src: &[f32], dst: &mut [f32]) -> Result<(), CmsError> {
+        if src.len() % self.channels != 0 || dst.len() % 3 != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / self.channels != dst.len() / 3 {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let mut pcs = [0f32; MPE_MAX_CHANNELS];
+        for (src, dst) in src.chunks_exact(self.channels).zip(dst.chunks_exact_mut(3)) {
+            self.mpe.evaluate(src, &mut pcs);
+            let v = mpe_to_pipeline_pcs(self.pcs, [pcs[0], pcs[1], pcs[2]]);
+            dst.copy_from_slice(&v);
+        }
+        Ok(())
+    }
+}
+
+/// PCS to device stage, reads PCS triplets in LUT PCS encoding
+/// and writes `channels` interleaved device values, extra channels are filled with 1
+pub(crate) struct MultiProcessPcsToDevice {
+    mpe: MultiProcessType,
+    channels: usize,
+    pcs: DataColorSpace,
+}
+
+impl MultiProcessPcsToDevice {
+    pub(crate) fn new(
+        mpe: &MultiProcessType,
+        channels: usize,
+        pcs: DataColorSpace,
+    ) -> Result<Self, CmsError> {
+        if mpe.num_input_channels != 3 || mpe.num_output_channels as usize > channels {
+            return Err(CmsError::UnsupportedChannelConfiguration);
+        }
+        Ok(Self {
+            mpe: mpe.clone(),
+            channels,
+            pcs,
+        })
+    }
+}
+
+impl Stage for MultiProcessPcsToDevice {
+    fn transform(&self, src: &[f32], dst: &mut [f32]) -> Result<(), CmsError> {
+        if src.len() % 3 != 0 || dst.len() % self.channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / 3 != dst.len() / self.channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let outputs = self.mpe.num_output_channels as usize;
+        let mut device = [0f32; MPE_MAX_CHANNELS];
+        for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact_mut(self.channels)) {
+            let pcs = mpe_from_pipeline_pcs(self.pcs, [src[0], src[1], src[2]]);
+            self.mpe.evaluate(&pcs, &mut device);
+            for (i, dst) in dst.iter_mut().enumerate() {
+                *dst = if i < outputs { device[i] } else { 1. };
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CurveSegment, SegmentFormula};
+
+    fn gamma_curve(gamma: f32) -> SegmentedCurve {
+        let formula = SegmentFormula::Gamma {
+            gamma,
+            a: 1.,
+            b: 0.,
+            c: 0.,
+        };
+        SegmentedCurve::new(
+            vec![0., 1.],
+            vec![
+                CurveSegment::Formula(SegmentFormula::Gamma {
+                    gamma: 1.,
+                    a: 0.,
+                    b: 0.,
+                    c: 0.,
+                }),
+                CurveSegment::Formula(formula),
+                CurveSegment::Formula(formula),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mpe_evaluate_chain() {
+        let mut table = vec![0f32; 2 * 2 * 2 * 3];
+        for (i, v) in table.chunks_exact_mut(3).enumerate() {
+            v[0] = ((i >> 2) & 1) as f32;
+            v[1] = ((i >> 1) & 1) as f32;
+            v[2] = (i & 1) as f32;
+        }
+        let mut grid_points = [0u8; 16];
+        grid_points[..3].fill(2);
+        let mpe = MultiProcessType {
+            num_input_channels: 3,
+            num_output_channels: 3,
+            elements: vec![
+                ProcessElement::CurveSet(vec![gamma_curve(2.); 3]),
+                ProcessElement::Clut {
+                    grid_points,
+                    inputs: 3,
+                    outputs: 3,
+                    table: table.into(),
+                },
+                ProcessElement::Matrix {
+                    inputs: 3,
+                    outputs: 3,
+                    matrix: vec![2., 0., 0., 0., 1., 0., 0., 0., 1.],
+                    offsets: vec![0., 0.5, 0.],
+                },
+            ],
+        };
+        mpe.validate().unwrap();
+        let mut dst = [0f32; 3];
+        mpe.evaluate(&[0.5, 1., 0.], &mut dst);
+        assert!((dst[0] - 0.5).abs() < 1e-5, "{dst:?}");
+        assert!((dst[1] - 1.5).abs() < 1e-5, "{dst:?}");
+        assert!(dst[2].abs() < 1e-5, "{dst:?}");
+    }
+}
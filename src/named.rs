@@ -0,0 +1,274 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::lab::{lab_to_xyz, xyz_to_lab};
+use crate::transform::PointeeSizeExpressible;
+use crate::{CmsError, ColorProfile, DataColorSpace, Layout, TransformExecutor, TransformOptions};
+use num_traits::AsPrimitive;
+
+/// Spot color of [NamedColorList]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedColor {
+    /// Root name, without prefix and suffix of the list
+    pub name: String,
+    /// PCS coordinates, XYZ is u1Fixed15 and Lab uses legacy 16-bit encoding
+    pub pcs: [u16; 3],
+    /// Device coordinates, empty when list has none
+    pub device: Vec<u16>,
+}
+
+/// Named colors of `ncl2` tag, e.g. Pantone-like spot colors.
+///
+/// Colors are addressed by index: a transform from [crate::ProfileClass::Named] profile takes
+/// one channel layout where each sample is an index into this list.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NamedColorList {
+    pub vendor_flags: u32,
+    pub prefix: String,
+    pub suffix: String,
+    /// Count of device coordinates of each color
+    pub device_channels: usize,
+    pub colors: Vec<NamedColor>,
+}
+
+impl NamedColorList {
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&NamedColor> {
+        self.colors.get(index)
+    }
+
+    /// Index of color which root name, or full name with prefix and suffix, matches `name`.
+    /// ASCII case and surrounding whitespace are ignored.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        let name = name.trim();
+        self.colors.iter().position(|color| {
+            color.name.trim().eq_ignore_ascii_case(name)
+                || self.full_name_of(color).trim().eq_ignore_ascii_case(name)
+        })
+    }
+
+    /// Name with prefix and suffix of the list, as it is meant to be displayed
+    pub fn full_name(&self, index: usize) -> Option<String> {
+        self.get(index).map(|color| self.full_name_of(color))
+    }
+
+    fn full_name_of(&self, color: &NamedColor) -> String {
+        format!("{}{}{}", self.prefix, color.name, self.suffix)
+    }
+}
+
+/// Decodes `ncl2` PCS coordinates into XYZ D50 with white `Y = 1`
+pub(crate) fn named_pcs_to_xyz(pcs: DataColorSpace, v: [u16; 3]) -> [f64; 3] {
+    if pcs == DataColorSpace::Lab {
+        lab_to_xyz([
+            v[0] as f64 * (100. / 65280.),
+            v[1] as f64 / 256. - 128.,
+            v[2] as f64 / 256. - 128.,
+        ])
+    } else {
+        v.map(|x| x as f64 / 32768.)
+    }
+}
+
+/// Maps color indices into destination values computed once per color
+struct NamedColorExecutor<T> {
+    table: Vec<T>,
+    src_layout: Layout,
+    dst_layout: Layout,
+}
+
+impl<T: Copy + Default + AsPrimitive<usize>> TransformExecutor<T> for NamedColorExecutor<T> {
+    fn transform(&self, src: &[T], dst: &mut [T]) -> Result<(), CmsError> {
+        let src_channels = self.src_layout.channels();
+        let dst_channels = self.dst_layout.channels();
+        if src.len() % src_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if dst.len() % dst_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / src_channels != dst.len() / dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let copy_alpha = self.src_layout.has_alpha() && self.dst_layout.has_alpha();
+        for (src, dst) in src
+            .chunks_exact(src_channels)
+            .zip(dst.chunks_exact_mut(dst_channels))
+        {
+            let index: usize = src[0].as_();
+            let values = self
+                .table
+                .get(index * dst_channels..(index + 1) * dst_channels)
+                .ok_or(CmsError::NamedColorOutOfRange(index))?;
+            dst.copy_from_slice(values);
+            if copy_alpha {
+                dst[dst_channels - 1] = src[1];
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Creates transform from named color `profile`, source samples are color indices
+pub(crate) fn make_named_color_transform<
+    T: Copy + Default + AsPrimitive<usize> + PointeeSizeExpressible + Send + Sync + 'static,
+    const BIT_DEPTH: usize,
+>(
+    src_layout: Layout,
+    profile: &ColorProfile,
+    dst_layout: Layout,
+    dest: &ColorProfile,
+    options: TransformOptions,
+) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+where
+    f32: AsPrimitive<T>,
+{
+    if src_layout != Layout::Gray && src_layout != Layout::GrayAlpha {
+        return Err(CmsError::InvalidLayout);
+    }
+    if profile.pcs != DataColorSpace::Xyz && profile.pcs != DataColorSpace::Lab {
+        return Err(CmsError::UnsupportedProfileConnection);
+    }
+    let list = profile
+        .named_colors
+        .as_ref()
+        .ok_or(CmsError::UnsupportedProfileConnection)?;
+    let mut values = Vec::with_capacity(list.len() * dst_layout.channels());
+    if dst_layout.is_pcs() {
+        if T::FINITE {
+            return Err(CmsError::FloatingPointRequired);
+        }
+        for color in list.colors.iter() {
+            let xyz = named_pcs_to_xyz(profile.pcs, color.pcs);
+            let v = if dst_layout == Layout::Lab {
+                xyz_to_lab(xyz)
+            } else {
+                xyz
+            };
+            values.extend(v.map(|x| x as f32));
+        }
+    } else {
+        // PCS XYZ carried by the identity tables of `new_lab`
+        let pcs =
+            ColorProfile::new_lab().create_transform_f32(Layout::Rgb, dest, dst_layout, options)?;
+        let mut src = Vec::with_capacity(list.len() * 3);
+        for color in list.colors.iter() {
+            let xyz = named_pcs_to_xyz(profile.pcs, color.pcs);
+            src.extend(xyz.map(|x| (x * (32768. / 65535.)) as f32));
+        }
+        values.resize(list.len() * dst_layout.channels(), 0.);
+        pcs.transform(&src, &mut values)?;
+    }
+    let max_value = ((1u32 << BIT_DEPTH) - 1) as f32;
+    let table = values
+        .iter()
+        .map(|&v| {
+            if T::FINITE {
+                (v * max_value + 0.5).max(0.).min(max_value).as_()
+            } else {
+                v.as_()
+            }
+        })
+        .collect();
+    Ok(Box::new(NamedColorExecutor {
+        table,
+        src_layout,
+        dst_layout,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProfileClass;
+
+    #[test]
+    fn test_named_colors() {
+        let color = |name: &str, lab: [f32; 3], device: [u16; 4]| NamedColor {
+            name: name.to_string(),
+            pcs: [
+                (lab[0] / 100. * 65280.).round() as u16,
+                ((lab[1] + 128.) * 256.).round() as u16,
+                ((lab[2] + 128.) * 256.).round() as u16,
+            ],
+            device: device.to_vec(),
+        };
+        let profile = ColorProfile {
+            profile_class: ProfileClass::Named,
+            color_space: DataColorSpace::Cmyk,
+            pcs: DataColorSpace::Lab,
+            named_colors: Some(NamedColorList {
+                vendor_flags: 0,
+                prefix: "SPOT ".to_string(),
+                suffix: " C".to_string(),
+                device_channels: 4,
+                colors: vec![
+                    color("White", [100., 0., 0.], [0, 0, 0, 0]),
+                    color("Red", [54.29, 80.8, 69.89], [0, 65535, 65535, 0]),
+                    color("Black", [0., 0., 0.], [0, 0, 0, 65535]),
+                ],
+            }),
+            ..Default::default()
+        };
+        let profile = ColorProfile::new_from_slice(&profile.encode().unwrap()).unwrap();
+        let list = profile.named_colors.as_ref().unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.find("red"), Some(1));
+        assert_eq!(list.find("SPOT Black C"), Some(2));
+        assert_eq!(list.find("Blue"), None);
+        assert_eq!(list.full_name(0).as_deref(), Some("SPOT White C"));
+        assert_eq!(list.get(1).unwrap().device, vec![0, 65535, 65535, 0]);
+
+        let srgb = ColorProfile::new_srgb();
+        let transform = profile
+            .create_transform_8bit(Layout::Gray, &srgb, Layout::Rgb, Default::default())
+            .unwrap();
+        let mut dst = [0u8; 9];
+        transform.transform(&[0, 1, 2], &mut dst).unwrap();
+        let expected = [255u8, 255, 255, 255, 0, 0, 0, 0, 0];
+        for (&a, &b) in dst.iter().zip(expected.iter()) {
+            assert!(a.abs_diff(b) <= 2, "{dst:?}");
+        }
+        assert!(transform.transform(&[3], &mut dst[..3]).is_err());
+
+        let transform = profile
+            .create_transform_f32(Layout::Gray, &srgb, Layout::Lab, Default::default())
+            .unwrap();
+        let mut lab = [0f32; 3];
+        transform.transform(&[1.], &mut lab).unwrap();
+        assert!((lab[0] - 54.29).abs() < 0.1, "{lab:?}");
+        assert!((lab[1] - 80.8).abs() < 0.1, "{lab:?}");
+    }
+}
@@ -29,7 +29,7 @@
 use crate::math::{FusedMultiplyAdd, FusedMultiplyNegAdd};
 use crate::mlaf::{mlaf, neg_mlaf};
 use crate::safe_math::{SafeAdd, SafeMul};
-use crate::{CmsError, MalformedSize, Vector3f, Vector4f};
+use crate::{CmsError, InterpolationMethod, MalformedSize, Vector3f, Vector4f};
 use std::ops::{Add, Mul, Sub};
 
 impl FusedMultiplyAdd<f32> for f32 {
@@ -143,7 +143,50 @@ impl Hypercube<'_> {
         })
     }
 
-    pub(crate) fn new_checked_hypercube(
+    /// Builds CLUT data for `grid_size` hypercube, `f` receives node coordinates in \[0; 1\]
+    /// and returns `CHANNELS` values of the node.
+    ///
+    /// Result is laid out as [Hypercube::new_hypercube] expects, first coordinate changes slowest.
+    pub fn from_fn<const CHANNELS: usize>(
+        grid_size: [u8; 4],
+        mut f: impl FnMut(f32, f32, f32, f32) -> [f32; CHANNELS],
+    ) -> Vec<f32> {
+        let scale = grid_size.map(|x| 1. / (x.max(2) - 1) as f32);
+        let mut array =
+            Vec::with_capacity(grid_size.iter().map(|&x| x as usize).product::<usize>() * CHANNELS);
+        for x in 0..grid_size[0] {
+            for y in 0..grid_size[1] {
+                for z in 0..grid_size[2] {
+                    for w in 0..grid_size[3] {
+                        array.extend_from_slice(&f(
+                            x as f32 * scale[0],
+                            y as f32 * scale[1],
+                            z as f32 * scale[2],
+                            w as f32 * scale[3],
+                        ));
+                    }
+                }
+            }
+        }
+        array
+    }
+
+    /// Resamples 3 channels hypercube into `grid_size`
+    pub fn resampled_vec3(&self, grid_size: [u8; 4], method: InterpolationMethod) -> Vec<f32> {
+        Hypercube::from_fn(grid_size, |x, y, z, w| {
+            self.sample_vec3([x, y, z, w], method).v
+        })
+    }
+
+    /// Resamples 4 channels hypercube into `grid_size`
+    pub fn resampled_vec4(&self, grid_size: [u8; 4], method: InterpolationMethod) -> Vec<f32> {
+        Hypercube::from_fn(grid_size, |x, y, z, w| {
+            self.sample_vec4([x, y, z, w], method).v
+        })
+    }
+
+    /// Creates hypercube over `array` and checks that it holds `channels` values for each grid node
+    pub fn new_checked_hypercube(
         array: &[f32],
         grid_size: [u8; 4],
         channels: usize,
@@ -162,9 +205,9 @@ impl Hypercube<'_> {
                 grid_size,
             });
         }
-        let z_stride = grid_size[2] as u32;
-        let y_stride = z_stride * grid_size[1] as u32;
-        let x_stride = y_stride * grid_size[0] as u32;
+        let z_stride = grid_size[3] as u32;
+        let y_stride = z_stride * grid_size[2] as u32;
+        let x_stride = y_stride * grid_size[1] as u32;
         let last_index = (grid_size[0] as usize - 1)
             .safe_mul(x_stride as usize)?
             .safe_add((grid_size[1] as usize - 1).safe_mul(y_stride as usize)?)?
@@ -189,9 +232,9 @@ impl Hypercube<'_> {
     }
 
     pub fn new_hypercube(array: &[f32], grid_size: [u8; 4]) -> Hypercube<'_> {
-        let z_stride = grid_size[2] as u32;
-        let y_stride = z_stride * grid_size[1] as u32;
-        let x_stride = y_stride * grid_size[0] as u32;
+        let z_stride = grid_size[3] as u32;
+        let y_stride = z_stride * grid_size[2] as u32;
+        let x_stride = y_stride * grid_size[1] as u32;
         Hypercube {
             array,
             x_stride,
@@ -308,6 +351,40 @@ impl Hypercube<'_> {
     }
 
     #[inline]
+    /// Samples 3 channels hypercube at `point` in \[0; 1\] with the same kernels as transforms use.
+    ///
+    /// [InterpolationMethod::Auto] uses quadlinear interpolation.
+    pub fn sample_vec3(&self, point: [f32; 4], method: InterpolationMethod) -> Vector3f {
+        let [x, y, z, w] = point;
+        match method {
+            #[cfg(feature = "options")]
+            InterpolationMethod::Tetrahedral => self.tetra_vec3(x, y, z, w),
+            #[cfg(feature = "options")]
+            InterpolationMethod::Pyramid => self.pyramid_vec3(x, y, z, w),
+            #[cfg(feature = "options")]
+            InterpolationMethod::Prism => self.prism_vec3(x, y, z, w),
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
+                self.quadlinear_vec3(x, y, z, w)
+            }
+        }
+    }
+
+    /// Samples 4 channels hypercube at `point` in \[0; 1\], see [Hypercube::sample_vec3].
+    pub fn sample_vec4(&self, point: [f32; 4], method: InterpolationMethod) -> Vector4f {
+        let [x, y, z, w] = point;
+        match method {
+            #[cfg(feature = "options")]
+            InterpolationMethod::Tetrahedral => self.tetra_vec4(x, y, z, w),
+            #[cfg(feature = "options")]
+            InterpolationMethod::Pyramid => self.pyramid_vec4(x, y, z, w),
+            #[cfg(feature = "options")]
+            InterpolationMethod::Prism => self.prism_vec4(x, y, z, w),
+            InterpolationMethod::Linear | InterpolationMethod::Auto => {
+                self.quadlinear_vec4(x, y, z, w)
+            }
+        }
+    }
+
     pub fn quadlinear_vec3(&self, lin_x: f32, lin_y: f32, lin_z: f32, lin_w: f32) -> Vector3f {
         self.quadlinear(
             lin_x,
@@ -954,7 +1031,43 @@ impl Cube<'_> {
         }
     }
 
-    pub(crate) fn new_checked_cube(
+    /// Builds CLUT data for `grid_size` cube, `f` receives node coordinates in \[0; 1\]
+    /// and returns `CHANNELS` values of the node.
+    ///
+    /// Result is laid out as [Cube::new_cube] expects, first coordinate changes slowest.
+    pub fn from_fn<const CHANNELS: usize>(
+        grid_size: [u8; 3],
+        mut f: impl FnMut(f32, f32, f32) -> [f32; CHANNELS],
+    ) -> Vec<f32> {
+        let scale = grid_size.map(|x| 1. / (x.max(2) - 1) as f32);
+        let mut array =
+            Vec::with_capacity(grid_size.iter().map(|&x| x as usize).product::<usize>() * CHANNELS);
+        for x in 0..grid_size[0] {
+            for y in 0..grid_size[1] {
+                for z in 0..grid_size[2] {
+                    array.extend_from_slice(&f(
+                        x as f32 * scale[0],
+                        y as f32 * scale[1],
+                        z as f32 * scale[2],
+                    ));
+                }
+            }
+        }
+        array
+    }
+
+    /// Resamples 3 channels cube into `grid_size`
+    pub fn resampled_vec3(&self, grid_size: [u8; 3], method: InterpolationMethod) -> Vec<f32> {
+        Cube::from_fn(grid_size, |x, y, z| self.sample_vec3([x, y, z], method).v)
+    }
+
+    /// Resamples 4 channels cube into `grid_size`
+    pub fn resampled_vec4(&self, grid_size: [u8; 3], method: InterpolationMethod) -> Vec<f32> {
+        Cube::from_fn(grid_size, |x, y, z| self.sample_vec4([x, y, z], method).v)
+    }
+
+    /// Creates cube over `array` and checks that it holds `channels` values for each grid node
+    pub fn new_checked_cube(
         array: &[f32],
         grid_size: [u8; 3],
         channels: usize,
@@ -1287,6 +1400,36 @@ impl Cube<'_> {
         }
     }
 
+    /// Samples 3 channels cube at `point` in \[0; 1\] with the same kernels as transforms use.
+    ///
+    /// [InterpolationMethod::Auto] uses trilinear interpolation.
+    pub fn sample_vec3(&self, point: [f32; 3], method: InterpolationMethod) -> Vector3f {
+        let [x, y, z] = point;
+        match method {
+            #[cfg(feature = "options")]
+            InterpolationMethod::Tetrahedral => self.tetra_vec3(x, y, z),
+            #[cfg(feature = "options")]
+            InterpolationMethod::Pyramid => self.pyramid_vec3(x, y, z),
+            #[cfg(feature = "options")]
+            InterpolationMethod::Prism => self.prism_vec3(x, y, z),
+            InterpolationMethod::Linear | InterpolationMethod::Auto => self.trilinear_vec3(x, y, z),
+        }
+    }
+
+    /// Samples 4 channels cube at `point` in \[0; 1\], see [Cube::sample_vec3].
+    pub fn sample_vec4(&self, point: [f32; 3], method: InterpolationMethod) -> Vector4f {
+        let [x, y, z] = point;
+        match method {
+            #[cfg(feature = "options")]
+            InterpolationMethod::Tetrahedral => self.tetra_vec4(x, y, z),
+            #[cfg(feature = "options")]
+            InterpolationMethod::Pyramid => self.pyramid_vec4(x, y, z),
+            #[cfg(feature = "options")]
+            InterpolationMethod::Prism => self.prism_vec4(x, y, z),
+            InterpolationMethod::Linear | InterpolationMethod::Auto => self.trilinear_vec4(x, y, z),
+        }
+    }
+
     pub fn trilinear_vec3(&self, lin_x: f32, lin_y: f32, lin_z: f32) -> Vector3f {
         self.trilinear(
             lin_x,
@@ -1402,3 +1545,42 @@ impl Cube<'_> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cube_sample() {
+        // Identity 2x2x2 cube
+        let clut = (0..8)
+            .flat_map(|i| [(i >> 2) & 1, (i >> 1) & 1, i & 1].map(|x| x as f32))
+            .collect::<Vec<f32>>();
+        let cube = Cube::new_checked_cube(&clut, [2, 2, 2], 3).unwrap();
+        let point = [0.25, 0.5, 0.75];
+        let v = cube.sample_vec3(point, InterpolationMethod::Linear);
+        for (a, b) in v.v.iter().zip(point.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+        assert!(Cube::new_checked_cube(&clut[..21], [2, 2, 2], 3).is_err());
+    }
+
+    #[test]
+    fn test_cube_from_fn_resampled() {
+        let clut = Cube::from_fn([2, 2, 2], |x, y, z| [x, y, z]);
+        assert_eq!(&clut[..6], &[0., 0., 0., 0., 0., 1.]);
+        let cube = Cube::new_checked_cube(&clut, [2, 2, 2], 3).unwrap();
+        let dense = cube.resampled_vec3([5, 5, 5], InterpolationMethod::Linear);
+        let reference = Cube::from_fn([5, 5, 5], |x, y, z| [x, y, z]);
+        for (a, b) in dense.iter().zip(reference.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+
+        let clut = Hypercube::from_fn([2, 3, 2, 2], |x, y, z, w| [x * w, y, z]);
+        let hypercube = Hypercube::new_checked_hypercube(&clut, [2, 3, 2, 2], 3).unwrap();
+        let v = hypercube.sample_vec3([0.5, 0.25, 0.75, 0.5], InterpolationMethod::Linear);
+        assert!((v.v[0] - 0.25).abs() < 1e-6);
+        assert!((v.v[1] - 0.25).abs() < 1e-6);
+        assert!((v.v[2] - 0.75).abs() < 1e-6);
+    }
+}
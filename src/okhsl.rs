@@ -0,0 +1,412 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::gamut::OklabCusp;
+use crate::mlaf::mlaf;
+use crate::{Oklab, OklabGamut, Rgb};
+use pxfm::{f_atan2f, f_cbrtf, f_hypotf, f_sincosf};
+use std::sync::OnceLock;
+
+/// Represents *Okhsl* colorspace.
+///
+/// Hue, saturation and lightness built on top of [Oklab] and bounded by sRGB gamut.
+/// See [Björn Ottosson, Okhsv and Okhsl](https://bottosson.github.io/posts/colorpicker/).
+#[repr(C)]
+#[derive(Copy, Clone, PartialOrd, PartialEq, Debug)]
+pub struct Okhsl {
+    /// Hue, normalized \[0; 1\]
+    pub h: f32,
+    /// Saturation \[0; 1\]
+    pub s: f32,
+    /// Lightness \[0; 1\]
+    pub l: f32,
+}
+
+/// Lightness and chroma pair
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct OkLc {
+    pub(crate) l: f32,
+    pub(crate) c: f32,
+}
+
+/// Saturation `C / L` and `C / (1 - L)` pair
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct OkSt {
+    pub(crate) s: f32,
+    pub(crate) t: f32,
+}
+
+impl OkLc {
+    #[inline]
+    pub(crate) fn to_st(self) -> OkSt {
+        OkSt {
+            s: self.c / self.l,
+            t: self.c / (1. - self.l),
+        }
+    }
+}
+
+const TOE_K1: f32 = 0.206;
+const TOE_K2: f32 = 0.03;
+const TOE_K3: f32 = (1. + TOE_K1) / (1. + TOE_K2);
+
+/// Lightness estimate closer to CIE L*
+#[inline]
+pub(crate) fn toe(x: f32) -> f32 {
+    let k = mlaf(-TOE_K1, TOE_K3, x);
+    0.5 * (k + mlaf(k * k, 4. * TOE_K2 * TOE_K3, x).sqrt())
+}
+
+#[inline]
+pub(crate) fn toe_inv(x: f32) -> f32 {
+    mlaf(x * x, TOE_K1, x) / (TOE_K3 * (x + TOE_K2))
+}
+
+/// Finds maximum saturation `S = C / L` possible in sRGB for normalized `a` and `b`
+#[inline]
+fn compute_max_saturation(a: f32, b: f32) -> f32 {
+    // Polynomial approximation picked per the channel that clips first,
+    // then refined with one step of Halley's method
+    let (k0, k1, k2, k3, k4, wl, wm, ws) = if -1.88170328 * a - 0.80936493 * b > 1. {
+        // Red
+        (
+            1.19086277,
+            1.76576728,
+            0.59662641,
+            0.75515197,
+            0.56771245,
+            4.0767416621,
+            -3.3077115913,
+            0.2309699292,
+        )
+    } else if 1.81444104 * a - 1.19445276 * b > 1. {
+        // Green
+        (
+            0.73956515,
+            -0.45954404,
+            0.08285427,
+            0.12541070,
+            0.14503204,
+            -1.2684380046,
+            2.6097574011,
+            -0.3413193965,
+        )
+    } else {
+        // Blue
+        (
+            1.35733652,
+            -0.00915799,
+            -1.15130210,
+            -0.50559606,
+            0.00692167,
+            -0.0041960863,
+            -0.7034186147,
+            1.7076147010,
+        )
+    };
+
+    let s = mlaf(mlaf(mlaf(mlaf(k0, k1, a), k2, b), k3 * a, a), k4 * a, b);
+
+    let k_l = mlaf(0.3963377774 * a, 0.2158037573, b);
+    let k_m = mlaf(-0.1055613458 * a, -0.0638541728, b);
+    let k_s = mlaf(-0.0894841775 * a, -1.2914855480, b);
+
+    let l_ = mlaf(1., s, k_l);
+    let m_ = mlaf(1., s, k_m);
+    let s_ = mlaf(1., s, k_s);
+
+    let l = l_ * l_ * l_;
+    let m = m_ * m_ * m_;
+    let s3 = s_ * s_ * s_;
+
+    let l_ds = 3. * k_l * l_ * l_;
+    let m_ds = 3. * k_m * m_ * m_;
+    let s_ds = 3. * k_s * s_ * s_;
+
+    let l_ds2 = 6. * k_l * k_l * l_;
+    let m_ds2 = 6. * k_m * k_m * m_;
+    let s_ds2 = 6. * k_s * k_s * s_;
+
+    let f = mlaf(mlaf(wl * l, wm, m), ws, s3);
+    let f1 = mlaf(mlaf(wl * l_ds, wm, m_ds), ws, s_ds);
+    let f2 = mlaf(mlaf(wl * l_ds2, wm, m_ds2), ws, s_ds2);
+
+    s - f * f1 / (f1 * f1 - 0.5 * f * f2)
+}
+
+/// Finds the cusp of sRGB gamut for normalized `a` and `b`
+#[inline]
+pub(crate) fn find_srgb_cusp(a: f32, b: f32) -> OkLc {
+    let s_cusp = compute_max_saturation(a, b);
+    let rgb_at_max = Oklab::new(1., s_cusp * a, s_cusp * b).to_linear_rgb();
+    let l_cusp = f_cbrtf(1. / rgb_at_max.r.max(rgb_at_max.g).max(rgb_at_max.b));
+    OkLc {
+        l: l_cusp,
+        c: l_cusp * s_cusp,
+    }
+}
+
+/// sRGB gamut boundary, cusp table is built once
+fn srgb_gamut() -> &'static OklabGamut {
+    static GAMUT: OnceLock<OklabGamut> = OnceLock::new();
+    GAMUT.get_or_init(OklabGamut::srgb)
+}
+
+/// Smooth approximation of the cusp saturation used as a midpoint in [Okhsl]
+#[inline]
+fn get_st_mid(a: f32, b: f32) -> OkSt {
+    let s = 0.11516993
+        + 1. / mlaf(
+            mlaf(7.44778970, 4.15901240, b),
+            a,
+            mlaf(
+                mlaf(-2.19557347, 1.75198401, b),
+                a,
+                mlaf(
+                    mlaf(-2.13704948, -10.02301043, b),
+                    a,
+                    mlaf(mlaf(-4.24894561, 5.38770819, b), 4.69891013, a),
+                ),
+            ),
+        );
+    let t = 0.11239642
+        + 1. / mlaf(
+            mlaf(1.61320320, -0.68124379, b),
+            a,
+            mlaf(
+                mlaf(0.40370612, 0.90148123, b),
+                a,
+                mlaf(
+                    mlaf(-0.27087943, 0.61223990, b),
+                    a,
+                    mlaf(mlaf(0.00299215, -0.45399568, b), -0.14661872, a),
+                ),
+            ),
+        );
+    OkSt { s, t }
+}
+
+/// Chroma anchors used for the [Okhsl] saturation
+struct OkCs {
+    c_0: f32,
+    c_mid: f32,
+    c_max: f32,
+}
+
+fn get_cs(l: f32, a: f32, b: f32) -> OkCs {
+    let cusp = find_srgb_cusp(a, b);
+
+    let c_max = srgb_gamut().find_gamut_intersection_with_cusp(
+        a,
+        b,
+        l,
+        1.,
+        l,
+        OklabCusp {
+            l: cusp.l,
+            c: cusp.c,
+        },
+    );
+    let st_max = cusp.to_st();
+
+    // Scale factor to compensate for the curved part of gamut shape
+    let k = c_max / (l * st_max.s).min((1. - l) * st_max.t);
+
+    let c_mid = {
+        let st_mid = get_st_mid(a, b);
+
+        // Use a soft minimum function, instead of a sharp triangle shape to get a smooth value for chroma
+        let c_a = l * st_mid.s;
+        let c_b = (1. - l) * st_mid.t;
+        let c_a2 = c_a * c_a;
+        let c_b2 = c_b * c_b;
+        0.9 * k
+            * (1. / (1. / (c_a2 * c_a2) + 1. / (c_b2 * c_b2)))
+                .sqrt()
+                .sqrt()
+    };
+
+    let c_0 = {
+        // For C_0, the shape is independent of hue, so ST are constant.
+        // Values picked to roughly be the average values of ST.
+        let c_a = l * 0.4;
+        let c_b = (1. - l) * 0.8;
+
+        // Use a soft minimum function, instead of a sharp triangle shape to get a smooth value for chroma
+        (1. / (1. / (c_a * c_a) + 1. / (c_b * c_b))).sqrt()
+    };
+
+    OkCs { c_0, c_mid, c_max }
+}
+
+/// Hue normalized to \[0; 1\] from *Oklab* `a` and `b`
+#[inline]
+pub(crate) fn oklab_hue(a: f32, b: f32) -> f32 {
+    0.5 + 0.5 * f_atan2f(-b, -a) * std::f32::consts::FRAC_1_PI
+}
+
+impl Okhsl {
+    /// Creates new instance
+    #[inline]
+    pub const fn new(h: f32, s: f32, l: f32) -> Okhsl {
+        Okhsl { h, s, l }
+    }
+
+    /// Converts linear sRGB into [Okhsl]
+    #[inline]
+    pub fn from_linear_rgb(rgb: Rgb<f32>) -> Okhsl {
+        Okhsl::from_oklab(Oklab::from_linear_rgb(rgb))
+    }
+
+    /// Converts [Okhsl] into linear sRGB
+    #[inline]
+    pub fn to_linear_rgb(&self) -> Rgb<f32> {
+        self.to_oklab().to_linear_rgb()
+    }
+
+    /// Converts *Oklab* to *Okhsl*
+    pub fn from_oklab(oklab: Oklab) -> Okhsl {
+        let c = f_hypotf(oklab.a, oklab.b);
+        let l = oklab.l;
+
+        if c < f32::EPSILON || l <= 0. || l >= 1. {
+            return Okhsl::new(0., 0., toe(l.max(0.).min(1.)));
+        }
+
+        let a_ = oklab.a / c;
+        let b_ = oklab.b / c;
+
+        let h = oklab_hue(oklab.a, oklab.b);
+
+        let cs = get_cs(l, a_, b_);
+
+        // Inverse of the interpolation in [Okhsl::to_oklab]
+        const MID: f32 = 0.8;
+        const MID_INV: f32 = 1.25;
+
+        let s = if c < cs.c_mid {
+            let k_1 = MID * cs.c_0;
+            let k_2 = 1. - k_1 / cs.c_mid;
+
+            let t = c / mlaf(k_1, k_2, c);
+            t * MID
+        } else {
+            let k_0 = cs.c_mid;
+            let k_1 = (1. - MID) * cs.c_mid * cs.c_mid * MID_INV * MID_INV / cs.c_0;
+            let k_2 = 1. - k_1 / (cs.c_max - cs.c_mid);
+
+            let t = (c - k_0) / mlaf(k_1, k_2, c - k_0);
+            mlaf(MID, 1. - MID, t)
+        };
+
+        Okhsl::new(h, s, toe(l))
+    }
+
+    /// Converts *Okhsl* to *Oklab*
+    pub fn to_oklab(&self) -> Oklab {
+        if self.l >= 1. {
+            return Oklab::new(1., 0., 0.);
+        } else if self.l <= 0. {
+            return Oklab::new(0., 0., 0.);
+        }
+
+        let l = toe_inv(self.l);
+        if self.s <= 0. {
+            return Oklab::new(l, 0., 0.);
+        }
+
+        let (sin_h, cos_h) = f_sincosf(std::f32::consts::TAU * self.h);
+        let a_ = cos_h;
+        let b_ = sin_h;
+
+        let cs = get_cs(l, a_, b_);
+
+        // Interpolate the three values for C so that:
+        // At s=0: dC/ds = C_0, C=0
+        // At s=0.8: C=C_mid
+        // At s=1.0: C=C_max
+        const MID: f32 = 0.8;
+        const MID_INV: f32 = 1.25;
+
+        let c = if self.s < MID {
+            let t = MID_INV * self.s;
+
+            let k_1 = MID * cs.c_0;
+            let k_2 = 1. - k_1 / cs.c_mid;
+
+            t * k_1 / (1. - k_2 * t)
+        } else {
+            let t = (self.s - MID) / (1. - MID);
+
+            let k_0 = cs.c_mid;
+            let k_1 = (1. - MID) * cs.c_mid * cs.c_mid * MID_INV * MID_INV / cs.c_0;
+            let k_2 = 1. - k_1 / (cs.c_max - cs.c_mid);
+
+            k_0 + t * k_1 / (1. - k_2 * t)
+        };
+
+        Oklab::new(l, c * a_, c * b_)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let colors = [
+            Rgb::new(0.1, 0.2, 0.3),
+            Rgb::new(0.9, 0.05, 0.02),
+            Rgb::new(0.02, 0.8, 0.1),
+            Rgb::new(0.3, 0.3, 0.3),
+            Rgb::new(0.05, 0.1, 0.95),
+        ];
+        for rgb in colors {
+            let hsl = Okhsl::from_linear_rgb(rgb);
+            let rolled_back = hsl.to_linear_rgb();
+            let dx = (rgb.r - rolled_back.r).abs();
+            let dy = (rgb.g - rolled_back.g).abs();
+            let dz = (rgb.b - rolled_back.b).abs();
+            assert!(dx < 1e-3, "{rgb:?} -> {hsl:?} -> {rolled_back:?}");
+            assert!(dy < 1e-3, "{rgb:?} -> {hsl:?} -> {rolled_back:?}");
+            assert!(dz < 1e-3, "{rgb:?} -> {hsl:?} -> {rolled_back:?}");
+        }
+    }
+
+    #[test]
+    fn stays_in_srgb_gamut() {
+        for h in 0..12 {
+            let hsl = Okhsl::new(h as f32 / 12., 1., 0.5);
+            let rgb = hsl.to_linear_rgb();
+            for v in [rgb.r, rgb.g, rgb.b] {
+                assert!((-1e-3..=1. + 1e-3).contains(&v), "{hsl:?} -> {rgb:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::okhsl::{find_srgb_cusp, oklab_hue, toe, toe_inv};
+use crate::{Oklab, Rgb};
+use pxfm::{f_cbrtf, f_hypotf, f_sincosf};
+
+/// Represents *Okhsv* colorspace.
+///
+/// Hue, saturation and value built on top of [Oklab] and bounded by sRGB gamut.
+/// See [Björn Ottosson, Okhsv and Okhsl](https://bottosson.github.io/posts/colorpicker/).
+#[repr(C)]
+#[derive(Copy, Clone, PartialOrd, PartialEq, Debug)]
+pub struct Okhsv {
+    /// Hue, normalized \[0; 1\]
+    pub h: f32,
+    /// Saturation \[0; 1\]
+    pub s: f32,
+    /// Value \[0; 1\]
+    pub v: f32,
+}
+
+/// Saturation of the gray axis in the `S` `T` triangle
+const S_0: f32 = 0.5;
+
+impl Okhsv {
+    /// Creates new instance
+    #[inline]
+    pub const fn new(h: f32, s: f32, v: f32) -> Okhsv {
+        Okhsv { h, s, v }
+    }
+
+    /// Converts linear sRGB into [Okhsv]
+    #[inline]
+    pub fn from_linear_rgb(rgb: Rgb<f32>) -> Okhsv {
+        Okhsv::from_oklab(Oklab::from_linear_rgb(rgb))
+    }
+
+    /// Converts [Okhsv] into linear sRGB
+    #[inline]
+    pub fn to_linear_rgb(&self) -> Rgb<f32> {
+        self.to_oklab().to_linear_rgb()
+    }
+
+    /// Converts *Oklab* to *Okhsv*
+    pub fn from_oklab(oklab: Oklab) -> Okhsv {
+        let c = f_hypotf(oklab.a, oklab.b);
+        let mut l = oklab.l;
+
+        if l <= 0. {
+            return Okhsv::new(0., 0., 0.);
+        }
+        if c < f32::EPSILON {
+            return Okhsv::new(0., 0., toe(l.min(1.)));
+        }
+
+        let a_ = oklab.a / c;
+        let b_ = oklab.b / c;
+
+        let h = oklab_hue(oklab.a, oklab.b);
+
+        let st_max = find_srgb_cusp(a_, b_).to_st();
+        let k = 1. - S_0 / st_max.s;
+
+        // First we find L_v, C_v, L_vt and C_vt
+        let t = st_max.t / (c + l * st_max.t);
+        let l_v = t * l;
+        let c_v = t * c;
+
+        let l_vt = toe_inv(l_v);
+        let c_vt = c_v * l_vt / l_v;
+
+        // Invert the scaling applied in [Okhsv::to_oklab]
+        let rgb_scale = Oklab::new(l_vt, a_ * c_vt, b_ * c_vt).to_linear_rgb();
+        let scale_l = f_cbrtf(1. / rgb_scale.r.max(rgb_scale.g).max(rgb_scale.b.max(0.)));
+
+        l = toe(l / scale_l);
+
+        let v = l / l_v;
+        let s = (S_0 + st_max.t) * c_v / (st_max.t * S_0 + st_max.t * k * c_v);
+
+        Okhsv::new(h, s, v)
+    }
+
+    /// Converts *Okhsv* to *Oklab*
+    pub fn to_oklab(&self) -> Oklab {
+        if self.v <= 0. {
+            return Oklab::new(0., 0., 0.);
+        }
+
+        let (sin_h, cos_h) = f_sincosf(std::f32::consts::TAU * self.h);
+        let a_ = cos_h;
+        let b_ = sin_h;
+
+        let st_max = find_srgb_cusp(a_, b_).to_st();
+        let k = 1. - S_0 / st_max.s;
+
+        // First we compute L and V as if the gamut is a perfect triangle
+        let denom = S_0 + st_max.t - st_max.t * k * self.s;
+        let l_v = 1. - self.s * S_0 / denom;
+        let c_v = self.s * st_max.t * S_0 / denom;
+
+        let mut l = self.v * l_v;
+        let mut c = self.v * c_v;
+
+        // Then we compensate for both toe and the curved top part of the triangle
+        let l_vt = toe_inv(l_v);
+        let c_vt = c_v * l_vt / l_v;
+
+        let l_new = toe_inv(l);
+        c = c * l_new / l;
+        l = l_new;
+
+        let rgb_scale = Oklab::new(l_vt, a_ * c_vt, b_ * c_vt).to_linear_rgb();
+        let scale_l = f_cbrtf(1. / rgb_scale.r.max(rgb_scale.g).max(rgb_scale.b.max(0.)));
+
+        l *= scale_l;
+        c *= scale_l;
+
+        Oklab::new(l, c * a_, c * b_)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let colors = [
+            Rgb::new(0.1, 0.2, 0.3),
+            Rgb::new(0.9, 0.05, 0.02),
+            Rgb::new(0.02, 0.8, 0.1),
+            Rgb::new(0.05, 0.1, 0.95),
+        ];
+        for rgb in colors {
+            let hsv = Okhsv::from_linear_rgb(rgb);
+            let rolled_back = hsv.to_linear_rgb();
+            let dx = (rgb.r - rolled_back.r).abs();
+            let dy = (rgb.g - rolled_back.g).abs();
+            let dz = (rgb.b - rolled_back.b).abs();
+            assert!(dx < 1e-3, "{rgb:?} -> {hsv:?} -> {rolled_back:?}");
+            assert!(dy < 1e-3, "{rgb:?} -> {hsv:?} -> {rolled_back:?}");
+            assert!(dz < 1e-3, "{rgb:?} -> {hsv:?} -> {rolled_back:?}");
+        }
+    }
+
+    #[test]
+    fn full_value_and_saturation_hits_gamut() {
+        for h in 0..12 {
+            let hsv = Okhsv::new(h as f32 / 12., 1., 1.);
+            let rgb = hsv.to_linear_rgb();
+            let max = rgb.r.max(rgb.g).max(rgb.b);
+            assert!((max - 1.).abs() < 1e-2, "{hsv:?} -> {rgb:?}");
+        }
+    }
+}
@@ -4,8 +4,8 @@
  * // Use of this source code is governed by a BSD-style
  * // license that can be found in the LICENSE file.
  */
-use crate::Rgb;
 use crate::mlaf::mlaf;
+use crate::{GamutClipMode, OklabGamut, Rgb};
 use num_traits::Pow;
 use pxfm::{f_cbrtf, f_powf};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
@@ -126,6 +126,30 @@ impl Oklab {
     }
 }
 
+impl Oklab {
+    /// Interpolates from `a` to `b` along a straight line, `t` in \[0; 1\]
+    #[inline]
+    pub fn lerp(a: Oklab, b: Oklab, t: f32) -> Oklab {
+        Oklab::new(
+            mlaf(a.l, b.l - a.l, t),
+            mlaf(a.a, b.a - a.a, t),
+            mlaf(a.b, b.b - a.b, t),
+        )
+    }
+
+    /// Same as [Oklab::lerp], result is clipped into `gamut` with `mode`
+    pub fn lerp_in_gamut(
+        a: Oklab,
+        b: Oklab,
+        t: f32,
+        gamut: &OklabGamut,
+        mode: GamutClipMode,
+    ) -> Oklab {
+        let rgb = gamut.to_linear_rgb(Oklab::lerp(a, b, t));
+        gamut.to_oklab(gamut.clip(rgb, mode))
+    }
+}
+
 impl Oklab {
     pub fn euclidean_distance(&self, other: Self) -> f32 {
         let lax = self.l - other.l;
@@ -4,7 +4,7 @@
  * // Use of this source code is governed by a BSD-style
  * // license that can be found in the LICENSE file.
  */
-use crate::{Oklab, Rgb};
+use crate::{GamutClipMode, Oklab, OklabGamut, Rgb};
 use num_traits::Pow;
 use pxfm::{f_atan2f, f_cbrtf, f_hypotf, f_powf, f_sincosf};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
@@ -64,6 +64,66 @@ impl Oklch {
     }
 }
 
+/// Direction of hue when interpolating [Oklch], as CSS Color 4 `hue-interpolation-method`
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum HuePath {
+    /// Shortest arc, at most half a turn
+    #[default]
+    Shorter,
+    /// Longest arc, at least half a turn
+    Longer,
+    /// Hue angle only grows
+    Increasing,
+    /// Hue angle only shrinks
+    Decreasing,
+}
+
+impl HuePath {
+    /// Hue difference from `from` to `to` in radians that follows this path
+    fn delta(self, from: f32, to: f32) -> f32 {
+        use std::f32::consts::{PI, TAU};
+        let d = (to - from).rem_euclid(TAU);
+        match self {
+            HuePath::Shorter if d > PI => d - TAU,
+            HuePath::Longer if d > 0. && d < PI => d - TAU,
+            HuePath::Longer if d == 0. => TAU,
+            HuePath::Decreasing if d > 0. => d - TAU,
+            _ => d,
+        }
+    }
+}
+
+impl Oklch {
+    /// Interpolates from `a` to `b`, `t` in \[0; 1\], hue travels along `path`.
+    ///
+    /// Hue of an achromatic endpoint is meaningless, the other endpoint hue is used instead.
+    /// Resulting hue is kept in \[-π; π\] as [Oklch::from_oklab] produces.
+    pub fn lerp(a: Oklch, b: Oklch, t: f32, path: HuePath) -> Oklch {
+        const ACHROMATIC: f32 = 1e-5;
+        let h = match (a.c < ACHROMATIC, b.c < ACHROMATIC) {
+            (true, false) => b.h,
+            (false, true) => a.h,
+            _ => a.h + path.delta(a.h, b.h) * t,
+        };
+        let h = (h + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        Oklch::new(a.l + (b.l - a.l) * t, a.c + (b.c - a.c) * t, h)
+    }
+
+    /// Same as [Oklch::lerp], result is clipped into `gamut` with `mode`
+    pub fn lerp_in_gamut(
+        a: Oklch,
+        b: Oklch,
+        t: f32,
+        path: HuePath,
+        gamut: &OklabGamut,
+        mode: GamutClipMode,
+    ) -> Oklch {
+        let oklab = Oklch::lerp(a, b, t, path).to_oklab();
+        let rgb = gamut.clip(gamut.to_linear_rgb(oklab), mode);
+        Oklch::from_oklab(gamut.to_oklab(rgb))
+    }
+}
+
 impl Oklch {
     #[inline]
     pub fn euclidean_distance(&self, other: Self) -> f32 {
@@ -279,6 +339,47 @@ impl Oklch {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_lerp_hue_paths() {
+        use std::f32::consts::PI;
+        let a = Oklch::new(0.5, 0.1, 170f32.to_radians());
+        let b = Oklch::new(0.7, 0.1, -170f32.to_radians());
+        let hue = |path| Oklch::lerp(a, b, 0.5, path).h.to_degrees();
+        assert!((hue(HuePath::Shorter).abs() - 180.).abs() < 1e-2);
+        assert!(hue(HuePath::Longer).abs() < 1e-2);
+        assert!((hue(HuePath::Increasing).abs() - 180.).abs() < 1e-2);
+        assert!(hue(HuePath::Decreasing).abs() < 1e-2);
+        let mid = Oklch::lerp(a, b, 0.5, HuePath::Shorter);
+        assert!((mid.l - 0.6).abs() < 1e-6);
+        assert!(mid.h >= -PI && mid.h <= PI);
+
+        // Achromatic endpoint keeps hue of the other one
+        let gray = Oklch::new(0.5, 0., 0.);
+        let mid = Oklch::lerp(gray, a, 0.5, HuePath::Longer);
+        assert!((mid.h - a.h).abs() < 1e-5);
+
+        let gamut = OklabGamut::srgb();
+        let red = Oklch::from_oklab(gamut.to_oklab(Rgb::new(1., 0., 0.)));
+        let blue = Oklch::from_oklab(gamut.to_oklab(Rgb::new(0., 0., 1.)));
+        for path in [HuePath::Shorter, HuePath::Longer] {
+            let v =
+                Oklch::lerp_in_gamut(red, blue, 0.5, path, &gamut, GamutClipMode::PreserveChroma);
+            let rgb = gamut.to_linear_rgb(v.to_oklab());
+            for c in [rgb.r, rgb.g, rgb.b] {
+                assert!((-1e-3..=1. + 1e-3).contains(&c));
+            }
+        }
+        let v = Oklab::lerp_in_gamut(
+            red.to_oklab(),
+            blue.to_oklab(),
+            0.5,
+            &gamut,
+            GamutClipMode::PreserveChroma,
+        );
+        let straight = Oklab::lerp(red.to_oklab(), blue.to_oklab(), 0.5);
+        assert!(v.euclidean_distance(straight) < 0.1);
+    }
+
     #[test]
     fn round_trip() {
         let xyz = Rgb::new(0.1, 0.2, 0.3);
@@ -0,0 +1,499 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Splitting image transforms between threads.
+//!
+//! Spawning threads costs tens of microseconds, so small images are converted
+//! in place while large ones are split in chunks sized by estimated per pixel cost.
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, InterpolationMethod, Layout, TransformExecutor,
+    TransformOptions, TransformScratch,
+};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+/// Estimated time of converting a single pixel, in nanoseconds on a typical desktop core.
+///
+/// Only ratios matter: it is used to decide how much work justifies a thread.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct TransformCost {
+    pub per_pixel: f32,
+}
+
+impl TransformCost {
+    /// Estimates cost of transform created with the same arguments.
+    pub fn estimate(
+        source: &ColorProfile,
+        src_layout: Layout,
+        dest: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> TransformCost {
+        let uses_lut = source.has_device_to_pcs_lut()
+            || dest.has_pcs_to_device_lut()
+            || source.color_space != DataColorSpace::Rgb
+                && source.color_space != DataColorSpace::Gray
+            || dest.color_space != DataColorSpace::Rgb && dest.color_space != DataColorSpace::Gray;
+        let per_pixel = if source.spectral_device_to_pcs().is_some()
+            || dest.spectral_pcs_to_device().is_some()
+        {
+            200.
+        } else if uses_lut {
+            let input_channels = source.color_space.channels().max(3);
+            // Every extra input dimension doubles fetched cell corners
+            let cell = (1u32 << (input_channels.min(8) - 3)) as f32;
+            let method = match options.interpolation_method {
+                InterpolationMethod::Linear => 1.5,
+                _ => 1.,
+            };
+            6. * cell * method + dst_layout.channels() as f32
+        } else {
+            2. + (src_layout.channels() + dst_layout.channels()) as f32 * 0.25
+        };
+        let simd = if TransformCost::has_simd() { 0.5 } else { 1. };
+        TransformCost {
+            per_pixel: per_pixel * simd,
+        }
+    }
+
+    fn has_simd() -> bool {
+        #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+        if crate::equivalence::x86_feature_detected!("avx2") {
+            return true;
+        }
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "sse"))]
+        if crate::equivalence::x86_feature_detected!("sse4.1") {
+            return true;
+        }
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon", feature = "neon"))]
+        return true;
+        #[allow(unreachable_code)]
+        false
+    }
+}
+
+/// How many threads convert an image and how many pixels each of them takes at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkPlan {
+    pub threads: usize,
+    pub pixels_per_chunk: usize,
+}
+
+impl ChunkPlan {
+    /// Estimated work in nanoseconds that pays for spawning a thread
+    pub const MIN_WORK_PER_THREAD: f32 = 100_000.;
+    /// Chunks per thread, so threads finishing early take over remaining work
+    pub const CHUNKS_PER_THREAD: usize = 4;
+
+    /// Plans conversion of `pixels` pixels using at most `max_threads` threads.
+    pub fn new(pixels: usize, cost: TransformCost, max_threads: usize) -> ChunkPlan {
+        let work = pixels as f32 * cost.per_pixel.max(f32::MIN_POSITIVE);
+        let threads = ((work / Self::MIN_WORK_PER_THREAD) as usize).clamp(1, max_threads.max(1));
+        if threads == 1 {
+            return ChunkPlan {
+                threads,
+                pixels_per_chunk: pixels.max(1),
+            };
+        }
+        let chunks = threads * Self::CHUNKS_PER_THREAD;
+        ChunkPlan {
+            threads,
+            pixels_per_chunk: pixels.div_ceil(chunks).max(1),
+        }
+    }
+
+    /// Plans conversion using all threads available to the process.
+    pub fn for_available_threads(pixels: usize, cost: TransformCost) -> ChunkPlan {
+        ChunkPlan::new(pixels, cost, available_threads())
+    }
+
+    /// Plans conversion using available threads capped by [TransformOptions::max_threads].
+    pub fn for_options(pixels: usize, cost: TransformCost, options: TransformOptions) -> ChunkPlan {
+        let max_threads = match options.max_threads {
+            Some(cap) => cap.clamp(1, available_threads()),
+            None => available_threads(),
+        };
+        ChunkPlan::new(pixels, cost, max_threads)
+    }
+}
+
+fn available_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
+}
+
+/// Runs workers of [transform_parallel_with] on threads owned by the application.
+pub trait ChunkRunner {
+    /// Calls `work` with worker index `0..workers`, possibly concurrently.
+    ///
+    /// `work` borrows the image, so it must be called only before `run` returns.
+    /// Calls that never happened are not lost, leftover chunks are converted
+    /// on the calling thread.
+    fn run(&self, workers: usize, work: &(dyn Fn(usize) + Sync));
+}
+
+/// [ChunkRunner] spawning scoped threads for each conversion.
+#[derive(Default)]
+pub struct ScopedThreads {
+    thread_start: Option<Box<dyn Fn(usize) + Send + Sync>>,
+}
+
+impl ScopedThreads {
+    /// Calls `hook` with worker index on each spawned thread before it takes work,
+    /// e.g. to set thread affinity or priority.
+    pub fn with_thread_start(hook: impl Fn(usize) + Send + Sync + 'static) -> Self {
+        Self {
+            thread_start: Some(Box::new(hook)),
+        }
+    }
+}
+
+impl ChunkRunner for ScopedThreads {
+    fn run(&self, workers: usize, work: &(dyn Fn(usize) + Sync)) {
+        std::thread::scope(|scope| {
+            for index in 0..workers {
+                scope.spawn(move || {
+                    if let Some(thread_start) = &self.thread_start {
+                        thread_start(index);
+                    }
+                    work(index);
+                });
+            }
+        });
+    }
+}
+
+/// Runs `executor` over tightly packed pixels following `plan`.
+///
+/// Each thread has its own [TransformScratch], chunks are handed out as threads become free.
+pub fn transform_parallel<V: Copy + Default + Send + Sync>(
+    executor: &(dyn TransformExecutor<V> + Send + Sync),
+    src: &[V],
+    src_layout: Layout,
+    dst: &mut [V],
+    dst_layout: Layout,
+    plan: ChunkPlan,
+) -> Result<(), CmsError> {
+    transform_parallel_with(
+        executor,
+        src,
+        src_layout,
+        dst,
+        dst_layout,
+        plan,
+        &ScopedThreads::default(),
+    )
+}
+
+/// Same as [transform_parallel], workers are run by `runner`.
+pub fn transform_parallel_with<V: Copy + Default + Send + Sync>(
+    executor: &(dyn TransformExecutor<V> + Send + Sync),
+    src: &[V],
+    src_layout: Layout,
+    dst: &mut [V],
+    dst_layout: Layout,
+    plan: ChunkPlan,
+    runner: &dyn ChunkRunner,
+) -> Result<(), CmsError> {
+    let src_channels = src_layout.channels();
+    let dst_channels = dst_layout.channels();
+    if src.len() % src_channels != 0 || dst.len() % dst_channels != 0 {
+        return Err(CmsError::LaneMultipleOfChannels);
+    }
+    if src.len() / src_channels != dst.len() / dst_channels {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    if plan.threads <= 1 {
+        return executor.transform(src, dst);
+    }
+    let pixels_per_chunk = plan.pixels_per_chunk.max(1);
+    let chunks = Mutex::new(
+        src.chunks(pixels_per_chunk * src_channels)
+            .zip(dst.chunks_mut(pixels_per_chunk * dst_channels)),
+    );
+    let result = Mutex::new(Ok(()));
+    let work = |_: usize| {
+        let mut scratch = TransformScratch::default();
+        loop {
+            let next = chunks.lock().ok().and_then(|mut chunks| chunks.next());
+            let Some((src, dst)) = next else {
+                break;
+            };
+            if let Err(err) = executor.transform_with_scratch(src, dst, &mut scratch) {
+                if let Ok(mut result) = result.lock() {
+                    *result = Err(err);
+                }
+                break;
+            }
+        }
+    };
+    runner.run(plan.threads, &work);
+    // Runner might skip workers, or error stopped them early
+    if matches!(result.lock().as_deref(), Ok(Ok(()))) {
+        work(plan.threads);
+    }
+    result
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Converts many independent buffers, such as tiles, with `plan.threads` workers run by `runner`.
+///
+/// Workers take whole buffers as they become free and keep their [TransformScratch]
+/// between them, so each buffer costs only its conversion.
+/// [ChunkPlan::pixels_per_chunk] is not used, buffers are never split.
+/// Conversion stops on the first error.
+pub fn transform_many_parallel<V: Copy + Default + Send + Sync>(
+    executor: &(dyn TransformExecutor<V> + Send + Sync),
+    buffers: &mut [(&[V], &mut [V])],
+    plan: ChunkPlan,
+    runner: &dyn ChunkRunner,
+) -> Result<(), CmsError> {
+    if plan.threads <= 1 || buffers.len() <= 1 {
+        return executor.transform_many(buffers);
+    }
+    let workers = plan.threads.min(buffers.len());
+    let buffers = Mutex::new(buffers.iter_mut());
+    let result = Mutex::new(Ok(()));
+    let work = |_: usize| {
+        let mut scratch = TransformScratch::default();
+        loop {
+            let next = buffers.lock().ok().and_then(|mut buffers| buffers.next());
+            let Some((src, dst)) = next else {
+                break;
+            };
+            if let Err(err) = executor.transform_with_scratch(src, dst, &mut scratch) {
+                if let Ok(mut result) = result.lock() {
+                    *result = Err(err);
+                }
+                break;
+            }
+        }
+    };
+    runner.run(workers, &work);
+    if matches!(result.lock().as_deref(), Ok(Ok(()))) {
+        work(workers);
+    }
+    result
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_plan() {
+        let srgb = ColorProfile::new_srgb();
+        let p3 = ColorProfile::new_display_p3();
+        let options = TransformOptions::default();
+        let matrix = TransformCost::estimate(&srgb, Layout::Rgb, &p3, Layout::Rgb, options);
+        let cmyk = ColorProfile {
+            color_space: DataColorSpace::Cmyk,
+            pcs: DataColorSpace::Lab,
+            ..Default::default()
+        };
+        let clut = TransformCost::estimate(&cmyk, Layout::Rgba, &srgb, Layout::Rgb, options);
+        assert!(clut.per_pixel > matrix.per_pixel);
+
+        // Thumbnail is not worth a thread
+        assert_eq!(ChunkPlan::new(64 * 64, matrix, 16).threads, 1);
+        // Large image uses every thread with several chunks each
+        let plan = ChunkPlan::new(4096 * 4096, clut, 16);
+        assert_eq!(plan.threads, 16);
+        assert!(plan.pixels_per_chunk * 16 * ChunkPlan::CHUNKS_PER_THREAD >= 4096 * 4096);
+    }
+
+    #[test]
+    fn test_transform_parallel() {
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let options = TransformOptions::default();
+        let transform = srgb
+            .create_transform_8bit(Layout::Rgba, &bt2020, Layout::Rgb, options)
+            .unwrap();
+        let pixels = 10_007;
+        let src = (0..pixels * 4)
+            .map(|i| ((i * 31 + 7) % 256) as u8)
+            .collect::<Vec<_>>();
+        let mut expected = vec![0u8; pixels * 3];
+        transform.transform(&src, &mut expected).unwrap();
+        let plan = ChunkPlan {
+            threads: 4,
+            pixels_per_chunk: 333,
+        };
+        let mut dst = vec![0u8; pixels * 3];
+        transform_parallel(
+            transform.as_ref(),
+            &src,
+            Layout::Rgba,
+            &mut dst,
+            Layout::Rgb,
+            plan,
+        )
+        .unwrap();
+        assert_eq!(dst, expected);
+        assert!(
+            transform_parallel(
+                transform.as_ref(),
+                &src,
+                Layout::Rgba,
+                &mut dst[3..],
+                Layout::Rgb,
+                plan
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_thread_cap_and_runner() {
+        let srgb = ColorProfile::new_srgb();
+        let options = TransformOptions {
+            max_threads: Some(1),
+            ..Default::default()
+        };
+        let cost = TransformCost { per_pixel: 100. };
+        assert_eq!(
+            ChunkPlan::for_options(4096 * 4096, cost, options).threads,
+            1
+        );
+
+        let transform = srgb
+            .create_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, options)
+            .unwrap();
+        let src = (0..3000).map(|i| (i % 256) as u8).collect::<Vec<_>>();
+        let plan = ChunkPlan {
+            threads: 3,
+            pixels_per_chunk: 10,
+        };
+
+        let started = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = started.clone();
+        let runner = ScopedThreads::with_thread_start(move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        });
+        let mut dst = vec![0u8; src.len()];
+        transform_parallel_with(
+            transform.as_ref(),
+            &src,
+            Layout::Rgb,
+            &mut dst,
+            Layout::Rgb,
+            plan,
+            &runner,
+        )
+        .unwrap();
+        assert_eq!(started.load(std::sync::atomic::Ordering::Relaxed), 3);
+        assert_eq!(dst, src);
+
+        // Runner that never calls workers still gets the image converted
+        struct Lazy;
+        impl ChunkRunner for Lazy {
+            fn run(&self, _: usize, _: &(dyn Fn(usize) + Sync)) {}
+        }
+        let mut dst = vec![0u8; src.len()];
+        transform_parallel_with(
+            transform.as_ref(),
+            &src,
+            Layout::Rgb,
+            &mut dst,
+            Layout::Rgb,
+            plan,
+            &Lazy,
+        )
+        .unwrap();
+        assert_eq!(dst, src);
+    }
+
+    #[test]
+    fn test_transform_many() {
+        let srgb = ColorProfile::new_srgb();
+        let p3 = ColorProfile::new_display_p3();
+        let transform = srgb
+            .create_transform_8bit(Layout::Rgb, &p3, Layout::Rgb, TransformOptions::default())
+            .unwrap();
+        let tiles = (0..37)
+            .map(|t| {
+                (0..(t % 5 + 1) * 48)
+                    .map(|i| ((i * 13 + t * 7) % 256) as u8)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let expected = tiles
+            .iter()
+            .map(|tile| {
+                let mut dst = vec![0u8; tile.len()];
+                transform.transform(tile, &mut dst).unwrap();
+                dst
+            })
+            .collect::<Vec<_>>();
+
+        let mut sequential = tiles.iter().map(|t| vec![0u8; t.len()]).collect::<Vec<_>>();
+        let mut buffers = tiles
+            .iter()
+            .zip(sequential.iter_mut())
+            .map(|(src, dst)| (src.as_slice(), dst.as_mut_slice()))
+            .collect::<Vec<_>>();
+        transform.transform_many(&mut buffers).unwrap();
+        assert_eq!(sequential, expected);
+
+        let plan = ChunkPlan {
+            threads: 4,
+            pixels_per_chunk: 1,
+        };
+        let mut parallel = tiles.iter().map(|t| vec![0u8; t.len()]).collect::<Vec<_>>();
+        let mut buffers = tiles
+            .iter()
+            .zip(parallel.iter_mut())
+            .map(|(src, dst)| (src.as_slice(), dst.as_mut_slice()))
+            .collect::<Vec<_>>();
+        transform_many_parallel(
+            transform.as_ref(),
+            &mut buffers,
+            plan,
+            &ScopedThreads::default(),
+        )
+        .unwrap();
+        assert_eq!(parallel, expected);
+
+        let mut short = vec![0u8; 3];
+        let mut buffers = [(tiles[1].as_slice(), short.as_mut_slice())];
+        assert!(
+            transform_many_parallel(
+                transform.as_ref(),
+                &mut buffers,
+                plan,
+                &ScopedThreads::default()
+            )
+            .is_err()
+        );
+    }
+}
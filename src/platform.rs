@@ -0,0 +1,245 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::ColorProfile;
+
+/// Raw data of discovered display profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DisplayProfileSource {
+    Icc(Vec<u8>),
+    Edid(Vec<u8>),
+}
+
+impl DisplayProfileSource {
+    fn to_profile(&self) -> Option<ColorProfile> {
+        match self {
+            DisplayProfileSource::Icc(data) => ColorProfile::new_from_slice(data).ok(),
+            DisplayProfileSource::Edid(data) => ColorProfile::new_from_edid(data).ok(),
+        }
+    }
+}
+
+/// Locates ICC profile of the primary display.
+///
+/// - Windows: profile associated with the screen device context.
+/// - macOS: ColorSync profile of the main display.
+/// - Linux: most recent display class profile in colord storage, then EDID of the first
+///   connected DRM output.
+///
+/// Returns `None` if nothing was found or profile can't be parsed.
+pub fn display_profile() -> Option<ColorProfile> {
+    locate_display_profile().and_then(|source| source.to_profile())
+}
+
+/// Polls display profile and reports when it changes,
+/// e.g. after user switched calibration or moved window to other display.
+///
+/// Call [DisplayProfileWatcher::poll] on display change events or on a timer.
+#[derive(Debug, Default)]
+pub struct DisplayProfileWatcher {
+    last: Option<DisplayProfileSource>,
+}
+
+impl DisplayProfileWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns new profile when it differs from the one seen on previous poll,
+    /// first poll returns current profile.
+    pub fn poll(&mut self) -> Option<ColorProfile> {
+        let source = locate_display_profile();
+        if source == self.last {
+            return None;
+        }
+        self.last = source;
+        self.last.as_ref().and_then(|source| source.to_profile())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn locate_display_profile() -> Option<DisplayProfileSource> {
+    linux::colord_profile()
+        .map(DisplayProfileSource::Icc)
+        .or_else(|| linux::drm_edid().map(DisplayProfileSource::Edid))
+}
+
+#[cfg(target_os = "windows")]
+fn locate_display_profile() -> Option<DisplayProfileSource> {
+    windows::icm_profile().map(DisplayProfileSource::Icc)
+}
+
+#[cfg(target_os = "macos")]
+fn locate_display_profile() -> Option<DisplayProfileSource> {
+    macos::main_display_icc().map(DisplayProfileSource::Icc)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn locate_display_profile() -> Option<DisplayProfileSource> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use crate::{ColorProfile, ProfileClass};
+    use std::path::PathBuf;
+
+    fn colord_dirs() -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if let Some(data_home) = std::env::var_os("XDG_DATA_HOME") {
+            dirs.push(PathBuf::from(data_home).join("icc"));
+        } else if let Some(home) = std::env::var_os("HOME") {
+            dirs.push(PathBuf::from(home).join(".local/share/icc"));
+        }
+        dirs.push(PathBuf::from("/var/lib/colord/icc"));
+        dirs
+    }
+
+    /// colord keeps assigned profiles of every device in its storage, the most recently
+    /// modified display class one is the latest assigned or calibrated display profile.
+    pub(super) fn colord_profile() -> Option<Vec<u8>> {
+        let mut candidates = colord_dirs()
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.filter_map(|e| e.ok()))
+            .filter(|e| {
+                e.path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext.eq_ignore_ascii_case("icc") || ext.eq_ignore_ascii_case("icm"))
+                    .unwrap_or(false)
+            })
+            .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.0));
+        // Printer and scanner profiles share the storage
+        candidates.iter().find_map(|(_, path)| {
+            let data = std::fs::read(path).ok()?;
+            let profile = ColorProfile::new_from_slice(&data).ok()?;
+            (profile.profile_class == ProfileClass::DisplayDevice).then_some(data)
+        })
+    }
+
+    pub(super) fn drm_edid() -> Option<Vec<u8>> {
+        let mut connectors = std::fs::read_dir("/sys/class/drm")
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect::<Vec<PathBuf>>();
+        connectors.sort();
+        connectors.iter().find_map(|connector| {
+            let status = std::fs::read_to_string(connector.join("status")).ok()?;
+            if status.trim() != "connected" {
+                return None;
+            }
+            std::fs::read(connector.join("edid"))
+                .ok()
+                .filter(|edid| edid.len() >= 128)
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStringExt;
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetDC(hwnd: *mut c_void) -> *mut c_void;
+        fn ReleaseDC(hwnd: *mut c_void, hdc: *mut c_void) -> i32;
+    }
+
+    #[link(name = "gdi32")]
+    unsafe extern "system" {
+        fn GetICMProfileW(hdc: *mut c_void, size: *mut u32, filename: *mut u16) -> i32;
+    }
+
+    pub(super) fn icm_profile() -> Option<Vec<u8>> {
+        const MAX_PATH: usize = 260;
+        let mut filename = [0u16; MAX_PATH];
+        let mut size = MAX_PATH as u32;
+        let succeed = unsafe {
+            let hdc = GetDC(std::ptr::null_mut());
+            if hdc.is_null() {
+                return None;
+            }
+            let result = GetICMProfileW(hdc, &mut size, filename.as_mut_ptr());
+            ReleaseDC(std::ptr::null_mut(), hdc);
+            result != 0
+        };
+        if !succeed {
+            return None;
+        }
+        let len = filename.iter().position(|&c| c == 0).unwrap_or(MAX_PATH);
+        let path = std::ffi::OsString::from_wide(&filename[..len]);
+        std::fs::read(path).ok()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::c_void;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGMainDisplayID() -> u32;
+        fn CGDisplayCopyColorSpace(display: u32) -> *const c_void;
+        fn CGColorSpaceCopyICCData(space: *const c_void) -> *const c_void;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFDataGetLength(data: *const c_void) -> isize;
+        fn CFDataGetBytePtr(data: *const c_void) -> *const u8;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    pub(super) fn main_display_icc() -> Option<Vec<u8>> {
+        unsafe {
+            let space = CGDisplayCopyColorSpace(CGMainDisplayID());
+            if space.is_null() {
+                return None;
+            }
+            let data = CGColorSpaceCopyICCData(space);
+            CFRelease(space);
+            if data.is_null() {
+                return None;
+            }
+            let len = CFDataGetLength(data);
+            let ptr = CFDataGetBytePtr(data);
+            let icc = if len > 0 && !ptr.is_null() {
+                Some(std::slice::from_raw_parts(ptr, len as usize).to_vec())
+            } else {
+                None
+            };
+            CFRelease(data);
+            icc
+        }
+    }
+}
@@ -0,0 +1,242 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::conversions::do_any_to_any;
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, InterpolationMethod, Layout, TransformOptions,
+};
+
+/// Interpolation error of a built transform.
+///
+/// Error is measured in normalized output units against the same profile pair
+/// evaluated stage by stage, without baking into a sampled grid.
+/// Samples are taken between grid nodes, where interpolation error is the largest.
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Default)]
+pub struct InterpolationError {
+    /// Maximum absolute error found over all samples and channels
+    pub max: f32,
+    /// Mean absolute error over all samples and channels
+    pub mean: f32,
+    /// Number of evaluated pixels
+    pub samples: usize,
+}
+
+impl InterpolationError {
+    /// Maximum error expressed in code values of the given bit depth.
+    ///
+    /// Rounding of the output adds at most one more code value.
+    pub fn max_ulp(&self, bit_depth: usize) -> u32 {
+        let max_value = ((1u64 << bit_depth.min(32)) - 1) as f64;
+        (self.max as f64 * max_value).ceil() as u32
+    }
+}
+
+/// Points per axis, chosen to hit node midpoints of
+/// 33 grid for three channels and 17 grid for four channels.
+fn samples_per_axis(dimensions: usize) -> usize {
+    match dimensions {
+        0..=3 => 65,
+        4 => 33,
+        5 => 9,
+        _ => 5,
+    }
+}
+
+impl ColorProfile {
+    /// Estimates interpolation error of the transform built with `options`.
+    ///
+    /// Transform is evaluated in `f32` to keep output quantization out of the numbers,
+    /// so result describes interpolation method and grid that the transform uses.
+    /// Estimation evaluates a dense set of points, it is much slower than building
+    /// the transform itself and is meant for validation rather than for the hot path.
+    pub fn interpolation_error(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<InterpolationError, CmsError> {
+        // Reference interpolates profile CLUTs with the same method,
+        // so only error of the transform own grid is reported.
+        let options = TransformOptions {
+            interpolation_method: match options.interpolation_method {
+                InterpolationMethod::Auto => {
+                    self.auto_interpolation_method(dst_pr, options.rendering_intent)
+                }
+                method => method,
+            },
+            ..options
+        };
+        let transform = self.create_transform_f32(src_layout, dst_pr, dst_layout, options)?;
+        let reference =
+            do_any_to_any::<f32, 1, 65536, 32768>(src_layout, self, dst_layout, dst_pr, options)?;
+
+        let src_channels = src_layout.channels();
+        let has_alpha = src_layout.has_alpha()
+            && (self.color_space == DataColorSpace::Rgb
+                || self.color_space == DataColorSpace::Gray);
+        let dimensions = if has_alpha {
+            src_channels - 1
+        } else {
+            src_channels
+        };
+        let steps = samples_per_axis(dimensions);
+        let total = steps.pow(dimensions as u32);
+        let scale = 1. / (steps - 1) as f32;
+
+        let mut src = vec![1f32; total * src_channels];
+        for (i, pixel) in src.chunks_exact_mut(src_channels).enumerate() {
+            let mut index = i;
+            for v in pixel.iter_mut().take(dimensions) {
+                *v = (index % steps) as f32 * scale;
+                index /= steps;
+            }
+        }
+
+        let dst_channels = dst_layout.channels();
+        let mut dst = vec![0f32; total * dst_channels];
+        let mut expected = vec![0f32; total * dst_channels];
+        transform.transform(&src, &mut dst)?;
+        reference.transform(&src, &mut expected)?;
+
+        // Integer outputs are clamped, float outputs of device spaces compared the same way
+        let clamp =
+            dst_pr.color_space != DataColorSpace::Lab && dst_pr.color_space != DataColorSpace::Xyz;
+        let mut max = 0f32;
+        let mut sum = 0f64;
+        for (&a, &b) in dst.iter().zip(expected.iter()) {
+            let diff = if clamp {
+                (a.max(0.).min(1.) - b.max(0.).min(1.)).abs()
+            } else {
+                (a - b).abs()
+            };
+            max = max.max(diff);
+            sum += diff as f64;
+        }
+        Ok(InterpolationError {
+            max,
+            mean: if dst.is_empty() {
+                0.
+            } else {
+                (sum / dst.len() as f64) as f32
+            },
+            samples: total,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RenderingIntent;
+
+    #[test]
+    fn test_interpolation_error_matrix_shaper() {
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let error = srgb
+            .interpolation_error(
+                Layout::Rgb,
+                &bt2020,
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(error.samples, 65 * 65 * 65);
+        assert!(error.max < 1e-3, "Max error {}", error.max);
+        assert!(error.mean <= error.max);
+        assert_eq!(error.max_ulp(8), 1);
+    }
+
+    #[test]
+    fn test_interpolation_error_lut() {
+        if let Ok(srgb_perceptual_icc) = std::fs::read("./assets/srgb_perceptual.icc") {
+            let src_profile = ColorProfile::new_from_slice(&srgb_perceptual_icc).unwrap();
+            let dst_profile = ColorProfile::new_srgb();
+            let error = src_profile
+                .interpolation_error(
+                    Layout::Rgb,
+                    &dst_profile,
+                    Layout::Rgb,
+                    TransformOptions {
+                        rendering_intent: RenderingIntent::Perceptual,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            assert!(error.max > 0.);
+            assert!(error.max < 0.1, "Max error {}", error.max);
+            assert!(error.mean < 0.005, "Mean error {}", error.mean);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "options")]
+    fn test_interpolation_error_ulp_per_method() {
+        if let Ok(srgb_perceptual_icc) = std::fs::read("./assets/srgb_perceptual.icc") {
+            let src_profile = ColorProfile::new_from_slice(&srgb_perceptual_icc).unwrap();
+            let dst_profile = ColorProfile::new_srgb();
+            // Q1.14 tables of float transforms saturate on out of gamut nodes,
+            // contract is stated for float weights.
+            for (interpolation_method, max_ulp_8, max_ulp_16) in [
+                (InterpolationMethod::Tetrahedral, 22, 5417),
+                (InterpolationMethod::Pyramid, 22, 5417),
+                (InterpolationMethod::Prism, 22, 5417),
+                (InterpolationMethod::Linear, 22, 5417),
+            ] {
+                let error = src_profile
+                    .interpolation_error(
+                        Layout::Rgb,
+                        &dst_profile,
+                        Layout::Rgb,
+                        TransformOptions {
+                            interpolation_method,
+                            prefer_fixed_point: false,
+                            ..Default::default()
+                        },
+                    )
+                    .unwrap();
+                assert!(
+                    error.max_ulp(8) <= max_ulp_8,
+                    "{interpolation_method:?}: 8 bit ULP {}",
+                    error.max_ulp(8)
+                );
+                assert!(
+                    error.max_ulp(16) <= max_ulp_16,
+                    "{interpolation_method:?}: 16 bit ULP {}",
+                    error.max_ulp(16)
+                );
+                assert!(
+                    error.mean < 0.0015,
+                    "{interpolation_method:?}: mean error {}",
+                    error.mean
+                );
+            }
+        }
+    }
+}
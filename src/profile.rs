@@ -30,15 +30,24 @@ use crate::chad::BRADFORD_D;
 use crate::cicp::{
     CicpColorPrimaries, ColorPrimaries, MatrixCoefficients, TransferCharacteristics,
 };
+use crate::custom_tag::{CustomTag, custom_tag_handler};
 use crate::dat::ColorDateTime;
-use crate::err::CmsError;
+use crate::err::{CmsError, LayoutChannels};
 use crate::matrix::{Matrix3f, Xyz};
+use crate::mpe::MultiProcessType;
+use crate::named::NamedColorList;
 use crate::reader::s15_fixed16_number_to_float;
 use crate::safe_math::{SafeAdd, SafeMul};
 use crate::tag::{TAG_SIZE, Tag};
-use crate::trc::ToneReprCurve;
-use crate::{Chromaticity, Layout, Matrix3d, Vector3d, XyY, Xyzd, adapt_to_d50_d};
+use crate::trace::trace_span;
+use crate::trc::{ToneReprCurve, invert_lut};
+use crate::{
+    Chromaticity, Layout, Matrix3d, SpectralPcs, SpectralPcsHandler, TransformOptions, Vector3d,
+    VideoCardGamma, XyY, Xyzd, adapt_to_d50_d,
+};
+use std::any::Any;
 use std::io::Read;
+use std::sync::Arc;
 
 const MAX_PROFILE_SIZE: usize = 1024 * 1024 * 10; // 10 MB max, for Fogra39 etc
 
@@ -82,6 +91,8 @@ pub enum ProfileVersion {
     V4_3 = 0x04300000,
     #[default]
     V4_4 = 0x04400000,
+    V5_0 = 0x05000000,
+    V5_1 = 0x05100000,
     Unknown,
 }
 
@@ -98,7 +109,9 @@ impl TryFrom<u32> for ProfileVersion {
             0x04100000 => Ok(ProfileVersion::V4_1),
             0x04200000 => Ok(ProfileVersion::V4_2),
             0x04300000 => Ok(ProfileVersion::V4_3),
-            0x04400000 => Ok(ProfileVersion::V4_3),
+            0x04400000 => Ok(ProfileVersion::V4_4),
+            0x05000000 => Ok(ProfileVersion::V5_0),
+            0x05100000 => Ok(ProfileVersion::V5_1),
             _ => Err(CmsError::InvalidProfile),
         }
     }
@@ -117,11 +130,18 @@ impl From<ProfileVersion> for u32 {
             ProfileVersion::V4_2 => 0x04200000,
             ProfileVersion::V4_3 => 0x04300000,
             ProfileVersion::V4_4 => 0x04400000,
+            ProfileVersion::V5_0 => 0x05000000,
+            ProfileVersion::V5_1 => 0x05100000,
             ProfileVersion::Unknown => 0x02000000,
         }
     }
 }
 
+/// Maximum count of device channels, ICC defines color spaces up to 15 colorants.
+///
+/// LUTs with more input or output channels are rejected when profile is read.
+pub const MAX_CHANNELS: usize = 15;
+
 #[repr(u32)]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Default, Hash)]
 pub enum DataColorSpace {
@@ -171,25 +191,57 @@ impl DataColorSpace {
             DataColorSpace::Color2 => layout != Layout::GrayAlpha,
             DataColorSpace::Color3 => layout != Layout::Rgb,
             DataColorSpace::Color4 => layout != Layout::Rgba,
-            DataColorSpace::Color5 => layout != Layout::Inks5,
-            DataColorSpace::Color6 => layout != Layout::Inks6,
-            DataColorSpace::Color7 => layout != Layout::Inks7,
-            DataColorSpace::Color8 => layout != Layout::Inks8,
-            DataColorSpace::Color9 => layout != Layout::Inks9,
-            DataColorSpace::Color10 => layout != Layout::Inks10,
-            DataColorSpace::Color11 => layout != Layout::Inks11,
-            DataColorSpace::Color12 => layout != Layout::Inks12,
-            DataColorSpace::Color13 => layout != Layout::Inks13,
-            DataColorSpace::Color14 => layout != Layout::Inks14,
-            DataColorSpace::Color15 => layout != Layout::Inks15,
+            DataColorSpace::Color5
+            | DataColorSpace::Color6
+            | DataColorSpace::Color7
+            | DataColorSpace::Color8
+            | DataColorSpace::Color9
+            | DataColorSpace::Color10
+            | DataColorSpace::Color11
+            | DataColorSpace::Color12
+            | DataColorSpace::Color13
+            | DataColorSpace::Color14
+            | DataColorSpace::Color15 => !layout.is_inks() || layout.channels() != self.channels(),
         };
         if unsupported {
-            Err(CmsError::InvalidLayout)
+            Err(CmsError::InvalidLayoutChannels(LayoutChannels {
+                expected: self.channels(),
+                actual: layout.channels(),
+            }))
         } else {
             Ok(())
         }
     }
 
+    /// Layout that holds values of this color space without alpha
+    pub const fn suggested_layout(self) -> Layout {
+        match Layout::try_from_inks(self.channels()) {
+            Some(layout) => layout,
+            None => Layout::Rgb,
+        }
+    }
+
+    /// Number of channels of values in this color space
+    pub const fn channels(self) -> usize {
+        match self {
+            DataColorSpace::Gray => 1,
+            DataColorSpace::Color2 => 2,
+            DataColorSpace::Cmyk | DataColorSpace::Color4 => 4,
+            DataColorSpace::Color5
+            | DataColorSpace::Color6
+            | DataColorSpace::Color7
+            | DataColorSpace::Color8
+            | DataColorSpace::Color9
+            | DataColorSpace::Color10
+            | DataColorSpace::Color11
+            | DataColorSpace::Color12
+            | DataColorSpace::Color13
+            | DataColorSpace::Color14
+            | DataColorSpace::Color15 => self as usize - DataColorSpace::Color5 as usize + 5,
+            _ => 3,
+        }
+    }
+
     pub(crate) fn is_three_channels(self) -> bool {
         matches!(
             self,
@@ -256,10 +308,14 @@ impl From<ProfileClass> for u32 {
     }
 }
 
+/// LUT table samples.
+///
+/// Tables are reference counted, so clones of a profile share them instead of copying
+/// what might be megabytes of CLUT. Use `.into()` to build one from a `Vec`.
 #[derive(Debug, Clone, PartialEq)]
 pub enum LutStore {
-    Store8(Vec<u8>),
-    Store16(Vec<u16>),
+    Store8(Arc<[u8]>),
+    Store16(Arc<[u16]>),
 }
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
@@ -483,10 +539,49 @@ impl From<u32> for TechnologySignatures {
     }
 }
 
+impl From<TechnologySignatures> for u32 {
+    fn from(value: TechnologySignatures) -> Self {
+        let signature = match value {
+            TechnologySignatures::FilmScanner => *b"fscn",
+            TechnologySignatures::DigitalCamera => *b"dcam",
+            TechnologySignatures::ReflectiveScanner => *b"rscn",
+            TechnologySignatures::InkJetPrinter => *b"ijet",
+            TechnologySignatures::ThermalWaxPrinter => *b"twax",
+            TechnologySignatures::ElectrophotographicPrinter => *b"epho",
+            TechnologySignatures::ElectrostaticPrinter => *b"esta",
+            TechnologySignatures::DyeSublimationPrinter => *b"dsub",
+            TechnologySignatures::PhotographicPaperPrinter => *b"rpho",
+            TechnologySignatures::FilmWriter => *b"fprn",
+            TechnologySignatures::VideoMonitor => *b"vidm",
+            TechnologySignatures::VideoCamera => *b"vidc",
+            TechnologySignatures::ProjectionTelevision => *b"pjtv",
+            TechnologySignatures::CathodeRayTubeDisplay => *b"CRT ",
+            TechnologySignatures::PassiveMatrixDisplay => *b"PMD ",
+            TechnologySignatures::ActiveMatrixDisplay => *b"AMD ",
+            TechnologySignatures::LiquidCrystalDisplay => *b"LCD ",
+            TechnologySignatures::OrganicLedDisplay => *b"OLED",
+            TechnologySignatures::PhotoCd => *b"KPCD",
+            TechnologySignatures::PhotographicImageSetter => *b"imgs",
+            TechnologySignatures::Gravure => *b"grav",
+            TechnologySignatures::OffsetLithography => *b"offs",
+            TechnologySignatures::Silkscreen => *b"silk",
+            TechnologySignatures::Flexography => *b"flex",
+            TechnologySignatures::MotionPictureFilmScanner => *b"mpfs",
+            TechnologySignatures::MotionPictureFilmRecorder => *b"mpfr",
+            TechnologySignatures::DigitalMotionPictureCamera => *b"dmpc",
+            TechnologySignatures::DigitalCinemaProjector => *b"dcpj",
+            TechnologySignatures::Unknown(value) => return value,
+        };
+        u32::from_be_bytes(signature)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum LutWarehouse {
     Lut(LutDataType),
     Multidimensional(LutMultidimensionalType),
+    /// Floating point `mpet` transform, read from `D2Bx`/`B2Dx` tags
+    MultiProcess(MultiProcessType),
 }
 
 impl PartialEq for LutWarehouse {
@@ -494,6 +589,7 @@ impl PartialEq for LutWarehouse {
         match (self, other) {
             (LutWarehouse::Lut(a), LutWarehouse::Lut(b)) => a == b,
             (LutWarehouse::Multidimensional(a), LutWarehouse::Multidimensional(b)) => a == b,
+            (LutWarehouse::MultiProcess(a), LutWarehouse::MultiProcess(b)) => a == b,
             _ => false, // Different variants are not equal
         }
     }
@@ -515,6 +611,17 @@ pub struct LutDataType {
 }
 
 impl LutDataType {
+    /// Matrix applied before input curves when [TransformOptions::lcms_lut_matrix] is set.
+    pub(crate) fn compat_input_matrix(&self, options: TransformOptions) -> Option<Matrix3f> {
+        if !options.lcms_lut_matrix
+            || self.num_input_channels != 3
+            || self.matrix == Matrix3d::IDENTITY
+        {
+            return None;
+        }
+        Some(self.matrix.to_f32())
+    }
+
     pub(crate) fn has_same_kind(&self) -> bool {
         matches!(
             (&self.input_table, &self.clut_table, &self.output_table),
@@ -632,6 +739,23 @@ impl ProfileHeader {
         }
     }
 
+    /// iccMAX profiles may declare only spectral PCS, leaving colorimetric PCS empty.
+    /// XYZ is then used as a placeholder, actual connection is described by spectral PCS.
+    fn read_pcs(pcs: u32, spectral_pcs: u32) -> Result<DataColorSpace, CmsError> {
+        if pcs == 0 && spectral_pcs != 0 {
+            return Ok(DataColorSpace::Xyz);
+        }
+        DataColorSpace::try_from(pcs)
+    }
+
+    /// Spectral PCS signature, only defined by v5 (iccMAX) profiles
+    pub(crate) fn spectral_pcs(&self) -> Result<Option<SpectralPcs>, CmsError> {
+        if self.version < ProfileVersion::V5_0 {
+            return Ok(None);
+        }
+        SpectralPcs::from_signature(u32::from_be_bytes(self.reserved[0..4].try_into().unwrap()))
+    }
+
     /// Creates profile from the buffer
     pub(crate) fn new_from_slice(slice: &[u8]) -> Result<Self, CmsError> {
         if slice.len() < size_of::<ProfileHeader>() {
@@ -655,7 +779,10 @@ impl ProfileHeader {
             data_color_space: DataColorSpace::try_from(u32::from_be_bytes(
                 buffer[16..20].try_into().unwrap(),
             ))?,
-            pcs: DataColorSpace::try_from(u32::from_be_bytes(buffer[20..24].try_into().unwrap()))?,
+            pcs: Self::read_pcs(
+                u32::from_be_bytes(buffer[20..24].try_into().unwrap()),
+                u32::from_be_bytes(buffer[100..104].try_into().unwrap()),
+            )?,
             creation_date_time: ColorDateTime::new_from_slice(buffer[24..36].try_into().unwrap())?,
             signature: ProfileSignature::try_from(u32::from_be_bytes(
                 buffer[36..40].try_into().unwrap(),
@@ -801,6 +928,16 @@ impl From<u32> for MeasurementGeometry {
     }
 }
 
+impl From<MeasurementGeometry> for u32 {
+    fn from(value: MeasurementGeometry) -> Self {
+        match value {
+            MeasurementGeometry::Unknown => 0,
+            MeasurementGeometry::D45to45 => 1,
+            MeasurementGeometry::D0to0 => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum StandardIlluminant {
     Unknown,
@@ -883,6 +1020,22 @@ pub struct ColorProfile {
     pub lut_b_to_a_perceptual: Option<LutWarehouse>,
     pub lut_b_to_a_colorimetric: Option<LutWarehouse>,
     pub lut_b_to_a_saturation: Option<LutWarehouse>,
+    /// Spectral PCS declared by iccMAX profiles
+    pub spectral_pcs: Option<SpectralPcs>,
+    /// Raw `D2B0` tag when it is not parsed into [ColorProfile::lut_a_to_b_perceptual]
+    pub lut_d_to_b_perceptual: Option<Vec<u8>>,
+    /// Raw `D2B1` tag when it is not parsed into [ColorProfile::lut_a_to_b_colorimetric]
+    pub lut_d_to_b_colorimetric: Option<Vec<u8>>,
+    /// Raw `D2B2` tag when it is not parsed into [ColorProfile::lut_a_to_b_saturation]
+    pub lut_d_to_b_saturation: Option<Vec<u8>>,
+    /// Raw `B2D0` tag when it is not parsed into [ColorProfile::lut_b_to_a_perceptual]
+    pub lut_b_to_d_perceptual: Option<Vec<u8>>,
+    /// Raw `B2D1` tag when it is not parsed into [ColorProfile::lut_b_to_a_colorimetric]
+    pub lut_b_to_d_colorimetric: Option<Vec<u8>>,
+    /// Raw `B2D2` tag when it is not parsed into [ColorProfile::lut_b_to_a_saturation]
+    pub lut_b_to_d_saturation: Option<Vec<u8>>,
+    /// Handler used to connect profile through its spectral PCS
+    pub spectral_pcs_handler: Option<Arc<dyn SpectralPcsHandler>>,
     pub gamut: Option<LutWarehouse>,
     pub copyright: Option<ProfileText>,
     pub description: Option<ProfileText>,
@@ -893,9 +1046,34 @@ pub struct ColorProfile {
     pub viewing_conditions_description: Option<ProfileText>,
     pub technology: Option<TechnologySignatures>,
     pub calibration_date: Option<ColorDateTime>,
+    /// Display calibration curves from `vcgt` tag
+    pub video_card_gamma: Option<VideoCardGamma>,
+    /// Spot colors from `ncl2` tag of [ProfileClass::Named] profile
+    pub named_colors: Option<NamedColorList>,
+    /// Tags parsed by handlers from [crate::register_custom_tag_handler]
+    pub custom_tags: Vec<CustomTag>,
     /// Version for internal and viewing purposes only.
     /// On encoding added value to profile will always be V4.
     pub(crate) version_internal: ProfileVersion,
+    /// `A2Bx` and `B2Ax` overridden by `D2Bx` and `B2Dx` in the same order,
+    /// written back for CMMs without multi process elements support.
+    pub(crate) lut_fallbacks: [Option<LutWarehouse>; 6],
+    /// Issues that were repaired while reading the profile.
+    pub(crate) warnings: Vec<ProfileWarning>,
+    /// Set by [ColorProfile::sanitized], header creation time is not written on encoding.
+    pub(crate) sanitized: bool,
+}
+
+/// Non-fatal issue found and repaired while reading a profile
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Ord, Eq, Hash)]
+pub enum ProfileWarning {
+    /// `wtpt` tag is missing, media white point was derived as sum of colorants
+    MediaWhitePointFromColorants,
+    /// Header illuminant is zeroed, it was derived as sum of colorants
+    IlluminantFromColorants,
+    /// `B2A` output tables are truncated, they were rebuilt by inverting input tables
+    /// of the matching `A2B`, see [ParsingOptions::repair_output_tables]
+    OutputTablesRegenerated,
 }
 
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Hash)]
@@ -906,6 +1084,12 @@ pub struct ParsingOptions {
     pub max_allowed_clut_size: usize,
     // Maximum allowed TRC size in elements count
     pub max_allowed_trc_size: usize,
+    /// Rebuilds truncated `lut8`/`lut16` output tables of `B2A` tags instead of failing.
+    ///
+    /// Device side curves are inverted from input tables of the `A2B` with the same intent,
+    /// or perceptual one, and are identity when the profile has no suitable `A2B`.
+    /// Repaired profile reports [ProfileWarning::OutputTablesRegenerated].
+    pub repair_output_tables: bool,
 }
 
 impl Default for ParsingOptions {
@@ -914,6 +1098,7 @@ impl Default for ParsingOptions {
             max_profile_size: MAX_PROFILE_SIZE,
             max_allowed_clut_size: 10_000_000,
             max_allowed_trc_size: 40_000,
+            repair_output_tables: false,
         }
     }
 }
@@ -924,6 +1109,11 @@ impl ColorProfile {
         self.version_internal
     }
 
+    /// Returns issues that were repaired while reading the profile
+    pub fn warnings(&self) -> &[ProfileWarning] {
+        &self.warnings
+    }
+
     pub fn new_from_slice(slice: &[u8]) -> Result<Self, CmsError> {
         Self::new_from_slice_with_options(slice, Default::default())
     }
@@ -932,6 +1122,7 @@ impl ColorProfile {
         slice: &[u8],
         options: ParsingOptions,
     ) -> Result<Self, CmsError> {
+        let _span = trace_span!("moxcms::parse_profile", size = slice.len());
         let header = ProfileHeader::new_from_slice(slice)?;
         let tags_count = header.tag_count as usize;
         if slice.len() >= options.max_profile_size {
@@ -951,6 +1142,7 @@ impl ColorProfile {
             color_space: header.data_color_space,
             white_point: header.illuminant.to_xyzd(),
             version_internal: header.version,
+            spectral_pcs: header.spectral_pcs()?,
             ..Default::default()
         };
         let color_space = profile.color_space;
@@ -958,7 +1150,7 @@ impl ColorProfile {
             let tag_value = u32::from_be_bytes([tag[0], tag[1], tag[2], tag[3]]);
             let tag_entry = u32::from_be_bytes([tag[4], tag[5], tag[6], tag[7]]);
             let tag_size = u32::from_be_bytes([tag[8], tag[9], tag[10], tag[11]]) as usize;
-            // Just ignore unknown tags
+            // Unknown tags are ignored, unless application registered handler for them
             if let Ok(tag) = Tag::try_from(tag_value) {
                 match tag {
                     Tag::RedXyz => {
@@ -1077,6 +1269,30 @@ impl ColorProfile {
                         profile.lut_b_to_a_saturation =
                             Self::read_lut_tag(slice, tag_entry, tag_size, &options)?;
                     }
+                    Tag::DeviceToSpectralPcsPerceptual => {
+                        profile.lut_d_to_b_perceptual =
+                            Self::read_raw_tag(slice, tag_entry as usize, tag_size)?;
+                    }
+                    Tag::DeviceToSpectralPcsColorimetric => {
+                        profile.lut_d_to_b_colorimetric =
+                            Self::read_raw_tag(slice, tag_entry as usize, tag_size)?;
+                    }
+                    Tag::DeviceToSpectralPcsSaturation => {
+                        profile.lut_d_to_b_saturation =
+                            Self::read_raw_tag(slice, tag_entry as usize, tag_size)?;
+                    }
+                    Tag::SpectralPcsToDevicePerceptual => {
+                        profile.lut_b_to_d_perceptual =
+                            Self::read_raw_tag(slice, tag_entry as usize, tag_size)?;
+                    }
+                    Tag::SpectralPcsToDeviceColorimetric => {
+                        profile.lut_b_to_d_colorimetric =
+                            Self::read_raw_tag(slice, tag_entry as usize, tag_size)?;
+                    }
+                    Tag::SpectralPcsToDeviceSaturation => {
+                        profile.lut_b_to_d_saturation =
+                            Self::read_raw_tag(slice, tag_entry as usize, tag_size)?;
+                    }
                     Tag::Gamut => {
                         profile.gamut = Self::read_lut_tag(slice, tag_entry, tag_size, &options)?;
                     }
@@ -1117,12 +1333,149 @@ impl ColorProfile {
                         profile.calibration_date =
                             Self::read_date_time_tag(slice, tag_entry as usize, tag_size)?;
                     }
+                    Tag::VideoCardGamma => {
+                        profile.video_card_gamma =
+                            Self::read_vcgt_tag(slice, tag_entry as usize, tag_size)?;
+                    }
+                    Tag::NamedColor => {
+                        profile.named_colors =
+                            Self::read_named_color_tag(slice, tag_entry as usize, tag_size)?;
+                    }
+                }
+            } else if let Some(handler) = custom_tag_handler(tag_value) {
+                let tag_end = (tag_entry as usize).safe_add(tag_size)?;
+                if tag_end > slice.len() {
+                    return Err(CmsError::InvalidProfile);
                 }
+                let value = handler.parse(&slice[tag_entry as usize..tag_end])?;
+                profile.custom_tags.push(CustomTag {
+                    signature: tag_value,
+                    value: Arc::from(value),
+                });
             }
         }
 
+        // Float PCS `D2Bx`/`B2Dx` are interpreted only when PCS is colorimetric
+        if profile.spectral_pcs.is_none() {
+            profile.read_multi_process_tags(&options)?;
+        }
+        if profile.color_space == DataColorSpace::Rgb {
+            profile.derive_white_point_from_colorants();
+        }
+        if options.repair_output_tables {
+            profile.regenerate_output_tables()?;
+        }
+
         Ok(profile)
     }
+
+    /// Fills `B2A` output tables left empty by the reader, see [ParsingOptions::repair_output_tables].
+    /// Truncated tables of other tags cannot be repaired.
+    fn regenerate_output_tables(&mut self) -> Result<(), CmsError> {
+        let is_truncated = |lut: &Option<LutWarehouse>| match lut {
+            Some(LutWarehouse::Lut(lut)) => match &lut.output_table {
+                LutStore::Store8(v) => v.is_empty(),
+                LutStore::Store16(v) => v.is_empty(),
+            },
+            _ => false,
+        };
+        if is_truncated(&self.lut_a_to_b_perceptual)
+            || is_truncated(&self.lut_a_to_b_colorimetric)
+            || is_truncated(&self.lut_a_to_b_saturation)
+            || is_truncated(&self.gamut)
+        {
+            return Err(CmsError::InvalidProfile);
+        }
+
+        let forward = [
+            self.lut_a_to_b_perceptual.clone(),
+            self.lut_a_to_b_colorimetric.clone(),
+            self.lut_a_to_b_saturation.clone(),
+        ];
+        let mut regenerated = false;
+        for (i, lut) in [
+            &mut self.lut_b_to_a_perceptual,
+            &mut self.lut_b_to_a_colorimetric,
+            &mut self.lut_b_to_a_saturation,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if !is_truncated(lut) {
+                continue;
+            }
+            let Some(LutWarehouse::Lut(lut)) = lut else {
+                continue;
+            };
+            let device_tables = forward[i]
+                .as_ref()
+                .or(forward[0].as_ref())
+                .and_then(|x| match x {
+                    LutWarehouse::Lut(a2b) if a2b.num_input_channels == lut.num_output_channels => {
+                        Some(a2b)
+                    }
+                    _ => None,
+                });
+            let entries = lut.num_output_table_entries as usize;
+            let mut output = Vec::with_capacity(entries * lut.num_output_channels as usize);
+            for channel in 0..lut.num_output_channels as usize {
+                let curve = match device_tables {
+                    Some(a2b) => {
+                        let size = a2b.num_input_table_entries as usize;
+                        let table: Vec<u16> = match &a2b.input_table {
+                            LutStore::Store8(v) => v[channel * size..(channel + 1) * size]
+                                .iter()
+                                .map(|&x| u16::from_ne_bytes([x, x]))
+                                .collect(),
+                            LutStore::Store16(v) => {
+                                v[channel * size..(channel + 1) * size].to_vec()
+                            }
+                        };
+                        invert_lut(&table, entries)
+                    }
+                    None => (0..entries)
+                        .map(|x| ((x * 65535) as f64 / (entries - 1) as f64).round() as u16)
+                        .collect(),
+                };
+                output.extend_from_slice(&curve);
+            }
+            lut.output_table = match lut.output_table {
+                LutStore::Store8(_) => {
+                    LutStore::Store8(output.iter().map(|&x| (x >> 8) as u8).collect())
+                }
+                LutStore::Store16(_) => LutStore::Store16(output.into()),
+            };
+            regenerated = true;
+        }
+        if regenerated {
+            self.warnings.push(ProfileWarning::OutputTablesRegenerated);
+        }
+        Ok(())
+    }
+
+    /// Some malformed RGB profiles omit `wtpt` or zero the header illuminant.
+    /// Colorants sum is the white they were measured against, it is used instead of zeros.
+    fn derive_white_point_from_colorants(&mut self) {
+        let white = Xyzd::new(
+            self.red_colorant.x + self.green_colorant.x + self.blue_colorant.x,
+            self.red_colorant.y + self.green_colorant.y + self.blue_colorant.y,
+            self.red_colorant.z + self.green_colorant.z + self.blue_colorant.z,
+        );
+        let is_valid =
+            |xyz: Xyzd| xyz.y > 0. && xyz.x.is_finite() && xyz.y.is_finite() && xyz.z.is_finite();
+        if !is_valid(white) {
+            return;
+        }
+        if self.media_white_point.is_none() {
+            self.media_white_point = Some(white);
+            self.warnings
+                .push(ProfileWarning::MediaWhitePointFromColorants);
+        }
+        if !is_valid(self.white_point) {
+            self.white_point = white;
+            self.warnings.push(ProfileWarning::IlluminantFromColorants);
+        }
+    }
 }
 
 impl ColorProfile {
@@ -1287,6 +1640,18 @@ impl ColorProfile {
         dest_inverse.mat_mul(source)
     }
 
+    /// Ratio of this profile peak luminance to `dest` peak luminance from `lumi` tags.
+    ///
+    /// Linear light multiplied by this ratio keeps its absolute brightness in nits
+    /// when shown on the destination display.
+    /// Returns `None` if any of profiles doesn't have valid luminance.
+    pub fn luminance_scale(&self, dest: &ColorProfile) -> Option<f64> {
+        let src = self.luminance?.y;
+        let dst = dest.luminance?.y;
+        let is_valid = |v: f64| v > 0. && v.is_finite();
+        (is_valid(src) && is_valid(dst)).then(|| src / dst)
+    }
+
     /// Returns volume of colors stored in profile
     pub fn profile_volume(&self) -> Option<f32> {
         let red_prim = self.red_colorant;
@@ -1303,17 +1668,107 @@ impl ColorProfile {
         Some((det / 6.0f64) as f32)
     }
 
-    pub(crate) fn has_device_to_pcs_lut(&self) -> bool {
+    /// Returns true if profile has any device to PCS (`A2Bx`) LUT
+    pub fn has_device_to_pcs_lut(&self) -> bool {
         self.lut_a_to_b_perceptual.is_some()
             || self.lut_a_to_b_saturation.is_some()
             || self.lut_a_to_b_colorimetric.is_some()
     }
 
-    pub(crate) fn has_pcs_to_device_lut(&self) -> bool {
+    /// Returns true if profile has any PCS to device (`B2Ax`) LUT
+    pub fn has_pcs_to_device_lut(&self) -> bool {
         self.lut_b_to_a_perceptual.is_some()
             || self.lut_b_to_a_saturation.is_some()
             || self.lut_b_to_a_colorimetric.is_some()
     }
+
+    /// Returns true if profile is described with LUTs in any direction
+    pub fn is_lut_based(&self) -> bool {
+        self.has_device_to_pcs_lut() || self.has_pcs_to_device_lut()
+    }
+
+    /// Returns true if profile has only device to PCS LUTs, so it can be used only as a source
+    pub fn has_only_a2b(&self) -> bool {
+        self.has_device_to_pcs_lut() && !self.has_pcs_to_device_lut()
+    }
+
+    /// Layout matching profile device color space, see [DataColorSpace::suggested_layout]
+    pub fn suggested_layout(&self) -> Layout {
+        self.color_space.suggested_layout()
+    }
+
+    /// Number of device channels, e.g. 4 for CMYK
+    pub fn device_channel_count(&self) -> usize {
+        self.color_space.channels()
+    }
+
+    /// Number of profile connection space channels
+    pub fn pcs_channel_count(&self) -> usize {
+        self.pcs.channels()
+    }
+
+    /// Returns value of custom tag `signature` if it was parsed into `T`
+    pub fn custom_tag<T: Any>(&self, signature: u32) -> Option<&T> {
+        self.custom_tags
+            .iter()
+            .find(|tag| tag.signature == signature)
+            .and_then(|tag| tag.downcast_ref::<T>())
+    }
+
+    /// Returns copy of profile without identifying metadata.
+    ///
+    /// Description, copyright, device manufacturer and model, characterization target,
+    /// viewing conditions description and calibration date are removed, and creation time
+    /// is not written into header when the copy is encoded.
+    /// Everything that affects colorimetry is kept intact.
+    pub fn sanitized(&self) -> ColorProfile {
+        ColorProfile {
+            description: None,
+            copyright: None,
+            device_manufacturer: None,
+            device_model: None,
+            char_target: None,
+            viewing_conditions_description: None,
+            calibration_date: None,
+            sanitized: true,
+            ..self.clone()
+        }
+    }
+
+    /// Returns spectral PCS when profile has no colorimetric way from device to PCS
+    pub(crate) fn spectral_device_to_pcs(&self) -> Option<SpectralPcs> {
+        let spectral_pcs = self.spectral_pcs?;
+        if self.is_matrix_shaper() || self.has_device_to_pcs_lut() || self.gray_trc.is_some() {
+            return None;
+        }
+        Some(spectral_pcs)
+    }
+
+    /// Returns true if device to PCS connection for `intent` is floating point `mpet`
+    pub(crate) fn is_multi_process_device_to_pcs(&self, intent: RenderingIntent) -> bool {
+        !self.is_matrix_shaper()
+            && matches!(
+                self.get_device_to_pcs(intent),
+                Some(LutWarehouse::MultiProcess(_))
+            )
+    }
+
+    /// Returns true if PCS to device connection for `intent` is floating point `mpet`
+    pub(crate) fn is_multi_process_pcs_to_device(&self, intent: RenderingIntent) -> bool {
+        matches!(
+            self.get_pcs_to_device(intent),
+            Some(LutWarehouse::MultiProcess(_))
+        )
+    }
+
+    /// Returns spectral PCS when profile has no colorimetric way from PCS to device
+    pub(crate) fn spectral_pcs_to_device(&self) -> Option<SpectralPcs> {
+        let spectral_pcs = self.spectral_pcs?;
+        if self.is_matrix_shaper() || self.has_pcs_to_device_lut() || self.gray_trc.is_some() {
+            return None;
+        }
+        Some(spectral_pcs)
+    }
 }
 
 #[cfg(test)]
@@ -1329,6 +1784,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_srgb_with_white_point() {
+        use crate::{WHITE_POINT_D55, WHITE_POINT_D65};
+        let srgb = ColorProfile::new_srgb();
+        let srgb_d65 = ColorProfile::new_srgb_with_white_point(WHITE_POINT_D65);
+        assert!((srgb.red_colorant.x - srgb_d65.red_colorant.x).abs() < 1e-9);
+        assert!((srgb.green_colorant.y - srgb_d65.green_colorant.y).abs() < 1e-9);
+        assert!((srgb.blue_colorant.z - srgb_d65.blue_colorant.z).abs() < 1e-9);
+
+        let srgb_d55 = ColorProfile::new_srgb_with_white_point(WHITE_POINT_D55);
+        assert!(srgb_d55.cicp.is_none());
+        assert!((srgb_d55.red_colorant.x - srgb.red_colorant.x).abs() > 1e-4);
+        // Adapted colorants always sum up to PCS white
+        let white = srgb_d55
+            .rgb_to_xyz_matrix()
+            .mul_vector(Vector3d { v: [1., 1., 1.] });
+        let d50 = Chromaticity::D50.to_xyzd();
+        assert!((white.v[0] - d50.x).abs() < 1e-3);
+        assert!((white.v[1] - d50.y).abs() < 1e-3);
+        assert!((white.v[2] - d50.z).abs() < 1e-3);
+    }
+
     #[test]
     fn test_perceptual() {
         if let Ok(srgb_perceptual_icc) = fs::read("./assets/srgb_perceptual.icc") {
@@ -1362,6 +1839,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_lut_store_shared_between_clones() {
+        if let Ok(us_swop_coated) = fs::read("./assets/us_swop_coated.icc") {
+            let f_p = ColorProfile::new_from_slice(&us_swop_coated).unwrap();
+            let cloned = f_p.clone();
+            match (&f_p.lut_b_to_a_perceptual, &cloned.lut_b_to_a_perceptual) {
+                (Some(LutWarehouse::Lut(a)), Some(LutWarehouse::Lut(b))) => {
+                    match (&a.clut_table, &b.clut_table) {
+                        (LutStore::Store8(a), LutStore::Store8(b)) => assert!(Arc::ptr_eq(a, b)),
+                        (LutStore::Store16(a), LutStore::Store16(b)) => {
+                            assert!(Arc::ptr_eq(a, b))
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                _ => panic!("B2A0 of US SWOP Coated is lut8 or lut16"),
+            }
+        }
+    }
+
     #[test]
     fn test_matrix_shaper() {
         if let Ok(matrix_shaper) = fs::read("./assets/Display P3.icc") {
@@ -1382,4 +1879,175 @@ mod tests {
             assert!(f_p.description.is_some());
         }
     }
+
+    #[test]
+    fn test_introspection() {
+        if let Ok(us_swop_coated) = fs::read("./assets/us_swop_coated.icc") {
+            let f_p = ColorProfile::new_from_slice(&us_swop_coated).unwrap();
+            assert!(f_p.is_lut_based());
+            assert!(!f_p.has_only_a2b());
+            assert!(!f_p.is_matrix_shaper());
+            assert_eq!(f_p.device_channel_count(), 4);
+            assert_eq!(f_p.pcs_channel_count(), 3);
+        }
+        if let Ok(matrix_shaper) = fs::read("./assets/Display P3.icc") {
+            let f_p = ColorProfile::new_from_slice(&matrix_shaper).unwrap();
+            assert!(f_p.is_matrix_shaper());
+            assert!(!f_p.is_lut_based());
+            assert_eq!(f_p.device_channel_count(), 3);
+        }
+    }
+
+    #[test]
+    fn test_layout_channels_mismatch() {
+        let srgb = ColorProfile::new_srgb();
+        assert_eq!(srgb.suggested_layout(), Layout::Rgb);
+        assert_eq!(DataColorSpace::Cmyk.suggested_layout(), Layout::Rgba);
+        assert_eq!(DataColorSpace::Color7.suggested_layout(), Layout::Inks7);
+        assert_eq!(
+            DataColorSpace::Cmyk.check_layout(Layout::Rgb),
+            Err(CmsError::InvalidLayoutChannels(LayoutChannels {
+                expected: 4,
+                actual: 3
+            }))
+        );
+        let error = srgb
+            .create_transform_8bit(Layout::Gray, &srgb, Layout::Rgb, Default::default())
+            .err();
+        assert_eq!(
+            error,
+            Some(CmsError::InvalidLayoutChannels(LayoutChannels {
+                expected: 3,
+                actual: 1
+            }))
+        );
+    }
+
+    #[test]
+    fn test_white_point_from_colorants() {
+        let mut srgb = ColorProfile::new_srgb();
+        srgb.media_white_point = None;
+        let mut encoded = srgb.encode().unwrap();
+        let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+        assert_eq!(
+            decoded.warnings(),
+            &[ProfileWarning::MediaWhitePointFromColorants]
+        );
+        let white_point = decoded.media_white_point.unwrap();
+        let d50 = Chromaticity::D50.to_xyzd();
+        assert!((white_point.x - d50.x).abs() < 1e-3, "{white_point:?}");
+        assert!((white_point.y - d50.y).abs() < 1e-3, "{white_point:?}");
+        assert!((white_point.z - d50.z).abs() < 1e-3, "{white_point:?}");
+
+        // Header illuminant
+        encoded[68..80].fill(0);
+        let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+        assert_eq!(
+            decoded.warnings(),
+            &[
+                ProfileWarning::MediaWhitePointFromColorants,
+                ProfileWarning::IlluminantFromColorants
+            ]
+        );
+        assert!((decoded.white_point.y - d50.y).abs() < 1e-3);
+
+        let decoded =
+            ColorProfile::new_from_slice(&ColorProfile::new_srgb().encode().unwrap()).unwrap();
+        assert!(decoded.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_multidimensional_lut_channels() {
+        let options = ParsingOptions::default();
+        let make_tag = |inputs: u8, outputs: u8| {
+            let mut tag = vec![0u8; 48];
+            tag[..4].copy_from_slice(b"mAB ");
+            tag[8] = inputs;
+            tag[9] = outputs;
+            tag
+        };
+        for (inputs, outputs) in [(6, 3), (8, 8), (15, 4)] {
+            let tag = make_tag(inputs, outputs);
+            let lut = ColorProfile::read_lut_abm_type(&tag, 0, tag.len(), true, &options)
+                .unwrap()
+                .unwrap();
+            let LutWarehouse::Multidimensional(lut) = lut else {
+                panic!("mAB must be read as multidimensional LUT");
+            };
+            assert_eq!(lut.num_input_channels, inputs);
+            assert_eq!(lut.num_output_channels, outputs);
+        }
+        for (inputs, outputs) in [(16, 3), (3, 0)] {
+            let tag = make_tag(inputs, outputs);
+            assert!(matches!(
+                ColorProfile::read_lut_abm_type(&tag, 0, tag.len(), true, &options),
+                Err(CmsError::IncorrectlyFormedLut(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_repair_output_tables() {
+        let mut data = include_bytes!("../assets/us_swop_coated.icc").to_vec();
+        let tag_count = u32::from_be_bytes(data[128..132].try_into().unwrap()) as usize;
+        let entry = (0..tag_count)
+            .map(|i| 132 + i * 12)
+            .find(|&x| &data[x..x + 4] == b"B2A0")
+            .unwrap();
+        let size = u32::from_be_bytes(data[entry + 8..entry + 12].try_into().unwrap());
+        data[entry + 8..entry + 12].copy_from_slice(&(size - 100).to_be_bytes());
+
+        assert!(ColorProfile::new_from_slice(&data).is_err());
+        let repaired = ColorProfile::new_from_slice_with_options(
+            &data,
+            ParsingOptions {
+                repair_output_tables: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            repaired.warnings(),
+            &[ProfileWarning::OutputTablesRegenerated]
+        );
+        let Some(LutWarehouse::Lut(b2a)) = &repaired.lut_b_to_a_perceptual else {
+            panic!("B2A0 must be lut16");
+        };
+        let Some(LutWarehouse::Lut(a2b)) = &repaired.lut_a_to_b_perceptual else {
+            panic!("A2B0 must be lut16");
+        };
+        let (LutStore::Store16(output), LutStore::Store16(input)) =
+            (&b2a.output_table, &a2b.input_table)
+        else {
+            panic!("Tables must be 16 bit");
+        };
+        let entries = b2a.num_output_table_entries as usize;
+        assert_eq!(output.len(), entries * 4);
+        // Output curve of the first ink undoes the input curve of the first ink
+        let half = input[a2b.num_input_table_entries as usize / 2];
+        let position = half as usize * (entries - 1) / 65535;
+        let restored = output[position] as i32;
+        assert!((restored - 32767).abs() < 1024, "{restored}");
+
+        let srgb = ColorProfile::new_srgb();
+        srgb.create_transform_8bit(Layout::Rgb, &repaired, Layout::Rgba, Default::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sanitized() {
+        let mut srgb = ColorProfile::new_srgb();
+        srgb.description = Some(ProfileText::PlainString("Owner's display".to_string()));
+        srgb.device_model = Some(ProfileText::PlainString("SN-123456".to_string()));
+        let sanitized = srgb.sanitized();
+        let encoded = sanitized.encode().unwrap();
+        // No timestamp, so encoding is reproducible
+        assert_eq!(encoded, sanitized.encode().unwrap());
+        assert!(encoded[24..36].iter().all(|&v| v == 0));
+        let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+        assert!(decoded.description.is_none());
+        assert!(decoded.device_model.is_none());
+        let original = ColorProfile::new_from_slice(&srgb.encode().unwrap()).unwrap();
+        assert_eq!(decoded.rgb_to_xyz_matrix(), original.rgb_to_xyz_matrix());
+    }
 }
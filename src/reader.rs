@@ -28,15 +28,18 @@
  */
 use crate::err::try_vec;
 use crate::helpers::{read_matrix_3d, read_vector_3d};
-use crate::profile::LutDataType;
+use crate::mpe::{MPE_MAX_CHANNELS, MultiProcessType, ProcessElement};
+use crate::named::{NamedColor, NamedColorList};
+use crate::profile::{LutDataType, MAX_CHANNELS};
 use crate::safe_math::{SafeAdd, SafeMul, SafePowi};
 use crate::tag::{TAG_SIZE, TagTypeDefinition};
 use crate::{
-    CicpColorPrimaries, CicpProfile, CmsError, ColorDateTime, ColorProfile, DescriptionString,
-    LocalizableString, LutMultidimensionalType, LutStore, LutType, LutWarehouse, Matrix3d,
-    Matrix3f, MatrixCoefficients, Measurement, MeasurementGeometry, ParsingOptions, ProfileText,
-    StandardIlluminant, StandardObserver, TechnologySignatures, ToneReprCurve,
-    TransferCharacteristics, Vector3d, ViewingConditions, Xyz, Xyzd,
+    CicpColorPrimaries, CicpProfile, CmsError, ColorDateTime, ColorProfile, CurveSegment,
+    DescriptionString, LocalizableString, LutMultidimensionalType, LutStore, LutType, LutWarehouse,
+    Matrix3d, Matrix3f, MatrixCoefficients, Measurement, MeasurementGeometry, ParsingOptions,
+    ProfileText, SegmentFormula, SegmentedCurve, StandardIlluminant, StandardObserver,
+    TechnologySignatures, ToneReprCurve, TransferCharacteristics, Vector3d, VideoCardGamma,
+    VideoCardGammaFormula, ViewingConditions, Xyz, Xyzd,
 };
 
 /// Produces the nearest float to `a` with a maximum error of 1/1024 which
@@ -149,6 +152,22 @@ impl ColorProfile {
         }))
     }
 
+    /// Reads tag data as is, used for the tags that are not interpreted, like `multiProcessElementType`
+    pub(crate) fn read_raw_tag(
+        slice: &[u8],
+        entry: usize,
+        tag_size: usize,
+    ) -> Result<Option<Vec<u8>>, CmsError> {
+        if tag_size < TAG_SIZE {
+            return Ok(None);
+        }
+        let last_tag_offset = tag_size.safe_add(entry)?;
+        if last_tag_offset > slice.len() {
+            return Err(CmsError::InvalidProfile);
+        }
+        Ok(Some(slice[entry..last_tag_offset].to_vec()))
+    }
+
     pub(crate) fn read_string_tag(
         slice: &[u8],
         entry: usize,
@@ -301,13 +320,13 @@ impl ColorProfile {
             for (src, dst) in table.chunks_exact(2).zip(clut.iter_mut()) {
                 *dst = u16::from_be_bytes([src[0], src[1]]);
             }
-            Ok(LutStore::Store16(clut))
+            Ok(LutStore::Store16(clut.into()))
         } else if lut_type == LutType::Lut8 {
             let mut clut = try_vec![0u8; table.len()];
             for (&src, dst) in table.iter().zip(clut.iter_mut()) {
                 *dst = src;
             }
-            Ok(LutStore::Store8(clut))
+            Ok(LutStore::Store8(clut.into()))
         } else {
             unreachable!("This should never happen, report to https://github.com/awxkee/moxcms")
         }
@@ -369,8 +388,15 @@ impl ColorProfile {
         }
         let in_channels = tag[8];
         let out_channels = tag[9];
-        if in_channels > 4 && out_channels > 4 {
-            return Ok(None);
+        if in_channels == 0
+            || out_channels == 0
+            || in_channels as usize > MAX_CHANNELS
+            || out_channels as usize > MAX_CHANNELS
+        {
+            return Err(CmsError::IncorrectlyFormedLut(format!(
+                "LUT has {in_channels} inputs and {out_channels} outputs, \
+                 supported from 1 to {MAX_CHANNELS} channels"
+            )));
         }
         let a_curve_offset = u32::from_be_bytes([tag[28], tag[29], tag[30], tag[31]]) as usize;
         let clut_offset = u32::from_be_bytes([tag[24], tag[25], tag[26], tag[27]]) as usize;
@@ -658,12 +684,16 @@ impl ColorProfile {
         let output_size = (num_output_table_entries as usize).safe_mul(out_chan as usize)?;
 
         let shaped_output = output_offset.safe_add(output_size.safe_mul(entry_size)?)?;
-        if tag.len() < shaped_output {
+        let gamma_table = if tag.len() >= shaped_output {
+            let shaped_output_table = &tag[output_offset..shaped_output];
+            Self::read_lut_table_f32(shaped_output_table, lut_type)?
+        } else if parsing_options.repair_output_tables {
+            // Empty table is regenerated once all tags are read,
+            // see `ColorProfile::regenerate_output_tables`
+            Self::read_lut_table_f32(&[], lut_type)?
+        } else {
             return Err(CmsError::InvalidProfile);
-        }
-
-        let shaped_output_table = &tag[output_offset..shaped_output];
-        let gamma_table = Self::read_lut_table_f32(shaped_output_table, lut_type)?;
+        };
 
         let wh = LutWarehouse::Lut(LutDataType {
             num_input_table_entries,
@@ -797,6 +827,9 @@ impl ColorProfile {
             }
             *read_size = 12 + COUNT_TO_LENGTH[entry_count] * 4;
             Ok(Some(ToneReprCurve::Parametric(params)))
+        } else if curve_type == TagTypeDefinition::SegmentedCurve {
+            let curve = Self::read_segmented_curve(tag, read_size, options)?;
+            Ok(Some(ToneReprCurve::Segmented(curve)))
         } else {
             Err(CmsError::MalformedTrcCurve(
                 "Unknown parametric curve tag".to_string(),
@@ -804,6 +837,287 @@ impl ColorProfile {
         }
     }
 
+    fn read_segmented_curve(
+        tag: &[u8],
+        read_size: &mut usize,
+        options: &ParsingOptions,
+    ) -> Result<SegmentedCurve, CmsError> {
+        let exhausted = || CmsError::MalformedTrcCurve("Segmented curve exhausted".to_string());
+        let read_f32 = |offset: usize| -> Result<f32, CmsError> {
+            let bytes = tag.get(offset..offset.safe_add(4)?).ok_or_else(exhausted)?;
+            Ok(f32::from_bits(u32::from_be_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3],
+            ])))
+        };
+        let segments_count = u16::from_be_bytes([tag[8], tag[9]]) as usize;
+        if segments_count == 0 {
+            return Err(CmsError::MalformedTrcCurve(
+                "Segmented curve has no segments".to_string(),
+            ));
+        }
+        let mut offset = 12usize;
+        let mut breakpoints = Vec::with_capacity(segments_count - 1);
+        for _ in 0..segments_count - 1 {
+            breakpoints.push(read_f32(offset)?);
+            offset += 4;
+        }
+        let mut segments = Vec::with_capacity(segments_count);
+        let mut total_samples = 0usize;
+        for _ in 0..segments_count {
+            let header = tag
+                .get(offset..offset.safe_add(12)?)
+                .ok_or_else(exhausted)?;
+            let signature = [header[0], header[1], header[2], header[3]];
+            offset += 12;
+            if &signature == b"parf" {
+                let function_type = u16::from_be_bytes([header[8], header[9]]);
+                let params_count = match function_type {
+                    0 => 4,
+                    1 | 2 => 5,
+                    _ => {
+                        return Err(CmsError::MalformedTrcCurve(
+                            "Unknown segment function type".to_string(),
+                        ));
+                    }
+                };
+                let mut p = [0f32; 5];
+                for v in p.iter_mut().take(params_count) {
+                    *v = read_f32(offset)?;
+                    offset += 4;
+                }
+                let formula = match function_type {
+                    0 => SegmentFormula::Gamma {
+                        gamma: p[0],
+                        a: p[1],
+                        b: p[2],
+                        c: p[3],
+                    },
+                    1 => SegmentFormula::Log {
+                        gamma: p[0],
+                        a: p[1],
+                        b: p[2],
+                        c: p[3],
+                        d: p[4],
+                    },
+                    _ => SegmentFormula::Exp {
+                        a: p[0],
+                        b: p[1],
+                        c: p[2],
+                        d: p[3],
+                        e: p[4],
+                    },
+                };
+                segments.push(CurveSegment::Formula(formula));
+            } else if &signature == b"samf" {
+                let count =
+                    u32::from_be_bytes([header[8], header[9], header[10], header[11]]) as usize;
+                total_samples = total_samples.safe_add(count)?;
+                if total_samples > options.max_allowed_trc_size {
+                    return Err(CmsError::CurveLutIsTooLarge);
+                }
+                let mut samples = try_vec![0f32; count];
+                for v in samples.iter_mut() {
+                    *v = read_f32(offset)?;
+                    offset += 4;
+                }
+                segments.push(CurveSegment::Sampled(samples));
+            } else {
+                return Err(CmsError::MalformedTrcCurve(
+                    "Unknown curve segment".to_string(),
+                ));
+            }
+        }
+        *read_size = offset;
+        SegmentedCurve::new(breakpoints, segments)
+    }
+
+    /// Parses multi process elements of `D2Bx` and `B2Dx`, as ICC v4.3 requires they take
+    /// precedence over `A2Bx` and `B2Ax`. Overridden tables are kept as fallback for encoding.
+    ///
+    /// Malformed tag is kept raw when there is a table to fall back to.
+    pub(crate) fn read_multi_process_tags(
+        &mut self,
+        options: &ParsingOptions,
+    ) -> Result<(), CmsError> {
+        for ((raw, lut), fallback) in [
+            (
+                &mut self.lut_d_to_b_perceptual,
+                &mut self.lut_a_to_b_perceptual,
+            ),
+            (
+                &mut self.lut_d_to_b_colorimetric,
+                &mut self.lut_a_to_b_colorimetric,
+            ),
+            (
+                &mut self.lut_d_to_b_saturation,
+                &mut self.lut_a_to_b_saturation,
+            ),
+            (
+                &mut self.lut_b_to_d_perceptual,
+                &mut self.lut_b_to_a_perceptual,
+            ),
+            (
+                &mut self.lut_b_to_d_colorimetric,
+                &mut self.lut_b_to_a_colorimetric,
+            ),
+            (
+                &mut self.lut_b_to_d_saturation,
+                &mut self.lut_b_to_a_saturation,
+            ),
+        ]
+        .into_iter()
+        .zip(self.lut_fallbacks.iter_mut())
+        {
+            let Some(tag) = raw.as_deref() else {
+                continue;
+            };
+            if TagTypeDefinition::from(u32::from_be_bytes([tag[0], tag[1], tag[2], tag[3]]))
+                != TagTypeDefinition::MultiProcessElement
+            {
+                continue;
+            }
+            let mpe = match Self::read_multi_process_type(tag, options) {
+                Ok(mpe) => mpe,
+                Err(_) if lut.is_some() => continue,
+                Err(err) => return Err(err),
+            };
+            *fallback = lut.replace(LutWarehouse::MultiProcess(mpe));
+            *raw = None;
+        }
+        Ok(())
+    }
+
+    fn read_multi_process_type(
+        tag: &[u8],
+        options: &ParsingOptions,
+    ) -> Result<MultiProcessType, CmsError> {
+        let malformed =
+            || CmsError::IncorrectlyFormedLut("Multi process elements are malformed".to_string());
+        if tag.len() < 16 {
+            return Err(malformed());
+        }
+        let num_input_channels = u16::from_be_bytes([tag[8], tag[9]]);
+        let num_output_channels = u16::from_be_bytes([tag[10], tag[11]]);
+        if num_input_channels as usize > MPE_MAX_CHANNELS
+            || num_output_channels as usize > MPE_MAX_CHANNELS
+        {
+            return Err(malformed());
+        }
+        let elements_count = u32::from_be_bytes([tag[12], tag[13], tag[14], tag[15]]) as usize;
+        let positions = tag
+            .get(16..elements_count.safe_mul(8)?.safe_add(16)?)
+            .ok_or_else(malformed)?;
+        let mut elements = Vec::new();
+        for position in positions.chunks_exact(8) {
+            let offset =
+                u32::from_be_bytes([position[0], position[1], position[2], position[3]]) as usize;
+            let size =
+                u32::from_be_bytes([position[4], position[5], position[6], position[7]]) as usize;
+            let element = tag
+                .get(offset..offset.safe_add(size)?)
+                .ok_or_else(malformed)?;
+            if let Some(element) = Self::read_process_element(element, options)? {
+                elements.push(element);
+            }
+        }
+        let mpe = MultiProcessType {
+            num_input_channels: num_input_channels as u8,
+            num_output_channels: num_output_channels as u8,
+            elements,
+        };
+        mpe.validate()?;
+        Ok(mpe)
+    }
+
+    /// Reads single element of `mpet`, `bACS` and `eACS` are no-op and give `None`
+    fn read_process_element(
+        element: &[u8],
+        options: &ParsingOptions,
+    ) -> Result<Option<ProcessElement>, CmsError> {
+        let malformed =
+            || CmsError::IncorrectlyFormedLut("Multi process element is malformed".to_string());
+        if element.len() < 12 {
+            return Err(malformed());
+        }
+        let inputs = u16::from_be_bytes([element[8], element[9]]) as usize;
+        let outputs = u16::from_be_bytes([element[10], element[11]]) as usize;
+        let read_floats = |offset: usize, count: usize| -> Result<Vec<f32>, CmsError> {
+            let bytes = element
+                .get(offset..count.safe_mul(4)?.safe_add(offset)?)
+                .ok_or_else(malformed)?;
+            let mut values = try_vec![0f32; count];
+            for (dst, src) in values.iter_mut().zip(bytes.chunks_exact(4)) {
+                *dst = f32::from_bits(u32::from_be_bytes([src[0], src[1], src[2], src[3]]));
+            }
+            Ok(values)
+        };
+        match &element[..4] {
+            b"bACS" | b"eACS" => return Ok(None),
+            b"cvst" | b"matf" | b"clut" => {}
+            _ => {
+                return Err(CmsError::IncorrectlyFormedLut(
+                    "Unknown multi process element".to_string(),
+                ));
+            }
+        }
+        if inputs == 0 || outputs == 0 || inputs > MPE_MAX_CHANNELS || outputs > MPE_MAX_CHANNELS {
+            return Err(malformed());
+        }
+        Ok(Some(match &element[..4] {
+            b"cvst" => {
+                if inputs != outputs {
+                    return Err(malformed());
+                }
+                let positions = element.get(12..12 + inputs * 8).ok_or_else(malformed)?;
+                let mut curves = Vec::with_capacity(inputs);
+                for position in positions.chunks_exact(8) {
+                    let offset =
+                        u32::from_be_bytes([position[0], position[1], position[2], position[3]])
+                            as usize;
+                    let size =
+                        u32::from_be_bytes([position[4], position[5], position[6], position[7]])
+                            as usize;
+                    let curve = element
+                        .get(offset..offset.safe_add(size)?)
+                        .ok_or_else(malformed)?;
+                    if curve.len() < 12 || &curve[..4] != b"curf" {
+                        return Err(malformed());
+                    }
+                    let mut read_size = 0usize;
+                    curves.push(Self::read_segmented_curve(curve, &mut read_size, options)?);
+                }
+                ProcessElement::CurveSet(curves)
+            }
+            b"matf" => ProcessElement::Matrix {
+                inputs: inputs as u8,
+                outputs: outputs as u8,
+                matrix: read_floats(12, inputs * outputs)?,
+                offsets: read_floats(12 + inputs * outputs * 4, outputs)?,
+            },
+            _ => {
+                let grid = element.get(12..28).ok_or_else(malformed)?;
+                let mut grid_points = [0u8; 16];
+                let mut clut_size = outputs;
+                for (dst, &points) in grid_points.iter_mut().zip(grid.iter()).take(inputs) {
+                    if points == 0 {
+                        return Err(malformed());
+                    }
+                    *dst = points;
+                    clut_size = clut_size.safe_mul(points as usize)?;
+                }
+                if clut_size > options.max_allowed_clut_size {
+                    return Err(CmsError::InvalidProfile);
+                }
+                ProcessElement::Clut {
+                    grid_points,
+                    inputs: inputs as u8,
+                    outputs: outputs as u8,
+                    table: read_floats(28, clut_size)?.into(),
+                }
+            }
+        }))
+    }
+
     #[inline]
     pub(crate) fn read_chad_tag(
         slice: &[u8],
@@ -864,6 +1178,150 @@ impl ColorProfile {
         Ok(None)
     }
 
+    pub(crate) fn read_named_color_tag(
+        slice: &[u8],
+        entry: usize,
+        tag_size: usize,
+    ) -> Result<Option<NamedColorList>, CmsError> {
+        if tag_size < 84 {
+            return Err(CmsError::InvalidProfile);
+        }
+        let last_tag_offset = tag_size.safe_add(entry)?;
+        if last_tag_offset > slice.len() {
+            return Err(CmsError::InvalidProfile);
+        }
+        let tag = &slice[entry..last_tag_offset];
+        let tag_type = u32::from_be_bytes([tag[0], tag[1], tag[2], tag[3]]);
+        if TagTypeDefinition::from(tag_type) != TagTypeDefinition::NamedColor {
+            return Ok(None);
+        }
+        // Names are null terminated 7-bit ASCII in 32 bytes
+        let read_name = |bytes: &[u8]| {
+            let end = bytes.iter().position(|&x| x == 0).unwrap_or(bytes.len());
+            bytes[..end]
+                .iter()
+                .map(|&x| (x & 0x7f) as char)
+                .collect::<String>()
+        };
+        let vendor_flags = u32::from_be_bytes([tag[8], tag[9], tag[10], tag[11]]);
+        let count = u32::from_be_bytes([tag[12], tag[13], tag[14], tag[15]]) as usize;
+        let device_channels = u32::from_be_bytes([tag[16], tag[17], tag[18], tag[19]]) as usize;
+        if device_channels > MAX_CHANNELS {
+            return Err(CmsError::InvalidProfile);
+        }
+        let color_size = 38 + device_channels * 2;
+        let colors_data = &tag[84..];
+        if colors_data.len() < count.safe_mul(color_size)? {
+            return Err(CmsError::InvalidProfile);
+        }
+        let mut colors = Vec::with_capacity(count);
+        for color in colors_data.chunks_exact(color_size).take(count) {
+            let coords = color[32..]
+                .chunks_exact(2)
+                .map(|x| u16::from_be_bytes([x[0], x[1]]))
+                .collect::<Vec<u16>>();
+            colors.push(NamedColor {
+                name: read_name(&color[..32]),
+                pcs: [coords[0], coords[1], coords[2]],
+                device: coords[3..].to_vec(),
+            });
+        }
+        Ok(Some(NamedColorList {
+            vendor_flags,
+            prefix: read_name(&tag[20..52]),
+            suffix: read_name(&tag[52..84]),
+            device_channels,
+            colors,
+        }))
+    }
+
+    pub(crate) fn read_vcgt_tag(
+        slice: &[u8],
+        entry: usize,
+        tag_size: usize,
+    ) -> Result<Option<VideoCardGamma>, CmsError> {
+        if tag_size < 12 {
+            return Err(CmsError::InvalidProfile);
+        }
+        let last_tag_offset = tag_size.safe_add(entry)?;
+        if last_tag_offset > slice.len() {
+            return Err(CmsError::InvalidProfile);
+        }
+        let tag = &slice[entry..last_tag_offset];
+        let tag_type = u32::from_be_bytes([tag[0], tag[1], tag[2], tag[3]]);
+        if TagTypeDefinition::from(tag_type) != TagTypeDefinition::VideoCardGamma {
+            return Ok(None);
+        }
+        let gamma_type = u32::from_be_bytes([tag[8], tag[9], tag[10], tag[11]]);
+        match gamma_type {
+            0 => {
+                if tag.len() < 18 {
+                    return Err(CmsError::InvalidProfile);
+                }
+                let channels = u16::from_be_bytes([tag[12], tag[13]]) as usize;
+                let entry_count = u16::from_be_bytes([tag[14], tag[15]]) as usize;
+                let entry_size = u16::from_be_bytes([tag[16], tag[17]]) as usize;
+                if (channels != 1 && channels != 3) || (entry_size != 1 && entry_size != 2) {
+                    return Err(CmsError::InvalidProfile);
+                }
+                let table_size = channels.safe_mul(entry_count)?.safe_mul(entry_size)?;
+                let table = &tag[18..];
+                if table.len() < table_size {
+                    return Err(CmsError::InvalidProfile);
+                }
+                let mut curves = table[..table_size]
+                    .chunks_exact(entry_count.max(1) * entry_size)
+                    .map(|curve| {
+                        if entry_size == 1 {
+                            curve.iter().map(|&x| x as u16 * 257).collect::<Vec<u16>>()
+                        } else {
+                            curve
+                                .chunks_exact(2)
+                                .map(|x| u16::from_be_bytes([x[0], x[1]]))
+                                .collect::<Vec<u16>>()
+                        }
+                    })
+                    .collect::<Vec<Vec<u16>>>();
+                if curves.len() != channels {
+                    return Err(CmsError::InvalidProfile);
+                }
+                let (red, green, blue) = if channels == 1 {
+                    let gray = curves.remove(0);
+                    (gray.clone(), gray.clone(), gray)
+                } else {
+                    let blue = curves.remove(2);
+                    let green = curves.remove(1);
+                    (curves.remove(0), green, blue)
+                };
+                Ok(Some(VideoCardGamma::Table { red, green, blue }))
+            }
+            1 => {
+                if tag.len() < 12 + 36 {
+                    return Err(CmsError::InvalidProfile);
+                }
+                let read_fixed = |offset: usize| {
+                    s15_fixed16_number_to_float(i32::from_be_bytes([
+                        tag[offset],
+                        tag[offset + 1],
+                        tag[offset + 2],
+                        tag[offset + 3],
+                    ]))
+                };
+                let formula = |offset: usize| VideoCardGammaFormula {
+                    gamma: read_fixed(offset),
+                    min: read_fixed(offset + 4),
+                    max: read_fixed(offset + 8),
+                };
+                Ok(Some(VideoCardGamma::Formula {
+                    red: formula(12),
+                    green: formula(24),
+                    blue: formula(36),
+                }))
+            }
+            _ => Err(CmsError::InvalidProfile),
+        }
+    }
+
     #[inline]
     pub(crate) fn read_date_time_tag(
         slice: &[u8],
@@ -881,7 +1339,7 @@ impl ColorProfile {
         let tag_type = u32::from_be_bytes([tag[0], tag[1], tag[2], tag[3]]);
         let def = TagTypeDefinition::from(tag_type);
         if def == TagTypeDefinition::DateTime {
-            let tag_value = &slice[8..20];
+            let tag_value = &tag[8..20];
             let time = ColorDateTime::new_from_slice(tag_value)?;
             return Ok(Some(time));
         }
@@ -0,0 +1,284 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Conversion of rectangular regions of strided images and solid color fills.
+use crate::{CmsError, Layout, TransformExecutor, TransformScratch};
+
+/// Rectangle in pixels, `x` and `y` are its top left corner.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub const fn new(x: usize, y: usize, width: usize, height: usize) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Converts rectangles of images with given layouts, e.g. dirty regions of a compositor.
+///
+/// Pixels outside of the rectangle are not read in source and not touched in destination.
+pub struct RectTransform<'a, V: Copy + Default> {
+    executor: &'a (dyn TransformExecutor<V> + Send + Sync),
+    src_layout: Layout,
+    dst_layout: Layout,
+}
+
+impl<'a, V: Copy + Default> RectTransform<'a, V> {
+    /// `src_layout` and `dst_layout` must be the ones `executor` was created with.
+    pub fn new(
+        executor: &'a (dyn TransformExecutor<V> + Send + Sync),
+        src_layout: Layout,
+        dst_layout: Layout,
+    ) -> Self {
+        Self {
+            executor,
+            src_layout,
+            dst_layout,
+        }
+    }
+
+    /// Converts `rect` of `src` into the same `rect` of `dst`, strides are in elements.
+    pub fn transform_rect(
+        &self,
+        src: &[V],
+        src_stride: usize,
+        dst: &mut [V],
+        dst_stride: usize,
+        rect: Rect,
+    ) -> Result<(), CmsError> {
+        if rect.width == 0 || rect.height == 0 {
+            return Ok(());
+        }
+        let src_channels = self.src_layout.channels();
+        let dst_channels = self.dst_layout.channels();
+        let src_range = check_rect(src.len(), src_stride, src_channels, rect)?;
+        let dst_range = check_rect(dst.len(), dst_stride, dst_channels, rect)?;
+        let src_row = rect.width * src_channels;
+        let dst_row = rect.width * dst_channels;
+        // Rectangle spanning whole tightly packed rows is converted at once
+        if src_stride == src_row && dst_stride == dst_row {
+            return self
+                .executor
+                .transform(&src[src_range], &mut dst[dst_range]);
+        }
+        let mut scratch = TransformScratch::default();
+        for (src, dst) in src[src_range]
+            .chunks(src_stride)
+            .zip(dst[dst_range].chunks_mut(dst_stride))
+        {
+            self.executor.transform_with_scratch(
+                &src[..src_row],
+                &mut dst[..dst_row],
+                &mut scratch,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Converts single pixel `color` in source layout.
+    fn convert_color(&self, color: &[V]) -> Result<[V; 16], CmsError> {
+        let src_channels = self.src_layout.channels();
+        let dst_channels = self.dst_layout.channels();
+        if color.len() != src_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let mut converted = [V::default(); 16];
+        self.executor
+            .transform(color, &mut converted[..dst_channels])?;
+        Ok(converted)
+    }
+
+    /// Converts `color`, a single pixel in source layout, once and fills tightly packed `dst` with it.
+    pub fn fill(&self, color: &[V], dst: &mut [V]) -> Result<(), CmsError> {
+        let dst_channels = self.dst_layout.channels();
+        if dst.len() % dst_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        let converted = self.convert_color(color)?;
+        for dst in dst.chunks_exact_mut(dst_channels) {
+            dst.copy_from_slice(&converted[..dst_channels]);
+        }
+        Ok(())
+    }
+
+    /// Same as [RectTransform::fill], only `rect` of `dst` is filled, `dst_stride` is in elements.
+    pub fn fill_rect(
+        &self,
+        color: &[V],
+        dst: &mut [V],
+        dst_stride: usize,
+        rect: Rect,
+    ) -> Result<(), CmsError> {
+        if rect.width == 0 || rect.height == 0 {
+            return Ok(());
+        }
+        let dst_channels = self.dst_layout.channels();
+        let dst_range = check_rect(dst.len(), dst_stride, dst_channels, rect)?;
+        let converted = self.convert_color(color)?;
+        let dst_row = rect.width * dst_channels;
+        for dst in dst[dst_range].chunks_mut(dst_stride) {
+            for dst in dst[..dst_row].chunks_exact_mut(dst_channels) {
+                dst.copy_from_slice(&converted[..dst_channels]);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns span of image from the first to the last sample of `rect`
+fn check_rect(
+    len: usize,
+    stride: usize,
+    channels: usize,
+    rect: Rect,
+) -> Result<std::ops::Range<usize>, CmsError> {
+    let right = rect
+        .x
+        .checked_add(rect.width)
+        .and_then(|x| x.checked_mul(channels))
+        .ok_or(CmsError::LaneSizeMismatch)?;
+    if right > stride {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    let start = rect
+        .y
+        .checked_mul(stride)
+        .and_then(|v| v.checked_add(rect.x * channels))
+        .ok_or(CmsError::LaneSizeMismatch)?;
+    let end = (rect.y + rect.height - 1)
+        .checked_mul(stride)
+        .and_then(|v| v.checked_add(right))
+        .ok_or(CmsError::LaneSizeMismatch)?;
+    if end > len {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    Ok(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColorProfile, TransformOptions};
+
+    #[test]
+    fn test_transform_rect() {
+        let srgb = ColorProfile::new_srgb();
+        let p3 = ColorProfile::new_display_p3();
+        let transform = srgb
+            .create_transform_8bit(Layout::Rgba, &p3, Layout::Rgb, TransformOptions::default())
+            .unwrap();
+        let (width, height) = (17, 11);
+        let src_stride = width * 4 + 5;
+        let dst_stride = width * 3 + 2;
+        let src = (0..src_stride * height)
+            .map(|i| ((i * 37 + 11) % 256) as u8)
+            .collect::<Vec<_>>();
+        let mut expected = vec![0u8; dst_stride * height];
+        for (src, dst) in src.chunks(src_stride).zip(expected.chunks_mut(dst_stride)) {
+            transform
+                .transform(&src[..width * 4], &mut dst[..width * 3])
+                .unwrap();
+        }
+
+        let rect = Rect::new(3, 2, 9, 6);
+        let mut dst = vec![7u8; dst_stride * height];
+        let rect_transform = RectTransform::new(transform.as_ref(), Layout::Rgba, Layout::Rgb);
+        rect_transform
+            .transform_rect(&src, src_stride, &mut dst, dst_stride, rect)
+            .unwrap();
+        for y in 0..height {
+            for x in 0..width {
+                let px = &dst[y * dst_stride + x * 3..][..3];
+                let inside = (rect.x..rect.x + rect.width).contains(&x)
+                    && (rect.y..rect.y + rect.height).contains(&y);
+                if inside {
+                    assert_eq!(px, &expected[y * dst_stride + x * 3..][..3]);
+                } else {
+                    assert_eq!(px, &[7, 7, 7]);
+                }
+            }
+        }
+
+        let outside = Rect::new(10, 2, 9, 6);
+        assert!(
+            rect_transform
+                .transform_rect(&src, src_stride, &mut dst, dst_stride, outside)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_fill() {
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let transform = srgb
+            .create_transform_16bit(
+                Layout::Rgb,
+                &bt2020,
+                Layout::Rgba,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let color = [65535u16, 32768, 0];
+        let mut expected = [0u16; 4];
+        transform.transform(&color, &mut expected).unwrap();
+
+        let rect_transform = RectTransform::new(transform.as_ref(), Layout::Rgb, Layout::Rgba);
+        let mut dst = vec![0u16; 4 * 64];
+        rect_transform.fill(&color, &mut dst).unwrap();
+        assert!(dst.chunks_exact(4).all(|px| px == expected));
+
+        let (width, stride) = (8, 8 * 4 + 3);
+        let mut dst = vec![1u16; stride * 8];
+        let rect = Rect::new(2, 3, 4, 2);
+        rect_transform
+            .fill_rect(&color, &mut dst, stride, rect)
+            .unwrap();
+        for y in 0..8 {
+            for x in 0..width {
+                let px = &dst[y * stride + x * 4..][..4];
+                if (2..6).contains(&x) && (3..5).contains(&y) {
+                    assert_eq!(px, expected);
+                } else {
+                    assert_eq!(px, [1, 1, 1, 1]);
+                }
+            }
+        }
+        assert!(rect_transform.fill(&color[..2], &mut dst).is_err());
+    }
+}
@@ -0,0 +1,1038 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Slow reference implementation of ICC pipeline.
+//!
+//! Everything here is evaluated in `f64` directly from profile tags, without tables,
+//! fixed point or SIMD, so it is meant to be read rather than to be fast.
+//! Fast transforms are expected to stay close to it.
+use crate::conversions::BlackPointCompensation;
+use crate::lab::{lab_to_xyz, xyz_to_lab};
+use crate::mpe::{MPE_MAX_CHANNELS, MultiProcessType, mpe_from_pipeline_pcs, mpe_to_pipeline_pcs};
+use crate::{
+    Chromaticity, CmsError, ColorProfile, DataColorSpace, LutDataType, LutMultidimensionalType,
+    LutStore, LutWarehouse, MAX_CHANNELS, MalformedSize, Matrix3d, ProfileVersion, RenderingIntent,
+    ToneReprCurve, TransferCharacteristics, TransformOptions, Vector3d, Xyz,
+};
+
+#[derive(Clone, Debug)]
+enum ReferenceCurve {
+    Curve(ToneReprCurve),
+    Cicp(TransferCharacteristics),
+}
+
+impl ReferenceCurve {
+    fn new(
+        profile: &ColorProfile,
+        trc: &Option<ToneReprCurve>,
+        allow_use_cicp: bool,
+    ) -> Result<ReferenceCurve, CmsError> {
+        if let Some(tc) = profile
+            .cicp
+            .as_ref()
+            .map(|c| c.transfer_characteristics)
+            .filter(|tc| allow_use_cicp && tc.has_transfer_curve())
+        {
+            return Ok(ReferenceCurve::Cicp(tc));
+        }
+        let curve = trc.as_ref().ok_or(CmsError::BuildTransferFunction)?;
+        check_curve(curve)?;
+        Ok(ReferenceCurve::Curve(curve.clone()))
+    }
+
+    fn linearize(&self, x: f64) -> f64 {
+        match self {
+            ReferenceCurve::Curve(curve) => eval_curve(curve, x),
+            ReferenceCurve::Cicp(tc) => tc.linearize(x.clamp(0., 1.)),
+        }
+    }
+
+    fn gamma(&self, y: f64) -> f64 {
+        match self {
+            ReferenceCurve::Curve(curve) => eval_inverse_curve(curve, y),
+            ReferenceCurve::Cicp(tc) => tc.gamma(y.clamp(0., 1.)),
+        }
+    }
+}
+
+fn check_curve(curve: &ToneReprCurve) -> Result<(), CmsError> {
+    match curve {
+        ToneReprCurve::Lut(_) => Ok(()),
+        ToneReprCurve::Segmented(_) | ToneReprCurve::Custom(_) => Ok(()),
+        ToneReprCurve::Parametric(params) => {
+            if matches!(params.len(), 1 | 3 | 4 | 5 | 7) {
+                Ok(())
+            } else {
+                Err(CmsError::BuildTransferFunction)
+            }
+        }
+    }
+}
+
+/// Linear interpolation over table, whose nodes are evenly spread on [0, 1]
+fn interp_table(x: f64, len: usize, fetch: impl Fn(usize) -> f64) -> f64 {
+    if len == 0 {
+        return x;
+    }
+    if len == 1 {
+        return fetch(0);
+    }
+    let pos = x.clamp(0., 1.) * (len - 1) as f64;
+    let lo = (pos.floor() as usize).min(len - 2);
+    let t = pos - lo as f64;
+    fetch(lo) * (1. - t) + fetch(lo + 1) * t
+}
+
+/// Evaluates curve as ICC.1 describes it for `curv`, `para` and `curf` types
+fn eval_curve(curve: &ToneReprCurve, x: f64) -> f64 {
+    let x = x.clamp(0., 1.);
+    match curve {
+        ToneReprCurve::Lut(lut) => match lut.len() {
+            0 => x,
+            1 => x.powf(lut[0] as f64 / 256.),
+            len => interp_table(x, len, |i| lut[i] as f64 / 65535.),
+        },
+        ToneReprCurve::Parametric(params) => {
+            let p = params.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+            let pow = |base: f64, g: f64| base.max(0.).powf(g);
+            match p[..] {
+                [g] => pow(x, g),
+                [g, a, b] => {
+                    if x >= -b / a {
+                        pow(a * x + b, g)
+                    } else {
+                        0.
+                    }
+                }
+                [g, a, b, c] => {
+                    if x >= -b / a {
+                        pow(a * x + b, g) + c
+                    } else {
+                        c
+                    }
+                }
+                [g, a, b, c, d] => {
+                    if x >= d {
+                        pow(a * x + b, g)
+                    } else {
+                        c * x
+                    }
+                }
+                [g, a, b, c, d, e, f] => {
+                    if x >= d {
+                        pow(a * x + b, g) + e
+                    } else {
+                        c * x + f
+                    }
+                }
+                _ => x,
+            }
+        }
+        ToneReprCurve::Segmented(curve) => curve.eval(x as f32) as f64,
+        ToneReprCurve::Custom(curve) => curve.eval(x as f32) as f64,
+    }
+}
+
+/// Inverts monotonic curve by bisection on [0, 1]
+fn eval_inverse_curve(curve: &ToneReprCurve, y: f64) -> f64 {
+    if let ToneReprCurve::Lut(lut) = curve {
+        match lut.len() {
+            0 => return y.clamp(0., 1.),
+            1 => return y.clamp(0., 1.).powf(256. / lut[0] as f64),
+            _ => {}
+        }
+    }
+    let start = eval_curve(curve, 0.);
+    let end = eval_curve(curve, 1.);
+    let ascending = end >= start;
+    let (mut lo, mut hi) = (0f64, 1f64);
+    for _ in 0..64 {
+        let mid = (lo + hi) * 0.5;
+        if (eval_curve(curve, mid) < y) == ascending {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (lo + hi) * 0.5
+}
+
+fn store_value(store: &LutStore, index: usize) -> f64 {
+    match store {
+        LutStore::Store8(v) => v[index] as f64 / 255.,
+        LutStore::Store16(v) => v[index] as f64 / 65535.,
+    }
+}
+
+fn store_len(store: &LutStore) -> usize {
+    match store {
+        LutStore::Store8(v) => v.len(),
+        LutStore::Store16(v) => v.len(),
+    }
+}
+
+/// Multilinear interpolation in CLUT, first input channel has the largest stride
+fn interp_clut(
+    store: &LutStore,
+    grid: &[usize],
+    outputs: usize,
+    input: &[f64],
+    dst: &mut [f64],
+) -> Result<(), CmsError> {
+    let expected = grid.iter().product::<usize>() * outputs;
+    if store_len(store) != expected {
+        return Err(CmsError::MalformedClut(MalformedSize {
+            size: store_len(store),
+            expected,
+        }));
+    }
+    let mut lo = vec![0usize; grid.len()];
+    let mut frac = vec![0f64; grid.len()];
+    for (i, (&g, &x)) in grid.iter().zip(input.iter()).enumerate() {
+        if g < 2 {
+            continue;
+        }
+        let pos = x.clamp(0., 1.) * (g - 1) as f64;
+        lo[i] = (pos.floor() as usize).min(g - 2);
+        frac[i] = pos - lo[i] as f64;
+    }
+    dst[..outputs].fill(0.);
+    // Sum over every corner of the enclosing hypercube
+    for corner in 0..(1usize << grid.len()) {
+        let mut weight = 1f64;
+        let mut offset = 0usize;
+        for (i, &g) in grid.iter().enumerate() {
+            let upper = (corner >> i) & 1 == 1 && g >= 2;
+            weight *= if upper { frac[i] } else { 1. - frac[i] };
+            offset = offset * g + lo[i] + upper as usize;
+        }
+        if weight == 0. {
+            continue;
+        }
+        for (k, dst) in dst[..outputs].iter_mut().enumerate() {
+            *dst += weight * store_value(store, offset * outputs + k);
+        }
+    }
+    Ok(())
+}
+
+fn eval_lut(lut: &LutDataType, use_matrix: bool, src: &[f64]) -> Result<Vec<f64>, CmsError> {
+    let inputs = lut.num_input_channels as usize;
+    let outputs = lut.num_output_channels as usize;
+    let mut values = src[..inputs].to_vec();
+    // Matrix is used only when input is PCS XYZ, or always in lcms2 compatible mode
+    if use_matrix && inputs == 3 {
+        let v = lut.matrix.mul_vector(Vector3d {
+            v: [values[0], values[1], values[2]],
+        });
+        values.copy_from_slice(&v.v);
+    }
+    let in_entries = lut.num_input_table_entries as usize;
+    let out_entries = lut.num_output_table_entries as usize;
+    if store_len(&lut.input_table) < in_entries * inputs
+        || store_len(&lut.output_table) < out_entries * outputs
+    {
+        return Err(CmsError::InvalidAtoBLut);
+    }
+    for (i, v) in values.iter_mut().enumerate() {
+        *v = interp_table(*v, in_entries, |k| {
+            store_value(&lut.input_table, i * in_entries + k)
+        });
+    }
+    let grid = vec![lut.num_clut_grid_points as usize; inputs];
+    let mut out = vec![0f64; outputs];
+    interp_clut(&lut.clut_table, &grid, outputs, &values, &mut out)?;
+    for (i, v) in out.iter_mut().enumerate() {
+        *v = interp_table(*v, out_entries, |k| {
+            store_value(&lut.output_table, i * out_entries + k)
+        });
+    }
+    Ok(out)
+}
+
+fn apply_curves(curves: &[ToneReprCurve], values: &mut [f64]) -> Result<(), CmsError> {
+    if curves.len() != values.len() {
+        return Err(CmsError::InvalidAtoBLut);
+    }
+    for (curve, v) in curves.iter().zip(values.iter_mut()) {
+        check_curve(curve)?;
+        *v = eval_curve(curve, *v);
+    }
+    Ok(())
+}
+
+fn apply_matrix(mab: &LutMultidimensionalType, values: &mut [f64]) {
+    let v = mab.matrix.mul_vector(Vector3d {
+        v: [values[0], values[1], values[2]],
+    });
+    for (dst, (v, bias)) in values.iter_mut().zip(v.v.iter().zip(mab.bias.v.iter())) {
+        *dst = v + bias;
+    }
+}
+
+fn apply_mab_clut(mab: &LutMultidimensionalType, values: Vec<f64>) -> Result<Vec<f64>, CmsError> {
+    let inputs = mab.num_input_channels as usize;
+    let outputs = mab.num_output_channels as usize;
+    match &mab.clut {
+        Some(clut) => {
+            let grid = mab.grid_points[..inputs]
+                .iter()
+                .map(|&g| g as usize)
+                .collect::<Vec<usize>>();
+            let mut out = vec![0f64; outputs];
+            interp_clut(clut, &grid, outputs, &values, &mut out)?;
+            Ok(out)
+        }
+        None if inputs == outputs => Ok(values),
+        None => Err(CmsError::InvalidAtoBLut),
+    }
+}
+
+/// `mAB`: A curves, CLUT, M curves, matrix, B curves
+fn eval_mab(mab: &LutMultidimensionalType, src: &[f64]) -> Result<Vec<f64>, CmsError> {
+    let mut values = src[..mab.num_input_channels as usize].to_vec();
+    if !mab.a_curves.is_empty() {
+        apply_curves(&mab.a_curves, &mut values)?;
+        values = apply_mab_clut(mab, values)?;
+    }
+    if mab.m_curves.len() == 3 && values.len() == 3 {
+        apply_curves(&mab.m_curves, &mut values)?;
+        apply_matrix(mab, &mut values);
+    }
+    apply_curves(&mab.b_curves, &mut values)?;
+    Ok(values)
+}
+
+/// `mBA`: B curves, matrix, M curves, CLUT, A curves
+fn eval_mba(mab: &LutMultidimensionalType, src: &[f64]) -> Result<Vec<f64>, CmsError> {
+    let mut values = src[..mab.num_input_channels as usize].to_vec();
+    apply_curves(&mab.b_curves, &mut values)?;
+    if mab.m_curves.len() == 3 && values.len() == 3 {
+        apply_matrix(mab, &mut values);
+        apply_curves(&mab.m_curves, &mut values)?;
+    }
+    if !mab.a_curves.is_empty() {
+        values = apply_mab_clut(mab, values)?;
+        apply_curves(&mab.a_curves, &mut values)?;
+    }
+    Ok(values)
+}
+
+/// Evaluates `mpet`, elements are defined in single precision so it is evaluated as is
+fn eval_mpe(mpe: &MultiProcessType, src: &[f64]) -> Vec<f64> {
+    let mut values = [0f32; MPE_MAX_CHANNELS];
+    for (dst, &src) in values.iter_mut().zip(src.iter()) {
+        *dst = src as f32;
+    }
+    let mut dst = [0f32; MPE_MAX_CHANNELS];
+    mpe.evaluate(&values, &mut dst);
+    dst[..mpe.num_output_channels as usize]
+        .iter()
+        .map(|&v| v as f64)
+        .collect()
+}
+
+/// Evaluates device to PCS `mpet`, result is in LUT PCS encoding
+fn eval_mpe_to_pcs(mpe: &MultiProcessType, pcs: DataColorSpace, src: &[f64]) -> Vec<f64> {
+    let v = eval_mpe(mpe, src);
+    mpe_to_pipeline_pcs(pcs, [v[0] as f32, v[1] as f32, v[2] as f32])
+        .iter()
+        .map(|&v| v as f64)
+        .collect()
+}
+
+/// Evaluates PCS to device `mpet`, `src` is in LUT PCS encoding
+fn eval_mpe_from_pcs(mpe: &MultiProcessType, pcs: DataColorSpace, src: &[f64]) -> Vec<f64> {
+    let v = mpe_from_pipeline_pcs(pcs, [src[0] as f32, src[1] as f32, src[2] as f32]);
+    eval_mpe(mpe, &[v[0] as f64, v[1] as f64, v[2] as f64])
+}
+
+/// Describes how LUT encodes PCS values in [0, 1]
+#[derive(Clone, Copy, Debug)]
+struct PcsEncoding {
+    pcs: DataColorSpace,
+    legacy_lab: bool,
+}
+
+impl PcsEncoding {
+    fn new(profile: &ColorProfile) -> Result<PcsEncoding, CmsError> {
+        if profile.pcs != DataColorSpace::Xyz && profile.pcs != DataColorSpace::Lab {
+            return Err(CmsError::UnsupportedProfileConnection);
+        }
+        Ok(PcsEncoding {
+            pcs: profile.pcs,
+            legacy_lab: profile.pcs == DataColorSpace::Lab
+                && profile.version_internal < ProfileVersion::V4_0,
+        })
+    }
+
+    fn decode(self, v: &[f64]) -> [f64; 3] {
+        if self.pcs == DataColorSpace::Xyz {
+            let scale = 65535. / 32768.;
+            return [v[0] * scale, v[1] * scale, v[2] * scale];
+        }
+        let scale = if self.legacy_lab { 65535. / 65280. } else { 1. };
+        lab_to_xyz([
+            v[0] * scale * 100.,
+            v[1] * scale * 255. - 128.,
+            v[2] * scale * 255. - 128.,
+        ])
+    }
+
+    fn encode(self, xyz: [f64; 3]) -> [f64; 3] {
+        if self.pcs == DataColorSpace::Xyz {
+            let scale = 32768. / 65535.;
+            return [xyz[0] * scale, xyz[1] * scale, xyz[2] * scale];
+        }
+        let scale = if self.legacy_lab { 65280. / 65535. } else { 1. };
+        let lab = xyz_to_lab(xyz);
+        [
+            lab[0] / 100. * scale,
+            (lab[1] + 128.) / 255. * scale,
+            (lab[2] + 128.) / 255. * scale,
+        ]
+    }
+}
+
+/// Guesses PCS actually produced by device to PCS LUT, when it disagrees with header.
+///
+/// Device white is evaluated through `A2Bx`: Lab encoded white is close to `(1, 0.5, 0.5)`,
+/// while XYZ encoded white is close to `(0.48, 0.5, 0.41)`. Returns the other PCS only when
+/// LUT output clearly matches it and clearly doesn't match the header one.
+pub(crate) fn detect_lut_pcs(
+    profile: &ColorProfile,
+    intent: RenderingIntent,
+) -> Option<DataColorSpace> {
+    if profile.pcs != DataColorSpace::Xyz && profile.pcs != DataColorSpace::Lab {
+        return None;
+    }
+    let white = match profile.color_space {
+        DataColorSpace::Rgb | DataColorSpace::Gray => 1.,
+        DataColorSpace::Cmyk | DataColorSpace::Cmy => 0.,
+        _ => return None,
+    };
+    let lut = profile.get_device_to_pcs(intent)?;
+    let channels = profile.color_space.channels();
+    if ReferenceTransform::check_lut(lut, channels, 3).is_err() {
+        return None;
+    }
+    let src = vec![white; channels];
+    let encoded = match lut {
+        LutWarehouse::Lut(lut) => eval_lut(lut, false, &src).ok()?,
+        LutWarehouse::Multidimensional(mab) => eval_mab(mab, &src).ok()?,
+        // Float PCS values are unambiguous
+        LutWarehouse::MultiProcess(_) => return None,
+    };
+    let wp = Chromaticity::D50.to_xyzd();
+    let distance = |pcs: DataColorSpace| {
+        let expected = PcsEncoding {
+            pcs,
+            legacy_lab: false,
+        }
+        .encode([wp.x, wp.y, wp.z]);
+        expected
+            .iter()
+            .zip(encoded.iter())
+            .map(|(&a, &b)| (a - b) * (a - b))
+            .sum::<f64>()
+            .sqrt()
+    };
+    let other = if profile.pcs == DataColorSpace::Xyz {
+        DataColorSpace::Lab
+    } else {
+        DataColorSpace::Xyz
+    };
+    if distance(profile.pcs) > 0.25 && distance(other) < 0.1 {
+        Some(other)
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+enum DeviceStage {
+    MatrixShaper {
+        curves: [ReferenceCurve; 3],
+        matrix: Matrix3d,
+    },
+    Gray(ReferenceCurve),
+    Lut {
+        lut: LutWarehouse,
+        encoding: PcsEncoding,
+        lcms_lut_matrix: bool,
+    },
+}
+
+impl DeviceStage {
+    fn device_to_pcs(&self, src: &[f64]) -> Result<[f64; 3], CmsError> {
+        Ok(match self {
+            DeviceStage::MatrixShaper { curves, matrix } => {
+                let rgb = Vector3d {
+                    v: [
+                        curves[0].linearize(src[0]),
+                        curves[1].linearize(src[1]),
+                        curves[2].linearize(src[2]),
+                    ],
+                };
+                matrix.mul_vector(rgb).v
+            }
+            DeviceStage::Gray(curve) => {
+                let wp = Chromaticity::D50.to_xyzd();
+                let y = curve.linearize(src[0]);
+                [wp.x * y, wp.y * y, wp.z * y]
+            }
+            DeviceStage::Lut {
+                lut,
+                encoding,
+                lcms_lut_matrix,
+            } => {
+                let pcs = match lut {
+                    LutWarehouse::Lut(lut) => eval_lut(lut, *lcms_lut_matrix, src)?,
+                    LutWarehouse::Multidimensional(mab) => eval_mab(mab, src)?,
+                    LutWarehouse::MultiProcess(mpe) => eval_mpe_to_pcs(mpe, encoding.pcs, src),
+                };
+                encoding.decode(&pcs)
+            }
+        })
+    }
+
+    fn pcs_to_device(&self, xyz: [f64; 3], dst: &mut [f64]) -> Result<(), CmsError> {
+        match self {
+            DeviceStage::MatrixShaper { curves, matrix } => {
+                let rgb = matrix.inverse().mul_vector(Vector3d { v: xyz });
+                for ((dst, curve), v) in dst.iter_mut().zip(curves.iter()).zip(rgb.v.iter()) {
+                    *dst = curve.gamma(v.clamp(0., 1.));
+                }
+            }
+            DeviceStage::Gray(curve) => {
+                dst[0] = curve.gamma(xyz[1].clamp(0., 1.));
+            }
+            DeviceStage::Lut {
+                lut,
+                encoding,
+                lcms_lut_matrix,
+            } => {
+                let pcs = encoding.encode(xyz);
+                let values = match lut {
+                    LutWarehouse::Lut(lut) => eval_lut(
+                        lut,
+                        encoding.pcs == DataColorSpace::Xyz || *lcms_lut_matrix,
+                        &pcs,
+                    )?,
+                    LutWarehouse::Multidimensional(mab) => eval_mba(mab, &pcs)?,
+                    LutWarehouse::MultiProcess(mpe) => eval_mpe_from_pcs(mpe, encoding.pcs, &pcs),
+                };
+                for (dst, v) in dst.iter_mut().zip(values.iter()) {
+                    *dst = v.clamp(0., 1.);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Slow evaluation of `gamt` tag, which maps PCS to single channel,
+/// zero meaning the color is in gamut of the profile.
+#[derive(Clone, Debug)]
+pub(crate) struct ReferenceGamutTag(DeviceStage);
+
+impl ReferenceGamutTag {
+    /// Returns `None` when profile has no `gamt` tag
+    pub(crate) fn new(profile: &ColorProfile) -> Result<Option<ReferenceGamutTag>, CmsError> {
+        let Some(lut) = profile.gamut.clone() else {
+            return Ok(None);
+        };
+        ReferenceTransform::check_lut(&lut, 3, 1)?;
+        Ok(Some(ReferenceGamutTag(DeviceStage::Lut {
+            lut,
+            encoding: PcsEncoding::new(profile)?,
+            lcms_lut_matrix: false,
+        })))
+    }
+
+    /// Evaluates tag for D50 PCS XYZ, where white has Y = 1
+    pub(crate) fn evaluate(&self, xyz: [f64; 3]) -> Result<f64, CmsError> {
+        let mut out = [0f64];
+        self.0.pcs_to_device(xyz, &mut out)?;
+        Ok(out[0])
+    }
+}
+
+/// Slow and straightforward `f64` transform between two profiles.
+///
+/// Walks ICC pipeline from source device values through PCS XYZ to destination device values,
+/// evaluating curves, matrices and CLUTs exactly as tags define them on every call.
+/// Intended for tests and conformance checks of fast transforms, not for image processing.
+///
+/// Values are normalized to [0, 1] and ordered as profile color space channels, without alpha.
+/// Absolute colorimetric intent is evaluated as relative colorimetric.
+#[derive(Clone, Debug)]
+pub struct ReferenceTransform {
+    source: DeviceStage,
+    destination: DeviceStage,
+    src_channels: usize,
+    dst_channels: usize,
+    luminance_scale: f64,
+    black_point_compensation: Option<BlackPointCompensation>,
+    viewing_adaptation: Option<Matrix3d>,
+    surround_gamma: Option<f64>,
+}
+
+impl ReferenceTransform {
+    /// Creates reference transform from `source` to `dest`.
+    ///
+    /// LUTs take precedence over matrix-shaper tags, as in regular transforms.
+    /// PCS of LUT based profiles is resolved the same way, see [TransformOptions::assume_pcs].
+    pub fn new(
+        source: &ColorProfile,
+        dest: &ColorProfile,
+        options: TransformOptions,
+    ) -> Result<ReferenceTransform, CmsError> {
+        if let Some(source) = source.resolve_lut_pcs(options) {
+            return ReferenceTransform::new(&source, dest, options);
+        }
+        if let Some(dest) = dest.resolve_lut_pcs(options) {
+            return ReferenceTransform::new(source, &dest, options);
+        }
+        let source_stage = ReferenceTransform::make_source_stage(source, options)?;
+        let destination_stage = ReferenceTransform::make_destination_stage(dest, options)?;
+        Ok(ReferenceTransform {
+            source: source_stage,
+            destination: destination_stage,
+            src_channels: source.color_space.channels(),
+            dst_channels: dest.color_space.channels(),
+            luminance_scale: options.luminance_scale(source, dest).unwrap_or(1.),
+            black_point_compensation: BlackPointCompensation::new(source, dest, options),
+            viewing_adaptation: options.viewing_adaptation.and_then(|v| v.matrix()),
+            surround_gamma: options.surround_compensation.and_then(|v| v.gamma()),
+        })
+    }
+
+    fn make_source_stage(
+        profile: &ColorProfile,
+        options: TransformOptions,
+    ) -> Result<DeviceStage, CmsError> {
+        if !profile.has_device_to_pcs_lut() {
+            return ReferenceTransform::make_shaper_stage(profile, options);
+        }
+        let lut = profile
+            .get_device_to_pcs(options.rendering_intent)
+            .ok_or(CmsError::UnsupportedLutRenderingIntent(
+                options.rendering_intent,
+            ))?
+            .clone();
+        ReferenceTransform::check_lut(&lut, profile.color_space.channels(), 3)?;
+        Ok(DeviceStage::Lut {
+            lut,
+            encoding: PcsEncoding::new(profile)?,
+            lcms_lut_matrix: options.lcms_lut_matrix,
+        })
+    }
+
+    fn make_destination_stage(
+        profile: &ColorProfile,
+        options: TransformOptions,
+    ) -> Result<DeviceStage, CmsError> {
+        if !profile.has_pcs_to_device_lut() {
+            return ReferenceTransform::make_shaper_stage(profile, options);
+        }
+        let lut = profile
+            .get_pcs_to_device(options.rendering_intent)
+            .ok_or(CmsError::UnsupportedLutRenderingIntent(
+                options.rendering_intent,
+            ))?
+            .clone();
+        ReferenceTransform::check_lut(&lut, 3, profile.color_space.channels())?;
+        Ok(DeviceStage::Lut {
+            lut,
+            encoding: PcsEncoding::new(profile)?,
+            lcms_lut_matrix: options.lcms_lut_matrix,
+        })
+    }
+
+    fn make_shaper_stage(
+        profile: &ColorProfile,
+        options: TransformOptions,
+    ) -> Result<DeviceStage, CmsError> {
+        let allow_cicp = options.allow_use_cicp_transfer;
+        match profile.color_space {
+            DataColorSpace::Rgb if profile.is_matrix_shaper() => Ok(DeviceStage::MatrixShaper {
+                curves: [
+                    ReferenceCurve::new(profile, &profile.red_trc, allow_cicp)?,
+                    ReferenceCurve::new(profile, &profile.green_trc, allow_cicp)?,
+                    ReferenceCurve::new(profile, &profile.blue_trc, allow_cicp)?,
+                ],
+                matrix: profile.rgb_to_xyz_matrix(),
+            }),
+            DataColorSpace::Gray => Ok(DeviceStage::Gray(ReferenceCurve::new(
+                profile,
+                &profile.gray_trc,
+                allow_cicp,
+            )?)),
+            _ => Err(CmsError::UnsupportedProfileConnection),
+        }
+    }
+
+    fn check_lut(lut: &LutWarehouse, inputs: usize, outputs: usize) -> Result<(), CmsError> {
+        let (lut_inputs, lut_outputs) = match lut {
+            LutWarehouse::Lut(lut) => (lut.num_input_channels, lut.num_output_channels),
+            LutWarehouse::Multidimensional(mab) => {
+                (mab.num_input_channels, mab.num_output_channels)
+            }
+            LutWarehouse::MultiProcess(mpe) => (mpe.num_input_channels, mpe.num_output_channels),
+        };
+        if lut_inputs as usize != inputs || lut_outputs as usize != outputs || inputs > MAX_CHANNELS
+        {
+            return Err(CmsError::UnsupportedChannelConfiguration);
+        }
+        Ok(())
+    }
+
+    /// Number of source channels expected for each pixel
+    pub fn src_channels(&self) -> usize {
+        self.src_channels
+    }
+
+    /// Number of destination channels produced for each pixel
+    pub fn dst_channels(&self) -> usize {
+        self.dst_channels
+    }
+
+    /// Converts source device values into D50 PCS XYZ, where white has Y = 1.
+    ///
+    /// Luminance scaling, black point compensation, viewing adaptation
+    /// and surround compensation are already applied.
+    pub fn device_to_pcs(&self, src: &[f64]) -> Result<[f64; 3], CmsError> {
+        if src.len() != self.src_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let xyz = self
+            .source
+            .device_to_pcs(src)?
+            .map(|v| v * self.luminance_scale);
+        let xyz = self
+            .black_point_compensation
+            .map(|bpc| bpc.apply(xyz))
+            .unwrap_or(xyz);
+        let xyz = match self.viewing_adaptation {
+            Some(matrix) => matrix.mul_vector(Vector3d { v: xyz }).v,
+            None => xyz,
+        };
+        Ok(match self.surround_gamma {
+            Some(gamma) if xyz[1] > 0. => xyz.map(|v| v * xyz[1].powf(gamma - 1.)),
+            _ => xyz,
+        })
+    }
+
+    /// Converts D50 PCS XYZ, where white has Y = 1, into destination device values
+    pub fn pcs_to_device(&self, xyz: [f64; 3], dst: &mut [f64]) -> Result<(), CmsError> {
+        if dst.len() != self.dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        self.destination.pcs_to_device(xyz, dst)
+    }
+
+    /// Transforms single pixel
+    pub fn transform_pixel(&self, src: &[f64], dst: &mut [f64]) -> Result<(), CmsError> {
+        let xyz = self.device_to_pcs(src)?;
+        self.pcs_to_device(xyz, dst)
+    }
+
+    /// Transforms tightly packed pixels
+    pub fn transform(&self, src: &[f64], dst: &mut [f64]) -> Result<(), CmsError> {
+        if src.len() % self.src_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if dst.len() % self.dst_channels != 0 {
+            return Err(CmsError::LaneMultipleOfChannels);
+        }
+        if src.len() / self.src_channels != dst.len() / self.dst_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        for (src, dst) in src
+            .chunks_exact(self.src_channels)
+            .zip(dst.chunks_exact_mut(self.dst_channels))
+        {
+            self.transform_pixel(src, dst)?;
+        }
+        Ok(())
+    }
+}
+
+impl ColorProfile {
+    /// Evaluates single device color into D50 PCS XYZ, where white has Y = 1.
+    ///
+    /// Values are normalized to [0, 1] and ordered as profile color space channels.
+    /// Goes through the same slow path as [ReferenceTransform], so it is meant
+    /// for probing specific patches, not for images. Use [crate::Lab::from_pcs_xyz]
+    /// when Lab is needed.
+    pub fn device_to_pcs(&self, src: &[f32], intent: RenderingIntent) -> Result<Xyz, CmsError> {
+        if src.len() != self.color_space.channels() {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let options = TransformOptions {
+            rendering_intent: intent,
+            ..Default::default()
+        };
+        if let Some(profile) = self.resolve_lut_pcs(options) {
+            return profile.device_to_pcs(src, intent);
+        }
+        let src = src.iter().map(|&v| v as f64).collect::<Vec<_>>();
+        let xyz = ReferenceTransform::make_source_stage(self, options)?.device_to_pcs(&src)?;
+        Ok(Xyz::new(xyz[0] as f32, xyz[1] as f32, xyz[2] as f32))
+    }
+
+    /// Evaluates D50 PCS XYZ, where white has Y = 1, into device color.
+    ///
+    /// `dst` receives normalized values ordered as profile color space channels.
+    /// See [ColorProfile::device_to_pcs].
+    pub fn pcs_to_device(
+        &self,
+        xyz: Xyz,
+        intent: RenderingIntent,
+        dst: &mut [f32],
+    ) -> Result<(), CmsError> {
+        if dst.len() != self.color_space.channels() {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let options = TransformOptions {
+            rendering_intent: intent,
+            ..Default::default()
+        };
+        if let Some(profile) = self.resolve_lut_pcs(options) {
+            return profile.pcs_to_device(xyz, intent, dst);
+        }
+        let mut values = vec![0f64; dst.len()];
+        ReferenceTransform::make_destination_stage(self, options)?
+            .pcs_to_device([xyz.x as f64, xyz.y as f64, xyz.z as f64], &mut values)?;
+        for (dst, v) in dst.iter_mut().zip(values.iter()) {
+            *dst = *v as f32;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Layout;
+    use rand::Rng;
+
+    #[test]
+    fn test_reference_round_trip() {
+        let srgb = ColorProfile::new_srgb();
+        let profiles = [
+            ColorProfile::new_display_p3(),
+            ColorProfile::new_bt2020(),
+            ColorProfile::new_adobe_rgb(),
+            ColorProfile::new_gray_with_gamma(2.2),
+        ];
+        let mut rng = rand::rng();
+        for profile in profiles.iter() {
+            let forward =
+                ReferenceTransform::new(&srgb, profile, TransformOptions::default()).unwrap();
+            let backward =
+                ReferenceTransform::new(profile, &srgb, TransformOptions::default()).unwrap();
+            let mut intermediate = vec![0f64; forward.dst_channels()];
+            let mut dst = [0f64; 3];
+            for _ in 0..256 {
+                let v: f64 = rng.random_range(0.0..1.0);
+                // Gray round trip preserves only neutral colors
+                let src = if profile.color_space == DataColorSpace::Gray {
+                    [v, v, v]
+                } else {
+                    [v, rng.random_range(0.0..1.0), rng.random_range(0.0..1.0)]
+                };
+                forward.transform_pixel(&src, &mut intermediate).unwrap();
+                backward.transform_pixel(&intermediate, &mut dst).unwrap();
+                for (a, b) in src.iter().zip(dst.iter()) {
+                    assert!((a - b).abs() < 1e-5, "{src:?} became {dst:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reference_matches_matrix_shaper() {
+        let srgb = ColorProfile::new_srgb();
+        let p3 = ColorProfile::new_display_p3();
+        let options = TransformOptions::default();
+        let reference = ReferenceTransform::new(&srgb, &p3, options).unwrap();
+        let transform = srgb
+            .create_transform_16bit(Layout::Rgb, &p3, Layout::Rgb, options)
+            .unwrap();
+        let mut rng = rand::rng();
+        let src = (0..3 * 4096)
+            .map(|_| rng.random::<u16>())
+            .collect::<Vec<u16>>();
+        let mut dst = vec![0u16; src.len()];
+        transform.transform(&src, &mut dst).unwrap();
+        let mut expected = [0f64; 3];
+        for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact(3)) {
+            let src = [src[0], src[1], src[2]].map(|v| v as f64 / 65535.);
+            reference.transform_pixel(&src, &mut expected).unwrap();
+            for (&v, e) in dst.iter().zip(expected.iter()) {
+                let diff = (v as f64 - e * 65535.).abs();
+                assert!(diff <= 32., "difference {diff} for {src:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_reference_matches_lut() {
+        if let Ok(us_swop_coated) = std::fs::read("./assets/us_swop_coated.icc") {
+            let cmyk = ColorProfile::new_from_slice(&us_swop_coated).unwrap();
+            let srgb = ColorProfile::new_srgb();
+            let options = TransformOptions::default();
+            let reference = ReferenceTransform::new(&cmyk, &srgb, options).unwrap();
+            let transform = cmyk
+                .create_transform_8bit(Layout::Rgba, &srgb, Layout::Rgb, options)
+                .unwrap();
+            // Regular grid with nodes between CLUT nodes of 8 bit profiles
+            const LEVELS: usize = 11;
+            let level = |i: usize| ((i % LEVELS) * 255 / (LEVELS - 1)) as u8;
+            let src = (0..LEVELS.pow(4))
+                .flat_map(|i| {
+                    [
+                        level(i),
+                        level(i / LEVELS),
+                        level(i / LEVELS.pow(2)),
+                        level(i / LEVELS.pow(3)),
+                    ]
+                })
+                .collect::<Vec<u8>>();
+            let mut dst = vec![0u8; src.len() / 4 * 3];
+            transform.transform(&src, &mut dst).unwrap();
+            let mut expected = [0f64; 3];
+            let mut total_diff = 0f64;
+            let mut count = 0usize;
+            for (src, dst) in src.chunks_exact(4).zip(dst.chunks_exact(3)) {
+                let src = [src[0], src[1], src[2], src[3]].map(|v| v as f64 / 255.);
+                reference.transform_pixel(&src, &mut expected).unwrap();
+                // Interpolating gamut clipping kink in CLUT is inexact by nature
+                if expected.iter().any(|&e| !(0.05..=0.95).contains(&e)) {
+                    continue;
+                }
+                for (&v, e) in dst.iter().zip(expected.iter()) {
+                    let diff = (v as f64 - e * 255.).abs();
+                    // Worst case on this grid is 7.1 with SIMD and 6.96 with scalar kernels
+                    assert!(diff <= 7.5, "difference {diff} for {src:?}");
+                    total_diff += diff;
+                    count += 1;
+                }
+            }
+            assert!(
+                total_diff / count as f64 <= 1.,
+                "mean difference is too high"
+            );
+        }
+    }
+
+    #[test]
+    fn test_profile_single_point_evaluation() {
+        let srgb = ColorProfile::new_srgb();
+        let intent = RenderingIntent::Perceptual;
+        let white = srgb.device_to_pcs(&[1., 1., 1.], intent).unwrap();
+        let d50 = Chromaticity::D50.to_xyzd();
+        assert!((white.x as f64 - d50.x).abs() < 1e-3, "{white:?}");
+        assert!((white.y - 1.).abs() < 1e-3, "{white:?}");
+        assert!((white.z as f64 - d50.z).abs() < 1e-3, "{white:?}");
+
+        let src = [0.2f32, 0.6, 0.9];
+        let xyz = srgb.device_to_pcs(&src, intent).unwrap();
+        let mut dst = [0f32; 3];
+        srgb.pcs_to_device(xyz, intent, &mut dst).unwrap();
+        for (a, b) in src.iter().zip(dst.iter()) {
+            assert!((a - b).abs() < 1e-4, "{src:?} became {dst:?}");
+        }
+        assert!(srgb.device_to_pcs(&[1., 1.], intent).is_err());
+    }
+
+    #[test]
+    fn test_lcms_lut_matrix() {
+        // Legacy `mft1` profile with scaled matrix on RGB input
+        let identity = (0..=255u8).collect::<Vec<u8>>();
+        let mut clut = Vec::new();
+        for corner in 0..8u32 {
+            let lightness = corner.count_ones() * 255 / 3;
+            clut.extend_from_slice(&[lightness as u8, 128, 128]);
+        }
+        let mut legacy = ColorProfile {
+            color_space: DataColorSpace::Rgb,
+            pcs: DataColorSpace::Lab,
+            version_internal: ProfileVersion::V2_1,
+            ..Default::default()
+        };
+        legacy.lut_a_to_b_perceptual = Some(LutWarehouse::Lut(LutDataType {
+            num_input_channels: 3,
+            num_output_channels: 3,
+            num_clut_grid_points: 2,
+            matrix: Matrix3d {
+                v: [[0.5, 0., 0.], [0., 0.5, 0.], [0., 0., 0.5]],
+            },
+            num_input_table_entries: 256,
+            num_output_table_entries: 256,
+            input_table: LutStore::Store8(identity.repeat(3).into()),
+            clut_table: LutStore::Store8(clut.into()),
+            output_table: LutStore::Store8(identity.repeat(3).into()),
+            lut_type: crate::LutType::Lut8,
+        }));
+        let legacy = ColorProfile::new_from_slice(&legacy.encode().unwrap()).unwrap();
+        let mut srgb = ColorProfile::new_srgb();
+        srgb.cicp = None;
+
+        let mut whites = [0u16; 2];
+        for (white, lcms_lut_matrix) in whites.iter_mut().zip([false, true]) {
+            let options = TransformOptions {
+                lcms_lut_matrix,
+                ..Default::default()
+            };
+            let reference = ReferenceTransform::new(&legacy, &srgb, options).unwrap();
+            let transform = legacy
+                .create_transform_16bit(Layout::Rgb, &srgb, Layout::Rgb, options)
+                .unwrap();
+            let src = (0..=16u16)
+                .flat_map(|v| [v * 4095, v * 4095, v * 4095])
+                .collect::<Vec<u16>>();
+            let mut dst = vec![0u16; src.len()];
+            transform.transform(&src, &mut dst).unwrap();
+            let mut expected = [0f64; 3];
+            for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact(3)) {
+                let src = [src[0], src[1], src[2]].map(|v| v as f64 / 65535.);
+                reference.transform_pixel(&src, &mut expected).unwrap();
+                for (&v, e) in dst.iter().zip(expected.iter()) {
+                    let diff = (v as f64 - e * 65535.).abs();
+                    assert!(diff <= 256., "difference {diff} for {src:?}");
+                }
+            }
+            *white = dst[dst.len() - 2];
+        }
+        assert!(whites[0] > 65000, "{whites:?}");
+        assert!(whites[1] < 40000, "{whites:?}");
+    }
+}
@@ -0,0 +1,228 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{
+    CmsError, ColorProfile, Layout, Transform8BitExecutor, Transform16BitExecutor,
+    TransformF32BitExecutor, TransformOptions,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Profiles registered under application defined names, e.g. "working", "display" or "proof".
+///
+/// Lookups take a read lock, so transforms may be created from any thread while
+/// a profile is being replaced, e.g. when user moves a window to another monitor.
+/// Transforms created before a replacement keep the old profile, use [ProfileRegistry::generation]
+/// to find out that they should be rebuilt.
+#[derive(Debug, Default)]
+pub struct ProfileRegistry {
+    profiles: RwLock<Vec<(String, Arc<ColorProfile>)>>,
+    generation: AtomicU64,
+}
+
+static GLOBAL_REGISTRY: ProfileRegistry = ProfileRegistry::new();
+
+impl ProfileRegistry {
+    pub const fn new() -> Self {
+        Self {
+            profiles: RwLock::new(Vec::new()),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Process wide registry
+    pub fn global() -> &'static ProfileRegistry {
+        &GLOBAL_REGISTRY
+    }
+
+    /// Registers `profile` under `name`, replacing and returning previous one if any.
+    pub fn register(
+        &self,
+        name: impl Into<String>,
+        profile: ColorProfile,
+    ) -> Option<Arc<ColorProfile>> {
+        let name = name.into();
+        let profile = Arc::new(profile);
+        let mut profiles = self.profiles.write().unwrap_or_else(|e| e.into_inner());
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        match profiles.iter_mut().find(|(k, _)| *k == name) {
+            Some((_, current)) => Some(std::mem::replace(current, profile)),
+            None => {
+                profiles.push((name, profile));
+                None
+            }
+        }
+    }
+
+    /// Removes profile registered under `name`
+    pub fn remove(&self, name: &str) -> Option<Arc<ColorProfile>> {
+        let mut profiles = self.profiles.write().unwrap_or_else(|e| e.into_inner());
+        let index = profiles.iter().position(|(k, _)| k == name)?;
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        Some(profiles.remove(index).1)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<ColorProfile>> {
+        self.profiles
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, profile)| profile.clone())
+    }
+
+    /// Names of registered profiles in registration order
+    pub fn names(&self) -> Vec<String> {
+        self.profiles
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
+    /// Incremented on every registration or removal
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    fn pair(
+        &self,
+        source: &str,
+        destination: &str,
+    ) -> Result<(Arc<ColorProfile>, Arc<ColorProfile>), CmsError> {
+        let source = self
+            .get(source)
+            .ok_or_else(|| CmsError::ProfileNotRegistered(source.to_string()))?;
+        let destination = self
+            .get(destination)
+            .ok_or_else(|| CmsError::ProfileNotRegistered(destination.to_string()))?;
+        Ok((source, destination))
+    }
+
+    /// Creates 8 bit transform between profiles registered under `source` and `destination`
+    pub fn create_transform_8bit(
+        &self,
+        source: &str,
+        src_layout: Layout,
+        destination: &str,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<Transform8BitExecutor>, CmsError> {
+        let (src, dst) = self.pair(source, destination)?;
+        src.create_transform_8bit(src_layout, &dst, dst_layout, options)
+    }
+
+    /// Creates 16 bit transform between profiles registered under `source` and `destination`
+    pub fn create_transform_16bit(
+        &self,
+        source: &str,
+        src_layout: Layout,
+        destination: &str,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<Transform16BitExecutor>, CmsError> {
+        let (src, dst) = self.pair(source, destination)?;
+        src.create_transform_16bit(src_layout, &dst, dst_layout, options)
+    }
+
+    /// Creates f32 transform between profiles registered under `source` and `destination`
+    pub fn create_transform_f32(
+        &self,
+        source: &str,
+        src_layout: Layout,
+        destination: &str,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<TransformF32BitExecutor>, CmsError> {
+        let (src, dst) = self.pair(source, destination)?;
+        src.create_transform_f32(src_layout, &dst, dst_layout, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_registry() {
+        let registry = Arc::new(ProfileRegistry::new());
+        registry.register("working", ColorProfile::new_srgb());
+        registry.register("display", ColorProfile::new_srgb());
+        assert_eq!(registry.names(), ["working", "display"]);
+        let generation = registry.generation();
+
+        let src = [255u8, 0, 0];
+        let mut identity = [0u8; 3];
+        registry
+            .create_transform_8bit(
+                "working",
+                Layout::Rgb,
+                "display",
+                Layout::Rgb,
+                Default::default(),
+            )
+            .unwrap()
+            .transform(&src, &mut identity)
+            .unwrap();
+        assert_eq!(identity, src);
+
+        let swapper = {
+            let registry = registry.clone();
+            std::thread::spawn(move || registry.register("display", ColorProfile::new_display_p3()))
+        };
+        assert!(swapper.join().unwrap().is_some());
+        assert!(registry.generation() > generation);
+
+        let mut swapped = [0u8; 3];
+        registry
+            .create_transform_8bit(
+                "working",
+                Layout::Rgb,
+                "display",
+                Layout::Rgb,
+                Default::default(),
+            )
+            .unwrap()
+            .transform(&src, &mut swapped)
+            .unwrap();
+        assert_ne!(swapped, src);
+
+        assert!(registry.remove("proof").is_none());
+        assert!(matches!(
+            registry.create_transform_16bit(
+                "proof",
+                Layout::Rgb,
+                "display",
+                Layout::Rgb,
+                Default::default()
+            ),
+            Err(CmsError::ProfileNotRegistered(name)) if name == "proof"
+        ));
+    }
+}
@@ -0,0 +1,263 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::err::CmsError;
+
+/// Formula of a `parf` curve segment as defined by ICC.1 for segmented curves
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentFormula {
+    /// `Y = (a * X + b) ^ gamma + c`
+    Gamma { gamma: f32, a: f32, b: f32, c: f32 },
+    /// `Y = a * log10(b * X ^ gamma + c) + d`
+    Log {
+        gamma: f32,
+        a: f32,
+        b: f32,
+        c: f32,
+        d: f32,
+    },
+    /// `Y = a * b ^ (c * X + d) + e`
+    Exp {
+        a: f32,
+        b: f32,
+        c: f32,
+        d: f32,
+        e: f32,
+    },
+}
+
+impl SegmentFormula {
+    /// Function type as it is stored in `parf` element
+    pub const fn function_type(&self) -> u16 {
+        match self {
+            SegmentFormula::Gamma { .. } => 0,
+            SegmentFormula::Log { .. } => 1,
+            SegmentFormula::Exp { .. } => 2,
+        }
+    }
+
+    pub(crate) fn eval(&self, x: f32) -> f32 {
+        match *self {
+            SegmentFormula::Gamma { gamma, a, b, c } => {
+                let base = a * x + b;
+                if base <= 0. { c } else { base.powf(gamma) + c }
+            }
+            SegmentFormula::Log { gamma, a, b, c, d } => {
+                let arg = b * x.max(0.).powf(gamma) + c;
+                if arg <= 0. { d } else { a * arg.log10() + d }
+            }
+            SegmentFormula::Exp { a, b, c, d, e } => a * b.powf(c * x + d) + e,
+        }
+    }
+}
+
+/// One segment of [SegmentedCurve]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CurveSegment {
+    Formula(SegmentFormula),
+    /// Values evenly spread over the segment, value at the segment start is taken
+    /// from the previous segment.
+    Sampled(Vec<f32>),
+}
+
+/// Segmented curve `curf`, segment `i` covers `(breakpoints[i - 1], breakpoints[i]]`,
+/// first and last segments extend to infinity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentedCurve {
+    pub(crate) breakpoints: Vec<f32>,
+    pub(crate) segments: Vec<CurveSegment>,
+}
+
+impl SegmentedCurve {
+    /// Creates segmented curve, there must be one segment more than breakpoints,
+    /// breakpoints must ascend, first and last segments must be formulas.
+    pub fn new(breakpoints: Vec<f32>, segments: Vec<CurveSegment>) -> Result<Self, CmsError> {
+        if segments.is_empty() || segments.len() != breakpoints.len() + 1 {
+            return Err(CmsError::MalformedTrcCurve(
+                "Segmented curve must have one segment more than breakpoints".to_string(),
+            ));
+        }
+        if breakpoints.iter().any(|x| !x.is_finite())
+            || breakpoints.windows(2).any(|w| w[0] >= w[1])
+        {
+            return Err(CmsError::MalformedTrcCurve(
+                "Segmented curve breakpoints must ascend".to_string(),
+            ));
+        }
+        if matches!(segments.first(), Some(CurveSegment::Sampled(_)))
+            || matches!(segments.last(), Some(CurveSegment::Sampled(_)))
+        {
+            return Err(CmsError::MalformedTrcCurve(
+                "Unbounded segment can't be sampled".to_string(),
+            ));
+        }
+        if segments
+            .iter()
+            .any(|x| matches!(x, CurveSegment::Sampled(samples) if samples.is_empty()))
+        {
+            return Err(CmsError::MalformedTrcCurve(
+                "Sampled segment is empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            breakpoints,
+            segments,
+        })
+    }
+
+    pub fn breakpoints(&self) -> &[f32] {
+        &self.breakpoints
+    }
+
+    pub fn segments(&self) -> &[CurveSegment] {
+        &self.segments
+    }
+
+    /// Value of segment `index` at its end breakpoint
+    fn segment_end_value(&self, index: usize) -> f32 {
+        match &self.segments[index] {
+            CurveSegment::Formula(formula) => formula.eval(self.breakpoints[index]),
+            CurveSegment::Sampled(samples) => samples.last().copied().unwrap_or(0.),
+        }
+    }
+
+    /// Evaluates curve at `x`
+    pub fn eval(&self, x: f32) -> f32 {
+        let index = self.breakpoints.partition_point(|&bp| bp < x);
+        match &self.segments[index] {
+            CurveSegment::Formula(formula) => formula.eval(x),
+            CurveSegment::Sampled(samples) => {
+                // Sampled segments never are first or last
+                let start = self.breakpoints[index - 1];
+                let end = self.breakpoints[index];
+                let pos = (x - start) / (end - start) * samples.len() as f32;
+                let lo = (pos.floor().max(0.) as usize).min(samples.len() - 1);
+                let t = pos - lo as f32;
+                let y0 = if lo == 0 {
+                    self.segment_end_value(index - 1)
+                } else {
+                    samples[lo - 1]
+                };
+                let y1 = samples[lo];
+                y0 + (y1 - y0) * t
+            }
+        }
+    }
+
+    /// Samples curve on [0, 1] into `curv` like table
+    pub(crate) fn to_lut(&self, entries: usize) -> Vec<u16> {
+        let divisor = (entries.max(2) - 1) as f32;
+        (0..entries.max(2))
+            .map(|i| {
+                let v = self.eval(i as f32 / divisor);
+                (v.max(0.).min(1.) * 65535. + 0.5) as u16
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segmented_curve_eval() {
+        let curve = SegmentedCurve::new(
+            vec![0., 0.5],
+            vec![
+                CurveSegment::Formula(SegmentFormula::Gamma {
+                    gamma: 1.,
+                    a: 0.,
+                    b: 0.,
+                    c: 0.,
+                }),
+                CurveSegment::Sampled(vec![0.125, 0.25, 0.5]),
+                CurveSegment::Formula(SegmentFormula::Gamma {
+                    gamma: 2.,
+                    a: 1.,
+                    b: 0.,
+                    c: 0.25,
+                }),
+            ],
+        )
+        .unwrap();
+        assert_eq!(curve.eval(-1.), 0.);
+        assert!((curve.eval(0.25) - 0.1875).abs() < 1e-6);
+        assert!((curve.eval(0.5) - 0.5).abs() < 1e-6);
+        assert!((curve.eval(1.) - 1.25).abs() < 1e-6);
+        assert!(SegmentedCurve::new(vec![0.5, 0.], curve.segments.clone()).is_err());
+        assert!(SegmentedCurve::new(vec![0.], curve.segments.clone()).is_err());
+    }
+
+    #[test]
+    fn test_segmented_curve_roundtrip() {
+        use crate::{ColorProfile, Layout, ToneReprCurve, TransformOptions};
+
+        // sRGB EOTF with sampled toe
+        let toe = (1..=16)
+            .map(|i| i as f32 * (0.04045 / 16.) / 12.92)
+            .collect::<Vec<_>>();
+        let curve = SegmentedCurve::new(
+            vec![0., 0.04045],
+            vec![
+                CurveSegment::Formula(SegmentFormula::Gamma {
+                    gamma: 1.,
+                    a: 1. / 12.92,
+                    b: 0.,
+                    c: 0.,
+                }),
+                CurveSegment::Sampled(toe),
+                CurveSegment::Formula(SegmentFormula::Gamma {
+                    gamma: 2.4,
+                    a: 1. / 1.055,
+                    b: 0.055 / 1.055,
+                    c: 0.,
+                }),
+            ],
+        )
+        .unwrap();
+        let mut profile = ColorProfile::new_srgb();
+        profile.red_trc = Some(ToneReprCurve::Segmented(curve.clone()));
+        profile.green_trc = Some(ToneReprCurve::Segmented(curve.clone()));
+        profile.blue_trc = Some(ToneReprCurve::Segmented(curve));
+        let encoded = profile.encode().unwrap();
+        let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+        assert_eq!(decoded.red_trc, profile.red_trc);
+
+        let srgb = ColorProfile::new_srgb();
+        let transform = decoded
+            .create_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, TransformOptions::default())
+            .unwrap();
+        let src = [0u8, 5, 10, 64, 128, 200, 255, 255, 255];
+        let mut dst = [0u8; 9];
+        transform.transform(&src, &mut dst).unwrap();
+        for (&a, &b) in src.iter().zip(dst.iter()) {
+            assert!((a as i32 - b as i32).abs() <= 1, "{src:?} {dst:?}");
+        }
+    }
+}
@@ -0,0 +1,127 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{CmsError, ColorProfile, Layout, RenderingIntent, Stage};
+use std::fmt::Debug;
+
+/// Kind of iccMAX spectral profile connection space
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum SpectralPcsKind {
+    /// Reflectance spectral PCS, `rs`
+    Reflectance,
+    /// Transmission spectral PCS, `ts`
+    Transmission,
+    /// Radiant emission spectral PCS, `es`
+    RadiantEmission,
+    /// Bi-spectral reflectance PCS, `bs`
+    BiSpectralReflectance,
+    /// Sparse matrix reflectance PCS, `sm`
+    SparseMatrixReflectance,
+}
+
+/// Spectral (N-component) profile connection space declared in the profile header
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct SpectralPcs {
+    pub kind: SpectralPcsKind,
+    /// Count of PCS components
+    pub components: u16,
+}
+
+impl SpectralPcs {
+    /// Decodes spectral PCS signature from the header, zero means no spectral PCS
+    pub(crate) fn from_signature(value: u32) -> Result<Option<SpectralPcs>, CmsError> {
+        if value == 0 {
+            return Ok(None);
+        }
+        let kind = match (value >> 16) as u16 {
+            0x7273 => SpectralPcsKind::Reflectance,
+            0x7473 => SpectralPcsKind::Transmission,
+            0x6573 => SpectralPcsKind::RadiantEmission,
+            0x6273 => SpectralPcsKind::BiSpectralReflectance,
+            0x736d => SpectralPcsKind::SparseMatrixReflectance,
+            _ => return Err(CmsError::InvalidProfile),
+        };
+        let components = (value & 0xffff) as u16;
+        if components == 0 {
+            return Err(CmsError::InvalidProfile);
+        }
+        Ok(Some(SpectralPcs { kind, components }))
+    }
+
+    /// Encodes spectral PCS into header signature
+    pub fn signature(self) -> u32 {
+        let kind: u32 = match self.kind {
+            SpectralPcsKind::Reflectance => 0x7273,
+            SpectralPcsKind::Transmission => 0x7473,
+            SpectralPcsKind::RadiantEmission => 0x6573,
+            SpectralPcsKind::BiSpectralReflectance => 0x6273,
+            SpectralPcsKind::SparseMatrixReflectance => 0x736d,
+        };
+        (kind << 16) | self.components as u32
+    }
+}
+
+/// Custom handler for profiles connected only through a spectral PCS.
+///
+/// Spectral `D2Bx`/`B2Dx` tags are not evaluated by this crate; raw tag data
+/// is kept in the profile, and handler is expected to interpret it.
+/// Stages work with normalized values in *[0, 1]* range and D50 CIE XYZ PCS.
+pub trait SpectralPcsHandler: Debug + Send + Sync {
+    /// Creates stage converting device values of `layout` into CIE XYZ, 3 values per pixel.
+    fn device_to_pcs(
+        &self,
+        profile: &ColorProfile,
+        layout: Layout,
+        intent: RenderingIntent,
+    ) -> Result<Box<dyn Stage + Send + Sync>, CmsError>;
+
+    /// Creates stage converting CIE XYZ, 3 values per pixel, into device values of `layout`.
+    fn pcs_to_device(
+        &self,
+        profile: &ColorProfile,
+        layout: Layout,
+        intent: RenderingIntent,
+    ) -> Result<Box<dyn Stage + Send + Sync>, CmsError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectral_pcs_signature() {
+        let pcs = SpectralPcs::from_signature(u32::from_be_bytes([b'r', b's', 0, 36]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(pcs.kind, SpectralPcsKind::Reflectance);
+        assert_eq!(pcs.components, 36);
+        assert_eq!(pcs.signature(), u32::from_be_bytes([b'r', b's', 0, 36]));
+        assert_eq!(SpectralPcs::from_signature(0), Ok(None));
+        assert!(SpectralPcs::from_signature(u32::from_be_bytes(*b"zz\0\x03")).is_err());
+    }
+}
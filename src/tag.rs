@@ -49,6 +49,12 @@ pub(crate) enum Tag {
     PcsToDeviceLutPerceptual,
     PcsToDeviceLutColorimetric,
     PcsToDeviceLutSaturation,
+    DeviceToSpectralPcsPerceptual,
+    DeviceToSpectralPcsColorimetric,
+    DeviceToSpectralPcsSaturation,
+    SpectralPcsToDevicePerceptual,
+    SpectralPcsToDeviceColorimetric,
+    SpectralPcsToDeviceSaturation,
     ProfileDescription,
     Copyright,
     ViewingConditionsDescription,
@@ -62,6 +68,8 @@ pub(crate) enum Tag {
     CharTarget,
     Technology,
     CalibrationDateTime,
+    VideoCardGamma,
+    NamedColor,
 }
 
 impl TryFrom<u32> for Tag {
@@ -102,6 +110,18 @@ impl TryFrom<u32> for Tag {
             return Ok(Self::PcsToDeviceLutColorimetric);
         } else if value == u32::from_ne_bytes(*b"B2A2").to_be() {
             return Ok(Self::PcsToDeviceLutSaturation);
+        } else if value == u32::from_ne_bytes(*b"D2B0").to_be() {
+            return Ok(Self::DeviceToSpectralPcsPerceptual);
+        } else if value == u32::from_ne_bytes(*b"D2B1").to_be() {
+            return Ok(Self::DeviceToSpectralPcsColorimetric);
+        } else if value == u32::from_ne_bytes(*b"D2B2").to_be() {
+            return Ok(Self::DeviceToSpectralPcsSaturation);
+        } else if value == u32::from_ne_bytes(*b"B2D0").to_be() {
+            return Ok(Self::SpectralPcsToDevicePerceptual);
+        } else if value == u32::from_ne_bytes(*b"B2D1").to_be() {
+            return Ok(Self::SpectralPcsToDeviceColorimetric);
+        } else if value == u32::from_ne_bytes(*b"B2D2").to_be() {
+            return Ok(Self::SpectralPcsToDeviceSaturation);
         } else if value == u32::from_ne_bytes(*b"desc").to_be() {
             return Ok(Self::ProfileDescription);
         } else if value == u32::from_ne_bytes(*b"cprt").to_be() {
@@ -128,6 +148,10 @@ impl TryFrom<u32> for Tag {
             return Ok(Self::Technology);
         } else if value == u32::from_ne_bytes(*b"calt").to_be() {
             return Ok(Self::CalibrationDateTime);
+        } else if value == u32::from_ne_bytes(*b"vcgt").to_be() {
+            return Ok(Self::VideoCardGamma);
+        } else if value == u32::from_ne_bytes(*b"ncl2").to_be() {
+            return Ok(Self::NamedColor);
         }
         Err(CmsError::UnknownTag(value))
     }
@@ -153,6 +177,12 @@ impl From<Tag> for u32 {
             Tag::PcsToDeviceLutPerceptual => u32::from_ne_bytes(*b"B2A0").to_be(),
             Tag::PcsToDeviceLutColorimetric => u32::from_ne_bytes(*b"B2A1").to_be(),
             Tag::PcsToDeviceLutSaturation => u32::from_ne_bytes(*b"B2A2").to_be(),
+            Tag::DeviceToSpectralPcsPerceptual => u32::from_ne_bytes(*b"D2B0").to_be(),
+            Tag::DeviceToSpectralPcsColorimetric => u32::from_ne_bytes(*b"D2B1").to_be(),
+            Tag::DeviceToSpectralPcsSaturation => u32::from_ne_bytes(*b"D2B2").to_be(),
+            Tag::SpectralPcsToDevicePerceptual => u32::from_ne_bytes(*b"B2D0").to_be(),
+            Tag::SpectralPcsToDeviceColorimetric => u32::from_ne_bytes(*b"B2D1").to_be(),
+            Tag::SpectralPcsToDeviceSaturation => u32::from_ne_bytes(*b"B2D2").to_be(),
             Tag::ProfileDescription => u32::from_ne_bytes(*b"desc").to_be(),
             Tag::Copyright => u32::from_ne_bytes(*b"cprt").to_be(),
             Tag::ViewingConditionsDescription => u32::from_ne_bytes(*b"vued").to_be(),
@@ -166,6 +196,8 @@ impl From<Tag> for u32 {
             Tag::CharTarget => u32::from_ne_bytes(*b"targ").to_be(),
             Tag::Technology => u32::from_ne_bytes(*b"tech").to_be(),
             Tag::CalibrationDateTime => u32::from_ne_bytes(*b"calt").to_be(),
+            Tag::VideoCardGamma => u32::from_ne_bytes(*b"vcgt").to_be(),
+            Tag::NamedColor => u32::from_ne_bytes(*b"ncl2").to_be(),
         }
     }
 }
@@ -179,6 +211,7 @@ pub(crate) enum TagTypeDefinition {
     MbaLut,
     ParametricToneCurve,
     LutToneCurve,
+    SegmentedCurve,
     Xyz,
     MultiProcessElement,
     DefViewingConditions,
@@ -192,6 +225,8 @@ pub(crate) enum TagTypeDefinition {
     U32Array,
     U64Array,
     Measurement,
+    VideoCardGamma,
+    NamedColor,
     NotAllowed,
 }
 
@@ -211,6 +246,8 @@ impl From<u32> for TagTypeDefinition {
             return TagTypeDefinition::ParametricToneCurve;
         } else if value == u32::from_ne_bytes(*b"curv").to_be() {
             return TagTypeDefinition::LutToneCurve;
+        } else if value == u32::from_ne_bytes(*b"curf").to_be() {
+            return TagTypeDefinition::SegmentedCurve;
         } else if value == u32::from_ne_bytes(*b"XYZ ").to_be() {
             return TagTypeDefinition::Xyz;
         } else if value == u32::from_ne_bytes(*b"mpet").to_be() {
@@ -237,6 +274,10 @@ impl From<u32> for TagTypeDefinition {
             return TagTypeDefinition::U64Array;
         } else if value == u32::from_ne_bytes(*b"ui08").to_be() {
             return TagTypeDefinition::U8Array;
+        } else if value == u32::from_ne_bytes(*b"vcgt").to_be() {
+            return TagTypeDefinition::VideoCardGamma;
+        } else if value == u32::from_ne_bytes(*b"ncl2").to_be() {
+            return TagTypeDefinition::NamedColor;
         }
         TagTypeDefinition::NotAllowed
     }
@@ -252,6 +293,7 @@ impl From<TagTypeDefinition> for u32 {
             TagTypeDefinition::MbaLut => u32::from_ne_bytes(*b"mBA ").to_be(),
             TagTypeDefinition::ParametricToneCurve => u32::from_ne_bytes(*b"para").to_be(),
             TagTypeDefinition::LutToneCurve => u32::from_ne_bytes(*b"curv").to_be(),
+            TagTypeDefinition::SegmentedCurve => u32::from_ne_bytes(*b"curf").to_be(),
             TagTypeDefinition::Xyz => u32::from_ne_bytes(*b"XYZ ").to_be(),
             TagTypeDefinition::MultiProcessElement => u32::from_ne_bytes(*b"mpet").to_be(),
             TagTypeDefinition::DefViewingConditions => u32::from_ne_bytes(*b"view").to_be(),
@@ -265,6 +307,8 @@ impl From<TagTypeDefinition> for u32 {
             TagTypeDefinition::U32Array => u32::from_ne_bytes(*b"ui32").to_be(),
             TagTypeDefinition::U64Array => u32::from_ne_bytes(*b"ui64").to_be(),
             TagTypeDefinition::Measurement => u32::from_ne_bytes(*b"meas").to_be(),
+            TagTypeDefinition::VideoCardGamma => u32::from_ne_bytes(*b"vcgt").to_be(),
+            TagTypeDefinition::NamedColor => u32::from_ne_bytes(*b"ncl2").to_be(),
             TagTypeDefinition::NotAllowed => 0,
         }
     }
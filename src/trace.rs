@@ -0,0 +1,76 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Spans for latency attribution, compiled out completely without `tracing` feature.
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_span {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        tracing::debug_span!($name $(, $($fields)*)?).entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_span {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        crate::trace::NoSpan
+    };
+}
+
+pub(crate) use trace_span;
+
+/// Placeholder for entered span when tracing is disabled
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+#[cfg(feature = "tracing")]
+pub(crate) struct TracedExecutor<T> {
+    pub(crate) inner: Box<dyn crate::TransformExecutor<T> + Send + Sync>,
+}
+
+#[cfg(feature = "tracing")]
+impl<T: Copy + Default> crate::TransformExecutor<T> for TracedExecutor<T> {
+    fn transform(&self, src: &[T], dst: &mut [T]) -> Result<(), crate::CmsError> {
+        let _span = trace_span!("moxcms::transform", samples = src.len());
+        self.inner.transform(src, dst)
+    }
+
+    fn fixed_point_format(&self) -> Option<crate::FixedPointFormat> {
+        self.inner.fixed_point_format()
+    }
+
+    fn transform_with_scratch(
+        &self,
+        src: &[T],
+        dst: &mut [T],
+        scratch: &mut crate::TransformScratch,
+    ) -> Result<(), crate::CmsError> {
+        let _span = trace_span!("moxcms::transform", samples = src.len());
+        self.inner.transform_with_scratch(src, dst, scratch)
+    }
+}
@@ -26,22 +26,117 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+use crate::chad::adaption_matrix_d;
 use crate::conversions::{
-    LutBarycentricReduction, RgbXyzFactory, RgbXyzFactoryOpt, ToneReproductionRgbToGray,
-    TransformMatrixShaper, make_gray_to_unfused, make_gray_to_x, make_lut_transform,
-    make_rgb_to_gray,
+    BlackPointCompensation, LUT_BLOCK_SIZE, LutBarycentricReduction, RgbXyzFactory,
+    RgbXyzFactoryOpt, ToneReproductionRgbToGray, TransformMatrixShaper, do_any_to_pcs,
+    make_gray_to_unfused, make_gray_to_x, make_lut_transform, make_rgb_to_gray, make_rgb_to_luma,
+    make_rgb_xyz_rgb_transform_scalar,
 };
+use crate::device_link::make_device_link_transform;
 use crate::err::CmsError;
+use crate::mlaf::mlaf;
+use crate::named::make_named_color_transform;
+use crate::trace::trace_span;
 use crate::trc::GammaLutInterpolate;
-use crate::{ColorProfile, DataColorSpace, LutWarehouse, RenderingIntent, Vector3f, Xyzd};
+use crate::{
+    Chromaticity, ColorProfile, CurveExtension, DataColorSpace, LutMultidimensionalType,
+    LutWarehouse, Matrix3d, ProfileClass, RenderingIntent, ToneReprCurve, Vector3d, Vector3f, Xyzd,
+};
 use num_traits::AsPrimitive;
 use std::marker::PhantomData;
 
+#[cfg(feature = "options")]
+use crate::lut_hint::ClutSmoothness;
+#[cfg(feature = "options")]
+const AUTO_DENSE_GRID_SIZE: u8 = 33;
+#[cfg(feature = "options")]
+const AUTO_SMOOTH_CURVATURE: f32 = 1.0;
+
 /// Transformation executor itself
 pub trait TransformExecutor<V: Copy + Default> {
     /// Count of samples always must match.
     /// If there is N samples of *Cmyk* source then N samples of *Rgb* is expected as an output.
     fn transform(&self, src: &[V], dst: &mut [V]) -> Result<(), CmsError>;
+
+    /// Returns fixed point format used by the executor,
+    /// or `None` if transform is executed in floating point.
+    fn fixed_point_format(&self) -> Option<FixedPointFormat> {
+        None
+    }
+
+    /// Same as [TransformExecutor::transform], but intermediate buffers are taken from `scratch`.
+    ///
+    /// Once scratch has grown to the largest processed row, subsequent calls do not allocate,
+    /// what is useful for real-time processing. Executors that do not need
+    /// intermediate storage simply ignore it.
+    fn transform_with_scratch(
+        &self,
+        src: &[V],
+        dst: &mut [V],
+        scratch: &mut TransformScratch,
+    ) -> Result<(), CmsError> {
+        _ = scratch;
+        self.transform(src, dst)
+    }
+
+    /// Converts many independent buffers, e.g. tiles of an image, one after another.
+    ///
+    /// Each pair is a source and its destination, with the same requirements as
+    /// [TransformExecutor::transform]. Intermediate storage is shared by all of them,
+    /// so small buffers don't pay for allocation each. Conversion stops on the first error.
+    /// See [crate::transform_many_parallel] to spread buffers between threads.
+    fn transform_many(&self, buffers: &mut [(&[V], &mut [V])]) -> Result<(), CmsError> {
+        let mut scratch = TransformScratch::default();
+        for (src, dst) in buffers.iter_mut() {
+            self.transform_with_scratch(src, dst, &mut scratch)?;
+        }
+        Ok(())
+    }
+
+    /// Same as [TransformExecutor::transform], measuring time spent in each stage.
+    ///
+    /// Stages are timed only by staged pipelines, fused executors report total time.
+    #[cfg(feature = "bench")]
+    fn transform_timed(&self, src: &[V], dst: &mut [V]) -> Result<crate::StageTimings, CmsError> {
+        let start = std::time::Instant::now();
+        self.transform(src, dst)?;
+        Ok(crate::StageTimings {
+            total: start.elapsed(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Reusable working memory for [TransformExecutor::transform_with_scratch].
+///
+/// Scratch is not bound to any transform and might be shared between them,
+/// but not between threads at the same time.
+#[derive(Debug, Default, Clone)]
+pub struct TransformScratch {
+    pub(crate) buffer: Vec<f32>,
+}
+
+impl TransformScratch {
+    /// Count of pixels processed at once by transforms in [TransformOptions::real_time] mode.
+    pub const REAL_TIME_PIXELS: usize = 1024;
+
+    /// Creates empty scratch, it grows on the first use.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates scratch large enough to process `pixels` without further allocations.
+    pub fn with_capacity(pixels: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(pixels * 3),
+        }
+    }
+
+    /// Creates scratch enough for any transform created in [TransformOptions::real_time] mode.
+    pub fn real_time() -> Self {
+        Self::with_capacity(Self::REAL_TIME_PIXELS)
+    }
 }
 
 /// Helper for intermediate transformation stages
@@ -85,6 +180,8 @@ pub struct TransformOptions {
     ///
     /// Q2.13 for RGB->XYZ->RGB is used.
     /// LUT interpolation use Q0.15.
+    /// Gray sources without direct path are tabulated for every gray code,
+    /// other four channel sources are sampled into a grid interpolated in Q0.15.
     pub prefer_fixed_point: bool,
     /// Interpolation method for 3D LUT
     ///
@@ -107,7 +204,264 @@ pub struct TransformOptions {
     /// This allows to work with excellent precision with extended range,
     /// at a cost of execution time.
     pub allow_extended_range_rgb_xyz: bool,
-    // pub black_point_compensation: bool,
+    /// Forces exact fixed point format for *Matrix Shaper* transforms.
+    ///
+    /// `None` lets the library choose format depending on bit-depth and CPU features,
+    /// the chosen one can be queried later with [TransformExecutor::fixed_point_format].
+    /// If the requested format is not available for the transform then
+    /// [CmsError::UnsupportedFixedPointFormat] is returned.
+    pub fixed_point_format: Option<FixedPointFormat>,
+    /// Count of pixels processed in a block by RGB 3D LUT transforms, both floating and fixed point.
+    ///
+    /// CLUT nodes for the whole block are prefetched before interpolation,
+    /// this hides memory latency on large grids. `0` or `1` disables prefetching.
+    /// Default value is good for most CPUs, it is exposed mostly for tuning.
+    pub lut_block_size: usize,
+    /// Real-time mode for audio/video callback-like threads.
+    ///
+    /// All precomputation always happens when transform is created, nothing is deferred
+    /// to the first call. When this is set, transforms that need intermediate storage also
+    /// process rows in chunks of [TransformScratch::REAL_TIME_PIXELS], so the row length
+    /// doesn't affect the working memory and the latency between stages.
+    /// Together with a scratch from [TransformScratch::real_time] passed to
+    /// [TransformExecutor::transform_with_scratch] no allocation happens on conversion.
+    ///
+    /// Plain [TransformExecutor::transform] uses thread local scratch instead, which is
+    /// allocated once per thread.
+    pub real_time: bool,
+    /// Scales linear light by [ColorProfile::luminance_scale] of the profile pair.
+    ///
+    /// Keeps brightness in nits when content moves between displays of different peak
+    /// luminance, e.g. SDR white is not stretched to HDR display peak.
+    /// Has no effect unless both profiles have `lumi` tag.
+    pub luminance_scaling: bool,
+    /// Weights used when *Matrix Shaper* RGB is converted to grayscale.
+    ///
+    /// Default [GrayWeights::Colorimetric] computes luminance through PCS.
+    /// Other weights compute luma directly from gamma-encoded RGB as video standards
+    /// and document scanning pipelines expect.
+    pub gray_weights: GrayWeights,
+    /// Overrides PCS declared in header of LUT based profiles.
+    ///
+    /// Some profiles in the wild declare XYZ PCS while their LUTs produce Lab, or the opposite.
+    /// When this is `None`, device white is evaluated through `A2Bx` and the header PCS is
+    /// replaced only if LUT output obviously belongs to the other one.
+    /// Set to `Some(DataColorSpace::Lab)` or `Some(DataColorSpace::Xyz)` to force PCS
+    /// for every LUT based profile in the transform. Matrix shaper profiles are not affected.
+    pub assume_pcs: Option<DataColorSpace>,
+    /// Behavior of parametric curves outside of [0, 1] when
+    /// [TransformOptions::allow_extended_range_rgb_xyz] is in force.
+    ///
+    /// Default [CurveExtension::Mirror] keeps sRGB like extended range,
+    /// [CurveExtension::Clip] follows ICC specification.
+    pub curve_extension: CurveExtension,
+    /// Caps threads used by [crate::ChunkPlan::for_options], `None` uses every available thread.
+    ///
+    /// Transforms themselves never spawn threads, parallel conversion happens only through
+    /// [crate::transform_parallel] and [crate::transform_parallel_with].
+    pub max_threads: Option<usize>,
+    /// Applies matrix of `lut8` and `lut16` tags to every 3 channel input, as lcms2 does.
+    ///
+    /// ICC specification uses this matrix only when input of the LUT is PCS XYZ.
+    /// lcms2 applies it whenever it is not identity, and some legacy ColorSync profiles
+    /// carry scaled matrices that render as intended only with that behavior.
+    pub lcms_lut_matrix: bool,
+    /// Maps source black onto destination black, scaling PCS XYZ towards D50 white.
+    ///
+    /// Applies to relative colorimetric and saturation intents only. Absolute colorimetric
+    /// keeps media black by definition, and perceptual tables are expected to handle black
+    /// themselves. Black points come from `bkpt` tag or are estimated,
+    /// see [ColorProfile::media_black].
+    pub black_point_compensation: bool,
+    /// Adapts PCS to a different viewing illuminant, applied after luminance scaling
+    /// and black point compensation. Transforms from or to gray matrix-shaper profiles
+    /// are not adapted.
+    pub viewing_adaptation: Option<ViewingAdaptation>,
+    /// Raises PCS luminance to the relative system gamma of two viewing surrounds,
+    /// applied after viewing adaptation. Matrix-shaper RGB pairs switch to LUT
+    /// pipeline since the stage isn't linear, gray matrix-shaper profiles are not compensated.
+    pub surround_compensation: Option<SurroundCompensation>,
+}
+
+/// Defines how RGB is reduced to a single gray channel.
+#[derive(Debug, Copy, Clone, Default)]
+pub enum GrayWeights {
+    /// Relative luminance `Y` from the source colorants through PCS.
+    #[default]
+    Colorimetric,
+    /// ITU-R BT.601 luma: 0.299, 0.587, 0.114.
+    Rec601,
+    /// ITU-R BT.709 luma: 0.2126, 0.7152, 0.0722.
+    Rec709,
+    /// ITU-R BT.2020 luma: 0.2627, 0.6780, 0.0593.
+    Rec2020,
+    /// Custom weights for R, G and B, expected to sum to 1.
+    Custom([f32; 3]),
+}
+
+impl GrayWeights {
+    /// Returns luma weights for R, G and B, `None` for [GrayWeights::Colorimetric].
+    pub const fn weights(self) -> Option<[f32; 3]> {
+        match self {
+            GrayWeights::Colorimetric => None,
+            GrayWeights::Rec601 => Some([0.299, 0.587, 0.114]),
+            GrayWeights::Rec709 => Some([0.2126, 0.7152, 0.0722]),
+            GrayWeights::Rec2020 => Some([0.2627, 0.6780, 0.0593]),
+            GrayWeights::Custom(weights) => Some(weights),
+        }
+    }
+
+    fn key(&self) -> (u8, [u32; 3]) {
+        match *self {
+            GrayWeights::Colorimetric => (0, [0; 3]),
+            GrayWeights::Rec601 => (1, [0; 3]),
+            GrayWeights::Rec709 => (2, [0; 3]),
+            GrayWeights::Rec2020 => (3, [0; 3]),
+            GrayWeights::Custom(w) => (4, w.map(f32::to_bits)),
+        }
+    }
+}
+
+impl PartialEq for GrayWeights {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for GrayWeights {}
+
+impl PartialOrd for GrayWeights {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GrayWeights {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl std::hash::Hash for GrayWeights {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// Pair of viewing illuminants, for proofing media under a different light,
+/// e.g. a print made for D50 viewed in a D65 booth.
+///
+/// PCS colors are adapted with Bradford from `source` white to `destination` white.
+#[derive(Debug, Copy, Clone)]
+pub struct ViewingAdaptation {
+    pub source: Chromaticity,
+    pub destination: Chromaticity,
+}
+
+impl ViewingAdaptation {
+    pub const fn new(source: Chromaticity, destination: Chromaticity) -> Self {
+        Self {
+            source,
+            destination,
+        }
+    }
+
+    /// Bradford adaptation in PCS XYZ, `None` when both illuminants are the same
+    pub fn matrix(&self) -> Option<Matrix3d> {
+        if self.source == self.destination {
+            return None;
+        }
+        Some(adaption_matrix_d(
+            self.source.to_xyz(),
+            self.destination.to_xyz(),
+        ))
+    }
+
+    fn key(&self) -> [u32; 4] {
+        [
+            self.source.x.to_bits(),
+            self.source.y.to_bits(),
+            self.destination.x.to_bits(),
+            self.destination.y.to_bits(),
+        ]
+    }
+}
+
+impl PartialEq for ViewingAdaptation {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for ViewingAdaptation {}
+
+impl PartialOrd for ViewingAdaptation {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ViewingAdaptation {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl std::hash::Hash for ViewingAdaptation {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// Luminance of the environment relative to the viewed image.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub enum Surround {
+    /// Office or living room, surround is as bright as image white
+    #[default]
+    Average,
+    /// Television viewing at home, surround is dimmer than image white
+    Dim,
+    /// Cinema, surround is black
+    Dark,
+}
+
+impl Surround {
+    /// System gamma required to preserve apparent contrast, after Bartleson and Breneman
+    pub const fn system_gamma(self) -> f64 {
+        match self {
+            Surround::Average => 1.,
+            Surround::Dim => 1.25,
+            Surround::Dark => 1.5,
+        }
+    }
+}
+
+/// Pair of viewing surrounds, e.g. content mastered in a dark cinema shown
+/// on an office display.
+///
+/// PCS luminance is raised to `destination / source` system gamma with chromaticity kept,
+/// white and black stay in place.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub struct SurroundCompensation {
+    pub source: Surround,
+    pub destination: Surround,
+}
+
+impl SurroundCompensation {
+    pub const fn new(source: Surround, destination: Surround) -> Self {
+        Self {
+            source,
+            destination,
+        }
+    }
+
+    /// Exponent applied to PCS `Y`, `None` when both surrounds are the same
+    pub fn gamma(&self) -> Option<f64> {
+        if self.source == self.destination {
+            return None;
+        }
+        Some(self.destination.system_gamma() / self.source.system_gamma())
+    }
 }
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
@@ -131,6 +485,72 @@ pub enum InterpolationMethod {
     /// Trilinear/Quadlinear interpolation
     #[default]
     Linear,
+    /// Picks interpolation from the profile pair when transform is created.
+    ///
+    /// Lab and XYZ sources and smooth dense CLUTs use cheaper methods, while sparse
+    /// or strongly curved CLUTs use tetrahedral interpolation.
+    /// Without `options` feature this is always [InterpolationMethod::Linear].
+    Auto,
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+/// Defines fixed point format used by *Matrix Shaper* transforms.
+///
+/// Formats are named after integer and fractional bits count of the adaptation matrix.
+pub enum FixedPointFormat {
+    /// 16-bit signed, 13 fractional bits, default for bit-depth less than 16.
+    #[default]
+    Q2_13,
+    /// 16-bit signed, 12 fractional bits, allows matrices with coefficients up to 8.
+    #[cfg(feature = "options")]
+    Q4_12,
+    /// 16-bit signed, 14 fractional bits, more precise but allows coefficients only up to 2.
+    #[cfg(feature = "options")]
+    Q2_14,
+    /// 32-bit signed, 30 fractional bits, available only on aarch64 with `rdm` feature.
+    Q1_30,
+}
+
+impl FixedPointFormat {
+    /// Returns count of fractional bits
+    pub const fn fractional_bits(self) -> u32 {
+        match self {
+            FixedPointFormat::Q2_13 => 13,
+            #[cfg(feature = "options")]
+            FixedPointFormat::Q4_12 => 12,
+            #[cfg(feature = "options")]
+            FixedPointFormat::Q2_14 => 14,
+            FixedPointFormat::Q1_30 => 30,
+        }
+    }
+}
+
+impl TransformOptions {
+    /// Options mimicking Adobe Color Engine defaults for the given `rendering_intent`.
+    ///
+    /// Black point compensation is on for relative colorimetric and saturation,
+    /// off for absolute colorimetric, and perceptual tables are used as they are.
+    /// CICP is ignored, so only ICC tags drive the transform, as Photoshop does.
+    pub fn adobe_compatible(rendering_intent: RenderingIntent) -> TransformOptions {
+        TransformOptions {
+            rendering_intent,
+            allow_use_cicp_transfer: false,
+            black_point_compensation: rendering_intent != RenderingIntent::AbsoluteColorimetric,
+            ..Default::default()
+        }
+    }
+
+    /// Luminance factor that should be applied to PCS, `None` if nothing to scale
+    pub(crate) fn luminance_scale(
+        &self,
+        source: &ColorProfile,
+        dest: &ColorProfile,
+    ) -> Option<f64> {
+        if !self.luminance_scaling {
+            return None;
+        }
+        source.luminance_scale(dest).filter(|&scale| scale != 1.)
+    }
 }
 
 impl Default for TransformOptions {
@@ -142,7 +562,18 @@ impl Default for TransformOptions {
             interpolation_method: InterpolationMethod::default(),
             barycentric_weight_scale: BarycentricWeightScale::default(),
             allow_extended_range_rgb_xyz: false,
-            // black_point_compensation: false,
+            fixed_point_format: None,
+            lut_block_size: LUT_BLOCK_SIZE,
+            real_time: false,
+            luminance_scaling: false,
+            gray_weights: GrayWeights::default(),
+            assume_pcs: None,
+            curve_extension: CurveExtension::default(),
+            max_threads: None,
+            lcms_lut_matrix: false,
+            black_point_compensation: false,
+            viewing_adaptation: None,
+            surround_compensation: None,
         }
     }
 }
@@ -156,6 +587,8 @@ pub type TransformF64BitExecutor = dyn TransformExecutor<f64> + Send + Sync;
 /// For RGB it shows also the channel order.
 /// To handle different data bit-depth appropriate executor must be used.
 /// Cmyk8 uses the same layout as Rgba8.
+/// Source and destination may differ in alpha: alpha is dropped when destination has none,
+/// and set to opaque when source has none.
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub enum Layout {
     Rgb = 0,
@@ -173,6 +606,10 @@ pub enum Layout {
     Inks13 = 12,
     Inks14 = 13,
     Inks15 = 14,
+    /// Destination only: PCS XYZ D50 with white Y = 1, for floating point transforms
+    Xyz = 15,
+    /// Destination only: PCS CIE L*a*b* D50 with L* in [0, 100], for floating point transforms
+    Lab = 16,
 }
 
 impl Layout {
@@ -252,11 +689,20 @@ impl Layout {
             Layout::Inks13 => 13,
             Layout::Inks14 => 14,
             Layout::Inks15 => 15,
+            Layout::Xyz => 3,
+            Layout::Lab => 3,
         }
     }
 
     pub(crate) fn from_inks(inks: usize) -> Self {
-        match inks {
+        match Layout::try_from_inks(inks) {
+            Some(layout) => layout,
+            None => unreachable!("Impossible amount of inks"),
+        }
+    }
+
+    pub(crate) const fn try_from_inks(inks: usize) -> Option<Self> {
+        Some(match inks {
             1 => Layout::Gray,
             2 => Layout::GrayAlpha,
             3 => Layout::Rgb,
@@ -272,9 +718,46 @@ impl Layout {
             13 => Layout::Inks13,
             14 => Layout::Inks14,
             15 => Layout::Inks15,
-            _ => unreachable!("Impossible amount of inks"),
+            _ => return None,
+        })
+    }
+
+    /// Returns interleaved layout for `N` channels, checked at compile time.
+    ///
+    /// Allows writing code generic over inks count instead of matching
+    /// `Inks5..Inks15` variants, e.g. `Layout::inks::<6>()` is [Layout::Inks6].
+    /// Counts `1..=4` resolve to [Layout::Gray], [Layout::GrayAlpha], [Layout::Rgb]
+    /// and [Layout::Rgba] as these share the same memory layout.
+    #[inline]
+    pub const fn inks<const N: usize>() -> Self {
+        const {
+            assert!(N >= 1 && N <= 15, "Inks count must be in 1..=15");
+        }
+        match Layout::try_from_inks(N) {
+            Some(layout) => layout,
+            None => unreachable!(),
         }
     }
+
+    /// Returns interleaved layout for the given channels count.
+    ///
+    /// Returns [CmsError::InvalidInksCountForProfile] if count is outside `1..=15`.
+    #[inline]
+    pub fn from_ink_count(inks: usize) -> Result<Self, CmsError> {
+        Layout::try_from_inks(inks).ok_or(CmsError::InvalidInksCountForProfile)
+    }
+
+    /// Checks if layout is one of `Inks5..Inks15`
+    #[inline]
+    pub const fn is_inks(self) -> bool {
+        self as u8 >= Layout::Inks5 as u8 && self as u8 <= Layout::Inks15 as u8
+    }
+
+    /// Checks if layout is [Layout::Xyz] or [Layout::Lab]
+    #[inline]
+    pub const fn is_pcs(self) -> bool {
+        matches!(self, Layout::Xyz | Layout::Lab)
+    }
 }
 
 impl From<u8> for Layout {
@@ -308,6 +791,8 @@ impl Layout {
             12 => Layout::Inks13,
             13 => Layout::Inks14,
             14 => Layout::Inks15,
+            15 => Layout::Xyz,
+            16 => Layout::Lab,
             _ => unimplemented!(),
         }
     }
@@ -381,6 +866,38 @@ impl PointeeSizeExpressible for f64 {
     const IS_U16: bool = false;
 }
 
+/// `mAB`/`mBA` tag without CLUT reduced to matrix shaper form
+struct MatrixStage {
+    /// Curves in linearizing direction
+    curves: [ToneReprCurve; 3],
+    matrix: Matrix3d,
+    bias: Vector3d,
+}
+
+impl MatrixStage {
+    fn is_collapsible(mab: &LutMultidimensionalType) -> bool {
+        mab.num_input_channels == 3
+            && mab.num_output_channels == 3
+            && mab.clut.is_none()
+            && mab.m_curves.len() == 3
+            && mab.a_curves.iter().all(|curve| curve.is_linear())
+            && mab.b_curves.iter().all(|curve| curve.is_linear())
+    }
+
+    /// Virtual matrix shaper profile carrying stage curves
+    fn into_profile(self) -> ColorProfile {
+        let [red_trc, green_trc, blue_trc] = self.curves;
+        ColorProfile {
+            color_space: DataColorSpace::Rgb,
+            pcs: DataColorSpace::Xyz,
+            red_trc: Some(red_trc),
+            green_trc: Some(green_trc),
+            blue_trc: Some(blue_trc),
+            ..Default::default()
+        }
+    }
+}
+
 impl ColorProfile {
     /// Checks if profile is valid *Matrix Shaper* profile
     pub fn is_matrix_shaper(&self) -> bool {
@@ -435,6 +952,10 @@ impl ColorProfile {
     /// Thus, this implementation considers `f32` as 14-bit values.
     /// Floating point transformer works in extended mode, that means returned data might be negative
     /// or more than 1.
+    ///
+    /// For linear light and HDR buffers enable [TransformOptions::allow_extended_range_rgb_xyz],
+    /// then *Matrix Shaper* pairs evaluate curves directly instead of sampled tables, and
+    /// values out of [0, 1] are carried through without quantization.
     pub fn create_transform_f32(
         &self,
         src_layout: Layout,
@@ -462,6 +983,44 @@ impl ColorProfile {
     }
 
     fn create_transform_nbit<
+        T: Copy
+            + Default
+            + AsPrimitive<usize>
+            + PointeeSizeExpressible
+            + Send
+            + Sync
+            + AsPrimitive<f32>
+            + RgbXyzFactory<T>
+            + RgbXyzFactoryOpt<T>
+            + GammaLutInterpolate
+            + 'static,
+        const BIT_DEPTH: usize,
+        const LINEAR_CAP: usize,
+        const GAMMA_CAP: usize,
+    >(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+    where
+        f32: AsPrimitive<T>,
+        u32: AsPrimitive<T>,
+        (): LutBarycentricReduction<T, u8>,
+        (): LutBarycentricReduction<T, u16>,
+    {
+        let _span = trace_span!("moxcms::create_transform", bit_depth = BIT_DEPTH);
+        let executor = self.create_transform_nbit_impl::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
+            src_layout, dst_pr, dst_layout, options,
+        )?;
+        #[cfg(feature = "tracing")]
+        let executor: Box<dyn TransformExecutor<T> + Send + Sync> =
+            Box::new(crate::trace::TracedExecutor { inner: executor });
+        Ok(executor)
+    }
+
+    fn create_transform_nbit_impl<
         T: Copy
             + Default
             + AsPrimitive<usize>
@@ -488,135 +1047,141 @@ impl ColorProfile {
         (): LutBarycentricReduction<T, u8>,
         (): LutBarycentricReduction<T, u16>,
     {
+        if src_layout.is_pcs() {
+            return Err(CmsError::PcsSourceLayout(src_layout));
+        }
+        if self.profile_class == ProfileClass::Named {
+            return make_named_color_transform::<T, BIT_DEPTH>(
+                src_layout, self, dst_layout, dst_pr, options,
+            );
+        }
+        if dst_layout.is_pcs() {
+            if T::FINITE {
+                return Err(CmsError::FloatingPointRequired);
+            }
+            return do_any_to_pcs::<T, BIT_DEPTH, LINEAR_CAP>(
+                src_layout, self, dst_layout, options,
+            );
+        }
+        if self.profile_class == ProfileClass::DeviceLink {
+            return make_device_link_transform::<T, BIT_DEPTH>(
+                src_layout, self, dst_layout, dst_pr, options,
+            );
+        }
+        if let Some(source) = self.resolve_lut_pcs(options) {
+            return source.create_transform_nbit_impl::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
+                src_layout, dst_pr, dst_layout, options,
+            );
+        }
+        if let Some(dest) = dst_pr.resolve_lut_pcs(options) {
+            return self.create_transform_nbit_impl::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
+                src_layout, &dest, dst_layout, options,
+            );
+        }
+        let options = TransformOptions {
+            interpolation_method: match options.interpolation_method {
+                InterpolationMethod::Auto => {
+                    self.auto_interpolation_method(dst_pr, options.rendering_intent)
+                }
+                method => method,
+            },
+            ..options
+        };
+        if self.spectral_device_to_pcs().is_some()
+            || dst_pr.spectral_pcs_to_device().is_some()
+            || self.is_multi_process_device_to_pcs(options.rendering_intent)
+            || dst_pr.is_multi_process_pcs_to_device(options.rendering_intent)
+            || (options
+                .surround_compensation
+                .and_then(|v| v.gamma())
+                .is_some()
+                && self.color_space != DataColorSpace::Gray
+                && dst_pr.color_space != DataColorSpace::Gray)
+        {
+            return make_lut_transform::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
+                src_layout, self, dst_layout, dst_pr, options,
+            );
+        }
         if self.color_space == DataColorSpace::Rgb
             && dst_pr.pcs == DataColorSpace::Xyz
             && dst_pr.color_space == DataColorSpace::Rgb
             && self.pcs == DataColorSpace::Xyz
-            && self.is_matrix_shaper()
-            && dst_pr.is_matrix_shaper()
+            && (self.is_matrix_shaper() || self.has_device_to_pcs_lut())
+            && (dst_pr.is_matrix_shaper() || dst_pr.has_pcs_to_device_lut())
         {
-            if src_layout == Layout::Gray || src_layout == Layout::GrayAlpha {
-                return Err(CmsError::InvalidLayout);
-            }
-            if dst_layout == Layout::Gray || dst_layout == Layout::GrayAlpha {
-                return Err(CmsError::InvalidLayout);
-            }
+            self.color_space.check_layout(src_layout)?;
+            dst_pr.color_space.check_layout(dst_layout)?;
 
-            if self.has_device_to_pcs_lut() || dst_pr.has_pcs_to_device_lut() {
+            let src_stage = self.device_to_pcs_matrix_stage(options.rendering_intent);
+            let dst_stage = dst_pr.pcs_to_device_matrix_stage(options.rendering_intent);
+
+            if (self.has_device_to_pcs_lut() && src_stage.is_none())
+                || (dst_pr.has_pcs_to_device_lut() && dst_stage.is_none())
+            {
                 return make_lut_transform::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
                     src_layout, self, dst_layout, dst_pr, options,
                 );
             }
 
-            let transform = self.transform_matrix(dst_pr);
-
-            if !T::FINITE && options.allow_extended_range_rgb_xyz {
-                if let Some(gamma_evaluator) = dst_pr.try_extended_gamma_evaluator() {
-                    if let Some(linear_evaluator) = self.try_extended_linearizing_evaluator() {
-                        use crate::conversions::{
-                            TransformShaperFloatInOut, make_rgb_xyz_rgb_transform_float_in_out,
-                        };
-                        let p = TransformShaperFloatInOut {
-                            linear_evaluator,
-                            gamma_evaluator,
-                            adaptation_matrix: transform.to_f32(),
-                            phantom_data: PhantomData,
-                        };
-                        return make_rgb_xyz_rgb_transform_float_in_out::<T>(
-                            src_layout, dst_layout, p, BIT_DEPTH,
-                        );
-                    }
-
-                    let lin_r = self.build_r_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
-                        options.allow_use_cicp_transfer,
-                    )?;
-                    let lin_g = self.build_g_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
-                        options.allow_use_cicp_transfer,
-                    )?;
-                    let lin_b = self.build_b_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
-                        options.allow_use_cicp_transfer,
-                    )?;
-
-                    use crate::conversions::{
-                        TransformShaperRgbFloat, make_rgb_xyz_rgb_transform_float,
-                    };
-                    let p = TransformShaperRgbFloat {
-                        r_linear: lin_r,
-                        g_linear: lin_g,
-                        b_linear: lin_b,
-                        gamma_evaluator,
-                        adaptation_matrix: transform.to_f32(),
-                        phantom_data: PhantomData,
-                    };
-                    return make_rgb_xyz_rgb_transform_float::<T, LINEAR_CAP>(
-                        src_layout, dst_layout, p, BIT_DEPTH,
-                    );
+            if src_stage.is_none() && dst_stage.is_none() {
+                let mut transform = self.transform_matrix(dst_pr);
+                let mut bias = Vector3d::default();
+                if let Some(scale) = options.luminance_scale(self, dst_pr) {
+                    transform = transform.mul_scalar(scale);
+                }
+                if let Some(bpc) = BlackPointCompensation::new(self, dst_pr, options) {
+                    transform = transform.mul_scalar(bpc.scale);
+                    bias = dst_pr.rgb_to_xyz_matrix().inverse().mul_vector(bpc.offset);
                 }
+                if let Some(adaptation) = options.viewing_adaptation.and_then(|v| v.matrix()) {
+                    let dst_matrix = dst_pr.rgb_to_xyz_matrix();
+                    let dst_inverse = dst_matrix.inverse();
+                    let conjugated = dst_inverse.mat_mul(adaptation).mat_mul(dst_matrix);
+                    transform = conjugated.mat_mul(transform);
+                    bias = conjugated.mul_vector(bias);
+                }
+                return self.create_matrix_shaper_transform::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
+                    src_layout, dst_pr, dst_layout, options, transform, bias,
+                );
             }
 
-            if self.are_all_trc_the_same() && dst_pr.are_all_trc_the_same() {
-                let linear = self.build_r_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
-                    options.allow_use_cicp_transfer,
-                )?;
-
-                let gamma = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
-                    &dst_pr.red_trc,
-                    options.allow_use_cicp_transfer,
-                )?;
-
-                let profile_transform = crate::conversions::TransformMatrixShaperOptimized {
-                    linear,
-                    gamma,
-                    adaptation_matrix: transform.to_f32(),
-                };
-
-                return T::make_optimized_transform::<LINEAR_CAP, GAMMA_CAP, BIT_DEPTH>(
+            // Collapsed `mAB`/`mBA` stages make an affine transform
+            let (mut src_matrix, mut src_bias) = src_stage
+                .as_ref()
+                .map(|stage| (stage.matrix, stage.bias))
+                .unwrap_or((self.rgb_to_xyz_matrix(), Vector3d::default()));
+            let (dst_matrix, dst_bias) = dst_stage
+                .as_ref()
+                .map(|stage| (stage.matrix, stage.bias))
+                .unwrap_or((dst_pr.rgb_to_xyz_matrix().inverse(), Vector3d::default()));
+            if let Some(scale) = options.luminance_scale(self, dst_pr) {
+                src_matrix = src_matrix.mul_scalar(scale);
+                src_bias = src_bias * scale;
+            }
+            if let Some(bpc) = BlackPointCompensation::new(self, dst_pr, options) {
+                src_matrix = src_matrix.mul_scalar(bpc.scale);
+                src_bias = src_bias * bpc.scale + bpc.offset;
+            }
+            if let Some(adaptation) = options.viewing_adaptation.and_then(|v| v.matrix()) {
+                src_matrix = adaptation.mat_mul(src_matrix);
+                src_bias = adaptation.mul_vector(src_bias);
+            }
+            let transform = dst_matrix.mat_mul(src_matrix);
+            let bias = dst_matrix.mul_vector(src_bias) + dst_bias;
+
+            let src_shaper = src_stage.map(|stage| stage.into_profile());
+            let dst_shaper = dst_stage.map(|stage| stage.into_profile());
+            src_shaper
+                .as_ref()
+                .unwrap_or(self)
+                .create_matrix_shaper_transform::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
                     src_layout,
+                    dst_shaper.as_ref().unwrap_or(dst_pr),
                     dst_layout,
-                    profile_transform,
                     options,
-                );
-            }
-
-            let lin_r = self.build_r_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
-                options.allow_use_cicp_transfer,
-            )?;
-            let lin_g = self.build_g_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
-                options.allow_use_cicp_transfer,
-            )?;
-            let lin_b = self.build_b_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
-                options.allow_use_cicp_transfer,
-            )?;
-
-            let gamma_r = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
-                &dst_pr.red_trc,
-                options.allow_use_cicp_transfer,
-            )?;
-            let gamma_g = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
-                &dst_pr.green_trc,
-                options.allow_use_cicp_transfer,
-            )?;
-            let gamma_b = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
-                &dst_pr.blue_trc,
-                options.allow_use_cicp_transfer,
-            )?;
-
-            let profile_transform = TransformMatrixShaper {
-                r_linear: lin_r,
-                g_linear: lin_g,
-                b_linear: lin_b,
-                r_gamma: gamma_r,
-                g_gamma: gamma_g,
-                b_gamma: gamma_b,
-                adaptation_matrix: transform.to_f32(),
-            };
-
-            T::make_transform::<LINEAR_CAP, GAMMA_CAP, BIT_DEPTH>(
-                src_layout,
-                dst_layout,
-                profile_transform,
-                options,
-            )
+                    transform,
+                    bias,
+                )
         } else if (self.color_space == DataColorSpace::Gray && self.gray_trc.is_some())
             && (dst_pr.color_space == DataColorSpace::Rgb
                 || (dst_pr.color_space == DataColorSpace::Gray && dst_pr.gray_trc.is_some()))
@@ -637,8 +1202,12 @@ impl ColorProfile {
 
             if dst_pr.color_space == DataColorSpace::Gray {
                 if !T::FINITE && options.allow_extended_range_rgb_xyz {
-                    if let Some(gamma_evaluator) = dst_pr.try_extended_gamma_evaluator() {
-                        if let Some(linear_evaluator) = self.try_extended_linearizing_evaluator() {
+                    if let Some(gamma_evaluator) =
+                        dst_pr.try_extended_gamma_evaluator(options.curve_extension)
+                    {
+                        if let Some(linear_evaluator) =
+                            self.try_extended_linearizing_evaluator(options.curve_extension)
+                        {
                             // Gray -> Gray case extended range
                             use crate::conversions::make_gray_to_one_trc_extended;
                             return make_gray_to_one_trc_extended::<T>(
@@ -670,9 +1239,11 @@ impl ColorProfile {
                 #[allow(clippy::collapsible_if)]
                 if dst_pr.are_all_trc_the_same() {
                     if !T::FINITE && options.allow_extended_range_rgb_xyz {
-                        if let Some(gamma_evaluator) = dst_pr.try_extended_gamma_evaluator() {
+                        if let Some(gamma_evaluator) =
+                            dst_pr.try_extended_gamma_evaluator(options.curve_extension)
+                        {
                             if let Some(linear_evaluator) =
-                                self.try_extended_linearizing_evaluator()
+                                self.try_extended_linearizing_evaluator(options.curve_extension)
                             {
                                 // Gray -> RGB where all TRC is the same with extended range
                                 use crate::conversions::make_gray_to_one_trc_extended;
@@ -704,9 +1275,11 @@ impl ColorProfile {
                 } else {
                     // Gray -> RGB where all TRC is NOT the same
                     if !T::FINITE && options.allow_extended_range_rgb_xyz {
-                        if let Some(gamma_evaluator) = dst_pr.try_extended_gamma_evaluator() {
+                        if let Some(gamma_evaluator) =
+                            dst_pr.try_extended_gamma_evaluator(options.curve_extension)
+                        {
                             if let Some(linear_evaluator) =
-                                self.try_extended_linearizing_evaluator()
+                                self.try_extended_linearizing_evaluator(options.curve_extension)
                             {
                                 // Gray -> RGB where all TRC is NOT the same with extended range
 
@@ -757,12 +1330,8 @@ impl ColorProfile {
             && dst_pr.pcs == DataColorSpace::Xyz
             && self.pcs == DataColorSpace::Xyz
         {
-            if src_layout == Layout::Gray || src_layout == Layout::GrayAlpha {
-                return Err(CmsError::InvalidLayout);
-            }
-            if dst_layout != Layout::Gray && dst_layout != Layout::GrayAlpha {
-                return Err(CmsError::InvalidLayout);
-            }
+            self.color_space.check_layout(src_layout)?;
+            dst_pr.color_space.check_layout(dst_layout)?;
 
             if self.has_device_to_pcs_lut() || dst_pr.has_pcs_to_device_lut() {
                 return make_lut_transform::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
@@ -770,15 +1339,77 @@ impl ColorProfile {
                 );
             }
 
-            let transform = self.transform_matrix(dst_pr).to_f32();
+            if let Some(weights) = options.gray_weights.weights() {
+                let use_cicp = options.allow_use_cicp_transfer;
+                let cicp_transfer = |profile: &ColorProfile| {
+                    profile
+                        .cicp
+                        .filter(|_| use_cicp)
+                        .map(|c| c.transfer_characteristics)
+                        .filter(|tc| tc.has_transfer_curve())
+                };
+                let same_transfer = match (cicp_transfer(self), cicp_transfer(dst_pr)) {
+                    (Some(src_tc), Some(dst_tc)) => src_tc == dst_tc,
+                    (None, None) => self.are_all_trc_the_same() && self.red_trc == dst_pr.gray_trc,
+                    _ => false,
+                };
+                if same_transfer {
+                    // Luma is already encoded with destination curve, e.g. sRGB -> sRGB gray
+                    return make_rgb_to_luma::<T>(src_layout, dst_layout, weights, BIT_DEPTH);
+                }
+
+                // Luma is computed on encoded values, then re-encoded from source
+                // curve into destination gray curve.
+                let identity = ToneReprCurve::Lut(vec![])
+                    .build_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>()
+                    .ok_or(CmsError::BuildTransferFunction)?;
+                let src_linear = self.build_g_linearize_table::<u16, 65536, 16>(use_cicp)?;
+                let gray_gamma = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
+                    &dst_pr.gray_trc,
+                    use_cicp,
+                )?;
+                let mut luma_gamma = Box::new([T::default(); 65536]);
+                let scale = (GAMMA_CAP - 1) as f32;
+                for (i, dst) in luma_gamma.iter_mut().take(GAMMA_CAP).enumerate() {
+                    let code = mlaf(0.5, i as f32 / scale, 65535.) as usize;
+                    let linear = src_linear[code.min(65535)].min(1.).max(0.);
+                    *dst = gray_gamma[mlaf(0.5, linear, scale) as usize];
+                }
+
+                let trc_box = ToneReproductionRgbToGray::<T, LINEAR_CAP> {
+                    r_linear: identity.clone(),
+                    g_linear: identity.clone(),
+                    b_linear: identity,
+                    gray_gamma: luma_gamma,
+                };
+
+                return make_rgb_to_gray::<T, LINEAR_CAP>(
+                    src_layout,
+                    dst_layout,
+                    trc_box,
+                    Vector3f { v: weights },
+                    GAMMA_CAP,
+                    BIT_DEPTH,
+                );
+            }
+
+            let mut transform = self.transform_matrix(dst_pr);
+            if let Some(scale) = options.luminance_scale(self, dst_pr) {
+                transform = transform.mul_scalar(scale);
+            }
+            let transform = transform.to_f32();
 
             let vector = Vector3f {
                 v: [transform.v[1][0], transform.v[1][1], transform.v[1][2]],
             };
 
             if !T::FINITE && options.allow_extended_range_rgb_xyz {
-                if let Some(gamma_evaluator) = dst_pr.try_extended_gamma_evaluator() {
-                    if let Some(linear_evaluator) = self.try_extended_linearizing_evaluator() {
+                if let Some(gamma_evaluator) =
+                    dst_pr.try_extended_gamma_evaluator(options.curve_extension)
+                {
+                    if let Some(linear_evaluator) =
+                        self.try_extended_linearizing_evaluator(options.curve_extension)
+                    {
                         use crate::conversions::make_rgb_to_gray_extended;
                         return make_rgb_to_gray_extended::<T>(
                             src_layout,
@@ -825,12 +1456,8 @@ impl ColorProfile {
             && (dst_pr.pcs == DataColorSpace::Xyz || dst_pr.pcs == DataColorSpace::Lab)
             && (self.pcs == DataColorSpace::Xyz || self.pcs == DataColorSpace::Lab)
         {
-            if src_layout == Layout::Gray || src_layout == Layout::GrayAlpha {
-                return Err(CmsError::InvalidLayout);
-            }
-            if dst_layout == Layout::Gray || dst_layout == Layout::GrayAlpha {
-                return Err(CmsError::InvalidLayout);
-            }
+            self.color_space.check_layout(src_layout)?;
+            dst_pr.color_space.check_layout(dst_layout)?;
             make_lut_transform::<T, BIT_DEPTH, LINEAR_CAP, GAMMA_CAP>(
                 src_layout, self, dst_layout, dst_pr, options,
             )
@@ -841,6 +1468,173 @@ impl ColorProfile {
         }
     }
 
+    /// Matrix shaper RGB -> RGB transform with affine `transform` and `bias` in linear light
+    #[allow(clippy::too_many_arguments)]
+    fn create_matrix_shaper_transform<
+        T: Copy
+            + Default
+            + AsPrimitive<usize>
+            + PointeeSizeExpressible
+            + Send
+            + Sync
+            + AsPrimitive<f32>
+            + RgbXyzFactory<T>
+            + RgbXyzFactoryOpt<T>
+            + GammaLutInterpolate,
+        const BIT_DEPTH: usize,
+        const LINEAR_CAP: usize,
+        const GAMMA_CAP: usize,
+    >(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+        transform: Matrix3d,
+        bias: Vector3d,
+    ) -> Result<Box<dyn TransformExecutor<T> + Send + Sync>, CmsError>
+    where
+        f32: AsPrimitive<T>,
+        u32: AsPrimitive<T>,
+    {
+        let has_bias = bias != Vector3d::default();
+        let bias: Vector3f = bias.cast();
+
+        if !T::FINITE && options.allow_extended_range_rgb_xyz {
+            if let Some(gamma_evaluator) =
+                dst_pr.try_extended_gamma_evaluator(options.curve_extension)
+            {
+                if let Some(linear_evaluator) =
+                    self.try_extended_linearizing_evaluator(options.curve_extension)
+                {
+                    use crate::conversions::{
+                        TransformShaperFloatInOut, make_rgb_xyz_rgb_transform_float_in_out,
+                    };
+                    let p = TransformShaperFloatInOut {
+                        linear_evaluator,
+                        gamma_evaluator,
+                        adaptation_matrix: transform.to_f32(),
+                        bias,
+                        phantom_data: PhantomData,
+                    };
+                    return make_rgb_xyz_rgb_transform_float_in_out::<T>(
+                        src_layout, dst_layout, p, BIT_DEPTH,
+                    );
+                }
+
+                let lin_r = self.build_r_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
+                    options.allow_use_cicp_transfer,
+                )?;
+                let lin_g = self.build_g_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
+                    options.allow_use_cicp_transfer,
+                )?;
+                let lin_b = self.build_b_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
+                    options.allow_use_cicp_transfer,
+                )?;
+
+                use crate::conversions::{
+                    TransformShaperRgbFloat, make_rgb_xyz_rgb_transform_float,
+                };
+                let p = TransformShaperRgbFloat {
+                    r_linear: lin_r,
+                    g_linear: lin_g,
+                    b_linear: lin_b,
+                    gamma_evaluator,
+                    adaptation_matrix: transform.to_f32(),
+                    bias,
+                    phantom_data: PhantomData,
+                };
+                return make_rgb_xyz_rgb_transform_float::<T, LINEAR_CAP>(
+                    src_layout, dst_layout, p, BIT_DEPTH,
+                );
+            }
+        }
+
+        if !has_bias && self.are_all_trc_the_same() && dst_pr.are_all_trc_the_same() {
+            let _span = trace_span!("moxcms::optimize_matrix_shaper");
+            let linear = self.shared_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
+                &self.red_trc,
+                options.allow_use_cicp_transfer,
+            )?;
+
+            let gamma = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
+                &dst_pr.red_trc,
+                options.allow_use_cicp_transfer,
+            )?;
+
+            let profile_transform = crate::conversions::TransformMatrixShaperOptimized {
+                linear,
+                gamma,
+                adaptation_matrix: transform.to_f32(),
+            };
+
+            return T::make_optimized_transform::<LINEAR_CAP, GAMMA_CAP, BIT_DEPTH>(
+                src_layout,
+                dst_layout,
+                profile_transform,
+                options,
+            );
+        }
+
+        let lin_r = self.shared_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
+            &self.red_trc,
+            options.allow_use_cicp_transfer,
+        )?;
+        let lin_g = self.shared_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
+            &self.green_trc,
+            options.allow_use_cicp_transfer,
+        )?;
+        let lin_b = self.shared_linearize_table::<T, LINEAR_CAP, BIT_DEPTH>(
+            &self.blue_trc,
+            options.allow_use_cicp_transfer,
+        )?;
+
+        let gamma_r = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
+            &dst_pr.red_trc,
+            options.allow_use_cicp_transfer,
+        )?;
+        let gamma_g = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
+            &dst_pr.green_trc,
+            options.allow_use_cicp_transfer,
+        )?;
+        let gamma_b = dst_pr.build_gamma_table::<T, 65536, GAMMA_CAP, BIT_DEPTH>(
+            &dst_pr.blue_trc,
+            options.allow_use_cicp_transfer,
+        )?;
+
+        let profile_transform = TransformMatrixShaper {
+            r_linear: lin_r,
+            g_linear: lin_g,
+            b_linear: lin_b,
+            r_gamma: gamma_r,
+            g_gamma: gamma_g,
+            b_gamma: gamma_b,
+            adaptation_matrix: transform.to_f32(),
+            bias,
+        };
+
+        if has_bias {
+            // Only scalar executor handles affine stage
+            if let Some(format) = options.fixed_point_format {
+                return Err(CmsError::UnsupportedFixedPointFormat(format));
+            }
+            return make_rgb_xyz_rgb_transform_scalar::<T, LINEAR_CAP>(
+                src_layout,
+                dst_layout,
+                profile_transform,
+                GAMMA_CAP,
+                BIT_DEPTH,
+            );
+        }
+
+        T::make_transform::<LINEAR_CAP, GAMMA_CAP, BIT_DEPTH>(
+            src_layout,
+            dst_layout,
+            profile_transform,
+            options,
+        )
+    }
+
     /// Creates transform between source and destination profile
     /// Only 8 bit is supported.
     pub fn create_transform_8bit(
@@ -850,9 +1644,80 @@ impl ColorProfile {
         dst_layout: Layout,
         options: TransformOptions,
     ) -> Result<Box<Transform8BitExecutor>, CmsError> {
+        #[cfg(feature = "integer_pixels")]
+        if !self.has_fixed_point_executor(dst_pr) {
+            return self.create_tabulated_transform_8bit(src_layout, dst_pr, dst_layout, options);
+        }
+        #[cfg(feature = "integer_pixels")]
+        let options = TransformOptions {
+            fixed_point_format: Some(options.fixed_point_format.unwrap_or_default()),
+            ..options
+        };
         self.create_transform_nbit::<u8, 8, 256, 4096>(src_layout, dst_pr, dst_layout, options)
     }
 
+    /// Checks that profiles pair has fixed point or table lookup executor,
+    /// otherwise transform would need floating point arithmetic.
+    #[cfg(feature = "integer_pixels")]
+    fn has_fixed_point_executor(&self, dst_pr: &ColorProfile) -> bool {
+        let is_matrix_shaper_pair = self.color_space == DataColorSpace::Rgb
+            && dst_pr.color_space == DataColorSpace::Rgb
+            && self.pcs == DataColorSpace::Xyz
+            && dst_pr.pcs == DataColorSpace::Xyz
+            && self.is_matrix_shaper()
+            && dst_pr.is_matrix_shaper()
+            && !self.has_device_to_pcs_lut()
+            && !dst_pr.has_pcs_to_device_lut()
+            && self.spectral_device_to_pcs().is_none()
+            && dst_pr.spectral_pcs_to_device().is_none();
+        is_matrix_shaper_pair || self.profile_class == ProfileClass::Named
+    }
+
+    /// Gray, three and four channel sources are tabulated from floating point transform
+    /// into integer executors, other pairs have none.
+    #[cfg(feature = "integer_pixels")]
+    fn create_tabulated_transform_8bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<Box<Transform8BitExecutor>, CmsError> {
+        use crate::conversions::make_fixed_tabulated;
+        if src_layout.is_pcs() {
+            return Err(CmsError::PcsSourceLayout(src_layout));
+        }
+        if dst_layout.is_pcs() {
+            return Err(CmsError::FloatingPointRequired);
+        }
+        make_fixed_tabulated::<u8, 8>(src_layout, self, dst_layout, dst_pr, |layout| {
+            self.create_transform_f32(layout, dst_pr, dst_layout, options)
+        })?
+        .ok_or(CmsError::FloatingPointRequired)
+    }
+
+    /// Returns copy of LUT based profile with corrected PCS, when header PCS doesn't
+    /// match [TransformOptions::assume_pcs] or what its `A2Bx` LUT actually produces
+    pub(crate) fn resolve_lut_pcs(&self, options: TransformOptions) -> Option<ColorProfile> {
+        if !self.is_lut_based()
+            || (self.pcs != DataColorSpace::Xyz && self.pcs != DataColorSpace::Lab)
+        {
+            return None;
+        }
+        let pcs = match options.assume_pcs {
+            Some(pcs @ (DataColorSpace::Xyz | DataColorSpace::Lab)) => pcs,
+            Some(_) => return None,
+            None => crate::reference::detect_lut_pcs(self, options.rendering_intent)?,
+        };
+        if pcs == self.pcs {
+            return None;
+        }
+        Some(ColorProfile {
+            pcs,
+            ..self.clone()
+        })
+    }
+
     pub(crate) fn get_device_to_pcs(&self, intent: RenderingIntent) -> Option<&LutWarehouse> {
         match intent {
             RenderingIntent::AbsoluteColorimetric => self.lut_a_to_b_colorimetric.as_ref(),
@@ -862,6 +1727,78 @@ impl ColorProfile {
         }
     }
 
+    /// Returns raw spectral `D2Bx` tag for the rendering intent
+    pub fn get_device_to_spectral_pcs(&self, intent: RenderingIntent) -> Option<&[u8]> {
+        match intent {
+            RenderingIntent::AbsoluteColorimetric => self.lut_d_to_b_colorimetric.as_deref(),
+            RenderingIntent::Saturation => self.lut_d_to_b_saturation.as_deref(),
+            RenderingIntent::RelativeColorimetric => self.lut_d_to_b_colorimetric.as_deref(),
+            RenderingIntent::Perceptual => self.lut_d_to_b_perceptual.as_deref(),
+        }
+    }
+
+    /// Returns raw spectral `B2Dx` tag for the rendering intent
+    pub fn get_spectral_pcs_to_device(&self, intent: RenderingIntent) -> Option<&[u8]> {
+        match intent {
+            RenderingIntent::AbsoluteColorimetric => self.lut_b_to_d_colorimetric.as_deref(),
+            RenderingIntent::Saturation => self.lut_b_to_d_saturation.as_deref(),
+            RenderingIntent::RelativeColorimetric => self.lut_b_to_d_colorimetric.as_deref(),
+            RenderingIntent::Perceptual => self.lut_b_to_d_perceptual.as_deref(),
+        }
+    }
+
+    /// Resolves [InterpolationMethod::Auto] for the profile pair.
+    #[cfg(feature = "options")]
+    pub(crate) fn auto_interpolation_method(
+        &self,
+        dst_pr: &ColorProfile,
+        intent: RenderingIntent,
+    ) -> InterpolationMethod {
+        // Lab and XYZ inputs change slowly across the grid.
+        if self.color_space == DataColorSpace::Lab || self.color_space == DataColorSpace::Xyz {
+            return InterpolationMethod::Linear;
+        }
+        let src_lut = if self.is_matrix_shaper() {
+            None
+        } else {
+            self.get_device_to_pcs(intent)
+        };
+        let dst_lut = if dst_pr.has_pcs_to_device_lut() {
+            dst_pr.get_pcs_to_device(intent)
+        } else {
+            None
+        };
+        let smoothness = [src_lut, dst_lut]
+            .into_iter()
+            .flatten()
+            .filter_map(|lut| lut.clut_smoothness())
+            .reduce(|a, b| ClutSmoothness {
+                grid_size: a.grid_size.min(b.grid_size),
+                curvature: a.curvature.max(b.curvature),
+            });
+        match smoothness {
+            // Single cell grids are linear by construction
+            Some(smoothness) if smoothness.grid_size < 3 => InterpolationMethod::Linear,
+            Some(smoothness)
+                if smoothness.grid_size >= AUTO_DENSE_GRID_SIZE
+                    && smoothness.curvature <= AUTO_SMOOTH_CURVATURE =>
+            {
+                InterpolationMethod::Pyramid
+            }
+            _ => InterpolationMethod::Tetrahedral,
+        }
+    }
+
+    /// Resolves [InterpolationMethod::Auto] for the profile pair.
+    #[cfg(not(feature = "options"))]
+    pub(crate) fn auto_interpolation_method(
+        &self,
+        _: &ColorProfile,
+        _: RenderingIntent,
+    ) -> InterpolationMethod {
+        InterpolationMethod::Linear
+    }
+
     pub(crate) fn get_pcs_to_device(&self, intent: RenderingIntent) -> Option<&LutWarehouse> {
         match intent {
             RenderingIntent::AbsoluteColorimetric => self.lut_b_to_a_colorimetric.as_ref(),
@@ -870,13 +1807,80 @@ impl ColorProfile {
             RenderingIntent::Perceptual => self.lut_b_to_a_perceptual.as_ref(),
         }
     }
+
+    /// Returns `mAB` device to PCS tag without CLUT as curves and affine matrix stage
+    /// that maps linearized device values to PCS XYZ.
+    fn device_to_pcs_matrix_stage(&self, intent: RenderingIntent) -> Option<MatrixStage> {
+        if self.color_space != DataColorSpace::Rgb || self.pcs != DataColorSpace::Xyz {
+            return None;
+        }
+        let mab = match self.get_device_to_pcs(intent)? {
+            LutWarehouse::Multidimensional(mab) => mab,
+            LutWarehouse::Lut(_) | LutWarehouse::MultiProcess(_) => return None,
+        };
+        if !MatrixStage::is_collapsible(mab) {
+            return None;
+        }
+        // PCS XYZ is encoded as u1Fixed15
+        let scale = 65535. / 32768.;
+        let curves: [ToneReprCurve; 3] = mab.m_curves.clone().try_into().ok()?;
+        Some(MatrixStage {
+            curves,
+            matrix: mab.matrix.mul_scalar(scale),
+            bias: mab.bias * scale,
+        })
+    }
+
+    /// Returns `mBA` PCS to device tag without CLUT as curves and affine matrix stage
+    /// that maps PCS XYZ to linearized device values.
+    fn pcs_to_device_matrix_stage(&self, intent: RenderingIntent) -> Option<MatrixStage> {
+        if self.color_space != DataColorSpace::Rgb || self.pcs != DataColorSpace::Xyz {
+            return None;
+        }
+        let mba = match self.get_pcs_to_device(intent)? {
+            LutWarehouse::Multidimensional(mba) => mba,
+            LutWarehouse::Lut(_) | LutWarehouse::MultiProcess(_) => return None,
+        };
+        if !MatrixStage::is_collapsible(mba) {
+            return None;
+        }
+        let curves = [
+            mba.m_curves[0].inverse().ok()?,
+            mba.m_curves[1].inverse().ok()?,
+            mba.m_curves[2].inverse().ok()?,
+        ];
+        let scale = 32768. / 65535.;
+        Some(MatrixStage {
+            curves,
+            matrix: mba.matrix.mul_scalar(scale),
+            bias: mba.bias,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{ColorProfile, DataColorSpace, Layout, RenderingIntent, TransformOptions};
+    use crate::{
+        Chromaticity, CmsError, ColorProfile, DataColorSpace, FixedPointFormat, GrayWeights,
+        InterpolationMethod, Layout, RenderingIntent, Surround, SurroundCompensation,
+        TransformOptions, ViewingAdaptation, Xyzd,
+    };
     use rand::Rng;
 
+    #[test]
+    fn test_layout_inks() {
+        assert_eq!(Layout::inks::<3>(), Layout::Rgb);
+        assert_eq!(Layout::inks::<6>(), Layout::Inks6);
+        assert_eq!(Layout::inks::<10>(), Layout::Inks10);
+        for inks in 1..=15 {
+            let layout = Layout::from_ink_count(inks).unwrap();
+            assert_eq!(layout.channels(), inks);
+            assert_eq!(layout.is_inks(), inks >= 5);
+        }
+        assert!(Layout::from_ink_count(0).is_err());
+        assert!(Layout::from_ink_count(16).is_err());
+    }
+
     #[test]
     fn test_transform_rgb8() {
         let mut srgb_profile = ColorProfile::new_srgb();
@@ -931,6 +1935,268 @@ mod tests {
         transform.transform(&src, &mut dst).unwrap();
     }
 
+    #[derive(Debug)]
+    struct MatrixSpectralHandler;
+
+    struct MatrixStage(crate::Matrix3f);
+
+    impl crate::Stage for MatrixStage {
+        fn transform(&self, src: &[f32], dst: &mut [f32]) -> Result<(), crate::CmsError> {
+            for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
+                let v = self.0.mul_vector(crate::Vector3f {
+                    v: [src[0], src[1], src[2]],
+                });
+                dst.copy_from_slice(&v.v);
+            }
+            Ok(())
+        }
+    }
+
+    impl crate::SpectralPcsHandler for MatrixSpectralHandler {
+        fn device_to_pcs(
+            &self,
+            _: &ColorProfile,
+            _: Layout,
+            _: RenderingIntent,
+        ) -> Result<Box<dyn crate::Stage + Send + Sync>, crate::CmsError> {
+            Ok(Box::new(MatrixStage(
+                ColorProfile::new_srgb().rgb_to_xyz_matrix().to_f32(),
+            )))
+        }
+
+        fn pcs_to_device(
+            &self,
+            _: &ColorProfile,
+            _: Layout,
+            _: RenderingIntent,
+        ) -> Result<Box<dyn crate::Stage + Send + Sync>, crate::CmsError> {
+            Ok(Box::new(MatrixStage(
+                ColorProfile::new_srgb()
+                    .rgb_to_xyz_matrix()
+                    .inverse()
+                    .to_f32(),
+            )))
+        }
+    }
+
+    #[test]
+    fn test_spectral_pcs_handler() {
+        let spectral_pcs = crate::SpectralPcs {
+            kind: crate::SpectralPcsKind::Reflectance,
+            components: 36,
+        };
+        let mut spectral_profile = ColorProfile {
+            color_space: DataColorSpace::Rgb,
+            spectral_pcs: Some(spectral_pcs),
+            ..Default::default()
+        };
+        let srgb_profile = ColorProfile::new_srgb();
+        match spectral_profile.create_transform_f32(
+            Layout::Rgb,
+            &srgb_profile,
+            Layout::Rgb,
+            TransformOptions::default(),
+        ) {
+            Err(crate::CmsError::UnsupportedSpectralPcs(pcs)) => assert_eq!(pcs, spectral_pcs),
+            _ => panic!("Spectral PCS must not be supported without a handler"),
+        }
+        spectral_profile.spectral_pcs_handler = Some(std::sync::Arc::new(MatrixSpectralHandler));
+        let transform = spectral_profile
+            .create_transform_f32(
+                Layout::Rgb,
+                &srgb_profile,
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let src = [1.0f32, 1.0, 1.0];
+        let mut dst = [0f32; 3];
+        transform.transform(&src, &mut dst).unwrap();
+        for v in dst {
+            assert!((v - 1.0).abs() < 1e-2, "Expected white, got {dst:?}");
+        }
+        let inverse = srgb_profile
+            .create_transform_f32(
+                Layout::Rgb,
+                &spectral_profile,
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        inverse.transform(&src, &mut dst).unwrap();
+        for v in dst {
+            assert!((v - 1.0).abs() < 1e-2, "Expected white, got {dst:?}");
+        }
+
+        let src = (0..301 * 3)
+            .map(|x| (x % 17) as f32 / 16.)
+            .collect::<Vec<f32>>();
+        let mut reference = vec![0f32; src.len()];
+        let mut dst = vec![0f32; src.len()];
+        let mut scratch = crate::TransformScratch::new();
+        transform.transform(&src, &mut reference).unwrap();
+        transform
+            .transform_with_scratch(&src, &mut dst, &mut scratch)
+            .unwrap();
+        assert_eq!(reference, dst);
+        let capacity = scratch.buffer.capacity();
+        assert!(capacity >= src.len());
+        transform
+            .transform_with_scratch(&src, &mut dst, &mut scratch)
+            .unwrap();
+        assert_eq!(reference, dst);
+        assert_eq!(capacity, scratch.buffer.capacity());
+
+        let real_time = spectral_profile
+            .create_transform_f32(
+                Layout::Rgb,
+                &srgb_profile,
+                Layout::Rgb,
+                TransformOptions {
+                    real_time: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let src = src.repeat(5);
+        let mut dst = vec![0f32; src.len()];
+        let mut scratch = crate::TransformScratch::real_time();
+        let capacity = scratch.buffer.capacity();
+        real_time
+            .transform_with_scratch(&src, &mut dst, &mut scratch)
+            .unwrap();
+        assert_eq!(capacity, scratch.buffer.capacity());
+        assert_eq!(&dst[..reference.len()], &reference[..]);
+        let mut plain = vec![0f32; src.len()];
+        real_time.transform(&src, &mut plain).unwrap();
+        assert_eq!(plain, dst);
+    }
+
+    #[test]
+    fn test_multi_process_profile() {
+        use crate::{
+            CurveSegment, LutWarehouse, Matrix3d, MultiProcessType, ProcessElement, SegmentFormula,
+            SegmentedCurve,
+        };
+        let linear = |a: f32| {
+            CurveSegment::Formula(SegmentFormula::Gamma {
+                gamma: 1.,
+                a,
+                b: 0.,
+                c: 0.,
+            })
+        };
+        let linearize = SegmentedCurve::new(
+            vec![0.04045],
+            vec![
+                linear(1. / 12.92),
+                CurveSegment::Formula(SegmentFormula::Gamma {
+                    gamma: 2.4,
+                    a: 1. / 1.055,
+                    b: 0.055 / 1.055,
+                    c: 0.,
+                }),
+            ],
+        )
+        .unwrap();
+        let samples = (1..=4096)
+            .map(|i| {
+                let x = i as f32 / 4096.;
+                if x <= 0.0031308 {
+                    x * 12.92
+                } else {
+                    1.055 * x.powf(1. / 2.4) - 0.055
+                }
+            })
+            .collect::<Vec<f32>>();
+        let gamma = SegmentedCurve::new(
+            vec![0., 1.],
+            vec![linear(12.92), CurveSegment::Sampled(samples), linear(1.)],
+        )
+        .unwrap();
+        let srgb = ColorProfile::new_srgb();
+        let to_vec = |m: Matrix3d| {
+            m.v.iter()
+                .flat_map(|row| row.iter().map(|&v| v as f32))
+                .collect::<Vec<f32>>()
+        };
+        let a_to_b = LutWarehouse::MultiProcess(MultiProcessType {
+            num_input_channels: 3,
+            num_output_channels: 3,
+            elements: vec![
+                ProcessElement::CurveSet(vec![linearize.clone(); 3]),
+                ProcessElement::Matrix {
+                    inputs: 3,
+                    outputs: 3,
+                    matrix: to_vec(srgb.rgb_to_xyz_matrix()),
+                    offsets: vec![0.; 3],
+                },
+            ],
+        });
+        let b_to_a = LutWarehouse::MultiProcess(MultiProcessType {
+            num_input_channels: 3,
+            num_output_channels: 3,
+            elements: vec![
+                ProcessElement::Matrix {
+                    inputs: 3,
+                    outputs: 3,
+                    matrix: to_vec(srgb.rgb_to_xyz_matrix().inverse()),
+                    offsets: vec![0.; 3],
+                },
+                ProcessElement::CurveSet(vec![gamma; 3]),
+            ],
+        });
+        let profile = ColorProfile {
+            red_trc: None,
+            green_trc: None,
+            blue_trc: None,
+            lut_a_to_b_perceptual: Some(a_to_b.clone()),
+            lut_a_to_b_colorimetric: Some(a_to_b.clone()),
+            lut_b_to_a_perceptual: Some(b_to_a.clone()),
+            lut_b_to_a_colorimetric: Some(b_to_a.clone()),
+            ..srgb.clone()
+        };
+        let decoded = ColorProfile::new_from_slice(&profile.encode().unwrap()).unwrap();
+        assert!(decoded.lut_d_to_b_perceptual.is_none());
+        assert!(decoded.lut_b_to_d_colorimetric.is_none());
+        assert_eq!(decoded.lut_a_to_b_perceptual, Some(a_to_b.clone()));
+        assert_eq!(decoded.lut_b_to_a_colorimetric, Some(b_to_a.clone()));
+
+        // `D2B0` takes precedence over `A2B0`, which is still written back
+        let fallback = ColorProfile::new_abstract(2, |lab| lab)
+            .unwrap()
+            .lut_a_to_b_perceptual;
+        let mut with_fallback = profile.clone();
+        with_fallback.lut_fallbacks[0] = fallback.clone();
+        let mut reread = ColorProfile::new_from_slice(&with_fallback.encode().unwrap()).unwrap();
+        assert_eq!(reread.lut_a_to_b_perceptual, Some(a_to_b));
+        assert_eq!(reread.lut_fallbacks[0], fallback);
+        // Edits of parsed elements are not shadowed by the tag they were read from
+        reread.lut_a_to_b_perceptual = Some(b_to_a.clone());
+        let edited = ColorProfile::new_from_slice(&reread.encode().unwrap()).unwrap();
+        assert_eq!(edited.lut_a_to_b_perceptual, Some(b_to_a));
+        assert_eq!(edited.lut_fallbacks[0], fallback);
+
+        let src = (0..=255u8)
+            .step_by(15)
+            .flat_map(|r| {
+                (0..=255u8)
+                    .step_by(15)
+                    .flat_map(move |g| (0..=255u8).step_by(15).flat_map(move |b| [r, g, b]))
+            })
+            .collect::<Vec<u8>>();
+        let mut dst = vec![0u8; src.len()];
+        for (source, dest) in [(&decoded, &srgb), (&srgb, &decoded), (&decoded, &decoded)] {
+            let transform = source
+                .create_transform_8bit(Layout::Rgb, dest, Layout::Rgb, TransformOptions::default())
+                .unwrap();
+            transform.transform(&src, &mut dst).unwrap();
+            for (&a, &b) in src.iter().zip(dst.iter()) {
+                assert!(a.abs_diff(b) <= 1, "Expected {a}, got {b}");
+            }
+        }
+    }
+
     #[test]
     fn test_transform_rgba8() {
         let srgb_profile = ColorProfile::new_srgb();
@@ -1331,4 +2597,747 @@ mod tests {
         cvt_inverse.transform(&dst, &mut inverse).unwrap();
         assert!((inverse[0] - 0.273002833) < 1e-4);
     }
+
+    #[test]
+    fn test_forced_fixed_point_format() {
+        let srgb_profile = ColorProfile::new_srgb();
+        let bt2020_profile = ColorProfile::new_bt2020();
+        let src = (0..256 * 256 * 3)
+            .map(|x| (x % 251) as u8)
+            .collect::<Vec<u8>>();
+        let mut reference = vec![0u8; 256 * 256 * 3];
+        let mut dst = vec![0u8; 256 * 256 * 3];
+        let transform = srgb_profile
+            .create_transform_8bit(
+                Layout::Rgb,
+                &bt2020_profile,
+                Layout::Rgb,
+                TransformOptions {
+                    prefer_fixed_point: false,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        // `integer_pixels` keeps 8 bit pixels in fixed point regardless of preference
+        let expected = if cfg!(feature = "integer_pixels") {
+            Some(FixedPointFormat::Q2_13)
+        } else {
+            None
+        };
+        assert_eq!(transform.fixed_point_format(), expected);
+        transform.transform(&src, &mut reference).unwrap();
+
+        let formats = [
+            FixedPointFormat::Q2_13,
+            #[cfg(feature = "options")]
+            FixedPointFormat::Q4_12,
+            #[cfg(feature = "options")]
+            FixedPointFormat::Q2_14,
+        ];
+        for format in formats {
+            let transform = srgb_profile
+                .create_transform_8bit(
+                    Layout::Rgb,
+                    &bt2020_profile,
+                    Layout::Rgb,
+                    TransformOptions {
+                        fixed_point_format: Some(format),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            assert_eq!(transform.fixed_point_format(), Some(format));
+            transform.transform(&src, &mut dst).unwrap();
+            for (a, b) in reference.iter().zip(dst.iter()) {
+                assert!(a.abs_diff(*b) <= 2, "{format:?}: {a} vs {b}");
+            }
+        }
+
+        let transform = srgb_profile.create_transform_f64(
+            Layout::Rgb,
+            &bt2020_profile,
+            Layout::Rgb,
+            TransformOptions {
+                fixed_point_format: Some(FixedPointFormat::Q2_13),
+                ..Default::default()
+            },
+        );
+        assert!(matches!(
+            transform,
+            Err(CmsError::UnsupportedFixedPointFormat(
+                FixedPointFormat::Q2_13
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_lut_block_size() {
+        if let Ok(srgb_perceptual_icc) = std::fs::read("./assets/srgb_perceptual.icc") {
+            let src_profile = ColorProfile::new_from_slice(&srgb_perceptual_icc).unwrap();
+            let dst_profile = ColorProfile::new_srgb();
+            let src = (0..1001 * 3).map(|x| (x % 253) as u8).collect::<Vec<u8>>();
+            let mut reference = vec![0u8; src.len()];
+            let mut dst = vec![0u8; src.len()];
+            let make = |lut_block_size: usize, prefer_fixed_point: bool| {
+                src_profile
+                    .create_transform_8bit(
+                        Layout::Rgb,
+                        &dst_profile,
+                        Layout::Rgb,
+                        TransformOptions {
+                            prefer_fixed_point,
+                            lut_block_size,
+                            ..Default::default()
+                        },
+                    )
+                    .unwrap()
+            };
+            for prefer_fixed_point in [false, true] {
+                make(0, prefer_fixed_point)
+                    .transform(&src, &mut reference)
+                    .unwrap();
+                for block_size in [1, 7, 16] {
+                    make(block_size, prefer_fixed_point)
+                        .transform(&src, &mut dst)
+                        .unwrap();
+                    assert_eq!(
+                        reference, dst,
+                        "Block size {block_size}, fixed point {prefer_fixed_point}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_auto_interpolation_method() {
+        let srgb = ColorProfile::new_srgb();
+        #[cfg(feature = "options")]
+        let expected = InterpolationMethod::Tetrahedral;
+        #[cfg(not(feature = "options"))]
+        let expected = InterpolationMethod::Linear;
+        assert_eq!(
+            srgb.auto_interpolation_method(&ColorProfile::new_lab(), RenderingIntent::Perceptual),
+            expected
+        );
+        if let Ok(srgb_perceptual_icc) = std::fs::read("./assets/srgb_perceptual.icc") {
+            let src_profile = ColorProfile::new_from_slice(&srgb_perceptual_icc).unwrap();
+            let resolved =
+                src_profile.auto_interpolation_method(&srgb, RenderingIntent::Perceptual);
+            assert_ne!(resolved, InterpolationMethod::Auto);
+            let src = (0..1001 * 3).map(|x| (x % 253) as u8).collect::<Vec<u8>>();
+            let mut reference = vec![0u8; src.len()];
+            let mut dst = vec![0u8; src.len()];
+            let make = |interpolation_method: InterpolationMethod| {
+                src_profile
+                    .create_transform_8bit(
+                        Layout::Rgb,
+                        &srgb,
+                        Layout::Rgb,
+                        TransformOptions {
+                            interpolation_method,
+                            ..Default::default()
+                        },
+                    )
+                    .unwrap()
+            };
+            make(resolved).transform(&src, &mut reference).unwrap();
+            make(InterpolationMethod::Auto)
+                .transform(&src, &mut dst)
+                .unwrap();
+            assert_eq!(reference, dst);
+        }
+    }
+
+    #[test]
+    fn test_viewing_adaptation() {
+        let srgb = ColorProfile::new_srgb();
+        let cmyk =
+            ColorProfile::new_from_slice(include_bytes!("../assets/us_swop_coated.icc")).unwrap();
+        let options = TransformOptions {
+            viewing_adaptation: Some(ViewingAdaptation::new(Chromaticity::D50, Chromaticity::D65)),
+            ..Default::default()
+        };
+
+        let mut dst = [0u8; 3];
+        srgb.create_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, options)
+            .unwrap()
+            .transform(&[200, 200, 200], &mut dst)
+            .unwrap();
+        assert!(dst[2] > dst[0] + 5, "{dst:?}");
+
+        let mut plain = [0u8; 3];
+        let mut adapted = [0u8; 3];
+        let src = [0u8, 0, 0, 0];
+        cmyk.create_transform_8bit(Layout::Rgba, &srgb, Layout::Rgb, Default::default())
+            .unwrap()
+            .transform(&src, &mut plain)
+            .unwrap();
+        cmyk.create_transform_8bit(Layout::Rgba, &srgb, Layout::Rgb, options)
+            .unwrap()
+            .transform(&src, &mut adapted)
+            .unwrap();
+        assert!(
+            adapted[2] as i32 - adapted[0] as i32 > plain[2] as i32 - plain[0] as i32,
+            "{plain:?} {adapted:?}"
+        );
+    }
+
+    #[test]
+    fn test_surround_compensation() {
+        let srgb = ColorProfile::new_srgb();
+        let options = TransformOptions {
+            surround_compensation: Some(SurroundCompensation::new(
+                Surround::Average,
+                Surround::Dark,
+            )),
+            ..Default::default()
+        };
+        let src = [255u8, 255, 255, 128, 128, 128, 0, 0, 0];
+        let mut dst = [0u8; 9];
+        srgb.create_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, options)
+            .unwrap()
+            .transform(&src, &mut dst)
+            .unwrap();
+        assert!(dst[..3].iter().all(|&v| v >= 254), "{dst:?}");
+        assert!(dst[3..6].iter().all(|&v| v < 110), "{dst:?}");
+        assert!(dst[3].abs_diff(dst[5]) <= 1, "{dst:?}");
+        assert!(dst[6..].iter().all(|&v| v <= 1), "{dst:?}");
+
+        let back = TransformOptions {
+            surround_compensation: Some(SurroundCompensation::new(
+                Surround::Dark,
+                Surround::Average,
+            )),
+            ..Default::default()
+        };
+        let mut restored = [0u8; 9];
+        srgb.create_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, back)
+            .unwrap()
+            .transform(&dst, &mut restored)
+            .unwrap();
+        assert!(restored[3].abs_diff(128) <= 2, "{restored:?}");
+    }
+
+    #[test]
+    fn test_pcs_output_layouts() {
+        let srgb = ColorProfile::new_srgb();
+        let src = [1f32, 1., 1., 0.5, 0.5, 0.5, 1., 0., 0.];
+        let mut xyz = [0f32; 9];
+        let mut lab = [0f32; 9];
+        srgb.create_transform_f32(Layout::Rgb, &srgb, Layout::Xyz, Default::default())
+            .unwrap()
+            .transform(&src, &mut xyz)
+            .unwrap();
+        srgb.create_transform_f32(Layout::Rgb, &srgb, Layout::Lab, Default::default())
+            .unwrap()
+            .transform(&src, &mut lab)
+            .unwrap();
+        let d50 = Chromaticity::D50.to_xyz();
+        assert!((xyz[0] - d50.x).abs() < 2e-3, "{xyz:?}");
+        assert!((xyz[1] - 1.).abs() < 2e-3, "{xyz:?}");
+        assert!((xyz[2] - d50.z).abs() < 2e-3, "{xyz:?}");
+        assert!((xyz[4] - 0.214).abs() < 3e-3, "{xyz:?}");
+        assert!((lab[0] - 100.).abs() < 0.2, "{lab:?}");
+        assert!(lab[1].abs() < 0.3 && lab[2].abs() < 0.3, "{lab:?}");
+        assert!((lab[6] - 54.3).abs() < 0.5 && lab[7] > 75., "{lab:?}");
+
+        assert!(matches!(
+            srgb.create_transform_8bit(Layout::Rgb, &srgb, Layout::Lab, Default::default()),
+            Err(CmsError::FloatingPointRequired)
+        ));
+        assert!(matches!(
+            srgb.create_transform_f32(Layout::Lab, &srgb, Layout::Rgb, Default::default()),
+            Err(CmsError::PcsSourceLayout(Layout::Lab))
+        ));
+        assert!(matches!(
+            srgb.create_transform_f32(Layout::Xyz, &srgb, Layout::Lab, Default::default()),
+            Err(CmsError::PcsSourceLayout(Layout::Xyz))
+        ));
+
+        let gray = ColorProfile::new_gray_with_gamma(2.2);
+        let mut xyz = [0f32; 6];
+        gray.create_transform_f32(Layout::GrayAlpha, &srgb, Layout::Xyz, Default::default())
+            .unwrap()
+            .transform(&[1., 1., 0.5, 1.], &mut xyz)
+            .unwrap();
+        assert!((xyz[1] - 1.).abs() < 2e-3, "{xyz:?}");
+        assert!((xyz[2] - d50.z).abs() < 2e-3, "{xyz:?}");
+        assert!((xyz[4] - 0.5f32.powf(2.2)).abs() < 2e-3, "{xyz:?}");
+
+        let cmyk =
+            ColorProfile::new_from_slice(&std::fs::read("./assets/us_swop_coated.icc").unwrap())
+                .unwrap();
+        let mut lab = [0f32; 6];
+        cmyk.create_transform_f32(Layout::Rgba, &srgb, Layout::Lab, Default::default())
+            .unwrap()
+            .transform(&[0., 0., 0., 0., 0., 0., 0., 1.], &mut lab)
+            .unwrap();
+        assert!(lab[0] > 85. && lab[3] < 30., "{lab:?}");
+    }
+
+    #[test]
+    fn test_luminance_scaling() {
+        let mut sdr = ColorProfile::new_srgb();
+        sdr.luminance = Some(Xyzd::new(0., 100., 0.));
+        let mut hdr = ColorProfile::new_srgb();
+        hdr.luminance = Some(Xyzd::new(0., 400., 0.));
+        assert_eq!(sdr.luminance_scale(&hdr), Some(0.25));
+
+        let src = [255u8, 255, 255];
+        let mut dst = [0u8; 3];
+        let make = |luminance_scaling: bool| {
+            sdr.create_transform_8bit(
+                Layout::Rgb,
+                &hdr,
+                Layout::Rgb,
+                TransformOptions {
+                    luminance_scaling,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+        make(false).transform(&src, &mut dst).unwrap();
+        assert_eq!(dst, [255, 255, 255]);
+        make(true).transform(&src, &mut dst).unwrap();
+        // sRGB encoding of 0.25
+        for v in dst {
+            assert!((v as i32 - 137).abs() <= 1, "{dst:?}");
+        }
+
+        if let Ok(srgb_perceptual_icc) = std::fs::read("./assets/srgb_perceptual.icc") {
+            let mut src_profile = ColorProfile::new_from_slice(&srgb_perceptual_icc).unwrap();
+            src_profile.luminance = Some(Xyzd::new(0., 100., 0.));
+            let lut = |luminance_scaling: bool| {
+                let mut dst = [0u8; 3];
+                src_profile
+                    .create_transform_8bit(
+                        Layout::Rgb,
+                        &hdr,
+                        Layout::Rgb,
+                        TransformOptions {
+                            luminance_scaling,
+                            ..Default::default()
+                        },
+                    )
+                    .unwrap()
+                    .transform(&src, &mut dst)
+                    .unwrap();
+                dst
+            };
+            let plain = lut(false);
+            let scaled = lut(true);
+            for (plain, scaled) in plain.iter().zip(scaled.iter()) {
+                assert!(*scaled < *plain - 64, "{plain:?} {scaled:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_alpha_drop_and_synthesize() {
+        let srgb = ColorProfile::new_srgb();
+        let p3 = ColorProfile::new_display_p3();
+        let gray = ColorProfile::new_gray_with_gamma(2.2);
+        let options = TransformOptions::default();
+        let pairs = [
+            (&srgb, Layout::Rgba, &p3, Layout::Rgb),
+            (&srgb, Layout::Rgb, &p3, Layout::Rgba),
+            (&gray, Layout::GrayAlpha, &srgb, Layout::Rgb),
+            (&gray, Layout::GrayAlpha, &srgb, Layout::Rgba),
+            (&gray, Layout::GrayAlpha, &gray, Layout::Gray),
+            (&srgb, Layout::Rgba, &gray, Layout::GrayAlpha),
+        ];
+        for (src_pr, src_layout, dst_pr, dst_layout) in pairs {
+            let src_alpha = src_layout.has_alpha();
+            let dst_alpha = dst_layout.has_alpha();
+
+            let transform = src_pr
+                .create_transform_8bit(src_layout, dst_pr, dst_layout, options)
+                .unwrap();
+            let src = vec![77u8; src_layout.channels() * 16];
+            let mut dst = vec![0u8; dst_layout.channels() * 16];
+            transform.transform(&src, &mut dst).unwrap();
+            if dst_alpha {
+                for pixel in dst.chunks_exact(dst_layout.channels()) {
+                    assert_eq!(*pixel.last().unwrap(), if src_alpha { 77 } else { 255 });
+                }
+            }
+
+            let transform = src_pr
+                .create_transform_16bit(src_layout, dst_pr, dst_layout, options)
+                .unwrap();
+            let src = vec![30000u16; src_layout.channels() * 16];
+            let mut dst = vec![0u16; dst_layout.channels() * 16];
+            transform.transform(&src, &mut dst).unwrap();
+            if dst_alpha {
+                for pixel in dst.chunks_exact(dst_layout.channels()) {
+                    assert_eq!(
+                        *pixel.last().unwrap(),
+                        if src_alpha { 30000 } else { 65535 }
+                    );
+                }
+            }
+
+            let transform = src_pr
+                .create_transform_f32(src_layout, dst_pr, dst_layout, options)
+                .unwrap();
+            let src = vec![0.3f32; src_layout.channels() * 16];
+            let mut dst = vec![0f32; dst_layout.channels() * 16];
+            transform.transform(&src, &mut dst).unwrap();
+            if dst_alpha {
+                for pixel in dst.chunks_exact(dst_layout.channels()) {
+                    assert_eq!(*pixel.last().unwrap(), if src_alpha { 0.3 } else { 1. });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rgb_to_gray_luma_weights() {
+        let srgb = ColorProfile::new_srgb();
+        let mut srgb_gray = ColorProfile::new_gray_with_gamma(2.2);
+        srgb_gray.gray_trc = srgb.red_trc.clone();
+        let options = TransformOptions {
+            gray_weights: GrayWeights::Rec601,
+            allow_use_cicp_transfer: false,
+            ..Default::default()
+        };
+        let transform = srgb
+            .create_transform_8bit(Layout::Rgb, &srgb_gray, Layout::Gray, options)
+            .unwrap();
+        let src = [
+            255u8, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255, 100, 150, 200,
+        ];
+        let mut dst = [0u8; 5];
+        transform.transform(&src, &mut dst).unwrap();
+        assert_eq!(dst, [76, 150, 29, 255, 141]);
+
+        let options = TransformOptions {
+            gray_weights: GrayWeights::Custom([0., 1., 0.]),
+            ..Default::default()
+        };
+        let gamma_gray = ColorProfile::new_gray_with_gamma(2.2);
+        let transform = srgb
+            .create_transform_16bit(Layout::Rgba, &gamma_gray, Layout::GrayAlpha, options)
+            .unwrap();
+        let reference = srgb
+            .create_transform_16bit(
+                Layout::Rgba,
+                &gamma_gray,
+                Layout::GrayAlpha,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let src = [
+            0u16, 40000, 0, 1000, 0, 0, 0, 65535, 65535, 65535, 65535, 65535,
+        ];
+        let mut dst = [0u16; 6];
+        transform.transform(&src, &mut dst).unwrap();
+        let gray_src = [
+            40000u16, 40000, 40000, 1000, 0, 0, 0, 65535, 65535, 65535, 65535, 65535,
+        ];
+        let mut expected = [0u16; 6];
+        reference.transform(&gray_src, &mut expected).unwrap();
+        for (&v, &e) in dst.iter().zip(expected.iter()) {
+            assert!(v.abs_diff(e) <= 64, "Expected {e}, got {v}");
+        }
+        assert_eq!(dst[1], 1000);
+    }
+    #[test]
+    fn test_affine_matrix_stage() {
+        use crate::{LutMultidimensionalType, LutStore, LutWarehouse, ToneReprCurve, Vector3d};
+
+        let srgb = ColorProfile::new_srgb();
+        let trc = srgb.red_trc.clone().unwrap();
+        let make_profile = |clut: Option<LutStore>| {
+            let mut profile = ColorProfile::new_srgb();
+            profile.lut_a_to_b_perceptual =
+                Some(LutWarehouse::Multidimensional(LutMultidimensionalType {
+                    num_input_channels: 3,
+                    num_output_channels: 3,
+                    grid_points: [2, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                    clut,
+                    a_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+                    b_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+                    m_curves: vec![trc.clone(); 3],
+                    matrix: srgb.rgb_to_xyz_matrix().mul_scalar(32768. / 65535.),
+                    bias: Vector3d {
+                        v: [0.02, 0.01, 0.015],
+                    },
+                }));
+            profile
+        };
+        let identity_clut = (0..8u16)
+            .flat_map(|i| [(i >> 2) & 1, (i >> 1) & 1, i & 1].map(|x| x * 65535))
+            .collect::<Vec<_>>();
+        let collapsed = make_profile(None);
+        let reference = make_profile(Some(LutStore::Store16(identity_clut.into())));
+
+        let src = [
+            0u16, 0, 0, 65535, 65535, 65535, 32768, 16384, 8192, 1000, 50000, 30000,
+        ];
+        let mut dst = [0u16; 12];
+        let mut reference_dst = [0u16; 12];
+        collapsed
+            .create_transform_16bit(Layout::Rgb, &srgb, Layout::Rgb, TransformOptions::default())
+            .unwrap()
+            .transform(&src, &mut dst)
+            .unwrap();
+        reference
+            .create_transform_16bit(Layout::Rgb, &srgb, Layout::Rgb, TransformOptions::default())
+            .unwrap()
+            .transform(&src, &mut reference_dst)
+            .unwrap();
+        for (&a, &b) in dst.iter().zip(reference_dst.iter()) {
+            assert!(
+                (a as i32 - b as i32).abs() < 160,
+                "collapsed {dst:?} lut {reference_dst:?}"
+            );
+        }
+        // Bias must lift black
+        assert!(dst[0] > 0 || dst[1] > 0 || dst[2] > 0);
+
+        let options = TransformOptions {
+            fixed_point_format: Some(FixedPointFormat::Q2_13),
+            ..Default::default()
+        };
+        assert!(matches!(
+            collapsed.create_transform_16bit(Layout::Rgb, &srgb, Layout::Rgb, options),
+            Err(CmsError::UnsupportedFixedPointFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_non_cubic_mab_clut() {
+        use crate::{
+            Cube, Hypercube, LutMultidimensionalType, LutStore, LutWarehouse, ToneReprCurve,
+        };
+
+        let make_lut = |inputs: u8, grid: &[u8], clut: Vec<f32>| {
+            let mut grid_points = [0u8; 16];
+            grid_points[..grid.len()].copy_from_slice(grid);
+            LutWarehouse::Multidimensional(LutMultidimensionalType {
+                num_input_channels: inputs,
+                num_output_channels: 3,
+                grid_points,
+                clut: Some(LutStore::from_clut_f32(&clut)),
+                a_curves: vec![ToneReprCurve::Lut(vec![]); inputs as usize],
+                b_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+                m_curves: vec![],
+                matrix: Default::default(),
+                bias: Default::default(),
+            })
+        };
+        // Affine CLUT content is reproduced exactly by any interpolation on any grid,
+        // so every grid layout must match the cubic one.
+        let rgb_fn = |r: f32, g: f32, b: f32| [0.1 + 0.5 * r, 0.3 * g + 0.2 * b, 0.8 * b];
+        let cmyk_fn = |c: f32, m: f32, y: f32, k: f32| {
+            [
+                0.9 - 0.4 * c - 0.2 * k,
+                0.9 - 0.4 * m - 0.2 * k,
+                0.9 - 0.4 * y - 0.2 * k,
+            ]
+        };
+        let rgb_profile = |grid: [u8; 3]| {
+            let mut profile = ColorProfile::new_srgb();
+            profile.lut_a_to_b_perceptual = Some(make_lut(3, &grid, Cube::from_fn(grid, rgb_fn)));
+            profile
+        };
+        let cmyk_profile = |grid: [u8; 4]| ColorProfile {
+            color_space: DataColorSpace::Cmyk,
+            pcs: DataColorSpace::Lab,
+            lut_a_to_b_perceptual: Some(make_lut(4, &grid, Hypercube::from_fn(grid, cmyk_fn))),
+            ..Default::default()
+        };
+
+        let srgb = ColorProfile::new_srgb();
+        #[cfg(feature = "options")]
+        let methods = vec![
+            InterpolationMethod::Linear,
+            InterpolationMethod::Tetrahedral,
+            InterpolationMethod::Pyramid,
+            InterpolationMethod::Prism,
+        ];
+        #[cfg(not(feature = "options"))]
+        let methods = vec![InterpolationMethod::Linear];
+        let rgb_src = (0..16 * 3)
+            .map(|i| ((i * 97 + 13) % 256) as u8)
+            .collect::<Vec<_>>();
+        let cmyk_src = (0..16 * 4)
+            .map(|i| ((i * 89 + 7) % 256) as u8)
+            .collect::<Vec<_>>();
+        let run = |profile: &ColorProfile, layout: Layout, src: &[u8], method| {
+            let options = TransformOptions {
+                interpolation_method: method,
+                ..Default::default()
+            };
+            let mut dst = vec![0u8; src.len() / layout.channels() * 3];
+            profile
+                .create_transform_8bit(layout, &srgb, Layout::Rgb, options)
+                .unwrap()
+                .transform(src, &mut dst)
+                .unwrap();
+            dst
+        };
+        for method in methods {
+            let reference = run(&rgb_profile([2, 2, 2]), Layout::Rgb, &rgb_src, method);
+            for grid in [[2, 3, 5], [5, 3, 2], [9, 2, 17]] {
+                let dst = run(&rgb_profile(grid), Layout::Rgb, &rgb_src, method);
+                for (&a, &b) in dst.iter().zip(reference.iter()) {
+                    assert!(
+                        a.abs_diff(b) <= 1,
+                        "{method:?} {grid:?}: {dst:?} {reference:?}"
+                    );
+                }
+            }
+            let reference = run(&cmyk_profile([2, 2, 2, 2]), Layout::Rgba, &cmyk_src, method);
+            for grid in [[2, 3, 4, 5], [5, 4, 3, 2], [3, 9, 2, 6]] {
+                let dst = run(&cmyk_profile(grid), Layout::Rgba, &cmyk_src, method);
+                for (&a, &b) in dst.iter().zip(reference.iter()) {
+                    assert!(
+                        a.abs_diff(b) <= 1,
+                        "{method:?} {grid:?}: {dst:?} {reference:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mislabeled_lut_pcs() {
+        use crate::{Cube, LutMultidimensionalType, LutStore, LutWarehouse, ToneReprCurve};
+
+        // CLUT produces Lab encoded values while header may claim XYZ
+        let clut = Cube::from_fn([5, 5, 5], |r: f32, g: f32, b: f32| {
+            [
+                0.2 + 0.8 * (r + g + b) / 3.,
+                0.5 + 0.2 * (r - g),
+                0.5 + 0.2 * (g - b),
+            ]
+        });
+        let profile = |pcs: DataColorSpace| ColorProfile {
+            color_space: DataColorSpace::Rgb,
+            pcs,
+            lut_a_to_b_perceptual: Some(LutWarehouse::Multidimensional(LutMultidimensionalType {
+                num_input_channels: 3,
+                num_output_channels: 3,
+                grid_points: [5; 16],
+                clut: Some(LutStore::from_clut_f32(&clut)),
+                a_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+                b_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+                m_curves: vec![],
+                matrix: Default::default(),
+                bias: Default::default(),
+            })),
+            ..Default::default()
+        };
+        let srgb = ColorProfile::new_srgb();
+        let src = (0..64 * 3)
+            .map(|i| ((i * 97 + 13) % 256) as u8)
+            .collect::<Vec<_>>();
+        let run = |profile: &ColorProfile, assume_pcs: Option<DataColorSpace>| {
+            let options = TransformOptions {
+                assume_pcs,
+                ..Default::default()
+            };
+            let mut dst = vec![0u8; src.len()];
+            profile
+                .create_transform_8bit(Layout::Rgb, &srgb, Layout::Rgb, options)
+                .unwrap()
+                .transform(&src, &mut dst)
+                .unwrap();
+            dst
+        };
+        let lab = run(&profile(DataColorSpace::Lab), None);
+        assert_eq!(run(&profile(DataColorSpace::Xyz), None), lab);
+        assert_eq!(
+            run(&profile(DataColorSpace::Xyz), Some(DataColorSpace::Lab)),
+            lab
+        );
+        assert_ne!(
+            run(&profile(DataColorSpace::Lab), Some(DataColorSpace::Xyz)),
+            lab
+        );
+    }
+
+    #[cfg(feature = "integer_pixels")]
+    #[test]
+    fn test_integer_pixels_transform() {
+        let srgb = ColorProfile::new_srgb();
+        let bt2020 = ColorProfile::new_bt2020();
+        let transform = srgb
+            .create_transform_8bit(
+                Layout::Rgb,
+                &bt2020,
+                Layout::Rgb,
+                TransformOptions {
+                    prefer_fixed_point: false,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            transform.fixed_point_format(),
+            Some(FixedPointFormat::Q2_13)
+        );
+        // Gray has few enough codes to be tabulated
+        let gray = ColorProfile::new_gray_with_gamma(2.2);
+        let transform = gray
+            .create_transform_8bit(
+                Layout::Gray,
+                &srgb,
+                Layout::Rgb,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let mut dst = [0u8; 3];
+        transform.transform(&[255], &mut dst).unwrap();
+        assert_eq!(dst, [255, 255, 255]);
+
+        // Three channel source is sampled into a grid, alpha is passed through
+        let transform = srgb
+            .create_transform_8bit(
+                Layout::Rgba,
+                &gray,
+                Layout::GrayAlpha,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let reference = srgb
+            .create_transform_f32(
+                Layout::Rgb,
+                &gray,
+                Layout::Gray,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        let src = (0..4096 * 4)
+            .map(|x| if x % 4 == 3 { 77 } else { (x * 37 % 256) as u8 })
+            .collect::<Vec<u8>>();
+        let mut dst = vec![0u8; 4096 * 2];
+        transform.transform(&src, &mut dst).unwrap();
+        let src_f32 = src
+            .chunks_exact(4)
+            .flat_map(|px| px[..3].iter().map(|&v| v as f32 / 255.))
+            .collect::<Vec<f32>>();
+        let mut expected = vec![0f32; 4096];
+        reference.transform(&src_f32, &mut expected).unwrap();
+        for (px, &v) in dst.chunks_exact(2).zip(expected.iter()) {
+            let v = (v * 255.).round() as i32;
+            assert!((px[0] as i32 - v).abs() <= 1, "{} vs {v}", px[0]);
+            assert_eq!(px[1], 77);
+        }
+
+        // PCS output is floating point by definition
+        assert!(matches!(
+            srgb.create_transform_8bit(
+                Layout::Rgb,
+                &ColorProfile::new_lab(),
+                Layout::Lab,
+                TransformOptions::default()
+            ),
+            Err(CmsError::FloatingPointRequired)
+        ));
+    }
 }
@@ -30,29 +30,161 @@ use crate::cicp::create_rec709_parametric;
 use crate::matan::is_curve_linear16;
 use crate::math::m_clamp;
 use crate::mlaf::{mlaf, neg_mlaf};
+use crate::trace::trace_span;
 use crate::transform::PointeeSizeExpressible;
 use crate::writer::FloatToFixedU8Fixed8;
-use crate::{CmsError, ColorProfile, DataColorSpace, Rgb, TransferCharacteristics};
+use crate::{CmsError, ColorProfile, DataColorSpace, Rgb, SegmentedCurve, TransferCharacteristics};
 use num_traits::AsPrimitive;
 use pxfm::{dirty_powf, f_pow, f_powf};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// Samples count used when segmented curve has to be inverted
+const SEGMENTED_LUT_SIZE: usize = 4096;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ToneReprCurve {
     Lut(Vec<u16>),
     Parametric(Vec<f32>),
+    /// Segmented curve `curf` with formula and sampled segments
+    Segmented(SegmentedCurve),
+    /// User provided transfer function, only for programmatically built profiles
+    Custom(CustomCurve),
+}
+
+/// Behavior of parametric curves outside of [0, 1] domain.
+///
+/// ICC defines parametric curves only on [0, 1] and requires clipping, this matters only
+/// for floating point transforms with [crate::TransformOptions::allow_extended_range_rgb_xyz].
+#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum CurveExtension {
+    /// Input and output are clipped into [0, 1] as ICC specification requires
+    Clip,
+    /// Formula continues above 1, below 0 curve is point symmetric about `f(0)`,
+    /// this is the same as sRGB extended range for curves with linear segment
+    #[default]
+    Mirror,
+    /// Curve continues by tangent lines from 0 and 1
+    Linear,
+}
+
+/// Transfer function defined by a closure on [0, 1].
+///
+/// Evaluated directly where the pipeline allows it, otherwise sampled
+/// into a table of `lut_size` entries, and encoded as `curv` table.
+#[derive(Clone)]
+pub struct CustomCurve {
+    function: Arc<dyn Fn(f32) -> f32 + Send + Sync>,
+    lut_size: usize,
+}
+
+impl CustomCurve {
+    /// Creates curve from `function`, `lut_size` is clamped into [2, 65535]
+    pub fn new(function: Arc<dyn Fn(f32) -> f32 + Send + Sync>, lut_size: usize) -> Self {
+        Self {
+            function,
+            lut_size: lut_size.clamp(2, 65535),
+        }
+    }
+
+    /// Samples count used when curve has to be baked
+    pub fn lut_size(&self) -> usize {
+        self.lut_size
+    }
+
+    /// Evaluates curve at `x`
+    pub fn eval(&self, x: f32) -> f32 {
+        (self.function)(x)
+    }
+
+    /// Samples curve on [0, 1] into `curv` like table
+    pub fn to_lut(&self) -> Vec<u16> {
+        let divisor = (self.lut_size - 1) as f32;
+        (0..self.lut_size)
+            .map(|i| {
+                let v = self.eval(i as f32 / divisor);
+                (v.max(0.).min(1.) * 65535. + 0.5) as u16
+            })
+            .collect()
+    }
+}
+
+impl Debug for CustomCurve {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomCurve")
+            .field("lut_size", &self.lut_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for CustomCurve {
+    /// Closures can't be compared, curves are equal only when share the same function
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.function, &other.function) && self.lut_size == other.lut_size
+    }
 }
 
 impl ToneReprCurve {
     pub fn inverse(&self) -> Result<ToneReprCurve, CmsError> {
         match self {
-            ToneReprCurve::Lut(lut) => {
-                let inverse_length = lut.len().max(256);
-                Ok(ToneReprCurve::Lut(invert_lut(lut, inverse_length)))
-            }
+            ToneReprCurve::Lut(lut) => match lut.len() {
+                // Zero entries is identity, single entry is pure gamma
+                0 => Ok(ToneReprCurve::Lut(vec![])),
+                1 => Ok(ToneReprCurve::Parametric(vec![
+                    1. / u8_fixed_8number_to_float(lut[0]),
+                ])),
+                len => Ok(ToneReprCurve::Lut(invert_lut(lut, len.max(256)))),
+            },
             ToneReprCurve::Parametric(parametric) => ParametricCurve::new(parametric)
                 .and_then(|x| x.invert())
                 .map(|x| ToneReprCurve::Parametric([x.g, x.a, x.b, x.c, x.d, x.e, x.f].to_vec()))
                 .ok_or(CmsError::BuildTransferFunction),
+            ToneReprCurve::Segmented(curve) => Ok(ToneReprCurve::Lut(invert_lut(
+                &curve.to_lut(SEGMENTED_LUT_SIZE),
+                SEGMENTED_LUT_SIZE,
+            ))),
+            ToneReprCurve::Custom(curve) => Ok(ToneReprCurve::Lut(invert_lut(
+                &curve.to_lut(),
+                curve.lut_size(),
+            ))),
+        }
+    }
+
+    /// Slope of the curve at `x` in [0, 1].
+    ///
+    /// Parametric curves and single entry gamma are differentiated analytically,
+    /// sampled tables return slope of the interpolated segment containing `x`,
+    /// segmented and custom curves use central finite differences.
+    pub fn derivative(&self, x: f32) -> f32 {
+        let x = x.max(0.).min(1.);
+        match self {
+            ToneReprCurve::Lut(lut) => match lut.len() {
+                0 => 1.,
+                1 => {
+                    let gamma = u8_fixed_8number_to_float(lut[0]);
+                    if x == 0. {
+                        if gamma < 1. {
+                            f32::INFINITY
+                        } else if gamma == 1. {
+                            1.
+                        } else {
+                            0.
+                        }
+                    } else {
+                        gamma * f_powf(x, gamma - 1.)
+                    }
+                }
+                len => {
+                    let scale = (len - 1) as f32;
+                    let index = ((x * scale) as usize).min(len - 2);
+                    (lut[index + 1] as f32 - lut[index] as f32) * (1. / 65535.) * scale
+                }
+            },
+            ToneReprCurve::Parametric(params) => ParametricCurve::new(params)
+                .map(|x0| x0.derivative(x))
+                .unwrap_or(0.),
+            ToneReprCurve::Segmented(curve) => finite_difference(|v| curve.eval(v), x),
+            ToneReprCurve::Custom(curve) => finite_difference(|v| curve.eval(v), x),
         }
     }
 
@@ -77,10 +209,17 @@ impl ToneReprCurve {
             ToneReprCurve::Parametric(parametric) => {
                 let parametric_curve =
                     ParametricCurve::new(parametric).ok_or(CmsError::BuildTransferFunction)?;
-                Ok(Box::new(ToneCurveParametricEvaluator {
-                    parametric: parametric_curve,
-                }))
+                Ok(Box::new(ToneCurveParametricEvaluator::new(
+                    parametric_curve,
+                    CurveExtension::default(),
+                )))
             }
+            ToneReprCurve::Segmented(curve) => Ok(Box::new(ToneCurveSegmentedEvaluator {
+                curve: curve.clone(),
+            })),
+            ToneReprCurve::Custom(curve) => Ok(Box::new(ToneCurveCustomEvaluator {
+                curve: curve.clone(),
+            })),
         }
     }
 
@@ -122,8 +261,29 @@ impl ToneReprCurve {
                 let parametric_curve = ParametricCurve::new(parametric)
                     .and_then(|x| x.invert())
                     .ok_or(CmsError::BuildTransferFunction)?;
-                Ok(Box::new(ToneCurveParametricEvaluator {
-                    parametric: parametric_curve,
+                Ok(Box::new(ToneCurveParametricEvaluator::new(
+                    parametric_curve,
+                    CurveExtension::default(),
+                )))
+            }
+            ToneReprCurve::Segmented(curve) => {
+                let inverted_lut = invert_lut(&curve.to_lut(SEGMENTED_LUT_SIZE), 16384);
+                let converted_curve = inverted_lut
+                    .iter()
+                    .map(|&x| x as f32 / 65535.0)
+                    .collect::<Vec<_>>();
+                Ok(Box::new(ToneCurveLutEvaluator {
+                    lut: converted_curve,
+                }))
+            }
+            ToneReprCurve::Custom(curve) => {
+                let inverted_lut = invert_lut(&curve.to_lut(), 16384);
+                let converted_curve = inverted_lut
+                    .iter()
+                    .map(|&x| x as f32 / 65535.0)
+                    .collect::<Vec<_>>();
+                Ok(Box::new(ToneCurveLutEvaluator {
+                    lut: converted_curve,
                 }))
             }
         }
@@ -215,6 +375,14 @@ pub(crate) fn build_trc_table(num_entries: i32, eotf: impl Fn(f64) -> f64) -> Ve
     table
 }
 
+/// Central difference on [0, 1], one sided at the domain borders
+fn finite_difference(f: impl Fn(f32) -> f32, x: f32) -> f32 {
+    const H: f32 = 1. / 1024.;
+    let lo = (x - H).max(0.);
+    let hi = (x + H).min(1.);
+    (f(hi) - f(lo)) / (hi - lo)
+}
+
 /// Creates Tone Reproduction curve from gamma
 pub fn curve_from_gamma(gamma: f32) -> ToneReprCurve {
     ToneReprCurve::Lut(vec![gamma.to_u8_fixed8()])
@@ -302,6 +470,21 @@ impl ParametricCurve {
         }
     }
 
+    fn derivative(&self, x: f32) -> f32 {
+        if x < self.d {
+            return self.c;
+        }
+        let base = self.a * x + self.b;
+        if base <= 0. {
+            return if self.g < 1. && base == 0. {
+                f32::INFINITY
+            } else {
+                0.
+            };
+        }
+        self.g * self.a * f_powf(base, self.g - 1.)
+    }
+
     #[allow(dead_code)]
     #[allow(clippy::many_single_char_names)]
     fn invert(&self) -> Option<ParametricCurve> {
@@ -507,6 +690,28 @@ fn linear_curve_parametric<T: PointeeSizeExpressible, const N: usize, const BIT_
     Some(gamma_table)
 }
 
+fn linear_curve_evaluated<T: PointeeSizeExpressible, const N: usize, const BIT_DEPTH: usize>(
+    eval: impl Fn(f32) -> f32,
+) -> Box<[f32; N]> {
+    let mut gamma_table = Box::new([0f32; N]);
+    let max_value = if T::FINITE {
+        (1 << BIT_DEPTH) - 1
+    } else {
+        T::NOT_FINITE_LINEAR_TABLE_SIZE - 1
+    };
+    let cap_value = if T::FINITE {
+        1 << BIT_DEPTH
+    } else {
+        T::NOT_FINITE_LINEAR_TABLE_SIZE
+    };
+    let scale_value = 1f32 / max_value as f32;
+    for (i, g) in gamma_table.iter_mut().enumerate().take(cap_value) {
+        let x = i as f32 * scale_value;
+        *g = m_clamp(eval(x), 0.0, 1.0);
+    }
+    gamma_table
+}
+
 fn linear_curve_parametric_s<const N: usize>(params: &[f32]) -> Option<Box<[f32; N]>> {
     let params = ParametricCurve::new(params)?;
     let mut gamma_table = Box::new([0f32; N]);
@@ -988,7 +1193,7 @@ fn lut_inverse_interp16_boxed<const N: usize>(value: u16, lut_table: &[u16; N])
     (f + 0.5f64).floor() as u16
 }
 
-fn invert_lut(table: &[u16], out_length: usize) -> Vec<u16> {
+pub(crate) fn invert_lut(table: &[u16], out_length: usize) -> Vec<u16> {
     // For now, we invert the lut by creating a lut of size out_length
     // and attempting to look up a value for each entry using lut_inverse_interp16
     let mut output = vec![0u16; out_length];
@@ -1017,7 +1222,7 @@ fn invert_lut_boxed<const N: usize>(table: &[u16; N], out_length: usize) -> Vec<
 impl ToneReprCurve {
     pub(crate) fn to_clut(&self) -> Result<Vec<f32>, CmsError> {
         match self {
-            ToneReprCurve::Lut(lut) => {
+            ToneReprCurve::Lut(lut) if lut.len() != 1 => {
                 if lut.is_empty() {
                     let passthrough_table = passthrough_table::<f32, 16384, 1>();
                     Ok(passthrough_table.to_vec())
@@ -1028,7 +1233,11 @@ impl ToneReprCurve {
                         .collect::<Vec<_>>())
                 }
             }
-            ToneReprCurve::Parametric(_) => {
+            // Single entry is gamma exponent rather than table node
+            ToneReprCurve::Lut(_)
+            | ToneReprCurve::Parametric(_)
+            | ToneReprCurve::Segmented(_)
+            | ToneReprCurve::Custom(_) => {
                 let curve = self
                     .build_linearize_table::<f32, 65535, 1>()
                     .ok_or(CmsError::InvalidTrcCurve)?;
@@ -1048,6 +1257,12 @@ impl ToneReprCurve {
     ) -> Option<Box<[f32; N]>> {
         match self {
             ToneReprCurve::Parametric(params) => linear_curve_parametric::<T, N, BIT_DEPTH>(params),
+            ToneReprCurve::Segmented(curve) => {
+                Some(linear_curve_evaluated::<T, N, BIT_DEPTH>(|x| curve.eval(x)))
+            }
+            ToneReprCurve::Custom(curve) => {
+                Some(linear_curve_evaluated::<T, N, BIT_DEPTH>(|x| curve.eval(x)))
+            }
             ToneReprCurve::Lut(data) => match data.len() {
                 0 => Some(passthrough_table::<T, N, BIT_DEPTH>()),
                 1 => Some(linear_forward_table::<T, N, BIT_DEPTH>(data[0])),
@@ -1070,47 +1285,13 @@ impl ToneReprCurve {
     {
         match self {
             ToneReprCurve::Parametric(params) => {
-                if params.len() == 5 {
-                    let srgb_params = vec![2.4, 1. / 1.055, 0.055 / 1.055, 1. / 12.92, 0.04045];
-                    let rec709_params = create_rec709_parametric();
-
-                    let mut lc_params: [f32; 5] = [0.; 5];
-                    for (dst, src) in lc_params.iter_mut().zip(params.iter()) {
-                        *dst = *src;
-                    }
-
-                    if compare_parametric(lc_params.as_slice(), srgb_params.as_slice()) {
-                        return Some(
-                            TransferCharacteristics::Srgb
-                                .make_gamma_table::<T, BUCKET, N>(BIT_DEPTH),
-                        );
-                    }
-
-                    if compare_parametric(lc_params.as_slice(), rec709_params.as_slice()) {
-                        return Some(
-                            TransferCharacteristics::Bt709
-                                .make_gamma_table::<T, BUCKET, N>(BIT_DEPTH),
-                        );
-                    }
-                }
-
-                let parametric_curve = ParametricCurve::new(params);
-                if let Some(v) = parametric_curve?
-                    .invert()
-                    .map(|x| make_gamma_parametric_table::<T, BUCKET, N, BIT_DEPTH>(x))
-                {
-                    return Some(v);
-                }
-
-                let mut gamma_table_uint = Box::new([0; N]);
-
-                let inverted_size: usize = N;
-                let gamma_table = linear_curve_parametric_s::<N>(params)?;
-                for (&src, dst) in gamma_table.iter().zip(gamma_table_uint.iter_mut()) {
-                    *dst = (src * 65535f32) as u16;
+                let mut table =
+                    Self::build_parametric_gamma_table::<T, BUCKET, N, BIT_DEPTH>(params)?;
+                if BIT_DEPTH == 8 && T::FINITE {
+                    let linear = self.build_linearize_table::<u8, 256, 8>()?;
+                    refine_gamma_table_8bit::<T, BUCKET, N>(&mut table, linear.as_slice());
                 }
-                let inverted = invert_lut_boxed(&gamma_table_uint, inverted_size);
-                Some(make_gamma_lut::<T, BUCKET, N, BIT_DEPTH>(&inverted))
+                Some(table)
             }
             ToneReprCurve::Lut(data) => match data.len() {
                 0 => Some(make_gamma_linear_table::<T, BUCKET, N>(BIT_DEPTH)),
@@ -1127,6 +1308,91 @@ impl ToneReprCurve {
                     Some(make_gamma_lut::<T, BUCKET, N, BIT_DEPTH>(&inverted))
                 }
             },
+            ToneReprCurve::Segmented(curve) => {
+                let inverted = invert_lut(&curve.to_lut(SEGMENTED_LUT_SIZE), SEGMENTED_LUT_SIZE);
+                Some(make_gamma_lut::<T, BUCKET, N, BIT_DEPTH>(&inverted))
+            }
+            ToneReprCurve::Custom(curve) => {
+                let inverted = invert_lut(&curve.to_lut(), curve.lut_size());
+                Some(make_gamma_lut::<T, BUCKET, N, BIT_DEPTH>(&inverted))
+            }
+        }
+    }
+
+    fn build_parametric_gamma_table<
+        T: Default + Copy + 'static + PointeeSizeExpressible + GammaLutInterpolate,
+        const BUCKET: usize,
+        const N: usize,
+        const BIT_DEPTH: usize,
+    >(
+        params: &[f32],
+    ) -> Option<Box<[T; BUCKET]>>
+    where
+        f32: AsPrimitive<T>,
+        u32: AsPrimitive<T>,
+    {
+        if params.len() == 5 {
+            let srgb_params = vec![2.4, 1. / 1.055, 0.055 / 1.055, 1. / 12.92, 0.04045];
+            let rec709_params = create_rec709_parametric();
+
+            let mut lc_params: [f32; 5] = [0.; 5];
+            for (dst, src) in lc_params.iter_mut().zip(params.iter()) {
+                *dst = *src;
+            }
+
+            if compare_parametric(lc_params.as_slice(), srgb_params.as_slice()) {
+                return Some(
+                    TransferCharacteristics::Srgb.make_gamma_table::<T, BUCKET, N>(BIT_DEPTH),
+                );
+            }
+
+            if compare_parametric(lc_params.as_slice(), rec709_params.as_slice()) {
+                return Some(
+                    TransferCharacteristics::Bt709.make_gamma_table::<T, BUCKET, N>(BIT_DEPTH),
+                );
+            }
+        }
+
+        let parametric_curve = ParametricCurve::new(params);
+        if let Some(v) = parametric_curve?
+            .invert()
+            .map(|x| make_gamma_parametric_table::<T, BUCKET, N, BIT_DEPTH>(x))
+        {
+            return Some(v);
+        }
+
+        let mut gamma_table_uint = Box::new([0; N]);
+
+        let inverted_size: usize = N;
+        let gamma_table = linear_curve_parametric_s::<N>(params)?;
+        for (&src, dst) in gamma_table.iter().zip(gamma_table_uint.iter_mut()) {
+            *dst = (src * 65535f32) as u16;
+        }
+        let inverted = invert_lut_boxed(&gamma_table_uint, inverted_size);
+        Some(make_gamma_lut::<T, BUCKET, N, BIT_DEPTH>(&inverted))
+    }
+}
+
+/// Makes 8 bit codes round-trip through `linear` table and `table` where it is possible.
+///
+/// Plain sampling rounds the inverse curve at the bin centers, so a code linearized
+/// and encoded back may land to the neighbor code. Each bin hit exactly by a code
+/// is assigned to the nearest such code instead, other bins are left as sampled.
+fn refine_gamma_table_8bit<T: Copy + 'static, const BUCKET: usize, const N: usize>(
+    table: &mut [T; BUCKET],
+    linear: &[f32],
+) where
+    u32: AsPrimitive<T>,
+{
+    let scale = (N - 1) as f32;
+    let mut claimed = vec![f32::MAX; N];
+    for (code, &lin) in linear.iter().take(256).enumerate() {
+        let position = lin.max(0.).min(1.) * scale;
+        let bin = ((position + 0.5) as usize).min(N - 1);
+        let error = (position - bin as f32).abs();
+        if error < claimed[bin] {
+            claimed[bin] = error;
+            table[bin] = (code as u32).as_();
         }
     }
 }
@@ -1277,6 +1543,7 @@ impl ColorProfile {
         f32: AsPrimitive<T>,
         u32: AsPrimitive<T>,
     {
+        let _span = trace_span!("moxcms::build_gamma_table", bit_depth = BIT_DEPTH);
         if use_cicp {
             if let Some(tc) = self.cicp.as_ref().map(|c| c.transfer_characteristics) {
                 if tc.has_transfer_curve() {
@@ -1292,6 +1559,7 @@ impl ColorProfile {
     /// Checks if profile gamma can work in extended precision and we have implementation for this
     pub(crate) fn try_extended_gamma_evaluator(
         &self,
+        extension: CurveExtension,
     ) -> Option<Box<dyn ToneCurveEvaluator + Send + Sync>> {
         if let Some(tc) = self.cicp.as_ref().map(|c| c.transfer_characteristics) {
             if tc.has_transfer_curve() {
@@ -1310,13 +1578,14 @@ impl ColorProfile {
             self.red_trc.as_ref()
         };
         if let Some(red_trc) = reference_trc {
-            return Self::make_gamma_evaluator_all_the_same(red_trc);
+            return Self::make_gamma_evaluator_all_the_same(red_trc, extension);
         }
         None
     }
 
     fn make_gamma_evaluator_all_the_same(
         red_trc: &ToneReprCurve,
+        extension: CurveExtension,
     ) -> Option<Box<dyn ToneCurveEvaluator + Send + Sync>> {
         match red_trc {
             ToneReprCurve::Lut(lut) => {
@@ -1330,7 +1599,8 @@ impl ColorProfile {
                 None
             }
             ToneReprCurve::Parametric(params) => {
-                if params.len() == 5 {
+                // Known curves are already extended as their standards define
+                if params.len() == 5 && extension == CurveExtension::Mirror {
                     let srgb_params = vec![2.4, 1. / 1.055, 0.055 / 1.055, 1. / 12.92, 0.04045];
                     let rec709_params = create_rec709_parametric();
 
@@ -1356,10 +1626,11 @@ impl ColorProfile {
 
                 let parametric_curve = ParametricCurve::new(params);
                 if let Some(v) = parametric_curve?.invert() {
-                    return Some(Box::new(ToneCurveParametricEvaluator { parametric: v }));
+                    return Some(Box::new(ToneCurveParametricEvaluator::new(v, extension)));
                 }
                 None
             }
+            ToneReprCurve::Segmented(_) | ToneReprCurve::Custom(_) => None,
         }
     }
 
@@ -1431,6 +1702,7 @@ impl ColorProfile {
                     }
                     false
                 }
+                ToneReprCurve::Segmented(_) | ToneReprCurve::Custom(_) => false,
             };
         }
         false
@@ -1439,6 +1711,7 @@ impl ColorProfile {
     /// Checks if profile linearization can work in extended precision and we have implementation for this
     pub(crate) fn try_extended_linearizing_evaluator(
         &self,
+        extension: CurveExtension,
     ) -> Option<Box<dyn ToneCurveEvaluator + Send + Sync>> {
         if let Some(tc) = self.cicp.as_ref().map(|c| c.transfer_characteristics) {
             if tc.has_transfer_curve() {
@@ -1457,7 +1730,8 @@ impl ColorProfile {
             self.red_trc.as_ref()
         };
         if let Some(red_trc) = reference_trc {
-            if let Some(value) = Self::make_linear_curve_evaluator_all_the_same(red_trc) {
+            if let Some(value) = Self::make_linear_curve_evaluator_all_the_same(red_trc, extension)
+            {
                 return value;
             }
         }
@@ -1466,6 +1740,7 @@ impl ColorProfile {
 
     fn make_linear_curve_evaluator_all_the_same(
         evaluator_curve: &ToneReprCurve,
+        extension: CurveExtension,
     ) -> Option<Option<Box<dyn ToneCurveEvaluator + Send + Sync>>> {
         match evaluator_curve {
             ToneReprCurve::Lut(lut) => {
@@ -1478,7 +1753,8 @@ impl ColorProfile {
                 }
             }
             ToneReprCurve::Parametric(params) => {
-                if params.len() == 5 {
+                // Known curves are already extended as their standards define
+                if params.len() == 5 && extension == CurveExtension::Mirror {
                     let srgb_params = vec![2.4, 1. / 1.055, 0.055 / 1.055, 1. / 12.92, 0.04045];
                     let rec709_params = create_rec709_parametric();
 
@@ -1504,11 +1780,21 @@ impl ColorProfile {
 
                 let parametric_curve = ParametricCurve::new(params);
                 if let Some(v) = parametric_curve {
-                    return Some(Some(Box::new(ToneCurveParametricEvaluator {
-                        parametric: v,
-                    })));
+                    return Some(Some(Box::new(ToneCurveParametricEvaluator::new(
+                        v, extension,
+                    ))));
                 }
             }
+            ToneReprCurve::Segmented(curve) => {
+                return Some(Some(Box::new(ToneCurveSegmentedEvaluator {
+                    curve: curve.clone(),
+                })));
+            }
+            ToneReprCurve::Custom(curve) => {
+                return Some(Some(Box::new(ToneCurveCustomEvaluator {
+                    curve: curve.clone(),
+                })));
+            }
         }
         None
     }
@@ -1521,6 +1807,55 @@ pub(crate) struct ToneCurveCicpEvaluator {
 
 pub(crate) struct ToneCurveParametricEvaluator {
     parametric: ParametricCurve,
+    extension: CurveExtension,
+    /// Values and slopes at 0 and 1 for [CurveExtension::Linear]
+    lower: (f32, f32),
+    upper: (f32, f32),
+}
+
+impl ToneCurveParametricEvaluator {
+    fn new(parametric: ParametricCurve, extension: CurveExtension) -> Self {
+        const H: f32 = 1. / 4096.;
+        let v0 = parametric.eval(0.);
+        let v1 = parametric.eval(1.);
+        Self {
+            lower: (v0, (parametric.eval(H) - v0) / H),
+            upper: (v1, (v1 - parametric.eval(1. - H)) / H),
+            parametric,
+            extension,
+        }
+    }
+
+    #[inline]
+    fn eval(&self, x: f32) -> f32 {
+        match self.extension {
+            CurveExtension::Clip => m_clamp(self.parametric.eval(m_clamp(x, 0., 1.)), 0., 1.),
+            CurveExtension::Mirror => {
+                if x < 0. {
+                    2. * self.lower.0 - self.parametric.eval(-x)
+                } else {
+                    self.parametric.eval(x)
+                }
+            }
+            CurveExtension::Linear => {
+                if x < 0. {
+                    mlaf(self.lower.0, self.lower.1, x)
+                } else if x > 1. {
+                    mlaf(self.upper.0, self.upper.1, x - 1.)
+                } else {
+                    self.parametric.eval(x)
+                }
+            }
+        }
+    }
+}
+
+pub(crate) struct ToneCurveSegmentedEvaluator {
+    curve: SegmentedCurve,
+}
+
+pub(crate) struct ToneCurveCustomEvaluator {
+    curve: CustomCurve,
 }
 
 pub(crate) struct ToneCurveEvaluatorPureGamma {
@@ -1540,16 +1875,40 @@ impl ToneCurveEvaluator for ToneCurveCicpEvaluator {
 }
 
 impl ToneCurveEvaluator for ToneCurveParametricEvaluator {
+    fn evaluate_tristimulus(&self, rgb: Rgb<f32>) -> Rgb<f32> {
+        Rgb::new(self.eval(rgb.r), self.eval(rgb.g), self.eval(rgb.b))
+    }
+
+    fn evaluate_value(&self, value: f32) -> f32 {
+        self.eval(value)
+    }
+}
+
+impl ToneCurveEvaluator for ToneCurveSegmentedEvaluator {
     fn evaluate_tristimulus(&self, rgb: Rgb<f32>) -> Rgb<f32> {
         Rgb::new(
-            self.parametric.eval(rgb.r),
-            self.parametric.eval(rgb.g),
-            self.parametric.eval(rgb.b),
+            self.curve.eval(rgb.r),
+            self.curve.eval(rgb.g),
+            self.curve.eval(rgb.b),
         )
     }
 
     fn evaluate_value(&self, value: f32) -> f32 {
-        self.parametric.eval(value)
+        self.curve.eval(value)
+    }
+}
+
+impl ToneCurveEvaluator for ToneCurveCustomEvaluator {
+    fn evaluate_tristimulus(&self, rgb: Rgb<f32>) -> Rgb<f32> {
+        Rgb::new(
+            self.curve.eval(rgb.r),
+            self.curve.eval(rgb.g),
+            self.curve.eval(rgb.b),
+        )
+    }
+
+    fn evaluate_value(&self, value: f32) -> f32 {
+        self.curve.eval(value)
     }
 }
 
@@ -1581,3 +1940,293 @@ pub trait ToneCurveEvaluator {
     fn evaluate_tristimulus(&self, rgb: Rgb<f32>) -> Rgb<f32>;
     fn evaluate_value(&self, value: f32) -> f32;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curv_identity_and_gamma_entries() {
+        use crate::{Layout, LutMultidimensionalType, LutStore, LutWarehouse, TransformOptions};
+
+        // `curv` with zero entries is identity, with one entry is u8Fixed8 gamma
+        let gamma = u8_fixed_8number_to_float(563);
+        let pairs = [
+            (
+                ToneReprCurve::Lut(vec![]),
+                ToneReprCurve::Parametric(vec![1.]),
+            ),
+            (
+                ToneReprCurve::Lut(vec![563]),
+                ToneReprCurve::Parametric(vec![gamma]),
+            ),
+        ];
+        let mut srgb = ColorProfile::new_srgb();
+        srgb.cicp = None;
+        let matrix_shaper = |curve: &ToneReprCurve| {
+            let mut profile = srgb.clone();
+            profile.red_trc = Some(curve.clone());
+            profile.green_trc = Some(curve.clone());
+            profile.blue_trc = Some(curve.clone());
+            profile
+        };
+        let identity_clut = (0..8u16)
+            .flat_map(|i| [(i >> 2) & 1, (i >> 1) & 1, i & 1].map(|x| x * 65535))
+            .collect::<Vec<_>>();
+        let lut_based = |curve: &ToneReprCurve| {
+            let mut profile = srgb.clone();
+            profile.lut_a_to_b_perceptual =
+                Some(LutWarehouse::Multidimensional(LutMultidimensionalType {
+                    num_input_channels: 3,
+                    num_output_channels: 3,
+                    grid_points: [2, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                    clut: Some(LutStore::Store16(identity_clut.clone().into())),
+                    a_curves: vec![curve.clone(); 3],
+                    b_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+                    m_curves: vec![ToneReprCurve::Lut(vec![]); 3],
+                    matrix: srgb.rgb_to_xyz_matrix().mul_scalar(32768. / 65535.),
+                    bias: Default::default(),
+                }));
+            profile
+        };
+        let src = (0..=64u16)
+            .flat_map(|v| [v * 1023, 65535 - v * 1023, v * 511])
+            .collect::<Vec<u16>>();
+        let run = |profile: &ColorProfile, src_to_dst: bool| {
+            let (source, dest) = if src_to_dst {
+                (profile, &srgb)
+            } else {
+                (&srgb, profile)
+            };
+            let mut dst = vec![0u16; src.len()];
+            source
+                .create_transform_16bit(Layout::Rgb, dest, Layout::Rgb, TransformOptions::default())
+                .unwrap()
+                .transform(&src, &mut dst)
+                .unwrap();
+            dst
+        };
+        for (curv, parametric) in pairs.iter() {
+            let cases = [
+                (
+                    run(&matrix_shaper(curv), true),
+                    run(&matrix_shaper(parametric), true),
+                ),
+                (
+                    run(&matrix_shaper(curv), false),
+                    run(&matrix_shaper(parametric), false),
+                ),
+                (
+                    run(&lut_based(curv), true),
+                    run(&lut_based(parametric), true),
+                ),
+            ];
+            for (a, b) in cases.iter() {
+                for (&a, &b) in a.iter().zip(b.iter()) {
+                    assert!(a.abs_diff(b) <= 160, "{curv:?}: {a} vs {b}");
+                }
+            }
+            // Float LUT pipeline samples curves into tables
+            let src_f32 = src.iter().map(|&v| v as f32 / 65535.).collect::<Vec<f32>>();
+            let run_f32 = |profile: &ColorProfile| {
+                let mut dst = vec![0f32; src_f32.len()];
+                profile
+                    .create_transform_f32(
+                        Layout::Rgb,
+                        &srgb,
+                        Layout::Rgb,
+                        TransformOptions::default(),
+                    )
+                    .unwrap()
+                    .transform(&src_f32, &mut dst)
+                    .unwrap();
+                dst
+            };
+            let a = run_f32(&lut_based(curv));
+            let b = run_f32(&lut_based(parametric));
+            for (&a, &b) in a.iter().zip(b.iter()) {
+                assert!((a - b).abs() < 3e-3, "{curv:?}: {a} vs {b}");
+            }
+        }
+
+        assert_eq!(
+            ToneReprCurve::Lut(vec![]).inverse().unwrap(),
+            ToneReprCurve::Lut(vec![])
+        );
+        let inverse = ToneReprCurve::Lut(vec![512]).inverse().unwrap();
+        assert_eq!(inverse, ToneReprCurve::Parametric(vec![0.5]));
+        assert!(ToneReprCurve::Lut(vec![]).is_linear());
+        assert!(ToneReprCurve::Lut(vec![256]).is_linear());
+        assert!(!ToneReprCurve::Lut(vec![10]).is_linear());
+    }
+
+    #[test]
+    fn test_parametric_curve_extension() {
+        let mut profile = ColorProfile::new_srgb();
+        profile.cicp = None;
+        let curve = ToneReprCurve::Parametric(vec![2.2]);
+        profile.red_trc = Some(curve.clone());
+        profile.green_trc = Some(curve.clone());
+        profile.blue_trc = Some(curve);
+        let eval = |extension: CurveExtension, x: f32| {
+            profile
+                .try_extended_linearizing_evaluator(extension)
+                .unwrap()
+                .evaluate_value(x)
+        };
+
+        assert_eq!(eval(CurveExtension::Clip, -0.5), 0.);
+        assert_eq!(eval(CurveExtension::Clip, 1.5), 1.);
+
+        let mirrored = eval(CurveExtension::Mirror, -0.5);
+        assert!((mirrored + 0.5f32.powf(2.2)).abs() < 1e-5, "{mirrored}");
+        let continued = eval(CurveExtension::Mirror, 1.5);
+        assert!((continued - 1.5f32.powf(2.2)).abs() < 1e-4, "{continued}");
+
+        // Slope of x^2.2 is 0 at 0 and 2.2 at 1
+        assert!(eval(CurveExtension::Linear, -0.5).abs() < 1e-3);
+        let extended = eval(CurveExtension::Linear, 1.5);
+        assert!((extended - 2.1).abs() < 1e-2, "{extended}");
+        let inside = eval(CurveExtension::Linear, 0.5);
+        assert!((inside - 0.5f32.powf(2.2)).abs() < 1e-5, "{inside}");
+
+        let gamma = profile
+            .try_extended_gamma_evaluator(CurveExtension::Mirror)
+            .unwrap();
+        assert!((gamma.evaluate_value(mirrored) + 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_custom_curve() {
+        let custom = ToneReprCurve::Custom(CustomCurve::new(
+            Arc::new(|x: f32| x.max(0.).powf(2.2)),
+            1024,
+        ));
+        let reference = ToneReprCurve::Parametric(vec![2.2]);
+        let linear = custom.build_linearize_table::<u8, 256, 8>().unwrap();
+        let expected = reference.build_linearize_table::<u8, 256, 8>().unwrap();
+        for (&a, &b) in linear.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-4, "{a} {b}");
+        }
+
+        let mut profile = ColorProfile::new_srgb();
+        profile.cicp = None;
+        profile.red_trc = Some(custom.clone());
+        profile.green_trc = Some(custom.clone());
+        profile.blue_trc = Some(custom);
+        let mut target = ColorProfile::new_srgb();
+        target.cicp = None;
+        target.red_trc = Some(reference.clone());
+        target.green_trc = Some(reference.clone());
+        target.blue_trc = Some(reference);
+        // Compared against the same curve declared as parametric, gamma 2.2 collapses
+        // darkest codes to 0 in 8-bit anyway
+        let convert = |source: &ColorProfile| {
+            let transform = source
+                .create_transform_8bit(
+                    crate::Layout::Rgb,
+                    &target,
+                    crate::Layout::Rgb,
+                    crate::TransformOptions::default(),
+                )
+                .unwrap();
+            let src = (0..=255u8).flat_map(|x| [x, x, x]).collect::<Vec<_>>();
+            let mut dst = vec![0u8; src.len()];
+            transform.transform(&src, &mut dst).unwrap();
+            dst
+        };
+        for (&a, &b) in convert(&profile).iter().zip(convert(&target).iter()) {
+            assert!((a as i32 - b as i32).abs() <= 1, "{a} {b}");
+        }
+
+        // Encoding bakes closure into a table
+        let decoded = ColorProfile::new_from_slice(&profile.encode().unwrap()).unwrap();
+        assert!(matches!(decoded.red_trc, Some(ToneReprCurve::Lut(ref lut)) if lut.len() == 1024));
+    }
+
+    #[test]
+    fn test_8bit_gamma_table_round_trip() {
+        let curves = [
+            vec![2.4, 1. / 1.055, 0.055 / 1.055, 1. / 12.92, 0.04045],
+            vec![2.2],
+            vec![2.6, 0.95, 0.05, 0.],
+        ];
+        for params in curves {
+            let curve = ToneReprCurve::Parametric(params.clone());
+            let linear = curve.build_linearize_table::<u8, 256, 8>().unwrap();
+            let sampled =
+                ToneReprCurve::build_parametric_gamma_table::<u8, 65536, 4096, 8>(&params).unwrap();
+            let refined = curve.build_gamma_table::<u8, 65536, 4096, 8>().unwrap();
+            let bin = |lin: f32| ((lin.max(0.).min(1.) * 4095. + 0.5) as usize).min(4095);
+            let round_trips = |table: &[u8; 65536]| {
+                linear
+                    .iter()
+                    .enumerate()
+                    .filter(|&(code, &lin)| table[bin(lin)] as usize == code)
+                    .count()
+            };
+            let unique_bins = (0..256)
+                .filter(|&code| {
+                    (0..256)
+                        .filter(|&other| bin(linear[other]) == bin(linear[code]))
+                        .count()
+                        == 1
+                })
+                .count();
+            assert!(round_trips(&refined) >= round_trips(&sampled));
+            assert!(round_trips(&refined) >= unique_bins, "{params:?}");
+            // Refinement must not move any bin by more than one code
+            for (a, b) in refined.iter().zip(sampled.iter()).take(4096) {
+                assert!(a.abs_diff(*b) <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_curve_derivative() {
+        use crate::{CurveSegment, SegmentFormula};
+        let srgb = [2.4, 1. / 1.055, 0.055 / 1.055, 1. / 12.92, 0.04045];
+        let parametric = ToneReprCurve::Parametric(srgb.to_vec());
+        let evaluator = ParametricCurve::new(&srgb).unwrap();
+        let lut = ToneReprCurve::Lut(
+            (0..256)
+                .map(|i| (evaluator.eval(i as f32 / 255.) * 65535. + 0.5) as u16)
+                .collect(),
+        );
+        let segmented = ToneReprCurve::Segmented(
+            SegmentedCurve::new(
+                vec![0.04045],
+                vec![
+                    CurveSegment::Formula(SegmentFormula::Gamma {
+                        gamma: 1.,
+                        a: 1. / 12.92,
+                        b: 0.,
+                        c: 0.,
+                    }),
+                    CurveSegment::Formula(SegmentFormula::Gamma {
+                        gamma: 2.4,
+                        a: 1. / 1.055,
+                        b: 0.055 / 1.055,
+                        c: 0.,
+                    }),
+                ],
+            )
+            .unwrap(),
+        );
+        for i in 1..20 {
+            let x = i as f32 / 20.;
+            let expected = finite_difference(|v| evaluator.eval(v), x);
+            let analytic = parametric.derivative(x);
+            assert!(
+                (analytic - expected).abs() < 1e-2 * expected,
+                "{x}: {analytic}"
+            );
+            assert!((lut.derivative(x) - expected).abs() < 0.1 * expected);
+            assert!((segmented.derivative(x) - expected).abs() < 1e-2 * expected);
+        }
+        assert!((parametric.derivative(0.01) - 1. / 12.92).abs() < 1e-6);
+        let gamma = curve_from_gamma(2.2);
+        assert!((gamma.derivative(0.5) - 2.2 * 0.5f32.powf(1.2)).abs() < 1e-2);
+        assert_eq!(ToneReprCurve::Lut(vec![]).derivative(0.3), 1.);
+    }
+}
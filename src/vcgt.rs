@@ -0,0 +1,188 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/// Video card gamma formula for one channel, `min + (max - min) * x^gamma`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoCardGammaFormula {
+    pub gamma: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl VideoCardGammaFormula {
+    #[inline]
+    fn evaluate(&self, x: f32) -> f32 {
+        self.min + (self.max - self.min) * x.powf(self.gamma)
+    }
+}
+
+/// Video card gamma `vcgt` calibration tag, stores curves loaded into display hardware LUT.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VideoCardGamma {
+    /// Sampled curves for red, green and blue, each normalized to u16 range
+    Table {
+        red: Vec<u16>,
+        green: Vec<u16>,
+        blue: Vec<u16>,
+    },
+    Formula {
+        red: VideoCardGammaFormula,
+        green: VideoCardGammaFormula,
+        blue: VideoCardGammaFormula,
+    },
+}
+
+/// Ramps ready to be loaded into display, e.g. with `XRRSetCrtcGamma`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VideoCardGammaRamps {
+    pub red: Vec<u16>,
+    pub green: Vec<u16>,
+    pub blue: Vec<u16>,
+}
+
+/// Samples table at `x` in 0..1 with linear interpolation
+fn interpolate_table(table: &[u16], x: f32) -> f32 {
+    match table.len() {
+        0 => x,
+        1 => table[0] as f32 / 65535.,
+        len => {
+            let pos = x * (len - 1) as f32;
+            let index = (pos as usize).min(len - 2);
+            let t = pos - index as f32;
+            let a = table[index] as f32;
+            let b = table[index + 1] as f32;
+            (a + (b - a) * t) / 65535.
+        }
+    }
+}
+
+impl VideoCardGamma {
+    /// Generates ramps of `size` entries, tables are resampled with linear interpolation.
+    ///
+    /// Common sizes are 256 for `SetDeviceGammaRamp` and what `XRRGetCrtcGammaSize` reports for X11.
+    pub fn ramps(&self, size: usize) -> VideoCardGammaRamps {
+        let divisor = size.saturating_sub(1).max(1) as f32;
+        let ramp = |f: &dyn Fn(f32) -> f32| {
+            (0..size)
+                .map(|i| {
+                    let v = f(i as f32 / divisor);
+                    (v.max(0.).min(1.) * 65535. + 0.5) as u16
+                })
+                .collect::<Vec<u16>>()
+        };
+        match self {
+            VideoCardGamma::Table { red, green, blue } => VideoCardGammaRamps {
+                red: ramp(&|x| interpolate_table(red, x)),
+                green: ramp(&|x| interpolate_table(green, x)),
+                blue: ramp(&|x| interpolate_table(blue, x)),
+            },
+            VideoCardGamma::Formula { red, green, blue } => VideoCardGammaRamps {
+                red: ramp(&|x| red.evaluate(x)),
+                green: ramp(&|x| green.evaluate(x)),
+                blue: ramp(&|x| blue.evaluate(x)),
+            },
+        }
+    }
+
+    /// Generates ramp in layout expected by Windows `SetDeviceGammaRamp`
+    pub fn device_gamma_ramp(&self) -> [[u16; 256]; 3] {
+        let ramps = self.ramps(256);
+        let mut dst = [[0u16; 256]; 3];
+        dst[0].copy_from_slice(&ramps.red);
+        dst[1].copy_from_slice(&ramps.green);
+        dst[2].copy_from_slice(&ramps.blue);
+        dst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColorProfile;
+
+    #[test]
+    fn test_vcgt_read_table() {
+        let mut tag = b"vcgt".to_vec();
+        tag.extend_from_slice(&[0; 4]);
+        tag.extend_from_slice(&0u32.to_be_bytes());
+        tag.extend_from_slice(&3u16.to_be_bytes());
+        tag.extend_from_slice(&2u16.to_be_bytes());
+        tag.extend_from_slice(&2u16.to_be_bytes());
+        for v in [0u16, 65535, 100, 60000, 200, 50000] {
+            tag.extend_from_slice(&v.to_be_bytes());
+        }
+        let vcgt = ColorProfile::read_vcgt_tag(&tag, 0, tag.len())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            vcgt,
+            VideoCardGamma::Table {
+                red: vec![0, 65535],
+                green: vec![100, 60000],
+                blue: vec![200, 50000],
+            }
+        );
+        assert!(ColorProfile::read_vcgt_tag(&tag, 0, tag.len() - 1).is_err());
+    }
+
+    #[test]
+    fn test_vcgt_ramps() {
+        let table = VideoCardGamma::Table {
+            red: vec![0, 65535],
+            green: vec![0, 32768, 65535],
+            blue: vec![65535, 0],
+        };
+        let ramps = table.ramps(1024);
+        assert_eq!(ramps.red.len(), 1024);
+        assert_eq!(ramps.red[0], 0);
+        assert_eq!(ramps.red[1023], 65535);
+        assert!((ramps.red[512] as i32 - 32800).abs() < 64);
+        assert_eq!(ramps.blue[0], 65535);
+        assert_eq!(ramps.blue[1023], 0);
+
+        let linear = VideoCardGammaFormula {
+            gamma: 1.,
+            min: 0.,
+            max: 1.,
+        };
+        let formula = VideoCardGamma::Formula {
+            red: linear,
+            green: VideoCardGammaFormula {
+                gamma: 2.2,
+                ..linear
+            },
+            blue: VideoCardGammaFormula { max: 0.5, ..linear },
+        };
+        let ramp = formula.device_gamma_ramp();
+        assert_eq!(ramp[0][255], 65535);
+        assert_eq!(ramp[0][1], 257);
+        assert!(ramp[1][128] < ramp[0][128]);
+        assert_eq!(ramp[2][255], 32768);
+    }
+}
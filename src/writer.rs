@@ -26,15 +26,27 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+use crate::custom_tag::custom_tag_handler;
 use crate::profile::{LutDataType, ProfileHeader};
 use crate::tag::{TAG_SIZE, Tag, TagTypeDefinition};
 use crate::trc::ToneReprCurve;
 use crate::{
-    CicpProfile, CmsError, ColorDateTime, ColorProfile, DataColorSpace, LocalizableString,
-    LutMultidimensionalType, LutStore, LutType, LutWarehouse, Matrix3d, ProfileClass,
-    ProfileSignature, ProfileText, ProfileVersion, Vector3d, ViewingConditions, Xyz, Xyzd,
+    CicpProfile, CmsError, ColorDateTime, ColorPrimaries, ColorProfile, CurveSegment,
+    DataColorSpace, LocalizableString, LutMultidimensionalType, LutStore, LutType, LutWarehouse,
+    MalformedSize, Matrix3d, Measurement, MultiProcessType, NamedColorList, ProcessElement,
+    ProfileClass, ProfileSignature, ProfileText, ProfileVersion, SegmentFormula, SegmentedCurve,
+    TransferCharacteristics, Vector3d, VideoCardGamma, ViewingConditions, Xyz, Xyzd,
 };
 
+/// Options for [ColorProfile::encode_with_options]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EncodingOptions {
+    /// Creation time written into header, current time when `None`.
+    ///
+    /// Profiles from [ColorProfile::sanitized] default to zeroed time instead.
+    pub creation_date: Option<ColorDateTime>,
+}
+
 pub(crate) trait FloatToFixedS15Fixed16 {
     fn to_s15_fixed16(self) -> i32;
 }
@@ -182,6 +194,24 @@ fn write_string_value(into: &mut Vec<u8>, text: &ProfileText) -> usize {
     }
 }
 
+#[inline]
+fn write_signature_value(into: &mut Vec<u8>, signature: u32) -> usize {
+    let tag_definition: u32 = TagTypeDefinition::Signature.into();
+    write_u32_be(into, tag_definition);
+    write_u32_be(into, 0);
+    write_u32_be(into, signature);
+    12
+}
+
+#[inline]
+fn write_date_time_value(into: &mut Vec<u8>, date: &ColorDateTime) -> usize {
+    let tag_definition: u32 = TagTypeDefinition::DateTime.into();
+    write_u32_be(into, tag_definition);
+    write_u32_be(into, 0);
+    date.encode(into);
+    20
+}
+
 #[inline]
 fn write_xyz_tag_value(into: &mut Vec<u8>, xyz: Xyzd) {
     let tag_definition: u32 = TagTypeDefinition::Xyz.into();
@@ -195,6 +225,15 @@ fn write_xyz_tag_value(into: &mut Vec<u8>, xyz: Xyzd) {
     write_i32_be(into, z_fixed);
 }
 
+/// Tags start on 4 byte boundary, gap after previous tag is filled with zeros
+#[inline]
+fn pad_entry(entries: &mut Vec<u8>, base_offset: &mut usize) {
+    while *base_offset % 4 != 0 {
+        entries.push(0);
+        *base_offset += 1;
+    }
+}
+
 #[inline]
 fn write_tag_entry(into: &mut Vec<u8>, tag: Tag, tag_entry: usize, tag_size: usize) {
     let tag_value: u32 = tag.into();
@@ -227,8 +266,93 @@ fn write_viewing_conditions_value(
     36
 }
 
+#[inline]
+fn write_measurement_value(into: &mut Vec<u8>, measurement: &Measurement) -> usize {
+    let tag_definition: u32 = TagTypeDefinition::Measurement.into();
+    write_u32_be(into, tag_definition);
+    write_u32_be(into, 0);
+    write_u32_be(into, measurement.observer.into());
+    write_xyz(into, measurement.backing);
+    write_u32_be(into, measurement.geometry.into());
+    // u16Fixed16Number
+    let flare = (measurement.flare.clamp(0., 65535.) as f64 * 65536. + 0.5).floor();
+    write_u32_be(into, flare.min(u32::MAX as f64) as u32);
+    write_u32_be(into, measurement.illuminant.into());
+    36
+}
+
+fn write_vcgt_value(into: &mut Vec<u8>, vcgt: &VideoCardGamma) -> Result<usize, CmsError> {
+    let tag_definition: u32 = TagTypeDefinition::VideoCardGamma.into();
+    write_u32_be(into, tag_definition);
+    write_u32_be(into, 0);
+    match vcgt {
+        VideoCardGamma::Table { red, green, blue } => {
+            if red.len() != green.len() || red.len() != blue.len() || red.len() > u16::MAX as usize
+            {
+                return Err(CmsError::MalformedCurveLutTable(MalformedSize {
+                    size: green.len().max(blue.len()),
+                    expected: red.len(),
+                }));
+            }
+            write_u32_be(into, 0);
+            write_u16_be(into, 3);
+            write_u16_be(into, red.len() as u16);
+            write_u16_be(into, 2);
+            for &v in red.iter().chain(green.iter()).chain(blue.iter()) {
+                write_u16_be(into, v);
+            }
+            Ok(18 + red.len() * 3 * 2)
+        }
+        VideoCardGamma::Formula { red, green, blue } => {
+            write_u32_be(into, 1);
+            for formula in [red, green, blue] {
+                write_i32_be(into, formula.gamma.to_s15_fixed16());
+                write_i32_be(into, formula.min.to_s15_fixed16());
+                write_i32_be(into, formula.max.to_s15_fixed16());
+            }
+            Ok(12 + 36)
+        }
+    }
+}
+
+/// Writes 32 bytes null terminated 7-bit ASCII name of `ncl2`
+fn write_color_name(into: &mut Vec<u8>, name: &str) -> Result<(), CmsError> {
+    if name.len() > 31 || !name.is_ascii() {
+        return Err(CmsError::InvalidProfile);
+    }
+    into.extend_from_slice(name.as_bytes());
+    into.resize(into.len() + 32 - name.len(), 0);
+    Ok(())
+}
+
+fn write_named_color_value(
+    into: &mut Vec<u8>,
+    named_colors: &NamedColorList,
+) -> Result<usize, CmsError> {
+    let start = into.len();
+    let ncl2: u32 = TagTypeDefinition::NamedColor.into();
+    write_u32_be(into, ncl2);
+    write_u32_be(into, 0);
+    write_u32_be(into, named_colors.vendor_flags);
+    write_u32_be(into, named_colors.colors.len() as u32);
+    write_u32_be(into, named_colors.device_channels as u32);
+    write_color_name(into, &named_colors.prefix)?;
+    write_color_name(into, &named_colors.suffix)?;
+    for color in named_colors.colors.iter() {
+        if color.device.len() != named_colors.device_channels {
+            return Err(CmsError::InvalidProfile);
+        }
+        write_color_name(into, &color.name)?;
+        for &v in color.pcs.iter().chain(color.device.iter()) {
+            write_u16_be(into, v);
+        }
+    }
+    Ok(into.len() - start)
+}
+
 fn write_trc_entry(into: &mut Vec<u8>, trc: &ToneReprCurve) -> Result<usize, CmsError> {
     match trc {
+        ToneReprCurve::Custom(curve) => write_trc_entry(into, &ToneReprCurve::Lut(curve.to_lut())),
         ToneReprCurve::Lut(lut) => {
             let curv: u32 = TagTypeDefinition::LutToneCurve.into();
             write_u32_be(into, curv);
@@ -266,7 +390,49 @@ fn write_trc_entry(into: &mut Vec<u8>, trc: &ToneReprCurve) -> Result<usize, Cms
             }
             Ok(12 + 4 * parametric_curve.len())
         }
+        ToneReprCurve::Segmented(curve) => Ok(write_segmented_curve(into, curve)),
+    }
+}
+
+fn write_segmented_curve(into: &mut Vec<u8>, curve: &SegmentedCurve) -> usize {
+    let curf: u32 = TagTypeDefinition::SegmentedCurve.into();
+    write_u32_be(into, curf);
+    write_u32_be(into, 0);
+    write_u16_be(into, curve.segments.len() as u16);
+    write_u16_be(into, 0);
+    for breakpoint in curve.breakpoints.iter() {
+        write_u32_be(into, breakpoint.to_bits());
+    }
+    let mut written = 12 + curve.breakpoints.len() * 4;
+    for segment in curve.segments.iter() {
+        match segment {
+            CurveSegment::Formula(formula) => {
+                let params = match *formula {
+                    SegmentFormula::Gamma { gamma, a, b, c } => vec![gamma, a, b, c],
+                    SegmentFormula::Log { gamma, a, b, c, d } => vec![gamma, a, b, c, d],
+                    SegmentFormula::Exp { a, b, c, d, e } => vec![a, b, c, d, e],
+                };
+                write_u32_be(into, u32::from_be_bytes(*b"parf"));
+                write_u32_be(into, 0);
+                write_u16_be(into, formula.function_type());
+                write_u16_be(into, 0);
+                for param in params.iter() {
+                    write_u32_be(into, param.to_bits());
+                }
+                written += 12 + params.len() * 4;
+            }
+            CurveSegment::Sampled(samples) => {
+                write_u32_be(into, u32::from_be_bytes(*b"samf"));
+                write_u32_be(into, 0);
+                write_u32_be(into, samples.len() as u32);
+                for sample in samples.iter() {
+                    write_u32_be(into, sample.to_bits());
+                }
+                written += 12 + samples.len() * 4;
+            }
+        }
     }
+    written
 }
 
 #[inline]
@@ -326,8 +492,11 @@ fn write_lut_entry(into: &mut Vec<u8>, lut: &LutDataType) -> Result<usize, CmsEr
     into.push(lut.num_clut_grid_points);
     into.push(0);
     write_matrix3d(into, lut.matrix);
-    write_u16_be(into, lut.num_input_table_entries);
-    write_u16_be(into, lut.num_output_table_entries);
+    // lut8Type always has 256 entries and doesn't store their count
+    if matches!(lut.input_table, LutStore::Store16(_)) {
+        write_u16_be(into, lut.num_input_table_entries);
+        write_u16_be(into, lut.num_output_table_entries);
+    }
     match &lut.input_table {
         LutStore::Store8(input_table) => {
             for &item in input_table.iter() {
@@ -508,10 +677,74 @@ fn write_mab_entry(
     Ok(end - start)
 }
 
+fn write_process_element(into: &mut Vec<u8>, element: &ProcessElement) -> usize {
+    let start = into.len();
+    let signature = match element {
+        ProcessElement::CurveSet(_) => b"cvst",
+        ProcessElement::Matrix { .. } => b"matf",
+        ProcessElement::Clut { .. } => b"clut",
+    };
+    write_u32_be(into, u32::from_be_bytes(*signature));
+    write_u32_be(into, 0);
+    write_u16_be(into, element.num_input_channels() as u16);
+    write_u16_be(into, element.num_output_channels() as u16);
+    match element {
+        ProcessElement::CurveSet(curves) => {
+            let table_start = into.len();
+            into.resize(table_start + curves.len() * 8, 0);
+            for (i, curve) in curves.iter().enumerate() {
+                let offset = into.len() - start;
+                let size = write_segmented_curve(into, curve);
+                let position = table_start + i * 8;
+                into[position..position + 4].copy_from_slice(&(offset as u32).to_be_bytes());
+                into[position + 4..position + 8].copy_from_slice(&(size as u32).to_be_bytes());
+            }
+        }
+        ProcessElement::Matrix {
+            matrix, offsets, ..
+        } => {
+            for v in matrix.iter().chain(offsets.iter()) {
+                write_u32_be(into, v.to_bits());
+            }
+        }
+        ProcessElement::Clut {
+            grid_points, table, ..
+        } => {
+            into.extend_from_slice(grid_points);
+            for v in table.iter() {
+                write_u32_be(into, v.to_bits());
+            }
+        }
+    }
+    into.len() - start
+}
+
+fn write_mpe_entry(into: &mut Vec<u8>, mpe: &MultiProcessType) -> Result<usize, CmsError> {
+    mpe.validate()?;
+    let start = into.len();
+    let mpet: u32 = TagTypeDefinition::MultiProcessElement.into();
+    write_u32_be(into, mpet);
+    write_u32_be(into, 0);
+    write_u16_be(into, mpe.num_input_channels as u16);
+    write_u16_be(into, mpe.num_output_channels as u16);
+    write_u32_be(into, mpe.elements.len() as u32);
+    let table_start = into.len();
+    into.resize(table_start + mpe.elements.len() * 8, 0);
+    for (i, element) in mpe.elements.iter().enumerate() {
+        let offset = into.len() - start;
+        let size = write_process_element(into, element);
+        let position = table_start + i * 8;
+        into[position..position + 4].copy_from_slice(&(offset as u32).to_be_bytes());
+        into[position + 4..position + 8].copy_from_slice(&(size as u32).to_be_bytes());
+    }
+    Ok(into.len() - start)
+}
+
 fn write_lut(into: &mut Vec<u8>, lut: &LutWarehouse, is_a_to_b: bool) -> Result<usize, CmsError> {
     match lut {
         LutWarehouse::Lut(lut) => Ok(write_lut_entry(into, lut)?),
         LutWarehouse::Multidimensional(mab) => write_mab_entry(into, mab, is_a_to_b),
+        LutWarehouse::MultiProcess(mpe) => write_mpe_entry(into, mpe),
     }
 }
 
@@ -573,36 +806,23 @@ impl ColorProfile {
         if self.gray_trc.is_some() {
             tags_count += 1;
         }
-        if self.cicp.is_some() {
+        if self.cicp.is_some() && self.can_write_cicp() {
             tags_count += 1;
         }
         if self.media_white_point.is_some() {
             tags_count += 1;
         }
-        if self.gamut.is_some() {
-            tags_count += 1;
-        }
-        if self.chromatic_adaptation.is_some() {
-            tags_count += 1;
-        }
-        if self.lut_a_to_b_perceptual.is_some() {
-            tags_count += 1;
-        }
-        if self.lut_a_to_b_colorimetric.is_some() {
-            tags_count += 1;
-        }
-        if self.lut_a_to_b_saturation.is_some() {
-            tags_count += 1;
-        }
-        if self.lut_b_to_a_perceptual.is_some() {
+        if self.black_point.is_some() {
             tags_count += 1;
         }
-        if self.lut_b_to_a_colorimetric.is_some() {
+        if self.gamut.is_some() {
             tags_count += 1;
         }
-        if self.lut_b_to_a_saturation.is_some() {
+        if self.chromatic_adaptation.is_some() {
             tags_count += 1;
         }
+        tags_count += self.lut_tags().count();
+        tags_count += self.spectral_tags().count();
         if self.luminance.is_some() {
             tags_count += 1;
         }
@@ -619,11 +839,31 @@ impl ColorProfile {
         if self.viewing_conditions.is_some() {
             tags_count += 1;
         }
+        if self.measurement.is_some() {
+            tags_count += 1;
+        }
         if let Some(vd) = &self.viewing_conditions_description {
             if vd.has_values() {
                 tags_count += 1;
             }
         }
+        if self.technology.is_some() {
+            tags_count += 1;
+        }
+        if self.calibration_date.is_some() {
+            tags_count += 1;
+        }
+        if self.video_card_gamma.is_some() {
+            tags_count += 1;
+        }
+        if self.named_colors.is_some() {
+            tags_count += 1;
+        }
+        if let Some(vd) = &self.char_target {
+            if vd.has_values() {
+                tags_count += 1;
+            }
+        }
         if let Some(vd) = &self.device_model {
             if vd.has_values() {
                 tags_count += 1;
@@ -637,36 +877,217 @@ impl ColorProfile {
         tags_count
     }
 
+    /// `A2Bx` and `B2Ax` LUTs with their signatures, `mpet` transforms go to `D2Bx`
+    /// and `B2Dx` unless raw tag with the same signature is kept, which then holds them.
+    /// Tables overridden by `mpet` on decoding are written back under `A2Bx` and `B2Ax`.
+    fn lut_tags(&self) -> impl Iterator<Item = (Tag, &LutWarehouse, bool)> {
+        [
+            (
+                Tag::DeviceToPcsLutPerceptual,
+                Tag::DeviceToSpectralPcsPerceptual,
+                &self.lut_a_to_b_perceptual,
+                &self.lut_d_to_b_perceptual,
+                true,
+            ),
+            (
+                Tag::DeviceToPcsLutColorimetric,
+                Tag::DeviceToSpectralPcsColorimetric,
+                &self.lut_a_to_b_colorimetric,
+                &self.lut_d_to_b_colorimetric,
+                true,
+            ),
+            (
+                Tag::DeviceToPcsLutSaturation,
+                Tag::DeviceToSpectralPcsSaturation,
+                &self.lut_a_to_b_saturation,
+                &self.lut_d_to_b_saturation,
+                true,
+            ),
+            (
+                Tag::PcsToDeviceLutPerceptual,
+                Tag::SpectralPcsToDevicePerceptual,
+                &self.lut_b_to_a_perceptual,
+                &self.lut_b_to_d_perceptual,
+                false,
+            ),
+            (
+                Tag::PcsToDeviceLutColorimetric,
+                Tag::SpectralPcsToDeviceColorimetric,
+                &self.lut_b_to_a_colorimetric,
+                &self.lut_b_to_d_colorimetric,
+                false,
+            ),
+            (
+                Tag::PcsToDeviceLutSaturation,
+                Tag::SpectralPcsToDeviceSaturation,
+                &self.lut_b_to_a_saturation,
+                &self.lut_b_to_d_saturation,
+                false,
+            ),
+        ]
+        .into_iter()
+        .zip(self.lut_fallbacks.iter())
+        .flat_map(
+            |((tag, mpe_tag, lut, raw, is_a_to_b), fallback)| match lut {
+                Some(lut @ LutWarehouse::MultiProcess(_)) => [
+                    raw.is_none().then_some((mpe_tag, lut, is_a_to_b)),
+                    fallback.as_ref().map(|fallback| (tag, fallback, is_a_to_b)),
+                ],
+                lut => [lut.as_ref().map(|lut| (tag, lut, is_a_to_b)), None],
+            },
+        )
+        .flatten()
+    }
+
+    /// Raw spectral `D2Bx` and `B2Dx` tags, written back as they were read
+    fn spectral_tags(&self) -> impl Iterator<Item = (Tag, &[u8])> {
+        [
+            (
+                Tag::DeviceToSpectralPcsPerceptual,
+                &self.lut_d_to_b_perceptual,
+            ),
+            (
+                Tag::DeviceToSpectralPcsColorimetric,
+                &self.lut_d_to_b_colorimetric,
+            ),
+            (
+                Tag::DeviceToSpectralPcsSaturation,
+                &self.lut_d_to_b_saturation,
+            ),
+            (
+                Tag::SpectralPcsToDevicePerceptual,
+                &self.lut_b_to_d_perceptual,
+            ),
+            (
+                Tag::SpectralPcsToDeviceColorimetric,
+                &self.lut_b_to_d_colorimetric,
+            ),
+            (
+                Tag::SpectralPcsToDeviceSaturation,
+                &self.lut_b_to_d_saturation,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(tag, data)| data.as_deref().map(|data| (tag, data)))
+    }
+
+    /// Custom tags having registered handler, tags without one are skipped
+    fn encode_custom_tags(&self) -> Result<Vec<(u32, Vec<u8>)>, CmsError> {
+        let mut encoded = Vec::new();
+        for tag in self.custom_tags.iter() {
+            if let Some(handler) = custom_tag_handler(tag.signature) {
+                encoded.push((tag.signature, handler.encode(tag.value.as_ref())?));
+            }
+        }
+        Ok(encoded)
+    }
+
+    fn can_write_cicp(&self) -> bool {
+        (self.profile_class == ProfileClass::InputDevice
+            || self.profile_class == ProfileClass::DisplayDevice)
+            && (self.color_space == DataColorSpace::Rgb
+                || self.color_space == DataColorSpace::YCbr
+                || self.color_space == DataColorSpace::Xyz)
+    }
+
+    /// Checks that CICP describes the same colorimetry as colorants and TRC.
+    ///
+    /// TRC of PQ and HLG profiles often is tone mapped approximation
+    /// for SDR applications, so only SDR transfer characteristics are compared.
+    fn validate_cicp(&self, cicp: &CicpProfile) -> Result<(), CmsError> {
+        if self.color_space != DataColorSpace::Rgb {
+            return Ok(());
+        }
+        let has_colorants = self.red_colorant != Xyzd::default()
+            && self.green_colorant != Xyzd::default()
+            && self.blue_colorant != Xyzd::default();
+        if has_colorants && cicp.color_primaries.has_chromaticity() {
+            let primaries: ColorPrimaries = cicp.color_primaries.try_into()?;
+            let white_point = cicp.color_primaries.white_point()?;
+            let mut reference = ColorProfile::default();
+            reference.update_rgb_colorimetry(white_point.to_xyyb(), primaries);
+            const COLORANT_TOLERANCE: f64 = 2e-3;
+            let same = |a: Xyzd, b: Xyzd| {
+                (a.x - b.x).abs() < COLORANT_TOLERANCE
+                    && (a.y - b.y).abs() < COLORANT_TOLERANCE
+                    && (a.z - b.z).abs() < COLORANT_TOLERANCE
+            };
+            if !same(self.red_colorant, reference.red_colorant)
+                || !same(self.green_colorant, reference.green_colorant)
+                || !same(self.blue_colorant, reference.blue_colorant)
+            {
+                return Err(CmsError::InvalidCicp);
+            }
+        }
+        let tc = cicp.transfer_characteristics;
+        if tc.has_transfer_curve()
+            && tc != TransferCharacteristics::Smpte2084
+            && tc != TransferCharacteristics::Hlg
+        {
+            let expected = ToneReprCurve::try_from(tc)?.make_linear_evaluator()?;
+            for trc in [&self.red_trc, &self.green_trc, &self.blue_trc]
+                .into_iter()
+                .flatten()
+            {
+                let evaluator = trc.make_linear_evaluator()?;
+                const TRC_TOLERANCE: f32 = 5e-3;
+                let mismatch = (0..=64).any(|i| {
+                    let x = i as f32 / 64.;
+                    (evaluator.evaluate_value(x) - expected.evaluate_value(x)).abs() > TRC_TOLERANCE
+                });
+                if mismatch {
+                    return Err(CmsError::InvalidCicp);
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Encodes profile
     pub fn encode(&self) -> Result<Vec<u8>, CmsError> {
+        self.encode_with_options(EncodingOptions::default())
+    }
+
+    /// Encodes profile with options.
+    ///
+    /// Output depends only on the profile and options: tags are written in fixed order and
+    /// padding between them is zeroed, so fixing [EncodingOptions::creation_date]
+    /// gives byte-identical profiles across runs and platforms.
+    pub fn encode_with_options(&self, options: EncodingOptions) -> Result<Vec<u8>, CmsError> {
         let mut entries = Vec::new();
-        let tags_count = self.writable_tags_count();
+        let custom_tags = self.encode_custom_tags()?;
+        let tags_count = self.writable_tags_count() + custom_tags.len();
         let mut tags = Vec::with_capacity(TAG_SIZE * tags_count);
         let mut base_offset = size_of::<ProfileHeader>() + TAG_SIZE * tags_count;
         if self.red_colorant != Xyzd::default() {
             write_tag_entry(&mut tags, Tag::RedXyz, base_offset, 20);
             write_xyz_tag_value(&mut entries, self.red_colorant);
             base_offset += 20;
+            pad_entry(&mut entries, &mut base_offset);
         }
         if self.green_colorant != Xyzd::default() {
             write_tag_entry(&mut tags, Tag::GreenXyz, base_offset, 20);
             write_xyz_tag_value(&mut entries, self.green_colorant);
             base_offset += 20;
+            pad_entry(&mut entries, &mut base_offset);
         }
         if self.blue_colorant != Xyzd::default() {
             write_tag_entry(&mut tags, Tag::BlueXyz, base_offset, 20);
             write_xyz_tag_value(&mut entries, self.blue_colorant);
             base_offset += 20;
+            pad_entry(&mut entries, &mut base_offset);
         }
         if let Some(chad) = self.chromatic_adaptation {
             write_tag_entry(&mut tags, Tag::ChromaticAdaptation, base_offset, 8 + 9 * 4);
             write_chad(&mut entries, chad);
             base_offset += 8 + 9 * 4;
+            pad_entry(&mut entries, &mut base_offset);
         }
         if let Some(trc) = &self.red_trc {
             let entry_size = write_trc_entry(&mut entries, trc)?;
             write_tag_entry(&mut tags, Tag::RedToneReproduction, base_offset, entry_size);
             base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
         }
         if let Some(trc) = &self.green_trc {
             let entry_size = write_trc_entry(&mut entries, trc)?;
@@ -677,6 +1098,7 @@ impl ColorProfile {
                 entry_size,
             );
             base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
         }
         if let Some(trc) = &self.blue_trc {
             let entry_size = write_trc_entry(&mut entries, trc)?;
@@ -687,6 +1109,7 @@ impl ColorProfile {
                 entry_size,
             );
             base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
         }
         if let Some(trc) = &self.gray_trc {
             let entry_size = write_trc_entry(&mut entries, trc)?;
@@ -697,109 +1120,65 @@ impl ColorProfile {
                 entry_size,
             );
             base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
         }
 
         if let Some(media_wp) = self.media_white_point {
             write_tag_entry(&mut tags, Tag::MediaWhitePoint, base_offset, 20);
             write_xyz_tag_value(&mut entries, media_wp);
             base_offset += 20;
+            pad_entry(&mut entries, &mut base_offset);
+        }
+
+        if let Some(black_point) = self.black_point {
+            write_tag_entry(&mut tags, Tag::BlackPoint, base_offset, 20);
+            write_xyz_tag_value(&mut entries, black_point);
+            base_offset += 20;
+            pad_entry(&mut entries, &mut base_offset);
         }
 
-        let has_cicp = self.cicp.is_some();
+        let has_cicp = self.cicp.is_some() && self.can_write_cicp();
 
         // This tag may be present when the data colour space in the profile header is RGB, YCbCr, or XYZ, and the
         // profile class in the profile header is Input or Display. The tag shall not be present for other data colour spaces
         // or profile classes indicated in the profile header.
 
         if let Some(cicp) = &self.cicp {
-            if (self.profile_class == ProfileClass::InputDevice
-                || self.profile_class == ProfileClass::DisplayDevice)
-                && (self.color_space == DataColorSpace::Rgb
-                    || self.color_space == DataColorSpace::YCbr
-                    || self.color_space == DataColorSpace::Xyz)
-            {
+            if has_cicp {
+                self.validate_cicp(cicp)?;
                 write_tag_entry(&mut tags, Tag::CodeIndependentPoints, base_offset, 12);
                 write_cicp_entry(&mut entries, cicp);
                 base_offset += 12;
+                pad_entry(&mut entries, &mut base_offset);
             }
         }
 
-        if let Some(lut) = &self.lut_a_to_b_perceptual {
-            let entry_size = write_lut(&mut entries, lut, true)?;
-            write_tag_entry(
-                &mut tags,
-                Tag::DeviceToPcsLutPerceptual,
-                base_offset,
-                entry_size,
-            );
-            base_offset += entry_size;
-        }
-
-        if let Some(lut) = &self.lut_a_to_b_colorimetric {
-            let entry_size = write_lut(&mut entries, lut, true)?;
-            write_tag_entry(
-                &mut tags,
-                Tag::DeviceToPcsLutColorimetric,
-                base_offset,
-                entry_size,
-            );
-            base_offset += entry_size;
-        }
-
-        if let Some(lut) = &self.lut_a_to_b_saturation {
-            let entry_size = write_lut(&mut entries, lut, true)?;
-            write_tag_entry(
-                &mut tags,
-                Tag::DeviceToPcsLutSaturation,
-                base_offset,
-                entry_size,
-            );
-            base_offset += entry_size;
-        }
-
-        if let Some(lut) = &self.lut_b_to_a_perceptual {
-            let entry_size = write_lut(&mut entries, lut, false)?;
-            write_tag_entry(
-                &mut tags,
-                Tag::PcsToDeviceLutPerceptual,
-                base_offset,
-                entry_size,
-            );
-            base_offset += entry_size;
-        }
-
-        if let Some(lut) = &self.lut_b_to_a_colorimetric {
-            let entry_size = write_lut(&mut entries, lut, false)?;
-            write_tag_entry(
-                &mut tags,
-                Tag::PcsToDeviceLutColorimetric,
-                base_offset,
-                entry_size,
-            );
+        for (tag, lut, is_a_to_b) in self.lut_tags() {
+            let entry_size = write_lut(&mut entries, lut, is_a_to_b)?;
+            write_tag_entry(&mut tags, tag, base_offset, entry_size);
             base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
         }
 
-        if let Some(lut) = &self.lut_b_to_a_saturation {
-            let entry_size = write_lut(&mut entries, lut, false)?;
-            write_tag_entry(
-                &mut tags,
-                Tag::PcsToDeviceLutSaturation,
-                base_offset,
-                entry_size,
-            );
-            base_offset += entry_size;
+        for (tag, data) in self.spectral_tags() {
+            write_tag_entry(&mut tags, tag, base_offset, data.len());
+            entries.extend_from_slice(data);
+            base_offset += data.len();
+            pad_entry(&mut entries, &mut base_offset);
         }
 
         if let Some(lut) = &self.gamut {
             let entry_size = write_lut(&mut entries, lut, false)?;
             write_tag_entry(&mut tags, Tag::Gamut, base_offset, entry_size);
             base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
         }
 
         if let Some(luminance) = self.luminance {
             write_tag_entry(&mut tags, Tag::Luminance, base_offset, 20);
             write_xyz_tag_value(&mut entries, luminance);
             base_offset += 20;
+            pad_entry(&mut entries, &mut base_offset);
         }
 
         if let Some(description) = &self.description {
@@ -807,6 +1186,7 @@ impl ColorProfile {
                 let entry_size = write_string_value(&mut entries, description);
                 write_tag_entry(&mut tags, Tag::ProfileDescription, base_offset, entry_size);
                 base_offset += entry_size;
+                pad_entry(&mut entries, &mut base_offset);
             }
         }
 
@@ -815,6 +1195,7 @@ impl ColorProfile {
                 let entry_size = write_string_value(&mut entries, copyright);
                 write_tag_entry(&mut tags, Tag::Copyright, base_offset, entry_size);
                 base_offset += entry_size;
+                pad_entry(&mut entries, &mut base_offset);
             }
         }
 
@@ -822,6 +1203,14 @@ impl ColorProfile {
             let entry_size = write_viewing_conditions_value(&mut entries, vc);
             write_tag_entry(&mut tags, Tag::ObserverConditions, base_offset, entry_size);
             base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
+        }
+
+        if let Some(measurement) = &self.measurement {
+            let entry_size = write_measurement_value(&mut entries, measurement);
+            write_tag_entry(&mut tags, Tag::Measurement, base_offset, entry_size);
+            base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
         }
 
         if let Some(vd) = &self.viewing_conditions_description {
@@ -834,6 +1223,44 @@ impl ColorProfile {
                     entry_size,
                 );
                 base_offset += entry_size;
+                pad_entry(&mut entries, &mut base_offset);
+            }
+        }
+
+        if let Some(technology) = self.technology {
+            let entry_size = write_signature_value(&mut entries, technology.into());
+            write_tag_entry(&mut tags, Tag::Technology, base_offset, entry_size);
+            base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
+        }
+
+        if let Some(date) = &self.calibration_date {
+            let entry_size = write_date_time_value(&mut entries, date);
+            write_tag_entry(&mut tags, Tag::CalibrationDateTime, base_offset, entry_size);
+            base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
+        }
+
+        if let Some(vcgt) = &self.video_card_gamma {
+            let entry_size = write_vcgt_value(&mut entries, vcgt)?;
+            write_tag_entry(&mut tags, Tag::VideoCardGamma, base_offset, entry_size);
+            base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
+        }
+
+        if let Some(named_colors) = &self.named_colors {
+            let entry_size = write_named_color_value(&mut entries, named_colors)?;
+            write_tag_entry(&mut tags, Tag::NamedColor, base_offset, entry_size);
+            base_offset += entry_size;
+            pad_entry(&mut entries, &mut base_offset);
+        }
+
+        if let Some(vd) = &self.char_target {
+            if vd.has_values() {
+                let entry_size = write_string_value(&mut entries, vd);
+                write_tag_entry(&mut tags, Tag::CharTarget, base_offset, entry_size);
+                base_offset += entry_size;
+                pad_entry(&mut entries, &mut base_offset);
             }
         }
 
@@ -842,6 +1269,7 @@ impl ColorProfile {
                 let entry_size = write_string_value(&mut entries, vd);
                 write_tag_entry(&mut tags, Tag::DeviceModel, base_offset, entry_size);
                 base_offset += entry_size;
+                pad_entry(&mut entries, &mut base_offset);
             }
         }
 
@@ -849,10 +1277,20 @@ impl ColorProfile {
             if vd.has_values() {
                 let entry_size = write_string_value(&mut entries, vd);
                 write_tag_entry(&mut tags, Tag::DeviceManufacturer, base_offset, entry_size);
-                // base_offset += entry_size;
+                base_offset += entry_size;
+                pad_entry(&mut entries, &mut base_offset);
             }
         }
 
+        for (signature, data) in custom_tags.iter() {
+            write_u32_be(&mut tags, *signature);
+            write_u32_be(&mut tags, base_offset as u32);
+            write_u32_be(&mut tags, data.len() as u32);
+            entries.extend_from_slice(data);
+            base_offset += data.len();
+            pad_entry(&mut entries, &mut base_offset);
+        }
+
         tags.extend(entries);
 
         let profile_header = ProfileHeader {
@@ -861,15 +1299,20 @@ impl ColorProfile {
             profile_class: self.profile_class,
             rendering_intent: self.rendering_intent,
             cmm_type: 0,
+            // `cicp` tag was introduced in ICC.1:2022, v4.4
             version: if has_cicp {
-                ProfileVersion::V4_3
+                self.version_internal.max(ProfileVersion::V4_4)
             } else if self.version_internal < ProfileVersion::V4_0 {
                 ProfileVersion::V4_0
             } else {
                 self.version_internal
             },
             data_color_space: self.color_space,
-            creation_date_time: ColorDateTime::now(),
+            creation_date_time: match options.creation_date {
+                Some(date) => date,
+                None if self.sanitized => ColorDateTime::default(),
+                None => ColorDateTime::now(),
+            },
             signature: ProfileSignature::Acsp,
             platform: 0u32,
             flags: 0u32,
@@ -904,6 +1347,10 @@ impl FloatToFixedU8Fixed8 for f32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        MeasurementGeometry, StandardIlluminant, StandardObserver, TechnologySignatures,
+        VideoCardGammaFormula,
+    };
 
     #[test]
     fn to_u8_fixed8() {
@@ -912,6 +1359,48 @@ mod tests {
         assert_eq!(u16::MAX, (255f32 + (255f32 / 256f32)).to_u8_fixed8());
     }
 
+    #[test]
+    fn cicp_encoding() {
+        let srgb = ColorProfile::new_srgb();
+        let decoded = ColorProfile::new_from_slice(&srgb.encode().unwrap()).unwrap();
+        assert_eq!(decoded.version(), ProfileVersion::V4_4);
+        assert!(decoded.cicp.is_some());
+
+        for profile in [
+            ColorProfile::new_display_p3(),
+            ColorProfile::new_display_p3_pq(),
+            ColorProfile::new_dci_p3(),
+            ColorProfile::new_bt2020(),
+            ColorProfile::new_bt2020_pq(),
+            ColorProfile::new_bt2020_hlg(),
+            ColorProfile::new_aces_cg_linear(),
+        ] {
+            if profile.cicp.is_some() {
+                assert!(profile.encode().is_ok(), "{:?}", profile.description);
+            }
+        }
+
+        let mut mismatched_trc = ColorProfile::new_srgb();
+        mismatched_trc.red_trc = Some(ToneReprCurve::Parametric(vec![2.6]));
+        assert!(matches!(
+            mismatched_trc.encode(),
+            Err(CmsError::InvalidCicp)
+        ));
+
+        let mut mismatched_primaries = ColorProfile::new_display_p3();
+        mismatched_primaries.cicp = ColorProfile::new_srgb().cicp;
+        assert!(matches!(
+            mismatched_primaries.encode(),
+            Err(CmsError::InvalidCicp)
+        ));
+
+        // Not allowed for output profiles, so neither written nor checked
+        let mut output = mismatched_trc.clone();
+        output.profile_class = ProfileClass::OutputDevice;
+        let decoded = ColorProfile::new_from_slice(&output.encode().unwrap()).unwrap();
+        assert!(decoded.cicp.is_none());
+    }
+
     #[test]
     fn to_s15_fixed16() {
         assert_eq!(0x80000000u32 as i32, (-32768f32).to_s15_fixed16());
@@ -922,4 +1411,192 @@ mod tests {
             (32767f32 + (65535f32 / 65536f32)).to_s15_fixed16()
         );
     }
+
+    #[test]
+    fn luminance_round_trip() {
+        let mut srgb = ColorProfile::new_srgb();
+        srgb.luminance = Some(Xyzd::new(0., 203., 0.));
+        let decoded = ColorProfile::new_from_slice(&srgb.encode().unwrap()).unwrap();
+        let luminance = decoded.luminance.unwrap();
+        assert!((luminance.y - 203.).abs() < 1e-3, "{luminance:?}");
+    }
+
+    #[test]
+    fn measurement_and_viewing_conditions_round_trip() {
+        let mut srgb = ColorProfile::new_srgb();
+        srgb.measurement = Some(Measurement {
+            observer: StandardObserver::D50,
+            backing: Xyz::new(0.01, 0.02, 0.03),
+            geometry: MeasurementGeometry::D45to45,
+            flare: 0.01,
+            illuminant: StandardIlluminant::D50,
+        });
+        srgb.viewing_conditions = Some(ViewingConditions {
+            illuminant: Xyz::new(19.6445, 20.3718, 16.8089),
+            surround: Xyz::new(3.9289, 4.0744, 3.3618),
+            observer: StandardObserver::D50,
+        });
+        let decoded = ColorProfile::new_from_slice(&srgb.encode().unwrap()).unwrap();
+        let measurement = decoded.measurement.unwrap();
+        assert_eq!(u32::from(measurement.observer), 1);
+        assert_eq!(u32::from(measurement.geometry), 1);
+        assert_eq!(u32::from(measurement.illuminant), 1);
+        assert!((measurement.flare - 0.01).abs() < 1e-4, "{measurement:?}");
+        assert!(
+            (measurement.backing.z - 0.03).abs() < 1e-4,
+            "{measurement:?}"
+        );
+        let viewing_conditions = decoded.viewing_conditions.unwrap();
+        assert!(
+            (viewing_conditions.illuminant.y - 20.3718).abs() < 1e-3,
+            "{viewing_conditions:?}"
+        );
+        assert!(
+            (viewing_conditions.surround.x - 3.9289).abs() < 1e-3,
+            "{viewing_conditions:?}"
+        );
+    }
+
+    #[test]
+    fn technology_and_calibration_date_round_trip() {
+        let mut srgb = ColorProfile::new_srgb();
+        let date = ColorDateTime {
+            year: 2024,
+            month: 5,
+            day_of_the_month: 17,
+            hours: 9,
+            minutes: 41,
+            seconds: 3,
+        };
+        srgb.technology = Some(TechnologySignatures::LiquidCrystalDisplay);
+        srgb.calibration_date = Some(date);
+        let decoded = ColorProfile::new_from_slice(&srgb.encode().unwrap()).unwrap();
+        assert_eq!(
+            decoded.technology,
+            Some(TechnologySignatures::LiquidCrystalDisplay)
+        );
+        assert_eq!(decoded.calibration_date, Some(date));
+
+        srgb.technology = Some(TechnologySignatures::Unknown(0x74657374));
+        let decoded = ColorProfile::new_from_slice(&srgb.encode().unwrap()).unwrap();
+        assert_eq!(
+            decoded.technology,
+            Some(TechnologySignatures::Unknown(0x74657374))
+        );
+    }
+
+    #[test]
+    fn deterministic_encoding() {
+        let mut profile = ColorProfile::new_display_p3();
+        // Odd length text makes unaligned tag
+        profile.description = Some(ProfileText::PlainString("P3".to_string()));
+        profile.copyright = Some(ProfileText::PlainString("odd".to_string()));
+        profile.profile_class = ProfileClass::Abstract;
+        let options = EncodingOptions {
+            creation_date: Some(ColorDateTime {
+                year: 2024,
+                month: 1,
+                day_of_the_month: 2,
+                hours: 3,
+                minutes: 4,
+                seconds: 5,
+            }),
+        };
+        let encoded = profile.encode_with_options(options).unwrap();
+        assert_eq!(encoded, profile.encode_with_options(options).unwrap());
+        assert_eq!(encoded.len() % 4, 0);
+        let tag_count = u32::from_be_bytes(encoded[128..132].try_into().unwrap()) as usize;
+        for entry in encoded[132..132 + tag_count * TAG_SIZE].chunks_exact(TAG_SIZE) {
+            let offset = u32::from_be_bytes(entry[4..8].try_into().unwrap());
+            assert_eq!(offset % 4, 0, "tag {:?} is not aligned", &entry[0..4]);
+        }
+        let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+        assert!(decoded.copyright.is_some());
+    }
+
+    fn tag_signatures(profile: &[u8]) -> Vec<u32> {
+        let tag_count = u32::from_be_bytes(profile[128..132].try_into().unwrap()) as usize;
+        profile[132..132 + tag_count * TAG_SIZE]
+            .chunks_exact(TAG_SIZE)
+            .map(|entry| u32::from_be_bytes(entry[0..4].try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn assets_round_trip() {
+        let options = EncodingOptions {
+            creation_date: Some(ColorDateTime::default()),
+        };
+        for data in [
+            include_bytes!("../assets/bt_2020.icc").as_slice(),
+            include_bytes!("../assets/Display P3.icc"),
+            include_bytes!("../assets/Generic Gray Gamma 2.2 Profile.icc"),
+            include_bytes!("../assets/srgb_perceptual.icc"),
+            include_bytes!("../assets/us_swop_coated.icc"),
+        ] {
+            let profile = ColorProfile::new_from_slice(data).unwrap();
+            let encoded = profile.encode_with_options(options).unwrap();
+            let written = tag_signatures(&encoded);
+            for signature in tag_signatures(data) {
+                match Tag::try_from(signature) {
+                    // Derived from colorants, not stored
+                    Ok(Tag::Chromaticity) | Err(_) => {}
+                    Ok(tag) => assert!(
+                        written.contains(&signature),
+                        "{tag:?} is dropped from {:?}",
+                        profile.description
+                    ),
+                }
+            }
+            let decoded = ColorProfile::new_from_slice(&encoded).unwrap();
+            assert_eq!(decoded.encode_with_options(options).unwrap(), encoded);
+        }
+    }
+
+    #[test]
+    fn black_point_char_target_vcgt_round_trip() {
+        let mut srgb = ColorProfile::new_srgb();
+        srgb.black_point = Some(Xyzd::new(0.0034, 0.0036, 0.0029));
+        srgb.char_target = Some(ProfileText::PlainString("IT8.7/4".to_string()));
+        srgb.video_card_gamma = Some(VideoCardGamma::Table {
+            red: vec![0, 30000, 65535],
+            green: vec![0, 32768, 65535],
+            blue: vec![0, 35000, 65535],
+        });
+        srgb.lut_d_to_b_perceptual = Some(b"mpet\0\0\0\0\0\x03\0\x03\0\0\0\0".to_vec());
+        srgb.device_manufacturer = Some(ProfileText::PlainString("odd".to_string()));
+        let decoded = ColorProfile::new_from_slice(&srgb.encode().unwrap()).unwrap();
+        let black_point = decoded.black_point.unwrap();
+        assert!((black_point.y - 0.0036).abs() < 1e-4, "{black_point:?}");
+        let Some(ProfileText::Localizable(char_target)) = decoded.char_target else {
+            panic!("{:?}", decoded.char_target);
+        };
+        assert_eq!(char_target[0].value, "IT8.7/4");
+        assert_eq!(decoded.video_card_gamma, srgb.video_card_gamma);
+        assert!(decoded.lut_d_to_b_perceptual.is_none());
+        assert!(matches!(
+            decoded.lut_a_to_b_perceptual,
+            Some(LutWarehouse::MultiProcess(_))
+        ));
+
+        let formula = |gamma: f32| VideoCardGammaFormula {
+            gamma,
+            min: 0.,
+            max: 1.,
+        };
+        srgb.video_card_gamma = Some(VideoCardGamma::Formula {
+            red: formula(1.),
+            green: formula(1.5),
+            blue: formula(2.25),
+        });
+        let decoded = ColorProfile::new_from_slice(&srgb.encode().unwrap()).unwrap();
+        assert_eq!(decoded.video_card_gamma, srgb.video_card_gamma);
+
+        srgb.video_card_gamma = Some(VideoCardGamma::Table {
+            red: vec![0, 65535],
+            green: vec![0, 32768, 65535],
+            blue: vec![0, 65535],
+        });
+        assert!(srgb.encode().is_err());
+    }
 }
@@ -0,0 +1,616 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{
+    CmsError, ColorProfile, DataColorSpace, Layout, MatrixCoefficients, TransferCharacteristics,
+    TransformExecutor, TransformOptions,
+};
+use num_traits::AsPrimitive;
+
+/// Chroma planes resolution relative to luma plane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChromaSubsampling {
+    /// Full resolution chroma
+    Yuv444,
+    /// Half horizontal resolution
+    Yuv422,
+    /// Half horizontal and vertical resolution
+    #[default]
+    Yuv420,
+}
+
+impl ChromaSubsampling {
+    #[inline]
+    const fn chroma_size(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            ChromaSubsampling::Yuv444 => (width, height),
+            ChromaSubsampling::Yuv422 => (width.div_ceil(2), height),
+            ChromaSubsampling::Yuv420 => (width.div_ceil(2), height.div_ceil(2)),
+        }
+    }
+}
+
+/// Planar YCbCr image, strides are in elements
+#[derive(Debug, Clone, Copy)]
+pub struct YCbCrPlanes<'a, T> {
+    pub y_plane: &'a [T],
+    pub y_stride: usize,
+    pub cb_plane: &'a [T],
+    pub cb_stride: usize,
+    pub cr_plane: &'a [T],
+    pub cr_stride: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum YCbCrMatrix {
+    /// Luma weights of red and blue
+    Kr {
+        kr: f32,
+        kb: f32,
+    },
+    Identity,
+    YCgCo,
+}
+
+/// Transform from subsampled YCbCr planes to interleaved RGB through color managed pipeline.
+///
+/// Chroma is upsampled with bilinear filter, chroma samples are expected to be centered
+/// between luma samples.
+pub struct YCbCrTransform<T> {
+    executor: Box<dyn TransformExecutor<T> + Send + Sync>,
+    matrix: YCbCrMatrix,
+    full_range: bool,
+    bit_depth: usize,
+    subsampling: ChromaSubsampling,
+    dst_layout: Layout,
+}
+
+/// Transform from interleaved pixels to luma plane of YCbCr encoding declared by CICP.
+///
+/// Luma of constant luminance matrices is computed from linear light,
+/// otherwise it is weighted sum of gamma encoded RGB.
+pub struct LumaTransform<T> {
+    executor: Box<dyn TransformExecutor<T> + Send + Sync>,
+    matrix: YCbCrMatrix,
+    constant_luminance: Option<TransferCharacteristics>,
+    full_range: bool,
+    bit_depth: usize,
+    src_layout: Layout,
+}
+
+/// Pixels converted to RGB at once by [LumaTransform]
+const LUMA_CHUNK_PIXELS: usize = 512;
+
+impl<T: Copy + Default + AsPrimitive<f32>> LumaTransform<T>
+where
+    f32: AsPrimitive<T>,
+{
+    /// Converts interleaved `src` in source layout into one luma code value per pixel
+    pub fn transform(&self, src: &[T], dst: &mut [T]) -> Result<(), CmsError> {
+        let src_channels = self.src_layout.channels();
+        if src.len() != dst.len() * src_channels {
+            return Err(CmsError::LaneSizeMismatch);
+        }
+        let max_value = ((1u32 << self.bit_depth) - 1) as f32;
+        let shift = (1u32 << (self.bit_depth - 8)) as f32;
+        let (y_bias, y_scale) = if self.full_range {
+            (0., max_value)
+        } else {
+            (16. * shift, 219. * shift)
+        };
+        let norm = 1. / max_value;
+        let mut rgb = vec![T::default(); LUMA_CHUNK_PIXELS.min(dst.len()) * 3];
+        for (src, dst) in src
+            .chunks(LUMA_CHUNK_PIXELS * src_channels)
+            .zip(dst.chunks_mut(LUMA_CHUNK_PIXELS))
+        {
+            let rgb = &mut rgb[..dst.len() * 3];
+            self.executor.transform(src, rgb)?;
+            for (dst, px) in dst.iter_mut().zip(rgb.chunks_exact(3)) {
+                let r = px[0].as_() * norm;
+                let g = px[1].as_() * norm;
+                let b = px[2].as_() * norm;
+                let luma = match self.matrix {
+                    YCbCrMatrix::Kr { kr, kb } => match self.constant_luminance {
+                        Some(trc) => {
+                            let kg = 1. - kr - kb;
+                            let linear = kr as f64 * trc.linearize(r as f64)
+                                + kg as f64 * trc.linearize(g as f64)
+                                + kb as f64 * trc.linearize(b as f64);
+                            trc.gamma(linear) as f32
+                        }
+                        None => kr * r + (1. - kr - kb) * g + kb * b,
+                    },
+                    YCbCrMatrix::Identity => g,
+                    YCbCrMatrix::YCgCo => 0.25 * r + 0.5 * g + 0.25 * b,
+                };
+                *dst = (luma.max(0.).min(1.) * y_scale + y_bias + 0.5).as_();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Samples plane with bilinear interpolation at position in plane coordinates
+#[inline]
+fn sample_bilinear<T: Copy + AsPrimitive<f32>>(
+    plane: &[T],
+    stride: usize,
+    width: usize,
+    height: usize,
+    x: f32,
+    y: f32,
+) -> f32 {
+    let x = x.max(0.).min((width - 1) as f32);
+    let y = y.max(0.).min((height - 1) as f32);
+    let x0 = x as usize;
+    let y0 = y as usize;
+    let x1 = (x0 + 1).min(width - 1);
+    let y1 = (y0 + 1).min(height - 1);
+    let dx = x - x0 as f32;
+    let dy = y - y0 as f32;
+    let row0 = &plane[y0 * stride..];
+    let row1 = &plane[y1 * stride..];
+    let top = row0[x0].as_() + (row0[x1].as_() - row0[x0].as_()) * dx;
+    let bottom = row1[x0].as_() + (row1[x1].as_() - row1[x0].as_()) * dx;
+    top + (bottom - top) * dy
+}
+
+fn check_plane<T>(plane: &[T], stride: usize, width: usize, height: usize) -> Result<(), CmsError> {
+    if stride < width {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    if height > 0 && plane.len() < stride * (height - 1) + width {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    Ok(())
+}
+
+impl<T: Copy + Default + AsPrimitive<f32>> YCbCrTransform<T>
+where
+    f32: AsPrimitive<T>,
+{
+    /// Converts planes into `dst` with destination layout, `dst_stride` is in elements.
+    pub fn transform(
+        &self,
+        planes: &YCbCrPlanes<T>,
+        dst: &mut [T],
+        dst_stride: usize,
+    ) -> Result<(), CmsError> {
+        let width = planes.width;
+        let height = planes.height;
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let (chroma_width, chroma_height) = self.subsampling.chroma_size(width, height);
+        check_plane(planes.y_plane, planes.y_stride, width, height)?;
+        check_plane(
+            planes.cb_plane,
+            planes.cb_stride,
+            chroma_width,
+            chroma_height,
+        )?;
+        check_plane(
+            planes.cr_plane,
+            planes.cr_stride,
+            chroma_width,
+            chroma_height,
+        )?;
+        let dst_row_len = width * self.dst_layout.channels();
+        check_plane(dst, dst_stride, dst_row_len, height)?;
+
+        let max_value = ((1u32 << self.bit_depth) - 1) as f32;
+        let shift = (1u32 << (self.bit_depth - 8)) as f32;
+        let (y_bias, y_scale, c_scale) = if self.full_range {
+            (0., 1. / max_value, 1. / max_value)
+        } else {
+            (16. * shift, 1. / (219. * shift), 1. / (224. * shift))
+        };
+        let c_bias = (1u32 << (self.bit_depth - 1)) as f32;
+        let (scale_x, scale_y) = match self.subsampling {
+            ChromaSubsampling::Yuv444 => (1., 1.),
+            ChromaSubsampling::Yuv422 => (0.5, 1.),
+            ChromaSubsampling::Yuv420 => (0.5, 0.5),
+        };
+
+        let mut rgb_row = vec![T::default(); width * 3];
+        for (y, dst_row) in dst.chunks_mut(dst_stride).take(height).enumerate() {
+            let luma_row = &planes.y_plane[y * planes.y_stride..];
+            let cy = (y as f32 + 0.5) * scale_y - 0.5;
+            for (x, rgb) in rgb_row.chunks_exact_mut(3).enumerate() {
+                let cx = (x as f32 + 0.5) * scale_x - 0.5;
+                let luma = (luma_row[x].as_() - y_bias) * y_scale;
+                let cb = (sample_bilinear(
+                    planes.cb_plane,
+                    planes.cb_stride,
+                    chroma_width,
+                    chroma_height,
+                    cx,
+                    cy,
+                ) - c_bias)
+                    * c_scale;
+                let cr = (sample_bilinear(
+                    planes.cr_plane,
+                    planes.cr_stride,
+                    chroma_width,
+                    chroma_height,
+                    cx,
+                    cy,
+                ) - c_bias)
+                    * c_scale;
+                let (r, g, b) = match self.matrix {
+                    YCbCrMatrix::Kr { kr, kb } => {
+                        let kg = 1. - kr - kb;
+                        let r = luma + 2. * (1. - kr) * cr;
+                        let b = luma + 2. * (1. - kb) * cb;
+                        let g = (luma - kr * r - kb * b) / kg;
+                        (r, g, b)
+                    }
+                    YCbCrMatrix::Identity => (cr + 0.5, luma, cb + 0.5),
+                    YCbCrMatrix::YCgCo => (luma - cb + cr, luma + cb, luma - cb - cr),
+                };
+                rgb[0] = (r.max(0.).min(1.) * max_value + 0.5).as_();
+                rgb[1] = (g.max(0.).min(1.) * max_value + 0.5).as_();
+                rgb[2] = (b.max(0.).min(1.) * max_value + 0.5).as_();
+            }
+            self.executor
+                .transform(&rgb_row, &mut dst_row[..dst_row_len])?;
+        }
+        Ok(())
+    }
+}
+
+impl ColorProfile {
+    /// Creates transform from 8 bit YCbCr planes encoded in this RGB profile
+    pub fn create_ycbcr_transform_8bit(
+        &self,
+        matrix: MatrixCoefficients,
+        full_range: bool,
+        subsampling: ChromaSubsampling,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<YCbCrTransform<u8>, CmsError> {
+        let executor = self.create_transform_8bit(Layout::Rgb, dst_pr, dst_layout, options)?;
+        self.make_ycbcr_transform(executor, 8, matrix, full_range, subsampling, dst_layout)
+    }
+
+    /// Creates transform from 10 bit YCbCr planes encoded in this RGB profile
+    pub fn create_ycbcr_transform_10bit(
+        &self,
+        matrix: MatrixCoefficients,
+        full_range: bool,
+        subsampling: ChromaSubsampling,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<YCbCrTransform<u16>, CmsError> {
+        let executor = self.create_transform_10bit(Layout::Rgb, dst_pr, dst_layout, options)?;
+        self.make_ycbcr_transform(executor, 10, matrix, full_range, subsampling, dst_layout)
+    }
+
+    /// Creates transform from 12 bit YCbCr planes encoded in this RGB profile
+    pub fn create_ycbcr_transform_12bit(
+        &self,
+        matrix: MatrixCoefficients,
+        full_range: bool,
+        subsampling: ChromaSubsampling,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<YCbCrTransform<u16>, CmsError> {
+        let executor = self.create_transform_12bit(Layout::Rgb, dst_pr, dst_layout, options)?;
+        self.make_ycbcr_transform(executor, 12, matrix, full_range, subsampling, dst_layout)
+    }
+
+    /// Creates transform from 16 bit YCbCr planes encoded in this RGB profile
+    pub fn create_ycbcr_transform_16bit(
+        &self,
+        matrix: MatrixCoefficients,
+        full_range: bool,
+        subsampling: ChromaSubsampling,
+        dst_pr: &ColorProfile,
+        dst_layout: Layout,
+        options: TransformOptions,
+    ) -> Result<YCbCrTransform<u16>, CmsError> {
+        let executor = self.create_transform_16bit(Layout::Rgb, dst_pr, dst_layout, options)?;
+        self.make_ycbcr_transform(executor, 16, matrix, full_range, subsampling, dst_layout)
+    }
+
+    /// Creates transform producing 8 bit luma plane of `dst_pr` from pixels in this profile.
+    ///
+    /// `dst_pr` is YCbCr or RGB profile, its CICP declares matrix coefficients,
+    /// and for YCbCr profiles also primaries and transfer characteristics.
+    pub fn create_luma_transform_8bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        full_range: bool,
+        options: TransformOptions,
+    ) -> Result<LumaTransform<u8>, CmsError> {
+        let rgb = dst_pr.ycbcr_rgb_profile()?;
+        let executor = self.create_transform_8bit(src_layout, &rgb, Layout::Rgb, options)?;
+        rgb.make_luma_transform(executor, dst_pr, 8, full_range, src_layout)
+    }
+
+    /// Creates transform producing 10 bit luma plane of `dst_pr` from pixels in this profile.
+    pub fn create_luma_transform_10bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        full_range: bool,
+        options: TransformOptions,
+    ) -> Result<LumaTransform<u16>, CmsError> {
+        let rgb = dst_pr.ycbcr_rgb_profile()?;
+        let executor = self.create_transform_10bit(src_layout, &rgb, Layout::Rgb, options)?;
+        rgb.make_luma_transform(executor, dst_pr, 10, full_range, src_layout)
+    }
+
+    /// Creates transform producing 12 bit luma plane of `dst_pr` from pixels in this profile.
+    pub fn create_luma_transform_12bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        full_range: bool,
+        options: TransformOptions,
+    ) -> Result<LumaTransform<u16>, CmsError> {
+        let rgb = dst_pr.ycbcr_rgb_profile()?;
+        let executor = self.create_transform_12bit(src_layout, &rgb, Layout::Rgb, options)?;
+        rgb.make_luma_transform(executor, dst_pr, 12, full_range, src_layout)
+    }
+
+    /// Creates transform producing 16 bit luma plane of `dst_pr` from pixels in this profile.
+    pub fn create_luma_transform_16bit(
+        &self,
+        src_layout: Layout,
+        dst_pr: &ColorProfile,
+        full_range: bool,
+        options: TransformOptions,
+    ) -> Result<LumaTransform<u16>, CmsError> {
+        let rgb = dst_pr.ycbcr_rgb_profile()?;
+        let executor = self.create_transform_16bit(src_layout, &rgb, Layout::Rgb, options)?;
+        rgb.make_luma_transform(executor, dst_pr, 16, full_range, src_layout)
+    }
+
+    /// RGB profile in which YCbCr of this profile is encoded
+    fn ycbcr_rgb_profile(&self) -> Result<ColorProfile, CmsError> {
+        let cicp = self.cicp.ok_or(CmsError::InvalidCicp)?;
+        match self.color_space {
+            DataColorSpace::Rgb => Ok(self.clone()),
+            DataColorSpace::YCbr => {
+                if !cicp.color_primaries.has_chromaticity()
+                    || !cicp.transfer_characteristics.has_transfer_curve()
+                {
+                    return Err(CmsError::InvalidCicp);
+                }
+                Ok(ColorProfile::new_from_cicp(cicp))
+            }
+            _ => Err(CmsError::InvalidCicp),
+        }
+    }
+
+    /// `self` is RGB profile returned by [ColorProfile::ycbcr_rgb_profile]
+    fn make_luma_transform<T>(
+        &self,
+        executor: Box<dyn TransformExecutor<T> + Send + Sync>,
+        dst_pr: &ColorProfile,
+        bit_depth: usize,
+        full_range: bool,
+        src_layout: Layout,
+    ) -> Result<LumaTransform<T>, CmsError> {
+        let cicp = dst_pr.cicp.ok_or(CmsError::InvalidCicp)?;
+        let (matrix, constant_luminance) = match cicp.matrix_coefficients {
+            MatrixCoefficients::Bt2020Cl => (
+                YCbCrMatrix::Kr {
+                    kr: 0.2627,
+                    kb: 0.0593,
+                },
+                true,
+            ),
+            MatrixCoefficients::ChromaticityDerivedCL => (
+                self.ycbcr_matrix(MatrixCoefficients::ChromaticityDerivedNCL)?,
+                true,
+            ),
+            coefficients => (self.ycbcr_matrix(coefficients)?, false),
+        };
+        let constant_luminance = if constant_luminance {
+            if !cicp.transfer_characteristics.has_transfer_curve() {
+                return Err(CmsError::InvalidCicp);
+            }
+            Some(cicp.transfer_characteristics)
+        } else {
+            None
+        };
+        Ok(LumaTransform {
+            executor,
+            matrix,
+            constant_luminance,
+            full_range,
+            bit_depth,
+            src_layout,
+        })
+    }
+
+    fn make_ycbcr_transform<T>(
+        &self,
+        executor: Box<dyn TransformExecutor<T> + Send + Sync>,
+        bit_depth: usize,
+        matrix: MatrixCoefficients,
+        full_range: bool,
+        subsampling: ChromaSubsampling,
+        dst_layout: Layout,
+    ) -> Result<YCbCrTransform<T>, CmsError> {
+        Ok(YCbCrTransform {
+            executor,
+            matrix: self.ycbcr_matrix(matrix)?,
+            full_range,
+            bit_depth,
+            subsampling,
+            dst_layout,
+        })
+    }
+
+    fn ycbcr_matrix(&self, matrix: MatrixCoefficients) -> Result<YCbCrMatrix, CmsError> {
+        Ok(match matrix {
+            MatrixCoefficients::Identity => YCbCrMatrix::Identity,
+            MatrixCoefficients::YCgCo => YCbCrMatrix::YCgCo,
+            MatrixCoefficients::Bt709 | MatrixCoefficients::Unspecified => YCbCrMatrix::Kr {
+                kr: 0.2126,
+                kb: 0.0722,
+            },
+            MatrixCoefficients::Fcc => YCbCrMatrix::Kr { kr: 0.30, kb: 0.11 },
+            MatrixCoefficients::Bt470Bg | MatrixCoefficients::Smpte170m => YCbCrMatrix::Kr {
+                kr: 0.299,
+                kb: 0.114,
+            },
+            MatrixCoefficients::Smpte240m => YCbCrMatrix::Kr {
+                kr: 0.212,
+                kb: 0.087,
+            },
+            MatrixCoefficients::Bt2020Ncl => YCbCrMatrix::Kr {
+                kr: 0.2627,
+                kb: 0.0593,
+            },
+            MatrixCoefficients::ChromaticityDerivedNCL => {
+                // Luma weights are Y of the profile primaries
+                let m = self.rgb_to_xyz_matrix();
+                let y_sum = m.v[1][0] + m.v[1][1] + m.v[1][2];
+                if y_sum <= 0. {
+                    return Err(CmsError::DivisionByZero);
+                }
+                YCbCrMatrix::Kr {
+                    kr: (m.v[1][0] / y_sum) as f32,
+                    kb: (m.v[1][2] / y_sum) as f32,
+                }
+            }
+            _ => return Err(CmsError::InvalidCicp),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CicpColorPrimaries, CicpProfile};
+
+    #[test]
+    fn test_ycbcr_420_to_rgb() {
+        let srgb = ColorProfile::new_srgb();
+        let transform = srgb
+            .create_ycbcr_transform_8bit(
+                MatrixCoefficients::Bt709,
+                false,
+                ChromaSubsampling::Yuv420,
+                &srgb,
+                Layout::Rgba,
+                TransformOptions::default(),
+            )
+            .unwrap();
+        // Left half is white, right half is red, limited range BT.709
+        let width = 5;
+        let height = 3;
+        let y_plane = (0..width * height)
+            .map(|i| if i % width < 2 { 235u8 } else { 63 })
+            .collect::<Vec<u8>>();
+        let cb_plane = [128u8, 102, 102, 128, 102, 102];
+        let cr_plane = [128u8, 240, 240, 128, 240, 240];
+        let planes = YCbCrPlanes {
+            y_plane: &y_plane,
+            y_stride: width,
+            cb_plane: &cb_plane,
+            cb_stride: 3,
+            cr_plane: &cr_plane,
+            cr_stride: 3,
+            width,
+            height,
+        };
+        let mut dst = vec![0u8; width * height * 4];
+        transform.transform(&planes, &mut dst, width * 4).unwrap();
+        for row in dst.chunks_exact(width * 4) {
+            assert_eq!(&row[..4], &[255, 255, 255, 255]);
+            let red = &row[16..20];
+            assert!(red[0] >= 253 && red[1] <= 2 && red[2] <= 2, "{red:?}");
+            assert_eq!(red[3], 255);
+        }
+
+        let short_planes = YCbCrPlanes {
+            cb_plane: &cb_plane[..4],
+            ..planes
+        };
+        assert!(
+            transform
+                .transform(&short_planes, &mut dst, width * 4)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_luma_from_declared_matrix() {
+        let srgb = ColorProfile::new_srgb();
+        let transform = srgb
+            .create_luma_transform_8bit(Layout::Rgb, &srgb, false, TransformOptions::default())
+            .unwrap();
+        let src = [255u8, 255, 255, 255, 0, 0, 0, 0, 255, 0, 0, 0];
+        let mut luma = [0u8; 4];
+        transform.transform(&src, &mut luma).unwrap();
+        // BT.709 limited range: 16 + 219 * Kr, 16 + 219 * Kb
+        assert_eq!(luma, [235, 63, 32, 16]);
+
+        let cicp = CicpProfile {
+            color_primaries: CicpColorPrimaries::Bt2020,
+            transfer_characteristics: TransferCharacteristics::Bt709,
+            matrix_coefficients: MatrixCoefficients::Bt2020Cl,
+            full_range: true,
+        };
+        let mut ycbcr = ColorProfile::new_from_cicp(cicp);
+        ycbcr.color_space = DataColorSpace::YCbr;
+        let source = ColorProfile::new_from_cicp(cicp);
+        let transform = source
+            .create_luma_transform_16bit(Layout::Rgb, &ycbcr, true, TransformOptions::default())
+            .unwrap();
+        let src = [65535u16, 0, 0];
+        let mut luma = [0u16; 1];
+        transform.transform(&src, &mut luma).unwrap();
+        // Constant luminance luma is transfer function of linear luminance
+        let expected = TransferCharacteristics::Bt709.gamma(0.2627) * 65535.;
+        assert!(
+            (luma[0] as f64 - expected).abs() < 64.,
+            "{luma:?} {expected}"
+        );
+
+        let mut no_cicp = srgb.clone();
+        no_cicp.cicp = None;
+        assert!(
+            srgb.create_luma_transform_8bit(Layout::Rgb, &no_cicp, false, Default::default())
+                .is_err()
+        );
+        assert!(transform.transform(&src, &mut [0u16; 2]).is_err());
+    }
+}
@@ -27,7 +27,7 @@
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::mlaf::mlaf;
-use crate::{Matrix3f, Vector3f, Xyz};
+use crate::{Chromaticity, CmsError, Matrix3f, Vector3f, Xyz, adaption_matrix};
 use pxfm::{f_atan2f, f_hypotf, f_sincosf};
 
 /// Structure for Yrg colorspace
@@ -71,15 +71,37 @@ impl Yrg {
         Yrg { y, r, g }
     }
 
+    /// Matrix from XYZ D65 to CIE LMS 2006 used by [Yrg]
+    #[inline]
+    pub const fn xyz_to_lms_matrix() -> Matrix3f {
+        XYZ_TO_LMS
+    }
+
+    /// Inverse of [Yrg::xyz_to_lms_matrix]
+    #[inline]
+    pub const fn lms_to_xyz_matrix() -> Matrix3f {
+        LMS_TO_XYZ
+    }
+
     /// Convert [Xyz] D65 to [Yrg]
     ///
     /// Yrg defined in D65 white point. Ensure Xyz values is adapted.
     /// Yrg use CIE XYZ 2006, adapt CIE XYZ 1931 by using [cie_y_1931_to_cie_y_2006] at first.
     #[inline]
     pub fn from_xyz(xyz: Xyz) -> Self {
-        let lms = XYZ_TO_LMS.f_mul_vector(Vector3f {
+        Self::from_lms(XYZ_TO_LMS.f_mul_vector(Vector3f {
             v: [xyz.x, xyz.y, xyz.z],
-        });
+        }))
+    }
+
+    /// Convert [Xyz] D50 to [Yrg], adapting white point to D65 with Bradford
+    #[inline]
+    pub fn from_xyz_d50(xyz: Xyz) -> Self {
+        YrgConverter::D50.from_xyz(xyz)
+    }
+
+    #[inline]
+    fn from_lms(lms: Vector3f) -> Self {
         let y = mlaf(0.68990272 * lms.v[0], 0.34832189, lms.v[1]);
 
         let a = lms.v[0] + lms.v[1] + lms.v[2];
@@ -92,6 +114,22 @@ impl Yrg {
 
     #[inline]
     pub fn to_xyz(&self) -> Xyz {
+        let x = LMS_TO_XYZ.f_mul_vector(self.to_lms());
+        Xyz {
+            x: x.v[0],
+            y: x.v[1],
+            z: x.v[2],
+        }
+    }
+
+    /// Convert [Yrg] to [Xyz] D50, adapting white point from D65 with Bradford
+    #[inline]
+    pub fn to_xyz_d50(&self) -> Xyz {
+        YrgConverter::D50.to_xyz(*self)
+    }
+
+    #[inline]
+    fn to_lms(self) -> Vector3f {
         let l = mlaf(0.95 * self.r, 0.38, self.g);
         let m = mlaf(mlaf(0.03, 0.59, self.g), 0.02, self.r);
         let den = mlaf(0.68990272 * l, 0.34832189, m);
@@ -99,13 +137,7 @@ impl Yrg {
         let l0 = l * a;
         let m0 = m * a;
         let s0 = (1f32 - l - m) * a;
-        let v = Vector3f { v: [l0, m0, s0] };
-        let x = LMS_TO_XYZ.f_mul_vector(v);
-        Xyz {
-            x: x.v[0],
-            y: x.v[1],
-            z: x.v[2],
-        }
+        Vector3f { v: [l0, m0, s0] }
     }
 }
 
@@ -139,6 +171,123 @@ impl Ych {
         let g = mlaf(0.54371398, c, sincos.0);
         Yrg { y, r, g }
     }
+
+    /// Convert [Xyz] D65 to [Ych]
+    #[inline]
+    pub fn from_xyz(xyz: Xyz) -> Self {
+        Self::from_yrg(Yrg::from_xyz(xyz))
+    }
+
+    /// Convert [Ych] to [Xyz] D65
+    #[inline]
+    pub fn to_xyz(&self) -> Xyz {
+        self.to_yrg().to_xyz()
+    }
+}
+
+/// Converts [Xyz] of arbitrary white point to [Yrg] and back.
+///
+/// Source white is adapted to D65 with Bradford before entering CIE LMS 2006,
+/// adaptation and LMS matrices are premultiplied once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct YrgConverter {
+    to_lms: Matrix3f,
+    from_lms: Matrix3f,
+}
+
+impl YrgConverter {
+    /// Converter for XYZ D65, same as [Yrg::from_xyz] and [Yrg::to_xyz]
+    pub const D65: YrgConverter = YrgConverter {
+        to_lms: XYZ_TO_LMS,
+        from_lms: LMS_TO_XYZ,
+    };
+
+    /// Converter for PCS XYZ D50
+    pub const D50: YrgConverter = YrgConverter::new(Chromaticity::D50);
+
+    /// Creates converter for XYZ in `white` point
+    pub const fn new(white: Chromaticity) -> Self {
+        let to_d65 = adaption_matrix(white.to_xyz(), Chromaticity::D65.to_xyz());
+        let from_d65 = adaption_matrix(Chromaticity::D65.to_xyz(), white.to_xyz());
+        YrgConverter {
+            to_lms: XYZ_TO_LMS.mat_mul_const(to_d65),
+            from_lms: from_d65.mat_mul_const(LMS_TO_XYZ),
+        }
+    }
+
+    /// Matrix from source XYZ to CIE LMS 2006
+    #[inline]
+    pub const fn to_lms_matrix(&self) -> Matrix3f {
+        self.to_lms
+    }
+
+    /// Matrix from CIE LMS 2006 to source XYZ
+    #[inline]
+    pub const fn from_lms_matrix(&self) -> Matrix3f {
+        self.from_lms
+    }
+
+    #[inline]
+    pub fn from_xyz(&self, xyz: Xyz) -> Yrg {
+        Yrg::from_lms(self.to_lms.f_mul_vector(Vector3f {
+            v: [xyz.x, xyz.y, xyz.z],
+        }))
+    }
+
+    #[inline]
+    pub fn to_xyz(&self, yrg: Yrg) -> Xyz {
+        let x = self.from_lms.f_mul_vector(yrg.to_lms());
+        Xyz {
+            x: x.v[0],
+            y: x.v[1],
+            z: x.v[2],
+        }
+    }
+
+    #[inline]
+    pub fn ych_from_xyz(&self, xyz: Xyz) -> Ych {
+        Ych::from_yrg(self.from_xyz(xyz))
+    }
+
+    #[inline]
+    pub fn ych_to_xyz(&self, ych: Ych) -> Xyz {
+        self.to_xyz(ych.to_yrg())
+    }
+
+    /// Converts interleaved XYZ triplets into interleaved Yrg triplets
+    pub fn from_xyz_slice(&self, src: &[f32], dst: &mut [f32]) -> Result<(), CmsError> {
+        check_lanes(src, dst)?;
+        for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
+            let v = self.from_xyz(Xyz::new(src[0], src[1], src[2]));
+            dst[0] = v.y;
+            dst[1] = v.r;
+            dst[2] = v.g;
+        }
+        Ok(())
+    }
+
+    /// Converts interleaved Yrg triplets into interleaved XYZ triplets
+    pub fn to_xyz_slice(&self, src: &[f32], dst: &mut [f32]) -> Result<(), CmsError> {
+        check_lanes(src, dst)?;
+        for (src, dst) in src.chunks_exact(3).zip(dst.chunks_exact_mut(3)) {
+            let v = self.to_xyz(Yrg::new(src[0], src[1], src[2]));
+            dst[0] = v.x;
+            dst[1] = v.y;
+            dst[2] = v.z;
+        }
+        Ok(())
+    }
+}
+
+#[inline]
+fn check_lanes(src: &[f32], dst: &[f32]) -> Result<(), CmsError> {
+    if src.len() != dst.len() {
+        return Err(CmsError::LaneSizeMismatch);
+    }
+    if src.len() % 3 != 0 {
+        return Err(CmsError::LaneMultipleOfChannels);
+    }
+    Ok(())
 }
 
 // Pipeline and ICC luminance is CIE Y 1931
@@ -174,4 +323,28 @@ mod tests {
         assert!((xyz.r - yrg_to_xyz.r) < 1e-5);
         assert!((xyz.g - yrg_to_xyz.g) < 1e-5);
     }
+
+    #[test]
+    fn test_yrg_converter() {
+        let white = YrgConverter::D50.from_xyz(Chromaticity::D50.to_xyz());
+        let white_d65 = Yrg::from_xyz(Chromaticity::D65.to_xyz());
+        assert!((white.r - white_d65.r).abs() < 1e-4);
+        assert!((white.g - white_d65.g).abs() < 1e-4);
+
+        let converter = YrgConverter::new(Chromaticity::new(0.3127, 0.329));
+        let src = [0.2f32, 0.3, 0.4, 0.9642, 1.0, 0.8249, 0.05, 0.02, 0.3];
+        let mut yrg = [0f32; 9];
+        let mut back = [0f32; 9];
+        converter.from_xyz_slice(&src, &mut yrg).unwrap();
+        converter.to_xyz_slice(&yrg, &mut back).unwrap();
+        for (a, b) in src.iter().zip(back.iter()) {
+            assert!((a - b).abs() < 5e-4, "{a} != {b}");
+        }
+        let single = converter.from_xyz(Xyz::new(src[0], src[1], src[2]));
+        assert_eq!([single.y, single.r, single.g], yrg[..3]);
+        let ych = YrgConverter::D50.ych_from_xyz(Xyz::new(0.3, 0.4, 0.2));
+        let xyz = YrgConverter::D50.ych_to_xyz(ych);
+        assert!((xyz.y - 0.4).abs() < 5e-4);
+        assert!(converter.from_xyz_slice(&src[..4], &mut yrg[..4]).is_err());
+    }
 }